@@ -0,0 +1,3 @@
+ALTER TYPE third_party_item_kind ADD VALUE IF NOT EXISTS 'GithubTaskItem';
+
+ALTER TYPE task_kind ADD VALUE IF NOT EXISTS 'Github';
@@ -0,0 +1,7 @@
+ALTER TABLE authentication_token
+  DROP COLUMN last_used_ip,
+  DROP COLUMN last_used_at,
+  DROP COLUMN rate_limit_per_minute,
+  DROP COLUMN notification_source_kinds,
+  DROP COLUMN scope,
+  DROP COLUMN name;
@@ -0,0 +1,7 @@
+ALTER TABLE authentication_token
+  ADD COLUMN name TEXT,
+  ADD COLUMN scope TEXT,
+  ADD COLUMN notification_source_kinds TEXT[],
+  ADD COLUMN rate_limit_per_minute INTEGER,
+  ADD COLUMN last_used_at TIMESTAMP,
+  ADD COLUMN last_used_ip TEXT;
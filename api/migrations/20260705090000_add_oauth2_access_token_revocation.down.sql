@@ -0,0 +1,5 @@
+DROP TABLE oauth2_revoked_access_token;
+
+ALTER TABLE oauth2_refresh_token
+  DROP COLUMN access_token_jti,
+  DROP COLUMN access_token_expires_at;
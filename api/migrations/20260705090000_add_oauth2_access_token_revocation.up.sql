@@ -0,0 +1,14 @@
+-- Access token minted alongside each refresh token, so that revoking a refresh
+-- token (RFC 7009) also revokes the access token of the same grant
+ALTER TABLE oauth2_refresh_token
+  ADD COLUMN access_token_jti TEXT,
+  ADD COLUMN access_token_expires_at TIMESTAMPTZ;
+
+-- Revoked OAuth2 access tokens, kept until they expire
+CREATE TABLE oauth2_revoked_access_token (
+    jti TEXT PRIMARY KEY,
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX oauth2_revoked_access_token_expires_at_idx ON oauth2_revoked_access_token (expires_at);
@@ -0,0 +1,16 @@
+-- OAuth2 device authorization requests (RFC 8628, short-lived)
+CREATE TABLE oauth2_device_authorization (
+    device_code_hash TEXT PRIMARY KEY,
+    user_code TEXT UNIQUE NOT NULL,
+    client_id TEXT NOT NULL REFERENCES oauth2_client(client_id) ON DELETE CASCADE,
+    scope TEXT,
+    resource TEXT,
+    status TEXT NOT NULL DEFAULT 'pending',
+    user_id UUID REFERENCES "user"(id) ON DELETE CASCADE,
+    polling_interval_secs INTEGER NOT NULL,
+    last_polled_at TIMESTAMPTZ,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX oauth2_device_authorization_expires_at_idx ON oauth2_device_authorization (expires_at);
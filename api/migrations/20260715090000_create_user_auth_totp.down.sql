@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS user_auth_recovery_code;
+DROP TABLE IF EXISTS user_auth_totp;
@@ -0,0 +1,23 @@
+-- TOTP second factor of local (password) logins, one per user. The secret
+-- is encrypted with the token encryption key and the TOTP is only enforced
+-- once `enabled_at` is set, ie. after the enrollment has been confirmed
+-- with a first valid code.
+CREATE TABLE user_auth_totp (
+    user_id UUID PRIMARY KEY REFERENCES "user"(id) ON DELETE CASCADE,
+    encrypted_secret BYTEA NOT NULL,
+    enabled_at TIMESTAMPTZ,
+    -- Last accepted time step, to reject the replay of a code
+    last_used_step BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- One-time recovery codes, usable in place of a TOTP code
+CREATE TABLE user_auth_recovery_code (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+    code_hash TEXT NOT NULL,
+    used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    CONSTRAINT user_auth_recovery_code_user_id_code_hash_unique UNIQUE (user_id, code_hash)
+);
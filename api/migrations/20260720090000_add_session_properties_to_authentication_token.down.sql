@@ -0,0 +1,4 @@
+DROP INDEX authentication_token_user_id_is_session_token_idx;
+
+ALTER TABLE authentication_token
+  DROP COLUMN user_agent;
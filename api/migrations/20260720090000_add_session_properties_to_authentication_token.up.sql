@@ -0,0 +1,5 @@
+ALTER TABLE authentication_token
+  ADD COLUMN user_agent TEXT;
+
+CREATE INDEX authentication_token_user_id_is_session_token_idx
+  ON authentication_token(user_id, is_session_token);
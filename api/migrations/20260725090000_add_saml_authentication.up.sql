@@ -0,0 +1,37 @@
+ALTER TABLE user_auth
+  DROP CONSTRAINT user_auth_type_chk;
+
+ALTER TABLE user_auth
+  ALTER COLUMN kind TYPE TEXT;
+
+DROP TYPE user_auth_kind;
+
+CREATE TYPE user_auth_kind AS ENUM ('SAML', 'Passkey', 'OIDCAuthorizationCodePKCE', 'OIDCGoogleAuthorizationCode', 'Local');
+
+ALTER TABLE user_auth
+  ALTER COLUMN kind TYPE user_auth_kind
+  USING (kind::user_auth_kind);
+
+ALTER TABLE user_auth
+  ADD CONSTRAINT user_auth_type_chk CHECK
+    (
+      CASE
+        WHEN kind = 'SAML' THEN auth_user_id IS NOT NULL
+        WHEN kind = 'Passkey' THEN username IS NOT NULL AND passkey IS NOT NULL
+        WHEN kind = 'OIDCGoogleAuthorizationCode' THEN auth_user_id IS NOT NULL AND auth_id_token IS NOT NULL
+        WHEN kind = 'OIDCAuthorizationCodePKCE' THEN auth_user_id IS NOT NULL AND auth_id_token IS NOT NULL
+        WHEN kind = 'Local' THEN password_hash IS NOT NULL
+      END
+    );
+
+-- SAML authentication requests sent to the identity provider, consumed when
+-- its response is received (short-lived)
+CREATE TABLE saml_authn_request (
+    id TEXT PRIMARY KEY,
+    -- Set when the request links a SAML identity to an existing user
+    linking_user_id UUID REFERENCES "user"(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX saml_authn_request_expires_at_idx ON saml_authn_request (expires_at);
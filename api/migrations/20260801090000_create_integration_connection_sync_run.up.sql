@@ -0,0 +1,21 @@
+-- Bounded history of the synchronizations of each integration connection,
+-- used to diagnose misbehaving syncs. Only the most recent runs of each
+-- connection and kind are kept.
+CREATE TABLE integration_connection_sync_run (
+    id UUID PRIMARY KEY,
+    integration_connection_id UUID NOT NULL REFERENCES integration_connection(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    started_at TIMESTAMPTZ NOT NULL,
+    ended_at TIMESTAMPTZ NOT NULL,
+    items_fetched INTEGER NOT NULL DEFAULT 0,
+    items_created INTEGER NOT NULL DEFAULT 0,
+    items_updated INTEGER NOT NULL DEFAULT 0,
+    items_marked_stale INTEGER NOT NULL DEFAULT 0,
+    upstream_http_calls INTEGER NOT NULL DEFAULT 0,
+    rate_limit_remaining BIGINT,
+    -- Error and its causes, outermost first. Empty for a successful run.
+    error_chain TEXT[] NOT NULL DEFAULT '{}'
+);
+
+CREATE INDEX integration_connection_sync_run_connection_started_at_idx
+    ON integration_connection_sync_run (integration_connection_id, kind, started_at DESC);
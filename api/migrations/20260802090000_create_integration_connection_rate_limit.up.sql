@@ -0,0 +1,9 @@
+-- Upstream API budget of each integration connection, as last reported by its
+-- provider. Syncs of a connection are deferred until `limited_until`.
+CREATE TABLE integration_connection_rate_limit (
+    integration_connection_id UUID PRIMARY KEY REFERENCES integration_connection(id) ON DELETE CASCADE,
+    remaining BIGINT,
+    reset_at TIMESTAMPTZ,
+    limited_until TIMESTAMPTZ,
+    updated_at TIMESTAMPTZ NOT NULL
+);
@@ -0,0 +1,16 @@
+-- Asynchronous jobs which failed after exhausting the retries of their kind,
+-- kept so that they can be inspected, pushed back into the queue or purged.
+CREATE TABLE dead_letter_job (
+    id UUID PRIMARY KEY,
+    job_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    user_id UUID REFERENCES "user"(id) ON DELETE CASCADE,
+    payload JSONB NOT NULL,
+    -- Error and its causes, outermost first
+    error_chain TEXT[] NOT NULL DEFAULT '{}',
+    attempts INTEGER NOT NULL,
+    failed_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX dead_letter_job_name_failed_at_idx ON dead_letter_job (name, failed_at DESC);
+CREATE INDEX dead_letter_job_user_id_idx ON dead_letter_job (user_id);
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS login_throttle;
+DROP TABLE IF EXISTS cache_set_member;
+DROP TABLE IF EXISTS cache_entry;
@@ -0,0 +1,29 @@
+-- Storage of the cache, locks and login throttling when the `Postgres` storage
+-- backend is used instead of Redis. Expired rows are ignored when read and
+-- periodically deleted by the workers.
+CREATE TABLE cache_entry (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX cache_entry_expires_at_idx ON cache_entry (expires_at);
+
+CREATE TABLE cache_set_member (
+    key TEXT NOT NULL,
+    member TEXT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (key, member)
+);
+
+CREATE INDEX cache_set_member_expires_at_idx ON cache_set_member (expires_at);
+
+CREATE TABLE login_throttle (
+    key TEXT PRIMARY KEY,
+    fail_count INTEGER NOT NULL,
+    -- Epoch seconds, 0 when the account is not locked
+    locked_until BIGINT NOT NULL DEFAULT 0,
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX login_throttle_expires_at_idx ON login_throttle (expires_at);
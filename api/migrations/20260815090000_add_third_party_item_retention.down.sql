@@ -0,0 +1,10 @@
+DROP TABLE third_party_item_tombstone;
+
+DROP INDEX notification_status_status_updated_at_idx;
+
+DROP TRIGGER notification_status_update ON notification;
+
+DROP FUNCTION notification_status_updated_at_trigger;
+
+ALTER TABLE notification
+  DROP COLUMN status_updated_at;
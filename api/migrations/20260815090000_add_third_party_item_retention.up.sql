@@ -0,0 +1,46 @@
+-- Time of the last status change of a notification. The status is updated
+-- from many places, hence it is maintained by a trigger. Used by the retention
+-- policy to find the notifications deleted or unsubscribed for a long time.
+ALTER TABLE notification
+  ADD COLUMN status_updated_at TIMESTAMP;
+
+UPDATE notification
+   SET status_updated_at = updated_at;
+
+ALTER TABLE notification
+  ALTER COLUMN status_updated_at SET NOT NULL,
+  ALTER COLUMN status_updated_at SET DEFAULT (NOW() AT TIME ZONE 'UTC');
+
+CREATE FUNCTION notification_status_updated_at_trigger() RETURNS trigger AS $$
+begin
+  new.status_updated_at := NOW() AT TIME ZONE 'UTC';
+  return new;
+end
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER notification_status_update BEFORE
+  UPDATE ON notification
+  FOR EACH ROW
+    WHEN (OLD.status IS DISTINCT FROM NEW.status)
+    EXECUTE FUNCTION notification_status_updated_at_trigger();
+
+CREATE INDEX notification_status_status_updated_at_idx
+  ON notification (status, status_updated_at);
+
+-- Minimal trace of the third party items purged by the retention policy, so
+-- that an upstream item fetched again is not re-created as a new notification.
+-- `activity_digest` is the SHA-256 digest of the marker of the last upstream
+-- activity on the item (e.g. its `updated_at`) when it was purged.
+CREATE TABLE third_party_item_tombstone (
+    source_id TEXT NOT NULL,
+    kind THIRD_PARTY_ITEM_KIND NOT NULL,
+    integration_connection_id UUID NOT NULL REFERENCES integration_connection(id) ON DELETE CASCADE,
+    PRIMARY KEY (source_id, kind, integration_connection_id),
+    user_id UUID NOT NULL REFERENCES "user"(id) ON DELETE CASCADE,
+    notification_status NOTIFICATION_STATUS NOT NULL,
+    activity_digest BYTEA NOT NULL,
+    purged_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX third_party_item_tombstone_user_id_source_id_idx
+  ON third_party_item_tombstone (user_id, source_id);
@@ -0,0 +1,9 @@
+-- Delete a key only if it still holds the given value, atomically.
+--
+-- KEYS[1] = key to delete
+-- ARGV[1] = expected value of the key
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+  return redis.call("DEL", KEYS[1])
+end
+
+return 0
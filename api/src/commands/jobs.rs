@@ -0,0 +1,208 @@
+use std::sync::Arc;
+
+use anyhow::{Context, anyhow};
+use chrono::{TimeDelta, Utc};
+use log::info;
+use tabled::{
+    builder::Builder,
+    settings::{Color, object::Rows, style::Style},
+};
+
+use crate::{
+    commands::JobFilterArgs,
+    jobs::{
+        dead_letter::{DeadLetterJobFilter, DeadLetterJobId},
+        storage::JobStorage,
+    },
+    universal_inbox::{UniversalInboxError, job::service::JobService},
+};
+
+impl JobFilterArgs {
+    pub fn to_dead_letter_job_filter(&self, id: Option<DeadLetterJobId>) -> DeadLetterJobFilter {
+        let now = Utc::now();
+        DeadLetterJobFilter {
+            id,
+            name: self.name.clone(),
+            user_id: self.user_id,
+            failed_before: self
+                .older_than_hours
+                .map(|hours| now - TimeDelta::hours(hours)),
+            failed_after: self
+                .newer_than_hours
+                .map(|hours| now - TimeDelta::hours(hours)),
+            limit: None,
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "list-dead-letter-jobs-command",
+    level = "info",
+    skip(job_service),
+    err
+)]
+pub async fn list_dead_letter_jobs(
+    job_service: Arc<JobService>,
+    filter: DeadLetterJobFilter,
+) -> Result<(), UniversalInboxError> {
+    let mut transaction = job_service
+        .begin()
+        .await
+        .context("Failed to create new transaction while listing dead-lettered jobs")?;
+
+    let dead_letter_jobs = job_service
+        .fetch_dead_letter_jobs(&mut transaction, &filter)
+        .await?;
+
+    let mut rows: Vec<Vec<String>> = dead_letter_jobs
+        .iter()
+        .map(|dead_letter_job| {
+            vec![
+                dead_letter_job.id.to_string(),
+                dead_letter_job.name.clone(),
+                dead_letter_job
+                    .user_id
+                    .map(|user_id| user_id.to_string())
+                    .unwrap_or_default(),
+                dead_letter_job.failed_at.to_rfc3339(),
+                dead_letter_job.attempts.to_string(),
+                dead_letter_job
+                    .error_chain
+                    .first()
+                    .cloned()
+                    .unwrap_or_default(),
+            ]
+        })
+        .collect();
+    rows.insert(
+        0,
+        vec![
+            "ID".to_string(),
+            "Job".to_string(),
+            "User ID".to_string(),
+            "Failed at".to_string(),
+            "Attempts".to_string(),
+            "Error".to_string(),
+        ],
+    );
+    let mut job_table = Builder::from(rows).build();
+    job_table
+        .with(Style::rounded())
+        .modify(Rows::first(), Color::FG_BLUE);
+
+    println!("{}", job_table);
+
+    Ok(())
+}
+
+#[tracing::instrument(
+    name = "show-dead-letter-job-command",
+    level = "info",
+    skip(job_service),
+    err
+)]
+pub async fn show_dead_letter_job(
+    job_service: Arc<JobService>,
+    id: DeadLetterJobId,
+) -> Result<(), UniversalInboxError> {
+    let mut transaction = job_service
+        .begin()
+        .await
+        .context("Failed to create new transaction while showing dead-lettered job")?;
+
+    let dead_letter_job = job_service
+        .fetch_dead_letter_jobs(
+            &mut transaction,
+            &DeadLetterJobFilter {
+                id: Some(id),
+                ..Default::default()
+            },
+        )
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| UniversalInboxError::ItemNotFound(format!("Dead-lettered job {id}")))?;
+
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&dead_letter_job)
+            .context("Failed to serialize dead-lettered job")?
+    );
+
+    Ok(())
+}
+
+#[tracing::instrument(
+    name = "retry-dead-letter-jobs-command",
+    level = "info",
+    skip(job_service, job_storage),
+    err
+)]
+pub async fn retry_dead_letter_jobs(
+    job_service: Arc<JobService>,
+    job_storage: JobStorage,
+    filter: DeadLetterJobFilter,
+    all: bool,
+) -> Result<(), UniversalInboxError> {
+    check_filter_or_all(&filter, all)?;
+    let mut transaction = job_service
+        .begin()
+        .await
+        .context("Failed to create new transaction while retrying dead-lettered jobs")?;
+
+    let retried_jobs = job_service
+        .retry_dead_letter_jobs(&mut transaction, &filter, &job_storage)
+        .await?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit transaction while retrying dead-lettered jobs")?;
+    info!(
+        "{} dead-lettered job(s) pushed back to the queue",
+        retried_jobs.len()
+    );
+
+    Ok(())
+}
+
+#[tracing::instrument(
+    name = "purge-dead-letter-jobs-command",
+    level = "info",
+    skip(job_service),
+    err
+)]
+pub async fn purge_dead_letter_jobs(
+    job_service: Arc<JobService>,
+    filter: DeadLetterJobFilter,
+    all: bool,
+) -> Result<(), UniversalInboxError> {
+    check_filter_or_all(&filter, all)?;
+    let mut transaction = job_service
+        .begin()
+        .await
+        .context("Failed to create new transaction while purging dead-lettered jobs")?;
+
+    let purged_jobs_count = job_service
+        .purge_dead_letter_jobs(&mut transaction, &filter)
+        .await?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit transaction while purging dead-lettered jobs")?;
+    info!("{purged_jobs_count} dead-lettered job(s) purged");
+
+    Ok(())
+}
+
+fn check_filter_or_all(filter: &DeadLetterJobFilter, all: bool) -> Result<(), UniversalInboxError> {
+    if filter.is_empty() && !all {
+        return Err(anyhow!(
+            "A job ID or a filter is required, use `--all` to consider all dead-lettered jobs"
+        )
+        .into());
+    }
+
+    Ok(())
+}
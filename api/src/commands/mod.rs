@@ -1,12 +1,10 @@
 use std::str::FromStr;
 
-use apalis_redis::RedisStorage;
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use email_address::EmailAddress;
 use futures::future;
 use std::{net::TcpListener, sync::Arc};
 use tokio::sync::RwLock;
-use tracing::info;
 
 use universal_inbox::{
     integration_connection::provider::IntegrationProviderKind,
@@ -18,10 +16,16 @@ use universal_inbox::{
 use crate::{
     configuration::Settings,
     integrations::slack::SlackService,
+    jobs::{
+        dead_letter::{DeadLetterJobFilter, DeadLetterJobId},
+        storage::{JOB_STORAGE_NAMESPACE, JobStorage},
+        sync::SyncConcurrencyLimiter,
+    },
+    mcp::subscriptions::publish_resource_updates_after,
     run_ping_server, run_server, run_worker,
     universal_inbox::{
         UniversalInboxError, auth_token::service::AuthenticationTokenService,
-        integration_connection::service::IntegrationConnectionService,
+        integration_connection::service::IntegrationConnectionService, job::service::JobService,
         notification::service::NotificationService, oauth2::service::OAuth2Service,
         slack_bridge::service::SlackBridgeService, task::service::TaskService,
         third_party::service::ThirdPartyItemService, user::service::UserService,
@@ -31,9 +35,12 @@ use crate::{
 
 pub mod anonymize;
 pub mod generate;
+pub mod jobs;
 pub mod oauth;
+pub mod retention;
 #[cfg(feature = "screenshots")]
 pub mod screenshots;
+pub mod secrets;
 pub mod sync;
 pub mod user;
 
@@ -49,6 +56,26 @@ async fn shutdown_signal() -> std::io::Result<()> {
     Ok(())
 }
 
+async fn connect_job_storage(
+    settings: &Settings,
+    integration_connection_service: &RwLock<IntegrationConnectionService>,
+) -> JobStorage {
+    let pool = integration_connection_service.read().await.pool();
+    JobStorage::connect(settings, pool, JOB_STORAGE_NAMESPACE)
+        .await
+        .expect("Job storage connection failed")
+}
+
+async fn connect_cache(
+    settings: &Settings,
+    integration_connection_service: &RwLock<IntegrationConnectionService>,
+) -> Cache {
+    let pool = integration_connection_service.read().await.pool();
+    Cache::new(settings, pool)
+        .await
+        .expect("Failed to create cache")
+}
+
 /// Universal Inbox API server and associated commands
 #[derive(Parser)]
 #[clap(version, about, long_about = None)]
@@ -91,6 +118,18 @@ pub enum Commands {
         minutes_before_expiry: i64,
     },
 
+    /// Purge the third party items of the notifications deleted or unsubscribed
+    /// for a long time, the items without notification nor task and the old
+    /// dead-lettered jobs, as configured in `application.cron.apply_retention_policy`
+    ApplyRetentionPolicy {
+        /// Only count the items which would be purged
+        #[arg(short, long)]
+        dry_run: bool,
+        /// Print the number of purged items as JSON instead of a table
+        #[arg(long)]
+        json: bool,
+    },
+
     /// Generate a new JWT key pair (to be added to your configuration file)
     GenerateJWTKeyPair,
 
@@ -128,6 +167,18 @@ pub enum Commands {
         #[clap(subcommand)]
         command: UserCommands,
     },
+
+    /// Manage asynchronous jobs which failed after exhausting their retries
+    Jobs {
+        #[clap(subcommand)]
+        command: JobCommands,
+    },
+
+    /// Manage encrypted secrets
+    Secrets {
+        #[clap(subcommand)]
+        command: SecretCommands,
+    },
 }
 
 #[derive(Subcommand)]
@@ -140,6 +191,70 @@ pub enum CacheCommands {
     },
 }
 
+#[derive(Subcommand)]
+pub enum JobCommands {
+    /// List dead-lettered jobs, latest failures first
+    List {
+        #[command(flatten)]
+        filter: JobFilterArgs,
+        /// Maximum number of jobs to list
+        #[arg(short, long, default_value_t = 100)]
+        limit: i64,
+    },
+    /// Show a dead-lettered job, including its payload and error
+    Show { id: DeadLetterJobId },
+    /// Push dead-lettered jobs back into the queue
+    Retry {
+        /// Retry the dead-lettered job with given ID
+        id: Option<DeadLetterJobId>,
+        #[command(flatten)]
+        filter: JobFilterArgs,
+        /// Retry all dead-lettered jobs (required when no other filter is set)
+        #[arg(long)]
+        all: bool,
+    },
+    /// Delete dead-lettered jobs
+    Purge {
+        /// Delete the dead-lettered job with given ID
+        id: Option<DeadLetterJobId>,
+        #[command(flatten)]
+        filter: JobFilterArgs,
+        /// Delete all dead-lettered jobs (required when no other filter is set)
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SecretCommands {
+    /// Re-encrypt OAuth credentials and TOTP secrets with the primary token
+    /// encryption key, so that previous keys can be removed from the configuration
+    Rotate {
+        /// Number of secrets re-encrypted per transaction
+        #[arg(long, default_value_t = 100)]
+        batch_size: i64,
+        /// Only count the secrets which would be re-encrypted
+        #[arg(short, long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Args)]
+pub struct JobFilterArgs {
+    /// Only consider jobs with given name (ie. `SlackPushEventCallback`)
+    #[arg(short, long)]
+    pub name: Option<String>,
+    /// Only consider jobs of given user
+    #[arg(short, long)]
+    pub user_id: Option<UserId>,
+    /// Only consider jobs which failed more than N hours ago
+    #[arg(long)]
+    pub older_than_hours: Option<i64>,
+    /// Only consider jobs which failed less than N hours ago
+    #[arg(long)]
+    pub newer_than_hours: Option<i64>,
+}
+
 #[derive(Subcommand)]
 pub enum TestCommands {
     /// Generate testing user
@@ -246,6 +361,9 @@ impl Cli {
             _ => match &self.command {
                 Commands::User {
                     command: UserCommands::List,
+                }
+                | Commands::Jobs {
+                    command: JobCommands::List { .. } | JobCommands::Show { .. },
                 } => (log::LevelFilter::Error.to_string(), log::LevelFilter::Error),
                 _ => (
                     settings
@@ -280,15 +398,25 @@ impl Cli {
         slack_service: Arc<SlackService>,
         slack_bridge_service: Arc<SlackBridgeService>,
         oauth2_service: Arc<OAuth2Service>,
+        job_service: Arc<JobService>,
     ) -> Result<(), UniversalInboxError> {
         match &self.command {
             Commands::SyncNotifications { source, user_id } => {
-                sync::sync_notifications_for_all_users(notification_service, *source, *user_id)
-                    .await
+                publish_resource_updates_after(sync::sync_notifications_for_all_users(
+                    notification_service,
+                    *source,
+                    *user_id,
+                ))
+                .await
             }
 
             Commands::SyncTasks { source, user_id } => {
-                sync::sync_tasks_for_all_users(task_service, *source, *user_id).await
+                publish_resource_updates_after(sync::sync_tasks_for_all_users(
+                    task_service,
+                    *source,
+                    *user_id,
+                ))
+                .await
             }
 
             Commands::RefreshOAuthTokens {
@@ -303,6 +431,17 @@ impl Cli {
                 .await
             }
 
+            Commands::ApplyRetentionPolicy { dry_run, json } => {
+                retention::apply_retention_policy(
+                    third_party_item_service,
+                    job_service,
+                    (&settings.application.cron.apply_retention_policy).into(),
+                    *dry_run,
+                    *json,
+                )
+                .await
+            }
+
             Commands::GenerateJWTKeyPair => {
                 let jwt_signing_keys = JWTBase64EncodedSigningKeys::generate()
                     .expect("Failed to generate JWT signing keys");
@@ -322,17 +461,8 @@ impl Cli {
                 async_workers_count,
                 embed_async_workers,
             } => {
-                info!(
-                    "Connecting to Redis server for job queuing on {}",
-                    &settings.redis.safe_connection_string()
-                );
-                let redis_storage = RedisStorage::new_with_config(
-                    apalis_redis::connect(settings.redis.connection_string())
-                        .await
-                        .expect("Redis storage connection failed"),
-                    apalis_redis::Config::default()
-                        .set_namespace("universal-inbox:jobs:UniversalInboxJob"),
-                );
+                let job_storage =
+                    connect_job_storage(&settings, &integration_connection_service).await;
 
                 let listener = TcpListener::bind(format!(
                     "{}:{}",
@@ -341,10 +471,13 @@ impl Cli {
                 .expect("Failed to bind port");
 
                 let cron_settings = settings.application.cron.clone();
-                let redis_connection_string = settings.redis.connection_string();
+                let cache = connect_cache(&settings, &integration_connection_service).await;
+                let sync_concurrency_limiter =
+                    SyncConcurrencyLimiter::new(cache.clone(), &settings.integrations);
                 let server = run_server(
                     listener,
-                    redis_storage.clone(),
+                    job_storage.clone(),
+                    cache.clone(),
                     settings,
                     notification_service.clone(),
                     task_service.clone(),
@@ -359,12 +492,9 @@ impl Cli {
                 .expect("Failed to start HTTP server");
 
                 if async_workers_count.is_some() || *embed_async_workers {
-                    let cache = Cache::new(redis_connection_string)
-                        .await
-                        .expect("Failed to create cache");
                     let worker = run_worker(
                         *async_workers_count,
-                        redis_storage,
+                        job_storage,
                         cron_settings,
                         cache,
                         notification_service,
@@ -372,6 +502,8 @@ impl Cli {
                         integration_connection_service,
                         third_party_item_service,
                         slack_service,
+                        job_service,
+                        sync_concurrency_limiter,
                     )
                     .await;
 
@@ -386,17 +518,8 @@ impl Cli {
             }
 
             Commands::StartWorkers { count } => {
-                info!(
-                    "Connecting to Redis server for job queuing on {}",
-                    &settings.redis.safe_connection_string()
-                );
-                let redis_storage = RedisStorage::new_with_config(
-                    apalis_redis::connect(settings.redis.connection_string())
-                        .await
-                        .expect("Redis storage connection failed"),
-                    apalis_redis::Config::default()
-                        .set_namespace("universal-inbox:jobs:UniversalInboxJob"),
-                );
+                let job_storage =
+                    connect_job_storage(&settings, &integration_connection_service).await;
 
                 let worker_port = settings
                     .application
@@ -408,20 +531,22 @@ impl Cli {
                 ))
                 .expect("Failed to bind worker health-check port");
 
-                let cache = Cache::new(settings.redis.connection_string())
-                    .await
-                    .expect("Failed to create cache");
+                let cache = connect_cache(&settings, &integration_connection_service).await;
                 let ping_server = run_ping_server(
                     listener,
+                    settings.clone(),
+                    job_storage.clone(),
                     cache.clone(),
                     integration_connection_service.clone(),
                 )
                 .await
                 .expect("Failed to start worker health-check server");
 
+                let sync_concurrency_limiter =
+                    SyncConcurrencyLimiter::new(cache.clone(), &settings.integrations);
                 let worker = run_worker(
                     *count,
-                    redis_storage,
+                    job_storage,
                     settings.application.cron.clone(),
                     cache,
                     notification_service,
@@ -429,6 +554,8 @@ impl Cli {
                     integration_connection_service,
                     third_party_item_service,
                     slack_service,
+                    job_service,
+                    sync_concurrency_limiter,
                 )
                 .await;
 
@@ -443,9 +570,7 @@ impl Cli {
 
             Commands::Cache { command } => match command {
                 CacheCommands::Clear { prefix } => {
-                    let cache = Cache::new(settings.redis.connection_string())
-                        .await
-                        .expect("Failed to create cache");
+                    let cache = connect_cache(&settings, &integration_connection_service).await;
                     cache.clear(prefix).await.expect("Failed to clear cache");
                     Ok(())
                 }
@@ -479,7 +604,7 @@ impl Cli {
                     .await
                 }
                 TestCommands::GenerateNotifications { user_id, source } => {
-                    generate::generate_notifications_for_user(
+                    publish_resource_updates_after(generate::generate_notifications_for_user(
                         user_service,
                         integration_connection_service,
                         notification_service,
@@ -488,7 +613,7 @@ impl Cli {
                         settings,
                         *user_id,
                         source.clone(),
-                    )
+                    ))
                     .await
                 }
                 TestCommands::AnonymizeDb => anonymize::anonymize_database(user_service).await,
@@ -557,6 +682,57 @@ impl Cli {
                     user::generate_jwt_token(user_service, auth_token_service, user_email).await
                 }
             },
+
+            Commands::Jobs { command } => match command {
+                JobCommands::List { filter, limit } => {
+                    jobs::list_dead_letter_jobs(
+                        job_service,
+                        DeadLetterJobFilter {
+                            limit: Some(*limit),
+                            ..filter.to_dead_letter_job_filter(None)
+                        },
+                    )
+                    .await
+                }
+
+                JobCommands::Show { id } => jobs::show_dead_letter_job(job_service, *id).await,
+
+                JobCommands::Retry { id, filter, all } => {
+                    let job_storage =
+                        connect_job_storage(&settings, &integration_connection_service).await;
+                    jobs::retry_dead_letter_jobs(
+                        job_service,
+                        job_storage,
+                        filter.to_dead_letter_job_filter(*id),
+                        *all,
+                    )
+                    .await
+                }
+
+                JobCommands::Purge { id, filter, all } => {
+                    jobs::purge_dead_letter_jobs(
+                        job_service,
+                        filter.to_dead_letter_job_filter(*id),
+                        *all,
+                    )
+                    .await
+                }
+            },
+
+            Commands::Secrets { command } => match command {
+                SecretCommands::Rotate {
+                    batch_size,
+                    dry_run,
+                } => {
+                    secrets::rotate_secrets(
+                        integration_connection_service,
+                        user_service,
+                        *batch_size,
+                        *dry_run,
+                    )
+                    .await
+                }
+            },
         }
     }
 }
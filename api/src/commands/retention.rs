@@ -0,0 +1,76 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use tabled::{
+    builder::Builder,
+    settings::{Color, object::Rows, style::Style},
+};
+use tokio::sync::RwLock;
+
+use crate::{
+    jobs,
+    universal_inbox::{
+        UniversalInboxError,
+        job::service::JobService,
+        third_party::{retention::RetentionPolicy, service::ThirdPartyItemService},
+    },
+};
+
+#[tracing::instrument(
+    name = "apply-retention-policy-command",
+    level = "info",
+    skip(third_party_item_service, job_service),
+    err
+)]
+pub async fn apply_retention_policy(
+    third_party_item_service: Arc<RwLock<ThirdPartyItemService>>,
+    job_service: Arc<JobService>,
+    policy: RetentionPolicy,
+    dry_run: bool,
+    json: bool,
+) -> Result<(), UniversalInboxError> {
+    let stats = jobs::retention::apply_retention_policy(
+        third_party_item_service,
+        job_service,
+        policy,
+        dry_run,
+    )
+    .await?;
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&stats)
+                .context("Failed to serialize retention policy stats")?
+        );
+        return Ok(());
+    }
+
+    let rows = vec![
+        vec![
+            "Items".to_string(),
+            if dry_run { "To purge" } else { "Purged" }.to_string(),
+        ],
+        vec![
+            "Items of deleted notifications".to_string(),
+            stats.deleted_notification_items.to_string(),
+        ],
+        vec![
+            "Items of unsubscribed notifications".to_string(),
+            stats.unsubscribed_notification_items.to_string(),
+        ],
+        vec!["Orphan items".to_string(), stats.orphan_items.to_string()],
+        vec![
+            "Dead-lettered jobs".to_string(),
+            stats.dead_letter_jobs.to_string(),
+        ],
+    ];
+    let mut stats_table = Builder::from(rows).build();
+    stats_table
+        .with(Style::rounded())
+        .modify(Rows::first(), Color::FG_BLUE);
+
+    println!("{}", stats_table);
+
+    Ok(())
+}
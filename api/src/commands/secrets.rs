@@ -0,0 +1,170 @@
+use std::{fmt::Display, sync::Arc};
+
+use anyhow::{Context, anyhow};
+use log::info;
+use sqlx::{Postgres, Transaction};
+use tokio::sync::RwLock;
+
+use crate::{
+    universal_inbox::{
+        UniversalInboxError, integration_connection::service::IntegrationConnectionService,
+        user::service::UserService,
+    },
+    utils::crypto::SecretRotationBatch,
+};
+
+#[derive(Debug, Default)]
+struct SecretRotationStats {
+    scanned_count: usize,
+    rotated_count: usize,
+    failed_count: usize,
+}
+
+#[tracing::instrument(
+    name = "rotate-secrets-command",
+    level = "info",
+    skip(integration_connection_service, user_service),
+    err
+)]
+pub async fn rotate_secrets(
+    integration_connection_service: Arc<RwLock<IntegrationConnectionService>>,
+    user_service: Arc<UserService>,
+    batch_size: i64,
+    dry_run: bool,
+) -> Result<(), UniversalInboxError> {
+    if batch_size <= 0 {
+        return Err(anyhow!("The batch size must be positive").into());
+    }
+
+    let oauth_credentials_stats = rotate_oauth_credentials(
+        &*integration_connection_service.read().await,
+        batch_size,
+        dry_run,
+    )
+    .await?;
+    let totp_secrets_stats = rotate_totp_secrets(&user_service, batch_size, dry_run).await?;
+
+    let failed_count = oauth_credentials_stats.failed_count + totp_secrets_stats.failed_count;
+    if failed_count > 0 {
+        return Err(anyhow!(
+            "{failed_count} secret(s) could not be re-encrypted, keep the previous token encryption keys until they are fixed"
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+async fn rotate_oauth_credentials(
+    service: &IntegrationConnectionService,
+    batch_size: i64,
+    dry_run: bool,
+) -> Result<SecretRotationStats, UniversalInboxError> {
+    let mut stats = SecretRotationStats::default();
+    let mut after = None;
+    loop {
+        let mut transaction = service
+            .begin()
+            .await
+            .context("Failed to create new transaction while rotating OAuth credentials")?;
+        let batch = service
+            .rotate_oauth_credentials_encryption_key(&mut transaction, after, batch_size, dry_run)
+            .await?;
+        end_batch_transaction(transaction, dry_run).await?;
+
+        let Some(last_id) = stats.add("OAuth credentials", &batch, dry_run) else {
+            break;
+        };
+        after = Some(last_id);
+    }
+
+    stats.log_summary("OAuth credentials", dry_run);
+    Ok(stats)
+}
+
+async fn rotate_totp_secrets(
+    service: &UserService,
+    batch_size: i64,
+    dry_run: bool,
+) -> Result<SecretRotationStats, UniversalInboxError> {
+    let mut stats = SecretRotationStats::default();
+    let mut after = None;
+    loop {
+        let mut transaction = service
+            .begin()
+            .await
+            .context("Failed to create new transaction while rotating TOTP secrets")?;
+        let batch = service
+            .rotate_totp_secrets_encryption_key(&mut transaction, after, batch_size, dry_run)
+            .await?;
+        end_batch_transaction(transaction, dry_run).await?;
+
+        let Some(last_id) = stats.add("TOTP secrets", &batch, dry_run) else {
+            break;
+        };
+        after = Some(last_id);
+    }
+
+    stats.log_summary("TOTP secrets", dry_run);
+    Ok(stats)
+}
+
+/// Each batch is committed on its own so that an interrupted rotation can be
+/// resumed, except on dry runs where nothing must be written
+async fn end_batch_transaction(
+    transaction: Transaction<'_, Postgres>,
+    dry_run: bool,
+) -> Result<(), UniversalInboxError> {
+    if dry_run {
+        transaction
+            .rollback()
+            .await
+            .context("Failed to rollback dry run secrets rotation transaction")?;
+    } else {
+        transaction
+            .commit()
+            .await
+            .context("Failed to commit secrets rotation transaction")?;
+    }
+    Ok(())
+}
+
+impl SecretRotationStats {
+    fn add<Id: Copy + Display>(
+        &mut self,
+        secrets_name: &str,
+        batch: &SecretRotationBatch<Id>,
+        dry_run: bool,
+    ) -> Option<Id> {
+        let last_id = batch.last_id?;
+        self.scanned_count += batch.scanned_count;
+        self.rotated_count += batch.rotated_count;
+        self.failed_count += batch.failed_count;
+        info!(
+            "{secrets_name}: {} scanned, {} {}, {} failed (up to {last_id})",
+            self.scanned_count,
+            self.rotated_count,
+            rotated_label(dry_run),
+            self.failed_count
+        );
+        Some(last_id)
+    }
+
+    fn log_summary(&self, secrets_name: &str, dry_run: bool) {
+        info!(
+            "{secrets_name} done: {} scanned, {} {}, {} failed",
+            self.scanned_count,
+            self.rotated_count,
+            rotated_label(dry_run),
+            self.failed_count
+        );
+    }
+}
+
+fn rotated_label(dry_run: bool) -> &'static str {
+    if dry_run {
+        "to re-encrypt (dry run)"
+    } else {
+        "re-encrypted"
+    }
+}
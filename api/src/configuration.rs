@@ -1,6 +1,7 @@
 use std::{collections::HashMap, env, time::Duration};
 
 use anyhow::Context;
+use chrono::{DateTime, Utc};
 use config::{Config, ConfigError, Environment, File};
 use hex;
 use openidconnect::{ClientId, ClientSecret as OidcClientSecret, IntrospectionUrl, IssuerUrl};
@@ -11,6 +12,7 @@ use serde::{
     de::{self, SeqAccess, Visitor},
 };
 use serde_with::{DisplayFromStr, serde_as};
+use sqlx::postgres::PgConnectOptions;
 use url::Url;
 
 use universal_inbox::{
@@ -26,6 +28,10 @@ pub struct Settings {
     pub application: ApplicationSettings,
     pub database: DatabaseSettings,
     pub redis: RedisSettings,
+    /// Backend of the job queue, cache, locks and MCP session store. The
+    /// section can be omitted to keep using Redis.
+    #[serde(default)]
+    pub storage: StorageSettings,
     pub integrations: HashMap<String, IntegrationSettings>,
     pub oauth2: Oauth2Settings,
 }
@@ -49,6 +55,8 @@ pub struct ApplicationSettings {
     pub sync_backoff_base_delay_in_seconds: u64,
     pub sync_backoff_max_delay_in_seconds: u64,
     pub sync_failure_window_in_hours: i64,
+    /// Number of sync runs kept per integration connection and kind of sync
+    pub sync_run_history_size: i64,
     pub observability: ObservabilitySettings,
     pub security: SecuritySettings,
     pub support_href: Option<String>,
@@ -68,6 +76,12 @@ pub struct ApplicationSettings {
 pub struct CronSettings {
     #[serde(default)]
     pub refresh_oauth_tokens: RefreshOAuthTokensCronSettings,
+    #[serde(default)]
+    pub sync_notifications: SyncCronSettings,
+    #[serde(default)]
+    pub sync_tasks: SyncCronSettings,
+    #[serde(default)]
+    pub apply_retention_policy: RetentionPolicyCronSettings,
 }
 
 #[derive(Deserialize, Clone, Debug)]
@@ -80,7 +94,7 @@ pub struct RefreshOAuthTokensCronSettings {
     /// Refresh OAuth tokens expiring within the next N minutes
     #[serde(default = "default_refresh_oauth_tokens_minutes_before_expiry")]
     pub minutes_before_expiry: i64,
-    /// TTL of the per-tick deduplication lock key in Redis
+    /// TTL of the per-tick deduplication lock key in the cache
     #[serde(default = "default_refresh_oauth_tokens_lock_ttl_seconds")]
     pub lock_ttl_seconds: u64,
 }
@@ -106,7 +120,129 @@ fn default_refresh_oauth_tokens_lock_ttl_seconds() -> u64 {
     60
 }
 
-/// Configuration for the Redis-backed MCP session store.
+/// Periodic sync of the integration connections of every user. Each tick
+/// enqueues one sync job per user and connection due for a sync, users active
+/// recently first.
+#[derive(Deserialize, Clone, Debug)]
+pub struct SyncCronSettings {
+    #[serde(default = "yes")]
+    pub is_enabled: bool,
+    /// Cron expression with a seconds field, e.g. `0 * * * * *`
+    #[serde(default = "default_sync_schedule")]
+    pub schedule: String,
+    /// TTL of the per-tick deduplication lock key in the cache
+    #[serde(default = "default_sync_lock_ttl_seconds")]
+    pub lock_ttl_seconds: u64,
+    /// A user with a session used within the last N minutes is considered
+    /// online: their connections are synced first and as often as the
+    /// `min_sync_*_interval_in_minutes` settings allow
+    #[serde(default = "default_sync_active_user_window_in_minutes")]
+    pub active_user_window_in_minutes: i64,
+    /// Minimum interval between 2 syncs of a connection of an offline user
+    #[serde(default = "default_sync_inactive_user_min_sync_interval_in_minutes")]
+    pub inactive_user_min_sync_interval_in_minutes: i64,
+}
+
+impl Default for SyncCronSettings {
+    fn default() -> Self {
+        Self {
+            is_enabled: yes(),
+            schedule: default_sync_schedule(),
+            lock_ttl_seconds: default_sync_lock_ttl_seconds(),
+            active_user_window_in_minutes: default_sync_active_user_window_in_minutes(),
+            inactive_user_min_sync_interval_in_minutes:
+                default_sync_inactive_user_min_sync_interval_in_minutes(),
+        }
+    }
+}
+
+fn default_sync_schedule() -> String {
+    "0 * * * * *".to_string()
+}
+fn default_sync_lock_ttl_seconds() -> u64 {
+    60
+}
+fn default_sync_active_user_window_in_minutes() -> i64 {
+    15
+}
+fn default_sync_inactive_user_min_sync_interval_in_minutes() -> i64 {
+    60
+}
+
+/// Periodic purge of the third party items of the notifications deleted or
+/// unsubscribed for a long time, and of the items not used anymore. Purged
+/// notification items leave a tombstone so that they are not re-created as
+/// new notifications when they are fetched again unchanged. Old
+/// dead-lettered jobs are purged along with them.
+#[derive(Deserialize, Clone, Debug)]
+pub struct RetentionPolicyCronSettings {
+    #[serde(default = "yes")]
+    pub is_enabled: bool,
+    /// Cron expression with a seconds field, e.g. `0 0 3 * * *`
+    #[serde(default = "default_retention_policy_schedule")]
+    pub schedule: String,
+    /// TTL of the per-tick deduplication lock key in the cache
+    #[serde(default = "default_retention_policy_lock_ttl_seconds")]
+    pub lock_ttl_seconds: u64,
+    /// Purge the items of the notifications deleted for more than N days
+    #[serde(default = "default_deleted_notifications_retention_in_days")]
+    pub deleted_notifications_retention_in_days: i64,
+    /// Purge the items of the notifications unsubscribed for more than N days
+    #[serde(default = "default_unsubscribed_notifications_retention_in_days")]
+    pub unsubscribed_notifications_retention_in_days: i64,
+    /// Purge the items without notification nor task not updated for more
+    /// than N days
+    #[serde(default = "default_orphan_third_party_items_retention_in_days")]
+    pub orphan_third_party_items_retention_in_days: i64,
+    /// Purge the jobs dead-lettered for more than N days
+    #[serde(default = "default_dead_letter_jobs_retention_in_days")]
+    pub dead_letter_jobs_retention_in_days: i64,
+    /// Number of items purged per transaction
+    #[serde(default = "default_retention_policy_batch_size")]
+    pub batch_size: i64,
+}
+
+impl Default for RetentionPolicyCronSettings {
+    fn default() -> Self {
+        Self {
+            is_enabled: yes(),
+            schedule: default_retention_policy_schedule(),
+            lock_ttl_seconds: default_retention_policy_lock_ttl_seconds(),
+            deleted_notifications_retention_in_days:
+                default_deleted_notifications_retention_in_days(),
+            unsubscribed_notifications_retention_in_days:
+                default_unsubscribed_notifications_retention_in_days(),
+            orphan_third_party_items_retention_in_days:
+                default_orphan_third_party_items_retention_in_days(),
+            dead_letter_jobs_retention_in_days: default_dead_letter_jobs_retention_in_days(),
+            batch_size: default_retention_policy_batch_size(),
+        }
+    }
+}
+
+fn default_retention_policy_schedule() -> String {
+    "0 0 3 * * *".to_string()
+}
+fn default_retention_policy_lock_ttl_seconds() -> u64 {
+    60
+}
+fn default_deleted_notifications_retention_in_days() -> i64 {
+    30
+}
+fn default_unsubscribed_notifications_retention_in_days() -> i64 {
+    90
+}
+fn default_orphan_third_party_items_retention_in_days() -> i64 {
+    30
+}
+fn default_dead_letter_jobs_retention_in_days() -> i64 {
+    30
+}
+fn default_retention_policy_batch_size() -> i64 {
+    500
+}
+
+/// Configuration for the MCP session store.
 ///
 /// The store persists each session's `initialize` parameters so that any pod
 /// behind the load balancer can transparently restore a session that was
@@ -177,12 +313,18 @@ pub struct SecuritySettings {
     /// access will be rejected with the corresponding message.
     #[serde(default)]
     pub email_domain_blacklist: HashMap<String, String>,
+    /// IDs of the users allowed to see operational details, such as the
+    /// dependency checks of the readiness probe
+    #[serde(default, deserialize_with = "deserialize_string_list")]
+    pub admin_user_ids: Vec<String>,
 }
 
 #[derive(Deserialize, Clone, Debug)]
 pub struct ObservabilitySettings {
     pub tracing: Option<TracingSettings>,
     pub logging: LoggingSettings,
+    #[serde(default)]
+    pub metrics: MetricsSettings,
 }
 
 fn yes() -> bool {
@@ -204,6 +346,15 @@ pub enum OtlpExporterProtocol {
     Grpc,
 }
 
+/// Metrics are exported over OTLP along with traces when tracing is configured
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct MetricsSettings {
+    /// Serve the metrics in the Prometheus text format on `/metrics` of the
+    /// worker health-check port
+    #[serde(default)]
+    pub is_prometheus_endpoint_enabled: bool,
+}
+
 #[derive(Deserialize, Clone, Debug)]
 pub struct LoggingSettings {
     pub log_directive: String,
@@ -216,6 +367,7 @@ pub enum AuthenticationSettings {
     OpenIDConnect(Box<OpenIDConnectSettings>),
     Local(LocalAuthenticationSettings),
     Passkey,
+    SAML(Box<SAMLSettings>),
 }
 
 #[derive(Deserialize, Clone, Debug)]
@@ -227,6 +379,60 @@ pub struct OpenIDConnectSettings {
     pub oidc_flow_settings: OIDCFlowSettings,
 }
 
+/// SAML 2.0 Web Browser SSO, Universal Inbox acting as the service provider
+#[derive(Deserialize, Clone, Debug)]
+pub struct SAMLSettings {
+    /// Entity ID of the identity provider, expected as the issuer of its responses
+    pub idp_entity_id: String,
+    /// Single sign-on service URL of the identity provider (HTTP-Redirect binding)
+    pub idp_sso_url: Url,
+    /// PEM encoded X.509 certificate the identity provider signs its responses with
+    pub idp_certificate: String,
+    /// Entity ID of Universal Inbox, defaults to the URL of its metadata
+    pub sp_entity_id: Option<String>,
+    #[serde(default)]
+    pub attribute_mapping: SAMLAttributeMapping,
+    /// Tolerated clock drift with the identity provider when checking the
+    /// validity period of an assertion
+    #[serde(default = "default_saml_allowed_clock_skew_seconds")]
+    pub allowed_clock_skew_seconds: i64,
+}
+
+/// Names of the assertion attributes mapped to the user's profile. The email
+/// falls back to the subject `NameID` when the attribute is missing.
+#[derive(Deserialize, Clone, Debug)]
+pub struct SAMLAttributeMapping {
+    #[serde(default = "default_saml_email_attribute")]
+    pub email: String,
+    #[serde(default = "default_saml_first_name_attribute")]
+    pub first_name: String,
+    #[serde(default = "default_saml_last_name_attribute")]
+    pub last_name: String,
+}
+
+impl Default for SAMLAttributeMapping {
+    fn default() -> Self {
+        Self {
+            email: default_saml_email_attribute(),
+            first_name: default_saml_first_name_attribute(),
+            last_name: default_saml_last_name_attribute(),
+        }
+    }
+}
+
+fn default_saml_allowed_clock_skew_seconds() -> i64 {
+    180
+}
+fn default_saml_email_attribute() -> String {
+    "email".to_string()
+}
+fn default_saml_first_name_attribute() -> String {
+    "firstName".to_string()
+}
+fn default_saml_last_name_attribute() -> String {
+    "lastName".to_string()
+}
+
 #[serde_as]
 #[derive(Deserialize, Clone, Debug)]
 pub struct LocalAuthenticationSettings {
@@ -242,7 +448,7 @@ pub struct LocalAuthenticationSettings {
     /// when omitted from config so existing deployments keep working.
     pub max_login_attempts: u32,
     /// Sliding window, in seconds, over which failed attempts are counted. The
-    /// Redis counter key expires after this window (or the active lockout,
+    /// counter key expires after this window (or the active lockout,
     /// whichever is longer), so attempts naturally decay.
     pub login_attempt_window_seconds: u64,
     /// Base lockout duration, in seconds, applied the first time the attempt
@@ -283,6 +489,10 @@ pub struct HttpSessionSettings {
     pub jwt_public_key: String,
     pub jwt_token_expiration_in_days: i64,
     pub max_age_days: i64,
+    /// Sessions opened before session tokens were stored are registered when
+    /// first seen, and rejected from this date onwards
+    #[serde(default)]
+    pub unregistered_sessions_rejected_from: Option<DateTime<Utc>>,
 }
 
 #[derive(Deserialize, Clone, Debug)]
@@ -305,10 +515,33 @@ pub struct RedisSettings {
     pub use_tls: bool,
 }
 
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct StorageSettings {
+    #[serde(default)]
+    pub backend: StorageBackend,
+}
+
+/// `Postgres` stores everything in the application database so that
+/// self-hosted instances can run without Redis.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StorageBackend {
+    #[default]
+    Redis,
+    Postgres,
+}
+
 #[derive(Deserialize, Clone, Debug)]
 pub struct Oauth2Settings {
     /// Hex-encoded 32-byte AES-256 key for encrypting OAuth tokens at rest.
     pub token_encryption_key: String,
+    /// ID of `token_encryption_key`, stored along with every token it encrypts.
+    #[serde(default = "default_token_encryption_key_id")]
+    pub token_encryption_key_id: String,
+    /// Hex-encoded keys by ID which are only used to decrypt tokens. When
+    /// rotating keys, the previous primary key is moved here until
+    /// `secrets rotate` re-encrypted every token with the new one.
+    #[serde(default)]
+    pub previous_token_encryption_keys: HashMap<String, String>,
     /// Tunables for the Client ID Metadata Discovery (CIMD) document fetcher.
     /// Defaults match draft-ietf-oauth-client-id-metadata-document recommendations.
     #[serde(default)]
@@ -349,6 +582,10 @@ impl Default for CimdSettings {
     }
 }
 
+fn default_token_encryption_key_id() -> String {
+    "default".to_string()
+}
+
 fn default_cimd_timeout_secs() -> u64 {
     5
 }
@@ -398,6 +635,9 @@ pub struct IntegrationSettings {
     pub is_enabled: bool,
     pub api_max_retry_duration_http_seconds: Option<u64>,
     pub api_max_retry_duration_worker_seconds: Option<u64>,
+    /// Maximum number of sync jobs of this integration running at the same
+    /// time across all the workers, to stay within the provider's rate limits
+    pub max_concurrent_syncs: Option<usize>,
     pub oauth_client_id: String,
     pub oauth_client_secret: ClientSecret,
     #[serde(default)]
@@ -486,6 +726,15 @@ impl DatabaseSettings {
         self.connection_string().replace(&self.password, "********")
     }
 
+    pub fn connect_options(&self) -> PgConnectOptions {
+        PgConnectOptions::new()
+            .username(&self.username)
+            .password(&self.password)
+            .host(&self.host)
+            .port(self.port)
+            .database(&self.database_name)
+    }
+
     pub fn connection_string_without_db(&self) -> String {
         format!(
             "postgres://{}:{}@{}:{}{}",
@@ -613,9 +862,45 @@ impl ApplicationSettings {
             .join("auth/session/authenticated")
             .context("Failed to parse OIDC redirect URL")?)
     }
+
+    /// URL of the SAML service provider metadata, registered in the identity provider
+    pub fn get_saml_metadata_url(&self) -> Result<Url, UniversalInboxError> {
+        Ok(self
+            .front_base_url
+            .join(&self.api_path)
+            .context("Failed to parse SAML metadata URL")?
+            .join("auth/saml/metadata")
+            .context("Failed to parse SAML metadata URL")?)
+    }
+
+    /// URL of the SAML Assertion Consumer Service (HTTP-POST binding)
+    pub fn get_saml_acs_url(&self) -> Result<Url, UniversalInboxError> {
+        Ok(self
+            .front_base_url
+            .join(&self.api_path)
+            .context("Failed to parse SAML ACS URL")?
+            .join("auth/saml/acs")
+            .context("Failed to parse SAML ACS URL")?)
+    }
+
+    pub fn get_saml_sp_entity_id(
+        &self,
+        saml_settings: &SAMLSettings,
+    ) -> Result<String, UniversalInboxError> {
+        match &saml_settings.sp_entity_id {
+            Some(sp_entity_id) => Ok(sp_entity_id.clone()),
+            None => Ok(self.get_saml_metadata_url()?.to_string()),
+        }
+    }
 }
 
 impl SecuritySettings {
+    pub fn is_admin(&self, user_id: &str) -> bool {
+        self.admin_user_ids
+            .iter()
+            .any(|admin_user_id| admin_user_id == user_id)
+    }
+
     pub fn get_authentication_settings(
         &self,
         user_auth_kind: UserAuthKind,
@@ -625,6 +910,7 @@ impl SecuritySettings {
             .find(|auth_settings| match (auth_settings, user_auth_kind) {
                 (AuthenticationSettings::Local(_), UserAuthKind::Local) => true,
                 (AuthenticationSettings::Passkey, UserAuthKind::Passkey) => true,
+                (AuthenticationSettings::SAML(_), UserAuthKind::SAML) => true,
                 (
                     AuthenticationSettings::OpenIDConnect(oidc_settings),
                     UserAuthKind::OIDCAuthorizationCodePKCE,
@@ -674,6 +960,7 @@ mod tests {
             mcp_extra_allowed_origins: vec![],
             authentication: vec![local_auth_settings],
             email_domain_blacklist: HashMap::new(),
+            admin_user_ids: vec![],
         };
 
         let result = security_settings.get_authentication_settings(UserAuthKind::Local);
@@ -708,6 +995,7 @@ mod tests {
             mcp_extra_allowed_origins: vec![],
             authentication: vec![oidc_auth_settings],
             email_domain_blacklist: HashMap::new(),
+            admin_user_ids: vec![],
         };
 
         let result =
@@ -734,6 +1022,7 @@ mod tests {
             mcp_extra_allowed_origins: vec![],
             authentication: vec![oidc_auth_settings],
             email_domain_blacklist: HashMap::new(),
+            admin_user_ids: vec![],
         };
 
         let result = security_settings
@@ -780,6 +1069,7 @@ mod tests {
             mcp_extra_allowed_origins: vec![],
             authentication: vec![local_auth_settings, oidc_auth_settings],
             email_domain_blacklist: HashMap::new(),
+            admin_user_ids: vec![],
         };
 
         let result = security_settings.get_authentication_settings(UserAuthKind::Local);
@@ -0,0 +1,297 @@
+use anyhow::Context;
+use url::Url;
+
+use universal_inbox::third_party::integrations::github::{
+    GithubActor, GithubBotSummary, GithubIssue, GithubIssueComment, GithubIssueState,
+    GithubIssueStateReason, GithubLabel, GithubLinkedPullRequest, GithubMilestone,
+    GithubPullRequestState, GithubRepositorySummary, GithubUserSummary,
+};
+
+use crate::{integrations::github::graphql::issue_query, universal_inbox::UniversalInboxError};
+
+impl From<issue_query::IssueState> for GithubIssueState {
+    fn from(value: issue_query::IssueState) -> Self {
+        match value {
+            issue_query::IssueState::CLOSED => GithubIssueState::Closed,
+            issue_query::IssueState::OPEN => GithubIssueState::Open,
+            issue_query::IssueState::Other(_) => GithubIssueState::Open,
+        }
+    }
+}
+
+impl From<issue_query::IssueStateReason> for GithubIssueStateReason {
+    fn from(value: issue_query::IssueStateReason) -> Self {
+        match value {
+            issue_query::IssueStateReason::COMPLETED => GithubIssueStateReason::Completed,
+            issue_query::IssueStateReason::NOT_PLANNED => GithubIssueStateReason::NotPlanned,
+            issue_query::IssueStateReason::REOPENED => GithubIssueStateReason::Reopened,
+            issue_query::IssueStateReason::Other(_) => GithubIssueStateReason::Completed,
+        }
+    }
+}
+
+impl From<issue_query::PullRequestState> for GithubPullRequestState {
+    fn from(value: issue_query::PullRequestState) -> Self {
+        match value {
+            issue_query::PullRequestState::CLOSED => GithubPullRequestState::Closed,
+            issue_query::PullRequestState::MERGED => GithubPullRequestState::Merged,
+            issue_query::PullRequestState::OPEN => GithubPullRequestState::Open,
+            issue_query::PullRequestState::Other(_) => GithubPullRequestState::Open,
+        }
+    }
+}
+
+impl From<issue_query::IssueQueryRepositoryIssueLabels> for Vec<GithubLabel> {
+    fn from(value: issue_query::IssueQueryRepositoryIssueLabels) -> Self {
+        value
+            .nodes
+            .map(|labels| {
+                labels
+                    .into_iter()
+                    .filter_map(|label| {
+                        label.map(|label| GithubLabel {
+                            name: label.name,
+                            color: label.color,
+                            description: label.description,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl TryFrom<issue_query::IssueQueryRepositoryIssueAssignees> for Vec<GithubActor> {
+    type Error = UniversalInboxError;
+
+    fn try_from(
+        value: issue_query::IssueQueryRepositoryIssueAssignees,
+    ) -> Result<Self, Self::Error> {
+        value
+            .nodes
+            .map(|nodes| {
+                nodes
+                    .into_iter()
+                    .filter_map(|node| {
+                        node.map(|node| {
+                            Ok(GithubActor::User(GithubUserSummary {
+                                name: node.name,
+                                login: node.login,
+                                avatar_url: node.avatar_url.parse::<Url>().with_context(|| {
+                                    format!(
+                                        "Github actor should have a valid avatar URL: {:?}",
+                                        node.avatar_url
+                                    )
+                                })?,
+                            }))
+                        })
+                    })
+                    .collect::<Result<Vec<GithubActor>, UniversalInboxError>>()
+            })
+            .unwrap_or_else(|| Ok(Vec::new()))
+    }
+}
+
+impl TryFrom<issue_query::IssueQueryRepositoryIssueMilestone> for GithubMilestone {
+    type Error = UniversalInboxError;
+
+    fn try_from(
+        value: issue_query::IssueQueryRepositoryIssueMilestone,
+    ) -> Result<Self, Self::Error> {
+        Ok(GithubMilestone {
+            title: value.title,
+            url: value.url.parse().with_context(|| {
+                format!("Unable to parse Github milestone URL: {:?}", value.url)
+            })?,
+            due_on: value.due_on,
+        })
+    }
+}
+
+impl TryFrom<issue_query::IssueQueryRepositoryIssueAuthor> for GithubActor {
+    type Error = UniversalInboxError;
+
+    fn try_from(value: issue_query::IssueQueryRepositoryIssueAuthor) -> Result<Self, Self::Error> {
+        let avatar_url = value.avatar_url.parse::<Url>().with_context(|| {
+            format!(
+                "Github actor should have a valid avatar URL: {:?}",
+                value.avatar_url
+            )
+        })?;
+        Ok(match value.on {
+            issue_query::IssueQueryRepositoryIssueAuthorOn::User(user) => {
+                GithubActor::User(GithubUserSummary {
+                    login: value.login,
+                    name: user.name,
+                    avatar_url,
+                })
+            }
+            // Simplification: any other users are considered as bot. May be revisited in the future
+            _ => GithubActor::Bot(GithubBotSummary {
+                login: value.login,
+                avatar_url,
+            }),
+        })
+    }
+}
+
+impl TryFrom<issue_query::IssueQueryRepositoryIssueCommentsNodesAuthor> for GithubActor {
+    type Error = UniversalInboxError;
+
+    fn try_from(
+        value: issue_query::IssueQueryRepositoryIssueCommentsNodesAuthor,
+    ) -> Result<Self, Self::Error> {
+        let avatar_url = value.avatar_url.parse::<Url>().with_context(|| {
+            format!(
+                "Github actor should have a valid avatar URL: {:?}",
+                value.avatar_url
+            )
+        })?;
+        Ok(match value.on {
+            issue_query::IssueQueryRepositoryIssueCommentsNodesAuthorOn::User(user) => {
+                GithubActor::User(GithubUserSummary {
+                    login: value.login,
+                    name: user.name,
+                    avatar_url,
+                })
+            }
+            _ => GithubActor::Bot(GithubBotSummary {
+                login: value.login,
+                avatar_url,
+            }),
+        })
+    }
+}
+
+impl TryFrom<issue_query::IssueQueryRepositoryIssueCommentsNodes> for GithubIssueComment {
+    type Error = UniversalInboxError;
+
+    fn try_from(
+        value: issue_query::IssueQueryRepositoryIssueCommentsNodes,
+    ) -> Result<Self, Self::Error> {
+        Ok(GithubIssueComment {
+            url: value.url.parse().with_context(|| {
+                format!("Unable to parse Github issue comment URL: {:?}", value.url)
+            })?,
+            body: value.body_html,
+            created_at: value.created_at,
+            author: value.author.map(|author| author.try_into()).transpose()?,
+        })
+    }
+}
+
+impl TryFrom<issue_query::IssueQueryRepositoryIssueTimelineItemsNodesOnCrossReferencedEvent>
+    for Option<GithubLinkedPullRequest>
+{
+    type Error = UniversalInboxError;
+
+    fn try_from(
+        value: issue_query::IssueQueryRepositoryIssueTimelineItemsNodesOnCrossReferencedEvent,
+    ) -> Result<Self, Self::Error> {
+        let issue_query::IssueQueryRepositoryIssueTimelineItemsNodesOnCrossReferencedEventSource::PullRequest(pr) = value.source else {
+            // Only pull requests are considered as linked to the issue
+            return Ok(None);
+        };
+
+        Ok(Some(GithubLinkedPullRequest {
+            number: pr.number,
+            url: pr.url.parse().with_context(|| {
+                format!("Unable to parse Github pull request URL: {:?}", pr.url)
+            })?,
+            title: pr.title,
+            state: pr.state.into(),
+            repository: GithubRepositorySummary {
+                name_with_owner: pr.repository.name_with_owner,
+                url: pr.repository.url.parse().with_context(|| {
+                    format!(
+                        "Unable to parse Github repository URL: {:?}",
+                        pr.repository.url
+                    )
+                })?,
+            },
+            will_close_issue: value.will_close_target,
+        }))
+    }
+}
+
+impl TryFrom<issue_query::IssueQueryRepositoryIssueTimelineItems> for Vec<GithubLinkedPullRequest> {
+    type Error = UniversalInboxError;
+
+    fn try_from(
+        value: issue_query::IssueQueryRepositoryIssueTimelineItems,
+    ) -> Result<Self, Self::Error> {
+        let mut linked_pull_requests: Vec<GithubLinkedPullRequest> = vec![];
+        for node in value.nodes.unwrap_or_default().into_iter().flatten() {
+            if let issue_query::IssueQueryRepositoryIssueTimelineItemsNodes::CrossReferencedEvent(
+                event,
+            ) = node
+                && let Some(linked_pull_request) =
+                    TryInto::<Option<GithubLinkedPullRequest>>::try_into(event)?
+                // A pull request may reference the issue several times
+                && !linked_pull_requests
+                    .iter()
+                    .any(|pr| pr.url == linked_pull_request.url)
+            {
+                linked_pull_requests.push(linked_pull_request);
+            }
+        }
+
+        Ok(linked_pull_requests)
+    }
+}
+
+impl TryFrom<issue_query::ResponseData> for GithubIssue {
+    type Error = UniversalInboxError;
+
+    fn try_from(value: issue_query::ResponseData) -> Result<Self, Self::Error> {
+        let issue = value
+            .repository
+            .context("Github repository not found")?
+            .issue
+            .context("Github issue not found")?;
+
+        Ok(GithubIssue {
+            id: issue.id,
+            number: issue.number,
+            url: issue
+                .url
+                .parse()
+                .with_context(|| format!("Unable to parse Github issue URL: {:?}", issue.url))?,
+            title: issue.title_html,
+            body: issue.body_html,
+            state: issue.state.into(),
+            state_reason: issue.state_reason.map(|state_reason| state_reason.into()),
+            closed_at: issue.closed_at,
+            created_at: issue.created_at,
+            updated_at: issue.updated_at,
+            repository: GithubRepositorySummary {
+                name_with_owner: issue.repository.name_with_owner,
+                url: issue.repository.url.parse().with_context(|| {
+                    format!(
+                        "Unable to parse Github repository URL: {:?}",
+                        issue.repository.url
+                    )
+                })?,
+            },
+            labels: issue.labels.map(|labels| labels.into()).unwrap_or_default(),
+            assignees: issue.assignees.try_into()?,
+            milestone: issue
+                .milestone
+                .map(|milestone| milestone.try_into())
+                .transpose()?,
+            comments_count: issue.comments.total_count,
+            comments: issue
+                .comments
+                .nodes
+                .map(|nodes| {
+                    nodes
+                        .into_iter()
+                        .filter_map(|node| node.map(|node| node.try_into()))
+                        .collect::<Result<Vec<GithubIssueComment>, UniversalInboxError>>()
+                })
+                .unwrap_or_else(|| Ok(Vec::new()))?,
+            linked_pull_requests: issue.timeline_items.try_into()?,
+            author: issue.author.map(|author| author.try_into()).transpose()?,
+        })
+    }
+}
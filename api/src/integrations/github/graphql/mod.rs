@@ -6,7 +6,10 @@ use graphql_client::GraphQLQuery;
 use universal_inbox::third_party::integrations::github::GitObjectId;
 
 pub mod discussion;
+pub mod issue;
+pub mod notification_items;
 pub mod pull_request;
+pub mod task_items;
 
 // Define some GraphQL types used in the Github API
 type DateTime = ChronoDateTime<Utc>;
@@ -31,3 +34,48 @@ pub struct PullRequestQuery;
     variables_derives = "Deserialize"
 )]
 pub struct DiscussionQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/integrations/github/graphql/schema.graphql",
+    query_path = "src/integrations/github/graphql/issue_query.graphql",
+    response_derives = "Debug,Clone,Serialize",
+    variables_derives = "Deserialize"
+)]
+pub struct IssueQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/integrations/github/graphql/schema.graphql",
+    query_path = "src/integrations/github/graphql/task_items_query.graphql",
+    response_derives = "Debug,Clone,Serialize",
+    variables_derives = "Deserialize"
+)]
+pub struct TaskItemsQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/integrations/github/graphql/schema.graphql",
+    query_path = "src/integrations/github/graphql/add_pull_request_review_mutation.graphql",
+    response_derives = "Debug,Clone,Serialize",
+    variables_derives = "Deserialize"
+)]
+pub struct AddPullRequestReviewMutation;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/integrations/github/graphql/schema.graphql",
+    query_path = "src/integrations/github/graphql/add_comment_mutation.graphql",
+    response_derives = "Debug,Clone,Serialize",
+    variables_derives = "Deserialize"
+)]
+pub struct AddCommentMutation;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/integrations/github/graphql/schema.graphql",
+    query_path = "src/integrations/github/graphql/merge_pull_request_mutation.graphql",
+    response_derives = "Debug,Clone,Serialize",
+    variables_derives = "Deserialize"
+)]
+pub struct MergePullRequestMutation;
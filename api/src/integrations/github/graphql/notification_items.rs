@@ -0,0 +1,264 @@
+use anyhow::{Context, anyhow};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Map, Value, json};
+
+use universal_inbox::third_party::integrations::github::{GithubNotificationItem, GithubUrl};
+
+use crate::{
+    integrations::github::graphql::{discussion_query, issue_query, pull_request_query},
+    universal_inbox::UniversalInboxError,
+};
+
+pub static NOTIFICATION_ITEMS_QUERY_OPERATION_NAME: &str = "NotificationItemsQuery";
+
+// The batched query reuses the selection of each single item query so that
+// their generated response types can parse each aliased result
+static PULL_REQUEST_QUERY: &str = include_str!("pull_request_query.graphql");
+static DISCUSSION_QUERY: &str = include_str!("discussion_query.graphql");
+static ISSUE_QUERY: &str = include_str!("issue_query.graphql");
+
+/// Body of a GraphQL request fetching the details of several notification subjects at once,
+/// each one being queried under its own `item{index}` alias
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct NotificationItemsQueryBody {
+    pub variables: Map<String, Value>,
+    pub query: String,
+    #[serde(rename = "operationName")]
+    pub operation_name: String,
+}
+
+pub fn build_notification_items_query(
+    subjects: &[GithubUrl],
+) -> Result<NotificationItemsQueryBody, UniversalInboxError> {
+    let mut variable_definitions = vec![];
+    let mut selections = vec![];
+    let mut variables = Map::new();
+
+    for (index, subject) in subjects.iter().enumerate() {
+        let (query, subject_variables) = match subject.clone() {
+            GithubUrl::PullRequest {
+                owner,
+                repository,
+                number,
+            } => (
+                PULL_REQUEST_QUERY,
+                serde_json::to_value(pull_request_query::Variables {
+                    owner,
+                    repository,
+                    pr_number: number,
+                }),
+            ),
+            GithubUrl::Discussion {
+                owner,
+                repository,
+                number,
+            } => (
+                DISCUSSION_QUERY,
+                serde_json::to_value(discussion_query::Variables {
+                    owner,
+                    repository,
+                    discussion_number: number,
+                }),
+            ),
+            GithubUrl::Issue {
+                owner,
+                repository,
+                number,
+            } => (
+                ISSUE_QUERY,
+                serde_json::to_value(issue_query::Variables {
+                    owner,
+                    repository,
+                    issue_number: number,
+                }),
+            ),
+            GithubUrl::Release { .. } => {
+                return Err(UniversalInboxError::UnsupportedAction(format!(
+                    "Cannot query Github release {subject:?} using the Github graphql API"
+                )));
+            }
+        };
+        let Value::Object(subject_variables) =
+            subject_variables.context("Cannot serialize Github graphql query variables")?
+        else {
+            return Err(anyhow!("Github graphql query variables must be an object").into());
+        };
+        let (definitions, selection) = split_query(query)
+            .ok_or_else(|| anyhow!("Cannot parse Github graphql query of {subject:?}"))?;
+
+        variable_definitions.extend(
+            definitions
+                .into_iter()
+                .map(|(name, variable_type)| format!("${name}_{index}: {variable_type}")),
+        );
+        variables.extend(
+            subject_variables
+                .into_iter()
+                .map(|(name, value)| (format!("{name}_{index}"), value)),
+        );
+        selections.push(format!(
+            "item{index}: {}",
+            suffix_variables(selection.trim(), index)
+        ));
+    }
+
+    Ok(NotificationItemsQueryBody {
+        variables,
+        query: format!(
+            "query {NOTIFICATION_ITEMS_QUERY_OPERATION_NAME}({}) {{\n{}\n}}\n",
+            variable_definitions.join(", "),
+            selections.join("\n")
+        ),
+        operation_name: NOTIFICATION_ITEMS_QUERY_OPERATION_NAME.to_string(),
+    })
+}
+
+/// Parse the details of the subject queried under the alias of the given index
+pub fn parse_notification_item(
+    subject: &GithubUrl,
+    index: usize,
+    data: &mut Map<String, Value>,
+) -> Result<Option<GithubNotificationItem>, UniversalInboxError> {
+    let repository = data.remove(&format!("item{index}")).unwrap_or(Value::Null);
+
+    Ok(match subject {
+        GithubUrl::PullRequest { .. } => Some(GithubNotificationItem::GithubPullRequest(
+            parse_response_data::<pull_request_query::ResponseData>(repository)?.try_into()?,
+        )),
+        GithubUrl::Discussion { .. } => Some(GithubNotificationItem::GithubDiscussion(
+            parse_response_data::<discussion_query::ResponseData>(repository)?.try_into()?,
+        )),
+        GithubUrl::Issue { .. } => Some(GithubNotificationItem::GithubIssue(
+            parse_response_data::<issue_query::ResponseData>(repository)?.try_into()?,
+        )),
+        GithubUrl::Release { .. } => None,
+    })
+}
+
+fn parse_response_data<T: DeserializeOwned>(repository: Value) -> Result<T, UniversalInboxError> {
+    Ok(serde_json::from_value(json!({ "repository": repository }))
+        .context("Failed to parse Github graphql batched response")?)
+}
+
+/// Split a `query Name($variable: Type, ...) { selection }` document into its variable
+/// definitions and its selection set
+fn split_query(query: &str) -> Option<(Vec<(&str, &str)>, &str)> {
+    let selection_start = query.find('{')?;
+    let selection_end = query.rfind('}')?;
+    let header = &query[..selection_start];
+    let definitions = &header[header.find('(')? + 1..header.rfind(')')?];
+
+    let definitions = definitions
+        .split(',')
+        .map(|definition| {
+            definition.split_once(':').map(|(name, variable_type)| {
+                (name.trim().trim_start_matches('$'), variable_type.trim())
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some((definitions, &query[selection_start + 1..selection_end]))
+}
+
+/// Rename every `$variable` of a selection to `$variable_{suffix}`
+fn suffix_variables(selection: &str, suffix: usize) -> String {
+    let mut result = String::with_capacity(selection.len());
+    let mut in_variable = false;
+    for c in selection.chars() {
+        if in_variable && !(c.is_alphanumeric() || c == '_') {
+            result.push_str(&format!("_{suffix}"));
+            in_variable = false;
+        }
+        if c == '$' {
+            in_variable = true;
+        }
+        result.push(c);
+    }
+    if in_variable {
+        result.push_str(&format!("_{suffix}"));
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_split_query() {
+        let (definitions, selection) = split_query(
+            "query Q($owner: String!, $pr_number: Int!) {\n  repository(owner: $owner) { id }\n}\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            definitions,
+            vec![("owner", "String!"), ("pr_number", "Int!")]
+        );
+        assert_eq!(selection.trim(), "repository(owner: $owner) { id }");
+    }
+
+    #[test]
+    fn test_suffix_variables() {
+        assert_eq!(
+            suffix_variables(
+                "repository(owner: $owner, name: $repository) { pr(n: $n) }",
+                3
+            ),
+            "repository(owner: $owner_3, name: $repository_3) { pr(n: $n_3) }"
+        );
+    }
+
+    #[test]
+    fn test_build_notification_items_query() {
+        let query_body = build_notification_items_query(&[
+            GithubUrl::PullRequest {
+                owner: "octokit".to_string(),
+                repository: "octokit.rb".to_string(),
+                number: 123,
+            },
+            GithubUrl::Issue {
+                owner: "octokit".to_string(),
+                repository: "octokit.rb".to_string(),
+                number: 456,
+            },
+        ])
+        .unwrap();
+
+        assert_eq!(query_body.operation_name, "NotificationItemsQuery");
+        assert_eq!(query_body.variables["pr_number_0"], json!(123));
+        assert_eq!(query_body.variables["issue_number_1"], json!(456));
+        assert_eq!(query_body.variables["owner_1"], json!("octokit"));
+        assert!(query_body.query.starts_with(
+            "query NotificationItemsQuery($owner_0: String!, $repository_0: String!, $pr_number_0: Int!, $owner_1: String!"
+        ));
+        assert!(
+            query_body
+                .query
+                .contains("item0: repository(owner: $owner_0, name: $repository_0)")
+        );
+        assert!(
+            query_body
+                .query
+                .contains("pullRequest(number: $pr_number_0)")
+        );
+        assert!(query_body.query.contains("issue(number: $issue_number_1)"));
+    }
+
+    #[test]
+    fn test_build_notification_items_query_with_release() {
+        let result = build_notification_items_query(&[GithubUrl::Release {
+            owner: "octokit".to_string(),
+            repository: "octokit.rb".to_string(),
+            id: 1,
+        }]);
+
+        assert!(matches!(
+            result,
+            Err(UniversalInboxError::UnsupportedAction(_))
+        ));
+    }
+}
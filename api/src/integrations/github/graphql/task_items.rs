@@ -0,0 +1,191 @@
+use anyhow::Context;
+use url::Url;
+
+use universal_inbox::third_party::integrations::github::{
+    GithubActor, GithubBotSummary, GithubLabel, GithubRepositorySummary, GithubTaskItem,
+    GithubTaskItemKind, GithubTaskItemState, GithubUserSummary,
+};
+
+use crate::{
+    integrations::github::graphql::task_items_query, universal_inbox::UniversalInboxError,
+};
+
+fn parse_repository_summary(
+    name_with_owner: String,
+    url: String,
+) -> Result<GithubRepositorySummary, UniversalInboxError> {
+    Ok(GithubRepositorySummary {
+        name_with_owner,
+        url: url
+            .parse()
+            .with_context(|| format!("Unable to parse Github repository URL: {url:?}"))?,
+    })
+}
+
+fn parse_actor(
+    login: String,
+    avatar_url: String,
+    user_name: Option<Option<String>>,
+) -> Result<GithubActor, UniversalInboxError> {
+    let avatar_url = avatar_url
+        .parse::<Url>()
+        .with_context(|| format!("Github actor should have a valid avatar URL: {avatar_url:?}"))?;
+
+    Ok(match user_name {
+        Some(name) => GithubActor::User(GithubUserSummary {
+            login,
+            name,
+            avatar_url,
+        }),
+        // Simplification: any other users are considered as bot. May be revisited in the future
+        None => GithubActor::Bot(GithubBotSummary { login, avatar_url }),
+    })
+}
+
+impl TryFrom<task_items_query::TaskItemsQueryAssignedIssuesNodesOnIssue> for GithubTaskItem {
+    type Error = UniversalInboxError;
+
+    fn try_from(
+        issue: task_items_query::TaskItemsQueryAssignedIssuesNodesOnIssue,
+    ) -> Result<Self, Self::Error> {
+        Ok(GithubTaskItem {
+            id: issue.id,
+            kind: GithubTaskItemKind::AssignedIssue,
+            number: issue.number,
+            url: issue
+                .url
+                .parse()
+                .with_context(|| format!("Unable to parse Github issue URL: {:?}", issue.url))?,
+            title: issue.title,
+            body: issue.body,
+            state: if issue.closed_at.is_some() {
+                GithubTaskItemState::Completed
+            } else {
+                GithubTaskItemState::Open
+            },
+            repository: parse_repository_summary(
+                issue.repository.name_with_owner,
+                issue.repository.url,
+            )?,
+            labels: issue
+                .labels
+                .and_then(|labels| labels.nodes)
+                .map(|labels| {
+                    labels
+                        .into_iter()
+                        .flatten()
+                        .map(|label| GithubLabel {
+                            name: label.name,
+                            color: label.color,
+                            description: label.description,
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            author: issue
+                .author
+                .map(|author| {
+                    let user_name = match author.on {
+                        task_items_query::TaskItemsQueryAssignedIssuesNodesOnIssueAuthorOn::User(
+                            user,
+                        ) => Some(user.name),
+                        _ => None,
+                    };
+                    parse_actor(author.login, author.avatar_url, user_name)
+                })
+                .transpose()?,
+            created_at: issue.created_at,
+            updated_at: issue.updated_at,
+            completed_at: issue.closed_at,
+        })
+    }
+}
+
+impl TryFrom<task_items_query::TaskItemsQueryReviewRequestsNodesOnPullRequest> for GithubTaskItem {
+    type Error = UniversalInboxError;
+
+    fn try_from(
+        pr: task_items_query::TaskItemsQueryReviewRequestsNodesOnPullRequest,
+    ) -> Result<Self, Self::Error> {
+        Ok(GithubTaskItem {
+            id: pr.id,
+            kind: GithubTaskItemKind::ReviewRequest,
+            number: pr.number,
+            url: pr
+                .url
+                .parse()
+                .with_context(|| format!("Unable to parse Github pull request URL: {:?}", pr.url))?,
+            title: pr.title,
+            body: pr.body,
+            state: if pr.closed_at.is_some() {
+                GithubTaskItemState::Completed
+            } else {
+                GithubTaskItemState::Open
+            },
+            repository: parse_repository_summary(pr.repository.name_with_owner, pr.repository.url)?,
+            labels: pr
+                .labels
+                .and_then(|labels| labels.nodes)
+                .map(|labels| {
+                    labels
+                        .into_iter()
+                        .flatten()
+                        .map(|label| GithubLabel {
+                            name: label.name,
+                            color: label.color,
+                            description: label.description,
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            author: pr
+                .author
+                .map(|author| {
+                    let user_name = match author.on {
+                        task_items_query::TaskItemsQueryReviewRequestsNodesOnPullRequestAuthorOn::User(
+                            user,
+                        ) => Some(user.name),
+                        _ => None,
+                    };
+                    parse_actor(author.login, author.avatar_url, user_name)
+                })
+                .transpose()?,
+            created_at: pr.created_at,
+            updated_at: pr.updated_at,
+            completed_at: pr.closed_at,
+        })
+    }
+}
+
+impl TryFrom<task_items_query::ResponseData> for Vec<GithubTaskItem> {
+    type Error = UniversalInboxError;
+
+    fn try_from(value: task_items_query::ResponseData) -> Result<Self, Self::Error> {
+        let assigned_issues = value
+            .assigned_issues
+            .nodes
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .filter_map(|node| match node {
+                task_items_query::TaskItemsQueryAssignedIssuesNodes::Issue(issue) => {
+                    Some(issue.try_into())
+                }
+                _ => None,
+            });
+        let review_requests = value
+            .review_requests
+            .nodes
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .filter_map(|node| match node {
+                task_items_query::TaskItemsQueryReviewRequestsNodes::PullRequest(pr) => {
+                    Some(pr.try_into())
+                }
+                _ => None,
+            });
+
+        assigned_issues.chain(review_requests).collect()
+    }
+}
@@ -3,13 +3,13 @@ use std::{sync::Arc, time::Duration};
 use anyhow::{Context, anyhow};
 use async_trait::async_trait;
 use chrono::{DateTime, Timelike, Utc};
-use futures::stream::{self, TryStreamExt};
 use graphql_client::{GraphQLQuery, Response};
 use http::{HeaderMap, HeaderValue};
 use notification::RawGithubNotification;
-use serde_json::json;
+use serde_json::{Map, Value, json};
 use sqlx::{Postgres, Transaction};
 use tokio::sync::RwLock;
+use tracing::debug;
 use url::Url;
 use uuid::Uuid;
 use wiremock::{
@@ -18,29 +18,64 @@ use wiremock::{
 };
 
 use universal_inbox::{
-    integration_connection::provider::{IntegrationProviderKind, IntegrationProviderSource},
-    notification::{Notification, NotificationSource, NotificationSourceKind, NotificationStatus},
+    HasHtmlUrl,
+    integration_connection::{
+        integrations::github::GithubContext,
+        provider::{
+            IntegrationConnectionContext, IntegrationProvider, IntegrationProviderKind,
+            IntegrationProviderSource,
+        },
+    },
+    notification::{
+        Notification, NotificationSource, NotificationSourceKind, NotificationStatus,
+        service::GithubPullRequestAction,
+    },
+    task::{
+        CreateOrUpdateTaskRequest, TaskCreationConfig, TaskSource, TaskSourceKind, TaskStatus,
+        integrations::todoist::TODOIST_INBOX_PROJECT, service::TaskPatch,
+    },
     third_party::{
-        integrations::github::{GithubNotification, GithubNotificationItem, GithubUrl},
+        integrations::github::{
+            GitObjectId, GithubMergeMethod, GithubNotification, GithubNotificationItem,
+            GithubRelease, GithubRepositorySummary, GithubTaskItem, GithubTaskItemState, GithubUrl,
+            GithubWorkflowRun,
+        },
         item::{ThirdPartyItem, ThirdPartyItemFromSource, ThirdPartyItemSourceKind},
     },
     user::UserId,
+    utils::default_value::DefaultValue,
 };
 
 use crate::{
     integrations::{
         github::graphql::{
-            DiscussionQuery, PullRequestQuery, discussion_query, pull_request_query,
+            AddCommentMutation, AddPullRequestReviewMutation, DiscussionQuery, IssueQuery,
+            MergePullRequestMutation, PullRequestQuery, TaskItemsQuery, add_comment_mutation,
+            add_pull_request_review_mutation, discussion_query, issue_query,
+            merge_pull_request_mutation,
+            notification_items::{
+                NOTIFICATION_ITEMS_QUERY_OPERATION_NAME, build_notification_items_query,
+                parse_notification_item,
+            },
+            pull_request_query, task_items_query,
+        },
+        github::{
+            release::RawGithubRelease,
+            workflow_run::{
+                RawGithubWorkflowJobs, RawGithubWorkflowRuns, is_failed_conclusion,
+                parse_workflow_run_notification_title,
+            },
         },
         notification::ThirdPartyNotificationSourceService,
         oauth2::AccessToken,
+        task::ThirdPartyTaskService,
         third_party::ThirdPartyItemSourceService,
     },
     universal_inbox::{
         UniversalInboxError, integration_connection::service::IntegrationConnectionService,
     },
     utils::{
-        api::{ApiClient, ApiClientError},
+        api::{ApiClient, ApiClientError, ConditionalResponse},
         graphql::assert_no_error_in_graphql_response,
     },
 };
@@ -48,6 +83,8 @@ use crate::{
 pub mod graphql;
 pub mod notification;
 pub mod oauth;
+pub mod release;
+pub mod workflow_run;
 
 #[derive(Clone)]
 pub struct GithubService {
@@ -61,6 +98,14 @@ pub struct GithubService {
 
 static GITHUB_BASE_URL: &str = "https://api.github.com";
 static GITHUB_GRAPHQL_API_NAME: &str = "Github";
+pub static GITHUB_ASSIGNED_ISSUES_SEARCH_QUERY: &str =
+    "is:open is:issue archived:false assignee:@me";
+pub static GITHUB_REVIEW_REQUESTS_SEARCH_QUERY: &str =
+    "is:open is:pr archived:false review-requested:@me";
+// Maximum number of items returned by a Github search query page
+pub static GITHUB_TASK_ITEMS_MAX_COUNT: i64 = 100;
+// Number of recent workflow runs considered to find the one a notification is about
+static GITHUB_WORKFLOW_RUNS_PAGE_SIZE: usize = 20;
 
 impl GithubService {
     pub fn new(
@@ -133,6 +178,77 @@ impl GithubService {
             .mount(mock_server)
             .await;
 
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({ "operationName": NOTIFICATION_ITEMS_QUERY_OPERATION_NAME }),
+            ))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "application/json")
+                    .set_body_json(&Response::<Map<String, Value>> {
+                        data: Some(Map::new()),
+                        errors: None,
+                        extensions: None,
+                    }),
+            )
+            .mount(mock_server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({ "operationName": "IssueQuery" })))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "application/json")
+                    .set_body_json(&Response::<issue_query::ResponseData> {
+                        data: None,
+                        errors: None,
+                        extensions: None,
+                    }),
+            )
+            .mount(mock_server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({ "operationName": "TaskItemsQuery" }),
+            ))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "application/json")
+                    .set_body_json(&Response {
+                        data: Some(task_items_query::ResponseData {
+                            assigned_issues: task_items_query::TaskItemsQueryAssignedIssues {
+                                page_info: task_items_query::TaskItemsQueryAssignedIssuesPageInfo {
+                                    has_next_page: false,
+                                    end_cursor: None,
+                                },
+                                nodes: Some(vec![]),
+                            },
+                            review_requests: task_items_query::TaskItemsQueryReviewRequests {
+                                page_info: task_items_query::TaskItemsQueryReviewRequestsPageInfo {
+                                    has_next_page: false,
+                                    end_cursor: None,
+                                },
+                                nodes: Some(vec![]),
+                            },
+                        }),
+                        errors: None,
+                        extensions: None,
+                    }),
+            )
+            .mount(mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path_regex("/repos/[^/]*/[^/]*/actions/runs"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "application/json")
+                    .set_body_json(json!({ "total_count": 0, "workflow_runs": [] })),
+            )
+            .mount(mock_server)
+            .await;
+
         Mock::given(method("PATCH"))
             .and(path_regex("/notifications/threads/.*"))
             .respond_with(
@@ -198,6 +314,18 @@ impl GithubService {
                     self.github_base_path
                 ),
                 format!("{}/graphql", self.github_base_path),
+                format!(
+                    "{}/repos/{{owner}}/{{repository}}/actions/runs",
+                    self.github_base_path
+                ),
+                format!(
+                    "{}/repos/{{owner}}/{{repository}}/actions/runs/{{run_id}}/jobs",
+                    self.github_base_path
+                ),
+                format!(
+                    "{}/repos/{{owner}}/{{repository}}/releases/{{release_id}}",
+                    self.github_base_path
+                ),
             ],
             self.max_retry_duration,
         )
@@ -222,6 +350,28 @@ impl GithubService {
         Ok(notifications)
     }
 
+    pub async fn fetch_notifications_if_modified(
+        &self,
+        per_page: usize,
+        access_token: &AccessToken,
+        github_context: Option<&GithubContext>,
+    ) -> Result<ConditionalResponse<Vec<RawGithubNotification>>, UniversalInboxError> {
+        let url = format!(
+            "{}/notifications?page=1&per_page={per_page}",
+            self.github_base_url
+        );
+
+        Ok(self
+            .build_github_rest_client(access_token)?
+            .get_if_modified(
+                url,
+                github_context.and_then(|context| context.notifications_last_modified.as_deref()),
+                github_context.and_then(|context| context.notifications_etag.as_deref()),
+            )
+            .await
+            .context("Cannot fetch notifications from Github API")?)
+    }
+
     pub async fn mark_thread_as_done(
         &self,
         thread_id: &str,
@@ -327,21 +477,213 @@ impl GithubService {
             .ok_or_else(|| anyhow!("Failed to parse `data` from Github graphql response"))?)
     }
 
+    pub async fn query_issue(
+        &self,
+        owner: String,
+        repository: String,
+        issue_number: i64,
+        access_token: &AccessToken,
+    ) -> Result<issue_query::ResponseData, UniversalInboxError> {
+        let request_body = IssueQuery::build_query(issue_query::Variables {
+            owner,
+            repository,
+            issue_number,
+        });
+
+        let issue_response: graphql_client::Response<issue_query::ResponseData> = self
+            .build_github_graphql_client(access_token)?
+            .post(&self.github_graphql_url, Some(&request_body))
+            .await
+            .context("Cannot fetch issue from Github graphql API")?;
+
+        assert_no_error_in_graphql_response(&issue_response, GITHUB_GRAPHQL_API_NAME)?;
+
+        Ok(issue_response
+            .data
+            .ok_or_else(|| anyhow!("Failed to parse `data` from Github graphql response"))?)
+    }
+
+    /// Query the details of all the given pull requests, discussions and issues at once
+    pub async fn query_notification_items(
+        &self,
+        subjects: &[GithubUrl],
+        access_token: &AccessToken,
+    ) -> Result<Vec<Option<GithubNotificationItem>>, UniversalInboxError> {
+        if subjects.is_empty() {
+            return Ok(vec![]);
+        }
+        let request_body = build_notification_items_query(subjects)?;
+
+        let notification_items_response: graphql_client::Response<Map<String, Value>> = self
+            .build_github_graphql_client(access_token)?
+            .post(&self.github_graphql_url, Some(&request_body))
+            .await
+            .context("Cannot fetch notification details from Github graphql API")?;
+
+        assert_no_error_in_graphql_response(&notification_items_response, GITHUB_GRAPHQL_API_NAME)?;
+
+        let mut data = notification_items_response
+            .data
+            .ok_or_else(|| anyhow!("Failed to parse `data` from Github graphql response"))?;
+        subjects
+            .iter()
+            .enumerate()
+            .map(|(index, subject)| parse_notification_item(subject, index, &mut data))
+            .collect()
+    }
+
+    pub async fn query_task_items(
+        &self,
+        access_token: &AccessToken,
+        assigned_issues_after: Option<String>,
+        review_requests_after: Option<String>,
+    ) -> Result<task_items_query::ResponseData, UniversalInboxError> {
+        let request_body = TaskItemsQuery::build_query(task_items_query::Variables {
+            assigned_issues_query: GITHUB_ASSIGNED_ISSUES_SEARCH_QUERY.to_string(),
+            review_requests_query: GITHUB_REVIEW_REQUESTS_SEARCH_QUERY.to_string(),
+            first: GITHUB_TASK_ITEMS_MAX_COUNT,
+            assigned_issues_after,
+            review_requests_after,
+        });
+
+        let task_items_response: graphql_client::Response<task_items_query::ResponseData> = self
+            .build_github_graphql_client(access_token)?
+            .post(&self.github_graphql_url, Some(&request_body))
+            .await
+            .context("Cannot fetch assigned issues and review requests from Github graphql API")?;
+
+        assert_no_error_in_graphql_response(&task_items_response, GITHUB_GRAPHQL_API_NAME)?;
+
+        Ok(task_items_response
+            .data
+            .ok_or_else(|| anyhow!("Failed to parse `data` from Github graphql response"))?)
+    }
+
+    pub async fn add_pull_request_review(
+        &self,
+        pull_request_id: String,
+        event: add_pull_request_review_mutation::PullRequestReviewEvent,
+        body: Option<String>,
+        access_token: &AccessToken,
+    ) -> Result<(), UniversalInboxError> {
+        let request_body = AddPullRequestReviewMutation::build_query(
+            add_pull_request_review_mutation::Variables {
+                pull_request_id,
+                event,
+                body,
+            },
+        );
+
+        let response: graphql_client::Response<add_pull_request_review_mutation::ResponseData> =
+            self.build_github_graphql_client(access_token)?
+                .post(&self.github_graphql_url, Some(&request_body))
+                .await
+                .context("Cannot submit pull request review to Github graphql API")?;
+
+        assert_no_error_in_graphql_response(&response, GITHUB_GRAPHQL_API_NAME)?;
+
+        Ok(())
+    }
+
+    pub async fn add_comment(
+        &self,
+        subject_id: String,
+        body: String,
+        access_token: &AccessToken,
+    ) -> Result<(), UniversalInboxError> {
+        let request_body =
+            AddCommentMutation::build_query(add_comment_mutation::Variables { subject_id, body });
+
+        let response: graphql_client::Response<add_comment_mutation::ResponseData> = self
+            .build_github_graphql_client(access_token)?
+            .post(&self.github_graphql_url, Some(&request_body))
+            .await
+            .context("Cannot add comment using Github graphql API")?;
+
+        assert_no_error_in_graphql_response(&response, GITHUB_GRAPHQL_API_NAME)?;
+
+        Ok(())
+    }
+
+    pub async fn merge_pull_request(
+        &self,
+        pull_request_id: String,
+        merge_method: GithubMergeMethod,
+        expected_head_oid: GitObjectId,
+        access_token: &AccessToken,
+    ) -> Result<(), UniversalInboxError> {
+        let request_body =
+            MergePullRequestMutation::build_query(merge_pull_request_mutation::Variables {
+                pull_request_id,
+                merge_method: match merge_method {
+                    GithubMergeMethod::Merge => {
+                        merge_pull_request_mutation::PullRequestMergeMethod::MERGE
+                    }
+                    GithubMergeMethod::Squash => {
+                        merge_pull_request_mutation::PullRequestMergeMethod::SQUASH
+                    }
+                    GithubMergeMethod::Rebase => {
+                        merge_pull_request_mutation::PullRequestMergeMethod::REBASE
+                    }
+                },
+                expected_head_oid,
+            });
+
+        let response: graphql_client::Response<merge_pull_request_mutation::ResponseData> = self
+            .build_github_graphql_client(access_token)?
+            .post(&self.github_graphql_url, Some(&request_body))
+            .await
+            .context("Cannot merge pull request using Github graphql API")?;
+
+        assert_no_error_in_graphql_response(&response, GITHUB_GRAPHQL_API_NAME)?;
+
+        Ok(())
+    }
+
+    /// Apply a review, comment or merge action on the pull request of a Github notification
+    /// and return the notification with refreshed pull request data
     #[tracing::instrument(
         level = "debug",
         skip_all,
         fields(
-            raw_github_notification_id = raw_github_notification.id.to_string(),
+            github_notification_id = github_notification.id,
             user.id = user_id.to_string()
         ),
         err
     )]
-    pub async fn fetch_github_notification_item(
+    pub async fn apply_pull_request_action(
         &self,
         executor: &mut Transaction<'_, Postgres>,
-        raw_github_notification: &RawGithubNotification,
+        github_notification: &GithubNotification,
+        action: &GithubPullRequestAction,
         user_id: UserId,
-    ) -> Result<Option<GithubNotificationItem>, UniversalInboxError> {
+    ) -> Result<GithubNotification, UniversalInboxError> {
+        let Some(GithubNotificationItem::GithubPullRequest(pull_request)) =
+            &github_notification.item
+        else {
+            return Err(UniversalInboxError::UnsupportedAction(format!(
+                "Cannot {} Github notification {}, expected a pull request notification",
+                action.description(),
+                github_notification.id
+            )));
+        };
+        let Some(Ok(GithubUrl::PullRequest {
+            owner,
+            repository,
+            number,
+        })) = github_notification
+            .subject
+            .url
+            .as_ref()
+            .map(GithubUrl::try_from_api_url)
+        else {
+            return Err(UniversalInboxError::UnsupportedAction(format!(
+                "Cannot {} Github notification {}, its subject is not a pull request URL",
+                action.description(),
+                github_notification.id
+            )));
+        };
+
         let (access_token, _) = self
             .integration_connection_service
             .read()
@@ -349,38 +691,384 @@ impl GithubService {
             .find_access_token(executor, IntegrationProviderKind::Github, user_id)
             .await?
             .ok_or_else(|| {
-                anyhow!("Cannot fetch Github notification details without an access token")
+                anyhow!("Cannot act on a Github pull request without an access token")
             })?;
 
-        let github_notification_item =
-            if let Some(ref resource_url) = raw_github_notification.subject.url {
+        match action {
+            GithubPullRequestAction::Approve { body } => {
+                self.add_pull_request_review(
+                    pull_request.id.clone(),
+                    add_pull_request_review_mutation::PullRequestReviewEvent::APPROVE,
+                    body.clone(),
+                    &access_token,
+                )
+                .await?
+            }
+            GithubPullRequestAction::RequestChanges { body } => {
+                self.add_pull_request_review(
+                    pull_request.id.clone(),
+                    add_pull_request_review_mutation::PullRequestReviewEvent::REQUEST_CHANGES,
+                    Some(body.clone()),
+                    &access_token,
+                )
+                .await?
+            }
+            GithubPullRequestAction::Comment { body } => {
+                self.add_comment(pull_request.id.clone(), body.clone(), &access_token)
+                    .await?
+            }
+            GithubPullRequestAction::Merge { merge_method } => {
+                self.merge_pull_request(
+                    pull_request.id.clone(),
+                    *merge_method,
+                    pull_request.latest_commit.git_commit_id.clone(),
+                    &access_token,
+                )
+                .await?
+            }
+        }
+
+        let refreshed_pull_request = self
+            .query_pull_request(owner, repository, number, &access_token)
+            .await?
+            .try_into()?;
+
+        Ok(GithubNotification {
+            item: Some(GithubNotificationItem::GithubPullRequest(
+                refreshed_pull_request,
+            )),
+            ..github_notification.clone()
+        })
+    }
+
+    pub async fn fetch_workflow_runs(
+        &self,
+        owner: &str,
+        repository: &str,
+        branch: Option<&str>,
+        created_before: DateTime<Utc>,
+        access_token: &AccessToken,
+    ) -> Result<Option<RawGithubWorkflowRuns>, UniversalInboxError> {
+        let mut url = Url::parse(&format!(
+            "{}/repos/{owner}/{repository}/actions/runs",
+            self.github_base_url
+        ))
+        .context("Cannot build Github workflow runs URL")?;
+        url.query_pairs_mut()
+            .append_pair("per_page", &GITHUB_WORKFLOW_RUNS_PAGE_SIZE.to_string())
+            .append_pair(
+                "created",
+                &format!(
+                    "<={}",
+                    created_before.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+                ),
+            );
+        if let Some(branch) = branch {
+            url.query_pairs_mut().append_pair("branch", branch);
+        }
+
+        match self.build_github_rest_client(access_token)?.get(url).await {
+            Ok(workflow_runs) => Ok(Some(workflow_runs)),
+            // Github Actions may be disabled or not readable with the granted
+            // permissions, or the repository may have been deleted
+            Err(ApiClientError::NetworkError(err))
+                if matches!(
+                    err.status(),
+                    Some(
+                        reqwest_middleware::reqwest::StatusCode::FORBIDDEN
+                            | reqwest_middleware::reqwest::StatusCode::NOT_FOUND
+                    )
+                ) =>
+            {
+                Ok(None)
+            }
+            Err(err) => Err(UniversalInboxError::Unexpected(anyhow!(
+                "Cannot fetch workflow runs of `{owner}/{repository}` from Github API: {err}"
+            ))),
+        }
+    }
+
+    pub async fn fetch_workflow_run_jobs(
+        &self,
+        owner: &str,
+        repository: &str,
+        run_id: i64,
+        access_token: &AccessToken,
+    ) -> Result<Option<RawGithubWorkflowJobs>, UniversalInboxError> {
+        match self
+            .build_github_rest_client(access_token)?
+            .get(format!(
+                "{}/repos/{owner}/{repository}/actions/runs/{run_id}/jobs?filter=latest",
+                self.github_base_url
+            ))
+            .await
+        {
+            Ok(workflow_jobs) => Ok(Some(workflow_jobs)),
+            Err(ApiClientError::NetworkError(err))
+                if matches!(
+                    err.status(),
+                    Some(
+                        reqwest_middleware::reqwest::StatusCode::FORBIDDEN
+                            | reqwest_middleware::reqwest::StatusCode::NOT_FOUND
+                    )
+                ) =>
+            {
+                Ok(None)
+            }
+            Err(err) => Err(UniversalInboxError::Unexpected(anyhow!(
+                "Cannot fetch jobs of Github workflow run `{run_id}` from Github API: {err}"
+            ))),
+        }
+    }
+
+    pub async fn fetch_release(
+        &self,
+        owner: &str,
+        repository: &str,
+        release_id: i64,
+        access_token: &AccessToken,
+    ) -> Result<Option<RawGithubRelease>, UniversalInboxError> {
+        match self
+            .build_github_rest_client(access_token)?
+            .get(format!(
+                "{}/repos/{owner}/{repository}/releases/{release_id}",
+                self.github_base_url
+            ))
+            .await
+        {
+            Ok(release) => Ok(Some(release)),
+            // The release may have been deleted since the notification was sent
+            Err(ApiClientError::NetworkError(err))
+                if err.status() == Some(reqwest_middleware::reqwest::StatusCode::NOT_FOUND) =>
+            {
+                Ok(None)
+            }
+            Err(err) => Err(UniversalInboxError::Unexpected(anyhow!(
+                "Failed to fetch Github release `{release_id}`: {err}"
+            ))),
+        }
+    }
+
+    /// Find the workflow run a `CheckSuite` or `WorkflowRun` notification is about.
+    /// The run is matched using the workflow name and branch from the notification title and
+    /// the closest update date.
+    pub async fn find_workflow_run(
+        &self,
+        raw_github_notification: &RawGithubNotification,
+        access_token: &AccessToken,
+    ) -> Result<Option<GithubWorkflowRun>, UniversalInboxError> {
+        let Some((workflow_name, branch)) =
+            parse_workflow_run_notification_title(&raw_github_notification.subject.title)
+        else {
+            return Ok(None);
+        };
+        let owner = &raw_github_notification.repository.owner.login;
+        let repository = &raw_github_notification.repository.name;
+
+        let Some(workflow_runs) = self
+            .fetch_workflow_runs(
+                owner,
+                repository,
+                branch.as_deref(),
+                raw_github_notification.updated_at,
+                access_token,
+            )
+            .await?
+        else {
+            return Ok(None);
+        };
+        let notification_updated_at = raw_github_notification.updated_at;
+        let Some(workflow_run) = workflow_runs
+            .workflow_runs
+            .into_iter()
+            .filter(|run| run.name.as_deref() == Some(workflow_name.as_str()))
+            .min_by_key(|run| (run.updated_at - notification_updated_at).abs())
+        else {
+            return Ok(None);
+        };
+
+        let jobs = if is_failed_conclusion(workflow_run.conclusion().as_ref()) {
+            self.fetch_workflow_run_jobs(owner, repository, workflow_run.id, access_token)
+                .await?
+                .map(|workflow_jobs| workflow_jobs.jobs)
+                .unwrap_or_default()
+        } else {
+            vec![]
+        };
+
+        Ok(Some(workflow_run.into_github_workflow_run(jobs)?))
+    }
+
+    pub async fn find_release(
+        &self,
+        raw_github_notification: &RawGithubNotification,
+        owner: String,
+        repository: String,
+        release_id: i64,
+        access_token: &AccessToken,
+    ) -> Result<Option<GithubRelease>, UniversalInboxError> {
+        let Some(release) = self
+            .fetch_release(&owner, &repository, release_id, access_token)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(release.into_github_release(
+            GithubRepositorySummary {
+                name_with_owner: raw_github_notification.repository.full_name.clone(),
+                url: raw_github_notification.repository.html_url.clone(),
+            },
+        )?))
+    }
+
+    /// Fetch the details of a page of Github notifications. Pull requests, discussions and
+    /// issues are queried all at once, workflow runs and releases using the REST API.
+    pub async fn fetch_github_notification_items(
+        &self,
+        raw_github_notifications: &[RawGithubNotification],
+        access_token: &AccessToken,
+    ) -> Result<Vec<Option<GithubNotificationItem>>, UniversalInboxError> {
+        let mut github_notification_items = vec![];
+        let mut queried_subjects = vec![];
+        for (index, raw_github_notification) in raw_github_notifications.iter().enumerate() {
+            let subject_type = raw_github_notification.subject.r#type.as_str();
+            let github_notification_item = if subject_type == "CheckSuite"
+                || subject_type == "WorkflowRun"
+            {
+                self.find_workflow_run(raw_github_notification, access_token)
+                    .await?
+                    .map(GithubNotificationItem::GithubWorkflowRun)
+            } else if let Some(ref resource_url) = raw_github_notification.subject.url {
                 match GithubUrl::try_from_api_url(resource_url) {
-                    Ok(GithubUrl::PullRequest {
+                    Ok(GithubUrl::Release {
                         owner,
                         repository,
-                        number,
-                    }) => Some(GithubNotificationItem::GithubPullRequest(
-                        self.query_pull_request(owner, repository, number, &access_token)
-                            .await?
-                            .try_into()?,
-                    )),
-                    Ok(GithubUrl::Discussion {
-                        owner,
-                        repository,
-                        number,
-                    }) => Some(GithubNotificationItem::GithubDiscussion(
-                        self.query_discussion(owner, repository, number, &access_token)
-                            .await?
-                            .try_into()?,
-                    )),
+                        id,
+                    }) => self
+                        .find_release(raw_github_notification, owner, repository, id, access_token)
+                        .await?
+                        .map(GithubNotificationItem::GithubRelease),
+                    Ok(subject) => {
+                        queried_subjects.push((index, subject));
+                        None
+                    }
                     // Not yet implemented resource type
                     Err(_) => None,
                 }
             } else {
                 None
             };
+            github_notification_items.push(github_notification_item);
+        }
+
+        let (indexes, subjects): (Vec<usize>, Vec<GithubUrl>) =
+            queried_subjects.into_iter().unzip();
+        let queried_items = self
+            .query_notification_items(&subjects, access_token)
+            .await?;
+        for (index, github_notification_item) in indexes.into_iter().zip(queried_items) {
+            github_notification_items[index] = github_notification_item;
+        }
+
+        Ok(github_notification_items)
+    }
+
+    /// Fetch all Github notifications with their details. When conditional, the first page is
+    /// requested with the validators of the previous sync and `None` is returned if Github
+    /// reports it as unchanged, which does not count against the rate limit.
+    async fn fetch_notification_items(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        is_conditional: bool,
+    ) -> Result<Option<Vec<ThirdPartyItem>>, UniversalInboxError> {
+        let (access_token, integration_connection) = self
+            .integration_connection_service
+            .read()
+            .await
+            .find_access_token(executor, IntegrationProviderKind::Github, user_id)
+            .await?
+            .ok_or_else(|| anyhow!("Cannot fetch Github notifications without an access token"))?;
+
+        let github_context = match &integration_connection.provider {
+            IntegrationProvider::Github {
+                context: Some(github_context),
+                ..
+            } if is_conditional => Some(github_context),
+            _ => None,
+        };
+        let ConditionalResponse::Modified {
+            value: mut raw_github_notifications,
+            last_modified,
+            etag,
+        } = self
+            .fetch_notifications_if_modified(self.page_size, &access_token, github_context)
+            .await?
+        else {
+            debug!("Github notifications of user {user_id} are not modified since the last sync");
+            return Ok(None);
+        };
+
+        let mut is_last_page = raw_github_notifications.len() < self.page_size;
+        let mut page = 2;
+        while !is_last_page {
+            let github_notifs = self
+                .fetch_notifications(page, self.page_size, &access_token)
+                .await?;
+            is_last_page = github_notifs.len() < self.page_size;
+            raw_github_notifications.extend(github_notifs);
+            page += 1;
+        }
+
+        let mut third_party_items = vec![];
+        for raw_github_notifications_page in raw_github_notifications.chunks(self.page_size.max(1))
+        {
+            let github_notification_items = self
+                .fetch_github_notification_items(raw_github_notifications_page, &access_token)
+                .await?;
+            for (raw_github_notification, github_notification_item) in raw_github_notifications_page
+                .iter()
+                .zip(github_notification_items)
+            {
+                let github_notification = GithubNotification {
+                    id: raw_github_notification.id.clone(),
+                    repository: raw_github_notification.repository.clone(),
+                    subject: raw_github_notification.subject.clone(),
+                    reason: raw_github_notification.reason.clone(),
+                    unread: raw_github_notification.unread,
+                    updated_at: raw_github_notification.updated_at,
+                    last_read_at: raw_github_notification.last_read_at,
+                    url: raw_github_notification.url.clone(),
+                    subscription_url: raw_github_notification.subscription_url.clone(),
+                    item: github_notification_item,
+                };
+                third_party_items.push(
+                    github_notification.into_third_party_item(user_id, integration_connection.id),
+                )
+            }
+        }
+
+        self.integration_connection_service
+            .read()
+            .await
+            .update_integration_connection_context(
+                executor,
+                integration_connection.id,
+                IntegrationConnectionContext::Github(GithubContext {
+                    notifications_last_modified: last_modified,
+                    notifications_etag: etag,
+                }),
+            )
+            .await
+            .map_err(|_| {
+                anyhow!(
+                    "Failed to update Github integration connection {} context",
+                    integration_connection.id
+                )
+            })?;
 
-        Ok(github_notification_item)
+        Ok(Some(third_party_items))
     }
 }
 
@@ -399,57 +1087,26 @@ impl ThirdPartyItemSourceService<GithubNotification> for GithubService {
         user_id: UserId,
         _last_sync_completed_at: Option<DateTime<Utc>>,
     ) -> Result<Vec<ThirdPartyItem>, UniversalInboxError> {
-        let (access_token, integration_connection) = self
-            .integration_connection_service
-            .read()
-            .await
-            .find_access_token(executor, IntegrationProviderKind::Github, user_id)
+        Ok(self
+            .fetch_notification_items(executor, user_id, false)
             .await?
-            .ok_or_else(|| anyhow!("Cannot fetch Github notifications without an access token"))?;
-
-        let raw_github_notifications = stream::try_unfold(
-            (1, false, access_token),
-            |(page, stop, access_token)| async move {
-                if stop {
-                    return Ok(None);
-                }
-
-                let response = self
-                    .fetch_notifications(page, self.page_size, &access_token)
-                    .await;
-
-                response.map(|github_notifs| {
-                    let notifs_count = github_notifs.len();
-                    let is_last_page = notifs_count < self.page_size;
-                    Some((github_notifs, (page + 1, is_last_page, access_token)))
-                })
-            },
-        )
-        .try_collect::<Vec<Vec<RawGithubNotification>>>()
-        .await?;
-
-        let mut third_party_items = vec![];
-        for raw_github_notification in raw_github_notifications.into_iter().flatten() {
-            let github_notification_item = self
-                .fetch_github_notification_item(executor, &raw_github_notification, user_id)
-                .await?;
-            let github_notification = GithubNotification {
-                id: raw_github_notification.id.clone(),
-                repository: raw_github_notification.repository.clone(),
-                subject: raw_github_notification.subject.clone(),
-                reason: raw_github_notification.reason.clone(),
-                unread: raw_github_notification.unread,
-                updated_at: raw_github_notification.updated_at,
-                last_read_at: raw_github_notification.last_read_at,
-                url: raw_github_notification.url.clone(),
-                subscription_url: raw_github_notification.subscription_url.clone(),
-                item: github_notification_item,
-            };
-            third_party_items
-                .push(github_notification.into_third_party_item(user_id, integration_connection.id))
-        }
+            .unwrap_or_default())
+    }
 
-        Ok(third_party_items)
+    #[allow(clippy::blocks_in_conditions)]
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(user.id = user_id.to_string()),
+        err
+    )]
+    async fn fetch_items_if_modified(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        _last_sync_completed_at: Option<DateTime<Utc>>,
+    ) -> Result<Option<Vec<ThirdPartyItem>>, UniversalInboxError> {
+        self.fetch_notification_items(executor, user_id, true).await
     }
 
     fn is_sync_incremental(&self) -> bool {
@@ -564,6 +1221,197 @@ impl ThirdPartyNotificationSourceService<GithubNotification> for GithubService {
     }
 }
 
+#[async_trait]
+impl ThirdPartyItemSourceService<GithubTaskItem> for GithubService {
+    #[allow(clippy::blocks_in_conditions)]
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(user.id = user_id.to_string()),
+        err
+    )]
+    async fn fetch_items(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        _last_sync_completed_at: Option<DateTime<Utc>>,
+    ) -> Result<Vec<ThirdPartyItem>, UniversalInboxError> {
+        let (access_token, integration_connection) = self
+            .integration_connection_service
+            .read()
+            .await
+            .find_access_token(executor, IntegrationProviderKind::Github, user_id)
+            .await?
+            .ok_or_else(|| anyhow!("Cannot fetch Github tasks without an access token"))?;
+
+        // Both searches are paginated in the same query. Once a search has no more pages, its
+        // nodes are ignored in the following responses
+        let mut github_task_items: Vec<GithubTaskItem> = vec![];
+        let mut assigned_issues_after = None;
+        let mut review_requests_after = None;
+        let mut has_more_assigned_issues = true;
+        let mut has_more_review_requests = true;
+        while has_more_assigned_issues || has_more_review_requests {
+            let mut task_items_response = self
+                .query_task_items(
+                    &access_token,
+                    assigned_issues_after.clone(),
+                    review_requests_after.clone(),
+                )
+                .await?;
+
+            if has_more_assigned_issues {
+                let page_info = &task_items_response.assigned_issues.page_info;
+                has_more_assigned_issues =
+                    page_info.has_next_page && page_info.end_cursor.is_some();
+                assigned_issues_after = page_info.end_cursor.clone();
+            } else {
+                task_items_response.assigned_issues.nodes = None;
+            }
+            if has_more_review_requests {
+                let page_info = &task_items_response.review_requests.page_info;
+                has_more_review_requests =
+                    page_info.has_next_page && page_info.end_cursor.is_some();
+                review_requests_after = page_info.end_cursor.clone();
+            } else {
+                task_items_response.review_requests.nodes = None;
+            }
+
+            github_task_items.extend(TryInto::<Vec<GithubTaskItem>>::try_into(
+                task_items_response,
+            )?);
+        }
+
+        Ok(github_task_items
+            .into_iter()
+            .map(|github_task_item| {
+                github_task_item.into_third_party_item(user_id, integration_connection.id)
+            })
+            .collect())
+    }
+
+    // Closed issues and submitted reviews are no longer returned by the search queries,
+    // the corresponding tasks are completed when marking stale items as done
+    fn is_sync_incremental(&self) -> bool {
+        false
+    }
+
+    fn get_third_party_item_source_kind(&self) -> ThirdPartyItemSourceKind {
+        ThirdPartyItemSourceKind::GithubTaskItem
+    }
+}
+
+#[async_trait]
+impl ThirdPartyTaskService<GithubTaskItem> for GithubService {
+    #[allow(clippy::blocks_in_conditions)]
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(source_id = source.id, user.id = user_id.to_string()),
+        err
+    )]
+    async fn third_party_item_into_task(
+        &self,
+        _executor: &mut Transaction<'_, Postgres>,
+        source: &GithubTaskItem,
+        source_third_party_item: &ThirdPartyItem,
+        task_creation_config: Option<TaskCreationConfig>,
+        user_id: UserId,
+    ) -> Result<Box<CreateOrUpdateTaskRequest>, UniversalInboxError> {
+        let task_creation_config = task_creation_config.ok_or_else(|| {
+            UniversalInboxError::Unexpected(anyhow!(
+                "Cannot build a Github task without a task creation"
+            ))
+        })?;
+
+        Ok(Box::new(CreateOrUpdateTaskRequest {
+            id: Uuid::new_v4().into(),
+            title: format!(
+                "[{}#{} {}]({})",
+                source.repository.name_with_owner,
+                source.number,
+                source.title,
+                source.get_html_url()
+            ),
+            body: source.body.clone(),
+            status: match source.state {
+                GithubTaskItemState::Open => TaskStatus::Active,
+                GithubTaskItemState::Completed => TaskStatus::Done,
+            },
+            completed_at: source.completed_at,
+            priority: task_creation_config.priority,
+            due_at: DefaultValue::new(task_creation_config.due_at.clone(), None),
+            tags: source
+                .labels
+                .iter()
+                .map(|label| label.name.clone())
+                .collect(),
+            parent_id: None,
+            project: DefaultValue::new(
+                task_creation_config
+                    .project_name
+                    .clone()
+                    .unwrap_or_else(|| TODOIST_INBOX_PROJECT.to_string()),
+                None,
+            ),
+            is_recurring: false,
+            created_at: source.created_at,
+            updated_at: source.updated_at,
+            kind: TaskSourceKind::Github,
+            source_item: source_third_party_item.clone(),
+            sink_item: None,
+            user_id,
+        }))
+    }
+
+    async fn delete_task(
+        &self,
+        _executor: &mut Transaction<'_, Postgres>,
+        _third_party_item: &ThirdPartyItem,
+        _user_id: UserId,
+    ) -> Result<(), UniversalInboxError> {
+        // Github issues and review requests are not deleted from Universal Inbox => no-op
+        Ok(())
+    }
+
+    async fn complete_task(
+        &self,
+        _executor: &mut Transaction<'_, Postgres>,
+        _third_party_item: &ThirdPartyItem,
+        _user_id: UserId,
+    ) -> Result<(), UniversalInboxError> {
+        // Completing the task does not close the Github issue nor submit the review => no-op
+        Ok(())
+    }
+
+    async fn uncomplete_task(
+        &self,
+        _executor: &mut Transaction<'_, Postgres>,
+        _third_party_item: &ThirdPartyItem,
+        _user_id: UserId,
+    ) -> Result<(), UniversalInboxError> {
+        // Github issues and review requests are not reopened from Universal Inbox => no-op
+        Ok(())
+    }
+
+    async fn update_task(
+        &self,
+        _executor: &mut Transaction<'_, Postgres>,
+        _third_party_item: &ThirdPartyItem,
+        _patch: &TaskPatch,
+        _user_id: UserId,
+    ) -> Result<(), UniversalInboxError> {
+        // Nothing to do here for now
+        Ok(())
+    }
+}
+
+impl TaskSource for GithubService {
+    fn get_task_source_kind(&self) -> TaskSourceKind {
+        TaskSourceKind::Github
+    }
+}
+
 impl IntegrationProviderSource for GithubService {
     fn get_integration_provider_kind(&self) -> IntegrationProviderKind {
         IntegrationProviderKind::Github
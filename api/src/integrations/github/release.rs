@@ -0,0 +1,115 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use universal_inbox::third_party::integrations::github::{
+    GithubActor, GithubBotSummary, GithubRelease, GithubReleaseAsset, GithubRepositorySummary,
+    GithubUserSummary,
+};
+
+use crate::universal_inbox::UniversalInboxError;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RawGithubRelease {
+    pub id: i64,
+    pub tag_name: String,
+    pub name: Option<String>,
+    pub body: Option<String>,
+    pub html_url: String,
+    pub draft: bool,
+    pub prerelease: bool,
+    pub created_at: DateTime<Utc>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub author: Option<RawGithubReleaseAuthor>,
+    #[serde(default)]
+    pub assets: Vec<RawGithubReleaseAsset>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RawGithubReleaseAuthor {
+    pub login: String,
+    pub avatar_url: String,
+    pub r#type: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RawGithubReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+    pub content_type: String,
+    pub size: i64,
+    pub download_count: i64,
+}
+
+impl TryFrom<RawGithubReleaseAuthor> for GithubActor {
+    type Error = UniversalInboxError;
+
+    fn try_from(value: RawGithubReleaseAuthor) -> Result<Self, Self::Error> {
+        let avatar_url = value.avatar_url.parse::<Url>().with_context(|| {
+            format!(
+                "Github actor should have a valid avatar URL: {:?}",
+                value.avatar_url
+            )
+        })?;
+        Ok(match value.r#type.as_str() {
+            "Bot" => GithubActor::Bot(GithubBotSummary {
+                login: value.login,
+                avatar_url,
+            }),
+            // The REST API does not provide the user's name
+            _ => GithubActor::User(GithubUserSummary {
+                name: None,
+                login: value.login,
+                avatar_url,
+            }),
+        })
+    }
+}
+
+impl TryFrom<RawGithubReleaseAsset> for GithubReleaseAsset {
+    type Error = UniversalInboxError;
+
+    fn try_from(value: RawGithubReleaseAsset) -> Result<Self, Self::Error> {
+        Ok(GithubReleaseAsset {
+            name: value.name,
+            url: value.browser_download_url.parse().with_context(|| {
+                format!(
+                    "Unable to parse Github release asset URL: {:?}",
+                    value.browser_download_url
+                )
+            })?,
+            content_type: value.content_type,
+            size: value.size,
+            download_count: value.download_count,
+        })
+    }
+}
+
+impl RawGithubRelease {
+    pub fn into_github_release(
+        self,
+        repository: GithubRepositorySummary,
+    ) -> Result<GithubRelease, UniversalInboxError> {
+        Ok(GithubRelease {
+            id: self.id,
+            tag_name: self.tag_name,
+            name: self.name.filter(|name| !name.is_empty()),
+            body: self.body.filter(|body| !body.is_empty()),
+            url: self.html_url.parse().with_context(|| {
+                format!("Unable to parse Github release URL: {:?}", self.html_url)
+            })?,
+            is_draft: self.draft,
+            is_prerelease: self.prerelease,
+            created_at: self.created_at,
+            published_at: self.published_at,
+            author: self.author.map(|author| author.try_into()).transpose()?,
+            repository,
+            assets: self
+                .assets
+                .into_iter()
+                .map(|asset| asset.try_into())
+                .collect::<Result<Vec<GithubReleaseAsset>, UniversalInboxError>>()?,
+        })
+    }
+}
@@ -0,0 +1,236 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use universal_inbox::third_party::integrations::github::{
+    GithubCheckConclusionState, GithubCheckStatusState, GithubCommitSummary,
+    GithubRepositorySummary, GithubWorkflowJob, GithubWorkflowRun,
+};
+
+use crate::universal_inbox::UniversalInboxError;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RawGithubWorkflowRuns {
+    pub total_count: i64,
+    pub workflow_runs: Vec<RawGithubWorkflowRun>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RawGithubWorkflowRun {
+    pub id: i64,
+    pub name: Option<String>,
+    pub display_title: String,
+    pub run_number: i64,
+    pub event: String,
+    pub head_branch: Option<String>,
+    pub head_sha: String,
+    pub status: Option<String>,
+    pub conclusion: Option<String>,
+    pub html_url: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub head_commit: Option<RawGithubHeadCommit>,
+    pub repository: RawGithubWorkflowRunRepository,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RawGithubHeadCommit {
+    pub id: String,
+    pub message: String,
+    pub author: Option<RawGithubCommitAuthor>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RawGithubCommitAuthor {
+    pub name: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RawGithubWorkflowRunRepository {
+    pub full_name: String,
+    pub html_url: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RawGithubWorkflowJobs {
+    pub total_count: i64,
+    pub jobs: Vec<RawGithubWorkflowJob>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RawGithubWorkflowJob {
+    pub id: i64,
+    pub name: String,
+    pub conclusion: Option<String>,
+    pub html_url: String,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub steps: Vec<RawGithubWorkflowStep>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RawGithubWorkflowStep {
+    pub name: String,
+    pub conclusion: Option<String>,
+}
+
+/// Workflow run notifications do not have a subject URL, their title is the only
+/// way to find the run they are about, ie. `<workflow name> workflow run <outcome> for <branch> branch`
+pub fn parse_workflow_run_notification_title(title: &str) -> Option<(String, Option<String>)> {
+    let (workflow_name, outcome) = title.split_once(" workflow run ")?;
+    let branch = outcome
+        .strip_suffix(" branch")
+        .and_then(|outcome| outcome.split_once(" for "))
+        .map(|(_, branch)| branch.to_string());
+
+    Some((workflow_name.to_string(), branch))
+}
+
+pub fn is_failed_conclusion(conclusion: Option<&GithubCheckConclusionState>) -> bool {
+    matches!(
+        conclusion,
+        Some(GithubCheckConclusionState::Failure)
+            | Some(GithubCheckConclusionState::TimedOut)
+            | Some(GithubCheckConclusionState::StartupFailure)
+            | Some(GithubCheckConclusionState::ActionRequired)
+    )
+}
+
+fn parse_status(status: Option<&str>) -> GithubCheckStatusState {
+    match status {
+        Some("completed") => GithubCheckStatusState::Completed,
+        Some("in_progress") => GithubCheckStatusState::InProgress,
+        Some("queued") => GithubCheckStatusState::Queued,
+        Some("requested") => GithubCheckStatusState::Requested,
+        Some("waiting") => GithubCheckStatusState::Waiting,
+        _ => GithubCheckStatusState::Pending,
+    }
+}
+
+fn parse_conclusion(conclusion: Option<&str>) -> Option<GithubCheckConclusionState> {
+    match conclusion? {
+        "action_required" => Some(GithubCheckConclusionState::ActionRequired),
+        "cancelled" => Some(GithubCheckConclusionState::Cancelled),
+        "failure" => Some(GithubCheckConclusionState::Failure),
+        "neutral" => Some(GithubCheckConclusionState::Neutral),
+        "skipped" => Some(GithubCheckConclusionState::Skipped),
+        "stale" => Some(GithubCheckConclusionState::Stale),
+        "startup_failure" => Some(GithubCheckConclusionState::StartupFailure),
+        "success" => Some(GithubCheckConclusionState::Success),
+        "timed_out" => Some(GithubCheckConclusionState::TimedOut),
+        _ => None,
+    }
+}
+
+impl RawGithubWorkflowRun {
+    pub fn conclusion(&self) -> Option<GithubCheckConclusionState> {
+        parse_conclusion(self.conclusion.as_deref())
+    }
+
+    pub fn into_github_workflow_run(
+        self,
+        jobs: Vec<RawGithubWorkflowJob>,
+    ) -> Result<GithubWorkflowRun, UniversalInboxError> {
+        let repository_url: Url = self.repository.html_url.parse().with_context(|| {
+            format!(
+                "Unable to parse Github repository URL: {:?}",
+                self.repository.html_url
+            )
+        })?;
+        let head_commit = self.head_commit.map(|head_commit| {
+            let mut url = repository_url.clone();
+            url.set_path(&format!(
+                "{}/commit/{}",
+                repository_url.path(),
+                head_commit.id
+            ));
+            GithubCommitSummary {
+                sha: head_commit.id,
+                message: head_commit.message,
+                author_name: head_commit.author.map(|author| author.name),
+                url,
+            }
+        });
+        let failed_jobs = jobs
+            .into_iter()
+            .filter_map(|job| {
+                let conclusion = parse_conclusion(job.conclusion.as_deref());
+                if !is_failed_conclusion(conclusion.as_ref()) {
+                    return None;
+                }
+                Some(job.html_url.parse().map(|url| {
+                    GithubWorkflowJob {
+                        id: job.id,
+                        name: job.name,
+                        conclusion,
+                        url,
+                        failed_steps: job
+                            .steps
+                            .into_iter()
+                            .filter(|step| {
+                                is_failed_conclusion(
+                                    parse_conclusion(step.conclusion.as_deref()).as_ref(),
+                                )
+                            })
+                            .map(|step| step.name)
+                            .collect(),
+                        started_at: job.started_at,
+                        completed_at: job.completed_at,
+                    }
+                }))
+            })
+            .collect::<Result<Vec<GithubWorkflowJob>, url::ParseError>>()
+            .context("Unable to parse Github workflow job URL")?;
+
+        Ok(GithubWorkflowRun {
+            id: self.id,
+            name: self.name.unwrap_or_else(|| self.display_title.clone()),
+            display_title: self.display_title,
+            run_number: self.run_number,
+            event: self.event,
+            head_branch: self.head_branch,
+            status: parse_status(self.status.as_deref()),
+            conclusion: parse_conclusion(self.conclusion.as_deref()),
+            url: self.html_url.parse().with_context(|| {
+                format!(
+                    "Unable to parse Github workflow run URL: {:?}",
+                    self.html_url
+                )
+            })?,
+            repository: GithubRepositorySummary {
+                name_with_owner: self.repository.full_name,
+                url: repository_url,
+            },
+            head_commit,
+            failed_jobs,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::*;
+
+    #[rstest]
+    #[case::with_branch(
+        "CI workflow run failed for main branch",
+        Some(("CI".to_string(), Some("main".to_string())))
+    )]
+    #[case::without_branch(
+        "Release build workflow run cancelled",
+        Some(("Release build".to_string(), None))
+    )]
+    #[case::not_a_workflow_run("Fix the build", None)]
+    fn test_parse_workflow_run_notification_title(
+        #[case] title: &str,
+        #[case] expected: Option<(String, Option<String>)>,
+    ) {
+        assert_eq!(parse_workflow_run_notification_title(title), expected);
+    }
+}
@@ -4,14 +4,15 @@ use uuid::Uuid;
 use universal_inbox::{
     third_party::integrations::linear::{
         LinearIssue, LinearLabel, LinearProject, LinearProjectMilestone, LinearProjectStatus,
-        LinearTeam, LinearUser, LinearWorkflowState, LinearWorkflowStateIds,
+        LinearTeam, LinearTeamDetails, LinearTeamMember, LinearUser, LinearWorkflowState,
+        LinearWorkflowStateIds,
     },
     utils::emoji::replace_emoji_code_with_emoji,
 };
 
 use crate::universal_inbox::UniversalInboxError;
 
-use super::assigned_issues_query;
+use super::{assigned_issues_query, issue_query, issue_team_query};
 
 impl TryFrom<assigned_issues_query::AssignedIssuesQueryIssuesNodesTeam> for LinearTeam {
     type Error = UniversalInboxError;
@@ -285,3 +286,100 @@ impl TryFrom<assigned_issues_query::ResponseData> for Vec<LinearIssue> {
             .collect()
     }
 }
+
+impl TryFrom<issue_query::ResponseData> for LinearIssue {
+    type Error = UniversalInboxError;
+
+    fn try_from(value: issue_query::ResponseData) -> Result<Self, Self::Error> {
+        // `IssueQuery` selects the exact same issue fields as `AssignedIssuesQuery`,
+        // going through JSON allows to reuse its conversion
+        let issue: assigned_issues_query::AssignedIssuesQueryIssuesNodes = serde_json::from_value(
+            serde_json::to_value(value.issue).context("Failed to serialize Linear issue")?,
+        )
+        .context("Failed to parse Linear issue")?;
+
+        issue.try_into()
+    }
+}
+
+impl TryFrom<issue_team_query::ResponseData> for LinearTeamDetails {
+    type Error = UniversalInboxError;
+
+    fn try_from(value: issue_team_query::ResponseData) -> Result<Self, Self::Error> {
+        let team = value.issue.team;
+        let mut states = team.states.nodes;
+        states.sort_by(|state1, state2| state1.position.total_cmp(&state2.position));
+
+        Ok(LinearTeamDetails {
+            workflow_states: states
+                .into_iter()
+                .map(|state| {
+                    Ok(LinearWorkflowState {
+                        name: state.name,
+                        description: state.description,
+                        color: state.color,
+                        r#type: state.type_.try_into()?,
+                        id: Some(Uuid::parse_str(&state.id).with_context(|| {
+                            format!("Failed to parse UUID from `{}`", state.id)
+                        })?),
+                    })
+                })
+                .collect::<Result<_, UniversalInboxError>>()?,
+            members: team
+                .members
+                .nodes
+                .into_iter()
+                .filter(|member| member.active)
+                .map(|member| {
+                    Ok(LinearTeamMember {
+                        id: Uuid::parse_str(&member.id).with_context(|| {
+                            format!("Failed to parse UUID from `{}`", member.id)
+                        })?,
+                        name: member.display_name,
+                        avatar_url: member
+                            .avatar_url
+                            .map(|avatar_url| {
+                                avatar_url.parse().with_context(|| {
+                                    format!("Failed to parse URL from `{avatar_url}`")
+                                })
+                            })
+                            .transpose()?,
+                        is_viewer: member.id == value.viewer.id,
+                    })
+                })
+                .collect::<Result<_, UniversalInboxError>>()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use graphql_client::Response;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn load_fixture<T: for<'de> serde::Deserialize<'de>>(fixture_file_name: &str) -> T {
+        let input_str = fs::read_to_string(format!(
+            "{}/tests/api/fixtures/{fixture_file_name}",
+            env::var("CARGO_MANIFEST_DIR").unwrap()
+        ))
+        .unwrap();
+        serde_json::from_str(&input_str).unwrap()
+    }
+
+    #[test]
+    fn test_issue_query_is_converted_like_assigned_issues_query() {
+        let issue_response: Response<issue_query::ResponseData> = load_fixture("linear_issue.json");
+        let assigned_issues_response: Response<assigned_issues_query::ResponseData> =
+            load_fixture("sync_linear_tasks.json");
+
+        let issue: LinearIssue = issue_response.data.unwrap().try_into().unwrap();
+        let assigned_issues: Vec<LinearIssue> =
+            assigned_issues_response.data.unwrap().try_into().unwrap();
+
+        assert_eq!(issue, assigned_issues[0]);
+    }
+}
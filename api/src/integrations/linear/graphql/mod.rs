@@ -69,3 +69,57 @@ pub struct AssignedIssuesQuery;
     variables_derives = "Deserialize"
 )]
 pub struct IssueUpdateState;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/integrations/linear/graphql/schema.json",
+    query_path = "src/integrations/linear/graphql/issue_query.graphql",
+    response_derives = "Debug,Clone,Serialize",
+    variables_derives = "Deserialize"
+)]
+pub struct IssueQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/integrations/linear/graphql/schema.json",
+    query_path = "src/integrations/linear/graphql/issue_team_query.graphql",
+    response_derives = "Debug,Clone,Serialize",
+    variables_derives = "Deserialize"
+)]
+pub struct IssueTeamQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/integrations/linear/graphql/schema.json",
+    query_path = "src/integrations/linear/graphql/comment_create.graphql",
+    response_derives = "Debug,Clone,Serialize",
+    variables_derives = "Deserialize"
+)]
+pub struct CommentCreate;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/integrations/linear/graphql/schema.json",
+    query_path = "src/integrations/linear/graphql/issue_update_assignee.graphql",
+    response_derives = "Debug,Clone,Serialize",
+    variables_derives = "Deserialize"
+)]
+pub struct IssueUpdateAssignee;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/integrations/linear/graphql/schema.json",
+    query_path = "src/integrations/linear/graphql/issue_update_priority.graphql",
+    response_derives = "Debug,Clone,Serialize",
+    variables_derives = "Deserialize"
+)]
+pub struct IssueUpdatePriority;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/integrations/linear/graphql/schema.json",
+    query_path = "src/integrations/linear/graphql/issue_update_due_date.graphql",
+    response_derives = "Debug,Clone,Serialize",
+    variables_derives = "Deserialize"
+)]
+pub struct IssueUpdateDueDate;
@@ -3,7 +3,7 @@ use std::{sync::Arc, time::Duration};
 
 use anyhow::{Context, anyhow};
 use async_trait::async_trait;
-use chrono::{DateTime, Timelike, Utc};
+use chrono::{DateTime, NaiveDate, Timelike, Utc};
 use graphql::{
     issue_update_state::IssueUpdateStateIssueUpdate,
     issue_update_subscribers::IssueUpdateSubscribersIssueUpdate,
@@ -34,13 +34,18 @@ use wiremock::{
 use universal_inbox::{
     HasHtmlUrl,
     integration_connection::provider::{IntegrationProviderKind, IntegrationProviderSource},
-    notification::{Notification, NotificationSource, NotificationSourceKind, NotificationStatus},
+    notification::{
+        Notification, NotificationSource, NotificationSourceKind, NotificationStatus,
+        service::LinearIssueAction,
+    },
     task::{
         CreateOrUpdateTaskRequest, TaskCreationConfig, TaskSource, TaskSourceKind, TaskStatus,
         integrations::todoist::TODOIST_INBOX_PROJECT, service::TaskPatch,
     },
     third_party::{
-        integrations::linear::{LinearIssue, LinearNotification},
+        integrations::linear::{
+            LinearIssue, LinearIssuePriority, LinearNotification, LinearTeamDetails,
+        },
         item::{
             ThirdPartyItem, ThirdPartyItemData, ThirdPartyItemFromSource, ThirdPartyItemSourceKind,
         },
@@ -52,11 +57,14 @@ use universal_inbox::{
 use crate::{
     integrations::{
         linear::graphql::{
-            AssignedIssuesQuery, IssueUpdateState, IssueUpdateSubscribers, NotificationArchive,
-            NotificationSubscribersQuery, NotificationUpdateSnoozedUntilAt, NotificationsQuery,
-            assigned_issues_query, issue_update_state, issue_update_subscribers,
-            notification_archive, notification_subscribers_query,
-            notification_update_snoozed_until_at, notifications_query,
+            AssignedIssuesQuery, CommentCreate, IssueQuery, IssueTeamQuery, IssueUpdateAssignee,
+            IssueUpdateDueDate, IssueUpdatePriority, IssueUpdateState, IssueUpdateSubscribers,
+            NotificationArchive, NotificationSubscribersQuery, NotificationUpdateSnoozedUntilAt,
+            NotificationsQuery, assigned_issues_query, comment_create, issue_query,
+            issue_team_query, issue_update_assignee, issue_update_due_date, issue_update_priority,
+            issue_update_state, issue_update_subscribers, notification_archive,
+            notification_subscribers_query, notification_update_snoozed_until_at,
+            notifications_query,
         },
         notification::ThirdPartyNotificationSourceService,
         oauth2::AccessToken,
@@ -457,6 +465,319 @@ impl LinearService {
 
         Ok(())
     }
+
+    pub async fn create_comment(
+        &self,
+        access_token: &AccessToken,
+        issue_id: String,
+        body: String,
+    ) -> Result<(), UniversalInboxError> {
+        let request_body = CommentCreate::build_query(comment_create::Variables { issue_id, body });
+
+        let update_response: Response<comment_create::ResponseData> = self
+            .build_linear_client(access_token)?
+            .post(&self.linear_graphql_url, Some(&request_body))
+            .await
+            .context("Cannot create issue comment from Linear API")?;
+
+        assert_no_error_in_graphql_response(&update_response, LINEAR_GRAPHQL_API_NAME)?;
+
+        let response_data = update_response
+            .data
+            .ok_or_else(|| anyhow!("Failed to parse `data` from Linear graphql response"))?;
+
+        if !response_data.comment_create.success {
+            return Err(UniversalInboxError::Unexpected(anyhow!(
+                "Linear API call failed with an unknown error"
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub async fn update_issue_assignee(
+        &self,
+        access_token: &AccessToken,
+        issue_id: String,
+        assignee_id: Option<String>,
+    ) -> Result<(), UniversalInboxError> {
+        let request_body = IssueUpdateAssignee::build_query(issue_update_assignee::Variables {
+            id: issue_id,
+            assignee_id,
+        });
+
+        let update_response: Response<issue_update_assignee::ResponseData> = self
+            .build_linear_client(access_token)?
+            .post(&self.linear_graphql_url, Some(&request_body))
+            .await
+            .context("Cannot update issue assignee from Linear API")?;
+
+        assert_no_error_in_graphql_response(&update_response, LINEAR_GRAPHQL_API_NAME)?;
+
+        let response_data = update_response
+            .data
+            .ok_or_else(|| anyhow!("Failed to parse `data` from Linear graphql response"))?;
+
+        if !response_data.issue_update.success {
+            return Err(UniversalInboxError::Unexpected(anyhow!(
+                "Linear API call failed with an unknown error"
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub async fn update_issue_priority(
+        &self,
+        access_token: &AccessToken,
+        issue_id: String,
+        priority: LinearIssuePriority,
+    ) -> Result<(), UniversalInboxError> {
+        let request_body = IssueUpdatePriority::build_query(issue_update_priority::Variables {
+            id: issue_id,
+            priority: priority as i64,
+        });
+
+        let update_response: Response<issue_update_priority::ResponseData> = self
+            .build_linear_client(access_token)?
+            .post(&self.linear_graphql_url, Some(&request_body))
+            .await
+            .context("Cannot update issue priority from Linear API")?;
+
+        assert_no_error_in_graphql_response(&update_response, LINEAR_GRAPHQL_API_NAME)?;
+
+        let response_data = update_response
+            .data
+            .ok_or_else(|| anyhow!("Failed to parse `data` from Linear graphql response"))?;
+
+        if !response_data.issue_update.success {
+            return Err(UniversalInboxError::Unexpected(anyhow!(
+                "Linear API call failed with an unknown error"
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub async fn update_issue_due_date(
+        &self,
+        access_token: &AccessToken,
+        issue_id: String,
+        due_date: Option<NaiveDate>,
+    ) -> Result<(), UniversalInboxError> {
+        let request_body = IssueUpdateDueDate::build_query(issue_update_due_date::Variables {
+            id: issue_id,
+            due_date,
+        });
+
+        let update_response: Response<issue_update_due_date::ResponseData> = self
+            .build_linear_client(access_token)?
+            .post(&self.linear_graphql_url, Some(&request_body))
+            .await
+            .context("Cannot update issue due date from Linear API")?;
+
+        assert_no_error_in_graphql_response(&update_response, LINEAR_GRAPHQL_API_NAME)?;
+
+        let response_data = update_response
+            .data
+            .ok_or_else(|| anyhow!("Failed to parse `data` from Linear graphql response"))?;
+
+        if !response_data.issue_update.success {
+            return Err(UniversalInboxError::Unexpected(anyhow!(
+                "Linear API call failed with an unknown error"
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub async fn query_issue(
+        &self,
+        access_token: &AccessToken,
+        issue_id: String,
+    ) -> Result<LinearIssue, UniversalInboxError> {
+        let request_body = IssueQuery::build_query(issue_query::Variables { id: issue_id });
+
+        let issue_response: Response<issue_query::ResponseData> = self
+            .build_linear_client(access_token)?
+            .post(&self.linear_graphql_url, Some(&request_body))
+            .await
+            .context("Cannot fetch issue from Linear API")?;
+
+        assert_no_error_in_graphql_response(&issue_response, LINEAR_GRAPHQL_API_NAME)?;
+
+        issue_response
+            .data
+            .ok_or_else(|| anyhow!("Failed to parse `data` from Linear graphql response"))?
+            .try_into()
+    }
+
+    pub async fn query_issue_team(
+        &self,
+        access_token: &AccessToken,
+        issue_id: String,
+    ) -> Result<LinearTeamDetails, UniversalInboxError> {
+        let request_body =
+            IssueTeamQuery::build_query(issue_team_query::Variables { id: issue_id });
+
+        let issue_team_response: Response<issue_team_query::ResponseData> = self
+            .build_linear_client(access_token)?
+            .post(&self.linear_graphql_url, Some(&request_body))
+            .await
+            .context("Cannot fetch issue team from Linear API")?;
+
+        assert_no_error_in_graphql_response(&issue_team_response, LINEAR_GRAPHQL_API_NAME)?;
+
+        issue_team_response
+            .data
+            .ok_or_else(|| anyhow!("Failed to parse `data` from Linear graphql response"))?
+            .try_into()
+    }
+
+    /// Fetch the workflow states and members of the team of the issue of a Linear notification
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(user.id = user_id.to_string()),
+        err
+    )]
+    pub async fn fetch_issue_team_details(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        linear_notification: &LinearNotification,
+        user_id: UserId,
+    ) -> Result<LinearTeamDetails, UniversalInboxError> {
+        let Some(issue_id) = linear_notification.get_issue_id() else {
+            return Err(UniversalInboxError::UnsupportedAction(
+                "Cannot fetch the team of a Linear project notification".to_string(),
+            ));
+        };
+
+        let (access_token, _) = self
+            .integration_connection_service
+            .read()
+            .await
+            .find_access_token(executor, IntegrationProviderKind::Linear, user_id)
+            .await?
+            .ok_or_else(|| anyhow!("Cannot fetch a Linear issue team without an access token"))?;
+
+        self.query_issue_team(&access_token, issue_id.to_string())
+            .await
+    }
+
+    /// Apply an action on the issue of a Linear notification and return the notification
+    /// with refreshed issue data
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(action = action.description(), user.id = user_id.to_string()),
+        err
+    )]
+    pub async fn apply_issue_action(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        linear_notification: &LinearNotification,
+        action: &LinearIssueAction,
+        user_id: UserId,
+    ) -> Result<LinearNotification, UniversalInboxError> {
+        let LinearNotification::IssueNotification {
+            id,
+            r#type,
+            read_at,
+            updated_at,
+            snoozed_until_at,
+            organization,
+            issue,
+            comment,
+        } = linear_notification
+        else {
+            return Err(UniversalInboxError::UnsupportedAction(format!(
+                "Cannot {} a Linear project notification, expected an issue notification",
+                action.description()
+            )));
+        };
+
+        let (access_token, _) = self
+            .integration_connection_service
+            .read()
+            .await
+            .find_access_token(executor, IntegrationProviderKind::Linear, user_id)
+            .await?
+            .ok_or_else(|| anyhow!("Cannot act on a Linear issue without an access token"))?;
+
+        let issue_id = issue.id.to_string();
+        match action {
+            LinearIssueAction::Comment { body } => {
+                self.create_comment(&access_token, issue_id.clone(), body.clone())
+                    .await?
+            }
+            LinearIssueAction::UpdateState { state_id } => {
+                let team = self
+                    .query_issue_team(&access_token, issue_id.clone())
+                    .await?;
+                if !team
+                    .workflow_states
+                    .iter()
+                    .any(|state| state.id == Some(*state_id))
+                {
+                    return Err(UniversalInboxError::InvalidInputData {
+                        source: None,
+                        user_error: format!(
+                            "{state_id} is not a workflow state of the team of Linear issue {}",
+                            issue.identifier
+                        ),
+                    });
+                }
+                self.update_issue_state(&access_token, issue_id.clone(), state_id.to_string())
+                    .await?
+            }
+            LinearIssueAction::Assign { assignee_id } => {
+                let team = self
+                    .query_issue_team(&access_token, issue_id.clone())
+                    .await?;
+                if !team.members.iter().any(|member| member.id == *assignee_id) {
+                    return Err(UniversalInboxError::InvalidInputData {
+                        source: None,
+                        user_error: format!(
+                            "{assignee_id} is not an active member of the team of Linear issue {}",
+                            issue.identifier
+                        ),
+                    });
+                }
+                self.update_issue_assignee(
+                    &access_token,
+                    issue_id.clone(),
+                    Some(assignee_id.to_string()),
+                )
+                .await?
+            }
+            LinearIssueAction::Unassign => {
+                self.update_issue_assignee(&access_token, issue_id.clone(), None)
+                    .await?
+            }
+            LinearIssueAction::SetPriority { priority } => {
+                self.update_issue_priority(&access_token, issue_id.clone(), *priority)
+                    .await?
+            }
+            LinearIssueAction::SetDueDate { due_date } => {
+                self.update_issue_due_date(&access_token, issue_id.clone(), *due_date)
+                    .await?
+            }
+        }
+
+        let refreshed_issue = self.query_issue(&access_token, issue_id).await?;
+
+        Ok(LinearNotification::IssueNotification {
+            id: *id,
+            r#type: r#type.clone(),
+            read_at: *read_at,
+            updated_at: *updated_at,
+            snoozed_until_at: *snoozed_until_at,
+            organization: organization.clone(),
+            issue: refreshed_issue,
+            comment: comment.clone(),
+        })
+    }
 }
 
 #[async_trait]
@@ -26,6 +26,7 @@ pub mod google_oauth;
 pub mod linear;
 pub mod mock;
 pub mod oauth2;
+pub mod rate_limit;
 pub mod slack;
 pub mod slack_oauth;
 pub mod ticktick;
@@ -44,6 +45,19 @@ pub mod third_party {
             last_sync_completed_at: Option<DateTime<Utc>>,
         ) -> Result<Vec<ThirdPartyItem>, UniversalInboxError>;
 
+        /// Fetch items unless the source reports them as unchanged since the last sync,
+        /// in which case `None` is returned and the known items are kept as they are
+        async fn fetch_items_if_modified(
+            &self,
+            executor: &mut Transaction<'_, Postgres>,
+            user_id: UserId,
+            last_sync_completed_at: Option<DateTime<Utc>>,
+        ) -> Result<Option<Vec<ThirdPartyItem>>, UniversalInboxError> {
+            self.fetch_items(executor, user_id, last_sync_completed_at)
+                .await
+                .map(Some)
+        }
+
         fn is_sync_incremental(&self) -> bool;
 
         fn get_third_party_item_source_kind(&self) -> ThirdPartyItemSourceKind;
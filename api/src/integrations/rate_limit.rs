@@ -0,0 +1,350 @@
+//! Rate-limit signals sent by the providers along with their API responses.
+//!
+//! Each provider reports its budget differently:
+//! - Github: `X-RateLimit-Remaining`/`X-RateLimit-Reset` (epoch seconds) and
+//!   `X-Poll-Interval` (seconds to wait before polling notifications again)
+//! - Linear: `X-RateLimit-Requests-*` and `X-RateLimit-Complexity-*` (reset
+//!   as epoch milliseconds)
+//! - Slack: `Retry-After` on rate limited calls, surfaced by `slack-morphism`
+//!   as a `SlackClientError::RateLimitError`
+//! - Google: `429 Too Many Requests`, sometimes with a `Retry-After`
+
+use std::error::Error;
+
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+use http::{HeaderMap, StatusCode, header::RETRY_AFTER};
+use slack_morphism::errors::SlackClientError;
+
+use crate::utils::api::ApiClientError;
+
+/// Delay before calling again a provider which rate limited a call without
+/// telling for how long
+pub const DEFAULT_RATE_LIMITED_DELAY_IN_SECONDS: i64 = 60;
+
+const POLL_INTERVAL_HEADER: &str = "x-poll-interval";
+
+#[derive(Debug, Clone, Copy)]
+enum ResetUnit {
+    EpochSeconds,
+    EpochMilliseconds,
+    DeltaSeconds,
+}
+
+/// Pairs of headers reporting the remaining calls (or points) of a budget and
+/// when it resets
+const BUDGET_HEADERS: [(&str, &str, ResetUnit); 4] = [
+    (
+        "x-ratelimit-remaining",
+        "x-ratelimit-reset",
+        ResetUnit::EpochSeconds,
+    ),
+    (
+        "x-ratelimit-requests-remaining",
+        "x-ratelimit-requests-reset",
+        ResetUnit::EpochMilliseconds,
+    ),
+    (
+        "x-ratelimit-complexity-remaining",
+        "x-ratelimit-complexity-reset",
+        ResetUnit::EpochMilliseconds,
+    ),
+    (
+        "ratelimit-remaining",
+        "ratelimit-reset",
+        ResetUnit::DeltaSeconds,
+    ),
+];
+
+/// Rate-limit budget reported by a provider
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UpstreamRateLimit {
+    /// Remaining calls (or points) of the scarcest budget
+    pub remaining: Option<i64>,
+    /// When the scarcest budget resets
+    pub reset_at: Option<DateTime<Utc>>,
+    /// The provider asked not to be called again before this time
+    pub retry_at: Option<DateTime<Utc>>,
+    /// A call has been rejected with a `429 Too Many Requests`
+    pub is_rate_limited: bool,
+}
+
+impl UpstreamRateLimit {
+    pub fn from_response(status: StatusCode, headers: &HeaderMap, now: DateTime<Utc>) -> Self {
+        let (remaining, reset_at) = BUDGET_HEADERS
+            .iter()
+            .filter_map(|(remaining_header, reset_header, reset_unit)| {
+                let remaining = header_value::<i64>(headers, remaining_header)?;
+                let reset_at = header_value::<i64>(headers, reset_header)
+                    .and_then(|reset| parse_reset(reset, *reset_unit, now));
+                Some((remaining, reset_at))
+            })
+            .min_by_key(|(remaining, _)| *remaining)
+            .map(|(remaining, reset_at)| (Some(remaining), reset_at))
+            .unwrap_or_default();
+
+        let retry_after = headers
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| parse_retry_after(value, now));
+        let poll_interval = header_value::<i64>(headers, POLL_INTERVAL_HEADER)
+            .and_then(TimeDelta::try_seconds)
+            .map(|interval| now + interval);
+        let is_rate_limited = status == StatusCode::TOO_MANY_REQUESTS;
+        let rate_limited = (is_rate_limited && retry_after.is_none())
+            .then(|| now + TimeDelta::seconds(DEFAULT_RATE_LIMITED_DELAY_IN_SECONDS));
+
+        Self {
+            remaining,
+            reset_at,
+            retry_at: [retry_after, poll_interval, rate_limited]
+                .into_iter()
+                .flatten()
+                .max(),
+            is_rate_limited,
+        }
+    }
+
+    /// Rate limit carried by an error raised while rate limited by a provider
+    /// not going through `ApiClient`
+    pub fn from_error(error: &(dyn Error + 'static), now: DateTime<Utc>) -> Option<Self> {
+        std::iter::successors(Some(error), |&error| error.source()).find_map(|error| {
+            let Some(SlackClientError::RateLimitError(rate_limit_error)) =
+                error.downcast_ref::<SlackClientError>()
+            else {
+                return None;
+            };
+            let retry_after = rate_limit_error
+                .retry_after
+                .and_then(|retry_after| TimeDelta::from_std(retry_after).ok())
+                .unwrap_or_else(|| TimeDelta::seconds(DEFAULT_RATE_LIMITED_DELAY_IN_SECONDS));
+            Some(Self {
+                retry_at: Some(now + retry_after),
+                is_rate_limited: true,
+                ..Default::default()
+            })
+        })
+    }
+
+    /// Merge a more recent rate limit into this one
+    pub fn merge(self, newer: Self) -> Self {
+        Self {
+            remaining: newer.remaining.or(self.remaining),
+            reset_at: newer.reset_at.or(self.reset_at),
+            retry_at: newer.retry_at.max(self.retry_at),
+            is_rate_limited: self.is_rate_limited || newer.is_rate_limited,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Time before which the provider must not be called again, if any
+    pub fn limited_until(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let exhausted_until = self.remaining.filter(|remaining| *remaining <= 0).map(|_| {
+            self.reset_at
+                .unwrap_or_else(|| now + TimeDelta::seconds(DEFAULT_RATE_LIMITED_DELAY_IN_SECONDS))
+        });
+
+        [self.retry_at, exhausted_until]
+            .into_iter()
+            .flatten()
+            .max()
+            .filter(|limited_until| *limited_until > now)
+    }
+
+    /// Time until which a sync which failed with `error` must be deferred,
+    /// if it failed because the provider rate limited it. The error itself may
+    /// not tell as some integrations only keep the message of upstream errors.
+    pub fn rate_limited_until(
+        &self,
+        error: &(dyn Error + 'static),
+        now: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        let is_rate_limit_error = self.is_rate_limited
+            || std::iter::successors(Some(error), |&error| error.source()).any(|error| {
+                matches!(
+                    error.downcast_ref::<ApiClientError>(),
+                    Some(ApiClientError::RateLimitError { .. })
+                )
+            });
+        let budget_exhausted = self.remaining.is_some_and(|remaining| remaining <= 0);
+
+        if is_rate_limit_error || budget_exhausted {
+            self.limited_until(now).or_else(|| {
+                is_rate_limit_error
+                    .then(|| now + TimeDelta::seconds(DEFAULT_RATE_LIMITED_DELAY_IN_SECONDS))
+            })
+        } else {
+            None
+        }
+    }
+}
+
+fn header_value<T: std::str::FromStr>(headers: &HeaderMap, name: &str) -> Option<T> {
+    headers.get(name)?.to_str().ok()?.trim().parse::<T>().ok()
+}
+
+fn parse_reset(reset: i64, reset_unit: ResetUnit, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    match reset_unit {
+        ResetUnit::EpochSeconds => Utc.timestamp_opt(reset, 0).single(),
+        ResetUnit::EpochMilliseconds => Utc.timestamp_millis_opt(reset).single(),
+        ResetUnit::DeltaSeconds => TimeDelta::try_seconds(reset).map(|delta| now + delta),
+    }
+}
+
+/// `Retry-After` is either a number of seconds or an HTTP date
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<i64>() {
+        return TimeDelta::try_seconds(seconds).map(|delay| now + delay);
+    }
+    DateTime::parse_from_rfc2822(value)
+        .ok()
+        .map(|retry_at| retry_at.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use anyhow::Context;
+    use pretty_assertions::assert_eq;
+
+    fn headers(values: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in values {
+            headers.insert(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_github_exhausted_budget() {
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let reset_at = now + TimeDelta::minutes(10);
+        let rate_limit = UpstreamRateLimit::from_response(
+            StatusCode::FORBIDDEN,
+            &headers(&[
+                ("x-ratelimit-remaining", "0"),
+                ("x-ratelimit-reset", &reset_at.timestamp().to_string()),
+            ]),
+            now,
+        );
+
+        assert_eq!(
+            rate_limit,
+            UpstreamRateLimit {
+                remaining: Some(0),
+                reset_at: Some(reset_at),
+                retry_at: None,
+                is_rate_limited: false,
+            }
+        );
+        assert_eq!(rate_limit.limited_until(now), Some(reset_at));
+    }
+
+    #[test]
+    fn test_github_poll_interval() {
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let rate_limit = UpstreamRateLimit::from_response(
+            StatusCode::OK,
+            &headers(&[("x-ratelimit-remaining", "4000"), ("x-poll-interval", "60")]),
+            now,
+        );
+
+        assert_eq!(rate_limit.remaining, Some(4000));
+        assert_eq!(
+            rate_limit.limited_until(now),
+            Some(now + TimeDelta::seconds(60))
+        );
+    }
+
+    #[test]
+    fn test_linear_scarcest_budget() {
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let complexity_reset_at = now + TimeDelta::minutes(30);
+        let rate_limit = UpstreamRateLimit::from_response(
+            StatusCode::OK,
+            &headers(&[
+                ("x-ratelimit-requests-remaining", "1200"),
+                (
+                    "x-ratelimit-requests-reset",
+                    &(now + TimeDelta::minutes(60))
+                        .timestamp_millis()
+                        .to_string(),
+                ),
+                ("x-ratelimit-complexity-remaining", "0"),
+                (
+                    "x-ratelimit-complexity-reset",
+                    &complexity_reset_at.timestamp_millis().to_string(),
+                ),
+            ]),
+            now,
+        );
+
+        assert_eq!(rate_limit.remaining, Some(0));
+        assert_eq!(rate_limit.limited_until(now), Some(complexity_reset_at));
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        let rate_limit = UpstreamRateLimit::from_response(
+            StatusCode::TOO_MANY_REQUESTS,
+            &headers(&[("retry-after", "120")]),
+            now,
+        );
+        assert_eq!(rate_limit.retry_at, Some(now + TimeDelta::seconds(120)));
+
+        let rate_limit = UpstreamRateLimit::from_response(
+            StatusCode::TOO_MANY_REQUESTS,
+            &headers(&[("retry-after", "Mon, 01 Jan 2024 12:05:00 GMT")]),
+            now,
+        );
+        assert_eq!(rate_limit.retry_at, Some(now + TimeDelta::minutes(5)));
+    }
+
+    #[test]
+    fn test_too_many_requests_without_retry_after() {
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let rate_limit =
+            UpstreamRateLimit::from_response(StatusCode::TOO_MANY_REQUESTS, &HeaderMap::new(), now);
+
+        assert_eq!(
+            rate_limit.limited_until(now),
+            Some(now + TimeDelta::seconds(DEFAULT_RATE_LIMITED_DELAY_IN_SECONDS))
+        );
+    }
+
+    #[test]
+    fn test_rate_limited_until() {
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let rate_limit_error = Err::<(), _>(ApiClientError::rate_limit_error(
+            "Too many requests".to_string(),
+        ))
+        .context("Failed to fetch notifications")
+        .unwrap_err();
+        let other_error = anyhow::anyhow!("Connection reset");
+        let poll_interval = UpstreamRateLimit {
+            remaining: Some(4000),
+            retry_at: Some(now + TimeDelta::seconds(60)),
+            ..Default::default()
+        };
+
+        // Waiting for the poll interval is not a reason for a sync to fail
+        assert_eq!(
+            poll_interval.rate_limited_until(other_error.as_ref(), now),
+            None
+        );
+        assert_eq!(
+            poll_interval.rate_limited_until(rate_limit_error.as_ref(), now),
+            Some(now + TimeDelta::seconds(60))
+        );
+        assert_eq!(
+            UpstreamRateLimit::default().rate_limited_until(rate_limit_error.as_ref(), now),
+            Some(now + TimeDelta::seconds(DEFAULT_RATE_LIMITED_DELAY_IN_SECONDS))
+        );
+    }
+}
@@ -61,7 +61,7 @@ use crate::{
         UniversalInboxError, integration_connection::service::IntegrationConnectionService,
         slack_bridge::service::SlackBridgeService,
     },
-    utils::cache::build_redis_cache,
+    utils::cache::{IOCache, build_io_cache},
 };
 
 static SLACK_BASE_URL: &str = "https://api.slack.com/api";
@@ -1013,9 +1013,9 @@ impl SlackService {
     key = "String",
     // Use user_id to avoid leaking a message to an unauthorized user
     convert = r#"{ format!("{}__{}__{}__{}", slack_base_url, _user_id, channel, message) }"#,
-    ty = "cached::AsyncRedisCache<String, SlackHistoryMessage>",
+    ty = "IOCache<SlackHistoryMessage>",
     map_error = r##"|e| UniversalInboxError::Unexpected(anyhow!("Failed to cache Slack `fetch_message`: {:?}", e))"##,
-    create = r##" { build_redis_cache("slack:fetch_message", Duration::from_secs(60), false).await }"##,
+    create = r##" { build_io_cache("slack:fetch_message", Duration::from_secs(60), false).await }"##,
     with_cached_flag = true
 )]
 async fn cached_fetch_message(
@@ -1059,9 +1059,9 @@ async fn cached_fetch_message(
     key = "String",
     // Use user_id to avoid leaking a message to an unauthorized user
     convert = r#"{ format!("{}__{}__{}__{}__{:?}", slack_base_url, _user_id, channel, root_message, current_message) }"#,
-    ty = "cached::AsyncRedisCache<String, Vec<SlackHistoryMessage>>",
+    ty = "IOCache<Vec<SlackHistoryMessage>>",
     map_error = r##"|e| UniversalInboxError::Unexpected(anyhow!("Failed to cache Slack `fetch_thread`: {:?}", e))"##,
-    create = r##" { build_redis_cache("slack:fetch_thread", Duration::from_secs(60), false).await }"##,
+    create = r##" { build_io_cache("slack:fetch_thread", Duration::from_secs(60), false).await }"##,
     with_cached_flag = true
 )]
 async fn cached_fetch_thread(
@@ -1097,9 +1097,9 @@ async fn cached_fetch_thread(
 #[io_cached(
     key = "String",
     convert = r#"{ format!("{}__{}", slack_base_url, channel) }"#,
-    ty = "cached::AsyncRedisCache<String, SlackChannelInfo>",
+    ty = "IOCache<SlackChannelInfo>",
     map_error = r##"|e| UniversalInboxError::Unexpected(anyhow!("Failed to cache Slack `fetch_channel`: {:?}", e))"##,
-    create = r##" { build_redis_cache("slack:fetch_channel", Duration::from_secs(24 * 60 * 60), false).await }"##,
+    create = r##" { build_io_cache("slack:fetch_channel", Duration::from_secs(24 * 60 * 60), false).await }"##,
     with_cached_flag = true
 )]
 async fn cached_fetch_channel(
@@ -1122,9 +1122,9 @@ async fn cached_fetch_channel(
     key = "String",
     // Use user_id to avoid leaking user details to an unauthorized user
     convert = r#"{ format!("{}__{}__{}", slack_base_url, _user_id, user) }"#,
-    ty = "cached::AsyncRedisCache<String, SlackUser>",
+    ty = "IOCache<SlackUser>",
     map_error = r##"|e| UniversalInboxError::Unexpected(anyhow!("Failed to cache Slack `fetch_user`: {:?}", e))"##,
-    create = r##" { build_redis_cache("slack:fetch_user", Duration::from_secs(24 * 60 * 60), false).await }"##,
+    create = r##" { build_io_cache("slack:fetch_user", Duration::from_secs(24 * 60 * 60), false).await }"##,
     with_cached_flag = true
 )]
 async fn cached_fetch_user(
@@ -1147,9 +1147,9 @@ async fn cached_fetch_user(
 #[io_cached(
     key = "String",
     convert = r#"{ format!("{}", slack_base_url) }"#,
-    ty = "cached::AsyncRedisCache<String, Vec<SlackUserGroup>>",
+    ty = "IOCache<Vec<SlackUserGroup>>",
     map_error = r##"|e| UniversalInboxError::Unexpected(anyhow!("Failed to cache Slack `list_usergroups`: {:?}", e))"##,
-    create = r##" { build_redis_cache("slack:list_usergroups", Duration::from_secs(12 * 60 * 60), false).await }"##,
+    create = r##" { build_io_cache("slack:list_usergroups", Duration::from_secs(12 * 60 * 60), false).await }"##,
     with_cached_flag = true
 )]
 async fn cached_list_usergroups(
@@ -1170,9 +1170,9 @@ async fn cached_list_usergroups(
 #[io_cached(
     key = "String",
     convert = r#"{ format!("{}__{}", slack_base_url, usergroup_id) }"#,
-    ty = "cached::AsyncRedisCache<String, Vec<SlackUserId>>",
+    ty = "IOCache<Vec<SlackUserId>>",
     map_error = r##"|e| UniversalInboxError::Unexpected(anyhow!("Failed to cache Slack `list_users_in_usergroup`: {:?}", e))"##,
-    create = r##" { build_redis_cache("slack:list_users_in_usergroup", Duration::from_secs(12 * 60 * 60), false).await }"##,
+    create = r##" { build_io_cache("slack:list_users_in_usergroup", Duration::from_secs(12 * 60 * 60), false).await }"##,
     with_cached_flag = true
 )]
 async fn cached_list_users_in_usergroup(
@@ -1196,9 +1196,9 @@ async fn cached_list_users_in_usergroup(
 #[io_cached(
     key = "String",
     convert = r#"{ format!("{}__{}", slack_base_url, bot) }"#,
-    ty = "cached::AsyncRedisCache<String, SlackBotInfo>",
+    ty = "IOCache<SlackBotInfo>",
     map_error = r##"|e| UniversalInboxError::Unexpected(anyhow!("Failed to cache Slack `fetch_bot`: {:?}", e))"##,
-    create = r##" { build_redis_cache("slack:fetch_bot", Duration::from_secs(24 * 60 * 60), false).await }"##,
+    create = r##" { build_io_cache("slack:fetch_bot", Duration::from_secs(24 * 60 * 60), false).await }"##,
     with_cached_flag = true
 )]
 async fn cached_fetch_bot(
@@ -1220,9 +1220,9 @@ async fn cached_fetch_bot(
 #[io_cached(
     key = "String",
     convert = r#"{ format!("{}__{}", slack_base_url, team) }"#,
-    ty = "cached::AsyncRedisCache<String, SlackTeamInfo>",
+    ty = "IOCache<SlackTeamInfo>",
     map_error = r##"|e| UniversalInboxError::Unexpected(anyhow!("Failed to cache Slack `fetch_team`: {:?}", e))"##,
-    create = r##" { build_redis_cache("slack:fetch_team", Duration::from_secs(24 * 60 * 60), false).await }"##,
+    create = r##" { build_io_cache("slack:fetch_team", Duration::from_secs(24 * 60 * 60), false).await }"##,
     with_cached_flag = true
 )]
 async fn cached_fetch_team(
@@ -1244,9 +1244,9 @@ async fn cached_fetch_team(
 #[io_cached(
     key = "String",
     convert = r#"{ format!("{}__{}", slack_base_url, slack_api_token.team_id.as_ref().map(|t| t.0.as_str()).unwrap_or("no-team")) }"#,
-    ty = "cached::AsyncRedisCache<String, HashMap<SlackEmojiName, SlackEmojiRef>>",
+    ty = "IOCache<HashMap<SlackEmojiName, SlackEmojiRef>>",
     map_error = r##"|e| UniversalInboxError::Unexpected(anyhow!("Failed to cache Slack `list_emojis`: {:?}", e))"##,
-    create = r##" { build_redis_cache("slack:list_emojis", Duration::from_secs(24 * 60 * 60), false).await }"##,
+    create = r##" { build_io_cache("slack:list_emojis", Duration::from_secs(24 * 60 * 60), false).await }"##,
     with_cached_flag = true
 )]
 async fn cached_list_emojis(
@@ -1267,9 +1267,9 @@ async fn cached_list_emojis(
 #[io_cached(
     key = "String",
     convert = r#"{ format!("{}__{}__{}", slack_base_url, channel, message) }"#,
-    ty = "cached::AsyncRedisCache<String, Url>",
+    ty = "IOCache<Url>",
     map_error = r##"|e| UniversalInboxError::Unexpected(anyhow!("Failed to cache Slack `get_chat_permalink`: {:?}", e))"##,
-    create = r##" { build_redis_cache("slack:get_chat_permalink", Duration::from_secs(7 * 24 * 60 * 60), true).await }"##,
+    create = r##" { build_io_cache("slack:get_chat_permalink", Duration::from_secs(7 * 24 * 60 * 60), true).await }"##,
     with_cached_flag = true
 )]
 async fn cached_get_chat_permalink(
@@ -1,14 +1,23 @@
-use anyhow::Context;
+use std::{sync::Arc, time::Duration};
+
 use apalis::prelude::*;
 use apalis_cron::CronContext;
-use apalis_redis::RedisStorage;
 use chrono::{DateTime, Utc};
-use redis::{AsyncCommands, ExistenceCheck, SetExpiry, SetOptions};
+use tokio::sync::RwLock;
 use tracing::info;
 
 use crate::{
-    configuration::RefreshOAuthTokensCronSettings, jobs::UniversalInboxJob,
-    universal_inbox::UniversalInboxError, utils::cache::Cache,
+    configuration::{
+        RefreshOAuthTokensCronSettings, RetentionPolicyCronSettings, SyncCronSettings,
+    },
+    jobs::{UniversalInboxJob, storage::JobStorage},
+    universal_inbox::{
+        UniversalInboxError,
+        integration_connection::service::{
+            IntegrationConnectionService, IntegrationConnectionSyncType,
+        },
+    },
+    utils::cache::Cache,
 };
 
 /// Cron tick request for the `refresh-oauth-tokens` job. Carries no data; the
@@ -17,8 +26,8 @@ use crate::{
 pub struct RefreshOAuthTokensCronTick;
 
 /// Handles a cron tick by electing a single winner across all worker processes
-/// (per-tick Redis lock) and enqueuing a durable `RefreshOAuthTokens` job on
-/// the shared Redis-backed queue, executed once by the regular worker pool.
+/// (per-tick cache lock) and enqueuing a durable `RefreshOAuthTokens` job on
+/// the shared job queue, executed once by the regular worker pool.
 #[tracing::instrument(
     name = "refresh-oauth-tokens-cron-tick",
     level = "info",
@@ -29,7 +38,7 @@ pub struct RefreshOAuthTokensCronTick;
 pub async fn handle_refresh_oauth_tokens_cron_tick(
     _tick: RefreshOAuthTokensCronTick,
     ctx: CronContext<Utc>,
-    storage: Data<RedisStorage<UniversalInboxJob>>,
+    storage: Data<JobStorage>,
     cache: Data<Cache>,
     settings: Data<RefreshOAuthTokensCronSettings>,
 ) -> Result<(), UniversalInboxError> {
@@ -45,19 +54,154 @@ pub async fn handle_refresh_oauth_tokens_cron_tick(
         return Ok(());
     }
 
-    let mut storage = (*storage).clone();
     storage
         .push(UniversalInboxJob::RefreshOAuthTokens {
             minutes_before_expiry: settings.minutes_before_expiry,
         })
-        .await
-        .context("Failed to enqueue RefreshOAuthTokens job")?;
+        .await?;
     info!("Enqueued RefreshOAuthTokens job");
     Ok(())
 }
 
-/// Acquires a distributed lock for the given cron job and tick using Redis
-/// `SET NX EX`. The key is derived from the scheduled tick timestamp, which is
+/// Cron tick request for the `apply-retention-policy` job
+#[derive(Debug, Clone, Default)]
+pub struct ApplyRetentionPolicyCronTick;
+
+/// Handles an `apply-retention-policy` cron tick: the process winning the
+/// per-tick cache lock enqueues a durable `ApplyRetentionPolicy` job.
+#[tracing::instrument(
+    name = "apply-retention-policy-cron-tick",
+    level = "info",
+    skip_all,
+    fields(cron.tick = %ctx.get_timestamp()),
+    err
+)]
+pub async fn handle_apply_retention_policy_cron_tick(
+    _tick: ApplyRetentionPolicyCronTick,
+    ctx: CronContext<Utc>,
+    storage: Data<JobStorage>,
+    cache: Data<Cache>,
+    settings: Data<RetentionPolicyCronSettings>,
+) -> Result<(), UniversalInboxError> {
+    if !try_acquire_cron_tick_lock(
+        &cache,
+        "apply-retention-policy",
+        ctx.get_timestamp(),
+        settings.lock_ttl_seconds,
+    )
+    .await?
+    {
+        info!("Tick already handled by another worker process, skipping");
+        return Ok(());
+    }
+
+    storage
+        .push(UniversalInboxJob::ApplyRetentionPolicy {
+            policy: (&*settings).into(),
+        })
+        .await?;
+    info!("Enqueued ApplyRetentionPolicy job");
+    Ok(())
+}
+
+/// Cron tick request for the `sync-notifications` job
+#[derive(Debug, Clone, Default)]
+pub struct SyncNotificationsCronTick;
+
+/// Cron tick request for the `sync-tasks` job
+#[derive(Debug, Clone, Default)]
+pub struct SyncTasksCronTick;
+
+/// Handles a `sync-notifications` cron tick: the process winning the per-tick
+/// cache lock enqueues one `SyncNotifications` job per user and integration
+/// connection due for a sync.
+#[tracing::instrument(
+    name = "sync-notifications-cron-tick",
+    level = "info",
+    skip_all,
+    fields(cron.tick = %ctx.get_timestamp()),
+    err
+)]
+pub async fn handle_sync_notifications_cron_tick(
+    _tick: SyncNotificationsCronTick,
+    ctx: CronContext<Utc>,
+    storage: Data<JobStorage>,
+    cache: Data<Cache>,
+    settings: Data<SyncCronSettings>,
+    integration_connection_service: Data<Arc<RwLock<IntegrationConnectionService>>>,
+) -> Result<(), UniversalInboxError> {
+    fan_out_sync_jobs(
+        "sync-notifications",
+        IntegrationConnectionSyncType::Notifications,
+        ctx.get_timestamp(),
+        &storage,
+        &cache,
+        &settings,
+        &integration_connection_service,
+    )
+    .await
+}
+
+/// Handles a `sync-tasks` cron tick: the process winning the per-tick cache
+/// lock enqueues one `SyncTasks` job per user and integration connection due
+/// for a sync.
+#[tracing::instrument(
+    name = "sync-tasks-cron-tick",
+    level = "info",
+    skip_all,
+    fields(cron.tick = %ctx.get_timestamp()),
+    err
+)]
+pub async fn handle_sync_tasks_cron_tick(
+    _tick: SyncTasksCronTick,
+    ctx: CronContext<Utc>,
+    storage: Data<JobStorage>,
+    cache: Data<Cache>,
+    settings: Data<SyncCronSettings>,
+    integration_connection_service: Data<Arc<RwLock<IntegrationConnectionService>>>,
+) -> Result<(), UniversalInboxError> {
+    fan_out_sync_jobs(
+        "sync-tasks",
+        IntegrationConnectionSyncType::Tasks,
+        ctx.get_timestamp(),
+        &storage,
+        &cache,
+        &settings,
+        &integration_connection_service,
+    )
+    .await
+}
+
+async fn fan_out_sync_jobs(
+    job_name: &str,
+    sync_type: IntegrationConnectionSyncType,
+    tick: &DateTime<Utc>,
+    storage: &JobStorage,
+    cache: &Cache,
+    settings: &SyncCronSettings,
+    integration_connection_service: &RwLock<IntegrationConnectionService>,
+) -> Result<(), UniversalInboxError> {
+    if !try_acquire_cron_tick_lock(cache, job_name, tick, settings.lock_ttl_seconds).await? {
+        info!("Tick already handled by another worker process, skipping");
+        return Ok(());
+    }
+
+    let enqueued_jobs_count = integration_connection_service
+        .read()
+        .await
+        .trigger_scheduled_syncs_for_all_users(
+            sync_type,
+            settings.active_user_window_in_minutes,
+            settings.inactive_user_min_sync_interval_in_minutes,
+            storage,
+        )
+        .await?;
+    info!("Enqueued {enqueued_jobs_count} Sync{sync_type} jobs");
+    Ok(())
+}
+
+/// Acquires a distributed lock for the given cron job and tick using
+/// [`Cache::set_nx_ex`]. The key is derived from the scheduled tick timestamp, which is
 /// identical across processes, so exactly one process wins per tick. The TTL
 /// only bounds the key's lifetime; deduplication correctness comes from the
 /// per-tick key.
@@ -67,14 +211,8 @@ pub async fn try_acquire_cron_tick_lock(
     tick: &DateTime<Utc>,
     lock_ttl_seconds: u64,
 ) -> Result<bool, UniversalInboxError> {
-    let mut connection = cache.connection_manager.clone();
     let key = format!("universal-inbox:cron:{job_name}:{}", tick.timestamp());
-    let options = SetOptions::default()
-        .conditional_set(ExistenceCheck::NX)
-        .with_expiration(SetExpiry::EX(lock_ttl_seconds));
-    let acquired: Option<String> = connection
-        .set_options(&key, "locked", options)
+    cache
+        .set_nx_ex(&key, "locked", Duration::from_secs(lock_ttl_seconds))
         .await
-        .context(format!("Failed to acquire cron lock for {job_name}"))?;
-    Ok(acquired.is_some())
 }
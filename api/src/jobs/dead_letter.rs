@@ -0,0 +1,242 @@
+//! Retry policies of the asynchronous jobs and the dead-letter store keeping
+//! the jobs which failed on their last attempt.
+
+use std::{fmt, str::FromStr, time::Duration};
+
+use anyhow::Context;
+use chrono::{DateTime, SubsecRound, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use universal_inbox::user::UserId;
+
+use crate::{
+    jobs::UniversalInboxJob,
+    universal_inbox::{UniversalInboxError, integration_connection::sync_run::error_chain},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JobRetryPolicy {
+    /// Number of times a job is run before being dead-lettered
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for each following retry
+    pub base_delay: Duration,
+}
+
+impl JobRetryPolicy {
+    pub const fn new(max_attempts: u32, base_delay: Duration) -> Self {
+        Self {
+            max_attempts,
+            base_delay,
+        }
+    }
+
+    pub const fn no_retry() -> Self {
+        Self::new(1, Duration::ZERO)
+    }
+
+    /// Delay before running a job again after its `attempt` (the first one
+    /// being 1) failed, `None` if it was its last attempt
+    pub fn retry_delay(&self, attempt: u32) -> Option<Duration> {
+        (attempt < self.max_attempts).then(|| self.base_delay * 2u32.pow(attempt.saturating_sub(1)))
+    }
+
+    /// Only errors which may not happen again are worth retrying. Others, such
+    /// as a missing item or an upstream rate limit, would fail the same way.
+    pub fn is_retryable(error: &UniversalInboxError) -> bool {
+        matches!(
+            error,
+            UniversalInboxError::Unexpected(_)
+                | UniversalInboxError::Recoverable(_)
+                | UniversalInboxError::DatabaseError { .. }
+        )
+    }
+}
+
+/// A job which failed on its last attempt
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct DeadLetterJob {
+    pub id: DeadLetterJobId,
+    /// Identifier of the job in the queue
+    pub job_id: String,
+    pub name: String,
+    pub user_id: Option<UserId>,
+    /// Serialized `UniversalInboxJob`, used to push the job back into the queue
+    pub payload: Value,
+    /// Error of the last attempt and its causes, outermost first
+    pub error_chain: Vec<String>,
+    pub attempts: u32,
+    pub failed_at: DateTime<Utc>,
+}
+
+impl DeadLetterJob {
+    pub fn new(
+        job: &UniversalInboxJob,
+        job_id: String,
+        error: &UniversalInboxError,
+        attempts: u32,
+    ) -> Result<Self, UniversalInboxError> {
+        Ok(Self {
+            id: Uuid::new_v4().into(),
+            job_id,
+            name: job.name().to_string(),
+            user_id: job.user_id(),
+            payload: serde_json::to_value(job)
+                .with_context(|| format!("Failed to serialize {} job", job.name()))?,
+            error_chain: error_chain(error),
+            attempts,
+            // Truncated to the precision of PostgreSQL timestamps
+            failed_at: Utc::now().trunc_subsecs(6),
+        })
+    }
+
+    pub fn job(&self) -> Result<UniversalInboxJob, UniversalInboxError> {
+        Ok(serde_json::from_value(self.payload.clone())
+            .with_context(|| format!("Failed to deserialize dead-lettered job {}", self.id))?)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Copy, Clone, Eq, Hash)]
+#[serde(transparent)]
+pub struct DeadLetterJobId(pub Uuid);
+
+impl fmt::Display for DeadLetterJobId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<Uuid> for DeadLetterJobId {
+    fn from(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+}
+
+impl From<DeadLetterJobId> for Uuid {
+    fn from(dead_letter_job_id: DeadLetterJobId) -> Self {
+        dead_letter_job_id.0
+    }
+}
+
+impl FromStr for DeadLetterJobId {
+    type Err = uuid::Error;
+
+    fn from_str(uuid: &str) -> Result<Self, Self::Err> {
+        Ok(Self(Uuid::parse_str(uuid)?))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeadLetterJobFilter {
+    pub id: Option<DeadLetterJobId>,
+    pub name: Option<String>,
+    pub user_id: Option<UserId>,
+    pub failed_before: Option<DateTime<Utc>>,
+    pub failed_after: Option<DateTime<Utc>>,
+    /// Maximum number of jobs to consider, the oldest failures being
+    /// deleted first and the latest ones being fetched first
+    pub limit: Option<i64>,
+}
+
+impl DeadLetterJobFilter {
+    /// Whether the filter matches all dead-lettered jobs, whatever its limit
+    pub fn is_empty(&self) -> bool {
+        Self {
+            limit: None,
+            ..self.clone()
+        } == Self::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use anyhow::anyhow;
+    use pretty_assertions::assert_eq;
+
+    use universal_inbox::integration_connection::provider::IntegrationProviderKind;
+
+    #[test]
+    fn test_retry_delay() {
+        let retry_policy = JobRetryPolicy::new(4, Duration::from_secs(2));
+        assert_eq!(
+            (1..=4)
+                .map(|attempt| retry_policy.retry_delay(attempt))
+                .collect::<Vec<_>>(),
+            vec![
+                Some(Duration::from_secs(2)),
+                Some(Duration::from_secs(4)),
+                Some(Duration::from_secs(8)),
+                None
+            ]
+        );
+        assert_eq!(JobRetryPolicy::no_retry().retry_delay(1), None);
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(JobRetryPolicy::is_retryable(
+            &UniversalInboxError::Unexpected(anyhow!("Connection reset"))
+        ));
+        assert!(!JobRetryPolicy::is_retryable(
+            &UniversalInboxError::ItemNotFound("Notification not found".to_string())
+        ));
+    }
+
+    #[test]
+    fn test_is_dead_letterable() {
+        let sync_job =
+            UniversalInboxJob::SyncNotifications(crate::jobs::sync::SyncNotificationsJob {
+                source: None,
+                user_id: None,
+            });
+        let side_effects_job = UniversalInboxJob::ProcessNotificationSideEffects {
+            notification_id: Uuid::new_v4().into(),
+            patch: Default::default(),
+            user_id: Uuid::new_v4().into(),
+        };
+        let rate_limited = UniversalInboxError::UpstreamRateLimited {
+            provider_kind: IntegrationProviderKind::Github,
+            until: Utc::now(),
+        };
+        let recoverable = UniversalInboxError::Recoverable(anyhow!("Github API is down"));
+        let unexpected = UniversalInboxError::Unexpected(anyhow!("Connection reset"));
+
+        assert!(!sync_job.is_dead_letterable(&rate_limited));
+        assert!(!sync_job.is_dead_letterable(&recoverable));
+        assert!(sync_job.is_dead_letterable(&unexpected));
+        assert!(!side_effects_job.is_dead_letterable(&rate_limited));
+        assert!(side_effects_job.is_dead_letterable(&recoverable));
+        assert!(
+            !UniversalInboxJob::Retry {
+                job: Box::new(sync_job),
+                attempt: 2
+            }
+            .is_dead_letterable(&recoverable)
+        );
+    }
+
+    #[test]
+    fn test_dead_letter_job_payload_roundtrip() {
+        let user_id: UserId = Uuid::new_v4().into();
+        let job = UniversalInboxJob::SyncNotifications(crate::jobs::sync::SyncNotificationsJob {
+            source: None,
+            user_id: Some(user_id),
+        });
+
+        let dead_letter_job = DeadLetterJob::new(
+            &job,
+            "job-id".to_string(),
+            &UniversalInboxError::Unexpected(anyhow!("Connection reset")),
+            1,
+        )
+        .unwrap();
+
+        assert_eq!(dead_letter_job.name, "SyncNotifications");
+        assert_eq!(dead_letter_job.user_id, Some(user_id));
+        assert_eq!(dead_letter_job.error_chain, vec!["Connection reset"]);
+        assert_eq!(dead_letter_job.job().unwrap().user_id(), Some(user_id));
+    }
+}
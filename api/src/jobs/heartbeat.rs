@@ -0,0 +1,58 @@
+//! Heartbeat of the asynchronous workers, checked by the readiness probe.
+//!
+//! The heartbeat key is derived from the namespace of the job storage, so
+//! that it reports workers consuming this very queue.
+
+use std::time::Duration;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use tracing::warn;
+
+use crate::{jobs::storage::JobStorage, universal_inbox::UniversalInboxError, utils::cache::Cache};
+
+const WORKER_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+/// Workers are considered down when they did not send a heartbeat for this long
+pub const WORKER_HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub fn worker_heartbeat_key(storage: &JobStorage) -> String {
+    format!("{}:worker-heartbeat", storage.namespace())
+}
+
+pub async fn send_worker_heartbeat(
+    storage: &JobStorage,
+    cache: &Cache,
+) -> Result<(), UniversalInboxError> {
+    cache
+        .set_ex(
+            &worker_heartbeat_key(storage),
+            &Utc::now().timestamp().to_string(),
+            WORKER_HEARTBEAT_TIMEOUT,
+        )
+        .await
+}
+
+/// Time of the last heartbeat, if one was sent during the last
+/// `WORKER_HEARTBEAT_TIMEOUT`
+pub async fn fetch_last_worker_heartbeat(
+    storage: &JobStorage,
+    cache: &Cache,
+) -> Result<Option<DateTime<Utc>>, UniversalInboxError> {
+    let timestamp = cache.get(&worker_heartbeat_key(storage)).await?;
+    Ok(timestamp
+        .and_then(|timestamp| timestamp.parse::<i64>().ok())
+        .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+        .filter(|sent_at| {
+            Utc::now() - *sent_at
+                <= TimeDelta::from_std(WORKER_HEARTBEAT_TIMEOUT).unwrap_or(TimeDelta::MAX)
+        }))
+}
+
+pub async fn send_worker_heartbeats(storage: JobStorage, cache: Cache) {
+    let mut interval = tokio::time::interval(WORKER_HEARTBEAT_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(err) = send_worker_heartbeat(&storage, &cache).await {
+            warn!("{err:?}");
+        }
+    }
+}
@@ -1,10 +1,14 @@
-use std::sync::Arc;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use apalis::prelude::*;
 use opentelemetry::trace::Status;
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
-use tracing::{error, info};
+use tokio_retry::strategy::jitter;
+use tracing::{error, info, warn};
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 use universal_inbox::{
@@ -14,20 +18,30 @@ use universal_inbox::{
 
 use crate::{
     integrations::slack::SlackService,
+    jobs::{dead_letter::JobRetryPolicy, storage::JobStorage},
+    mcp::subscriptions::publish_resource_updates_after,
+    metrics,
     universal_inbox::{
-        UniversalInboxError, integration_connection::service::IntegrationConnectionService,
-        notification::service::NotificationService, task::service::TaskService,
-        third_party::service::ThirdPartyItemService,
+        UniversalInboxError,
+        integration_connection::service::IntegrationConnectionService,
+        job::service::JobService,
+        notification::service::NotificationService,
+        task::service::TaskService,
+        third_party::{retention::RetentionPolicy, service::ThirdPartyItemService},
     },
 };
 
 pub mod cron;
+pub mod dead_letter;
+pub mod heartbeat;
 pub mod oauth;
+pub mod retention;
 pub mod slack;
+pub mod storage;
 pub mod sync;
 
 #[allow(clippy::large_enum_variant)]
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum UniversalInboxJob {
     SyncNotifications(sync::SyncNotificationsJob),
     SyncTasks(sync::SyncTasksJob),
@@ -40,6 +54,15 @@ pub enum UniversalInboxJob {
     RefreshOAuthTokens {
         minutes_before_expiry: i64,
     },
+    ApplyRetentionPolicy {
+        policy: RetentionPolicy,
+    },
+    /// `job` scheduled to run again after a failure, `attempt` being the
+    /// number of this new attempt
+    Retry {
+        job: Box<UniversalInboxJob>,
+        attempt: u32,
+    },
 }
 
 impl UniversalInboxJob {
@@ -50,6 +73,74 @@ impl UniversalInboxJob {
             Self::SlackPushEventCallback(_) => "SlackPushEventCallback",
             Self::ProcessNotificationSideEffects { .. } => "ProcessNotificationSideEffects",
             Self::RefreshOAuthTokens { .. } => "RefreshOAuthTokens",
+            Self::ApplyRetentionPolicy { .. } => "ApplyRetentionPolicy",
+            Self::Retry { job, .. } => job.name(),
+        }
+    }
+
+    pub fn user_id(&self) -> Option<UserId> {
+        match self {
+            Self::SyncNotifications(job) => job.user_id,
+            Self::SyncTasks(job) => job.user_id,
+            Self::ProcessNotificationSideEffects { user_id, .. } => Some(*user_id),
+            Self::Retry { job, .. } => job.user_id(),
+            Self::SlackPushEventCallback(_)
+            | Self::RefreshOAuthTokens { .. }
+            | Self::ApplyRetentionPolicy { .. } => None,
+        }
+    }
+
+    /// Syncs are not retried as they are triggered again periodically and
+    /// already back off after consecutive failures
+    pub fn retry_policy(&self) -> JobRetryPolicy {
+        match self {
+            Self::SyncNotifications(_) | Self::SyncTasks(_) => JobRetryPolicy::no_retry(),
+            Self::SlackPushEventCallback(_) | Self::ProcessNotificationSideEffects { .. } => {
+                JobRetryPolicy::new(3, Duration::from_secs(2))
+            }
+            Self::RefreshOAuthTokens { .. } | Self::ApplyRetentionPolicy { .. } => {
+                JobRetryPolicy::new(2, Duration::from_secs(30))
+            }
+            Self::Retry { job, .. } => job.retry_policy(),
+        }
+    }
+
+    /// Whether a failure of the job is kept in the dead-letter store. Upstream
+    /// rate limits are deliberate deferrals, and syncs already record their
+    /// failures on the integration connection (status, sync run and backoff)
+    /// before being triggered again periodically.
+    pub fn is_dead_letterable(&self, error: &UniversalInboxError) -> bool {
+        match (self, error) {
+            (_, UniversalInboxError::UpstreamRateLimited { .. }) => false,
+            (
+                Self::SyncNotifications(_) | Self::SyncTasks(_),
+                UniversalInboxError::Recoverable(_),
+            ) => false,
+            (Self::Retry { job, .. }, error) => job.is_dead_letterable(error),
+            _ => true,
+        }
+    }
+
+    /// The job to run along with the number of this attempt
+    fn into_attempt(self) -> (Self, u32) {
+        match self {
+            Self::Retry { job, attempt } => (*job, attempt),
+            job => (job, 1),
+        }
+    }
+}
+
+const JOB_QUEUE_DEPTH_POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Periodically record the number of jobs waiting in the queue. The queue is
+/// shared by all kinds of jobs, hence its depth is not known per job name.
+pub async fn poll_job_queue_depth(storage: JobStorage) {
+    let mut interval = tokio::time::interval(JOB_QUEUE_DEPTH_POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        match storage.len().await {
+            Ok(depth) => metrics::record_job_queue_depth("UniversalInboxJob", depth),
+            Err(err) => warn!("Failed to fetch the depth of the job queue: {err:?}"),
         }
     }
 }
@@ -63,7 +154,10 @@ impl UniversalInboxJob {
         task_service,
         integration_connection_service,
         third_party_item_service,
-        slack_service
+        slack_service,
+        job_service,
+        sync_concurrency_limiter,
+        job_storage
     ),
     fields(
         job.id = %task_id.to_string(),
@@ -71,6 +165,7 @@ impl UniversalInboxJob {
     ),
     err
 )]
+#[allow(clippy::too_many_arguments)]
 pub async fn handle_universal_inbox_job(
     job: UniversalInboxJob,
     task_id: TaskId,
@@ -79,6 +174,9 @@ pub async fn handle_universal_inbox_job(
     integration_connection_service: Data<Arc<RwLock<IntegrationConnectionService>>>,
     third_party_item_service: Data<Arc<RwLock<ThirdPartyItemService>>>,
     slack_service: Data<Arc<SlackService>>,
+    job_service: Data<Arc<JobService>>,
+    sync_concurrency_limiter: Data<sync::SyncConcurrencyLimiter>,
+    job_storage: Data<JobStorage>,
 ) -> Result<(), UniversalInboxError> {
     let current_span = tracing::Span::current();
 
@@ -87,11 +185,131 @@ pub async fn handle_universal_inbox_job(
         "Processing {} job",
         job.name()
     );
-    let result = match job {
+    let (job, attempt) = job.into_attempt();
+    if attempt > 1 {
+        warn!(
+            job_id = task_id.to_string(),
+            "Retrying {} job (attempt {attempt})",
+            job.name()
+        );
+    }
+    let started_at = Instant::now();
+    let result = publish_resource_updates_after(run_universal_inbox_job(
+        job.clone(),
+        notification_service,
+        task_service,
+        integration_connection_service,
+        third_party_item_service,
+        slack_service,
+        job_service.clone(),
+        sync_concurrency_limiter,
+        job_storage.clone(),
+    ))
+    .await;
+    metrics::record_job(job.name(), started_at.elapsed(), result.is_ok());
+
+    let err = match result {
+        Ok(_) => {
+            current_span.set_status(Status::Ok);
+            info!(job_id = task_id.to_string(), "Successfully executed job");
+            return Ok(());
+        }
+        Err(err) => err,
+    };
+    current_span.set_status(Status::error(err.to_string()));
+
+    // Retried as a new job scheduled later rather than waiting here, which
+    // would hold the worker
+    if let Some(delay) = job
+        .retry_policy()
+        .retry_delay(attempt)
+        .filter(|_| JobRetryPolicy::is_retryable(&err))
+    {
+        let delay = jitter(delay);
+        warn!(
+            job_id = task_id.to_string(),
+            "Failed to execute job (attempt {attempt}), retrying in {}s: {err:?}",
+            delay.as_secs()
+        );
+        let retried_job = UniversalInboxJob::Retry {
+            job: Box::new(job.clone()),
+            attempt: attempt + 1,
+        };
+        match job_storage.schedule(retried_job, delay).await {
+            Ok(_) => return Ok(()),
+            Err(schedule_err) => error!(
+                job_id = task_id.to_string(),
+                "Failed to schedule the retry of the job: {schedule_err:?}"
+            ),
+        }
+    }
+
+    if !job.is_dead_letterable(&err) {
+        warn!(
+            job_id = task_id.to_string(),
+            "Failed to execute job, not dead-lettered as its failure is already recorded: {err:?}"
+        );
+        return Err(err);
+    }
+
+    error!(
+        job_id = task_id.to_string(),
+        "Failed to execute job after {attempt} attempt(s): {err:?}"
+    );
+    if let Err(dead_letter_err) =
+        dead_letter_job(&job_service, &job, task_id.to_string(), &err, attempt).await
+    {
+        error!(
+            job_id = task_id.to_string(),
+            "Failed to store job in the dead-letter store: {dead_letter_err:?}"
+        );
+    }
+    Err(err)
+}
+
+async fn dead_letter_job(
+    job_service: &JobService,
+    job: &UniversalInboxJob,
+    job_id: String,
+    error: &UniversalInboxError,
+    attempts: u32,
+) -> Result<(), UniversalInboxError> {
+    let mut transaction = job_service.begin().await?;
+    job_service
+        .dead_letter_job(&mut transaction, job, job_id, error, attempts)
+        .await?;
+    transaction
+        .commit()
+        .await
+        .map_err(|e| UniversalInboxError::from(anyhow::Error::from(e)))?;
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_universal_inbox_job(
+    job: UniversalInboxJob,
+    notification_service: Data<Arc<RwLock<NotificationService>>>,
+    task_service: Data<Arc<RwLock<TaskService>>>,
+    integration_connection_service: Data<Arc<RwLock<IntegrationConnectionService>>>,
+    third_party_item_service: Data<Arc<RwLock<ThirdPartyItemService>>>,
+    slack_service: Data<Arc<SlackService>>,
+    job_service: Data<Arc<JobService>>,
+    sync_concurrency_limiter: Data<sync::SyncConcurrencyLimiter>,
+    job_storage: Data<JobStorage>,
+) -> Result<(), UniversalInboxError> {
+    match job {
         UniversalInboxJob::SyncNotifications(job) => {
-            sync::handle_sync_notifications(job, notification_service).await
+            sync::handle_sync_notifications(
+                job,
+                notification_service,
+                sync_concurrency_limiter,
+                job_storage,
+            )
+            .await
+        }
+        UniversalInboxJob::SyncTasks(job) => {
+            sync::handle_sync_tasks(job, task_service, sync_concurrency_limiter, job_storage).await
         }
-        UniversalInboxJob::SyncTasks(job) => sync::handle_sync_tasks(job, task_service).await,
         UniversalInboxJob::SlackPushEventCallback(job) => {
             slack::handle_slack_push_event(
                 job,
@@ -126,22 +344,18 @@ pub async fn handle_universal_inbox_job(
             )
             .await
         }
-    };
-
-    match result {
-        Ok(_) => {
-            current_span.set_status(Status::Ok);
-            info!(job_id = task_id.to_string(), "Successfully executed job");
-            Ok(())
-        }
-        Err(err) => {
-            current_span.set_status(Status::error(err.to_string()));
-            error!(
-                job_id = task_id.to_string(),
-                "Failed to execute job: {err:?}"
-            );
-            Err(err)
-        }
+        UniversalInboxJob::ApplyRetentionPolicy { policy } => retention::apply_retention_policy(
+            (*third_party_item_service).clone(),
+            (*job_service).clone(),
+            policy,
+            false,
+        )
+        .await
+        .map(|_| ()),
+        // Unwrapped by `UniversalInboxJob::into_attempt` before being run
+        UniversalInboxJob::Retry { .. } => Err(UniversalInboxError::Unexpected(anyhow::anyhow!(
+            "A retried job must be run as the job it retries"
+        ))),
     }
 }
 
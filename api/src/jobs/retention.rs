@@ -0,0 +1,216 @@
+use std::sync::Arc;
+
+use anyhow::{Context, anyhow};
+use chrono::{DateTime, Utc};
+use tokio::sync::RwLock;
+use tracing::info;
+
+use universal_inbox::notification::NotificationStatus;
+
+use crate::{
+    jobs::dead_letter::DeadLetterJobFilter,
+    universal_inbox::{
+        UniversalInboxError,
+        job::service::JobService,
+        third_party::{
+            retention::{RetentionPolicy, RetentionStats},
+            service::ThirdPartyItemService,
+        },
+    },
+};
+
+#[tracing::instrument(
+    name = "apply-retention-policy",
+    level = "info",
+    skip(third_party_item_service, job_service),
+    err
+)]
+pub async fn apply_retention_policy(
+    third_party_item_service: Arc<RwLock<ThirdPartyItemService>>,
+    job_service: Arc<JobService>,
+    policy: RetentionPolicy,
+    dry_run: bool,
+) -> Result<RetentionStats, UniversalInboxError> {
+    if policy.batch_size <= 0 {
+        return Err(anyhow!("The retention policy batch size must be positive").into());
+    }
+
+    let service = third_party_item_service.read().await;
+    let now = Utc::now();
+    let mut stats = RetentionStats::default();
+
+    for notification_status in [
+        NotificationStatus::Deleted,
+        NotificationStatus::Unsubscribed,
+    ] {
+        let Some(status_updated_before) =
+            policy.notification_status_updated_before(notification_status, now)
+        else {
+            continue;
+        };
+        let purged_count = purge_notification_third_party_items(
+            &service,
+            notification_status,
+            status_updated_before,
+            policy.batch_size,
+            dry_run,
+        )
+        .await?;
+        stats.add_notification_items(notification_status, purged_count);
+    }
+
+    stats.orphan_items = purge_orphan_third_party_items(
+        &service,
+        policy.orphan_third_party_items_updated_before(now),
+        policy.batch_size,
+        dry_run,
+    )
+    .await?;
+
+    stats.dead_letter_jobs = purge_dead_letter_jobs(
+        &job_service,
+        policy.dead_letter_jobs_failed_before(now),
+        policy.batch_size,
+        dry_run,
+    )
+    .await?;
+
+    info!(
+        "Retention policy {}: {} items of deleted notifications, {} items of unsubscribed notifications, {} orphan items, {} dead-lettered jobs",
+        if dry_run { "dry run" } else { "applied" },
+        stats.deleted_notification_items,
+        stats.unsubscribed_notification_items,
+        stats.orphan_items,
+        stats.dead_letter_jobs
+    );
+    Ok(stats)
+}
+
+/// Each batch is committed on its own to keep the transactions short. On a
+/// dry run, the items are only counted.
+async fn purge_notification_third_party_items(
+    service: &ThirdPartyItemService,
+    notification_status: NotificationStatus,
+    status_updated_before: DateTime<Utc>,
+    batch_size: i64,
+    dry_run: bool,
+) -> Result<u64, UniversalInboxError> {
+    if dry_run {
+        let mut transaction = service.begin().await.context(format!(
+            "Failed to create new transaction while counting items of {notification_status} notifications to purge"
+        ))?;
+        return service
+            .count_purgeable_notification_third_party_items(
+                &mut transaction,
+                notification_status,
+                status_updated_before,
+            )
+            .await;
+    }
+
+    let mut purged_count = 0;
+    loop {
+        let mut transaction = service.begin().await.context(format!(
+            "Failed to create new transaction while purging items of {notification_status} notifications"
+        ))?;
+        let batch_purged_count = service
+            .purge_notification_third_party_items(
+                &mut transaction,
+                notification_status,
+                status_updated_before,
+                batch_size,
+            )
+            .await?;
+        transaction.commit().await.context(format!(
+            "Failed to commit transaction while purging items of {notification_status} notifications"
+        ))?;
+
+        purged_count += batch_purged_count;
+        if batch_purged_count < batch_size as u64 {
+            return Ok(purged_count);
+        }
+        info!("{purged_count} items of {notification_status} notifications purged so far");
+    }
+}
+
+async fn purge_orphan_third_party_items(
+    service: &ThirdPartyItemService,
+    updated_before: DateTime<Utc>,
+    batch_size: i64,
+    dry_run: bool,
+) -> Result<u64, UniversalInboxError> {
+    if dry_run {
+        let mut transaction = service.begin().await.context(
+            "Failed to create new transaction while counting orphan third party items to purge",
+        )?;
+        return service
+            .count_orphan_third_party_items(&mut transaction, updated_before)
+            .await;
+    }
+
+    let mut purged_count = 0;
+    loop {
+        let mut transaction = service
+            .begin()
+            .await
+            .context("Failed to create new transaction while purging orphan third party items")?;
+        let batch_purged_count = service
+            .purge_orphan_third_party_items(&mut transaction, updated_before, batch_size)
+            .await?;
+        transaction
+            .commit()
+            .await
+            .context("Failed to commit transaction while purging orphan third party items")?;
+
+        purged_count += batch_purged_count;
+        if batch_purged_count < batch_size as u64 {
+            return Ok(purged_count);
+        }
+        info!("{purged_count} orphan third party items purged so far");
+    }
+}
+
+async fn purge_dead_letter_jobs(
+    service: &JobService,
+    failed_before: DateTime<Utc>,
+    batch_size: i64,
+    dry_run: bool,
+) -> Result<u64, UniversalInboxError> {
+    let filter = DeadLetterJobFilter {
+        failed_before: Some(failed_before),
+        ..Default::default()
+    };
+    if dry_run {
+        let mut transaction = service.begin().await.context(
+            "Failed to create new transaction while counting dead-lettered jobs to purge",
+        )?;
+        return service
+            .count_dead_letter_jobs(&mut transaction, &filter)
+            .await;
+    }
+
+    let filter = DeadLetterJobFilter {
+        limit: Some(batch_size),
+        ..filter
+    };
+    let mut purged_count = 0;
+    loop {
+        let mut transaction = service
+            .begin()
+            .await
+            .context("Failed to create new transaction while purging dead-lettered jobs")?;
+        let batch_purged_count = service
+            .purge_dead_letter_jobs(&mut transaction, &filter)
+            .await?;
+        transaction
+            .commit()
+            .await
+            .context("Failed to commit transaction while purging dead-lettered jobs")?;
+
+        purged_count += batch_purged_count;
+        if batch_purged_count < batch_size as u64 {
+            return Ok(purged_count);
+        }
+        info!("{purged_count} dead-lettered jobs purged so far");
+    }
+}
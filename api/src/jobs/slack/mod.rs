@@ -24,7 +24,7 @@ use crate::{
 pub mod slack_message;
 pub mod slack_reaction;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SlackPushEventCallbackJob(pub SlackPushEventCallback);
 
 pub fn fail_if_needed<T>(
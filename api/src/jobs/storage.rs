@@ -0,0 +1,184 @@
+//! Queue of the asynchronous jobs, stored in Redis or in PostgreSQL depending
+//! on the `storage.backend` setting.
+//!
+//! Handlers do not depend on the backend: `handle_universal_inbox_job` only
+//! extracts the job and its `TaskId`, which both backends provide.
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Context;
+use apalis::prelude::*;
+use apalis_redis::RedisStorage;
+use apalis_sql::postgres::PostgresStorage;
+use chrono::{TimeDelta, Utc};
+use sqlx::{Executor, PgPool};
+use tracing::info;
+
+use crate::{
+    configuration::{Settings, StorageBackend},
+    jobs::UniversalInboxJob,
+    universal_inbox::UniversalInboxError,
+};
+
+pub const JOB_STORAGE_NAMESPACE: &str = "universal-inbox:jobs:UniversalInboxJob";
+/// Schema holding the migration history of the apalis tables, kept apart from
+/// the application's own `_sqlx_migrations` table
+const APALIS_MIGRATIONS_SCHEMA: &str = "apalis_migrations";
+
+#[derive(Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum JobStorageBackend {
+    Redis(RedisStorage<UniversalInboxJob>),
+    Postgres(PostgresStorage<UniversalInboxJob>),
+}
+
+#[derive(Clone)]
+pub struct JobStorage {
+    namespace: String,
+    backend: JobStorageBackend,
+}
+
+impl JobStorage {
+    pub async fn connect(
+        settings: &Settings,
+        pool: Arc<PgPool>,
+        namespace: &str,
+    ) -> Result<Self, UniversalInboxError> {
+        let backend = match settings.storage.backend {
+            StorageBackend::Redis => {
+                info!(
+                    "Connecting to Redis server for job queuing on {} with namespace {namespace}",
+                    &settings.redis.safe_connection_string()
+                );
+                let connection = apalis_redis::connect(settings.redis.connection_string())
+                    .await
+                    .context("Failed to connect to Redis for job queuing")?;
+                JobStorageBackend::Redis(RedisStorage::new_with_config(
+                    connection,
+                    apalis_redis::Config::default().set_namespace(namespace),
+                ))
+            }
+            StorageBackend::Postgres => {
+                info!("Using PostgreSQL for job queuing with namespace {namespace}");
+                migrate_postgres_job_storage(&pool).await?;
+                JobStorageBackend::Postgres(PostgresStorage::new_with_config(
+                    (*pool).clone(),
+                    apalis_sql::Config::new(namespace),
+                ))
+            }
+        };
+
+        Ok(Self {
+            namespace: namespace.to_string(),
+            backend,
+        })
+    }
+
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    pub fn backend(&self) -> &JobStorageBackend {
+        &self.backend
+    }
+
+    pub async fn push(&self, job: UniversalInboxJob) -> Result<TaskId, UniversalInboxError> {
+        let job_name = job.name();
+        let task_id = match &self.backend {
+            JobStorageBackend::Redis(storage) => {
+                storage
+                    .clone()
+                    .push(job)
+                    .await
+                    .with_context(|| format!("Failed to push {job_name} job to Redis"))?
+                    .task_id
+            }
+            JobStorageBackend::Postgres(storage) => {
+                storage
+                    .clone()
+                    .push(job)
+                    .await
+                    .with_context(|| format!("Failed to push {job_name} job to PostgreSQL"))?
+                    .task_id
+            }
+        };
+        Ok(task_id)
+    }
+
+    /// Push a job to be run once the given delay has elapsed
+    pub async fn schedule(
+        &self,
+        job: UniversalInboxJob,
+        delay: Duration,
+    ) -> Result<TaskId, UniversalInboxError> {
+        let job_name = job.name();
+        let run_at = (Utc::now()
+            + TimeDelta::from_std(delay)
+                .with_context(|| format!("Invalid delay to schedule {job_name} job"))?)
+        .timestamp();
+        let task_id = match &self.backend {
+            JobStorageBackend::Redis(storage) => {
+                storage
+                    .clone()
+                    .schedule(job, run_at)
+                    .await
+                    .with_context(|| format!("Failed to schedule {job_name} job in Redis"))?
+                    .task_id
+            }
+            JobStorageBackend::Postgres(storage) => {
+                storage
+                    .clone()
+                    .schedule(job, run_at)
+                    .await
+                    .with_context(|| format!("Failed to schedule {job_name} job in PostgreSQL"))?
+                    .task_id
+            }
+        };
+        Ok(task_id)
+    }
+
+    /// Number of jobs waiting in the queue
+    pub async fn len(&self) -> Result<i64, UniversalInboxError> {
+        let len = match &self.backend {
+            JobStorageBackend::Redis(storage) => storage
+                .clone()
+                .len()
+                .await
+                .context("Failed to fetch the length of the Redis job queue")?,
+            JobStorageBackend::Postgres(storage) => storage
+                .clone()
+                .len()
+                .await
+                .context("Failed to fetch the length of the PostgreSQL job queue")?,
+        };
+        Ok(len)
+    }
+
+    pub async fn is_empty(&self) -> Result<bool, UniversalInboxError> {
+        Ok(self.len().await? == 0)
+    }
+}
+
+/// The apalis tables live in their own `apalis` schema. Their migrations are
+/// run with a dedicated `search_path` so that their history does not end up in
+/// the application's `_sqlx_migrations` table, which would make the
+/// application migrations fail on unknown versions.
+async fn migrate_postgres_job_storage(pool: &PgPool) -> Result<(), UniversalInboxError> {
+    // Detached from the pool so that the modified `search_path` cannot leak
+    let mut connection = pool
+        .acquire()
+        .await
+        .context("Failed to connect to PostgreSQL to migrate the job storage")?
+        .detach();
+    connection
+        .execute(&*format!(
+            "CREATE SCHEMA IF NOT EXISTS {APALIS_MIGRATIONS_SCHEMA}; SET search_path TO {APALIS_MIGRATIONS_SCHEMA}"
+        ))
+        .await
+        .context("Failed to prepare the job storage migrations schema")?;
+    PostgresStorage::<()>::migrations()
+        .run(&mut connection)
+        .await
+        .context("Failed to migrate the PostgreSQL job storage")?;
+    Ok(())
+}
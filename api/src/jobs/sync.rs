@@ -1,19 +1,128 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use apalis::prelude::Data;
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
+use tokio_retry::strategy::jitter;
 
+use tracing::{debug, info, warn};
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 use universal_inbox::{
+    integration_connection::provider::IntegrationProviderKind,
     notification::NotificationSyncSourceKind, task::TaskSyncSourceKind, user::UserId,
 };
+use uuid::Uuid;
 
-use crate::universal_inbox::{
-    UniversalInboxError, notification::service::NotificationService, task::service::TaskService,
+use crate::{
+    configuration::IntegrationSettings,
+    jobs::{UniversalInboxJob, storage::JobStorage},
+    universal_inbox::{
+        UniversalInboxError, notification::service::NotificationService, task::service::TaskService,
+    },
+    utils::cache::Cache,
 };
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Delay before a sync job is run again when all the sync slots of its
+/// provider are taken
+const SYNC_SLOT_WAIT_DELAY: Duration = Duration::from_secs(10);
+/// Bounds how long the slot of a worker which crashed while syncing stays taken
+const SYNC_SLOT_TTL: Duration = Duration::from_secs(30 * 60);
+const SYNC_SLOTS_NAMESPACE: &str = "universal-inbox:sync-slots:";
+
+/// Bounds, per integration provider, the number of sync jobs running at the
+/// same time across all the worker processes so that a fan-out of sync jobs
+/// does not exceed the provider's rate limits.
+///
+/// Slots are keys of the shared [`Cache`], taken with [`Cache::set_nx_ex`] the
+/// same way cron ticks are locked.
+#[derive(Clone)]
+pub struct SyncConcurrencyLimiter {
+    cache: Cache,
+    max_concurrent_syncs: HashMap<IntegrationProviderKind, usize>,
+}
+
+#[derive(Debug)]
+pub enum SyncSlot {
+    /// The concurrency of the provider's syncs is not limited
+    Unlimited,
+    /// To be released once the sync is done
+    Acquired { key: String, token: String },
+    /// All the sync slots of the provider are taken
+    Unavailable,
+}
+
+impl SyncConcurrencyLimiter {
+    pub fn new(cache: Cache, integrations: &HashMap<String, IntegrationSettings>) -> Self {
+        Self {
+            cache,
+            max_concurrent_syncs: integrations
+                .values()
+                .filter_map(|settings| {
+                    settings
+                        .max_concurrent_syncs
+                        .map(|max| (settings.kind, max.max(1)))
+                })
+                .collect(),
+        }
+    }
+
+    /// Take a sync slot of the provider without waiting for one to be released
+    pub async fn try_acquire(
+        &self,
+        provider_kind: IntegrationProviderKind,
+    ) -> Result<SyncSlot, UniversalInboxError> {
+        let Some(max_concurrent_syncs) = self.max_concurrent_syncs.get(&provider_kind) else {
+            return Ok(SyncSlot::Unlimited);
+        };
+        let token = Uuid::new_v4().to_string();
+        for index in 0..*max_concurrent_syncs {
+            let key = format!("{SYNC_SLOTS_NAMESPACE}{provider_kind}:{index}");
+            if self.cache.set_nx_ex(&key, &token, SYNC_SLOT_TTL).await? {
+                return Ok(SyncSlot::Acquired { key, token });
+            }
+        }
+        Ok(SyncSlot::Unavailable)
+    }
+
+    /// Release a sync slot, unless it expired and has been taken by another
+    /// sync meanwhile. A slot which cannot be released expires after
+    /// `SYNC_SLOT_TTL`.
+    pub async fn release(&self, slot: SyncSlot) {
+        let SyncSlot::Acquired { key, token } = slot else {
+            return;
+        };
+        if let Err(err) = self.cache.del_if_eq(&key, &token).await {
+            warn!("Failed to release sync slot `{key}`: {err:?}");
+        }
+    }
+}
+
+/// Waiting for a sync slot would hold a worker, hence the job is scheduled
+/// again later when all the slots of its provider are taken.
+/// Returns `None` when the job has been deferred.
+async fn acquire_sync_slot_or_defer(
+    sync_concurrency_limiter: &SyncConcurrencyLimiter,
+    job_storage: &JobStorage,
+    provider_kind: IntegrationProviderKind,
+    job: UniversalInboxJob,
+) -> Result<Option<SyncSlot>, UniversalInboxError> {
+    match sync_concurrency_limiter.try_acquire(provider_kind).await? {
+        SyncSlot::Unavailable => {
+            // Jittered so that deferred jobs do not all compete for the slots again at once
+            let delay = jitter(SYNC_SLOT_WAIT_DELAY);
+            debug!(
+                "Maximum number of concurrent {provider_kind} syncs reached, deferring {} job by {}s",
+                job.name(),
+                delay.as_secs()
+            );
+            job_storage.schedule(job, delay).await?;
+            Ok(None)
+        }
+        slot => Ok(Some(slot)),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncNotificationsJob {
     pub source: Option<NotificationSyncSourceKind>,
     pub user_id: Option<UserId>,
@@ -22,18 +131,46 @@ pub struct SyncNotificationsJob {
 pub async fn handle_sync_notifications(
     event: SyncNotificationsJob,
     notification_service: Data<Arc<RwLock<NotificationService>>>,
+    sync_concurrency_limiter: Data<SyncConcurrencyLimiter>,
+    job_storage: Data<JobStorage>,
 ) -> Result<(), UniversalInboxError> {
-    let service = notification_service.read().await;
     let current_span = tracing::Span::current();
     if let Some(user_id) = event.user_id {
         current_span.set_attribute("user.id", user_id.to_string());
         if let Some(source) = event.source {
             current_span.set_attribute("synced_source", source.to_string());
-            service
+            let Some(sync_slot) = acquire_sync_slot_or_defer(
+                &sync_concurrency_limiter,
+                &job_storage,
+                IntegrationProviderKind::from(source),
+                UniversalInboxJob::SyncNotifications(event.clone()),
+            )
+            .await?
+            else {
+                return Ok(());
+            };
+            let service = notification_service.read().await;
+            let result = service
                 .sync_notifications_with_transaction(source, user_id, false)
-                .await?;
+                .await;
+            sync_concurrency_limiter.release(sync_slot).await;
+            match result {
+                // The sync will be triggered again once the rate limit is over
+                Err(UniversalInboxError::UpstreamRateLimited {
+                    provider_kind,
+                    until,
+                }) => {
+                    info!(
+                        "{provider_kind} notifications sync for user {user_id} deferred until {until}"
+                    );
+                }
+                result => {
+                    result?;
+                }
+            }
         } else {
             current_span.set_attribute("sync_all_sources", true);
+            let service = notification_service.read().await;
             service.sync_all_notifications(user_id, false).await?;
         };
     } else {
@@ -43,6 +180,7 @@ pub async fn handle_sync_notifications(
         } else {
             current_span.set_attribute("sync_all_sources", true);
         };
+        let service = notification_service.read().await;
         service
             .sync_notifications_for_all_users(event.source, false)
             .await?;
@@ -51,7 +189,7 @@ pub async fn handle_sync_notifications(
     Ok(())
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncTasksJob {
     pub source: Option<TaskSyncSourceKind>,
     pub user_id: Option<UserId>,
@@ -60,18 +198,44 @@ pub struct SyncTasksJob {
 pub async fn handle_sync_tasks(
     event: SyncTasksJob,
     task_service: Data<Arc<RwLock<TaskService>>>,
+    sync_concurrency_limiter: Data<SyncConcurrencyLimiter>,
+    job_storage: Data<JobStorage>,
 ) -> Result<(), UniversalInboxError> {
-    let service = task_service.read().await;
     let current_span = tracing::Span::current();
     if let Some(user_id) = event.user_id {
         current_span.set_attribute("user.id", user_id.to_string());
         if let Some(source) = event.source {
             current_span.set_attribute("synced_source", source.to_string());
-            service
+            let Some(sync_slot) = acquire_sync_slot_or_defer(
+                &sync_concurrency_limiter,
+                &job_storage,
+                IntegrationProviderKind::from(source),
+                UniversalInboxJob::SyncTasks(event.clone()),
+            )
+            .await?
+            else {
+                return Ok(());
+            };
+            let service = task_service.read().await;
+            let result = service
                 .sync_tasks_with_transaction(source, user_id, false)
-                .await?;
+                .await;
+            sync_concurrency_limiter.release(sync_slot).await;
+            match result {
+                // The sync will be triggered again once the rate limit is over
+                Err(UniversalInboxError::UpstreamRateLimited {
+                    provider_kind,
+                    until,
+                }) => {
+                    info!("{provider_kind} tasks sync for user {user_id} deferred until {until}");
+                }
+                result => {
+                    result?;
+                }
+            }
         } else {
             current_span.set_attribute("sync_all_sources", true);
+            let service = task_service.read().await;
             service.sync_all_tasks(user_id, false).await?;
         };
     } else {
@@ -81,6 +245,7 @@ pub async fn handle_sync_tasks(
         } else {
             current_span.set_attribute("sync_all_sources", true);
         };
+        let service = task_service.read().await;
         service
             .sync_tasks_for_all_users(event.source, false)
             .await?;
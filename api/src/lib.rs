@@ -8,7 +8,7 @@ use std::{
     str::FromStr,
     sync::{Arc, OnceLock, Weak},
     thread,
-    time::Duration as StdDuration,
+    time::{Duration as StdDuration, Instant},
 };
 
 use crate::middlewares::jwt_auth::{
@@ -39,13 +39,11 @@ use apalis::{
     prelude::*,
 };
 use apalis_cron::{CronStream, Schedule};
-use apalis_redis::RedisStorage;
 use base64::{Engine, engine::general_purpose::STANDARD as BASE64_STANDARD};
 use chrono::Utc;
 use configuration::{AuthenticationSettings, CronSettings};
 use csp::{CSP, Directive, Source, Sources};
 use integrations::{api::APIService, google_calendar::GoogleCalendarService, slack::SlackService};
-use jobs::UniversalInboxJob;
 use jsonwebtoken::{Algorithm, Validation};
 use mailer::Mailer;
 use regex::Regex;
@@ -54,7 +52,7 @@ use sqlx::PgPool;
 use tokio::sync::RwLock;
 use tracing::{Level, Span, error, event, info, warn};
 use tracing_actix_web::TracingLogger;
-use utils::cache::Cache;
+use utils::cache::{Cache, delete_expired_cache_entries};
 use utils::login_throttle::LoginThrottle;
 use webauthn_rs::prelude::*;
 
@@ -72,19 +70,31 @@ use crate::{
         todoist::TodoistService,
         todoist_oauth::TodoistOAuth2Provider,
     },
-    jobs::{cron::handle_refresh_oauth_tokens_cron_tick, handle_universal_inbox_job},
+    jobs::{
+        cron::{
+            handle_apply_retention_policy_cron_tick, handle_refresh_oauth_tokens_cron_tick,
+            handle_sync_notifications_cron_tick, handle_sync_tasks_cron_tick,
+        },
+        handle_universal_inbox_job,
+        heartbeat::send_worker_heartbeats,
+        poll_job_queue_depth,
+        storage::{JobStorage, JobStorageBackend},
+        sync::SyncConcurrencyLimiter,
+    },
+    mcp::subscriptions::{ResourceUpdatePublisher, publish_resource_updates_after},
     observability::AuthenticatedRootSpanBuilder,
     repository::Repository,
     universal_inbox::{
         UniversalInboxError, auth_token::service::AuthenticationTokenService,
-        integration_connection::service::IntegrationConnectionService,
+        integration_connection::service::IntegrationConnectionService, job::service::JobService,
         notification::service::NotificationService, oauth2::service::OAuth2Service,
         slack_bridge::service::SlackBridgeService, task::service::TaskService,
         third_party::service::ThirdPartyItemService, user::service::UserService,
     },
     utils::{
-        crypto::TokenEncryptionKey,
+        crypto::TokenEncryptionKeyRing,
         jwt::{Claims, JWT_SESSION_KEY, JWTBase64EncodedSigningKeys, JWTSigningKeys},
+        rate_limit::ApiKeyRateLimiter,
     },
 };
 
@@ -96,6 +106,7 @@ pub mod integrations;
 pub mod jobs;
 pub mod mailer;
 pub mod mcp;
+pub mod metrics;
 pub mod middlewares;
 pub mod observability;
 pub mod repository;
@@ -106,7 +117,8 @@ pub mod utils;
 #[allow(clippy::too_many_arguments)]
 pub async fn run_server(
     listener: TcpListener,
-    redis_storage: RedisStorage<UniversalInboxJob>,
+    job_storage: JobStorage,
+    cache: Cache,
     settings: Settings,
     notification_service: Arc<RwLock<NotificationService>>,
     task_service: Arc<RwLock<TaskService>>,
@@ -202,21 +214,32 @@ pub async fn run_server(
         }
     };
 
-    let storage_data = web::Data::new(redis_storage.clone());
-    let cache = Cache::new(settings.redis.connection_string())
-        .await
-        .expect("Failed to create cache");
+    let storage_data = web::Data::new(job_storage.clone());
     let mcp_session_store: Arc<dyn rmcp::transport::streamable_http_server::session::SessionStore> =
-        Arc::new(mcp::RedisSessionStore::new(
-            cache.connection_manager.clone(),
+        Arc::new(mcp::CacheSessionStore::new(
+            cache.clone(),
             settings.application.mcp_session_store.ttl_seconds,
         ));
+    let mcp_subscriptions = mcp::subscriptions::McpSubscriptions::new(
+        cache.clone(),
+        settings.application.mcp_session_store.ttl_seconds,
+    );
+    tokio::spawn(mcp_subscriptions.clone().listen());
+    if let Some(session_revocations_listener) = auth_token_service
+        .read()
+        .await
+        .listen_to_session_revocations()
+    {
+        tokio::spawn(session_revocations_listener);
+    }
     let cache_data = web::Data::new(cache);
     let mcp_extra_allowed_origins = settings
         .application
         .security
         .mcp_extra_allowed_origins
         .clone();
+    let readiness_probe_data =
+        web::Data::new(routes::health_check::ReadinessProbe::new(&settings, false));
     let settings_web_data = web::Data::new(settings);
 
     info!("Listening on {}", listen_address);
@@ -226,8 +249,10 @@ pub async fn run_server(
     let mcp_http_service = mcp::build_http_service(
         notification_service.clone(),
         task_service.clone(),
-        redis_storage.clone(),
+        integration_connection_service.clone(),
+        job_storage.clone(),
         mcp_session_store,
+        mcp_subscriptions,
     );
     let oauth2_rate_limiter = routes::oauth2::build_rate_limiter();
     let mcp_rate_limiter = mcp::build_rate_limiter();
@@ -241,6 +266,8 @@ pub async fn run_server(
     // Same Arc-sharing reasoning as `ping_rate_limiter`
     // so workers can't be hopped to reset the budget.
     let auth_rate_limiter = routes::user::build_auth_rate_limiter();
+    // Per-key rate limiters for personal API keys, shared for the same reason.
+    let api_key_rate_limiter = Arc::new(ApiKeyRateLimiter::default());
 
     // OAuth2 access tokens carry `aud` = MCP resource URL. Only the MCP scope
     // accepts them; every other API endpoint runs behind this guard to ensure
@@ -260,6 +287,29 @@ pub async fn run_server(
             AuthenticateMiddlewareFactory::<Claims>::new(auth_middleware_settings.clone());
 
         let api_scope = web::scope(api_path.trim_end_matches('/'))
+            // Scoped tokens are checked against the route's required scopes;
+            // the MCP scope enforces them per tool instead.
+            .wrap(
+                middlewares::scope_guard::RequireScopes::new(&api_path)
+                    .with_exempt_prefixes(audience_guard_exempt_prefixes.clone()),
+            )
+            // Personal API keys get their stored restrictions applied to the
+            // claims before the scope guard runs.
+            .wrap(middlewares::api_key_guard::ApiKeyGuard::new(
+                auth_token_service.clone(),
+                api_key_rate_limiter.clone(),
+            ))
+            // Revoked sessions are rejected, signing out the remote client.
+            .wrap(middlewares::session_guard::RejectRevokedSessions::new(
+                auth_token_service.clone(),
+            ))
+            // Revoked OAuth2 access tokens are rejected before reaching the
+            // MCP scope, the only one accepting them.
+            .wrap(
+                middlewares::revoked_token_guard::RejectRevokedAccessTokens::new(
+                    oauth2_service.clone(),
+                ),
+            )
             .wrap(
                 middlewares::audience_guard::RejectAudiencedTokens::new()
                     .with_exempt_prefixes(audience_guard_exempt_prefixes.clone()),
@@ -345,7 +395,10 @@ pub async fn run_server(
         let csp_header_value = csp_header_value.clone();
         let api_version = api_version.clone();
         let mut app = App::new()
+            // Innermost, so that handlers have committed their changes
+            .wrap_fn(|req, srv| publish_resource_updates_after(srv.call(req)))
             .wrap_fn(move |req, srv| {
+                let started_at = Instant::now();
                 let fut = srv.call(req);
                 async move {
                     let res = fut.await?;
@@ -355,6 +408,14 @@ pub async fn run_server(
                         res.request().uri().path(),
                         res.status()
                     );
+                    metrics::record_http_request(
+                        res.request().method().as_str(),
+                        &res.request()
+                            .match_pattern()
+                            .unwrap_or_else(|| "unmatched".to_string()),
+                        res.status().as_u16(),
+                        started_at.elapsed(),
+                    );
                     Ok(res)
                 }
             })
@@ -432,6 +493,7 @@ pub async fn run_server(
                 }
             })
             .route("/ping", web::get().to(routes::health_check::ping))
+            .route("/ready", web::get().to(routes::health_check::ready))
             .route(
                 "/api/front_config",
                 web::get().to(routes::config::front_config),
@@ -456,6 +518,7 @@ pub async fn run_server(
             .app_data(web::Data::new(notification_service.clone()))
             .app_data(web::Data::new(task_service.clone()))
             .app_data(settings_web_data.clone())
+            .app_data(readiness_probe_data.clone())
             .app_data(storage_data.clone())
             .app_data(cache_data.clone())
             .app_data(web::Data::new(integration_connection_service.clone()))
@@ -486,16 +549,28 @@ pub async fn run_server(
     Ok(server.run())
 }
 
-/// Minimal HTTP server exposing only the `GET /ping` health-check endpoint.
+/// Minimal HTTP server exposing only the `GET /ping` and `GET /ready`
+/// health-check endpoints.
 ///
 /// Started alongside the apalis worker `Monitor` (see the `StartWorkers` command)
 /// so a worker process can be probed for liveness/readiness the same way as the API.
-/// Reuses the API's `routes::health_check::ping` handler (Redis + Postgres checks).
+/// Reuses the API's `routes::health_check` handlers.
 pub async fn run_ping_server(
     listener: TcpListener,
+    settings: Settings,
+    job_storage: JobStorage,
     cache: Cache,
     integration_connection_service: Arc<RwLock<IntegrationConnectionService>>,
 ) -> Result<Server, UniversalInboxError> {
+    let is_prometheus_endpoint_enabled = settings
+        .application
+        .observability
+        .metrics
+        .is_prometheus_endpoint_enabled;
+    let readiness_probe_data =
+        web::Data::new(routes::health_check::ReadinessProbe::new(&settings, true));
+    let settings_data = web::Data::new(settings);
+    let storage_data = web::Data::new(job_storage);
     let cache_data = web::Data::new(cache);
     let integration_connection_service_data = web::Data::new(integration_connection_service);
     // Built once outside the `HttpServer::new` closure so all Actix worker threads
@@ -503,12 +578,22 @@ pub async fn run_ping_server(
     let ping_rate_limiter = routes::health_check::build_rate_limiter();
 
     let server = HttpServer::new(move || {
-        App::new()
+        let app = App::new()
             .wrap(TracingLogger::default())
             .route("/ping", web::get().to(routes::health_check::ping))
+            .route("/ready", web::get().to(routes::health_check::ready))
+            .app_data(settings_data.clone())
+            .app_data(readiness_probe_data.clone())
+            .app_data(storage_data.clone())
             .app_data(cache_data.clone())
             .app_data(integration_connection_service_data.clone())
-            .app_data(web::Data::new(ping_rate_limiter.clone()))
+            .app_data(web::Data::new(ping_rate_limiter.clone()));
+
+        if is_prometheus_endpoint_enabled {
+            app.route("/metrics", web::get().to(routes::health_check::metrics))
+        } else {
+            app
+        }
     })
     .shutdown_timeout(10)
     .listen(listener)
@@ -534,7 +619,7 @@ impl<E: Display + Debug> OnFailure<E> for WorkerOnFailure {
 #[allow(clippy::too_many_arguments)]
 pub async fn run_worker(
     workers_count: Option<usize>,
-    redis_storage: RedisStorage<UniversalInboxJob>,
+    job_storage: JobStorage,
     cron_settings: CronSettings,
     cache: Cache,
     notification_service: Arc<RwLock<NotificationService>>,
@@ -542,6 +627,8 @@ pub async fn run_worker(
     integration_connection_service: Arc<RwLock<IntegrationConnectionService>>,
     third_party_item_service: Arc<RwLock<ThirdPartyItemService>>,
     slack_service: Arc<SlackService>,
+    job_service: Arc<JobService>,
+    sync_concurrency_limiter: SyncConcurrencyLimiter,
 ) -> Monitor {
     let count = workers_count.unwrap_or_else(|| {
         thread::available_parallelism()
@@ -549,23 +636,43 @@ pub async fn run_worker(
             .get()
     });
     info!("Starting {count} asynchronous Workers");
-    let mut monitor = Monitor::new().register(
-        WorkerBuilder::new("universal-inbox-worker")
-            .layer(
-                TraceLayer::new()
-                    .on_request(DefaultOnRequest::default().level(Level::INFO))
-                    .on_response(DefaultOnResponse::default().level(Level::INFO))
-                    .on_failure(WorkerOnFailure {}),
-            )
-            .concurrency(count)
-            .data(notification_service)
-            .data(task_service)
-            .data(integration_connection_service)
-            .data(third_party_item_service)
-            .data(slack_service)
-            .backend(redis_storage.clone())
-            .build_fn(handle_universal_inbox_job),
-    );
+    tokio::spawn(poll_job_queue_depth(job_storage.clone()));
+    tokio::spawn(send_worker_heartbeats(job_storage.clone(), cache.clone()));
+    tokio::spawn(delete_expired_cache_entries(cache.clone()));
+    // The service type of a `WorkerBuilder` depends on the context of the
+    // backend, so the builder is created for the configured backend only
+    macro_rules! worker_builder {
+        () => {
+            WorkerBuilder::new("universal-inbox-worker")
+                .layer(
+                    TraceLayer::new()
+                        .on_request(DefaultOnRequest::default().level(Level::INFO))
+                        .on_response(DefaultOnResponse::default().level(Level::INFO))
+                        .on_failure(WorkerOnFailure {}),
+                )
+                .concurrency(count)
+                .data(notification_service)
+                .data(task_service)
+                .data(integration_connection_service.clone())
+                .data(third_party_item_service)
+                .data(slack_service)
+                .data(job_service)
+                .data(sync_concurrency_limiter)
+                .data(job_storage.clone())
+        };
+    }
+    let mut monitor = match job_storage.backend() {
+        JobStorageBackend::Redis(storage) => Monitor::new().register(
+            worker_builder!()
+                .backend(storage.clone())
+                .build_fn(handle_universal_inbox_job),
+        ),
+        JobStorageBackend::Postgres(storage) => Monitor::new().register(
+            worker_builder!()
+                .backend(storage.clone())
+                .build_fn(handle_universal_inbox_job),
+        ),
+    };
 
     let refresh_oauth_tokens_settings = cron_settings.refresh_oauth_tokens;
     if refresh_oauth_tokens_settings.is_enabled {
@@ -583,14 +690,88 @@ pub async fn run_worker(
                         .on_response(DefaultOnResponse::default().level(Level::INFO))
                         .on_failure(WorkerOnFailure {}),
                 )
-                .data(redis_storage.clone())
-                .data(cache)
+                .data(job_storage.clone())
+                .data(cache.clone())
                 .data(refresh_oauth_tokens_settings)
                 .backend(CronStream::new_with_timezone(schedule, Utc))
                 .build_fn(handle_refresh_oauth_tokens_cron_tick),
         );
     }
 
+    let sync_notifications_settings = cron_settings.sync_notifications;
+    if sync_notifications_settings.is_enabled {
+        let schedule = Schedule::from_str(&sync_notifications_settings.schedule)
+            .expect("Invalid cron schedule for the sync-notifications job");
+        info!(
+            "Registering sync-notifications cron worker with schedule `{}`",
+            sync_notifications_settings.schedule
+        );
+        monitor = monitor.register(
+            WorkerBuilder::new("universal-inbox-cron-sync-notifications")
+                .layer(
+                    TraceLayer::new()
+                        .on_request(DefaultOnRequest::default().level(Level::INFO))
+                        .on_response(DefaultOnResponse::default().level(Level::INFO))
+                        .on_failure(WorkerOnFailure {}),
+                )
+                .data(job_storage.clone())
+                .data(cache.clone())
+                .data(sync_notifications_settings)
+                .data(integration_connection_service.clone())
+                .backend(CronStream::new_with_timezone(schedule, Utc))
+                .build_fn(handle_sync_notifications_cron_tick),
+        );
+    }
+
+    let sync_tasks_settings = cron_settings.sync_tasks;
+    if sync_tasks_settings.is_enabled {
+        let schedule = Schedule::from_str(&sync_tasks_settings.schedule)
+            .expect("Invalid cron schedule for the sync-tasks job");
+        info!(
+            "Registering sync-tasks cron worker with schedule `{}`",
+            sync_tasks_settings.schedule
+        );
+        monitor = monitor.register(
+            WorkerBuilder::new("universal-inbox-cron-sync-tasks")
+                .layer(
+                    TraceLayer::new()
+                        .on_request(DefaultOnRequest::default().level(Level::INFO))
+                        .on_response(DefaultOnResponse::default().level(Level::INFO))
+                        .on_failure(WorkerOnFailure {}),
+                )
+                .data(job_storage.clone())
+                .data(cache.clone())
+                .data(sync_tasks_settings)
+                .data(integration_connection_service)
+                .backend(CronStream::new_with_timezone(schedule, Utc))
+                .build_fn(handle_sync_tasks_cron_tick),
+        );
+    }
+
+    let apply_retention_policy_settings = cron_settings.apply_retention_policy;
+    if apply_retention_policy_settings.is_enabled {
+        let schedule = Schedule::from_str(&apply_retention_policy_settings.schedule)
+            .expect("Invalid cron schedule for the apply-retention-policy job");
+        info!(
+            "Registering apply-retention-policy cron worker with schedule `{}`",
+            apply_retention_policy_settings.schedule
+        );
+        monitor = monitor.register(
+            WorkerBuilder::new("universal-inbox-cron-apply-retention-policy")
+                .layer(
+                    TraceLayer::new()
+                        .on_request(DefaultOnRequest::default().level(Level::INFO))
+                        .on_response(DefaultOnResponse::default().level(Level::INFO))
+                        .on_failure(WorkerOnFailure {}),
+                )
+                .data(job_storage)
+                .data(cache)
+                .data(apply_retention_policy_settings)
+                .backend(CronStream::new_with_timezone(schedule, Utc))
+                .build_fn(handle_apply_retention_policy_cron_tick),
+        );
+    }
+
     monitor.on_event(|e| {
         let worker_id = e.id();
         match e.inner() {
@@ -640,20 +821,31 @@ pub async fn build_services(
     Arc<SlackService>,
     Arc<SlackBridgeService>,
     Arc<OAuth2Service>,
+    Arc<JobService>,
 ) {
     let repository = Arc::new(Repository::new(pool.clone()));
 
+    let cache = match Cache::new(settings, pool.clone()).await {
+        Ok(cache) => Some(cache),
+        Err(err) => {
+            warn!(
+                "Failed to connect to the cache; login throttling, MCP resource updates and session revocations across instances disabled: {err:?}"
+            );
+            None
+        }
+    };
+
     let auth_token_service = Arc::new(RwLock::new(AuthenticationTokenService::new(
         repository.clone(),
         settings.application.http_session.clone(),
+        cache.clone(),
     )));
-
-    // Per-account login throttle (Redis-backed), built once and shared by the
+    // Per-account login throttle (cache-backed), built once and shared by the
     // UserService. `None` when local password auth is unconfigured (nothing to
-    // throttle) or Redis is unreachable at startup — the per-IP limiter still
+    // throttle) or the cache is unreachable at startup — the per-IP limiter still
     // applies in that case. See utils::login_throttle.
-    let login_throttle = match Cache::new(settings.redis.connection_string()).await {
-        Ok(cache) => settings
+    let login_throttle = cache.as_ref().and_then(|cache| {
+        settings
             .application
             .security
             .authentication
@@ -662,12 +854,16 @@ pub async fn build_services(
                 AuthenticationSettings::Local(local) => Some(local.clone()),
                 _ => None,
             })
-            .map(|local| LoginThrottle::new(cache.connection_manager, local)),
-        Err(err) => {
-            warn!("Failed to connect to Redis for login throttle; throttling disabled: {err:?}");
-            None
-        }
-    };
+            .map(|local| LoginThrottle::new(cache.clone(), local))
+    });
+    // Publishes notification and task changes to the MCP sessions subscribed
+    // to them, from both the API and the workers. See mcp::subscriptions.
+    let resource_update_publisher = cache.map(ResourceUpdatePublisher::new);
+
+    let token_encryption_keys = SecretBox::new(Box::new(
+        TokenEncryptionKeyRing::from_settings(&settings.oauth2)
+            .expect("Invalid token encryption keys"),
+    ));
 
     let user_service = Arc::new(UserService::new(
         repository.clone(),
@@ -675,6 +871,8 @@ pub async fn build_services(
         mailer.clone(),
         webauthn.clone(),
         login_throttle,
+        token_encryption_keys.clone(),
+        auth_token_service.clone(),
     ));
 
     // Build the map of internal OAuth2 providers
@@ -749,11 +947,6 @@ pub async fn build_services(
         );
     }
 
-    let token_encryption_key = SecretBox::new(Box::new(
-        TokenEncryptionKey::from_hex(&settings.oauth2.token_encryption_key)
-            .expect("Invalid token encryption key"),
-    ));
-
     let redirect_uri = settings
         .application
         .get_oauth_redirect_url()
@@ -766,7 +959,7 @@ pub async fn build_services(
         settings.required_oauth_scopes(),
         oauth2_providers,
         oauth2_flow_service,
-        token_encryption_key,
+        token_encryption_keys,
         settings
             .application
             .min_sync_notifications_interval_in_minutes,
@@ -774,6 +967,7 @@ pub async fn build_services(
         settings.application.sync_backoff_base_delay_in_seconds,
         settings.application.sync_backoff_max_delay_in_seconds,
         settings.application.sync_failure_window_in_hours,
+        settings.application.sync_run_history_size,
     )));
 
     let todoist_service = Arc::new(
@@ -892,6 +1086,7 @@ pub async fn build_services(
         settings
             .application
             .min_sync_notifications_interval_in_minutes,
+        resource_update_publisher.clone(),
     )));
 
     google_mail_service
@@ -908,12 +1103,14 @@ pub async fn build_services(
         todoist_service.clone(),
         ticktick_service.clone(),
         linear_service.clone(),
+        github_service.clone(),
         Arc::downgrade(&notification_service),
         slack_service.clone(),
         integration_connection_service.clone(),
         user_service.clone(),
         Arc::downgrade(&third_party_item_service),
         settings.application.min_sync_tasks_interval_in_minutes,
+        resource_update_publisher,
     )));
 
     notification_service
@@ -940,6 +1137,7 @@ pub async fn build_services(
             .trim_end_matches('/'),
         settings.application.api_path
     );
+    let job_service = Arc::new(JobService::new(repository.clone()));
     let oauth2_service = Arc::new(OAuth2Service::new(
         repository,
         settings.application.http_session.jwt_secret_key.clone(),
@@ -958,6 +1156,7 @@ pub async fn build_services(
         slack_service,
         slack_bridge_service,
         oauth2_service,
+        job_service,
     )
 }
 
@@ -48,6 +48,9 @@ pub enum EmailTemplate {
     AccountLockout {
         first_name: Option<String>,
         login_url: Url,
+        /// The lock was triggered by wrong two-factor codes entered after a
+        /// valid password
+        second_factor_failed: bool,
     },
 }
 
@@ -132,14 +135,20 @@ impl EmailTemplate {
             EmailTemplate::AccountLockout {
                 first_name,
                 login_url,
+                second_factor_failed,
             } => {
                 let mut builder = EmailBuilder::new();
                 if let Some(first_name) = first_name {
                     builder = builder.greeting(Greeting::Name(first_name));
                 }
 
+                let intro = if *second_factor_failed {
+                    "Your Universal Inbox account was temporarily locked after too many failed two-factor authentication attempts. It will unlock automatically shortly. These attempts were made with your correct password: if this wasn't you, someone knows your password and you should reset it right away using the \"Forgot password\" link on the login page."
+                } else {
+                    "Your Universal Inbox account was temporarily locked after too many failed login attempts. It will unlock automatically shortly. If this was you, simply try again later. If this wasn't you, someone may be trying to access your account — we recommend resetting your password using the \"Forgot password\" link on the login page."
+                };
                 builder
-                    .intro("Your Universal Inbox account was temporarily locked after too many failed login attempts. It will unlock automatically shortly. If this was you, simply try again later. If this wasn't you, someone may be trying to access your account — we recommend resetting your password using the \"Forgot password\" link on the login page.")
+                    .intro(intro)
                     .action(Action {
                         text: "Go to login",
                         link: login_url.as_str(),
@@ -2,10 +2,7 @@
 use std::sync::Arc;
 
 use clap::Parser;
-use sqlx::{
-    ConnectOptions, Executor,
-    postgres::{PgConnectOptions, PgPoolOptions},
-};
+use sqlx::{ConnectOptions, Executor, postgres::PgPoolOptions};
 use tokio::sync::RwLock;
 use tracing::{error, info, warn};
 use wiremock::MockServer;
@@ -21,7 +18,7 @@ use universal_inbox_api::{
     mailer::SmtpMailer,
     observability::{
         get_subscriber, get_subscriber_with_telemetry, get_subscriber_with_telemetry_and_logging,
-        init_subscriber,
+        init_meter_provider, init_subscriber,
     },
     utils::passkey::build_webauthn,
 };
@@ -46,6 +43,12 @@ async fn main() -> std::io::Result<()> {
     let (log_env_filter, dep_log_level_filter) = cli.log_level(&settings);
     if let Some(tracing_settings) = &settings.application.observability.tracing {
         let service_name = cli.service_name();
+        init_meter_provider(
+            &settings.application.environment,
+            tracing_settings,
+            &service_name,
+            settings.application.version.clone(),
+        );
         if tracing_settings.is_stdout_logging_enabled {
             init_subscriber(
                 get_subscriber_with_telemetry_and_logging(
@@ -78,12 +81,9 @@ async fn main() -> std::io::Result<()> {
         "Connecting to PostgreSQL on {}",
         &settings.database.safe_connection_string()
     );
-    let options = PgConnectOptions::new()
-        .username(&settings.database.username)
-        .password(&settings.database.password)
-        .host(&settings.database.host)
-        .port(settings.database.port)
-        .database(&settings.database.database_name)
+    let options = settings
+        .database
+        .connect_options()
         .log_statements(log::LevelFilter::Debug);
     let pool = Arc::new(
         PgPoolOptions::new()
@@ -153,6 +153,7 @@ async fn main() -> std::io::Result<()> {
         slack_service,
         slack_bridge_service,
         oauth2_service,
+        job_service,
     ) = build_services(
         pool,
         &settings,
@@ -182,6 +183,7 @@ async fn main() -> std::io::Result<()> {
             slack_service,
             slack_bridge_service,
             oauth2_service,
+            job_service,
         )
         .await
     {
@@ -14,48 +14,72 @@ use actix_web::{
     http::{Method, header},
     web,
 };
-use apalis_redis::RedisStorage;
 use governor::{Quota, RateLimiter, clock::DefaultClock, state::keyed::DefaultKeyedStateStore};
 use rmcp::{
     ErrorData, ServerHandler,
     handler::server::{tool::ToolRouter, wrapper::Parameters},
     model::{
-        CallToolResult, Content, Implementation, ProtocolVersion, ServerCapabilities, ServerInfo,
+        CallToolResult, Content, GetPromptRequestParams, GetPromptResult, Implementation,
+        ListPromptsResult, ListResourceTemplatesResult, ListResourcesResult,
+        PaginatedRequestParams, ProtocolVersion, ReadResourceRequestParams, ReadResourceResult,
+        ResourceContents, ServerCapabilities, ServerInfo, SubscribeRequestParams,
+        UnsubscribeRequestParams,
     },
-    service::{RequestContext, RoleServer},
+    service::{NotificationContext, RequestContext, RoleServer},
     tool, tool_handler, tool_router,
     transport::streamable_http_server::session::{SessionStore, local::LocalSessionManager},
 };
 use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::json;
 use tokio::sync::RwLock;
 use tracing::{debug, warn};
 
-use universal_inbox::user::UserId;
+use universal_inbox::{auth::oauth2::OAuth2Scope, user::UserId};
 
 use crate::{
-    jobs::UniversalInboxJob,
-    mcp::tools::{
-        ActOnNotificationArgs, BulkActNotificationsArgs, CreateTaskFromNotificationArgs,
-        GetNotificationArgs, GetTaskArgs, ListNotificationsArgs, ListTasksArgs, McpServices,
-        SearchTasksArgs, SyncNotificationsArgs, SyncTasksArgs, ToolCallError, UpdateTaskArgs,
-        act_on_notification_output_schema, bulk_act_notifications_output_schema,
-        create_task_from_notification_output_schema, execute_tool, get_notification_output_schema,
-        get_task_output_schema, list_notifications_output_schema, list_tasks_output_schema,
-        search_tasks_output_schema, sync_notifications_output_schema, sync_tasks_output_schema,
-        update_task_output_schema,
+    jobs::storage::JobStorage,
+    mcp::{
+        prompts::PromptError,
+        resources::{MARKDOWN_MIME_TYPE, ResourceError},
+        subscriptions::{McpSessionId, McpSubscriptions, publish_resource_updates_after},
+        tools::{
+            ActOnGithubPullRequestArgs, ActOnLinearIssueArgs, ActOnNotificationArgs,
+            AnswerCalendarInvitationArgs, BulkActNotificationsArgs, CreateTaskArgs,
+            CreateTaskFromNotificationArgs, GetLinearIssueTeamArgs, GetNotificationArgs,
+            GetTaskArgs, LinkNotificationToTaskArgs, ListIntegrationConnectionsArgs,
+            ListNotificationsArgs, ListTasksArgs, McpServices, SearchProjectsArgs, SearchTasksArgs,
+            SnoozeNotificationArgs, SyncNotificationsArgs, SyncTasksArgs, ToolCallError,
+            UpdateTaskArgs, act_on_github_pull_request_output_schema,
+            act_on_linear_issue_output_schema, act_on_notification_output_schema,
+            answer_calendar_invitation_output_schema, bulk_act_notifications_output_schema,
+            create_task_from_notification_output_schema, create_task_output_schema, execute_tool,
+            get_linear_issue_team_output_schema, get_notification_output_schema,
+            get_task_output_schema, link_notification_to_task_output_schema,
+            list_integration_connections_output_schema, list_notifications_output_schema,
+            list_tasks_output_schema, search_projects_output_schema, search_tasks_output_schema,
+            snooze_notification_output_schema, sync_notifications_output_schema,
+            sync_tasks_output_schema, update_task_output_schema,
+        },
+    },
+    metrics,
+    universal_inbox::{
+        integration_connection::service::IntegrationConnectionService,
+        notification::service::NotificationService, task::service::TaskService,
     },
-    universal_inbox::{notification::service::NotificationService, task::service::TaskService},
     utils::jwt::Claims,
 };
 
+pub mod prompts;
+pub mod resources;
 pub mod session_store;
+pub mod subscriptions;
 pub mod tools;
 
-pub use session_store::RedisSessionStore;
+pub use session_store::CacheSessionStore;
 
 const SERVER_NAME: &str = "universal-inbox";
 const SERVER_TITLE: &str = "Universal Inbox";
-const SERVER_INSTRUCTIONS: &str = "Authenticate with a Universal Inbox API key. Universal Inbox aggregates notifications from multiple sources (GitHub, Linear, Slack, Google Mail/Calendar/Drive) and manages tasks synchronized between task management tools (e.g. Todoist, Linear). Tasks accessible here are only those synchronized through Universal Inbox, not all tasks from the underlying providers. Read tools do not trigger synchronization unless trigger_sync is true. Write tools execute immediately.";
+const SERVER_INSTRUCTIONS: &str = "Authenticate with a Universal Inbox API key. Universal Inbox aggregates notifications from multiple sources (GitHub, Linear, Slack, Google Mail/Calendar/Drive) and manages tasks synchronized between task management tools (e.g. Todoist, Linear). Tasks accessible here are only those synchronized through Universal Inbox, not all tasks from the underlying providers. Read tools do not trigger synchronization unless trigger_sync is true. Write tools execute immediately. Notifications and tasks are also available as markdown resources (inbox://summary, inbox://notifications/{id}, inbox://tasks/{id}).";
 const MCP_RATE_LIMIT_PER_MINUTE: u32 = 120;
 /// Protocol versions this server can negotiate.
 const SUPPORTED_PROTOCOL_VERSIONS: &[&str] =
@@ -70,21 +94,29 @@ pub type McpRateLimiter = RateLimiter<UserId, DefaultKeyedStateStore<UserId>, De
 /// The `session_store` is the cross-pod shared state: when a request lands on
 /// a pod whose `LocalSessionManager` does not know the session, the patched
 /// `rmcp-actix-web` consults the store and replays the `initialize` handshake.
+/// Resource subscriptions are shared across pods the same way by
+/// `subscriptions`.
 pub fn build_http_service(
     notification_service: Arc<RwLock<NotificationService>>,
     task_service: Arc<RwLock<TaskService>>,
-    job_storage: RedisStorage<UniversalInboxJob>,
+    integration_connection_service: Arc<RwLock<IntegrationConnectionService>>,
+    job_storage: JobStorage,
     session_store: Arc<dyn SessionStore>,
+    subscriptions: McpSubscriptions,
 ) -> StreamableHttpService<UniversalInboxMcpServer, LocalSessionManager> {
     let services = McpServices {
         notification_service,
         task_service,
+        integration_connection_service,
         job_storage,
     };
 
     StreamableHttpService::builder()
         .service_factory(Arc::new(move || {
-            Ok::<_, std::io::Error>(UniversalInboxMcpServer::new(services.clone()))
+            Ok::<_, std::io::Error>(UniversalInboxMcpServer::new(
+                services.clone(),
+                subscriptions.clone(),
+            ))
         }))
         .session_manager(Arc::new(LocalSessionManager::default()))
         .stateful_mode(true)
@@ -93,6 +125,13 @@ pub fn build_http_service(
             if let Some(authenticated) = http_req.extensions().get::<Authenticated<Claims>>() {
                 extensions.insert(authenticated.clone());
             }
+            if let Some(session_id) = http_req
+                .headers()
+                .get("Mcp-Session-Id")
+                .and_then(|value| value.to_str().ok())
+            {
+                extensions.insert(McpSessionId(session_id.to_string()));
+            }
         })
         .build()
 }
@@ -344,6 +383,7 @@ where
 #[derive(Clone)]
 pub struct UniversalInboxMcpServer {
     services: McpServices,
+    subscriptions: McpSubscriptions,
     // Consumed by `#[tool_handler]`'s macro-generated `call_tool` impl below;
     // the rmcp 1.6 macro expansion no longer references the field by name,
     // so static analysis does not see the read.
@@ -352,27 +392,23 @@ pub struct UniversalInboxMcpServer {
 }
 
 impl UniversalInboxMcpServer {
-    fn new(services: McpServices) -> Self {
+    fn new(services: McpServices, subscriptions: McpSubscriptions) -> Self {
         Self {
             services,
+            subscriptions,
             tool_router: Self::tool_router(),
         }
     }
 
-    fn user_id_from_context(
+    fn claims_from_context<'a>(
         &self,
-        context: &RequestContext<RoleServer>,
-    ) -> Result<UserId, ErrorData> {
-        let authenticated = context
+        context: &'a RequestContext<RoleServer>,
+    ) -> Result<&'a Claims, ErrorData> {
+        context
             .extensions
             .get::<Authenticated<Claims>>()
-            .ok_or_else(|| ErrorData::invalid_request("Missing authenticated user", None))?;
-
-        authenticated
-            .claims
-            .sub
-            .parse::<UserId>()
-            .map_err(|_| ErrorData::invalid_request("Invalid authenticated user", None))
+            .map(|authenticated| &authenticated.claims)
+            .ok_or_else(|| ErrorData::invalid_request("Missing authenticated user", None))
     }
 
     async fn call_structured_tool<T: serde::Serialize>(
@@ -381,12 +417,32 @@ impl UniversalInboxMcpServer {
         args: T,
         context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, ErrorData> {
-        let user_id = self.user_id_from_context(&context)?;
+        let claims = self.claims_from_context(&context)?;
+        let user_id = user_id_from_claims(claims)?;
         let arguments = serde_json::to_value(args).map(Some).map_err(|err| {
             ErrorData::invalid_params(format!("Failed to serialize tool arguments: {err}"), None)
         })?;
 
-        match execute_tool(tool_name, arguments, &self.services, user_id).await {
+        let result = publish_resource_updates_after(execute_tool(
+            tool_name,
+            arguments,
+            &self.services,
+            user_id,
+            claims,
+        ))
+        .await;
+        metrics::record_mcp_tool_call(
+            tool_name,
+            match &result {
+                Ok(_) => "success",
+                Err(ToolCallError::InvalidArguments(_)) => "invalid_arguments",
+                Err(ToolCallError::Execution(_)) => "error",
+                Err(ToolCallError::InsufficientScope(_)) => "insufficient_scope",
+                Err(ToolCallError::UnknownTool(_)) => "unknown_tool",
+            },
+        );
+
+        match result {
             Ok(result) => Ok(CallToolResult::structured(result)),
             Err(ToolCallError::InvalidArguments(err)) => {
                 Err(ErrorData::invalid_params(err.to_string(), None))
@@ -394,6 +450,9 @@ impl UniversalInboxMcpServer {
             Err(ToolCallError::Execution(err)) => {
                 Ok(CallToolResult::error(vec![Content::text(err.to_string())]))
             }
+            Err(ToolCallError::InsufficientScope(required_scopes)) => Err(
+                insufficient_scope_error(&required_scopes, &format!("call {tool_name}")),
+            ),
             Err(ToolCallError::UnknownTool(tool_name)) => Err(ErrorData::invalid_params(
                 format!("Unknown tool: {tool_name}"),
                 None,
@@ -402,6 +461,43 @@ impl UniversalInboxMcpServer {
     }
 }
 
+fn user_id_from_claims(claims: &Claims) -> Result<UserId, ErrorData> {
+    claims
+        .sub
+        .parse::<UserId>()
+        .map_err(|_| ErrorData::invalid_request("Invalid authenticated user", None))
+}
+
+fn session_id_from_context(
+    context: &RequestContext<RoleServer>,
+) -> Result<&McpSessionId, ErrorData> {
+    context
+        .extensions
+        .get::<McpSessionId>()
+        .ok_or_else(|| ErrorData::invalid_request("Missing MCP session", None))
+}
+
+fn insufficient_scope_error(required_scopes: &[OAuth2Scope], action: &str) -> ErrorData {
+    let scope = OAuth2Scope::to_scope_string(required_scopes);
+    ErrorData::invalid_request(
+        format!("Insufficient scope: {scope} is required to {action}"),
+        Some(json!({ "error": "insufficient_scope", "scope": scope })),
+    )
+}
+
+fn resource_error(uri: &str, err: ResourceError) -> ErrorData {
+    match err {
+        ResourceError::NotFound(uri) => ErrorData::resource_not_found(
+            format!("Resource not found: {uri}"),
+            Some(json!({ "uri": uri })),
+        ),
+        ResourceError::InsufficientScope(required_scopes) => {
+            insufficient_scope_error(&required_scopes, &format!("read {uri}"))
+        }
+        ResourceError::Execution(err) => ErrorData::internal_error(err.to_string(), None),
+    }
+}
+
 #[tool_router]
 impl UniversalInboxMcpServer {
     #[tool(
@@ -452,6 +548,86 @@ impl UniversalInboxMcpServer {
             .await
     }
 
+    #[tool(
+        name = "snooze_notification",
+        title = "Snooze notification",
+        description = "Snooze a notification until a preset time (later today, tomorrow morning, this weekend or next week) resolved in the user's time zone. The notification comes back to the inbox at that time.",
+        output_schema = snooze_notification_output_schema(),
+        annotations(destructive_hint = false)
+    )]
+    async fn snooze_notification(
+        &self,
+        Parameters(args): Parameters<SnoozeNotificationArgs>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.call_structured_tool("snooze_notification", args, context)
+            .await
+    }
+
+    #[tool(
+        name = "answer_calendar_invitation",
+        title = "Answer calendar invitation",
+        description = "Accept, tentatively accept or decline the event invitation of a Google Calendar notification, then remove the notification from the inbox. The organizer is notified of the answer.",
+        output_schema = answer_calendar_invitation_output_schema(),
+        annotations(destructive_hint = true, open_world_hint = true)
+    )]
+    async fn answer_calendar_invitation(
+        &self,
+        Parameters(args): Parameters<AnswerCalendarInvitationArgs>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.call_structured_tool("answer_calendar_invitation", args, context)
+            .await
+    }
+
+    #[tool(
+        name = "act_on_github_pull_request",
+        title = "Act on GitHub pull request",
+        description = "Approve, request changes on, comment on or merge the pull request of a GitHub notification, then return the notification with refreshed pull request data. Write operations execute immediately and merging cannot be undone.",
+        output_schema = act_on_github_pull_request_output_schema(),
+        annotations(destructive_hint = true, open_world_hint = true)
+    )]
+    async fn act_on_github_pull_request(
+        &self,
+        Parameters(args): Parameters<ActOnGithubPullRequestArgs>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.call_structured_tool("act_on_github_pull_request", args, context)
+            .await
+    }
+
+    #[tool(
+        name = "get_linear_issue_team",
+        title = "Get Linear issue team",
+        description = "List the workflow states and active members of the team of the issue of a Linear notification, ie. the states the issue can be moved to and the users it can be assigned to.",
+        output_schema = get_linear_issue_team_output_schema(),
+        annotations(read_only_hint = true, idempotent_hint = true)
+    )]
+    async fn get_linear_issue_team(
+        &self,
+        Parameters(args): Parameters<GetLinearIssueTeamArgs>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.call_structured_tool("get_linear_issue_team", args, context)
+            .await
+    }
+
+    #[tool(
+        name = "act_on_linear_issue",
+        title = "Act on Linear issue",
+        description = "Comment on the issue of a Linear notification, move it to another workflow state, assign or unassign it, or change its priority or due date, then return the notification with refreshed issue data. Write operations execute immediately.",
+        output_schema = act_on_linear_issue_output_schema(),
+        annotations(destructive_hint = true, open_world_hint = true)
+    )]
+    async fn act_on_linear_issue(
+        &self,
+        Parameters(args): Parameters<ActOnLinearIssueArgs>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.call_structured_tool("act_on_linear_issue", args, context)
+            .await
+    }
+
     #[tool(
         name = "bulk_act_notifications",
         title = "Bulk act on notifications",
@@ -484,6 +660,22 @@ impl UniversalInboxMcpServer {
             .await
     }
 
+    #[tool(
+        name = "link_notification_to_task",
+        title = "Link notification to task",
+        description = "Link a notification to an existing task: a link to the notification is appended to the task body and the notification is removed from the inbox.",
+        output_schema = link_notification_to_task_output_schema(),
+        annotations(destructive_hint = true)
+    )]
+    async fn link_notification_to_task(
+        &self,
+        Parameters(args): Parameters<LinkNotificationToTaskArgs>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.call_structured_tool("link_notification_to_task", args, context)
+            .await
+    }
+
     #[tool(
         name = "sync_notifications",
         title = "Synchronize notifications",
@@ -546,6 +738,38 @@ impl UniversalInboxMcpServer {
             .await
     }
 
+    #[tool(
+        name = "create_task",
+        title = "Create task",
+        description = "Create a task from scratch in a task manager (Todoist or TickTick) and synchronize it in Universal Inbox. Write operations execute immediately.",
+        output_schema = create_task_output_schema(),
+        annotations(destructive_hint = false, open_world_hint = true)
+    )]
+    async fn create_task(
+        &self,
+        Parameters(args): Parameters<CreateTaskArgs>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.call_structured_tool("create_task", args, context)
+            .await
+    }
+
+    #[tool(
+        name = "search_projects",
+        title = "Search projects",
+        description = "Search the projects of a task manager (Todoist or TickTick) by name, e.g. to pick the project of a new task.",
+        output_schema = search_projects_output_schema(),
+        annotations(read_only_hint = true, idempotent_hint = true)
+    )]
+    async fn search_projects(
+        &self,
+        Parameters(args): Parameters<SearchProjectsArgs>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.call_structured_tool("search_projects", args, context)
+            .await
+    }
+
     #[tool(
         name = "update_task",
         title = "Update task",
@@ -576,19 +800,167 @@ impl UniversalInboxMcpServer {
     ) -> Result<CallToolResult, ErrorData> {
         self.call_structured_tool("sync_tasks", args, context).await
     }
+
+    #[tool(
+        name = "list_integration_connections",
+        title = "List integration connections",
+        description = "List the integrations connected to Universal Inbox along with their status and the status of their last notifications and tasks synchronizations.",
+        output_schema = list_integration_connections_output_schema(),
+        annotations(read_only_hint = true, idempotent_hint = true)
+    )]
+    async fn list_integration_connections(
+        &self,
+        Parameters(args): Parameters<ListIntegrationConnectionsArgs>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.call_structured_tool("list_integration_connections", args, context)
+            .await
+    }
 }
 
 #[tool_handler]
 impl ServerHandler for UniversalInboxMcpServer {
     fn get_info(&self) -> ServerInfo {
-        ServerInfo::new(ServerCapabilities::builder().enable_tools().build())
-            .with_protocol_version(ProtocolVersion::V_2025_06_18)
-            .with_server_info(
-                Implementation::new(SERVER_NAME, env!("CARGO_PKG_VERSION"))
-                    .with_title(SERVER_TITLE.to_string())
-                    .with_description(SERVER_INSTRUCTIONS.to_string()),
-            )
-            .with_instructions(SERVER_INSTRUCTIONS)
+        ServerInfo::new(
+            ServerCapabilities::builder()
+                .enable_prompts()
+                .enable_resources()
+                .enable_resources_subscribe()
+                .enable_tools()
+                .build(),
+        )
+        .with_protocol_version(ProtocolVersion::V_2025_06_18)
+        .with_server_info(
+            Implementation::new(SERVER_NAME, env!("CARGO_PKG_VERSION"))
+                .with_title(SERVER_TITLE.to_string())
+                .with_description(SERVER_INSTRUCTIONS.to_string()),
+        )
+        .with_instructions(SERVER_INSTRUCTIONS)
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, ErrorData> {
+        Ok(ListResourcesResult::with_all_items(
+            resources::list_resources(),
+        ))
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, ErrorData> {
+        Ok(ListResourceTemplatesResult::with_all_items(
+            resources::list_resource_templates(),
+        ))
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, ErrorData> {
+        let claims = self.claims_from_context(&context)?;
+        let user_id = user_id_from_claims(claims)?;
+        let markdown = resources::read_resource(&request.uri, &self.services, user_id, claims)
+            .await
+            .map_err(|err| resource_error(&request.uri, err))?;
+        Ok(ReadResourceResult::new(vec![
+            ResourceContents::text(markdown, request.uri).with_mime_type(MARKDOWN_MIME_TYPE),
+        ]))
+    }
+
+    /// Only called with the request extensions when the session is restored
+    /// from the session store: this registers the sessions restored on this
+    /// pod, while fresh sessions are registered when they subscribe.
+    async fn on_initialized(&self, context: NotificationContext<RoleServer>) {
+        let (Some(authenticated), Some(session_id)) = (
+            context.extensions.get::<Authenticated<Claims>>(),
+            context.extensions.get::<McpSessionId>(),
+        ) else {
+            return;
+        };
+        if let Ok(user_id) = user_id_from_claims(&authenticated.claims) {
+            self.subscriptions
+                .register_session(user_id, session_id, context.peer.clone())
+                .await;
+        }
+    }
+
+    async fn subscribe(
+        &self,
+        request: SubscribeRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<(), ErrorData> {
+        let claims = self.claims_from_context(&context)?;
+        let user_id = user_id_from_claims(claims)?;
+        let session_id = session_id_from_context(&context)?;
+        resources::check_resource_access(&request.uri, &self.services, user_id, claims)
+            .await
+            .map_err(|err| resource_error(&request.uri, err))?;
+
+        self.subscriptions
+            .register_session(user_id, session_id, context.peer.clone())
+            .await;
+        self.subscriptions
+            .subscribe(session_id, &request.uri)
+            .await
+            .map_err(|err| {
+                ErrorData::internal_error(format!("Failed to store subscription: {err}"), None)
+            })
+    }
+
+    async fn unsubscribe(
+        &self,
+        request: UnsubscribeRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<(), ErrorData> {
+        let session_id = session_id_from_context(&context)?;
+        self.subscriptions
+            .unsubscribe(session_id, &request.uri)
+            .await
+            .map_err(|err| {
+                ErrorData::internal_error(format!("Failed to remove subscription: {err}"), None)
+            })
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, ErrorData> {
+        Ok(ListPromptsResult::with_all_items(prompts::list_prompts()))
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, ErrorData> {
+        let claims = self.claims_from_context(&context)?;
+        let user_id = user_id_from_claims(claims)?;
+        prompts::get_prompt(
+            &request.name,
+            request.arguments,
+            &self.services,
+            user_id,
+            claims,
+        )
+        .await
+        .map_err(|err| match err {
+            PromptError::UnknownPrompt(name) => {
+                ErrorData::invalid_params(format!("Unknown prompt: {name}"), None)
+            }
+            PromptError::InvalidArguments(err) => ErrorData::invalid_params(err.to_string(), None),
+            PromptError::InsufficientScope(required_scopes) => insufficient_scope_error(
+                &required_scopes,
+                &format!("get the {} prompt", request.name),
+            ),
+            PromptError::Resource(err) => resource_error(resources::INBOX_SUMMARY_URI, err),
+        })
     }
 }
 
@@ -601,8 +973,8 @@ mod registration_tests {
         let tools = UniversalInboxMcpServer::tool_router().list_all();
         assert_eq!(
             tools.len(),
-            11,
-            "expected all 11 MCP tools to be registered"
+            20,
+            "expected all 20 MCP tools to be registered"
         );
         for tool in &tools {
             assert!(
@@ -643,15 +1015,32 @@ mod registration_tests {
         let tools = UniversalInboxMcpServer::tool_router().list_all();
         assert_eq!(
             tools.len(),
-            11,
-            "expected all 11 MCP tools to be registered"
+            20,
+            "expected all 20 MCP tools to be registered"
         );
 
         let expected_required: std::collections::HashMap<&str, &[&str]> = [
             ("get_notification", &["notification_id"][..]),
             ("act_on_notification", &["notification_id", "action"][..]),
+            ("snooze_notification", &["notification_id", "preset"][..]),
+            (
+                "answer_calendar_invitation",
+                &["notification_id", "response"][..],
+            ),
+            (
+                "act_on_github_pull_request",
+                &["notification_id", "action"][..],
+            ),
+            ("get_linear_issue_team", &["notification_id"][..]),
+            ("act_on_linear_issue", &["notification_id", "action"][..]),
             ("bulk_act_notifications", &["action"][..]),
             ("create_task_from_notification", &["notification_id"][..]),
+            (
+                "link_notification_to_task",
+                &["notification_id", "task_id"][..],
+            ),
+            ("create_task", &["task_creation"][..]),
+            ("search_projects", &["matches"][..]),
             ("get_task", &["task_id"][..]),
             ("search_tasks", &["matches"][..]),
             ("update_task", &["task_id", "patch"][..]),
@@ -684,4 +1073,32 @@ mod registration_tests {
             }
         }
     }
+
+    /// Every tool must require at least one scope, and tools advertised as
+    /// read-only must be callable with the read-only default scopes granted
+    /// to agents.
+    #[test]
+    fn every_tool_requires_a_scope_matching_its_annotations() {
+        let tools = UniversalInboxMcpServer::tool_router().list_all();
+        for tool in &tools {
+            let required_scopes = tools::tool_required_scopes(tool.name.as_ref());
+            assert!(
+                !required_scopes.is_empty(),
+                "tool `{}` does not require any scope",
+                tool.name
+            );
+
+            let read_only = tool
+                .annotations
+                .as_ref()
+                .and_then(|annotations| annotations.read_only_hint)
+                .unwrap_or(false);
+            assert_eq!(
+                read_only,
+                required_scopes.iter().all(|scope| scope.is_read_only()),
+                "tool `{}`: read_only_hint does not match required scopes {required_scopes:?}",
+                tool.name
+            );
+        }
+    }
 }
@@ -0,0 +1,211 @@
+use anyhow::anyhow;
+use rmcp::model::{
+    GetPromptResult, JsonObject, Prompt, PromptArgument, PromptMessage, PromptMessageRole,
+};
+
+use universal_inbox::{
+    auth::oauth2::OAuth2Scope, notification::NotificationSourceKind, user::UserId,
+};
+
+use crate::{
+    mcp::{
+        resources::{INBOX_SUMMARY_URI, MARKDOWN_MIME_TYPE, ResourceError, render_inbox_summary},
+        tools::McpServices,
+    },
+    utils::jwt::{Claims, check_scopes},
+};
+
+pub enum PromptError {
+    UnknownPrompt(String),
+    InvalidArguments(anyhow::Error),
+    InsufficientScope(Vec<OAuth2Scope>),
+    Resource(ResourceError),
+}
+
+/// Scopes an OAuth2 token must hold to get the `name` prompt, ie. to read
+/// what the prompt asks the agent to work on.
+pub(crate) fn prompt_required_scopes(name: &str) -> &'static [OAuth2Scope] {
+    match name {
+        "triage_inbox" => &[OAuth2Scope::NotificationsRead],
+        "plan_today" => &[OAuth2Scope::TasksRead],
+        _ => &[],
+    }
+}
+
+pub(crate) fn list_prompts() -> Vec<Prompt> {
+    vec![
+        Prompt::new(
+            "triage_inbox",
+            Some(
+                "Go through unread notifications and propose an action for each of them: mark as read, delete, unsubscribe, snooze or create a task.",
+            ),
+            Some(vec![
+                PromptArgument::new("source")
+                    .with_description(
+                        "Only triage notifications from this source (e.g. Github, Linear, Slack, GoogleMail).",
+                    )
+                    .with_required(false),
+            ]),
+        )
+        .with_title("Triage my inbox"),
+        Prompt::new(
+            "plan_today",
+            Some(
+                "Build a plan for today from active tasks, starting with overdue tasks and tasks due today.",
+            ),
+            Some(vec![
+                PromptArgument::new("available_time")
+                    .with_description("Time available today, e.g. `6 hours` or `9:00-12:00`.")
+                    .with_required(false),
+            ]),
+        )
+        .with_title("Plan today's tasks"),
+    ]
+}
+
+/// Build the messages of the `name` prompt. The inbox summary resource is
+/// embedded in the first message so that the agent starts with context.
+pub async fn get_prompt(
+    name: &str,
+    arguments: Option<JsonObject>,
+    services: &McpServices,
+    user_id: UserId,
+    claims: &Claims,
+) -> Result<GetPromptResult, PromptError> {
+    let instructions = match name {
+        "triage_inbox" => {
+            let source = prompt_argument(&arguments, "source")?
+                .map(|source| {
+                    source.parse::<NotificationSourceKind>().map_err(|_| {
+                        PromptError::InvalidArguments(anyhow!(
+                            "Unknown notification source `{source}`"
+                        ))
+                    })
+                })
+                .transpose()?;
+            triage_inbox_instructions(source)
+        }
+        "plan_today" => {
+            let available_time = prompt_argument(&arguments, "available_time")?;
+            plan_today_instructions(available_time.as_deref())
+        }
+        _ => return Err(PromptError::UnknownPrompt(name.to_string())),
+    };
+
+    let required_scopes = prompt_required_scopes(name);
+    check_scopes(claims.granted_scopes().as_ref(), required_scopes)
+        .map_err(|_| PromptError::InsufficientScope(required_scopes.to_vec()))?;
+    let summary = render_inbox_summary(services, user_id, claims)
+        .await
+        .map_err(PromptError::Resource)?;
+
+    let description = list_prompts()
+        .into_iter()
+        .find(|prompt| prompt.name == name)
+        .and_then(|prompt| prompt.description);
+    let mut result = GetPromptResult::new(vec![
+        PromptMessage::new_resource(
+            PromptMessageRole::User,
+            INBOX_SUMMARY_URI.to_string(),
+            Some(MARKDOWN_MIME_TYPE.to_string()),
+            Some(summary),
+            None,
+            None,
+            None,
+        ),
+        PromptMessage::new_text(PromptMessageRole::User, instructions),
+    ]);
+    result.description = description;
+    Ok(result)
+}
+
+fn prompt_argument(
+    arguments: &Option<JsonObject>,
+    name: &str,
+) -> Result<Option<String>, PromptError> {
+    match arguments.as_ref().and_then(|arguments| arguments.get(name)) {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(serde_json::Value::String(value)) if value.trim().is_empty() => Ok(None),
+        Some(serde_json::Value::String(value)) => Ok(Some(value.trim().to_string())),
+        Some(_) => Err(PromptError::InvalidArguments(anyhow!(
+            "Prompt argument `{name}` must be a string"
+        ))),
+    }
+}
+
+fn triage_inbox_instructions(source: Option<NotificationSourceKind>) -> String {
+    let (scope, filter) = match source {
+        Some(source) => (
+            format!("my unread {source} notifications"),
+            format!(" with `status: [\"Unread\"]` and `sources: [\"{source}\"]`"),
+        ),
+        None => (
+            "my unread notifications".to_string(),
+            " with `status: [\"Unread\"]`".to_string(),
+        ),
+    };
+    format!(
+        "Help me triage {scope} in Universal Inbox, using the inbox summary above as a starting point.\n\n\
+         1. List them with the `list_notifications` tool{filter}, following `next_page_token` until all of them are listed. \
+         Read the `inbox://notifications/{{id}}` resource or call `get_notification` when the title is not enough to decide.\n\
+         2. Propose one action per notification: mark it as read when nothing is needed from me, delete or unsubscribe from it when it is noise, \
+         snooze it until a relevant date when it cannot be handled yet, or create a task from it when it needs follow-up work.\n\
+         3. Present the proposals grouped by action, with a one-line reason for each notification.\n\
+         4. Wait for my confirmation, then apply the confirmed actions with `act_on_notification`, `bulk_act_notifications` or `create_task_from_notification`."
+    )
+}
+
+fn plan_today_instructions(available_time: Option<&str>) -> String {
+    let constraint = available_time
+        .map(|available_time| format!(" I have {available_time} available today."))
+        .unwrap_or_default();
+    format!(
+        "Help me plan today's work, using the inbox summary above as a starting point.{constraint}\n\n\
+         1. List my active tasks with the `list_tasks` tool. Read the `inbox://tasks/{{id}}` resource or call `get_task` for the tasks whose title is not enough to estimate them.\n\
+         2. Start from overdue tasks and tasks due today, then pick other tasks by priority (P1 is the highest).\n\
+         3. Propose an ordered plan for today with a rough time estimate per task, and list the tasks that do not fit along with a suggested new due date.\n\
+         4. Wait for my confirmation, then reschedule the tasks I agree to move with `update_task`."
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn every_prompt_requires_a_scope() {
+        for prompt in list_prompts() {
+            assert!(
+                !prompt_required_scopes(&prompt.name).is_empty(),
+                "prompt `{}` does not require any scope",
+                prompt.name
+            );
+        }
+    }
+
+    #[test]
+    fn parse_prompt_arguments() {
+        let arguments = json!({ "source": " Github ", "empty": "", "count": 3 })
+            .as_object()
+            .cloned();
+
+        assert_eq!(
+            prompt_argument(&arguments, "source").ok().flatten(),
+            Some("Github".to_string())
+        );
+        assert_eq!(prompt_argument(&arguments, "empty").ok().flatten(), None);
+        assert_eq!(prompt_argument(&arguments, "missing").ok().flatten(), None);
+        assert!(prompt_argument(&arguments, "count").is_err());
+        assert_eq!(prompt_argument(&None, "source").ok().flatten(), None);
+    }
+
+    #[test]
+    fn triage_inbox_instructions_filter_on_source() {
+        let instructions = triage_inbox_instructions(Some(NotificationSourceKind::Github));
+
+        assert!(instructions.contains("my unread Github notifications"));
+        assert!(instructions.contains("`sources: [\"Github\"]`"));
+    }
+}
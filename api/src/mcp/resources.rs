@@ -0,0 +1,555 @@
+use std::{collections::BTreeSet, fmt::Write, str::FromStr};
+
+use chrono::{NaiveDate, Utc};
+use rmcp::model::{AnnotateAble, RawResource, RawResourceTemplate, Resource, ResourceTemplate};
+use serde_json::Value;
+
+use universal_inbox::{
+    HasHtmlUrl,
+    auth::oauth2::OAuth2Scope,
+    notification::{Notification, NotificationId, NotificationListOrder, NotificationStatus},
+    task::{DueDate, Task, TaskId, TaskStatus},
+    third_party::item::ThirdPartyItem,
+    user::UserId,
+};
+
+use crate::{
+    mcp::tools::McpServices,
+    universal_inbox::UniversalInboxError,
+    utils::jwt::{Claims, check_scopes, restrict_notification_source_kinds},
+};
+
+pub(crate) const INBOX_SUMMARY_URI: &str = "inbox://summary";
+const NOTIFICATION_URI_PREFIX: &str = "inbox://notifications/";
+const TASK_URI_PREFIX: &str = "inbox://tasks/";
+pub(crate) const MARKDOWN_MIME_TYPE: &str = "text/markdown";
+/// Maximum number of notifications or tasks listed in each section of the
+/// inbox summary.
+const SUMMARY_MAX_ITEMS: usize = 20;
+/// Provider payloads may embed whole email bodies or documents: long string
+/// values are truncated when rendered.
+const PAYLOAD_MAX_STRING_CHARS: usize = 500;
+const PAYLOAD_MAX_ARRAY_ITEMS: usize = 20;
+
+pub enum ResourceError {
+    NotFound(String),
+    InsufficientScope(Vec<OAuth2Scope>),
+    Execution(anyhow::Error),
+}
+
+impl ResourceError {
+    fn from_service_error(uri: &str, err: UniversalInboxError) -> Self {
+        match err {
+            // Do not disclose the existence of resources of other users or from
+            // sources the token cannot access
+            UniversalInboxError::Forbidden(_) => Self::NotFound(uri.to_string()),
+            err => Self::Execution(err.into()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum InboxResource {
+    Summary,
+    Notification(NotificationId),
+    Task(TaskId),
+}
+
+impl FromStr for InboxResource {
+    type Err = ();
+
+    fn from_str(uri: &str) -> Result<Self, Self::Err> {
+        if uri == INBOX_SUMMARY_URI {
+            return Ok(Self::Summary);
+        }
+        if let Some(id) = uri.strip_prefix(NOTIFICATION_URI_PREFIX) {
+            return id.parse().map(Self::Notification).map_err(|_| ());
+        }
+        if let Some(id) = uri.strip_prefix(TASK_URI_PREFIX) {
+            return id.parse().map(Self::Task).map_err(|_| ());
+        }
+        Err(())
+    }
+}
+
+pub fn notification_uri(notification_id: NotificationId) -> String {
+    format!("{NOTIFICATION_URI_PREFIX}{notification_id}")
+}
+
+pub fn task_uri(task_id: TaskId) -> String {
+    format!("{TASK_URI_PREFIX}{task_id}")
+}
+
+pub(crate) fn list_resources() -> Vec<Resource> {
+    vec![
+        RawResource::new(INBOX_SUMMARY_URI, "inbox_summary")
+            .with_title("Inbox summary")
+            .with_description(
+                "Unread notifications and active tasks due today or overdue, rendered as markdown.",
+            )
+            .with_mime_type(MARKDOWN_MIME_TYPE)
+            .no_annotation(),
+    ]
+}
+
+pub(crate) fn list_resource_templates() -> Vec<ResourceTemplate> {
+    vec![
+        RawResourceTemplate::new(format!("{NOTIFICATION_URI_PREFIX}{{id}}"), "notification")
+            .with_title("Notification")
+            .with_description(
+                "A Universal Inbox notification and its third-party item, rendered as markdown.",
+            )
+            .with_mime_type(MARKDOWN_MIME_TYPE)
+            .no_annotation(),
+        RawResourceTemplate::new(format!("{TASK_URI_PREFIX}{{id}}"), "task")
+            .with_title("Task")
+            .with_description(
+                "A task synchronized through Universal Inbox and its third-party item, rendered as markdown.",
+            )
+            .with_mime_type(MARKDOWN_MIME_TYPE)
+            .no_annotation(),
+    ]
+}
+
+/// Render the resource at `uri` as markdown.
+pub async fn read_resource(
+    uri: &str,
+    services: &McpServices,
+    user_id: UserId,
+    claims: &Claims,
+) -> Result<String, ResourceError> {
+    match parse_resource_uri(uri)? {
+        InboxResource::Summary => render_inbox_summary(services, user_id, claims).await,
+        InboxResource::Notification(notification_id) => {
+            let notification =
+                fetch_notification(uri, notification_id, services, user_id, claims).await?;
+            Ok(render_notification(&notification))
+        }
+        InboxResource::Task(task_id) => {
+            let task = fetch_task(uri, task_id, services, user_id, claims).await?;
+            Ok(render_task(&task))
+        }
+    }
+}
+
+/// Fails unless the resource at `uri` exists and the token is allowed to
+/// read it.
+pub async fn check_resource_access(
+    uri: &str,
+    services: &McpServices,
+    user_id: UserId,
+    claims: &Claims,
+) -> Result<(), ResourceError> {
+    match parse_resource_uri(uri)? {
+        InboxResource::Summary => summary_access(claims).map(|_| ()),
+        InboxResource::Notification(notification_id) => {
+            fetch_notification(uri, notification_id, services, user_id, claims)
+                .await
+                .map(|_| ())
+        }
+        InboxResource::Task(task_id) => fetch_task(uri, task_id, services, user_id, claims)
+            .await
+            .map(|_| ()),
+    }
+}
+
+fn parse_resource_uri(uri: &str) -> Result<InboxResource, ResourceError> {
+    uri.parse()
+        .map_err(|_| ResourceError::NotFound(uri.to_string()))
+}
+
+async fn fetch_notification(
+    uri: &str,
+    notification_id: NotificationId,
+    services: &McpServices,
+    user_id: UserId,
+    claims: &Claims,
+) -> Result<Notification, ResourceError> {
+    require_scopes(
+        claims.granted_scopes().as_ref(),
+        &[OAuth2Scope::NotificationsRead],
+    )?;
+    let service = services.notification_service.read().await;
+    let mut transaction = service.begin().await.map_err(execution_error)?;
+    service
+        .check_notification_source_access(
+            &mut transaction,
+            notification_id,
+            user_id,
+            claims.notification_source_kinds.as_deref(),
+        )
+        .await
+        .map_err(|err| ResourceError::from_service_error(uri, err))?;
+    let notification = service
+        .get_notification(&mut transaction, notification_id, user_id)
+        .await
+        .map_err(|err| ResourceError::from_service_error(uri, err))?
+        .ok_or_else(|| ResourceError::NotFound(uri.to_string()))?;
+    transaction.commit().await.map_err(execution_error)?;
+    Ok(notification)
+}
+
+async fn fetch_task(
+    uri: &str,
+    task_id: TaskId,
+    services: &McpServices,
+    user_id: UserId,
+    claims: &Claims,
+) -> Result<Task, ResourceError> {
+    require_scopes(claims.granted_scopes().as_ref(), &[OAuth2Scope::TasksRead])?;
+    let service = services.task_service.read().await;
+    let mut transaction = service.begin().await.map_err(execution_error)?;
+    let task = service
+        .get_task(&mut transaction, task_id, user_id)
+        .await
+        .map_err(|err| ResourceError::from_service_error(uri, err))?
+        .ok_or_else(|| ResourceError::NotFound(uri.to_string()))?;
+    transaction.commit().await.map_err(execution_error)?;
+    Ok(task)
+}
+
+/// Whether the token can read the notifications and the tasks sections of
+/// the inbox summary. Fails if it can read neither.
+fn summary_access(claims: &Claims) -> Result<(bool, bool), ResourceError> {
+    let granted_scopes = claims.granted_scopes();
+    let granted_scopes = granted_scopes.as_ref();
+    let can_read_notifications =
+        check_scopes(granted_scopes, &[OAuth2Scope::NotificationsRead]).is_ok();
+    let can_read_tasks = check_scopes(granted_scopes, &[OAuth2Scope::TasksRead]).is_ok();
+    if !can_read_notifications && !can_read_tasks {
+        return Err(ResourceError::InsufficientScope(vec![
+            OAuth2Scope::NotificationsRead,
+        ]));
+    }
+    Ok((can_read_notifications, can_read_tasks))
+}
+
+/// The summary only contains the sections the token has access to: unread
+/// notifications with `notifications:read` and tasks with `tasks:read`.
+pub async fn render_inbox_summary(
+    services: &McpServices,
+    user_id: UserId,
+    claims: &Claims,
+) -> Result<String, ResourceError> {
+    let (can_read_notifications, can_read_tasks) = summary_access(claims)?;
+
+    let mut markdown = format!(
+        "# Inbox summary\n\nGenerated at {}.\n",
+        Utc::now().to_rfc3339()
+    );
+
+    if can_read_notifications {
+        let service = services.notification_service.read().await;
+        let mut transaction = service.begin().await.map_err(execution_error)?;
+        let page = service
+            .list_notifications(
+                &mut transaction,
+                vec![NotificationStatus::Unread],
+                false,
+                false,
+                None,
+                NotificationListOrder::UpdatedAtDesc,
+                restrict_notification_source_kinds(
+                    claims.notification_source_kinds.as_deref(),
+                    vec![],
+                )
+                .map_err(execution_error)?,
+                None,
+                user_id,
+                None,
+            )
+            .await
+            .map_err(execution_error)?;
+        transaction.commit().await.map_err(execution_error)?;
+
+        let _ = write!(
+            markdown,
+            "\n## Notifications\n\n{} unread notification(s).\n\n",
+            page.total
+        );
+        for notification in page.content.iter().take(SUMMARY_MAX_ITEMS) {
+            let _ = writeln!(
+                markdown,
+                "- [{}] {} (`{}`)",
+                notification.kind,
+                notification.title,
+                notification_uri(notification.id)
+            );
+        }
+    }
+
+    if can_read_tasks {
+        let service = services.task_service.read().await;
+        let mut transaction = service.begin().await.map_err(execution_error)?;
+        let page = service
+            .list_tasks(&mut transaction, TaskStatus::Active, true, user_id, None)
+            .await
+            .map_err(execution_error)?;
+        transaction.commit().await.map_err(execution_error)?;
+
+        let today = Utc::now().date_naive();
+        let mut overdue_tasks: Vec<&Task> = vec![];
+        let mut tasks_due_today: Vec<&Task> = vec![];
+        for task in &page.content {
+            match task.due_at.as_ref().map(due_date) {
+                Some(date) if date < today => overdue_tasks.push(task),
+                Some(date) if date == today => tasks_due_today.push(task),
+                _ => {}
+            }
+        }
+        overdue_tasks.sort_by_key(|task| task.priority);
+        tasks_due_today.sort_by_key(|task| task.priority);
+
+        let _ = write!(
+            markdown,
+            "\n## Tasks\n\n{} active task(s), {} overdue, {} due today.\n",
+            page.content.len(),
+            overdue_tasks.len(),
+            tasks_due_today.len()
+        );
+        for (header, tasks) in [("Overdue", overdue_tasks), ("Due today", tasks_due_today)] {
+            if tasks.is_empty() {
+                continue;
+            }
+            let _ = write!(markdown, "\n### {header}\n\n");
+            for task in tasks.into_iter().take(SUMMARY_MAX_ITEMS) {
+                let due = task
+                    .due_at
+                    .as_ref()
+                    .map(|due_at| format!(", due {}", due_at.display_date()))
+                    .unwrap_or_default();
+                let _ = writeln!(
+                    markdown,
+                    "- [P{}] {}{due} (`{}`)",
+                    task.priority,
+                    task.title,
+                    task_uri(task.id)
+                );
+            }
+        }
+    }
+
+    Ok(markdown)
+}
+
+fn render_notification(notification: &Notification) -> String {
+    let mut markdown = format!("# {}\n\n", notification.title);
+    let _ = writeln!(markdown, "- **Source**: {}", notification.kind);
+    let _ = writeln!(markdown, "- **Status**: {}", notification.status);
+    let _ = writeln!(
+        markdown,
+        "- **Updated at**: {}",
+        notification.updated_at.to_rfc3339()
+    );
+    if let Some(snoozed_until) = notification.snoozed_until {
+        let _ = writeln!(
+            markdown,
+            "- **Snoozed until**: {}",
+            snoozed_until.to_rfc3339()
+        );
+    }
+    let _ = writeln!(markdown, "- **Link**: {}", notification.get_html_url());
+    if let Some(task_id) = notification.task_id {
+        let _ = writeln!(markdown, "- **Task**: `{}`", task_uri(task_id));
+    }
+    render_third_party_item(&mut markdown, &notification.source_item);
+    markdown
+}
+
+fn render_task(task: &Task) -> String {
+    let mut markdown = format!("# {}\n\n", task.title);
+    let _ = writeln!(markdown, "- **Source**: {}", task.kind);
+    let _ = writeln!(markdown, "- **Status**: {}", task.status);
+    let _ = writeln!(markdown, "- **Priority**: P{}", task.priority);
+    if !task.project.is_empty() {
+        let _ = writeln!(markdown, "- **Project**: {}", task.project);
+    }
+    if let Some(due_at) = &task.due_at {
+        let recurring = if task.is_recurring {
+            " (recurring)"
+        } else {
+            ""
+        };
+        let _ = writeln!(markdown, "- **Due**: {}{recurring}", due_at.display_date());
+    }
+    if !task.tags.is_empty() {
+        let _ = writeln!(markdown, "- **Tags**: {}", task.tags.join(", "));
+    }
+    let _ = writeln!(markdown, "- **Link**: {}", task.get_html_url());
+    if !task.body.is_empty() {
+        let _ = write!(markdown, "\n## Description\n\n{}\n", task.body.trim_end());
+    }
+    render_third_party_item(&mut markdown, &task.source_item);
+    markdown
+}
+
+fn render_third_party_item(markdown: &mut String, item: &ThirdPartyItem) {
+    let _ = write!(markdown, "\n## {} details\n\n", item.data.kind());
+    match serde_json::to_value(&item.data) {
+        Ok(payload) => {
+            // `ThirdPartyItemData` is serialized as `{"type": ..., "content": ...}`
+            let content = payload.get("content").unwrap_or(&payload);
+            render_payload(markdown, content, 0);
+        }
+        Err(_) => markdown.push_str("_Unable to render the item details._\n"),
+    }
+}
+
+/// Render a provider payload as a nested markdown list, skipping empty values.
+fn render_payload(markdown: &mut String, value: &Value, depth: usize) {
+    match value {
+        Value::Object(fields) => {
+            for (key, value) in fields {
+                render_payload_entry(markdown, Some(key), value, depth);
+            }
+        }
+        Value::Array(items) => {
+            for item in items.iter().take(PAYLOAD_MAX_ARRAY_ITEMS) {
+                render_payload_entry(markdown, None, item, depth);
+            }
+            if items.len() > PAYLOAD_MAX_ARRAY_ITEMS {
+                let _ = writeln!(
+                    markdown,
+                    "{}- _{} more_",
+                    "  ".repeat(depth),
+                    items.len() - PAYLOAD_MAX_ARRAY_ITEMS
+                );
+            }
+        }
+        value => render_payload_entry(markdown, None, value, depth),
+    }
+}
+
+fn render_payload_entry(markdown: &mut String, key: Option<&str>, value: &Value, depth: usize) {
+    if is_empty_value(value) {
+        return;
+    }
+    let indent = "  ".repeat(depth);
+    let label = key.map(|key| format!("**{key}**: ")).unwrap_or_default();
+    match value {
+        Value::Object(_) | Value::Array(_) => {
+            let label = key.map(|key| format!("**{key}**")).unwrap_or_default();
+            let _ = writeln!(markdown, "{indent}- {label}");
+            render_payload(markdown, value, depth + 1);
+        }
+        Value::String(text) => {
+            let _ = writeln!(markdown, "{indent}- {label}{}", render_payload_string(text));
+        }
+        value => {
+            let _ = writeln!(markdown, "{indent}- {label}{value}");
+        }
+    }
+}
+
+fn render_payload_string(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= PAYLOAD_MAX_STRING_CHARS {
+        return text;
+    }
+    let truncated: String = text.chars().take(PAYLOAD_MAX_STRING_CHARS).collect();
+    format!("{truncated}…")
+}
+
+fn is_empty_value(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(text) => text.trim().is_empty(),
+        Value::Array(items) => items.iter().all(is_empty_value),
+        Value::Object(fields) => fields.values().all(is_empty_value),
+        _ => false,
+    }
+}
+
+fn due_date(due_at: &DueDate) -> NaiveDate {
+    match due_at {
+        DueDate::Date(date) => *date,
+        DueDate::DateTime(datetime) => datetime.date(),
+        DueDate::DateTimeWithTz(datetime) => datetime.date_naive(),
+    }
+}
+
+fn require_scopes(
+    granted_scopes: Option<&BTreeSet<OAuth2Scope>>,
+    required_scopes: &[OAuth2Scope],
+) -> Result<(), ResourceError> {
+    check_scopes(granted_scopes, required_scopes)
+        .map_err(|_| ResourceError::InsufficientScope(required_scopes.to_vec()))
+}
+
+fn execution_error<E>(err: E) -> ResourceError
+where
+    E: Into<anyhow::Error>,
+{
+    ResourceError::Execution(err.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn parse_resource_uris() {
+        let id = Uuid::new_v4();
+        assert_eq!(
+            INBOX_SUMMARY_URI.parse::<InboxResource>(),
+            Ok(InboxResource::Summary)
+        );
+        assert_eq!(
+            format!("inbox://notifications/{id}").parse::<InboxResource>(),
+            Ok(InboxResource::Notification(NotificationId(id)))
+        );
+        assert_eq!(
+            format!("inbox://tasks/{id}").parse::<InboxResource>(),
+            Ok(InboxResource::Task(TaskId(id)))
+        );
+        assert!("inbox://tasks/not-a-uuid".parse::<InboxResource>().is_err());
+        assert!("inbox://projects/1".parse::<InboxResource>().is_err());
+    }
+
+    #[test]
+    fn render_payload_as_nested_list() {
+        let mut markdown = String::new();
+        render_payload(
+            &mut markdown,
+            &json!({
+                "title": "Fix the\n  build",
+                "number": 42,
+                "draft": false,
+                "closed_at": null,
+                "labels": [],
+                "author": { "login": "octocat", "avatar_url": null },
+                "reviewers": ["alice", "bob"]
+            }),
+            0,
+        );
+
+        assert_eq!(
+            markdown,
+            "- **title**: Fix the build\n\
+             - **number**: 42\n\
+             - **draft**: false\n\
+             - **author**\n  \
+             - **login**: octocat\n\
+             - **reviewers**\n  \
+             - alice\n  \
+             - bob\n"
+        );
+    }
+
+    #[test]
+    fn render_payload_truncates_long_values() {
+        let mut markdown = String::new();
+        let items: Vec<usize> = (0..PAYLOAD_MAX_ARRAY_ITEMS + 2).collect();
+        render_payload(
+            &mut markdown,
+            &json!({ "body": "a".repeat(PAYLOAD_MAX_STRING_CHARS + 1), "items": items }),
+            0,
+        );
+
+        assert!(markdown.contains(&format!("{}…", "a".repeat(PAYLOAD_MAX_STRING_CHARS))));
+        assert!(markdown.ends_with("  - _2 more_\n"));
+    }
+}
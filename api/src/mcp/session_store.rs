@@ -1,31 +1,35 @@
-//! Redis-backed [`SessionStore`] for cross-pod MCP session restore.
+//! [`SessionStore`] for cross-pod MCP session restore, backed by the [`Cache`]
+//! (Redis or PostgreSQL).
 //!
 //! When the API runs as multiple replicas behind a load balancer, each pod
 //! holds its own [`LocalSessionManager`] and so does not know about sessions
 //! that initialised on a different pod. This store persists each session's
-//! `initialize` parameters (the [`SessionState`]) to shared Redis so the
+//! `initialize` parameters (the [`SessionState`]) to shared storage so the
 //! upstream rmcp transport can transparently replay the handshake on whichever
 //! pod a follow-up request lands on.
 //!
 //! [`LocalSessionManager`]: rmcp::transport::streamable_http_server::session::local::LocalSessionManager
 
+use std::time::Duration;
+
 use async_trait::async_trait;
-use redis::{AsyncCommands, aio::ConnectionManager};
 use rmcp::transport::streamable_http_server::session::{
     SessionState, SessionStore, SessionStoreError,
 };
 
+use crate::utils::cache::Cache;
+
 const NAMESPACE: &str = "universal-inbox:mcp:session:";
 
 #[derive(Clone)]
-pub struct RedisSessionStore {
-    conn: ConnectionManager,
+pub struct CacheSessionStore {
+    cache: Cache,
     ttl_seconds: u64,
 }
 
-impl RedisSessionStore {
-    pub fn new(conn: ConnectionManager, ttl_seconds: u64) -> Self {
-        Self { conn, ttl_seconds }
+impl CacheSessionStore {
+    pub fn new(cache: Cache, ttl_seconds: u64) -> Self {
+        Self { cache, ttl_seconds }
     }
 
     fn key(id: &str) -> String {
@@ -34,11 +38,11 @@ impl RedisSessionStore {
 }
 
 #[async_trait]
-impl SessionStore for RedisSessionStore {
+impl SessionStore for CacheSessionStore {
     async fn load(&self, session_id: &str) -> Result<Option<SessionState>, SessionStoreError> {
-        let mut conn = self.conn.clone();
-        let raw: Option<String> = conn
-            .get(Self::key(session_id))
+        let raw = self
+            .cache
+            .get(&Self::key(session_id))
             .await
             .map_err(|e| Box::new(e) as SessionStoreError)?;
         match raw {
@@ -50,18 +54,21 @@ impl SessionStore for RedisSessionStore {
     }
 
     async fn store(&self, session_id: &str, state: &SessionState) -> Result<(), SessionStoreError> {
-        let mut conn = self.conn.clone();
         let payload = serde_json::to_string(state).map_err(|e| Box::new(e) as SessionStoreError)?;
-        conn.set_ex::<_, _, ()>(Self::key(session_id), payload, self.ttl_seconds)
+        self.cache
+            .set_ex(
+                &Self::key(session_id),
+                &payload,
+                Duration::from_secs(self.ttl_seconds),
+            )
             .await
             .map_err(|e| Box::new(e) as SessionStoreError)?;
         Ok(())
     }
 
     async fn delete(&self, session_id: &str) -> Result<(), SessionStoreError> {
-        let mut conn = self.conn.clone();
-        let _: () = conn
-            .del(Self::key(session_id))
+        self.cache
+            .del(&Self::key(session_id))
             .await
             .map_err(|e| Box::new(e) as SessionStoreError)?;
         Ok(())
@@ -0,0 +1,397 @@
+//! MCP resource subscriptions (`resources/subscribe`) and their
+//! `notifications/resources/updated` fan-out.
+//!
+//! The notification and task services publish a [`ResourceUpdate`] on a
+//! [`Cache`] channel (Redis publish/subscribe or PostgreSQL `NOTIFY`) whenever
+//! they modify notifications or tasks, including from the asynchronous
+//! workers. Every API pod listens to that channel and notifies the MCP sessions
+//! it hosts for the updated user.
+//!
+//! Services make these changes within their caller's transaction, so they
+//! cannot publish them right away: subscribers would read the resources before
+//! the changes are committed. The HTTP requests, jobs, MCP tool calls and
+//! commands owning the transactions run within
+//! [`publish_resource_updates_after`], which holds the updates back as a
+//! task-local and publishes them, merged per user, once they completed.
+//!
+//! Subscribed URIs are stored per session in the same [`Cache`] as the
+//! [`CacheSessionStore`](super::CacheSessionStore), so that a session restored
+//! on another pod keeps its subscriptions.
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, HashMap},
+    future::Future,
+    sync::Arc,
+    time::Duration,
+};
+
+use futures::StreamExt;
+use rmcp::{Peer, RoleServer, model::ResourceUpdatedNotificationParam};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use tracing::{debug, error, warn};
+
+use universal_inbox::{notification::NotificationId, task::TaskId, user::UserId};
+
+use crate::{
+    mcp::resources::{INBOX_SUMMARY_URI, notification_uri, task_uri},
+    universal_inbox::UniversalInboxError,
+    utils::cache::Cache,
+};
+
+const UPDATES_CHANNEL: &str = "universal-inbox:mcp:resource-updates";
+const SUBSCRIPTIONS_NAMESPACE: &str = "universal-inbox:mcp:subscriptions:";
+const LISTENER_RETRY_DELAY: Duration = Duration::from_secs(5);
+/// Keeps published payloads below the 8000 bytes limit of PostgreSQL `NOTIFY`
+const MAX_URIS_PER_UPDATE: usize = 50;
+
+tokio::task_local! {
+    static PENDING_RESOURCE_UPDATES: RefCell<PendingResourceUpdates>;
+}
+
+/// `Mcp-Session-Id` of the HTTP request carrying an MCP message, inserted in
+/// the message extensions by the streamable HTTP service.
+#[derive(Clone, Debug)]
+pub struct McpSessionId(pub String);
+
+/// Resources of a user updated by a change to their notifications or tasks.
+/// The inbox summary is updated along with any of them.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ResourceUpdate {
+    pub user_id: UserId,
+    pub uris: Vec<String>,
+}
+
+impl ResourceUpdate {
+    fn new(user_id: UserId, uris: impl Iterator<Item = String>) -> Option<Self> {
+        let mut uris: Vec<String> = uris.collect();
+        if uris.is_empty() {
+            return None;
+        }
+        uris.push(INBOX_SUMMARY_URI.to_string());
+        Some(Self { user_id, uris })
+    }
+}
+
+#[derive(Clone)]
+pub struct ResourceUpdatePublisher {
+    cache: Cache,
+}
+
+impl ResourceUpdatePublisher {
+    pub fn new(cache: Cache) -> Self {
+        Self { cache }
+    }
+
+    pub async fn publish_notification_updates(
+        &self,
+        user_id: UserId,
+        notification_ids: impl IntoIterator<Item = NotificationId>,
+    ) {
+        if let Some(update) =
+            ResourceUpdate::new(user_id, notification_ids.into_iter().map(notification_uri))
+        {
+            self.publish_or_defer(update).await;
+        }
+    }
+
+    pub async fn publish_task_updates(
+        &self,
+        user_id: UserId,
+        task_ids: impl IntoIterator<Item = TaskId>,
+    ) {
+        if let Some(update) = ResourceUpdate::new(user_id, task_ids.into_iter().map(task_uri)) {
+            self.publish_or_defer(update).await;
+        }
+    }
+
+    async fn publish_or_defer(&self, update: ResourceUpdate) {
+        let mut update = Some(update);
+        let _ = PENDING_RESOURCE_UPDATES.try_with(|pending| {
+            if let Some(update) = update.take() {
+                let mut pending = pending.borrow_mut();
+                pending.publisher.get_or_insert_with(|| self.clone());
+                pending.add(update);
+            }
+        });
+        // Outside of `publish_resource_updates_after`, there is no caller to
+        // publish it later
+        if let Some(update) = update {
+            self.publish(update).await;
+        }
+    }
+
+    /// Subscribers are only notified on a best effort basis: failing to
+    /// publish an update must not fail the change that triggered it.
+    async fn publish(&self, update: ResourceUpdate) {
+        for uris in update.uris.chunks(MAX_URIS_PER_UPDATE) {
+            let payload = match serde_json::to_string(&ResourceUpdate {
+                user_id: update.user_id,
+                uris: uris.to_vec(),
+            }) {
+                Ok(payload) => payload,
+                Err(err) => {
+                    warn!("Failed to serialize MCP resource update: {err:?}");
+                    return;
+                }
+            };
+            if let Err(err) = self.cache.publish(UPDATES_CHANNEL, &payload).await {
+                warn!(
+                    user.id = update.user_id.to_string(),
+                    "Failed to publish MCP resource update: {err:?}"
+                );
+            }
+        }
+    }
+}
+
+/// Resource updates held back until the request, job or command which made
+/// them completed
+#[derive(Default)]
+struct PendingResourceUpdates {
+    publisher: Option<ResourceUpdatePublisher>,
+    uris_by_user: HashMap<UserId, BTreeSet<String>>,
+}
+
+impl PendingResourceUpdates {
+    fn add(&mut self, update: ResourceUpdate) {
+        self.uris_by_user
+            .entry(update.user_id)
+            .or_default()
+            .extend(update.uris);
+    }
+
+    fn into_updates(self) -> Vec<ResourceUpdate> {
+        self.uris_by_user
+            .into_iter()
+            .map(|(user_id, uris)| ResourceUpdate {
+                user_id,
+                uris: uris.into_iter().collect(),
+            })
+            .collect()
+    }
+}
+
+/// Run `future`, publishing the resource updates made while it runs once it
+/// completed, ie. after it committed its transactions. Updates of changes
+/// which were eventually rolled back are published as well: subscribers are
+/// only told to read the resources again.
+pub async fn publish_resource_updates_after<F: Future>(future: F) -> F::Output {
+    let (output, pending) = PENDING_RESOURCE_UPDATES
+        .scope(RefCell::default(), async move {
+            let output = future.await;
+            (
+                output,
+                PENDING_RESOURCE_UPDATES.with(|pending| pending.take()),
+            )
+        })
+        .await;
+    if let Some(publisher) = pending.publisher.clone() {
+        for update in pending.into_updates() {
+            publisher.publish(update).await;
+        }
+    }
+    output
+}
+
+/// Peers of the MCP sessions hosted by this pod, by session id
+type SessionPeers = HashMap<String, Peer<RoleServer>>;
+
+/// Subscriptions of the MCP sessions hosted by this pod.
+#[derive(Clone)]
+pub struct McpSubscriptions {
+    cache: Cache,
+    ttl_seconds: u64,
+    sessions: Arc<RwLock<HashMap<UserId, SessionPeers>>>,
+}
+
+impl McpSubscriptions {
+    pub fn new(cache: Cache, ttl_seconds: u64) -> Self {
+        Self {
+            cache,
+            ttl_seconds,
+            sessions: Default::default(),
+        }
+    }
+
+    fn key(session_id: &str) -> String {
+        format!("{SUBSCRIPTIONS_NAMESPACE}{session_id}")
+    }
+
+    /// Register `peer` as the way to reach the session `session_id` from this
+    /// pod.
+    pub async fn register_session(
+        &self,
+        user_id: UserId,
+        session_id: &McpSessionId,
+        peer: Peer<RoleServer>,
+    ) {
+        self.sessions
+            .write()
+            .await
+            .entry(user_id)
+            .or_default()
+            .insert(session_id.0.clone(), peer);
+    }
+
+    pub async fn subscribe(
+        &self,
+        session_id: &McpSessionId,
+        uri: &str,
+    ) -> Result<(), UniversalInboxError> {
+        self.cache
+            .add_set_member(
+                &Self::key(&session_id.0),
+                uri,
+                Duration::from_secs(self.ttl_seconds),
+            )
+            .await
+    }
+
+    pub async fn unsubscribe(
+        &self,
+        session_id: &McpSessionId,
+        uri: &str,
+    ) -> Result<(), UniversalInboxError> {
+        self.cache
+            .remove_set_member(&Self::key(&session_id.0), uri)
+            .await
+    }
+
+    /// Notify the sessions of `update.user_id` hosted by this pod of the
+    /// updated resources they subscribed to.
+    pub async fn dispatch(&self, update: &ResourceUpdate) {
+        let sessions: Vec<(String, Peer<RoleServer>)> =
+            match self.sessions.read().await.get(&update.user_id) {
+                Some(sessions) => sessions
+                    .iter()
+                    .map(|(session_id, peer)| (session_id.clone(), peer.clone()))
+                    .collect(),
+                None => return,
+            };
+
+        let mut closed_session_ids = vec![];
+        for (session_id, peer) in sessions {
+            if peer.is_transport_closed() {
+                closed_session_ids.push(session_id);
+                continue;
+            }
+
+            let subscribed_uris = match self.cache.fetch_set_members(&Self::key(&session_id)).await
+            {
+                Ok(subscribed_uris) => subscribed_uris,
+                Err(err) => {
+                    warn!("Failed to load MCP subscriptions of session {session_id}: {err:?}");
+                    continue;
+                }
+            };
+            for uri in update
+                .uris
+                .iter()
+                .filter(|uri| subscribed_uris.contains(uri))
+            {
+                debug!("Notifying MCP session {session_id} of the update of {uri}");
+                if let Err(err) = peer
+                    .notify_resource_updated(ResourceUpdatedNotificationParam::new(uri.clone()))
+                    .await
+                {
+                    warn!(
+                        "Failed to notify MCP session {session_id} of the update of {uri}: {err:?}"
+                    );
+                }
+            }
+        }
+
+        if !closed_session_ids.is_empty() {
+            let mut sessions = self.sessions.write().await;
+            if let Some(user_sessions) = sessions.get_mut(&update.user_id) {
+                for session_id in closed_session_ids {
+                    user_sessions.remove(&session_id);
+                }
+                if user_sessions.is_empty() {
+                    sessions.remove(&update.user_id);
+                }
+            }
+        }
+    }
+
+    /// Listen to the resource updates published by every pod and worker, and
+    /// dispatch them to the local sessions. Reconnects until the process exits.
+    pub async fn listen(self) {
+        loop {
+            if let Err(err) = self.listen_once().await {
+                error!("MCP resource updates listener failed, retrying: {err:?}");
+            }
+            tokio::time::sleep(LISTENER_RETRY_DELAY).await;
+        }
+    }
+
+    async fn listen_once(&self) -> Result<(), UniversalInboxError> {
+        let mut payloads = self.cache.subscribe(UPDATES_CHANNEL).await?;
+        while let Some(payload) = payloads.next().await {
+            let payload = payload?;
+            match serde_json::from_str::<ResourceUpdate>(&payload) {
+                Ok(update) => self.dispatch(&update).await,
+                Err(err) => warn!("Ignoring invalid MCP resource update `{payload}`: {err:?}"),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn resource_update_includes_the_inbox_summary() {
+        let user_id = UserId(Uuid::new_v4());
+        let notification_id = NotificationId(Uuid::new_v4());
+
+        assert_eq!(
+            ResourceUpdate::new(user_id, [notification_id].into_iter().map(notification_uri)),
+            Some(ResourceUpdate {
+                user_id,
+                uris: vec![
+                    format!("inbox://notifications/{notification_id}"),
+                    "inbox://summary".to_string()
+                ]
+            })
+        );
+        assert_eq!(ResourceUpdate::new(user_id, std::iter::empty()), None);
+    }
+
+    #[test]
+    fn pending_resource_updates_are_merged_per_user() {
+        let user_id = UserId(Uuid::new_v4());
+        let notification_id = NotificationId(Uuid::new_v4());
+        let task_id = TaskId(Uuid::new_v4());
+        let mut pending = PendingResourceUpdates::default();
+
+        for update in [
+            ResourceUpdate::new(user_id, [notification_id].into_iter().map(notification_uri)),
+            ResourceUpdate::new(user_id, [task_id].into_iter().map(task_uri)),
+            ResourceUpdate::new(user_id, [notification_id].into_iter().map(notification_uri)),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            pending.add(update);
+        }
+
+        assert_eq!(
+            pending.into_updates(),
+            vec![ResourceUpdate {
+                user_id,
+                uris: vec![
+                    format!("inbox://notifications/{notification_id}"),
+                    "inbox://summary".to_string(),
+                    format!("inbox://tasks/{task_id}"),
+                ]
+            }]
+        );
+    }
+}
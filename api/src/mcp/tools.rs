@@ -1,8 +1,8 @@
-use std::sync::Arc;
+use std::{collections::BTreeSet, sync::Arc};
 
 use anyhow::{Context, anyhow};
-use apalis_redis::RedisStorage;
 use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use rmcp::{handler::server::tool::schema_for_output, model::JsonObject};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -11,22 +11,39 @@ use tokio::sync::RwLock;
 
 use universal_inbox::{
     Page, PageToken,
+    auth::oauth2::OAuth2Scope,
+    integration_connection::{
+        IntegrationConnectionStatus, IntegrationConnectionSummary,
+        provider::IntegrationProviderKind,
+    },
     notification::{
         Notification, NotificationId, NotificationListOrder, NotificationSourceKind,
         NotificationStatus, NotificationSyncSourceKind, NotificationWithTask,
-        NotificationWithTaskSummary, service::NotificationPatch,
+        NotificationWithTaskSummary,
+        service::{
+            GithubPullRequestAction, InvitationPatch, LinearIssueAction, NotificationPatch,
+            SnoozePreset,
+        },
     },
     task::{
-        Task, TaskCreation, TaskCreationResult, TaskId, TaskStatus, TaskSummary,
+        ProjectSummary, Task, TaskCreation, TaskCreationResult, TaskId, TaskStatus, TaskSummary,
         TaskSummaryWithStatus, TaskSyncSourceKind, service::TaskPatch,
     },
+    third_party::integrations::{
+        google_calendar::GoogleCalendarEventAttendeeResponseStatus, linear::LinearTeamDetails,
+    },
     user::UserId,
 };
 
 use crate::{
-    jobs::UniversalInboxJob,
+    jobs::storage::JobStorage,
     universal_inbox::{
-        UpdateStatus, notification::service::NotificationService, task::service::TaskService,
+        UpdateStatus, integration_connection::service::IntegrationConnectionService,
+        notification::service::NotificationService, task::service::TaskService,
+    },
+    utils::jwt::{
+        Claims, check_scopes, restrict_notification_source_kinds,
+        restrict_notification_sync_source_kinds,
     },
 };
 
@@ -34,13 +51,15 @@ use crate::{
 pub struct McpServices {
     pub notification_service: Arc<RwLock<NotificationService>>,
     pub task_service: Arc<RwLock<TaskService>>,
-    pub job_storage: RedisStorage<UniversalInboxJob>,
+    pub integration_connection_service: Arc<RwLock<IntegrationConnectionService>>,
+    pub job_storage: JobStorage,
 }
 
 pub enum ToolCallError {
     UnknownTool(String),
     InvalidArguments(anyhow::Error),
     Execution(anyhow::Error),
+    InsufficientScope(Vec<OAuth2Scope>),
 }
 
 impl ToolCallError {
@@ -56,6 +75,38 @@ impl ToolCallError {
     }
 }
 
+/// Scopes an OAuth2 token must hold to call the `name` tool. Tools triggering
+/// a synchronization on demand additionally require `sync`.
+pub(crate) fn tool_required_scopes(name: &str) -> &'static [OAuth2Scope] {
+    match name {
+        "list_notifications" | "get_notification" | "get_linear_issue_team" => {
+            &[OAuth2Scope::NotificationsRead]
+        }
+        "act_on_notification"
+        | "act_on_github_pull_request"
+        | "act_on_linear_issue"
+        | "bulk_act_notifications"
+        | "snooze_notification"
+        | "answer_calendar_invitation" => &[OAuth2Scope::NotificationsWrite],
+        "create_task_from_notification" | "link_notification_to_task" => {
+            &[OAuth2Scope::NotificationsWrite, OAuth2Scope::TasksWrite]
+        }
+        "list_tasks" | "get_task" | "search_tasks" | "search_projects" => &[OAuth2Scope::TasksRead],
+        "create_task" | "update_task" => &[OAuth2Scope::TasksWrite],
+        "list_integration_connections" => &[OAuth2Scope::IntegrationsRead],
+        "sync_notifications" | "sync_tasks" => &[OAuth2Scope::Sync],
+        _ => &[],
+    }
+}
+
+fn require_scopes(
+    granted_scopes: Option<&BTreeSet<OAuth2Scope>>,
+    required_scopes: &[OAuth2Scope],
+) -> Result<(), ToolCallError> {
+    check_scopes(granted_scopes, required_scopes)
+        .map_err(|_| ToolCallError::InsufficientScope(required_scopes.to_vec()))
+}
+
 fn default_true() -> bool {
     true
 }
@@ -69,6 +120,14 @@ pub(crate) enum NotificationAction {
     SnoozeUntil,
 }
 
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum InvitationResponse {
+    Accept,
+    Tentative,
+    Decline,
+}
+
 #[derive(Deserialize, Serialize, JsonSchema)]
 pub(crate) struct ListNotificationsArgs {
     #[serde(default)]
@@ -100,6 +159,45 @@ pub(crate) struct ActOnNotificationArgs {
     snoozed_until: Option<DateTime<Utc>>,
 }
 
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub(crate) struct SnoozeNotificationArgs {
+    notification_id: NotificationId,
+    preset: SnoozePreset,
+    #[schemars(
+        description = "IANA time zone of the user (e.g. `Europe/Paris`) in which presets are resolved. Defaults to UTC."
+    )]
+    timezone: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub(crate) struct AnswerCalendarInvitationArgs {
+    notification_id: NotificationId,
+    response: InvitationResponse,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub(crate) struct ActOnGithubPullRequestArgs {
+    notification_id: NotificationId,
+    #[schemars(
+        description = "`body` is required for `request_changes` and `comment`, `merge_method` for `merge`."
+    )]
+    action: GithubPullRequestAction,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub(crate) struct GetLinearIssueTeamArgs {
+    notification_id: NotificationId,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub(crate) struct ActOnLinearIssueArgs {
+    notification_id: NotificationId,
+    #[schemars(
+        description = "Workflow state and assignee ids come from get_linear_issue_team. A null `due_date` clears the due date."
+    )]
+    action: LinearIssueAction,
+}
+
 #[derive(Deserialize, Serialize, JsonSchema)]
 pub(crate) struct BulkActNotificationsArgs {
     #[serde(default)]
@@ -123,6 +221,12 @@ pub(crate) struct CreateTaskFromNotificationArgs {
     task_creation: Option<TaskCreation>,
 }
 
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub(crate) struct LinkNotificationToTaskArgs {
+    notification_id: NotificationId,
+    task_id: TaskId,
+}
+
 #[derive(Deserialize, Serialize, JsonSchema)]
 pub(crate) struct SyncNotificationsArgs {
     source: Option<NotificationSyncSourceKind>,
@@ -148,6 +252,22 @@ pub(crate) struct SearchTasksArgs {
     matches: String,
 }
 
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub(crate) struct CreateTaskArgs {
+    #[schemars(
+        description = "The task is created in the user's default task manager unless `task_provider_kind` is set. Project names come from search_projects."
+    )]
+    task_creation: TaskCreation,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub(crate) struct SearchProjectsArgs {
+    #[schemars(length(min = 1))]
+    matches: String,
+    #[schemars(description = "Defaults to the user's default task manager.")]
+    task_provider_kind: Option<IntegrationProviderKind>,
+}
+
 #[derive(Deserialize, Serialize, JsonSchema)]
 pub(crate) struct UpdateTaskArgs {
     task_id: TaskId,
@@ -159,15 +279,29 @@ pub(crate) struct SyncTasksArgs {
     source: Option<TaskSyncSourceKind>,
 }
 
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub(crate) struct ListIntegrationConnectionsArgs {
+    status: Option<IntegrationConnectionStatus>,
+}
+
 pub async fn execute_tool(
     name: &str,
     arguments: Option<Value>,
     services: &McpServices,
     user_id: UserId,
+    claims: &Claims,
 ) -> Result<Value, ToolCallError> {
+    let granted_scopes = claims.granted_scopes();
+    let granted_scopes = granted_scopes.as_ref();
+    let allowed_sources = claims.notification_source_kinds.as_deref();
+    require_scopes(granted_scopes, tool_required_scopes(name))?;
+
     match name {
         "list_notifications" => {
             let args: ListNotificationsArgs = parse_args(arguments)?;
+            if args.trigger_sync {
+                require_scopes(granted_scopes, &[OAuth2Scope::Sync])?;
+            }
             let service = services.notification_service.read().await;
             let mut transaction = service.begin().await.map_err(ToolCallError::execution)?;
             let page: Page<NotificationWithTask> = service
@@ -179,7 +313,8 @@ pub async fn execute_tool(
                     args.task_id,
                     args.order_by
                         .unwrap_or(NotificationListOrder::UpdatedAtDesc),
-                    args.sources,
+                    restrict_notification_source_kinds(allowed_sources, args.sources)
+                        .map_err(ToolCallError::execution)?,
                     args.page_token,
                     user_id,
                     args.trigger_sync.then(|| services.job_storage.clone()),
@@ -199,6 +334,15 @@ pub async fn execute_tool(
             let args: GetNotificationArgs = parse_args(arguments)?;
             let service = services.notification_service.read().await;
             let mut transaction = service.begin().await.map_err(ToolCallError::execution)?;
+            service
+                .check_notification_source_access(
+                    &mut transaction,
+                    args.notification_id,
+                    user_id,
+                    allowed_sources,
+                )
+                .await
+                .map_err(ToolCallError::execution)?;
             let notification = service
                 .get_notification(&mut transaction, args.notification_id, user_id)
                 .await
@@ -218,6 +362,63 @@ pub async fn execute_tool(
             let patch = notification_patch_from_action(args.action, args.snoozed_until)?;
             let service = services.notification_service.read().await;
             let mut transaction = service.begin().await.map_err(ToolCallError::execution)?;
+            service
+                .check_notification_source_access(
+                    &mut transaction,
+                    args.notification_id,
+                    user_id,
+                    allowed_sources,
+                )
+                .await
+                .map_err(ToolCallError::execution)?;
+            let updated = service
+                .patch_notification(
+                    &mut transaction,
+                    args.notification_id,
+                    &patch,
+                    true,
+                    true,
+                    user_id,
+                )
+                .await
+                .map_err(ToolCallError::execution)?;
+            transaction
+                .commit()
+                .await
+                .map_err(ToolCallError::execution)?;
+            serialize_update_status_notification(updated, args.notification_id)
+        }
+        "snooze_notification" => {
+            let args: SnoozeNotificationArgs = parse_args(arguments)?;
+            let timezone = args
+                .timezone
+                .as_deref()
+                .map(|timezone| {
+                    timezone
+                        .parse::<Tz>()
+                        .map_err(|_| anyhow!("Unknown time zone `{timezone}`"))
+                        .map_err(ToolCallError::invalid_arguments)
+                })
+                .transpose()?
+                .unwrap_or(Tz::UTC);
+            let patch = NotificationPatch {
+                snoozed_until: Some(
+                    args.preset
+                        .snoozed_until(Utc::now().with_timezone(&timezone)),
+                ),
+                ..Default::default()
+            };
+            let service = services.notification_service.read().await;
+            let mut transaction = service.begin().await.map_err(ToolCallError::execution)?;
+            service
+                .check_notification_source_access(
+                    &mut transaction,
+                    args.notification_id,
+                    user_id,
+                    allowed_sources,
+                )
+                .await
+                .map_err(ToolCallError::execution)?;
             let updated = service
                 .patch_notification(
                     &mut transaction,
@@ -235,6 +436,130 @@ pub async fn execute_tool(
                 .map_err(ToolCallError::execution)?;
             serialize_update_status_notification(updated, args.notification_id)
         }
+        "answer_calendar_invitation" => {
+            let args: AnswerCalendarInvitationArgs = parse_args(arguments)?;
+            let patch = InvitationPatch {
+                response_status: match args.response {
+                    InvitationResponse::Accept => {
+                        GoogleCalendarEventAttendeeResponseStatus::Accepted
+                    }
+                    InvitationResponse::Tentative => {
+                        GoogleCalendarEventAttendeeResponseStatus::Tentative
+                    }
+                    InvitationResponse::Decline => {
+                        GoogleCalendarEventAttendeeResponseStatus::Declined
+                    }
+                },
+            };
+            let service = services.notification_service.read().await;
+            let mut transaction = service.begin().await.map_err(ToolCallError::execution)?;
+            service
+                .check_notification_source_access(
+                    &mut transaction,
+                    args.notification_id,
+                    user_id,
+                    allowed_sources,
+                )
+                .await
+                .map_err(ToolCallError::execution)?;
+            let updated = service
+                .update_invitation_from_notification(
+                    &mut transaction,
+                    args.notification_id,
+                    &patch,
+                    user_id,
+                )
+                .await
+                .map_err(ToolCallError::execution)?;
+            transaction
+                .commit()
+                .await
+                .map_err(ToolCallError::execution)?;
+            serialize_update_status_notification(updated, args.notification_id)
+        }
+        "act_on_github_pull_request" => {
+            let args: ActOnGithubPullRequestArgs = parse_args(arguments)?;
+            let service = services.notification_service.read().await;
+            let mut transaction = service.begin().await.map_err(ToolCallError::execution)?;
+            service
+                .check_notification_source_access(
+                    &mut transaction,
+                    args.notification_id,
+                    user_id,
+                    allowed_sources,
+                )
+                .await
+                .map_err(ToolCallError::execution)?;
+            let updated = service
+                .apply_github_pull_request_action(
+                    &mut transaction,
+                    args.notification_id,
+                    &args.action,
+                    user_id,
+                )
+                .await
+                .map_err(ToolCallError::execution)?;
+            transaction
+                .commit()
+                .await
+                .map_err(ToolCallError::execution)?;
+            serialize_update_status_notification(updated, args.notification_id)
+        }
+        "get_linear_issue_team" => {
+            let args: GetLinearIssueTeamArgs = parse_args(arguments)?;
+            let service = services.notification_service.read().await;
+            let mut transaction = service.begin().await.map_err(ToolCallError::execution)?;
+            service
+                .check_notification_source_access(
+                    &mut transaction,
+                    args.notification_id,
+                    user_id,
+                    allowed_sources,
+                )
+                .await
+                .map_err(ToolCallError::execution)?;
+            let team_details = service
+                .get_linear_issue_team_details(&mut transaction, args.notification_id, user_id)
+                .await
+                .map_err(ToolCallError::execution)?
+                .ok_or_else(|| anyhow!("Notification {} was not found", args.notification_id))
+                .map_err(ToolCallError::execution)?;
+            transaction
+                .commit()
+                .await
+                .map_err(ToolCallError::execution)?;
+            serde_json::to_value(team_details)
+                .context("Failed to serialize Linear team details")
+                .map_err(ToolCallError::execution)
+        }
+        "act_on_linear_issue" => {
+            let args: ActOnLinearIssueArgs = parse_args(arguments)?;
+            let service = services.notification_service.read().await;
+            let mut transaction = service.begin().await.map_err(ToolCallError::execution)?;
+            service
+                .check_notification_source_access(
+                    &mut transaction,
+                    args.notification_id,
+                    user_id,
+                    allowed_sources,
+                )
+                .await
+                .map_err(ToolCallError::execution)?;
+            let updated = service
+                .apply_linear_issue_action(
+                    &mut transaction,
+                    args.notification_id,
+                    &args.action,
+                    user_id,
+                )
+                .await
+                .map_err(ToolCallError::execution)?;
+            transaction
+                .commit()
+                .await
+                .map_err(ToolCallError::execution)?;
+            serialize_update_status_notification(updated, args.notification_id)
+        }
         "bulk_act_notifications" => {
             let args: BulkActNotificationsArgs = parse_args(arguments)?;
             let patch = bulk_notification_patch(args.action);
@@ -243,14 +568,15 @@ pub async fn execute_tool(
             } else {
                 args.statuses
             };
-            let source_filters = if args.sources.is_empty() {
+            let source_filters = restrict_notification_source_kinds(allowed_sources, args.sources)
+                .map_err(ToolCallError::execution)?;
+            let source_filters = if source_filters.is_empty() {
                 all_notification_sources()
             } else {
-                args.sources
+                source_filters
             };
             let service = services.notification_service.read().await;
             let mut transaction = service.begin().await.map_err(ToolCallError::execution)?;
-            let mut storage = services.job_storage.clone();
             let notifications = service
                 .patch_notifications_bulk(
                     &mut transaction,
@@ -258,7 +584,7 @@ pub async fn execute_tool(
                     source_filters,
                     &patch,
                     user_id,
-                    &mut storage,
+                    &services.job_storage,
                 )
                 .await
                 .map_err(ToolCallError::execution)?;
@@ -279,6 +605,15 @@ pub async fn execute_tool(
             let args: CreateTaskFromNotificationArgs = parse_args(arguments)?;
             let service = services.notification_service.read().await;
             let mut transaction = service.begin().await.map_err(ToolCallError::execution)?;
+            service
+                .check_notification_source_access(
+                    &mut transaction,
+                    args.notification_id,
+                    user_id,
+                    allowed_sources,
+                )
+                .await
+                .map_err(ToolCallError::execution)?;
             let notification = service
                 .create_task_from_notification(
                     &mut transaction,
@@ -297,20 +632,69 @@ pub async fn execute_tool(
                 .map_err(ToolCallError::execution)?;
             serialize_result(CreateTaskFromNotificationResult { notification })
         }
+        "link_notification_to_task" => {
+            let args: LinkNotificationToTaskArgs = parse_args(arguments)?;
+            // Same patch as linking a notification from the web application:
+            // the notification leaves the inbox and is added to the task body
+            let patch = NotificationPatch {
+                status: Some(NotificationStatus::Deleted),
+                task_id: Some(args.task_id),
+                ..Default::default()
+            };
+            let service = services.notification_service.read().await;
+            let mut transaction = service.begin().await.map_err(ToolCallError::execution)?;
+            service
+                .check_notification_source_access(
+                    &mut transaction,
+                    args.notification_id,
+                    user_id,
+                    allowed_sources,
+                )
+                .await
+                .map_err(ToolCallError::execution)?;
+            let notification = service
+                .patch_notification(
+                    &mut transaction,
+                    args.notification_id,
+                    &patch,
+                    true,
+                    true,
+                    user_id,
+                )
+                .await
+                .map_err(ToolCallError::execution)?
+                .result
+                .ok_or_else(|| anyhow!("Notification {} was not updated", args.notification_id))
+                .map_err(ToolCallError::execution)?;
+            let task = services
+                .task_service
+                .read()
+                .await
+                .get_task(&mut transaction, args.task_id, user_id)
+                .await
+                .map_err(ToolCallError::execution)?
+                .ok_or_else(|| anyhow!("Task {} was not found", args.task_id))
+                .map_err(ToolCallError::execution)?;
+            transaction
+                .commit()
+                .await
+                .map_err(ToolCallError::execution)?;
+            serialize_result(LinkNotificationToTaskResult {
+                notification: *notification,
+                task,
+            })
+        }
         "sync_notifications" => {
             let args: SyncNotificationsArgs = parse_args(arguments)?;
-            let service = services.notification_service.read().await;
-            let notifications: Vec<Notification> = if let Some(source) = args.source {
-                service
-                    .sync_notifications_with_transaction(source, user_id, false)
-                    .await
-                    .map_err(ToolCallError::execution)?
-            } else {
-                service
-                    .sync_all_notifications(user_id, false)
-                    .await
-                    .map_err(ToolCallError::execution)?
-            };
+            let sources = restrict_notification_sync_source_kinds(allowed_sources, args.source)
+                .map_err(ToolCallError::execution)?;
+            let notifications: Vec<Notification> = services
+                .notification_service
+                .read()
+                .await
+                .sync_notifications_from_sources(sources, user_id, false)
+                .await
+                .map_err(ToolCallError::execution)?;
             let notifications: Vec<NotificationWithTaskSummary> = notifications
                 .into_iter()
                 .map(NotificationWithTaskSummary::from)
@@ -322,6 +706,9 @@ pub async fn execute_tool(
         }
         "list_tasks" => {
             let args: ListTasksArgs = parse_args(arguments)?;
+            if args.trigger_sync {
+                require_scopes(granted_scopes, &[OAuth2Scope::Sync])?;
+            }
             let service = services.task_service.read().await;
             let mut transaction = service.begin().await.map_err(ToolCallError::execution)?;
             let page: Page<Task> = service
@@ -389,6 +776,43 @@ pub async fn execute_tool(
                 .map_err(ToolCallError::execution)?;
             serialize_update_status_task(updated, args.task_id)
         }
+        "create_task" => {
+            let args: CreateTaskArgs = parse_args(arguments)?;
+            let service = services.task_service.read().await;
+            let mut transaction = service.begin().await.map_err(ToolCallError::execution)?;
+            let task = service
+                .create_task_in_task_manager(&mut transaction, &args.task_creation, user_id)
+                .await
+                .map_err(ToolCallError::execution)?;
+            transaction
+                .commit()
+                .await
+                .map_err(ToolCallError::execution)?;
+            serde_json::to_value(task)
+                .context("Failed to serialize task")
+                .map_err(ToolCallError::execution)
+        }
+        "search_projects" => {
+            let args: SearchProjectsArgs = parse_args(arguments)?;
+            let service = services.task_service.read().await;
+            let mut transaction = service.begin().await.map_err(ToolCallError::execution)?;
+            let task_provider_kind = match args.task_provider_kind {
+                Some(task_provider_kind) => task_provider_kind,
+                None => service
+                    .get_default_task_provider_kind(&mut transaction, user_id)
+                    .await
+                    .map_err(ToolCallError::execution)?,
+            };
+            let projects = service
+                .search_projects(&mut transaction, &args.matches, user_id, task_provider_kind)
+                .await
+                .map_err(ToolCallError::execution)?;
+            transaction
+                .commit()
+                .await
+                .map_err(ToolCallError::execution)?;
+            serialize_result(SearchProjectsResult { projects })
+        }
         "sync_tasks" => {
             let args: SyncTasksArgs = parse_args(arguments)?;
             let service = services.task_service.read().await;
@@ -412,6 +836,25 @@ pub async fn execute_tool(
                 tasks,
             })
         }
+        "list_integration_connections" => {
+            let args: ListIntegrationConnectionsArgs = parse_args(arguments)?;
+            let service = services.integration_connection_service.read().await;
+            let mut transaction = service.begin().await.map_err(ToolCallError::execution)?;
+            let integration_connections = service
+                .fetch_all_integration_connections(&mut transaction, user_id, args.status, false)
+                .await
+                .map_err(ToolCallError::execution)?;
+            transaction
+                .commit()
+                .await
+                .map_err(ToolCallError::execution)?;
+            serialize_result(ListIntegrationConnectionsResult {
+                integration_connections: integration_connections
+                    .into_iter()
+                    .map(IntegrationConnectionSummary::from)
+                    .collect(),
+            })
+        }
         _ => Err(ToolCallError::UnknownTool(name.to_string())),
     }
 }
@@ -549,6 +992,12 @@ pub struct CreateTaskFromNotificationResult {
     pub notification: NotificationWithTask,
 }
 
+#[derive(Serialize, JsonSchema)]
+pub struct LinkNotificationToTaskResult {
+    pub notification: Notification,
+    pub task: Task,
+}
+
 #[derive(Serialize, JsonSchema)]
 pub struct SyncNotificationsResult {
     pub count: usize,
@@ -560,12 +1009,22 @@ pub struct SearchTasksResult {
     pub tasks: Vec<TaskSummary>,
 }
 
+#[derive(Serialize, JsonSchema)]
+pub struct SearchProjectsResult {
+    pub projects: Vec<ProjectSummary>,
+}
+
 #[derive(Serialize, JsonSchema)]
 pub struct SyncTasksResult {
     pub count: usize,
     pub tasks: Vec<TaskSummaryWithStatus>,
 }
 
+#[derive(Serialize, JsonSchema)]
+pub struct ListIntegrationConnectionsResult {
+    pub integration_connections: Vec<IntegrationConnectionSummary>,
+}
+
 fn serialize_result<T: Serialize>(value: T) -> Result<Value, ToolCallError> {
     serde_json::to_value(value)
         .context("Failed to serialize tool result")
@@ -589,6 +1048,26 @@ pub(crate) fn act_on_notification_output_schema() -> Arc<JsonObject> {
     output_schema_for::<Notification>("act_on_notification")
 }
 
+pub(crate) fn snooze_notification_output_schema() -> Arc<JsonObject> {
+    output_schema_for::<Notification>("snooze_notification")
+}
+
+pub(crate) fn answer_calendar_invitation_output_schema() -> Arc<JsonObject> {
+    output_schema_for::<Notification>("answer_calendar_invitation")
+}
+
+pub(crate) fn act_on_github_pull_request_output_schema() -> Arc<JsonObject> {
+    output_schema_for::<Notification>("act_on_github_pull_request")
+}
+
+pub(crate) fn get_linear_issue_team_output_schema() -> Arc<JsonObject> {
+    output_schema_for::<LinearTeamDetails>("get_linear_issue_team")
+}
+
+pub(crate) fn act_on_linear_issue_output_schema() -> Arc<JsonObject> {
+    output_schema_for::<Notification>("act_on_linear_issue")
+}
+
 pub(crate) fn bulk_act_notifications_output_schema() -> Arc<JsonObject> {
     output_schema_for::<BulkActResult>("bulk_act_notifications")
 }
@@ -597,6 +1076,10 @@ pub(crate) fn create_task_from_notification_output_schema() -> Arc<JsonObject> {
     output_schema_for::<CreateTaskFromNotificationResult>("create_task_from_notification")
 }
 
+pub(crate) fn link_notification_to_task_output_schema() -> Arc<JsonObject> {
+    output_schema_for::<LinkNotificationToTaskResult>("link_notification_to_task")
+}
+
 pub(crate) fn sync_notifications_output_schema() -> Arc<JsonObject> {
     output_schema_for::<SyncNotificationsResult>("sync_notifications")
 }
@@ -617,10 +1100,22 @@ pub(crate) fn update_task_output_schema() -> Arc<JsonObject> {
     output_schema_for::<Task>("update_task")
 }
 
+pub(crate) fn create_task_output_schema() -> Arc<JsonObject> {
+    output_schema_for::<Task>("create_task")
+}
+
+pub(crate) fn search_projects_output_schema() -> Arc<JsonObject> {
+    output_schema_for::<SearchProjectsResult>("search_projects")
+}
+
 pub(crate) fn sync_tasks_output_schema() -> Arc<JsonObject> {
     output_schema_for::<SyncTasksResult>("sync_tasks")
 }
 
+pub(crate) fn list_integration_connections_output_schema() -> Arc<JsonObject> {
+    output_schema_for::<ListIntegrationConnectionsResult>("list_integration_connections")
+}
+
 #[cfg(test)]
 mod output_schema_tests {
     use super::*;
@@ -662,6 +1157,24 @@ mod output_schema_tests {
         assert_object_with_keys(&schema, &["id", "status", "source_item"]);
     }
 
+    #[test]
+    fn act_on_github_pull_request_output_schema_shape() {
+        let schema = act_on_github_pull_request_output_schema();
+        assert_object_with_keys(&schema, &["id", "status", "source_item"]);
+    }
+
+    #[test]
+    fn get_linear_issue_team_output_schema_shape() {
+        let schema = get_linear_issue_team_output_schema();
+        assert_object_with_keys(&schema, &["workflow_states", "members"]);
+    }
+
+    #[test]
+    fn act_on_linear_issue_output_schema_shape() {
+        let schema = act_on_linear_issue_output_schema();
+        assert_object_with_keys(&schema, &["id", "status", "source_item"]);
+    }
+
     #[test]
     fn bulk_act_notifications_output_schema_shape() {
         let schema = bulk_act_notifications_output_schema();
@@ -710,6 +1223,42 @@ mod output_schema_tests {
         assert_object_with_keys(&schema, &["count", "tasks"]);
     }
 
+    #[test]
+    fn snooze_notification_output_schema_shape() {
+        let schema = snooze_notification_output_schema();
+        assert_object_with_keys(&schema, &["id", "status", "source_item"]);
+    }
+
+    #[test]
+    fn answer_calendar_invitation_output_schema_shape() {
+        let schema = answer_calendar_invitation_output_schema();
+        assert_object_with_keys(&schema, &["id", "status", "source_item"]);
+    }
+
+    #[test]
+    fn link_notification_to_task_output_schema_shape() {
+        let schema = link_notification_to_task_output_schema();
+        assert_object_with_keys(&schema, &["notification", "task"]);
+    }
+
+    #[test]
+    fn create_task_output_schema_shape() {
+        let schema = create_task_output_schema();
+        assert_object_with_keys(&schema, &["id", "title", "status", "source_item"]);
+    }
+
+    #[test]
+    fn search_projects_output_schema_shape() {
+        let schema = search_projects_output_schema();
+        assert_object_with_keys(&schema, &["projects"]);
+    }
+
+    #[test]
+    fn list_integration_connections_output_schema_shape() {
+        let schema = list_integration_connections_output_schema();
+        assert_object_with_keys(&schema, &["integration_connections"]);
+    }
+
     #[test]
     #[ignore = "manual visual inspection only"]
     fn dump_notification_schema() {
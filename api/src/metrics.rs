@@ -0,0 +1,373 @@
+//! Application metrics, used to alert on integration outages.
+//!
+//! Each metric is recorded both through the OpenTelemetry global meter, exported
+//! over OTLP when tracing is configured, and in a Prometheus registry which the
+//! workers can serve on `/metrics`. Both use the same metric and label names.
+
+use std::time::Duration;
+
+use anyhow::Context;
+use once_cell::sync::Lazy;
+use opentelemetry::{
+    KeyValue, global,
+    metrics::{Counter as OtelCounter, Gauge as OtelGauge, Histogram as OtelHistogram, Meter},
+};
+use prometheus::{
+    HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+
+use universal_inbox::integration_connection::{
+    provider::IntegrationProviderKind,
+    sync_run::{IntegrationConnectionSyncRun, IntegrationConnectionSyncRunKind},
+};
+
+use crate::universal_inbox::UniversalInboxError;
+
+static METRICS: Lazy<Metrics> = Lazy::new(Metrics::new);
+
+const DURATION_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0,
+];
+
+struct Metrics {
+    registry: Registry,
+    sync_duration: Histogram,
+    sync_items: Counter,
+    sync_failures: Counter,
+    job_duration: Histogram,
+    job_queue_depth: Gauge,
+    oauth_token_refreshes: Counter,
+    http_request_duration: Histogram,
+    mcp_tool_calls: Counter,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let meter = global::meter("universal-inbox");
+        let registry = Registry::new();
+
+        Self {
+            sync_duration: Histogram::new(
+                &meter,
+                &registry,
+                "universal_inbox_sync_duration_seconds",
+                "Duration of the synchronizations of integration connections",
+                &["provider", "kind", "outcome"],
+            ),
+            sync_items: Counter::new(
+                &meter,
+                &registry,
+                "universal_inbox_sync_items_total",
+                "Items processed by the synchronizations of integration connections",
+                &["provider", "kind", "operation"],
+            ),
+            sync_failures: Counter::new(
+                &meter,
+                &registry,
+                "universal_inbox_sync_failures_total",
+                "Failed synchronizations of integration connections",
+                &["provider", "kind", "error_kind"],
+            ),
+            job_duration: Histogram::new(
+                &meter,
+                &registry,
+                "universal_inbox_job_duration_seconds",
+                "Duration of the asynchronous jobs, including their retries",
+                &["job", "outcome"],
+            ),
+            job_queue_depth: Gauge::new(
+                &meter,
+                &registry,
+                "universal_inbox_job_queue_depth",
+                "Number of asynchronous jobs waiting in the queue",
+                &["queue"],
+            ),
+            oauth_token_refreshes: Counter::new(
+                &meter,
+                &registry,
+                "universal_inbox_oauth_token_refreshes_total",
+                "Refreshes of OAuth tokens of integration connections",
+                &["provider", "outcome"],
+            ),
+            http_request_duration: Histogram::new(
+                &meter,
+                &registry,
+                "universal_inbox_http_request_duration_seconds",
+                "Duration of the HTTP requests",
+                &["method", "route", "status"],
+            ),
+            mcp_tool_calls: Counter::new(
+                &meter,
+                &registry,
+                "universal_inbox_mcp_tool_calls_total",
+                "Calls of the MCP tools",
+                &["tool", "outcome"],
+            ),
+            registry,
+        }
+    }
+}
+
+struct Counter {
+    otel: OtelCounter<u64>,
+    prometheus: IntCounterVec,
+    labels: &'static [&'static str],
+}
+
+impl Counter {
+    fn new(
+        meter: &Meter,
+        registry: &Registry,
+        name: &'static str,
+        description: &'static str,
+        labels: &'static [&'static str],
+    ) -> Self {
+        let prometheus = IntCounterVec::new(Opts::new(name, description), labels)
+            .expect("Invalid Prometheus counter");
+        registry
+            .register(Box::new(prometheus.clone()))
+            .expect("Failed to register Prometheus counter");
+
+        Self {
+            otel: meter
+                .u64_counter(name)
+                .with_description(description)
+                .build(),
+            prometheus,
+            labels,
+        }
+    }
+
+    fn add(&self, value: u64, label_values: &[&str]) {
+        self.otel.add(value, &key_values(self.labels, label_values));
+        self.prometheus
+            .with_label_values(label_values)
+            .inc_by(value);
+    }
+}
+
+struct Histogram {
+    otel: OtelHistogram<f64>,
+    prometheus: HistogramVec,
+    labels: &'static [&'static str],
+}
+
+impl Histogram {
+    fn new(
+        meter: &Meter,
+        registry: &Registry,
+        name: &'static str,
+        description: &'static str,
+        labels: &'static [&'static str],
+    ) -> Self {
+        let prometheus = HistogramVec::new(
+            HistogramOpts::new(name, description).buckets(DURATION_BUCKETS.to_vec()),
+            labels,
+        )
+        .expect("Invalid Prometheus histogram");
+        registry
+            .register(Box::new(prometheus.clone()))
+            .expect("Failed to register Prometheus histogram");
+
+        Self {
+            otel: meter
+                .f64_histogram(name)
+                .with_description(description)
+                .with_unit("s")
+                .with_boundaries(DURATION_BUCKETS.to_vec())
+                .build(),
+            prometheus,
+            labels,
+        }
+    }
+
+    fn record(&self, duration: Duration, label_values: &[&str]) {
+        let value = duration.as_secs_f64();
+        self.otel
+            .record(value, &key_values(self.labels, label_values));
+        self.prometheus
+            .with_label_values(label_values)
+            .observe(value);
+    }
+}
+
+struct Gauge {
+    otel: OtelGauge<i64>,
+    prometheus: IntGaugeVec,
+    labels: &'static [&'static str],
+}
+
+impl Gauge {
+    fn new(
+        meter: &Meter,
+        registry: &Registry,
+        name: &'static str,
+        description: &'static str,
+        labels: &'static [&'static str],
+    ) -> Self {
+        let prometheus = IntGaugeVec::new(Opts::new(name, description), labels)
+            .expect("Invalid Prometheus gauge");
+        registry
+            .register(Box::new(prometheus.clone()))
+            .expect("Failed to register Prometheus gauge");
+
+        Self {
+            otel: meter.i64_gauge(name).with_description(description).build(),
+            prometheus,
+            labels,
+        }
+    }
+
+    fn set(&self, value: i64, label_values: &[&str]) {
+        self.otel
+            .record(value, &key_values(self.labels, label_values));
+        self.prometheus.with_label_values(label_values).set(value);
+    }
+}
+
+fn key_values(labels: &[&'static str], label_values: &[&str]) -> Vec<KeyValue> {
+    labels
+        .iter()
+        .zip(label_values)
+        .map(|(label, value)| KeyValue::new(*label, value.to_string()))
+        .collect()
+}
+
+/// Render all metrics in the Prometheus text exposition format
+pub fn render_prometheus_metrics() -> Result<String, UniversalInboxError> {
+    Ok(TextEncoder::new()
+        .encode_to_string(&METRICS.registry.gather())
+        .context("Failed to encode metrics in the Prometheus format")?)
+}
+
+pub fn record_sync_run(
+    provider_kind: IntegrationProviderKind,
+    sync_run: &IntegrationConnectionSyncRun,
+    error: Option<&UniversalInboxError>,
+) {
+    let provider = provider_kind.to_string();
+    let kind = match sync_run.kind {
+        IntegrationConnectionSyncRunKind::Notifications => "notifications",
+        IntegrationConnectionSyncRunKind::Tasks => "tasks",
+    };
+    let outcome = if error.is_some() {
+        "failure"
+    } else {
+        "success"
+    };
+
+    METRICS.sync_duration.record(
+        (sync_run.ended_at - sync_run.started_at)
+            .to_std()
+            .unwrap_or_default(),
+        &[&provider, kind, outcome],
+    );
+    for (operation, count) in [
+        ("fetched", sync_run.items_fetched),
+        ("created", sync_run.items_created),
+        ("updated", sync_run.items_updated),
+        ("marked_stale", sync_run.items_marked_stale),
+    ] {
+        METRICS
+            .sync_items
+            .add(count as u64, &[&provider, kind, operation]);
+    }
+    if let Some(error) = error {
+        METRICS
+            .sync_failures
+            .add(1, &[&provider, kind, error_kind(error)]);
+    }
+}
+
+pub fn record_job(name: &str, duration: Duration, is_successful: bool) {
+    METRICS.job_duration.record(
+        duration,
+        &[name, if is_successful { "success" } else { "failure" }],
+    );
+}
+
+pub fn record_job_queue_depth(queue: &str, depth: i64) {
+    METRICS.job_queue_depth.set(depth, &[queue]);
+}
+
+pub fn record_oauth_token_refresh(provider_kind: IntegrationProviderKind, outcome: &str) {
+    METRICS
+        .oauth_token_refreshes
+        .add(1, &[&provider_kind.to_string(), outcome]);
+}
+
+/// `route` is the matched route pattern, not the request path, to keep the
+/// number of label values bounded
+pub fn record_http_request(method: &str, route: &str, status: u16, duration: Duration) {
+    METRICS
+        .http_request_duration
+        .record(duration, &[method, route, &status.to_string()]);
+}
+
+pub fn record_mcp_tool_call(tool: &str, outcome: &str) {
+    METRICS.mcp_tool_calls.add(1, &[tool, outcome]);
+}
+
+fn error_kind(error: &UniversalInboxError) -> &'static str {
+    match error {
+        UniversalInboxError::InvalidEnumData { .. }
+        | UniversalInboxError::InvalidUrlData { .. }
+        | UniversalInboxError::InvalidInputData { .. }
+        | UniversalInboxError::InvalidParameters(_) => "invalid_data",
+        UniversalInboxError::AlreadyExists { .. } | UniversalInboxError::Conflict(_) => "conflict",
+        UniversalInboxError::UnsupportedAction(_) => "unsupported_action",
+        UniversalInboxError::ItemNotFound(_) => "not_found",
+        UniversalInboxError::DatabaseError { .. } => "database",
+        UniversalInboxError::Unauthorized(_)
+        | UniversalInboxError::Forbidden(_)
+        | UniversalInboxError::InsufficientScope { .. }
+        | UniversalInboxError::TooManyLoginAttempts { .. } => "unauthorized",
+        UniversalInboxError::UpstreamRateLimited { .. } => "rate_limited",
+        UniversalInboxError::Recoverable(_) => "recoverable",
+        UniversalInboxError::OAuth2InvalidGrant(_) | UniversalInboxError::OAuth2DeviceGrant(_) => {
+            "oauth2"
+        }
+        UniversalInboxError::Unexpected(_) => "unexpected",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::{TimeDelta, Utc};
+    use uuid::Uuid;
+
+    #[test]
+    fn test_render_prometheus_metrics() {
+        let started_at = Utc::now();
+        let sync_run = IntegrationConnectionSyncRun {
+            id: Uuid::new_v4().into(),
+            integration_connection_id: Uuid::new_v4().into(),
+            kind: IntegrationConnectionSyncRunKind::Notifications,
+            started_at,
+            ended_at: started_at + TimeDelta::seconds(2),
+            items_fetched: 3,
+            items_created: 2,
+            items_updated: 1,
+            items_marked_stale: 0,
+            upstream_http_calls: 1,
+            rate_limit_remaining: None,
+            error_chain: vec![],
+        };
+        record_sync_run(IntegrationProviderKind::Github, &sync_run, None);
+        record_mcp_tool_call("list_notifications", "success");
+
+        let metrics = render_prometheus_metrics().unwrap();
+
+        assert!(metrics.contains(
+            r#"universal_inbox_sync_items_total{kind="notifications",operation="created",provider="Github"}"#
+        ));
+        assert!(metrics.contains(
+            r#"universal_inbox_sync_duration_seconds_count{kind="notifications",outcome="success",provider="Github"}"#
+        ));
+        assert!(metrics.contains(
+            r#"universal_inbox_mcp_tool_calls_total{outcome="success",tool="list_notifications"}"#
+        ));
+    }
+}
@@ -0,0 +1,209 @@
+use std::{
+    future::{Ready, ready},
+    net::IpAddr,
+    num::NonZeroU32,
+    rc::Rc,
+    sync::Arc,
+};
+
+use actix_web::{
+    HttpMessage, HttpResponse, ResponseError,
+    body::EitherBody,
+    dev::{Service, ServiceRequest, ServiceResponse, Transform, forward_ready},
+    http::header,
+};
+use anyhow::{Context, anyhow};
+use futures::{FutureExt, future::LocalBoxFuture};
+use serde_json::json;
+use tokio::sync::RwLock;
+
+use universal_inbox::auth::{auth_token::AuthenticationToken, oauth2::OAuth2Scope};
+
+use crate::{
+    middlewares::{jwt_auth::Authenticated, session_guard::check_session_token},
+    universal_inbox::{UniversalInboxError, auth_token::service::AuthenticationTokenService},
+    utils::{
+        jwt::Claims,
+        rate_limit::{ApiKeyRateLimiter, resolve_client_ip},
+    },
+};
+
+/// Middleware applying the stored properties of personal API keys. API keys
+/// are only accepted as `Bearer` tokens; for those, the matching
+/// `authentication_token` row is loaded to:
+/// - reject revoked or expired keys with `401`
+/// - apply the key's rate limit (`429` with `Retry-After`)
+/// - record its last usage time and IP address
+/// - replace the request's claims `scope` and `notification_source_kinds`
+///   with the key's current restrictions, enforced downstream by
+///   [`super::scope_guard`], the notification routes and the MCP tools
+///
+/// Tokens unknown to the database are not API keys, as those are all stored:
+/// they are sessions opened before session tokens were stored, replayed as
+/// `Bearer` tokens, and are registered or rejected like the same session read
+/// from the cookie (see [`super::session_guard`]).
+///
+/// OAuth2 tokens (carrying an `aud` claim) are let through untouched.
+#[derive(Clone)]
+pub struct ApiKeyGuard {
+    authentication_token_service: Arc<RwLock<AuthenticationTokenService>>,
+    rate_limiter: Arc<ApiKeyRateLimiter>,
+}
+
+impl ApiKeyGuard {
+    pub fn new(
+        authentication_token_service: Arc<RwLock<AuthenticationTokenService>>,
+        rate_limiter: Arc<ApiKeyRateLimiter>,
+    ) -> Self {
+        Self {
+            authentication_token_service,
+            rate_limiter,
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for ApiKeyGuard
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type InitError = ();
+    type Transform = ApiKeyGuardMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ApiKeyGuardMiddleware {
+            service: Rc::new(service),
+            authentication_token_service: self.authentication_token_service.clone(),
+            rate_limiter: self.rate_limiter.clone(),
+        }))
+    }
+}
+
+pub struct ApiKeyGuardMiddleware<S> {
+    service: Rc<S>,
+    authentication_token_service: Arc<RwLock<AuthenticationTokenService>>,
+    rate_limiter: Arc<ApiKeyRateLimiter>,
+}
+
+impl<S, B> Service<ServiceRequest> for ApiKeyGuardMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let svc = self.service.clone();
+        let authentication_token_service = self.authentication_token_service.clone();
+        let rate_limiter = self.rate_limiter.clone();
+        async move {
+            let Some(authenticated) = bearer_api_key_candidate(&req) else {
+                let res = svc.call(req).await?;
+                return Ok(res.map_into_left_body());
+            };
+
+            match apply_api_key(
+                &req,
+                authenticated,
+                &authentication_token_service,
+                &rate_limiter,
+            )
+            .await
+            {
+                Ok(()) => {
+                    let res = svc.call(req).await?;
+                    Ok(res.map_into_left_body())
+                }
+                Err(response) => Ok(req.into_response(response).map_into_right_body()),
+            }
+        }
+        .boxed_local()
+    }
+}
+
+/// Returns the request's authentication when it was provided as a `Bearer`
+/// token without an audience, ie. when it may be a personal API key.
+fn bearer_api_key_candidate(req: &ServiceRequest) -> Option<Authenticated<Claims>> {
+    let authenticated = req.extensions().get::<Authenticated<Claims>>().cloned()?;
+    let bearer_token = req
+        .headers()
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")?;
+    (authenticated.claims.aud.is_none() && bearer_token == authenticated.jwt.0)
+        .then_some(authenticated)
+}
+
+async fn apply_api_key(
+    req: &ServiceRequest,
+    mut authenticated: Authenticated<Claims>,
+    authentication_token_service: &RwLock<AuthenticationTokenService>,
+    rate_limiter: &ApiKeyRateLimiter,
+) -> Result<(), HttpResponse> {
+    let client_ip = resolve_client_ip(req.request());
+    let auth_token = load_api_key(
+        authentication_token_service,
+        &authenticated.jwt.0,
+        client_ip,
+    )
+    .await
+    .map_err(|err| err.error_response())?;
+    let Some(auth_token) = auth_token else {
+        return check_session_token(authentication_token_service, &authenticated, client_ip)
+            .await
+            .map_err(|err| err.error_response());
+    };
+
+    if auth_token.is_revoked || auth_token.is_expired() {
+        return Err(UniversalInboxError::Unauthorized(anyhow!(
+            "API key {} is revoked or expired",
+            auth_token.id
+        ))
+        .error_response());
+    }
+
+    let properties = auth_token.api_key_properties;
+    if let Some(per_minute) = properties.rate_limit_per_minute.and_then(NonZeroU32::new)
+        && let Err(wait_time) = rate_limiter.check(&auth_token.id, per_minute)
+    {
+        return Err(HttpResponse::TooManyRequests()
+            .insert_header((header::RETRY_AFTER, wait_time.as_secs().max(1).to_string()))
+            .content_type("application/json")
+            .body(json!({ "message": "API key rate limit exceeded" }).to_string()));
+    }
+
+    authenticated.claims.scope = properties.scopes.as_ref().map(OAuth2Scope::to_scope_string);
+    authenticated.claims.notification_source_kinds = properties.notification_source_kinds;
+    req.extensions_mut().insert(authenticated);
+    Ok(())
+}
+
+async fn load_api_key(
+    authentication_token_service: &RwLock<AuthenticationTokenService>,
+    jwt_token: &str,
+    client_ip: Option<IpAddr>,
+) -> Result<Option<AuthenticationToken>, UniversalInboxError> {
+    let service = authentication_token_service.read().await;
+    let mut transaction = service
+        .begin()
+        .await
+        .context("Failed to create new transaction while authenticating API key")?;
+    let auth_token = service
+        .authenticate_api_key(&mut transaction, jwt_token, client_ip)
+        .await?;
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit while authenticating API key")?;
+    Ok(auth_token)
+}
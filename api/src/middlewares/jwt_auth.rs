@@ -8,10 +8,9 @@
 //! extractors, and downstream middlewares ([`super::audience_guard`], MCP) read it directly
 //! from the request extensions.
 //!
-//! Token-level invalidation (a JWT blacklist) is intentionally omitted: logout clears the
-//! session cookie (`session.purge()`) and OAuth2 access is revoked by deleting refresh
-//! tokens in the database. If per-token revocation is ever required, add a Redis-backed
-//! store here that the request path consults.
+//! Token-level invalidation is not handled here: revoked session tokens, API keys and
+//! OAuth2 access tokens are rejected by the downstream [`super::session_guard`],
+//! [`super::api_key_guard`] and [`super::revoked_token_guard`] middlewares.
 
 use std::{
     future::{Ready, ready},
@@ -1,2 +1,6 @@
+pub mod api_key_guard;
 pub mod audience_guard;
 pub mod jwt_auth;
+pub mod revoked_token_guard;
+pub mod scope_guard;
+pub mod session_guard;
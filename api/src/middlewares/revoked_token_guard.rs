@@ -0,0 +1,132 @@
+use std::{
+    future::{Ready, ready},
+    rc::Rc,
+    sync::Arc,
+};
+
+use actix_web::{
+    HttpMessage, HttpResponse, ResponseError,
+    body::EitherBody,
+    dev::{Service, ServiceRequest, ServiceResponse, Transform, forward_ready},
+    http::header,
+};
+use anyhow::Context;
+use futures::{FutureExt, future::LocalBoxFuture};
+use tracing::warn;
+
+use crate::{
+    middlewares::jwt_auth::Authenticated,
+    universal_inbox::{UniversalInboxError, oauth2::service::OAuth2Service},
+    utils::jwt::Claims,
+};
+
+/// Middleware rejecting OAuth2 access tokens revoked through `/oauth2/revoke`
+/// (or along with their token family) with `401` and an RFC 6750
+/// `invalid_token` error. Access tokens are stateless JWTs, so their `jti` is
+/// checked against the revoked access tokens on every request.
+///
+/// Only tokens carrying an `aud` claim (ie. OAuth2 access tokens) are checked;
+/// session tokens and personal API keys are let through untouched.
+#[derive(Clone)]
+pub struct RejectRevokedAccessTokens {
+    oauth2_service: Arc<OAuth2Service>,
+}
+
+impl RejectRevokedAccessTokens {
+    pub fn new(oauth2_service: Arc<OAuth2Service>) -> Self {
+        Self { oauth2_service }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RejectRevokedAccessTokens
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type InitError = ();
+    type Transform = RejectRevokedAccessTokensMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RejectRevokedAccessTokensMiddleware {
+            service: Rc::new(service),
+            oauth2_service: self.oauth2_service.clone(),
+        }))
+    }
+}
+
+pub struct RejectRevokedAccessTokensMiddleware<S> {
+    service: Rc<S>,
+    oauth2_service: Arc<OAuth2Service>,
+}
+
+impl<S, B> Service<ServiceRequest> for RejectRevokedAccessTokensMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let svc = self.service.clone();
+        let oauth2_service = self.oauth2_service.clone();
+        async move {
+            let jti = req
+                .extensions()
+                .get::<Authenticated<Claims>>()
+                .filter(|authenticated| authenticated.claims.aud.is_some())
+                .map(|authenticated| authenticated.claims.jti.clone());
+            let Some(jti) = jti else {
+                let res = svc.call(req).await?;
+                return Ok(res.map_into_left_body());
+            };
+
+            match is_access_token_revoked(&oauth2_service, &jti).await {
+                Ok(false) => {
+                    let res = svc.call(req).await?;
+                    Ok(res.map_into_left_body())
+                }
+                Ok(true) => {
+                    warn!(jti, "Rejected revoked OAuth2 access token");
+                    let response = HttpResponse::Unauthorized()
+                        .insert_header((
+                            header::WWW_AUTHENTICATE,
+                            r#"Bearer error="invalid_token", error_description="The access token has been revoked""#,
+                        ))
+                        .finish();
+                    Ok(req.into_response(response).map_into_right_body())
+                }
+                Err(err) => Ok(req
+                    .into_response(err.error_response())
+                    .map_into_right_body()),
+            }
+        }
+        .boxed_local()
+    }
+}
+
+async fn is_access_token_revoked(
+    oauth2_service: &OAuth2Service,
+    jti: &str,
+) -> Result<bool, UniversalInboxError> {
+    let mut transaction = oauth2_service
+        .begin()
+        .await
+        .context("Failed to create new transaction while checking access token revocation")?;
+    let is_revoked = oauth2_service
+        .is_access_token_revoked(&mut transaction, jti)
+        .await?;
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit while checking access token revocation")?;
+    Ok(is_revoked)
+}
@@ -0,0 +1,206 @@
+use std::{
+    future::{Future, Ready, ready},
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+use actix_web::{
+    HttpMessage, ResponseError,
+    body::EitherBody,
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    http::Method,
+};
+
+use universal_inbox::auth::oauth2::OAuth2Scope;
+
+use crate::{
+    middlewares::jwt_auth::Authenticated,
+    universal_inbox::UniversalInboxError,
+    utils::jwt::{Claims, check_scopes},
+};
+
+/// Middleware enforcing the `scope` claim of scoped tokens on API routes.
+/// Unscoped tokens (session and legacy API key tokens) are let through
+/// untouched. Scoped tokens must hold every scope returned by
+/// [`required_scopes_for_route`] for the request, and are rejected with
+/// `403 insufficient_scope` otherwise. Routes with no scope mapping (account
+/// management, authentication, ...) are never reachable with a scoped token.
+///
+/// `api_path` is stripped from the request path before matching.
+/// `exempt_path_prefixes` lets paths enforcing scopes by themselves (e.g.
+/// `/api/mcp`, per tool) pass through.
+#[derive(Clone)]
+pub struct RequireScopes {
+    api_path: Rc<String>,
+    exempt_path_prefixes: Rc<Vec<String>>,
+}
+
+impl RequireScopes {
+    pub fn new(api_path: &str) -> Self {
+        Self {
+            api_path: Rc::new(api_path.to_string()),
+            exempt_path_prefixes: Rc::new(vec![]),
+        }
+    }
+
+    pub fn with_exempt_prefixes<I, S>(mut self, prefixes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.exempt_path_prefixes = Rc::new(prefixes.into_iter().map(Into::into).collect());
+        self
+    }
+}
+
+/// Scopes a token must hold to call `method` on `path` (relative to the API
+/// root), or `None` when the route is not accessible to scoped tokens.
+pub fn required_scopes_for_route(method: &Method, path: &str) -> Option<Vec<OAuth2Scope>> {
+    let segments: Vec<&str> = path
+        .trim_matches('/')
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    let is_read = *method == Method::GET || *method == Method::HEAD;
+
+    match segments.as_slice() {
+        ["notifications", "sync"] | ["tasks", "sync"] => Some(vec![OAuth2Scope::Sync]),
+        ["notifications", _, "task"] => Some(vec![
+            OAuth2Scope::NotificationsWrite,
+            OAuth2Scope::TasksWrite,
+        ]),
+        ["notifications", ..] if is_read => Some(vec![OAuth2Scope::NotificationsRead]),
+        ["notifications", ..] => Some(vec![OAuth2Scope::NotificationsWrite]),
+        ["tasks", ..] if is_read => Some(vec![OAuth2Scope::TasksRead]),
+        ["tasks", ..] => Some(vec![OAuth2Scope::TasksWrite]),
+        ["integration-connections", ..] if is_read => Some(vec![OAuth2Scope::IntegrationsRead]),
+        ["oauth2", "introspect"] => Some(vec![OAuth2Scope::TokensIntrospect]),
+        _ => None,
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequireScopes
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type InitError = ();
+    type Transform = RequireScopesMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequireScopesMiddleware {
+            service,
+            api_path: self.api_path.clone(),
+            exempt_path_prefixes: self.exempt_path_prefixes.clone(),
+        }))
+    }
+}
+
+pub struct RequireScopesMiddleware<S> {
+    service: S,
+    api_path: Rc<String>,
+    exempt_path_prefixes: Rc<Vec<String>>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequireScopesMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let path = req.path();
+        let exempt = self
+            .exempt_path_prefixes
+            .iter()
+            .any(|prefix| path.starts_with(prefix.as_str()));
+
+        let granted_scopes = if exempt {
+            None
+        } else {
+            req.extensions()
+                .get::<Authenticated<Claims>>()
+                .and_then(|auth| auth.claims.granted_scopes())
+        };
+
+        if let Some(granted_scopes) = granted_scopes {
+            let relative_path = path.strip_prefix(self.api_path.as_str()).unwrap_or(path);
+            let result = match required_scopes_for_route(req.method(), relative_path) {
+                Some(required_scopes) => check_scopes(Some(&granted_scopes), &required_scopes),
+                None => Err(UniversalInboxError::Forbidden(
+                    "Scoped tokens cannot be used on this endpoint".to_string(),
+                )),
+            };
+
+            if let Err(err) = result {
+                let response = req
+                    .into_response(err.error_response())
+                    .map_into_right_body();
+                return Box::pin(async move { Ok(response) });
+            }
+        }
+
+        let fut = self.service.call(req);
+        Box::pin(async move {
+            let res = fut.await?;
+            Ok(res.map_into_left_body())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::*;
+
+    #[rstest]
+    #[case::list_notifications(Method::GET, "notifications", Some(vec![OAuth2Scope::NotificationsRead]))]
+    #[case::patch_notification(
+        Method::PATCH,
+        "/notifications/0b1f5f8e-0000-0000-0000-000000000000",
+        Some(vec![OAuth2Scope::NotificationsWrite])
+    )]
+    #[case::create_task_from_notification(
+        Method::POST,
+        "notifications/0b1f5f8e-0000-0000-0000-000000000000/task",
+        Some(vec![OAuth2Scope::NotificationsWrite, OAuth2Scope::TasksWrite])
+    )]
+    #[case::sync_notifications(Method::POST, "notifications/sync", Some(vec![OAuth2Scope::Sync]))]
+    #[case::search_tasks(Method::GET, "tasks/search", Some(vec![OAuth2Scope::TasksRead]))]
+    #[case::sync_tasks(Method::POST, "tasks/sync", Some(vec![OAuth2Scope::Sync]))]
+    #[case::patch_task(Method::PATCH, "tasks/0b1f5f8e", Some(vec![OAuth2Scope::TasksWrite]))]
+    #[case::list_integration_connections(
+        Method::GET,
+        "integration-connections",
+        Some(vec![OAuth2Scope::IntegrationsRead])
+    )]
+    #[case::introspect_token(
+        Method::POST,
+        "oauth2/introspect",
+        Some(vec![OAuth2Scope::TokensIntrospect])
+    )]
+    #[case::create_integration_connection(Method::POST, "integration-connections", None)]
+    #[case::get_user(Method::GET, "users/me", None)]
+    #[case::create_api_key(Method::POST, "users/me/authentication-tokens", None)]
+    fn test_required_scopes_for_route(
+        #[case] method: Method,
+        #[case] path: &str,
+        #[case] expected: Option<Vec<OAuth2Scope>>,
+    ) {
+        assert_eq!(required_scopes_for_route(&method, path), expected);
+    }
+}
@@ -0,0 +1,179 @@
+use std::{
+    future::{Ready, ready},
+    net::IpAddr,
+    rc::Rc,
+    sync::Arc,
+};
+
+use actix_web::{
+    HttpMessage, ResponseError,
+    body::EitherBody,
+    dev::{Service, ServiceRequest, ServiceResponse, Transform, forward_ready},
+    http::header,
+};
+use anyhow::{Context, anyhow};
+use futures::{FutureExt, future::LocalBoxFuture};
+use tokio::sync::RwLock;
+
+use universal_inbox::auth::auth_token::AuthenticationToken;
+
+use crate::{
+    middlewares::jwt_auth::Authenticated,
+    universal_inbox::{UniversalInboxError, auth_token::service::AuthenticationTokenService},
+    utils::{jwt::Claims, rate_limit::resolve_client_ip},
+};
+
+/// Middleware rejecting revoked session tokens with `401`, which also clears
+/// the session cookie. Session tokens are stored when a session is opened so
+/// that users can list their sessions and sign out remotely. For requests
+/// authenticated with the session cookie, the matching `authentication_token`
+/// row is loaded to:
+/// - reject revoked or expired sessions
+/// - record the last time (and IP address) the session was seen
+/// - register sessions opened before session tokens were stored
+///
+/// A session successfully checked is not loaded again for a short while (see
+/// `AuthenticationTokenService::is_session_token_recently_checked`).
+///
+/// Bearer tokens (API keys, OAuth2 tokens) are let through untouched: a session
+/// token given as a `Bearer` token is checked by [`super::api_key_guard`].
+#[derive(Clone)]
+pub struct RejectRevokedSessions {
+    authentication_token_service: Arc<RwLock<AuthenticationTokenService>>,
+}
+
+impl RejectRevokedSessions {
+    pub fn new(authentication_token_service: Arc<RwLock<AuthenticationTokenService>>) -> Self {
+        Self {
+            authentication_token_service,
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RejectRevokedSessions
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type InitError = ();
+    type Transform = RejectRevokedSessionsMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RejectRevokedSessionsMiddleware {
+            service: Rc::new(service),
+            authentication_token_service: self.authentication_token_service.clone(),
+        }))
+    }
+}
+
+pub struct RejectRevokedSessionsMiddleware<S> {
+    service: Rc<S>,
+    authentication_token_service: Arc<RwLock<AuthenticationTokenService>>,
+}
+
+impl<S, B> Service<ServiceRequest> for RejectRevokedSessionsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let svc = self.service.clone();
+        let authentication_token_service = self.authentication_token_service.clone();
+        async move {
+            let Some(authenticated) = session_authentication(&req) else {
+                let res = svc.call(req).await?;
+                return Ok(res.map_into_left_body());
+            };
+
+            let client_ip = resolve_client_ip(req.request());
+            match check_session_token(&authentication_token_service, &authenticated, client_ip)
+                .await
+            {
+                Ok(()) => {
+                    let res = svc.call(req).await?;
+                    Ok(res.map_into_left_body())
+                }
+                Err(err) => Ok(req
+                    .into_response(err.error_response())
+                    .map_into_right_body()),
+            }
+        }
+        .boxed_local()
+    }
+}
+
+/// Returns the request's authentication when its JWT was read from the
+/// session cookie rather than given as a `Bearer` token.
+fn session_authentication(req: &ServiceRequest) -> Option<Authenticated<Claims>> {
+    let authenticated = req.extensions().get::<Authenticated<Claims>>().cloned()?;
+    if authenticated.claims.aud.is_some() {
+        return None;
+    }
+    let bearer_token = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    (bearer_token != Some(authenticated.jwt.0.as_str())).then_some(authenticated)
+}
+
+/// Rejects revoked or expired sessions with `Unauthorized`, registering the
+/// sessions opened before session tokens were stored
+pub(crate) async fn check_session_token(
+    authentication_token_service: &RwLock<AuthenticationTokenService>,
+    authenticated: &Authenticated<Claims>,
+    client_ip: Option<IpAddr>,
+) -> Result<(), UniversalInboxError> {
+    if let Some(session_token) =
+        load_session_token(authentication_token_service, authenticated, client_ip).await?
+        && (session_token.is_revoked || session_token.is_expired())
+    {
+        return Err(UniversalInboxError::Unauthorized(anyhow!(
+            "Session {} is revoked or expired",
+            session_token.id
+        )));
+    }
+
+    Ok(())
+}
+
+async fn load_session_token(
+    authentication_token_service: &RwLock<AuthenticationTokenService>,
+    authenticated: &Authenticated<Claims>,
+    client_ip: Option<IpAddr>,
+) -> Result<Option<AuthenticationToken>, UniversalInboxError> {
+    let service = authentication_token_service.read().await;
+    let jwt_token = authenticated.jwt.0.as_str();
+    if service.is_session_token_recently_checked(jwt_token, client_ip) {
+        return Ok(None);
+    }
+
+    let mut transaction = service
+        .begin()
+        .await
+        .context("Failed to create new transaction while authenticating session")?;
+    let session_token = service
+        .authenticate_session_token(
+            &mut transaction,
+            jwt_token,
+            &authenticated.claims,
+            client_ip,
+        )
+        .await?;
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit while authenticating session")?;
+    Ok(session_token)
+}
@@ -8,16 +8,17 @@ use actix_web::{
     HttpMessage,
     dev::{ServiceRequest, ServiceResponse},
 };
-use opentelemetry::{KeyValue, trace::TracerProvider as _};
+use opentelemetry::{KeyValue, global, trace::TracerProvider as _};
 use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
 use opentelemetry_otlp::tonic_types::metadata::MetadataMap;
 use opentelemetry_otlp::tonic_types::transport::ClientTlsConfig;
 use opentelemetry_otlp::{
-    LogExporter, SpanExporter, WithExportConfig, WithHttpConfig, WithTonicConfig,
+    LogExporter, MetricExporter, SpanExporter, WithExportConfig, WithHttpConfig, WithTonicConfig,
 };
 use opentelemetry_sdk::{
     Resource,
     logs::SdkLoggerProvider,
+    metrics::{PeriodicReader, SdkMeterProvider},
     trace::{RandomIdGenerator, Sampler, SdkTracerProvider},
 };
 use tokio::task::JoinHandle;
@@ -114,6 +115,28 @@ pub fn get_subscriber(env_filter_str: &str) -> impl Subscriber + Send + Sync {
     Registry::default().with(env_filter).with(fmt)
 }
 
+/// Export the metrics recorded through the global meter over OTLP, see `crate::metrics`
+pub fn init_meter_provider(
+    environment: &str,
+    config: &TracingSettings,
+    service_name: &str,
+    version: Option<String>,
+) {
+    let meter_provider = SdkMeterProvider::builder()
+        .with_resource(build_resource(environment, service_name, version))
+        .with_reader(
+            PeriodicReader::builder(build_metric_exporter(
+                config.otlp_exporter_protocol,
+                config.otlp_exporter_endpoint.to_string(),
+                config.otlp_exporter_headers.clone(),
+            ))
+            .build(),
+        )
+        .build();
+
+    global::set_meter_provider(meter_provider);
+}
+
 pub fn init_subscriber(
     subscriber: impl Subscriber + Send + Sync,
     log_level_filter: log::LevelFilter,
@@ -271,6 +294,56 @@ where
     }
 }
 
+fn build_metric_exporter(
+    otlp_exporter_protocol: OtlpExporterProtocol,
+    otlp_exporter_endpoint: String,
+    otlp_exporter_headers: HashMap<String, String>,
+) -> MetricExporter {
+    let builder = MetricExporter::builder();
+
+    if otlp_exporter_protocol == OtlpExporterProtocol::Http {
+        let mut headers = HashMap::with_capacity(2);
+        for (header_name, header_value) in &otlp_exporter_headers {
+            if !header_value.is_empty() {
+                headers.insert(
+                    // header names usually use dashes instead of underscores but env vars don't allow dashes
+                    header_name.replace('_', "-"),
+                    header_value.parse().unwrap(),
+                );
+            }
+        }
+
+        builder
+            .with_http()
+            .with_http_client(reqwest_v013::Client::new())
+            .with_endpoint(otlp_exporter_endpoint)
+            .with_timeout(Duration::from_secs(3))
+            .with_headers(headers.clone())
+            .build()
+            .unwrap()
+    } else {
+        let mut headers = MetadataMap::with_capacity(otlp_exporter_headers.len());
+        for (header_name, header_value) in &otlp_exporter_headers {
+            if !header_value.is_empty() {
+                headers.insert(
+                    // header names usually use dashes instead of underscores but env vars don't allow dashes
+                    AsciiMetadataKey::from_str(header_name.replace('_', "-").as_str()).unwrap(),
+                    header_value.parse().unwrap(),
+                );
+            }
+        }
+
+        builder
+            .with_tonic()
+            .with_endpoint(otlp_exporter_endpoint)
+            .with_tls_config(ClientTlsConfig::new().with_native_roots())
+            .with_timeout(Duration::from_secs(3))
+            .with_metadata(headers.clone())
+            .build()
+            .unwrap()
+    }
+}
+
 fn build_resource(environment: &str, service_name: &str, version: Option<String>) -> Resource {
     let mut resource = vec![
         KeyValue::new("service.name", service_name.to_string()),
@@ -1,10 +1,17 @@
+use anyhow::anyhow;
 use async_trait::async_trait;
 use chrono::{DateTime, NaiveDateTime, Utc};
 use secrecy::{ExposeSecret, SecretBox};
 use sqlx::{Postgres, QueryBuilder, Transaction};
 
 use universal_inbox::{
-    auth::auth_token::{AuthenticationToken, JWTToken},
+    auth::{
+        auth_token::{
+            ApiKeyPatch, ApiKeyProperties, AuthenticationToken, AuthenticationTokenId, JWTToken,
+        },
+        oauth2::OAuth2Scope,
+    },
+    notification::NotificationSourceKind,
     user::UserId,
 };
 use uuid::Uuid;
@@ -21,14 +28,81 @@ pub trait AuthenticationTokenRepository {
         auth_token: AuthenticationToken,
     ) -> Result<AuthenticationToken, UniversalInboxError>;
 
+    /// Store the session token `auth_token` unless a token with the same JWT
+    /// token is already stored, and return the stored token
+    async fn get_or_create_session_token(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        auth_token: AuthenticationToken,
+    ) -> Result<AuthenticationToken, UniversalInboxError>;
+
     async fn fetch_auth_tokens_for_user(
         &self,
         executor: &mut Transaction<'_, Postgres>,
         user_id: UserId,
         exclude_session_tokens: bool,
     ) -> Result<Vec<AuthenticationToken>, UniversalInboxError>;
+
+    async fn get_auth_token_by_jwt_token(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        jwt_token: &str,
+    ) -> Result<Option<AuthenticationToken>, UniversalInboxError>;
+
+    async fn update_api_key(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        auth_token_id: AuthenticationTokenId,
+        patch: &ApiKeyPatch,
+        for_user_id: UserId,
+    ) -> Result<Option<AuthenticationToken>, UniversalInboxError>;
+
+    async fn touch_auth_token(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        auth_token_id: AuthenticationTokenId,
+        last_used_at: DateTime<Utc>,
+        last_used_ip: Option<String>,
+    ) -> Result<(), UniversalInboxError>;
+
+    /// Revoke the active session tokens of `user_id` matching `filter` and
+    /// return the number of revoked sessions
+    async fn revoke_session_tokens(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        filter: SessionTokenFilter<'_>,
+    ) -> Result<u64, UniversalInboxError>;
 }
 
+/// Selection of the session tokens to revoke
+#[derive(Debug, Clone, Copy)]
+pub enum SessionTokenFilter<'a> {
+    All,
+    Id(&'a AuthenticationTokenId),
+    JwtToken(&'a str),
+    /// All sessions but the one of the given JWT token
+    AllExcept(&'a str),
+}
+
+const AUTHENTICATION_TOKEN_COLUMNS: &str = r#"
+                  id,
+                  created_at,
+                  updated_at,
+                  user_id,
+                  jwt_token,
+                  expire_at,
+                  is_revoked,
+                  is_session_token,
+                  name,
+                  scope,
+                  notification_source_kinds,
+                  rate_limit_per_minute,
+                  last_used_at,
+                  last_used_ip,
+                  user_agent
+"#;
+
 #[async_trait]
 impl AuthenticationTokenRepository for Repository {
     #[tracing::instrument(level = "debug", skip_all, err)]
@@ -37,7 +111,8 @@ impl AuthenticationTokenRepository for Repository {
         executor: &mut Transaction<'_, Postgres>,
         auth_token: AuthenticationToken,
     ) -> Result<AuthenticationToken, UniversalInboxError> {
-        sqlx::query!(
+        let properties = &auth_token.api_key_properties;
+        sqlx::query(
             r#"
                 INSERT INTO authentication_token
                   (
@@ -47,7 +122,14 @@ impl AuthenticationTokenRepository for Repository {
                     user_id,
                     jwt_token,
                     expire_at,
-                    is_session_token
+                    is_session_token,
+                    name,
+                    scope,
+                    notification_source_kinds,
+                    rate_limit_per_minute,
+                    last_used_at,
+                    last_used_ip,
+                    user_agent
                   )
                 VALUES
                   (
@@ -57,17 +139,37 @@ impl AuthenticationTokenRepository for Repository {
                     $4,
                     $5,
                     $6,
-                    $7
+                    $7,
+                    $8,
+                    $9,
+                    $10,
+                    $11,
+                    $12,
+                    $13,
+                    $14
                   )
             "#,
-            auth_token.id.0,
-            auth_token.created_at.naive_utc(),
-            auth_token.updated_at.naive_utc(),
-            auth_token.user_id.0,
-            auth_token.jwt_token.expose_secret().0,
-            auth_token.expire_at.map(|expire_at| expire_at.naive_utc()),
-            auth_token.is_session_token,
         )
+        .bind(auth_token.id.0)
+        .bind(auth_token.created_at.naive_utc())
+        .bind(auth_token.updated_at.naive_utc())
+        .bind(auth_token.user_id.0)
+        .bind(&auth_token.jwt_token.expose_secret().0)
+        .bind(auth_token.expire_at.map(|expire_at| expire_at.naive_utc()))
+        .bind(auth_token.is_session_token)
+        .bind(&properties.name)
+        .bind(scopes_to_column(&properties.scopes))
+        .bind(notification_source_kinds_to_column(
+            &properties.notification_source_kinds,
+        ))
+        .bind(rate_limit_to_column(properties.rate_limit_per_minute))
+        .bind(
+            properties
+                .last_used_at
+                .map(|last_used_at| last_used_at.naive_utc()),
+        )
+        .bind(&properties.last_used_ip)
+        .bind(&auth_token.user_agent)
         .execute(&mut **executor)
         .await
         .map_err(|err| {
@@ -81,6 +183,71 @@ impl AuthenticationTokenRepository for Repository {
         Ok(auth_token)
     }
 
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(user.id = auth_token.user_id.to_string()),
+        err
+    )]
+    async fn get_or_create_session_token(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        auth_token: AuthenticationToken,
+    ) -> Result<AuthenticationToken, UniversalInboxError> {
+        let properties = &auth_token.api_key_properties;
+        sqlx::query(
+            r#"
+                INSERT INTO authentication_token
+                  (
+                    id,
+                    created_at,
+                    updated_at,
+                    user_id,
+                    jwt_token,
+                    expire_at,
+                    is_revoked,
+                    is_session_token,
+                    last_used_at,
+                    last_used_ip
+                  )
+                VALUES
+                  ($1, $2, $3, $4, $5, $6, $7, true, $8, $9)
+                ON CONFLICT (jwt_token) DO NOTHING
+            "#,
+        )
+        .bind(auth_token.id.0)
+        .bind(auth_token.created_at.naive_utc())
+        .bind(auth_token.updated_at.naive_utc())
+        .bind(auth_token.user_id.0)
+        .bind(&auth_token.jwt_token.expose_secret().0)
+        .bind(auth_token.expire_at.map(|expire_at| expire_at.naive_utc()))
+        .bind(auth_token.is_revoked)
+        .bind(
+            properties
+                .last_used_at
+                .map(|last_used_at| last_used_at.naive_utc()),
+        )
+        .bind(&properties.last_used_ip)
+        .execute(&mut **executor)
+        .await
+        .map_err(|err| {
+            let message = format!("Failed to insert new session token into storage: {err}");
+            UniversalInboxError::DatabaseError {
+                source: err,
+                message,
+            }
+        })?;
+
+        self.get_auth_token_by_jwt_token(executor, &auth_token.jwt_token.expose_secret().0)
+            .await?
+            .ok_or_else(|| {
+                UniversalInboxError::Unexpected(anyhow!(
+                    "Failed to find session token {} after storing it",
+                    auth_token.id
+                ))
+            })
+    }
+
     #[tracing::instrument(
         level = "debug",
         skip_all,
@@ -93,23 +260,10 @@ impl AuthenticationTokenRepository for Repository {
         user_id: UserId,
         exclude_session_tokens: bool,
     ) -> Result<Vec<AuthenticationToken>, UniversalInboxError> {
-        let mut query_builder = QueryBuilder::new(
-            r#"
-                SELECT
-                  id,
-                  created_at,
-                  updated_at,
-                  user_id,
-                  jwt_token,
-                  expire_at,
-                  is_revoked,
-                  is_session_token
-                FROM
-                  authentication_token
-                WHERE
-                  user_id =
-            "#,
-        );
+        let mut query_builder = QueryBuilder::new("SELECT");
+        query_builder
+            .push(AUTHENTICATION_TOKEN_COLUMNS)
+            .push(" FROM authentication_token WHERE user_id = ");
         query_builder.push_bind(user_id.0);
         if exclude_session_tokens {
             query_builder.push(" AND is_session_token = false");
@@ -130,6 +284,213 @@ impl AuthenticationTokenRepository for Repository {
 
         Ok(rows.into_iter().map(|r| r.into()).collect())
     }
+
+    #[tracing::instrument(level = "debug", skip_all, err)]
+    async fn get_auth_token_by_jwt_token(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        jwt_token: &str,
+    ) -> Result<Option<AuthenticationToken>, UniversalInboxError> {
+        let mut query_builder = QueryBuilder::new("SELECT");
+        query_builder
+            .push(AUTHENTICATION_TOKEN_COLUMNS)
+            .push(" FROM authentication_token WHERE jwt_token = ")
+            .push_bind(jwt_token);
+
+        let row = query_builder
+            .build_query_as::<AuthenticationTokenRow>()
+            .fetch_optional(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message = format!("Failed to fetch authentication token from storage: {err}");
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
+        Ok(row.map(|r| r.into()))
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(auth_token_id = auth_token_id.to_string(), user.id = for_user_id.to_string()),
+        err
+    )]
+    async fn update_api_key(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        auth_token_id: AuthenticationTokenId,
+        patch: &ApiKeyPatch,
+        for_user_id: UserId,
+    ) -> Result<Option<AuthenticationToken>, UniversalInboxError> {
+        if *patch == Default::default() {
+            return Err(UniversalInboxError::InvalidInputData {
+                source: None,
+                user_error: format!("Missing fields to update API key {auth_token_id}"),
+            });
+        };
+
+        let mut query_builder = QueryBuilder::new("UPDATE authentication_token SET");
+        let mut separated = query_builder.separated(", ");
+        separated
+            .push(" updated_at = ")
+            .push_bind_unseparated(Utc::now().naive_utc());
+        if let Some(name) = &patch.name {
+            separated
+                .push(" name = ")
+                .push_bind_unseparated(name.clone());
+        }
+        if let Some(scopes) = &patch.scopes {
+            separated
+                .push(" scope = ")
+                .push_bind_unseparated(scopes_to_column(scopes));
+        }
+        if let Some(notification_source_kinds) = &patch.notification_source_kinds {
+            separated
+                .push(" notification_source_kinds = ")
+                .push_bind_unseparated(notification_source_kinds_to_column(
+                    notification_source_kinds,
+                ));
+        }
+        if let Some(rate_limit_per_minute) = patch.rate_limit_per_minute {
+            separated
+                .push(" rate_limit_per_minute = ")
+                .push_bind_unseparated(rate_limit_to_column(rate_limit_per_minute));
+        }
+        if let Some(is_revoked) = patch.is_revoked {
+            separated
+                .push(" is_revoked = ")
+                .push_bind_unseparated(is_revoked);
+        }
+
+        query_builder
+            .push(" WHERE id = ")
+            .push_bind(auth_token_id.0)
+            .push(" AND user_id = ")
+            .push_bind(for_user_id.0)
+            .push(" AND is_session_token = false RETURNING")
+            .push(AUTHENTICATION_TOKEN_COLUMNS);
+
+        let row = query_builder
+            .build_query_as::<AuthenticationTokenRow>()
+            .fetch_optional(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message =
+                    format!("Failed to update API key {auth_token_id} from storage: {err}");
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
+        Ok(row.map(|r| r.into()))
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(auth_token_id = auth_token_id.to_string()),
+        err
+    )]
+    async fn touch_auth_token(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        auth_token_id: AuthenticationTokenId,
+        last_used_at: DateTime<Utc>,
+        last_used_ip: Option<String>,
+    ) -> Result<(), UniversalInboxError> {
+        sqlx::query(
+            r#"
+                UPDATE authentication_token
+                SET last_used_at = $1, last_used_ip = $2
+                WHERE id = $3
+            "#,
+        )
+        .bind(last_used_at.naive_utc())
+        .bind(last_used_ip)
+        .bind(auth_token_id.0)
+        .execute(&mut **executor)
+        .await
+        .map_err(|err| {
+            let message = format!(
+                "Failed to update last usage of authentication token {auth_token_id}: {err}"
+            );
+            UniversalInboxError::DatabaseError {
+                source: err,
+                message,
+            }
+        })?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(user.id = user_id.to_string()),
+        err
+    )]
+    async fn revoke_session_tokens(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        filter: SessionTokenFilter<'_>,
+    ) -> Result<u64, UniversalInboxError> {
+        let mut query_builder =
+            QueryBuilder::new("UPDATE authentication_token SET is_revoked = true, updated_at = ");
+        query_builder
+            .push_bind(Utc::now().naive_utc())
+            .push(" WHERE user_id = ")
+            .push_bind(user_id.0)
+            .push(" AND is_session_token = true AND is_revoked = false");
+        match filter {
+            SessionTokenFilter::All => {}
+            SessionTokenFilter::Id(auth_token_id) => {
+                query_builder.push(" AND id = ").push_bind(auth_token_id.0);
+            }
+            SessionTokenFilter::JwtToken(jwt_token) => {
+                query_builder.push(" AND jwt_token = ").push_bind(jwt_token);
+            }
+            SessionTokenFilter::AllExcept(jwt_token) => {
+                query_builder
+                    .push(" AND jwt_token != ")
+                    .push_bind(jwt_token);
+            }
+        }
+
+        let result = query_builder
+            .build()
+            .execute(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message = format!("Failed to revoke session tokens of user {user_id}: {err}");
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+fn scopes_to_column(scopes: &Option<Vec<OAuth2Scope>>) -> Option<String> {
+    scopes.as_ref().map(OAuth2Scope::to_scope_string)
+}
+
+fn notification_source_kinds_to_column(
+    kinds: &Option<Vec<NotificationSourceKind>>,
+) -> Option<Vec<String>> {
+    kinds
+        .as_ref()
+        .map(|kinds| kinds.iter().map(|kind| kind.to_string()).collect())
+}
+
+fn rate_limit_to_column(rate_limit_per_minute: Option<u32>) -> Option<i32> {
+    rate_limit_per_minute.map(|rate_limit| rate_limit.min(i32::MAX as u32) as i32)
 }
 
 #[derive(Debug, sqlx::FromRow)]
@@ -142,6 +503,13 @@ pub struct AuthenticationTokenRow {
     pub expire_at: Option<NaiveDateTime>,
     pub is_revoked: bool,
     pub is_session_token: bool,
+    pub name: Option<String>,
+    pub scope: Option<String>,
+    pub notification_source_kinds: Option<Vec<String>>,
+    pub rate_limit_per_minute: Option<i32>,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub last_used_ip: Option<String>,
+    pub user_agent: Option<String>,
 }
 
 impl From<AuthenticationTokenRow> for AuthenticationToken {
@@ -163,6 +531,28 @@ impl From<&AuthenticationTokenRow> for AuthenticationToken {
                 .map(|expire_at| DateTime::from_naive_utc_and_offset(expire_at, Utc)),
             is_revoked: row.is_revoked,
             is_session_token: row.is_session_token,
+            api_key_properties: ApiKeyProperties {
+                name: row.name.clone(),
+                scopes: row
+                    .scope
+                    .as_deref()
+                    .map(|scope| OAuth2Scope::parse_scopes_lossy(scope).into_iter().collect()),
+                // Unknown kinds are ignored rather than widening the restriction
+                notification_source_kinds: row.notification_source_kinds.as_ref().map(|kinds| {
+                    kinds
+                        .iter()
+                        .filter_map(|kind| kind.parse::<NotificationSourceKind>().ok())
+                        .collect()
+                }),
+                rate_limit_per_minute: row
+                    .rate_limit_per_minute
+                    .map(|rate_limit| rate_limit.max(0) as u32),
+                last_used_at: row
+                    .last_used_at
+                    .map(|last_used_at| DateTime::from_naive_utc_and_offset(last_used_at, Utc)),
+                last_used_ip: row.last_used_ip.clone(),
+            },
+            user_agent: row.user_agent.clone(),
         }
     }
 }
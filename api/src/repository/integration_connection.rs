@@ -10,13 +10,20 @@ use universal_inbox::{
         IntegrationConnection, IntegrationConnectionId, IntegrationConnectionStatus,
         config::IntegrationConnectionConfig,
         provider::{IntegrationConnectionContext, IntegrationProvider, IntegrationProviderKind},
+        rate_limit::IntegrationConnectionRateLimit,
+        sync_run::IntegrationConnectionSyncRun,
     },
     user::UserId,
 };
 
 use crate::{
     repository::Repository,
-    universal_inbox::{UniversalInboxError, UpdateStatus},
+    universal_inbox::{
+        UniversalInboxError, UpdateStatus,
+        integration_connection::{
+            provider_health::IntegrationProviderSyncStats, service::IntegrationConnectionSyncType,
+        },
+    },
 };
 
 #[derive(Debug)]
@@ -102,6 +109,17 @@ pub trait IntegrationConnectionRepository {
         lock_rows: bool,
     ) -> Result<Vec<IntegrationConnection>, UniversalInboxError>;
 
+    /// Validated integration connections of the user with `sync_type` enabled,
+    /// not rate limited at `now` and not scheduled since `scheduled_before`
+    async fn fetch_integration_connections_due_for_sync(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        for_user_id: UserId,
+        sync_type: IntegrationConnectionSyncType,
+        scheduled_before: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<IntegrationConnection>, UniversalInboxError>;
+
     async fn create_integration_connection(
         &self,
         executor: &mut Transaction<'_, Postgres>,
@@ -135,6 +153,42 @@ pub trait IntegrationConnectionRepository {
         integration_connection_id: IntegrationConnectionId,
         provider_user_id: Option<String>,
     ) -> Result<UpdateStatus<Box<IntegrationConnection>>, UniversalInboxError>;
+
+    /// Save a sync run, keeping only the `history_size` most recent runs of
+    /// the connection for this kind of sync
+    async fn create_integration_connection_sync_run(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        sync_run: Box<IntegrationConnectionSyncRun>,
+        history_size: i64,
+    ) -> Result<Box<IntegrationConnectionSyncRun>, UniversalInboxError>;
+
+    /// Sync runs of the connection, most recent first
+    async fn fetch_integration_connection_sync_runs(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        integration_connection_id: IntegrationConnectionId,
+    ) -> Result<Vec<IntegrationConnectionSyncRun>, UniversalInboxError>;
+
+    /// Sync runs of all the integration connections started since the given
+    /// time, aggregated per provider
+    async fn fetch_integration_provider_sync_stats(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        started_since: DateTime<Utc>,
+    ) -> Result<Vec<IntegrationProviderSyncStats>, UniversalInboxError>;
+
+    async fn update_integration_connection_rate_limit(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        rate_limit: Box<IntegrationConnectionRateLimit>,
+    ) -> Result<Box<IntegrationConnectionRateLimit>, UniversalInboxError>;
+
+    async fn get_integration_connection_rate_limit(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        integration_connection_id: IntegrationConnectionId,
+    ) -> Result<Option<IntegrationConnectionRateLimit>, UniversalInboxError>;
 }
 
 pub const TOO_MANY_SYNC_FAILURES_ERROR_MESSAGE: &str = "♻️ Synchronization has been failing for too long. Please try to reconnect the integration. If the issue keeps happening, please contact our support.";
@@ -968,6 +1022,137 @@ impl IntegrationConnectionRepository for Repository {
             .collect::<Result<Vec<IntegrationConnection>, UniversalInboxError>>()
     }
 
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            user.id = for_user_id.to_string(),
+            sync_type = sync_type.to_string(),
+            scheduled_before = scheduled_before.to_rfc3339()
+        ),
+        err
+    )]
+    async fn fetch_integration_connections_due_for_sync(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        for_user_id: UserId,
+        sync_type: IntegrationConnectionSyncType,
+        scheduled_before: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<IntegrationConnection>, UniversalInboxError> {
+        let mut query_builder = QueryBuilder::new(
+            r#"
+                SELECT
+                  integration_connection.id,
+                  integration_connection.user_id,
+                  integration_connection.provider_user_id,
+                  integration_connection.status,
+                  integration_connection.failure_message,
+                  integration_connection.created_at,
+                  integration_connection.updated_at,
+                  integration_connection.last_notifications_sync_scheduled_at,
+                  integration_connection.last_notifications_sync_started_at,
+                  integration_connection.last_notifications_sync_completed_at,
+                  integration_connection.last_notifications_sync_failed_at,
+                  integration_connection.last_notifications_sync_failure_message,
+                  integration_connection.notifications_sync_failures,
+                  integration_connection.last_tasks_sync_scheduled_at,
+                  integration_connection.last_tasks_sync_started_at,
+                  integration_connection.last_tasks_sync_completed_at,
+                  integration_connection.last_tasks_sync_failed_at,
+                  integration_connection.last_tasks_sync_failure_message,
+                  integration_connection.tasks_sync_failures,
+                  integration_connection.first_notifications_sync_failed_at,
+                  integration_connection.first_tasks_sync_failed_at,
+                  integration_connection_config.config,
+                  integration_connection.context,
+                  integration_connection.registered_oauth_scopes
+                FROM integration_connection
+                INNER JOIN integration_connection_config
+                  ON integration_connection.id = integration_connection_config.integration_connection_id
+                LEFT JOIN integration_connection_rate_limit
+                  ON integration_connection.id = integration_connection_rate_limit.integration_connection_id
+                WHERE
+            "#,
+        );
+        let mut separated = query_builder.separated(" AND ");
+        separated
+            .push("integration_connection.user_id = ")
+            .push_bind_unseparated(for_user_id.0);
+        separated
+            .push("integration_connection.status::TEXT = ")
+            .push_bind_unseparated(IntegrationConnectionStatus::Validated.to_string());
+        separated
+            .push("(integration_connection_rate_limit.limited_until IS NULL OR integration_connection_rate_limit.limited_until <= ")
+            .push_bind_unseparated(now)
+            .push_unseparated(")");
+
+        // Must match `IntegrationProvider::is_sync_notifications_enabled` and
+        // `IntegrationProvider::is_sync_tasks_enabled`
+        match sync_type {
+            IntegrationConnectionSyncType::Notifications => {
+                separated
+                    .push("(integration_connection.last_notifications_sync_scheduled_at IS NULL OR integration_connection.last_notifications_sync_scheduled_at <= ")
+                    .push_bind_unseparated(scheduled_before)
+                    .push_unseparated(")");
+                // tag: New notification integration
+                // Todoist and TickTick notifications are synced along with their tasks
+                separated.push(
+                    r#"
+                    COALESCE(
+                      CASE integration_connection.provider_kind::TEXT
+                        WHEN 'Github' THEN (integration_connection_config.config->'content'->>'sync_notifications_enabled')::BOOLEAN
+                        WHEN 'Linear' THEN (integration_connection_config.config->'content'->>'sync_notifications_enabled')::BOOLEAN
+                        WHEN 'GoogleDrive' THEN (integration_connection_config.config->'content'->>'sync_notifications_enabled')::BOOLEAN
+                        WHEN 'GoogleMail' THEN (integration_connection_config.config->'content'->>'sync_notifications_enabled')::BOOLEAN
+                        WHEN 'Slack' THEN (integration_connection_config.config->'content'->'message_config'->>'sync_enabled')::BOOLEAN
+                        WHEN 'Todoist' THEN (integration_connection_config.config->'content'->>'sync_tasks_enabled')::BOOLEAN
+                        WHEN 'TickTick' THEN (integration_connection_config.config->'content'->>'sync_tasks_enabled')::BOOLEAN
+                        ELSE FALSE
+                      END,
+                      FALSE
+                    )
+                    "#,
+                );
+            }
+            IntegrationConnectionSyncType::Tasks => {
+                separated
+                    .push("(integration_connection.last_tasks_sync_scheduled_at IS NULL OR integration_connection.last_tasks_sync_scheduled_at <= ")
+                    .push_bind_unseparated(scheduled_before)
+                    .push_unseparated(")");
+                separated.push(
+                    r#"
+                    COALESCE(
+                      CASE integration_connection.provider_kind::TEXT
+                        WHEN 'Todoist' THEN (integration_connection_config.config->'content'->>'sync_tasks_enabled')::BOOLEAN
+                        WHEN 'TickTick' THEN (integration_connection_config.config->'content'->>'sync_tasks_enabled')::BOOLEAN
+                        WHEN 'Linear' THEN (integration_connection_config.config->'content'->'sync_task_config'->>'enabled')::BOOLEAN
+                        WHEN 'Github' THEN (integration_connection_config.config->'content'->'sync_task_config'->>'enabled')::BOOLEAN
+                        ELSE FALSE
+                      END,
+                      FALSE
+                    )
+                    "#,
+                );
+            }
+        }
+
+        let rows = query_builder
+            .build_query_as::<IntegrationConnectionRow>()
+            .fetch_all(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message = format!(
+                    "Failed to fetch integration connections due for {sync_type} sync for user {for_user_id} from storage: {err}"
+                );
+                UniversalInboxError::DatabaseError { source: err, message }
+            })?;
+
+        rows.into_iter()
+            .map(|r| r.try_into())
+            .collect::<Result<Vec<IntegrationConnection>, UniversalInboxError>>()
+    }
+
     #[tracing::instrument(
         level = "debug",
         skip_all,
@@ -1287,6 +1472,286 @@ impl IntegrationConnectionRepository for Repository {
             })
         }
     }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            integration_connection_id = sync_run.integration_connection_id.to_string(),
+            sync_run_kind = sync_run.kind.to_string()
+        ),
+        err
+    )]
+    async fn create_integration_connection_sync_run(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        sync_run: Box<IntegrationConnectionSyncRun>,
+        history_size: i64,
+    ) -> Result<Box<IntegrationConnectionSyncRun>, UniversalInboxError> {
+        sqlx::query(
+            r#"
+              INSERT INTO integration_connection_sync_run
+                (
+                  id,
+                  integration_connection_id,
+                  kind,
+                  started_at,
+                  ended_at,
+                  items_fetched,
+                  items_created,
+                  items_updated,
+                  items_marked_stale,
+                  upstream_http_calls,
+                  rate_limit_remaining,
+                  error_chain
+                )
+              VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            "#,
+        )
+        .bind(sync_run.id.0)
+        .bind(sync_run.integration_connection_id.0)
+        .bind(sync_run.kind.to_string())
+        .bind(sync_run.started_at)
+        .bind(sync_run.ended_at)
+        .bind(sync_run.items_fetched as i32)
+        .bind(sync_run.items_created as i32)
+        .bind(sync_run.items_updated as i32)
+        .bind(sync_run.items_marked_stale as i32)
+        .bind(sync_run.upstream_http_calls as i32)
+        .bind(sync_run.rate_limit_remaining)
+        .bind(&sync_run.error_chain)
+        .execute(&mut **executor)
+        .await
+        .map_err(|err| {
+            let message = format!(
+                "Failed to insert sync run for integration connection {} into storage: {err}",
+                sync_run.integration_connection_id
+            );
+            UniversalInboxError::DatabaseError {
+                source: err,
+                message,
+            }
+        })?;
+
+        sqlx::query(
+            r#"
+              DELETE FROM integration_connection_sync_run
+              WHERE integration_connection_id = $1
+                AND kind = $2
+                AND id NOT IN (
+                  SELECT id
+                  FROM integration_connection_sync_run
+                  WHERE integration_connection_id = $1
+                    AND kind = $2
+                  ORDER BY started_at DESC
+                  LIMIT $3
+                )
+            "#,
+        )
+        .bind(sync_run.integration_connection_id.0)
+        .bind(sync_run.kind.to_string())
+        .bind(history_size)
+        .execute(&mut **executor)
+        .await
+        .map_err(|err| {
+            let message = format!(
+                "Failed to delete old sync runs of integration connection {} from storage: {err}",
+                sync_run.integration_connection_id
+            );
+            UniversalInboxError::DatabaseError {
+                source: err,
+                message,
+            }
+        })?;
+
+        Ok(sync_run)
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(integration_connection_id = integration_connection_id.to_string()),
+        err
+    )]
+    async fn fetch_integration_connection_sync_runs(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        integration_connection_id: IntegrationConnectionId,
+    ) -> Result<Vec<IntegrationConnectionSyncRun>, UniversalInboxError> {
+        let rows = sqlx::query_as::<_, IntegrationConnectionSyncRunRow>(
+            r#"
+              SELECT
+                id,
+                integration_connection_id,
+                kind,
+                started_at,
+                ended_at,
+                items_fetched,
+                items_created,
+                items_updated,
+                items_marked_stale,
+                upstream_http_calls,
+                rate_limit_remaining,
+                error_chain
+              FROM integration_connection_sync_run
+              WHERE integration_connection_id = $1
+              ORDER BY started_at DESC
+            "#,
+        )
+        .bind(integration_connection_id.0)
+        .fetch_all(&mut **executor)
+        .await
+        .map_err(|err| {
+            let message = format!(
+                "Failed to fetch sync runs of integration connection {integration_connection_id} from storage: {err}"
+            );
+            UniversalInboxError::DatabaseError {
+                source: err,
+                message,
+            }
+        })?;
+
+        rows.into_iter()
+            .map(|row| row.try_into())
+            .collect::<Result<Vec<IntegrationConnectionSyncRun>, UniversalInboxError>>()
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(started_since = started_since.to_rfc3339()),
+        err
+    )]
+    async fn fetch_integration_provider_sync_stats(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        started_since: DateTime<Utc>,
+    ) -> Result<Vec<IntegrationProviderSyncStats>, UniversalInboxError> {
+        let rows = sqlx::query_as::<_, IntegrationProviderSyncStatsRow>(
+            r#"
+              SELECT
+                integration_connection.provider_kind::TEXT as provider_kind,
+                COUNT(*) as sync_runs_count,
+                COUNT(*) FILTER (
+                  WHERE cardinality(sync_run.error_chain) > 0
+                ) as failed_sync_runs_count,
+                MAX(sync_run.ended_at) FILTER (
+                  WHERE cardinality(sync_run.error_chain) = 0
+                ) as last_successful_sync_at
+              FROM integration_connection_sync_run as sync_run
+              INNER JOIN integration_connection
+                ON integration_connection.id = sync_run.integration_connection_id
+              WHERE sync_run.started_at >= $1
+              GROUP BY integration_connection.provider_kind
+              ORDER BY integration_connection.provider_kind::TEXT
+            "#,
+        )
+        .bind(started_since)
+        .fetch_all(&mut **executor)
+        .await
+        .map_err(|err| {
+            let message =
+                format!("Failed to fetch sync stats of integration providers from storage: {err}");
+            UniversalInboxError::DatabaseError {
+                source: err,
+                message,
+            }
+        })?;
+
+        rows.into_iter()
+            .map(|row| row.try_into())
+            .collect::<Result<Vec<IntegrationProviderSyncStats>, UniversalInboxError>>()
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(integration_connection_id = rate_limit.integration_connection_id.to_string()),
+        err
+    )]
+    async fn update_integration_connection_rate_limit(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        rate_limit: Box<IntegrationConnectionRateLimit>,
+    ) -> Result<Box<IntegrationConnectionRateLimit>, UniversalInboxError> {
+        sqlx::query(
+            r#"
+              INSERT INTO integration_connection_rate_limit
+                (
+                  integration_connection_id,
+                  remaining,
+                  reset_at,
+                  limited_until,
+                  updated_at
+                )
+              VALUES ($1, $2, $3, $4, $5)
+              ON CONFLICT (integration_connection_id) DO UPDATE
+              SET
+                remaining = EXCLUDED.remaining,
+                reset_at = EXCLUDED.reset_at,
+                limited_until = EXCLUDED.limited_until,
+                updated_at = EXCLUDED.updated_at
+            "#,
+        )
+        .bind(rate_limit.integration_connection_id.0)
+        .bind(rate_limit.remaining)
+        .bind(rate_limit.reset_at)
+        .bind(rate_limit.limited_until)
+        .bind(rate_limit.updated_at)
+        .execute(&mut **executor)
+        .await
+        .map_err(|err| {
+            let message = format!(
+                "Failed to update rate limit of integration connection {} in storage: {err}",
+                rate_limit.integration_connection_id
+            );
+            UniversalInboxError::DatabaseError {
+                source: err,
+                message,
+            }
+        })?;
+
+        Ok(rate_limit)
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(integration_connection_id = integration_connection_id.to_string()),
+        err
+    )]
+    async fn get_integration_connection_rate_limit(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        integration_connection_id: IntegrationConnectionId,
+    ) -> Result<Option<IntegrationConnectionRateLimit>, UniversalInboxError> {
+        let row = sqlx::query_as::<_, IntegrationConnectionRateLimitRow>(
+            r#"
+              SELECT
+                integration_connection_id,
+                remaining,
+                reset_at,
+                limited_until,
+                updated_at
+              FROM integration_connection_rate_limit
+              WHERE integration_connection_id = $1
+            "#,
+        )
+        .bind(integration_connection_id.0)
+        .fetch_optional(&mut **executor)
+        .await
+        .map_err(|err| {
+            let message = format!(
+                "Failed to fetch rate limit of integration connection {integration_connection_id} from storage: {err}"
+            );
+            UniversalInboxError::DatabaseError {
+                source: err,
+                message,
+            }
+        })?;
+
+        Ok(row.map(|row| row.into()))
+    }
 }
 
 #[derive(sqlx::Type, Debug)]
@@ -1411,3 +1876,98 @@ impl TryFrom<&IntegrationConnectionRow> for IntegrationConnection {
         })
     }
 }
+
+#[derive(Debug, sqlx::FromRow)]
+struct IntegrationProviderSyncStatsRow {
+    provider_kind: String,
+    sync_runs_count: i64,
+    failed_sync_runs_count: i64,
+    last_successful_sync_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<IntegrationProviderSyncStatsRow> for IntegrationProviderSyncStats {
+    type Error = UniversalInboxError;
+
+    fn try_from(row: IntegrationProviderSyncStatsRow) -> Result<Self, Self::Error> {
+        let provider_kind =
+            row.provider_kind
+                .parse()
+                .map_err(|e| UniversalInboxError::InvalidEnumData {
+                    source: e,
+                    output: row.provider_kind.clone(),
+                })?;
+
+        Ok(IntegrationProviderSyncStats {
+            provider_kind,
+            sync_runs_count: row.sync_runs_count as u64,
+            failed_sync_runs_count: row.failed_sync_runs_count as u64,
+            last_successful_sync_at: row.last_successful_sync_at,
+        })
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct IntegrationConnectionSyncRunRow {
+    id: Uuid,
+    integration_connection_id: Uuid,
+    kind: String,
+    started_at: DateTime<Utc>,
+    ended_at: DateTime<Utc>,
+    items_fetched: i32,
+    items_created: i32,
+    items_updated: i32,
+    items_marked_stale: i32,
+    upstream_http_calls: i32,
+    rate_limit_remaining: Option<i64>,
+    error_chain: Vec<String>,
+}
+
+impl TryFrom<IntegrationConnectionSyncRunRow> for IntegrationConnectionSyncRun {
+    type Error = UniversalInboxError;
+
+    fn try_from(row: IntegrationConnectionSyncRunRow) -> Result<Self, Self::Error> {
+        let kind = row
+            .kind
+            .parse()
+            .map_err(|e| UniversalInboxError::InvalidEnumData {
+                source: e,
+                output: row.kind.clone(),
+            })?;
+
+        Ok(IntegrationConnectionSyncRun {
+            id: row.id.into(),
+            integration_connection_id: row.integration_connection_id.into(),
+            kind,
+            started_at: row.started_at,
+            ended_at: row.ended_at,
+            items_fetched: row.items_fetched as u32,
+            items_created: row.items_created as u32,
+            items_updated: row.items_updated as u32,
+            items_marked_stale: row.items_marked_stale as u32,
+            upstream_http_calls: row.upstream_http_calls as u32,
+            rate_limit_remaining: row.rate_limit_remaining,
+            error_chain: row.error_chain,
+        })
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct IntegrationConnectionRateLimitRow {
+    integration_connection_id: Uuid,
+    remaining: Option<i64>,
+    reset_at: Option<DateTime<Utc>>,
+    limited_until: Option<DateTime<Utc>>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<IntegrationConnectionRateLimitRow> for IntegrationConnectionRateLimit {
+    fn from(row: IntegrationConnectionRateLimitRow) -> Self {
+        IntegrationConnectionRateLimit {
+            integration_connection_id: row.integration_connection_id.into(),
+            remaining: row.remaining,
+            reset_at: row.reset_at,
+            limited_until: row.limited_until,
+            updated_at: row.updated_at,
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::{Postgres, QueryBuilder, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    jobs::dead_letter::{DeadLetterJob, DeadLetterJobFilter},
+    repository::Repository,
+    universal_inbox::UniversalInboxError,
+};
+
+#[async_trait]
+pub trait JobRepository {
+    async fn create_dead_letter_job(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        dead_letter_job: Box<DeadLetterJob>,
+    ) -> Result<Box<DeadLetterJob>, UniversalInboxError>;
+
+    async fn fetch_dead_letter_jobs(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        filter: &DeadLetterJobFilter,
+    ) -> Result<Vec<DeadLetterJob>, UniversalInboxError>;
+
+    async fn count_dead_letter_jobs(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        filter: &DeadLetterJobFilter,
+    ) -> Result<u64, UniversalInboxError>;
+
+    async fn delete_dead_letter_jobs(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        filter: &DeadLetterJobFilter,
+    ) -> Result<u64, UniversalInboxError>;
+}
+
+#[async_trait]
+impl JobRepository for Repository {
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            job_id = %dead_letter_job.job_id,
+            job_name = %dead_letter_job.name
+        ),
+        err
+    )]
+    async fn create_dead_letter_job(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        dead_letter_job: Box<DeadLetterJob>,
+    ) -> Result<Box<DeadLetterJob>, UniversalInboxError> {
+        sqlx::query(
+            r#"
+              INSERT INTO dead_letter_job
+                (
+                  id,
+                  job_id,
+                  name,
+                  user_id,
+                  payload,
+                  error_chain,
+                  attempts,
+                  failed_at
+                )
+              VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(dead_letter_job.id.0)
+        .bind(&dead_letter_job.job_id)
+        .bind(&dead_letter_job.name)
+        .bind(dead_letter_job.user_id.map(|user_id| user_id.0))
+        .bind(&dead_letter_job.payload)
+        .bind(&dead_letter_job.error_chain)
+        .bind(dead_letter_job.attempts as i32)
+        .bind(dead_letter_job.failed_at)
+        .execute(&mut **executor)
+        .await
+        .map_err(|err| {
+            let message = format!(
+                "Failed to insert dead-lettered job {} into storage: {err}",
+                dead_letter_job.job_id
+            );
+            UniversalInboxError::DatabaseError {
+                source: err,
+                message,
+            }
+        })?;
+
+        Ok(dead_letter_job)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all, fields(?filter), err)]
+    async fn fetch_dead_letter_jobs(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        filter: &DeadLetterJobFilter,
+    ) -> Result<Vec<DeadLetterJob>, UniversalInboxError> {
+        let mut query_builder = QueryBuilder::new(
+            r#"
+              SELECT
+                id,
+                job_id,
+                name,
+                user_id,
+                payload,
+                error_chain,
+                attempts,
+                failed_at
+              FROM dead_letter_job
+            "#,
+        );
+        push_dead_letter_job_filter(&mut query_builder, filter);
+        query_builder.push(" ORDER BY failed_at DESC");
+        if let Some(limit) = filter.limit {
+            query_builder.push(" LIMIT ").push_bind(limit);
+        }
+
+        let rows = query_builder
+            .build_query_as::<DeadLetterJobRow>()
+            .fetch_all(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message = format!("Failed to fetch dead-lettered jobs from storage: {err}");
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
+        Ok(rows.into_iter().map(|row| row.into()).collect())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all, fields(?filter), err)]
+    async fn count_dead_letter_jobs(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        filter: &DeadLetterJobFilter,
+    ) -> Result<u64, UniversalInboxError> {
+        let mut query_builder = QueryBuilder::new("SELECT count(*) FROM dead_letter_job");
+        push_dead_letter_job_filter(&mut query_builder, filter);
+
+        let count: i64 = query_builder
+            .build_query_scalar()
+            .fetch_one(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message = format!("Failed to count dead-lettered jobs from storage: {err}");
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
+        Ok(filter.limit.map_or(count, |limit| count.min(limit)) as u64)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all, fields(?filter), err)]
+    async fn delete_dead_letter_jobs(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        filter: &DeadLetterJobFilter,
+    ) -> Result<u64, UniversalInboxError> {
+        let mut query_builder = QueryBuilder::new("DELETE FROM dead_letter_job");
+        if let Some(limit) = filter.limit {
+            query_builder.push(" WHERE id IN (SELECT id FROM dead_letter_job");
+            push_dead_letter_job_filter(&mut query_builder, filter);
+            query_builder
+                .push(" ORDER BY failed_at LIMIT ")
+                .push_bind(limit)
+                .push(")");
+        } else {
+            push_dead_letter_job_filter(&mut query_builder, filter);
+        }
+
+        let result = query_builder
+            .build()
+            .execute(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message = format!("Failed to delete dead-lettered jobs from storage: {err}");
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+fn push_dead_letter_job_filter(
+    query_builder: &mut QueryBuilder<Postgres>,
+    filter: &DeadLetterJobFilter,
+) {
+    let mut separated = query_builder.separated(" AND ");
+    if !filter.is_empty() {
+        separated.push_unseparated(" WHERE ");
+    }
+    if let Some(id) = filter.id {
+        separated.push("id = ").push_bind_unseparated(id.0);
+    }
+    if let Some(name) = &filter.name {
+        separated
+            .push("name = ")
+            .push_bind_unseparated(name.clone());
+    }
+    if let Some(user_id) = filter.user_id {
+        separated
+            .push("user_id = ")
+            .push_bind_unseparated(user_id.0);
+    }
+    if let Some(failed_before) = filter.failed_before {
+        separated
+            .push("failed_at < ")
+            .push_bind_unseparated(failed_before);
+    }
+    if let Some(failed_after) = filter.failed_after {
+        separated
+            .push("failed_at >= ")
+            .push_bind_unseparated(failed_after);
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct DeadLetterJobRow {
+    id: Uuid,
+    job_id: String,
+    name: String,
+    user_id: Option<Uuid>,
+    payload: Value,
+    error_chain: Vec<String>,
+    attempts: i32,
+    failed_at: DateTime<Utc>,
+}
+
+impl From<DeadLetterJobRow> for DeadLetterJob {
+    fn from(row: DeadLetterJobRow) -> Self {
+        DeadLetterJob {
+            id: row.id.into(),
+            job_id: row.job_id,
+            name: row.name,
+            user_id: row.user_id.map(|user_id| user_id.into()),
+            payload: row.payload,
+            error_chain: row.error_chain,
+            attempts: row.attempts as u32,
+            failed_at: row.failed_at,
+        }
+    }
+}
@@ -1,12 +1,15 @@
 use std::sync::Arc;
 
 use anyhow::Context;
-use sqlx::{PgPool, Postgres, Row, Transaction, pool::PoolConnection, postgres::PgRow};
+use sqlx::{
+    PgPool, Postgres, Row, Transaction, migrate::Migrator, pool::PoolConnection, postgres::PgRow,
+};
 
 use crate::universal_inbox::UniversalInboxError;
 
 pub mod auth_token;
 pub mod integration_connection;
+pub mod job;
 pub mod notification;
 pub mod oauth2;
 pub mod oauth_credential;
@@ -16,6 +19,9 @@ pub mod third_party;
 pub mod user;
 pub mod user_preferences;
 
+/// Migrations embedded at build time, to check they have all been applied
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
 #[derive(Debug)]
 pub struct Repository {
     pub pool: Arc<PgPool>,
@@ -41,6 +47,28 @@ impl Repository {
             .await
             .context("Failed to begin database transaction")?)
     }
+
+    /// Versions of the embedded migrations not applied to the database yet
+    pub async fn fetch_pending_migration_versions(&self) -> Result<Vec<i64>, UniversalInboxError> {
+        let applied_versions: Vec<i64> =
+            sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success")
+                .fetch_all(&*self.pool)
+                .await
+                .map_err(|err| {
+                    let message = format!("Failed to fetch applied migrations: {err}");
+                    UniversalInboxError::DatabaseError {
+                        source: err,
+                        message,
+                    }
+                })?;
+
+        Ok(MIGRATOR
+            .iter()
+            .filter(|migration| !migration.migration_type.is_down_migration())
+            .map(|migration| migration.version)
+            .filter(|version| !applied_versions.contains(version))
+            .collect())
+    }
 }
 
 trait FromRowWithPrefix<'r, R>: Sized
@@ -1,3 +1,6 @@
+use std::str::FromStr;
+
+use anyhow::anyhow;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use sqlx::{Postgres, QueryBuilder, Transaction};
@@ -5,8 +8,8 @@ use uuid::Uuid;
 
 use universal_inbox::{
     auth::oauth2::{
-        AuthorizedOAuth2Client, OAuth2AuthorizationCode, OAuth2Client, OAuth2RefreshToken,
-        OAuth2UserConsent,
+        AuthorizedOAuth2Client, OAuth2AuthorizationCode, OAuth2Client, OAuth2DeviceAuthorization,
+        OAuth2DeviceAuthorizationStatus, OAuth2RefreshToken, OAuth2UserConsent,
     },
     user::UserId,
 };
@@ -77,6 +80,59 @@ pub trait OAuth2Repository {
         code: &str,
     ) -> Result<Option<OAuth2AuthorizationCode>, UniversalInboxError>;
 
+    async fn create_device_authorization(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        device_code_hash: &str,
+        user_code: &str,
+        client_id: &str,
+        scope: Option<&str>,
+        resource: Option<&str>,
+        polling_interval_secs: i32,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), UniversalInboxError>;
+
+    /// Look up a pending, not yet expired, device authorization by its
+    /// normalized user code.
+    async fn get_pending_device_authorization_by_user_code(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_code: &str,
+    ) -> Result<Option<OAuth2DeviceAuthorization>, UniversalInboxError>;
+
+    /// Record the user's decision on a pending, not yet expired, device
+    /// authorization. Returns `false` when no such request matched.
+    async fn decide_device_authorization(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_code: &str,
+        user_id: UserId,
+        status: OAuth2DeviceAuthorizationStatus,
+    ) -> Result<bool, UniversalInboxError>;
+
+    /// Lock the device authorization matching `device_code_hash` and
+    /// `client_id` for the duration of the transaction, so that concurrent
+    /// polls are serialized.
+    async fn get_device_authorization_for_update(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        device_code_hash: &str,
+        client_id: &str,
+    ) -> Result<Option<OAuth2DeviceAuthorization>, UniversalInboxError>;
+
+    async fn record_device_authorization_poll(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        device_code_hash: &str,
+        polling_interval_secs: i32,
+    ) -> Result<(), UniversalInboxError>;
+
+    async fn delete_device_authorization(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        device_code_hash: &str,
+    ) -> Result<(), UniversalInboxError>;
+
     async fn create_refresh_token(
         &self,
         executor: &mut Transaction<'_, Postgres>,
@@ -86,6 +142,8 @@ pub trait OAuth2Repository {
         scope: Option<&str>,
         resource: Option<&str>,
         expires_at: Option<DateTime<Utc>>,
+        access_token_jti: Option<&str>,
+        access_token_expires_at: Option<DateTime<Utc>>,
     ) -> Result<(), UniversalInboxError>;
 
     async fn get_refresh_token_by_hash(
@@ -106,11 +164,14 @@ pub trait OAuth2Repository {
         token_hash: &str,
     ) -> Result<Option<OAuth2RefreshToken>, UniversalInboxError>;
 
+    /// Revoke an active refresh token issued to `client_id`. Returns the
+    /// revoked row, or `None` when no active token matched.
     async fn revoke_refresh_token(
         &self,
         executor: &mut Transaction<'_, Postgres>,
         token_hash: &str,
-    ) -> Result<(), UniversalInboxError>;
+        client_id: &str,
+    ) -> Result<Option<OAuth2RefreshToken>, UniversalInboxError>;
 
     /// Atomically claim and revoke a refresh token in a single SQL statement.
     ///
@@ -136,6 +197,9 @@ pub trait OAuth2Repository {
         user_id: UserId,
     ) -> Result<Vec<AuthorizedOAuth2Client>, UniversalInboxError>;
 
+    /// Revoke every refresh token of the `(client_id, user_id)` family and
+    /// add the still-valid access tokens issued alongside them to the
+    /// revoked access tokens.
     async fn revoke_all_refresh_tokens_for_client(
         &self,
         executor: &mut Transaction<'_, Postgres>,
@@ -143,6 +207,21 @@ pub trait OAuth2Repository {
         client_id: &str,
     ) -> Result<u64, UniversalInboxError>;
 
+    /// Add an access token `jti` to the revoked access tokens until the token
+    /// expires. Entries of already expired tokens are purged on the way.
+    async fn revoke_access_token(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        jti: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), UniversalInboxError>;
+
+    async fn is_access_token_revoked(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        jti: &str,
+    ) -> Result<bool, UniversalInboxError>;
+
     async fn get_user_consent(
         &self,
         executor: &mut Transaction<'_, Postgres>,
@@ -404,6 +483,230 @@ impl OAuth2Repository for Repository {
         Ok(row.map(|r| r.into()))
     }
 
+    #[tracing::instrument(level = "debug", skip_all, fields(client_id), err)]
+    async fn create_device_authorization(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        device_code_hash: &str,
+        user_code: &str,
+        client_id: &str,
+        scope: Option<&str>,
+        resource: Option<&str>,
+        polling_interval_secs: i32,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), UniversalInboxError> {
+        // Expired requests are never returned, purge them on the way.
+        sqlx::query("DELETE FROM oauth2_device_authorization WHERE expires_at <= now()")
+            .execute(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message =
+                    format!("Failed to purge expired OAuth2 device authorizations: {err}");
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
+        let mut query_builder = QueryBuilder::new(
+            r#"
+                INSERT INTO oauth2_device_authorization
+                  (device_code_hash, user_code, client_id, scope, resource,
+                   polling_interval_secs, expires_at)
+                VALUES (
+            "#,
+        );
+        let mut separated = query_builder.separated(", ");
+        separated.push_bind(device_code_hash);
+        separated.push_bind(user_code);
+        separated.push_bind(client_id);
+        separated.push_bind(scope);
+        separated.push_bind(resource);
+        separated.push_bind(polling_interval_secs);
+        separated.push_bind(expires_at);
+        query_builder.push(")");
+
+        query_builder
+            .build()
+            .execute(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message =
+                    format!("Failed to insert OAuth2 device authorization into storage: {err}");
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all, err)]
+    async fn get_pending_device_authorization_by_user_code(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_code: &str,
+    ) -> Result<Option<OAuth2DeviceAuthorization>, UniversalInboxError> {
+        let mut query_builder = QueryBuilder::new(format!(
+            r#"
+                SELECT {OAUTH2_DEVICE_AUTHORIZATION_COLUMNS}
+                FROM oauth2_device_authorization
+                WHERE user_code =
+            "#
+        ));
+        query_builder.push_bind(user_code);
+        query_builder.push(" AND status = ");
+        query_builder.push_bind(OAuth2DeviceAuthorizationStatus::Pending.to_string());
+        query_builder.push(" AND expires_at > now()");
+
+        let row = query_builder
+            .build_query_as::<OAuth2DeviceAuthorizationRow>()
+            .fetch_optional(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message =
+                    format!("Failed to fetch OAuth2 device authorization from storage: {err}");
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
+        row.map(TryInto::try_into).transpose()
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(user.id = user_id.to_string(), status = status.to_string()),
+        err
+    )]
+    async fn decide_device_authorization(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_code: &str,
+        user_id: UserId,
+        status: OAuth2DeviceAuthorizationStatus,
+    ) -> Result<bool, UniversalInboxError> {
+        let mut query_builder =
+            QueryBuilder::new("UPDATE oauth2_device_authorization SET status = ");
+        query_builder.push_bind(status.to_string());
+        query_builder.push(", user_id = ");
+        query_builder.push_bind(user_id.0);
+        query_builder.push(" WHERE user_code = ");
+        query_builder.push_bind(user_code);
+        query_builder.push(" AND status = ");
+        query_builder.push_bind(OAuth2DeviceAuthorizationStatus::Pending.to_string());
+        query_builder.push(" AND expires_at > now()");
+
+        let result = query_builder
+            .build()
+            .execute(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message =
+                    format!("Failed to update OAuth2 device authorization in storage: {err}");
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all, fields(client_id), err)]
+    async fn get_device_authorization_for_update(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        device_code_hash: &str,
+        client_id: &str,
+    ) -> Result<Option<OAuth2DeviceAuthorization>, UniversalInboxError> {
+        let mut query_builder = QueryBuilder::new(format!(
+            r#"
+                SELECT {OAUTH2_DEVICE_AUTHORIZATION_COLUMNS}
+                FROM oauth2_device_authorization
+                WHERE device_code_hash =
+            "#
+        ));
+        query_builder.push_bind(device_code_hash);
+        query_builder.push(" AND client_id = ");
+        query_builder.push_bind(client_id);
+        query_builder.push(" FOR UPDATE");
+
+        let row = query_builder
+            .build_query_as::<OAuth2DeviceAuthorizationRow>()
+            .fetch_optional(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message =
+                    format!("Failed to fetch OAuth2 device authorization from storage: {err}");
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
+        row.map(TryInto::try_into).transpose()
+    }
+
+    #[tracing::instrument(level = "debug", skip_all, err)]
+    async fn record_device_authorization_poll(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        device_code_hash: &str,
+        polling_interval_secs: i32,
+    ) -> Result<(), UniversalInboxError> {
+        let mut query_builder = QueryBuilder::new(
+            "UPDATE oauth2_device_authorization SET last_polled_at = now(), polling_interval_secs = ",
+        );
+        query_builder.push_bind(polling_interval_secs);
+        query_builder.push(" WHERE device_code_hash = ");
+        query_builder.push_bind(device_code_hash);
+
+        query_builder
+            .build()
+            .execute(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message =
+                    format!("Failed to record OAuth2 device authorization poll in storage: {err}");
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all, err)]
+    async fn delete_device_authorization(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        device_code_hash: &str,
+    ) -> Result<(), UniversalInboxError> {
+        let mut query_builder =
+            QueryBuilder::new("DELETE FROM oauth2_device_authorization WHERE device_code_hash = ");
+        query_builder.push_bind(device_code_hash);
+
+        query_builder
+            .build()
+            .execute(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message =
+                    format!("Failed to delete OAuth2 device authorization from storage: {err}");
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
+        Ok(())
+    }
+
     #[tracing::instrument(
         level = "debug",
         skip_all,
@@ -419,11 +722,14 @@ impl OAuth2Repository for Repository {
         scope: Option<&str>,
         resource: Option<&str>,
         expires_at: Option<DateTime<Utc>>,
+        access_token_jti: Option<&str>,
+        access_token_expires_at: Option<DateTime<Utc>>,
     ) -> Result<(), UniversalInboxError> {
         let mut query_builder = QueryBuilder::new(
             r#"
                 INSERT INTO oauth2_refresh_token
-                  (token_hash, client_id, user_id, scope, resource, expires_at)
+                  (token_hash, client_id, user_id, scope, resource, expires_at,
+                   access_token_jti, access_token_expires_at)
                 VALUES (
             "#,
         );
@@ -434,6 +740,8 @@ impl OAuth2Repository for Repository {
         separated.push_bind(scope);
         separated.push_bind(resource);
         separated.push_bind(expires_at.map(|t| t.naive_utc()));
+        separated.push_bind(access_token_jti);
+        separated.push_bind(access_token_expires_at);
         query_builder.push(")");
 
         query_builder
@@ -461,7 +769,8 @@ impl OAuth2Repository for Repository {
             r#"
                 SELECT
                   id, token_hash, client_id, user_id, scope,
-                  resource, expires_at, created_at, revoked_at
+                  resource, expires_at, created_at, revoked_at,
+                  access_token_jti, access_token_expires_at
                 FROM oauth2_refresh_token
                 WHERE token_hash =
             "#,
@@ -494,7 +803,8 @@ impl OAuth2Repository for Repository {
             r#"
                 SELECT
                   id, token_hash, client_id, user_id, scope,
-                  resource, expires_at, created_at, revoked_at
+                  resource, expires_at, created_at, revoked_at,
+                  access_token_jti, access_token_expires_at
                 FROM oauth2_refresh_token
                 WHERE token_hash =
             "#,
@@ -552,7 +862,8 @@ impl OAuth2Repository for Repository {
                 AND (expires_at IS NULL OR expires_at > now())
                 RETURNING
                   id, token_hash, client_id, user_id, scope,
-                  resource, expires_at, created_at, revoked_at
+                  resource, expires_at, created_at, revoked_at,
+                  access_token_jti, access_token_expires_at
             "#,
         );
 
@@ -572,12 +883,13 @@ impl OAuth2Repository for Repository {
         Ok(row.map(|r| r.into()))
     }
 
-    #[tracing::instrument(level = "debug", skip_all, err)]
+    #[tracing::instrument(level = "debug", skip_all, fields(client_id), err)]
     async fn revoke_refresh_token(
         &self,
         executor: &mut Transaction<'_, Postgres>,
         token_hash: &str,
-    ) -> Result<(), UniversalInboxError> {
+        client_id: &str,
+    ) -> Result<Option<OAuth2RefreshToken>, UniversalInboxError> {
         let mut query_builder = QueryBuilder::new(
             r#"
                 UPDATE oauth2_refresh_token
@@ -586,11 +898,21 @@ impl OAuth2Repository for Repository {
             "#,
         );
         query_builder.push_bind(token_hash);
-        query_builder.push(" AND revoked_at IS NULL");
+        query_builder.push(" AND client_id = ");
+        query_builder.push_bind(client_id);
+        query_builder.push(
+            r#"
+                AND revoked_at IS NULL
+                RETURNING
+                  id, token_hash, client_id, user_id, scope,
+                  resource, expires_at, created_at, revoked_at,
+                  access_token_jti, access_token_expires_at
+            "#,
+        );
 
-        query_builder
-            .build()
-            .execute(&mut **executor)
+        let row = query_builder
+            .build_query_as::<OAuth2RefreshTokenRow>()
+            .fetch_optional(&mut **executor)
             .await
             .map_err(|err| {
                 let message = format!("Failed to revoke OAuth2 refresh token in storage: {err}");
@@ -600,7 +922,7 @@ impl OAuth2Repository for Repository {
                 }
             })?;
 
-        Ok(())
+        Ok(row.map(|r| r.into()))
     }
 
     #[tracing::instrument(
@@ -705,9 +1027,117 @@ impl OAuth2Repository for Repository {
                 }
             })?;
 
+        // Access tokens minted from already rotated refresh tokens may still be
+        // valid, so every row of the family is considered, not only the ones
+        // revoked above.
+        let mut query_builder = QueryBuilder::new(
+            r#"
+                INSERT INTO oauth2_revoked_access_token (jti, expires_at)
+                SELECT access_token_jti, access_token_expires_at
+                FROM oauth2_refresh_token
+                WHERE user_id =
+            "#,
+        );
+        query_builder.push_bind(user_id.0);
+        query_builder.push(" AND client_id = ");
+        query_builder.push_bind(client_id);
+        query_builder.push(
+            r#"
+                AND access_token_jti IS NOT NULL
+                AND access_token_expires_at > now()
+                ON CONFLICT (jti) DO NOTHING
+            "#,
+        );
+
+        query_builder
+            .build()
+            .execute(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message =
+                    format!("Failed to revoke OAuth2 access tokens for client in storage: {err}");
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
         Ok(result.rows_affected())
     }
 
+    #[tracing::instrument(level = "debug", skip_all, err)]
+    async fn revoke_access_token(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        jti: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), UniversalInboxError> {
+        sqlx::query("DELETE FROM oauth2_revoked_access_token WHERE expires_at <= now()")
+            .execute(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message =
+                    format!("Failed to purge expired revoked OAuth2 access tokens: {err}");
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
+        let mut query_builder = QueryBuilder::new(
+            r#"
+                INSERT INTO oauth2_revoked_access_token (jti, expires_at)
+                VALUES (
+            "#,
+        );
+        let mut separated = query_builder.separated(", ");
+        separated.push_bind(jti);
+        separated.push_bind(expires_at);
+        query_builder.push(") ON CONFLICT (jti) DO NOTHING");
+
+        query_builder
+            .build()
+            .execute(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message = format!("Failed to revoke OAuth2 access token in storage: {err}");
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all, err)]
+    async fn is_access_token_revoked(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        jti: &str,
+    ) -> Result<bool, UniversalInboxError> {
+        let mut query_builder = QueryBuilder::new(
+            "SELECT EXISTS (SELECT 1 FROM oauth2_revoked_access_token WHERE jti = ",
+        );
+        query_builder.push_bind(jti);
+        query_builder.push(")");
+
+        let is_revoked: bool = query_builder
+            .build_query_scalar()
+            .fetch_one(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message =
+                    format!("Failed to check OAuth2 access token revocation in storage: {err}");
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
+        Ok(is_revoked)
+    }
+
     #[tracing::instrument(
         level = "debug",
         skip_all,
@@ -1048,6 +1478,53 @@ impl From<OAuth2AuthorizationCodeRow> for OAuth2AuthorizationCode {
     }
 }
 
+const OAUTH2_DEVICE_AUTHORIZATION_COLUMNS: &str = r#"
+  device_code_hash, user_code, client_id, scope, resource, status, user_id,
+  polling_interval_secs, last_polled_at, expires_at, created_at
+"#;
+
+#[derive(Debug, sqlx::FromRow)]
+struct OAuth2DeviceAuthorizationRow {
+    device_code_hash: String,
+    user_code: String,
+    client_id: String,
+    scope: Option<String>,
+    resource: Option<String>,
+    status: String,
+    user_id: Option<Uuid>,
+    polling_interval_secs: i32,
+    last_polled_at: Option<DateTime<Utc>>,
+    expires_at: DateTime<Utc>,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<OAuth2DeviceAuthorizationRow> for OAuth2DeviceAuthorization {
+    type Error = UniversalInboxError;
+
+    fn try_from(row: OAuth2DeviceAuthorizationRow) -> Result<Self, Self::Error> {
+        let status = OAuth2DeviceAuthorizationStatus::from_str(&row.status).map_err(|_| {
+            UniversalInboxError::Unexpected(anyhow!(
+                "Invalid OAuth2 device authorization status: {}",
+                row.status
+            ))
+        })?;
+
+        Ok(OAuth2DeviceAuthorization {
+            device_code_hash: row.device_code_hash,
+            user_code: row.user_code,
+            client_id: row.client_id,
+            scope: row.scope,
+            resource: row.resource,
+            status,
+            user_id: row.user_id.map(Into::into),
+            polling_interval_secs: row.polling_interval_secs,
+            last_polled_at: row.last_polled_at,
+            expires_at: row.expires_at,
+            created_at: row.created_at,
+        })
+    }
+}
+
 #[derive(Debug, sqlx::FromRow)]
 pub struct OAuth2RefreshTokenRow {
     pub id: Uuid,
@@ -1059,6 +1536,8 @@ pub struct OAuth2RefreshTokenRow {
     pub expires_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub revoked_at: Option<DateTime<Utc>>,
+    pub access_token_jti: Option<String>,
+    pub access_token_expires_at: Option<DateTime<Utc>>,
 }
 
 impl From<OAuth2RefreshTokenRow> for OAuth2RefreshToken {
@@ -1073,6 +1552,8 @@ impl From<OAuth2RefreshTokenRow> for OAuth2RefreshToken {
             expires_at: row.expires_at,
             created_at: row.created_at,
             revoked_at: row.revoked_at,
+            access_token_jti: row.access_token_jti,
+            access_token_expires_at: row.access_token_expires_at,
         }
     }
 }
@@ -24,6 +24,14 @@ pub struct StoredOAuthCredential {
     pub updated_at: DateTime<Utc>,
 }
 
+/// Encrypted tokens of a credential, to re-encrypt them with another key.
+#[derive(Debug, Clone)]
+pub struct OAuthCredentialCiphertexts {
+    pub integration_connection_id: IntegrationConnectionId,
+    pub encrypted_access_token: Vec<u8>,
+    pub encrypted_refresh_token: Option<Vec<u8>>,
+}
+
 /// Minimal info needed for the eager token refresh command.
 #[derive(Debug, Clone)]
 pub struct ExpiringOAuthCredential {
@@ -63,6 +71,22 @@ pub trait OAuthCredentialRepository {
         expiring_before: DateTime<Utc>,
         provider_kind: Option<IntegrationProviderKind>,
     ) -> Result<Vec<ExpiringOAuthCredential>, UniversalInboxError>;
+
+    /// Encrypted tokens of at most `limit` credentials, ordered by integration
+    /// connection ID and starting after `after`, locked until the end of the
+    /// transaction
+    async fn fetch_oauth_credential_ciphertexts(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        after: Option<IntegrationConnectionId>,
+        limit: i64,
+    ) -> Result<Vec<OAuthCredentialCiphertexts>, UniversalInboxError>;
+
+    async fn update_oauth_credential_ciphertexts(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        ciphertexts: &OAuthCredentialCiphertexts,
+    ) -> Result<(), UniversalInboxError>;
 }
 
 #[async_trait]
@@ -266,4 +290,87 @@ impl OAuthCredentialRepository for Repository {
             })
             .collect()
     }
+
+    #[tracing::instrument(level = "debug", skip_all, err)]
+    async fn fetch_oauth_credential_ciphertexts(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        after: Option<IntegrationConnectionId>,
+        limit: i64,
+    ) -> Result<Vec<OAuthCredentialCiphertexts>, UniversalInboxError> {
+        let rows = sqlx::query_as::<_, OAuthCredentialCiphertextsRow>(
+            r#"
+                SELECT integration_connection_id, encrypted_access_token, encrypted_refresh_token
+                FROM oauth_credential
+                WHERE $1::UUID IS NULL OR integration_connection_id > $1
+                ORDER BY integration_connection_id
+                LIMIT $2
+                FOR UPDATE
+            "#,
+        )
+        .bind(after.map(Uuid::from))
+        .bind(limit)
+        .fetch_all(&mut **executor)
+        .await
+        .map_err(|err| {
+            let message = format!("Failed to fetch OAuth credential ciphertexts: {err}");
+            UniversalInboxError::DatabaseError {
+                source: err,
+                message,
+            }
+        })?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| OAuthCredentialCiphertexts {
+                integration_connection_id: IntegrationConnectionId(row.integration_connection_id),
+                encrypted_access_token: row.encrypted_access_token,
+                encrypted_refresh_token: row.encrypted_refresh_token,
+            })
+            .collect())
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(integration_connection.id = ciphertexts.integration_connection_id.to_string()),
+        err
+    )]
+    async fn update_oauth_credential_ciphertexts(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        ciphertexts: &OAuthCredentialCiphertexts,
+    ) -> Result<(), UniversalInboxError> {
+        let integration_connection_id = ciphertexts.integration_connection_id;
+        sqlx::query(
+            r#"
+                UPDATE oauth_credential
+                SET encrypted_access_token = $2, encrypted_refresh_token = $3
+                WHERE integration_connection_id = $1
+            "#,
+        )
+        .bind(Uuid::from(integration_connection_id))
+        .bind(&ciphertexts.encrypted_access_token)
+        .bind(ciphertexts.encrypted_refresh_token.as_deref())
+        .execute(&mut **executor)
+        .await
+        .map_err(|err| {
+            let message = format!(
+                "Failed to update OAuth credential ciphertexts for integration connection {integration_connection_id}: {err}"
+            );
+            UniversalInboxError::DatabaseError {
+                source: err,
+                message,
+            }
+        })?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct OAuthCredentialCiphertextsRow {
+    integration_connection_id: Uuid,
+    encrypted_access_token: Vec<u8>,
+    encrypted_refresh_token: Option<Vec<u8>>,
 }
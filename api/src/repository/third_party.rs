@@ -14,7 +14,9 @@ use universal_inbox::{
 
 use crate::{
     repository::Repository,
-    universal_inbox::{UniversalInboxError, UpsertStatus},
+    universal_inbox::{
+        UniversalInboxError, UpsertStatus, third_party::retention::ThirdPartyItemTombstone,
+    },
 };
 
 use super::FromRowWithPrefix;
@@ -64,6 +66,59 @@ pub trait ThirdPartyItemRepository {
         notification_status: NotificationStatus,
         user_id: UserId,
     ) -> Result<Vec<ThirdPartyItem>, UniversalInboxError>;
+
+    async fn count_purgeable_notification_third_party_items(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        notification_status: NotificationStatus,
+        status_updated_before: DateTime<Utc>,
+    ) -> Result<i64, UniversalInboxError>;
+
+    async fn fetch_purgeable_notification_third_party_items(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        notification_status: NotificationStatus,
+        status_updated_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<ThirdPartyItem>, UniversalInboxError>;
+
+    async fn count_orphan_third_party_items(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        updated_before: DateTime<Utc>,
+    ) -> Result<i64, UniversalInboxError>;
+
+    async fn delete_orphan_third_party_items(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        updated_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<u64, UniversalInboxError>;
+
+    async fn delete_third_party_items(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        third_party_item_ids: &[ThirdPartyItemId],
+    ) -> Result<u64, UniversalInboxError>;
+
+    async fn create_or_update_third_party_item_tombstones(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        tombstones: &[ThirdPartyItemTombstone],
+    ) -> Result<(), UniversalInboxError>;
+
+    async fn find_third_party_item_tombstones(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        source_ids: &[String],
+        user_id: UserId,
+    ) -> Result<Vec<ThirdPartyItemTombstone>, UniversalInboxError>;
+
+    async fn delete_third_party_item_tombstone(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        tombstone: &ThirdPartyItemTombstone,
+    ) -> Result<(), UniversalInboxError>;
 }
 
 #[async_trait]
@@ -544,6 +599,448 @@ impl ThirdPartyItemRepository for Repository {
             .map(|r| r.try_into())
             .collect::<Result<Vec<ThirdPartyItem>, UniversalInboxError>>()
     }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(notification_status = notification_status.to_string()),
+        err
+    )]
+    async fn count_purgeable_notification_third_party_items(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        notification_status: NotificationStatus,
+        status_updated_before: DateTime<Utc>,
+    ) -> Result<i64, UniversalInboxError> {
+        let mut query_builder = QueryBuilder::new("SELECT count(*) FROM third_party_item");
+        push_purgeable_notification_third_party_items_filter(
+            &mut query_builder,
+            notification_status,
+            status_updated_before,
+        );
+
+        query_builder
+            .build_query_scalar()
+            .fetch_one(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message = format!(
+                    "Failed to count third party items of {notification_status} notifications to purge from storage: {err}"
+                );
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(notification_status = notification_status.to_string(), limit),
+        err
+    )]
+    async fn fetch_purgeable_notification_third_party_items(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        notification_status: NotificationStatus,
+        status_updated_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<ThirdPartyItem>, UniversalInboxError> {
+        let mut query_builder = QueryBuilder::new(
+            r#"
+              SELECT
+                third_party_item.id as third_party_item__id,
+                third_party_item.source_id as third_party_item__source_id,
+                third_party_item.data as third_party_item__data,
+                third_party_item.created_at as third_party_item__created_at,
+                third_party_item.updated_at as third_party_item__updated_at,
+                third_party_item.user_id as third_party_item__user_id,
+                third_party_item.integration_connection_id as third_party_item__integration_connection_id,
+                source_item.id as third_party_item__si__id,
+                source_item.source_id as third_party_item__si__source_id,
+                source_item.data as third_party_item__si__data,
+                source_item.created_at as third_party_item__si__created_at,
+                source_item.updated_at as third_party_item__si__updated_at,
+                source_item.user_id as third_party_item__si__user_id,
+                source_item.integration_connection_id as third_party_item__si__integration_connection_id
+              FROM third_party_item
+              LEFT JOIN third_party_item as source_item ON third_party_item.source_item_id = source_item.id
+            "#,
+        );
+        push_purgeable_notification_third_party_items_filter(
+            &mut query_builder,
+            notification_status,
+            status_updated_before,
+        );
+        query_builder.push(" ORDER BY third_party_item.id LIMIT ");
+        query_builder.push_bind(limit);
+        // Skip the items being synced, they will be purged on the next run
+        query_builder.push(" FOR UPDATE OF third_party_item SKIP LOCKED");
+
+        let records = query_builder
+            .build_query_as::<ThirdPartyItemRow>()
+            .fetch_all(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message = format!(
+                    "Failed to fetch third party items of {notification_status} notifications to purge from storage: {err}"
+                );
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
+        records
+            .iter()
+            .map(|r| r.try_into())
+            .collect::<Result<Vec<ThirdPartyItem>, UniversalInboxError>>()
+    }
+
+    #[tracing::instrument(level = "debug", skip_all, err)]
+    async fn count_orphan_third_party_items(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        updated_before: DateTime<Utc>,
+    ) -> Result<i64, UniversalInboxError> {
+        let mut query_builder = QueryBuilder::new("SELECT count(*) FROM third_party_item");
+        push_orphan_third_party_items_filter(&mut query_builder, updated_before);
+
+        query_builder
+            .build_query_scalar()
+            .fetch_one(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message =
+                    format!("Failed to count orphan third party items from storage: {err}");
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all, fields(limit), err)]
+    async fn delete_orphan_third_party_items(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        updated_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<u64, UniversalInboxError> {
+        let mut query_builder = QueryBuilder::new(
+            "DELETE FROM third_party_item WHERE id IN (SELECT third_party_item.id FROM third_party_item",
+        );
+        push_orphan_third_party_items_filter(&mut query_builder, updated_before);
+        query_builder.push(" ORDER BY third_party_item.id LIMIT ");
+        query_builder.push_bind(limit);
+        query_builder.push(" FOR UPDATE SKIP LOCKED)");
+
+        let result = query_builder
+            .build()
+            .execute(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message =
+                    format!("Failed to delete orphan third party items from storage: {err}");
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
+        Ok(result.rows_affected())
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(third_party_items_count = third_party_item_ids.len()),
+        err
+    )]
+    async fn delete_third_party_items(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        third_party_item_ids: &[ThirdPartyItemId],
+    ) -> Result<u64, UniversalInboxError> {
+        let ids = third_party_item_ids
+            .iter()
+            .map(|id| id.0)
+            .collect::<Vec<Uuid>>();
+        let result = sqlx::query("DELETE FROM third_party_item WHERE id = ANY($1)")
+            .bind(&ids[..])
+            .execute(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message = format!("Failed to delete third party items from storage: {err}");
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
+        Ok(result.rows_affected())
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(tombstones_count = tombstones.len()),
+        err
+    )]
+    async fn create_or_update_third_party_item_tombstones(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        tombstones: &[ThirdPartyItemTombstone],
+    ) -> Result<(), UniversalInboxError> {
+        if tombstones.is_empty() {
+            return Ok(());
+        }
+
+        let mut query_builder = QueryBuilder::new(
+            r#"
+              INSERT INTO third_party_item_tombstone
+                (
+                  source_id,
+                  kind,
+                  integration_connection_id,
+                  user_id,
+                  notification_status,
+                  activity_digest,
+                  purged_at
+                )
+            "#,
+        );
+        query_builder.push_values(tombstones, |mut row, tombstone| {
+            row.push_bind(&tombstone.source_id)
+                .push_bind(tombstone.kind.to_string())
+                .push_unseparated("::third_party_item_kind")
+                .push_bind(tombstone.integration_connection_id.0)
+                .push_bind(tombstone.user_id.0)
+                .push_bind(tombstone.notification_status.to_string())
+                .push_unseparated("::notification_status")
+                .push_bind(&tombstone.activity_digest)
+                .push_bind(tombstone.purged_at.naive_utc());
+        });
+        query_builder.push(
+            r#"
+              ON CONFLICT (source_id, kind, integration_connection_id) DO UPDATE
+              SET
+                user_id = EXCLUDED.user_id,
+                notification_status = EXCLUDED.notification_status,
+                activity_digest = EXCLUDED.activity_digest,
+                purged_at = EXCLUDED.purged_at
+            "#,
+        );
+
+        query_builder
+            .build()
+            .execute(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message =
+                    format!("Failed to store third party item tombstones in storage: {err}");
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(source_ids_count = source_ids.len(), user.id = user_id.to_string()),
+        err
+    )]
+    async fn find_third_party_item_tombstones(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        source_ids: &[String],
+        user_id: UserId,
+    ) -> Result<Vec<ThirdPartyItemTombstone>, UniversalInboxError> {
+        let rows = sqlx::query_as::<_, ThirdPartyItemTombstoneRow>(
+            r#"
+              SELECT
+                source_id,
+                kind::TEXT as kind,
+                integration_connection_id,
+                user_id,
+                notification_status::TEXT as notification_status,
+                activity_digest,
+                purged_at
+              FROM third_party_item_tombstone
+              WHERE user_id = $1 AND source_id = ANY($2)
+            "#,
+        )
+        .bind(user_id.0)
+        .bind(source_ids)
+        .fetch_all(&mut **executor)
+        .await
+        .map_err(|err| {
+            let message = format!(
+                "Failed to find third party item tombstones for user {user_id} from storage: {err}"
+            );
+            UniversalInboxError::DatabaseError {
+                source: err,
+                message,
+            }
+        })?;
+
+        rows.into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<ThirdPartyItemTombstone>, UniversalInboxError>>()
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            source_id = tombstone.source_id.as_str(),
+            kind = tombstone.kind.to_string(),
+            integration_connection_id = tombstone.integration_connection_id.to_string()
+        ),
+        err
+    )]
+    async fn delete_third_party_item_tombstone(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        tombstone: &ThirdPartyItemTombstone,
+    ) -> Result<(), UniversalInboxError> {
+        sqlx::query(
+            r#"
+              DELETE FROM third_party_item_tombstone
+              WHERE source_id = $1 AND kind::TEXT = $2 AND integration_connection_id = $3
+            "#,
+        )
+        .bind(&tombstone.source_id)
+        .bind(tombstone.kind.to_string())
+        .bind(tombstone.integration_connection_id.0)
+        .execute(&mut **executor)
+        .await
+        .map_err(|err| {
+            let message = format!(
+                "Failed to delete {} third party item tombstone for source ID {} from storage: {err}",
+                tombstone.kind, tombstone.source_id
+            );
+            UniversalInboxError::DatabaseError {
+                source: err,
+                message,
+            }
+        })?;
+
+        Ok(())
+    }
+}
+
+/// Items whose notifications all have `notification_status` since before
+/// `status_updated_before`, and which are neither used by a task nor the
+/// source of another item
+fn push_purgeable_notification_third_party_items_filter(
+    query_builder: &mut QueryBuilder<'_, Postgres>,
+    notification_status: NotificationStatus,
+    status_updated_before: DateTime<Utc>,
+) {
+    let notification_status = notification_status.to_string();
+    let status_updated_before = status_updated_before.naive_utc();
+    query_builder
+        .push(
+            r#"
+              WHERE EXISTS (
+                SELECT 1 FROM notification
+                WHERE notification.source_item_id = third_party_item.id
+                  AND notification.status::TEXT = "#,
+        )
+        .push_bind(notification_status.clone())
+        .push(" AND notification.status_updated_at < ")
+        .push_bind(status_updated_before)
+        .push(
+            r#"
+              )
+              AND NOT EXISTS (
+                SELECT 1 FROM notification
+                WHERE notification.source_item_id = third_party_item.id
+                  AND (notification.status::TEXT <> "#,
+        )
+        .push_bind(notification_status)
+        .push(" OR notification.status_updated_at >= ")
+        .push_bind(status_updated_before)
+        .push("))");
+    push_unused_third_party_items_filter(query_builder);
+}
+
+/// Items not updated since before `updated_before` without notification,
+/// which are neither used by a task nor the source of another item
+fn push_orphan_third_party_items_filter(
+    query_builder: &mut QueryBuilder<'_, Postgres>,
+    updated_before: DateTime<Utc>,
+) {
+    query_builder
+        .push(" WHERE third_party_item.updated_at < ")
+        .push_bind(updated_before.naive_utc())
+        .push(
+            r#"
+              AND NOT EXISTS (
+                SELECT 1 FROM notification WHERE notification.source_item_id = third_party_item.id
+              )"#,
+        );
+    push_unused_third_party_items_filter(query_builder);
+}
+
+fn push_unused_third_party_items_filter(query_builder: &mut QueryBuilder<'_, Postgres>) {
+    query_builder.push(
+        r#"
+              AND NOT EXISTS (
+                SELECT 1 FROM task
+                WHERE task.source_item_id = third_party_item.id
+                  OR task.sink_item_id = third_party_item.id
+              )
+              AND NOT EXISTS (
+                SELECT 1 FROM third_party_item AS derived_item
+                WHERE derived_item.source_item_id = third_party_item.id
+              )
+        "#,
+    );
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ThirdPartyItemTombstoneRow {
+    source_id: String,
+    kind: String,
+    integration_connection_id: Uuid,
+    user_id: Uuid,
+    notification_status: String,
+    activity_digest: Vec<u8>,
+    purged_at: NaiveDateTime,
+}
+
+impl TryFrom<ThirdPartyItemTombstoneRow> for ThirdPartyItemTombstone {
+    type Error = UniversalInboxError;
+
+    fn try_from(row: ThirdPartyItemTombstoneRow) -> Result<Self, Self::Error> {
+        Ok(ThirdPartyItemTombstone {
+            kind: row
+                .kind
+                .parse()
+                .map_err(|err| UniversalInboxError::InvalidEnumData {
+                    source: err,
+                    output: row.kind.clone(),
+                })?,
+            notification_status: row.notification_status.parse().map_err(|err| {
+                UniversalInboxError::InvalidEnumData {
+                    source: err,
+                    output: row.notification_status.clone(),
+                }
+            })?,
+            source_id: row.source_id,
+            integration_connection_id: row.integration_connection_id.into(),
+            user_id: row.user_id.into(),
+            activity_digest: row.activity_digest,
+            purged_at: DateTime::from_naive_utc_and_offset(row.purged_at, Utc),
+        })
+    }
 }
 
 #[derive(Debug, Clone)]
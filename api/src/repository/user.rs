@@ -22,7 +22,8 @@ use crate::{
     universal_inbox::{
         UniversalInboxError, UpdateStatus,
         user::model::{
-            AuthUserId, LocalUserAuth, OpenIdConnectUserAuth, PasskeyUserAuth, UserAuth,
+            AuthUserId, LocalUserAuth, OpenIdConnectUserAuth, PasskeyUserAuth, SAMLAuthnRequest,
+            SAMLUserAuth, TotpUserAuth, UserActivity, UserAuth,
         },
     },
 };
@@ -45,6 +46,12 @@ pub trait UserRepository {
         executor: &mut Transaction<'_, Postgres>,
     ) -> Result<Vec<(User, Vec<UserAuth>)>, UniversalInboxError>;
 
+    /// Last session activity of every user, most recently active users first
+    async fn fetch_all_user_activities(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+    ) -> Result<Vec<UserActivity>, UniversalInboxError>;
+
     async fn delete_user(
         &self,
         executor: &mut Transaction<'_, Postgres>,
@@ -167,6 +174,103 @@ pub trait UserRepository {
         user_id: UserId,
         patch: &UserPatch,
     ) -> Result<UpdateStatus<User>, UniversalInboxError>;
+
+    async fn get_totp_auth(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        for_update: bool,
+    ) -> Result<Option<TotpUserAuth>, UniversalInboxError>;
+
+    /// Encrypted TOTP secrets of at most `limit` users, ordered by user ID and
+    /// starting after `after`, locked until the end of the transaction
+    async fn fetch_totp_encrypted_secrets(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        after: Option<UserId>,
+        limit: i64,
+    ) -> Result<Vec<(UserId, Vec<u8>)>, UniversalInboxError>;
+
+    async fn update_totp_encrypted_secret(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        encrypted_secret: &[u8],
+    ) -> Result<(), UniversalInboxError>;
+
+    /// Store a new pending TOTP enrollment, replacing any pending one. An
+    /// enabled TOTP is left untouched and `false` is returned.
+    async fn save_pending_totp_auth(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        encrypted_secret: &[u8],
+    ) -> Result<bool, UniversalInboxError>;
+
+    async fn enable_totp_auth(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        used_step: i64,
+    ) -> Result<(), UniversalInboxError>;
+
+    async fn update_totp_last_used_step(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        used_step: i64,
+    ) -> Result<(), UniversalInboxError>;
+
+    /// Delete the TOTP of the user along with their recovery codes
+    async fn delete_totp_auth(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+    ) -> Result<bool, UniversalInboxError>;
+
+    async fn replace_recovery_codes(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        code_hashes: &[String],
+    ) -> Result<(), UniversalInboxError>;
+
+    /// Mark the unused recovery code matching `code_hash` as used. Returns
+    /// `false` when there is no such code.
+    async fn use_recovery_code(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        code_hash: &str,
+    ) -> Result<bool, UniversalInboxError>;
+
+    async fn count_unused_recovery_codes(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+    ) -> Result<usize, UniversalInboxError>;
+
+    async fn get_user_by_saml_name_id(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        name_id: &AuthUserId,
+    ) -> Result<Option<User>, UniversalInboxError>;
+
+    /// Store a SAML authentication request sent to the identity provider.
+    /// Expired requests are purged at the same time.
+    async fn create_saml_authn_request(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        authn_request: &SAMLAuthnRequest,
+    ) -> Result<(), UniversalInboxError>;
+
+    /// Delete and return the unexpired SAML authentication request `id`, so
+    /// that a response cannot be replayed.
+    async fn consume_saml_authn_request(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        id: &str,
+    ) -> Result<Option<SAMLAuthnRequest>, UniversalInboxError>;
 }
 
 #[async_trait]
@@ -304,6 +408,45 @@ impl UserRepository for Repository {
         Ok(user_map.into_values().collect())
     }
 
+    #[tracing::instrument(level = "debug", skip_all, err)]
+    async fn fetch_all_user_activities(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+    ) -> Result<Vec<UserActivity>, UniversalInboxError> {
+        let rows: Vec<(Uuid, Option<NaiveDateTime>)> = sqlx::query_as(
+            r#"
+                SELECT
+                  "user".id,
+                  MAX(authentication_token.last_used_at) AS last_active_at
+                FROM "user"
+                LEFT JOIN authentication_token
+                  ON authentication_token.user_id = "user".id
+                  AND authentication_token.is_session_token
+                  AND NOT authentication_token.is_revoked
+                GROUP BY "user".id
+                ORDER BY last_active_at DESC NULLS LAST, "user".id
+            "#,
+        )
+        .fetch_all(&mut **executor)
+        .await
+        .map_err(|err| {
+            let message = format!("Failed to fetch user activities from storage: {err}");
+            UniversalInboxError::DatabaseError {
+                source: err,
+                message,
+            }
+        })?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(user_id, last_active_at)| UserActivity {
+                user_id: UserId(user_id),
+                last_active_at: last_active_at
+                    .map(|last_active_at| DateTime::from_naive_utc_and_offset(last_active_at, Utc)),
+            })
+            .collect())
+    }
+
     #[tracing::instrument(
         level = "debug",
         skip_all,
@@ -506,6 +649,9 @@ impl UserRepository for Repository {
                 Some(passkey_user_auth.username.clone()),
                 Some(passkey_user_auth.passkey.clone()),
             ),
+            UserAuth::SAML(ref saml_user_auth) => {
+                (Some(saml_user_auth.name_id.clone()), None, None, None, None)
+            }
         };
 
         let new_id = Uuid::new_v4();
@@ -641,6 +787,8 @@ impl UserRepository for Repository {
         separated
             .push(" user_auth.auth_user_id = ")
             .push_bind_unseparated(auth_user_id.to_string());
+        // SAML NameIDs are stored in the same column and must not match an OIDC subject
+        separated.push(" user_auth.auth_id_token IS NOT NULL ");
 
         query_builder
             .push(
@@ -661,6 +809,7 @@ impl UserRepository for Repository {
             .push_bind(auth_id_token.to_string())
             .push(" FROM user_auth WHERE auth_user_id = ")
             .push_bind(auth_user_id.to_string())
+            .push(" AND auth_id_token IS NOT NULL")
             .push(r#") as "is_updated""#);
 
         let record: Option<UpdatedUserRow> = query_builder
@@ -1421,6 +1570,506 @@ impl UserRepository for Repository {
             })
         }
     }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(user.id = user_id.to_string()),
+        err
+    )]
+    async fn get_totp_auth(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        for_update: bool,
+    ) -> Result<Option<TotpUserAuth>, UniversalInboxError> {
+        let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new(
+            r#"
+                SELECT encrypted_secret, enabled_at, last_used_step
+                FROM user_auth_totp
+                WHERE user_id =
+            "#,
+        );
+        query_builder.push_bind(user_id.0);
+        if for_update {
+            query_builder.push(" FOR UPDATE");
+        }
+
+        let row: Option<TotpUserAuthRow> = query_builder
+            .build_query_as::<TotpUserAuthRow>()
+            .fetch_optional(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message = format!("Failed to fetch TOTP of user {user_id} from storage: {err}");
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
+        Ok(row.map(|row| TotpUserAuth {
+            encrypted_secret: row.encrypted_secret,
+            enabled_at: row.enabled_at,
+            last_used_step: row.last_used_step,
+        }))
+    }
+
+    #[tracing::instrument(level = "debug", skip_all, err)]
+    async fn fetch_totp_encrypted_secrets(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        after: Option<UserId>,
+        limit: i64,
+    ) -> Result<Vec<(UserId, Vec<u8>)>, UniversalInboxError> {
+        let rows: Vec<(Uuid, Vec<u8>)> = sqlx::query_as(
+            r#"
+                SELECT user_id, encrypted_secret
+                FROM user_auth_totp
+                WHERE $1::UUID IS NULL OR user_id > $1
+                ORDER BY user_id
+                LIMIT $2
+                FOR UPDATE
+            "#,
+        )
+        .bind(after.map(|user_id| user_id.0))
+        .bind(limit)
+        .fetch_all(&mut **executor)
+        .await
+        .map_err(|err| {
+            let message = format!("Failed to fetch TOTP secrets from storage: {err}");
+            UniversalInboxError::DatabaseError {
+                source: err,
+                message,
+            }
+        })?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(user_id, encrypted_secret)| (UserId(user_id), encrypted_secret))
+            .collect())
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(user.id = user_id.to_string()),
+        err
+    )]
+    async fn update_totp_encrypted_secret(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        encrypted_secret: &[u8],
+    ) -> Result<(), UniversalInboxError> {
+        sqlx::query(
+            r#"
+                UPDATE user_auth_totp
+                SET encrypted_secret = $2
+                WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id.0)
+        .bind(encrypted_secret)
+        .execute(&mut **executor)
+        .await
+        .map_err(|err| {
+            let message = format!("Failed to update TOTP secret of user {user_id}: {err}");
+            UniversalInboxError::DatabaseError {
+                source: err,
+                message,
+            }
+        })?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(user.id = user_id.to_string()),
+        err
+    )]
+    async fn save_pending_totp_auth(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        encrypted_secret: &[u8],
+    ) -> Result<bool, UniversalInboxError> {
+        let result = sqlx::query(
+            r#"
+                INSERT INTO user_auth_totp (user_id, encrypted_secret)
+                VALUES ($1, $2)
+                ON CONFLICT (user_id) DO UPDATE
+                SET
+                  encrypted_secret = EXCLUDED.encrypted_secret,
+                  last_used_step = NULL,
+                  created_at = now(),
+                  updated_at = now()
+                WHERE user_auth_totp.enabled_at IS NULL
+            "#,
+        )
+        .bind(user_id.0)
+        .bind(encrypted_secret)
+        .execute(&mut **executor)
+        .await
+        .map_err(|err| {
+            let message = format!("Failed to save TOTP of user {user_id} into storage: {err}");
+            UniversalInboxError::DatabaseError {
+                source: err,
+                message,
+            }
+        })?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(user.id = user_id.to_string()),
+        err
+    )]
+    async fn enable_totp_auth(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        used_step: i64,
+    ) -> Result<(), UniversalInboxError> {
+        sqlx::query(
+            r#"
+                UPDATE user_auth_totp
+                SET enabled_at = now(), last_used_step = $2, updated_at = now()
+                WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id.0)
+        .bind(used_step)
+        .execute(&mut **executor)
+        .await
+        .map_err(|err| {
+            let message = format!("Failed to enable TOTP of user {user_id} in storage: {err}");
+            UniversalInboxError::DatabaseError {
+                source: err,
+                message,
+            }
+        })?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(user.id = user_id.to_string()),
+        err
+    )]
+    async fn update_totp_last_used_step(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        used_step: i64,
+    ) -> Result<(), UniversalInboxError> {
+        sqlx::query(
+            r#"
+                UPDATE user_auth_totp
+                SET last_used_step = $2, updated_at = now()
+                WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id.0)
+        .bind(used_step)
+        .execute(&mut **executor)
+        .await
+        .map_err(|err| {
+            let message =
+                format!("Failed to update TOTP last used step of user {user_id} in storage: {err}");
+            UniversalInboxError::DatabaseError {
+                source: err,
+                message,
+            }
+        })?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(user.id = user_id.to_string()),
+        err
+    )]
+    async fn delete_totp_auth(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+    ) -> Result<bool, UniversalInboxError> {
+        sqlx::query("DELETE FROM user_auth_recovery_code WHERE user_id = $1")
+            .bind(user_id.0)
+            .execute(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message = format!(
+                    "Failed to delete recovery codes of user {user_id} from storage: {err}"
+                );
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
+        let result = sqlx::query("DELETE FROM user_auth_totp WHERE user_id = $1")
+            .bind(user_id.0)
+            .execute(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message =
+                    format!("Failed to delete TOTP of user {user_id} from storage: {err}");
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(user.id = user_id.to_string()),
+        err
+    )]
+    async fn replace_recovery_codes(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        code_hashes: &[String],
+    ) -> Result<(), UniversalInboxError> {
+        sqlx::query("DELETE FROM user_auth_recovery_code WHERE user_id = $1")
+            .bind(user_id.0)
+            .execute(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message = format!(
+                    "Failed to delete recovery codes of user {user_id} from storage: {err}"
+                );
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
+        if code_hashes.is_empty() {
+            return Ok(());
+        }
+
+        let mut query_builder: QueryBuilder<Postgres> =
+            QueryBuilder::new("INSERT INTO user_auth_recovery_code (id, user_id, code_hash) ");
+        query_builder.push_values(code_hashes, |mut builder, code_hash| {
+            builder
+                .push_bind(Uuid::new_v4())
+                .push_bind(user_id.0)
+                .push_bind(code_hash);
+        });
+
+        query_builder
+            .build()
+            .execute(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message = format!(
+                    "Failed to insert recovery codes of user {user_id} into storage: {err}"
+                );
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(user.id = user_id.to_string()),
+        err
+    )]
+    async fn use_recovery_code(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        code_hash: &str,
+    ) -> Result<bool, UniversalInboxError> {
+        let result = sqlx::query(
+            r#"
+                UPDATE user_auth_recovery_code
+                SET used_at = now()
+                WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL
+            "#,
+        )
+        .bind(user_id.0)
+        .bind(code_hash)
+        .execute(&mut **executor)
+        .await
+        .map_err(|err| {
+            let message =
+                format!("Failed to use a recovery code of user {user_id} from storage: {err}");
+            UniversalInboxError::DatabaseError {
+                source: err,
+                message,
+            }
+        })?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(user.id = user_id.to_string()),
+        err
+    )]
+    async fn count_unused_recovery_codes(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+    ) -> Result<usize, UniversalInboxError> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT count(*) FROM user_auth_recovery_code WHERE user_id = $1 AND used_at IS NULL",
+        )
+        .bind(user_id.0)
+        .fetch_one(&mut **executor)
+        .await
+        .map_err(|err| {
+            let message =
+                format!("Failed to count recovery codes of user {user_id} from storage: {err}");
+            UniversalInboxError::DatabaseError {
+                source: err,
+                message,
+            }
+        })?;
+
+        Ok(count as usize)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all, err)]
+    async fn get_user_by_saml_name_id(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        name_id: &AuthUserId,
+    ) -> Result<Option<User>, UniversalInboxError> {
+        let row: Option<UserRow> = sqlx::query_as(
+            r#"
+                SELECT
+                  "user".id,
+                  "user".first_name,
+                  "user".last_name,
+                  "user".email,
+                  "user".email_validated_at,
+                  "user".email_validation_sent_at,
+                  "user".is_testing,
+                  "user".created_at,
+                  "user".updated_at
+                FROM "user"
+                INNER JOIN user_auth ON user_auth.user_id = "user".id
+                WHERE user_auth.auth_user_id = $1
+                  AND user_auth.kind = 'SAML'::user_auth_kind
+            "#,
+        )
+        .bind(name_id.to_string())
+        .fetch_optional(&mut **executor)
+        .await
+        .map_err(|err| {
+            let message = format!("Failed to fetch user by SAML NameID from storage: {err}");
+            UniversalInboxError::DatabaseError {
+                source: err,
+                message,
+            }
+        })?;
+
+        row.map(|user_row| user_row.try_into()).transpose()
+    }
+
+    #[tracing::instrument(level = "debug", skip_all, err)]
+    async fn create_saml_authn_request(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        authn_request: &SAMLAuthnRequest,
+    ) -> Result<(), UniversalInboxError> {
+        sqlx::query("DELETE FROM saml_authn_request WHERE expires_at < now()")
+            .execute(&mut **executor)
+            .await
+            .map_err(|err| {
+                let message =
+                    format!("Failed to delete expired SAML authentication requests: {err}");
+                UniversalInboxError::DatabaseError {
+                    source: err,
+                    message,
+                }
+            })?;
+
+        sqlx::query(
+            r#"
+                INSERT INTO saml_authn_request (id, linking_user_id, expires_at)
+                VALUES ($1, $2, $3)
+            "#,
+        )
+        .bind(&authn_request.id)
+        .bind(authn_request.linking_user_id.map(|user_id| user_id.0))
+        .bind(authn_request.expires_at)
+        .execute(&mut **executor)
+        .await
+        .map_err(|err| {
+            let message = format!("Failed to store SAML authentication request: {err}");
+            UniversalInboxError::DatabaseError {
+                source: err,
+                message,
+            }
+        })?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all, err)]
+    async fn consume_saml_authn_request(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        id: &str,
+    ) -> Result<Option<SAMLAuthnRequest>, UniversalInboxError> {
+        let row: Option<(String, Option<Uuid>, DateTime<Utc>)> = sqlx::query_as(
+            r#"
+                DELETE FROM saml_authn_request
+                WHERE id = $1 AND expires_at >= now()
+                RETURNING id, linking_user_id, expires_at
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&mut **executor)
+        .await
+        .map_err(|err| {
+            let message = format!("Failed to consume SAML authentication request: {err}");
+            UniversalInboxError::DatabaseError {
+                source: err,
+                message,
+            }
+        })?;
+
+        Ok(
+            row.map(|(id, linking_user_id, expires_at)| SAMLAuthnRequest {
+                id,
+                linking_user_id: linking_user_id.map(UserId),
+                expires_at,
+            }),
+        )
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct TotpUserAuthRow {
+    encrypted_secret: Vec<u8>,
+    enabled_at: Option<DateTime<Utc>>,
+    last_used_step: Option<i64>,
 }
 
 #[derive(Debug, sqlx::FromRow)]
@@ -1486,6 +2135,8 @@ enum PgUserAuthKind {
     OIDCGoogleAuthorizationCode,
     Local,
     Passkey,
+    #[allow(clippy::upper_case_acronyms)]
+    SAML,
 }
 
 #[derive(Debug, sqlx::FromRow)]
@@ -1549,6 +2200,11 @@ impl TryFrom<&UserAuthRow> for UserAuth {
                     "Expected to find passkey in storage with Passkey authentication",
                 )?,
             })),
+            PgUserAuthKind::SAML => UserAuth::SAML(Box::new(SAMLUserAuth {
+                name_id: AuthUserId(row.auth_user_id.clone().context(
+                    "Expected to find NameID in storage with SAML authentication",
+                )?),
+            })),
         };
 
         Ok(auth)
@@ -1634,6 +2290,11 @@ impl TryFrom<&UserAndUserAuthRow> for UserAuth {
                     "Expected to find passkey in storage with Passkey authentication",
                 )?,
             })),
+            PgUserAuthKind::SAML => UserAuth::SAML(Box::new(SAMLUserAuth {
+                name_id: AuthUserId(row.user_auth_row.auth_user_id.clone().context(
+                    "Expected to find NameID in storage with SAML authentication",
+                )?),
+            })),
         };
 
         Ok(auth)
@@ -3,7 +3,9 @@ use std::{str::FromStr, sync::Arc};
 use crate::middlewares::jwt_auth::Authenticated;
 use actix_session::Session;
 use actix_web::{
-    HttpResponse, Scope,
+    HttpRequest, HttpResponse, Scope,
+    cookie::{Cookie, SameSite, time::Duration as CookieDuration},
+    http::header,
     web::{self, Redirect},
 };
 use anyhow::{Context, anyhow};
@@ -21,12 +23,20 @@ use universal_inbox::{
 
 use crate::{
     Claims,
-    configuration::{AuthenticationSettings, OIDCFlowSettings, Settings},
+    configuration::{AuthenticationSettings, OIDCFlowSettings, SAMLSettings, Settings},
     universal_inbox::{
-        UniversalInboxError, auth_token::service::AuthenticationTokenService,
+        UniversalInboxError,
+        auth_token::service::{AuthenticationTokenService, SessionClient},
         user::service::UserService,
     },
-    utils::jwt::JWT_SESSION_KEY,
+    utils::{
+        jwt::JWT_SESSION_KEY,
+        rate_limit::resolve_client_ip,
+        saml::{
+            MAX_SAML_RESPONSE_LENGTH, SAML_AUTHN_REQUEST_TTL_IN_MINUTES,
+            SAML_METADATA_CONTENT_TYPE, build_sp_metadata,
+        },
+    },
 };
 
 pub fn scope() -> Scope {
@@ -43,6 +53,16 @@ pub fn scope() -> Scope {
         // OIDC linking routes (for adding OIDC auth to existing user)
         .service(web::resource("link-oidc/authorize").route(web::get().to(authorize_link_oidc)))
         .service(web::resource("link-oidc/session").route(web::post().to(link_oidc_pkce_session)))
+        // SAML 2.0 Web Browser SSO
+        .service(web::resource("saml/metadata").route(web::get().to(saml_metadata)))
+        .service(web::resource("saml/authorize").route(web::get().to(authorize_saml)))
+        .service(
+            web::resource("saml/acs")
+                // Signed responses often exceed the default form size limit
+                .app_data(web::FormConfig::default().limit(MAX_SAML_RESPONSE_LENGTH + 4096))
+                .route(web::post().to(saml_assertion_consumer_service)),
+        )
+        .service(web::resource("link-saml/authorize").route(web::get().to(authorize_link_saml)))
 }
 
 /// Authenticate a user session using the Authorization Code + PKCE flow.
@@ -55,6 +75,7 @@ pub fn scope() -> Scope {
 /// If the user is unknown, it will create a new one.
 #[allow(clippy::too_many_arguments)]
 pub async fn authenticate_session(
+    req: HttpRequest,
     params: web::Json<SessionAuthValidationParameters>,
     user_service: web::Data<Arc<UserService>>,
     auth_token_service: web::Data<Arc<RwLock<AuthenticationTokenService>>>,
@@ -104,7 +125,7 @@ pub async fn authenticate_session(
     let auth_token_service = auth_token_service.read().await;
 
     let auth_token = auth_token_service
-        .create_auth_token(&mut transaction, true, user.id, None, false)
+        .create_session_token(&mut transaction, user.id, session_client(&req))
         .await?;
     session
         .insert(
@@ -127,6 +148,18 @@ pub async fn authenticate_session(
     Ok(HttpResponse::Ok().finish())
 }
 
+/// Identify the client opening a session, for the list of the user's sessions
+pub fn session_client(req: &HttpRequest) -> SessionClient {
+    SessionClient {
+        ip_address: resolve_client_ip(req),
+        user_agent: req
+            .headers()
+            .get(header::USER_AGENT)
+            .and_then(|user_agent| user_agent.to_str().ok())
+            .map(|user_agent| user_agent.to_string()),
+    }
+}
+
 pub const USER_AUTH_KIND_SESSION_KEY: &str = "user_auth_kind";
 pub const LINKING_USER_ID_SESSION_KEY: &str = "linking_user_id";
 const OIDC_CSRF_TOKEN_SESSION_KEY: &str = "oidc_csrf_token";
@@ -249,6 +282,7 @@ pub struct AuthenticatedSessionRequest {
 /// If `LINKING_USER_ID` is present in the session (set by `authorize_link_oidc`),
 /// the OIDC auth will be linked to the existing user instead of creating a new one.
 pub async fn authenticated_session(
+    req: HttpRequest,
     settings: web::Data<Settings>,
     session: Session,
     authenticated_session_request: web::Query<AuthenticatedSessionRequest>,
@@ -346,7 +380,7 @@ pub async fn authenticated_session(
     let auth_token_service = auth_token_service.read().await;
 
     let auth_token = auth_token_service
-        .create_auth_token(&mut transaction, true, user.id, None, false)
+        .create_session_token(&mut transaction, user.id, session_client(&req))
         .await?;
     session
         .insert(
@@ -373,6 +407,7 @@ pub async fn authenticated_session(
 
 pub async fn close_session(
     user_service: web::Data<Arc<UserService>>,
+    auth_token_service: web::Data<Arc<RwLock<AuthenticationTokenService>>>,
     authenticated: Authenticated<Claims>,
     session: Session,
 ) -> Result<HttpResponse, UniversalInboxError> {
@@ -395,11 +430,16 @@ pub async fn close_session(
     let logout_url = service
         .close_session(&mut transaction, user_id, user_auth_kind)
         .await?;
+    let auth_token_service = auth_token_service.read().await;
+    auth_token_service
+        .revoke_current_session(&mut transaction, user_id, &authenticated.jwt.0)
+        .await?;
 
     transaction
         .commit()
         .await
         .context("Failed to commit while authenticating user")?;
+    auth_token_service.forget_revoked_sessions(user_id).await;
 
     session.purge();
 
@@ -474,3 +514,216 @@ pub async fn link_oidc_pkce_session(
         .content_type("application/json")
         .body(serde_json::to_string(&auth_method).context("Cannot serialize auth method")?))
 }
+
+fn find_saml_settings(settings: &Settings) -> Result<&SAMLSettings, UniversalInboxError> {
+    settings
+        .application
+        .security
+        .authentication
+        .iter()
+        .find_map(|auth| match auth {
+            AuthenticationSettings::SAML(saml_settings) => Some(&**saml_settings),
+            _ => None,
+        })
+        .ok_or_else(|| {
+            UniversalInboxError::Unexpected(anyhow!(
+                "This service can only be called when SAML authentication is configured"
+            ))
+        })
+}
+
+/// Serve the SAML service provider metadata to register in the identity provider.
+pub async fn saml_metadata(
+    settings: web::Data<Settings>,
+) -> Result<HttpResponse, UniversalInboxError> {
+    let saml_settings = find_saml_settings(&settings)?;
+    let metadata = build_sp_metadata(
+        &settings.application.get_saml_sp_entity_id(saml_settings)?,
+        &settings.application.get_saml_acs_url()?,
+    );
+
+    Ok(HttpResponse::Ok()
+        .content_type(SAML_METADATA_CONTENT_TYPE)
+        .body(metadata))
+}
+
+/// One-time cookie binding a SAML linking flow to the browser which started it.
+/// It holds the ID of the authentication request and is sent along the identity
+/// provider's cross-site POST to the Assertion Consumer Service, unlike the
+/// session cookie.
+const SAML_LINKING_REQUEST_COOKIE_NAME: &str = "saml_linking_request";
+
+fn saml_linking_request_cookie(
+    settings: &Settings,
+    value: String,
+    max_age: CookieDuration,
+) -> Result<Cookie<'static>, UniversalInboxError> {
+    Ok(Cookie::build(SAML_LINKING_REQUEST_COOKIE_NAME, value)
+        .path(settings.application.get_saml_acs_url()?.path().to_string())
+        .http_only(true)
+        .secure(true)
+        .same_site(SameSite::None)
+        .max_age(max_age)
+        .finish())
+}
+
+async fn build_saml_authorization_response(
+    user_service: &UserService,
+    settings: &Settings,
+    linking_user_id: Option<UserId>,
+) -> Result<HttpResponse, UniversalInboxError> {
+    let saml_settings = find_saml_settings(settings)?;
+
+    let mut transaction = user_service
+        .begin()
+        .await
+        .context("Failed to create new transaction while building SAML authentication request")?;
+    let (authn_request, authorization_url) = user_service
+        .build_saml_authn_request(&mut transaction, saml_settings, linking_user_id)
+        .await?;
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit while building SAML authentication request")?;
+
+    let mut response = HttpResponse::Ok();
+    if linking_user_id.is_some() {
+        response.cookie(saml_linking_request_cookie(
+            settings,
+            authn_request.id,
+            CookieDuration::minutes(SAML_AUTHN_REQUEST_TTL_IN_MINUTES),
+        )?);
+    }
+
+    debug!("Redirecting to SAML identity provider: {authorization_url}");
+    Ok(response.content_type("application/json").body(
+        serde_json::to_string(&AuthorizeSessionResponse { authorization_url })
+            .context("Failed to serialize the authorization URL")?,
+    ))
+}
+
+/// Start a SAML login: return the identity provider URL carrying a new
+/// authentication request.
+pub async fn authorize_saml(
+    user_service: web::Data<Arc<UserService>>,
+    settings: web::Data<Settings>,
+) -> Result<HttpResponse, UniversalInboxError> {
+    build_saml_authorization_response(&user_service, &settings, None).await
+}
+
+/// Initiate SAML linking for an already-authenticated user.
+/// The user ID is stored with the authentication request rather than in the
+/// session, as the session cookie is not sent along the identity provider's
+/// cross-site POST to the Assertion Consumer Service. The request is bound to
+/// the browser with the `SAML_LINKING_REQUEST_COOKIE_NAME` cookie instead, so
+/// that its identity provider URL cannot be used from another browser to link
+/// another person's identity to this user.
+pub async fn authorize_link_saml(
+    user_service: web::Data<Arc<UserService>>,
+    authenticated: Authenticated<Claims>,
+    settings: web::Data<Settings>,
+) -> Result<HttpResponse, UniversalInboxError> {
+    let user_id = authenticated
+        .claims
+        .sub
+        .parse::<UserId>()
+        .context("Wrong user ID format")?;
+
+    build_saml_authorization_response(&user_service, &settings, Some(user_id)).await
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SAMLAssertionConsumerServiceRequest {
+    #[serde(rename = "SAMLResponse")]
+    saml_response: String,
+}
+
+/// SAML Assertion Consumer Service (HTTP-POST binding). It validates the signed
+/// response of the identity provider, then links the SAML identity to the user
+/// who initiated the flow with `authorize_link_saml` or logs the user in,
+/// creating it on its first login.
+///
+/// The request comes from the identity provider's page: it is not subject to the
+/// origin check and is authenticated by the response signature and the single-use
+/// authentication request it answers. A linking request must also come from the
+/// browser holding its `SAML_LINKING_REQUEST_COOKIE_NAME` cookie.
+pub async fn saml_assertion_consumer_service(
+    req: HttpRequest,
+    settings: web::Data<Settings>,
+    session: Session,
+    form: web::Form<SAMLAssertionConsumerServiceRequest>,
+    user_service: web::Data<Arc<UserService>>,
+    auth_token_service: web::Data<Arc<RwLock<AuthenticationTokenService>>>,
+) -> Result<HttpResponse, UniversalInboxError> {
+    let saml_settings = find_saml_settings(&settings)?;
+    let service = user_service.clone();
+    let mut transaction = service
+        .begin()
+        .await
+        .context("Failed to create new transaction while authenticating user")?;
+
+    let (identity, authn_request) = service
+        .validate_saml_response(&mut transaction, saml_settings, &form.saml_response)
+        .await?;
+
+    // The response to a POST must be followed with a GET
+    let mut front_redirect = HttpResponse::SeeOther();
+    front_redirect.insert_header((
+        header::LOCATION,
+        settings.application.front_base_url.to_string(),
+    ));
+
+    if let Some(linking_user_id) = authn_request.linking_user_id {
+        let linking_request_id = req
+            .cookie(SAML_LINKING_REQUEST_COOKIE_NAME)
+            .map(|cookie| cookie.value().to_string());
+        if linking_request_id.as_deref() != Some(authn_request.id.as_str()) {
+            return Err(UniversalInboxError::Unauthorized(anyhow!(
+                "SAML linking request {} was not initiated from this browser",
+                authn_request.id
+            )));
+        }
+
+        service
+            .link_for_saml(&mut transaction, linking_user_id, identity)
+            .await?;
+
+        transaction
+            .commit()
+            .await
+            .context("Failed to commit while linking SAML auth method")?;
+
+        return Ok(front_redirect
+            .cookie(saml_linking_request_cookie(
+                &settings,
+                String::new(),
+                CookieDuration::ZERO,
+            )?)
+            .finish());
+    }
+
+    let user = service
+        .authenticate_for_saml(&mut transaction, identity)
+        .await?;
+
+    let auth_token_service = auth_token_service.read().await;
+    let auth_token = auth_token_service
+        .create_session_token(&mut transaction, user.id, session_client(&req))
+        .await?;
+    session
+        .insert(
+            JWT_SESSION_KEY,
+            auth_token.jwt_token.expose_secret().0.clone(),
+        )
+        .context("Failed to insert JWT token into the session")?;
+    session
+        .insert(USER_AUTH_KIND_SESSION_KEY, UserAuthKind::SAML)
+        .context("Failed to insert authentication type into the session")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit while authenticating user")?;
+
+    Ok(front_redirect.finish())
+}
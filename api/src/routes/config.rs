@@ -64,6 +64,7 @@ pub async fn front_config(
             },
             AuthenticationSettings::Local(_) => FrontAuthenticationConfig::Local,
             AuthenticationSettings::Passkey => FrontAuthenticationConfig::Passkey,
+            AuthenticationSettings::SAML(_) => FrontAuthenticationConfig::SAML,
         })
         .collect();
 
@@ -1,17 +1,33 @@
-use std::{num::NonZeroU32, sync::Arc};
+use std::{collections::BTreeMap, num::NonZeroU32, sync::Arc};
 
 use actix_web::{HttpRequest, HttpResponse, body::BoxBody, web};
-use anyhow::Context;
+use anyhow::{Context, anyhow};
+use chrono::{TimeDelta, Utc};
 use governor::Quota;
-use redis::AsyncCommands;
+use serde::Serialize;
 use serde_json::json;
 use tokio::sync::RwLock;
+use tracing::warn;
 
 use crate::{
+    configuration::Settings,
+    jobs::{
+        heartbeat::{WORKER_HEARTBEAT_TIMEOUT, fetch_last_worker_heartbeat},
+        storage::JobStorage,
+    },
+    middlewares::jwt_auth::MaybeAuthenticated,
+    repository::Repository,
     universal_inbox::{
-        UniversalInboxError, integration_connection::service::IntegrationConnectionService,
+        UniversalInboxError,
+        integration_connection::{
+            provider_health::IntegrationProviderHealth, service::IntegrationConnectionService,
+        },
+    },
+    utils::{
+        cache::Cache,
+        jwt::{Claims, JWTBase64EncodedSigningKeys, JWTSigningKeys},
+        rate_limit::IpRateLimiter,
     },
-    utils::{cache::Cache, rate_limit::IpRateLimiter},
 };
 
 /// Per-IP request budget for `/ping`.
@@ -60,12 +76,7 @@ pub async fn ping(
         return Ok(response);
     }
 
-    let cache_result: Result<String, anyhow::Error> = cache
-        .connection_manager
-        .clone()
-        .ping()
-        .await
-        .context("Failed to ping Redis");
+    let cache_result = cache.ping().await;
 
     // Clone the pool Arc out of the service while holding the read lock for
     // the minimum possible duration, then drop the guard before issuing the
@@ -101,3 +112,227 @@ pub async fn ping(
         .to_string(),
     )))
 }
+
+/// Sync runs started during this period are used to report the health of the
+/// integration providers
+const PROVIDER_HEALTH_PERIOD_IN_HOURS: i64 = 1;
+
+/// Checks of the readiness probe depending on the server exposing it, decided
+/// when the server starts
+#[derive(Debug, Clone)]
+pub struct ReadinessProbe {
+    /// Only the workers' health-check server is not ready without a worker
+    /// heartbeat, the API serves requests without workers
+    is_worker_heartbeat_required: bool,
+    /// The JWT keys do not change while the server runs, their key pair is
+    /// checked once at startup
+    jwt_key_pair_check: Result<(), String>,
+}
+
+impl ReadinessProbe {
+    pub fn new(settings: &Settings, is_worker_heartbeat_required: bool) -> Self {
+        let jwt_key_pair_check =
+            JWTSigningKeys::load_from_base64_encoded_keys(JWTBase64EncodedSigningKeys {
+                secret_key: settings.application.http_session.jwt_secret_key.clone(),
+                public_key: settings.application.http_session.jwt_public_key.clone(),
+            })
+            .and_then(|jwt_signing_keys| jwt_signing_keys.check_key_pair())
+            .map_err(|err| err.to_string());
+        if let Err(err) = &jwt_key_pair_check {
+            warn!("Invalid JWT key pair: {err}");
+        }
+
+        ReadinessProbe {
+            is_worker_heartbeat_required,
+            jwt_key_pair_check,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DependencyCheck {
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+    /// Unhealthy dependencies that are not required are only reported
+    #[serde(skip)]
+    is_required: bool,
+}
+
+impl DependencyCheck {
+    fn new(result: Result<Option<String>, UniversalInboxError>) -> Self {
+        match result {
+            Ok(detail) => DependencyCheck {
+                status: "healthy",
+                detail,
+                is_required: true,
+            },
+            Err(err) => DependencyCheck {
+                status: "unhealthy",
+                detail: Some(err.to_string()),
+                is_required: true,
+            },
+        }
+    }
+
+    fn required(self, is_required: bool) -> Self {
+        DependencyCheck {
+            is_required,
+            ..self
+        }
+    }
+
+    fn is_ready(&self) -> bool {
+        !self.is_required || self.status == "healthy"
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ReadinessReport {
+    status: &'static str,
+    checks: BTreeMap<&'static str, DependencyCheck>,
+    /// Reported but not part of the readiness: an upstream outage must not
+    /// take the service down
+    #[serde(skip_serializing_if = "Option::is_none")]
+    providers: Option<Vec<IntegrationProviderHealth>>,
+}
+
+/// Public readiness probe, rate limited as `/ping`.
+///
+/// Unlike `/ping`, it checks every dependency the service needs to do its job:
+/// applied migrations, the job storage, the heartbeat of the asynchronous
+/// workers and the encryption and signing keys. The worker heartbeat is only
+/// reported by the API, see `ReadinessProbe`. Anonymous callers only get the
+/// overall status, while admins (see `SecuritySettings::admin_user_ids`) get
+/// the detail of each check and the health of the integration providers.
+#[allow(clippy::too_many_arguments)]
+pub async fn ready(
+    req: HttpRequest,
+    maybe_authenticated: MaybeAuthenticated<Claims>,
+    settings: web::Data<Settings>,
+    readiness_probe: web::Data<ReadinessProbe>,
+    job_storage: web::Data<JobStorage>,
+    cache: web::Data<Cache>,
+    integration_connection_service: web::Data<Arc<RwLock<IntegrationConnectionService>>>,
+    rate_limiter: web::Data<Arc<PingRateLimiter>>,
+) -> Result<HttpResponse, UniversalInboxError> {
+    if let Err(response) = crate::utils::rate_limit::check_ip_rate_limit(&req, &rate_limiter) {
+        return Ok(response);
+    }
+
+    // Same reasoning as `/ping` for not holding the read lock during the checks
+    let (pool, token_encryption_key_result) = {
+        let service = integration_connection_service.read().await;
+        (service.pool(), service.check_token_encryption_key())
+    };
+    let repository = Repository::new(pool);
+
+    let migrations_result = repository
+        .fetch_pending_migration_versions()
+        .await
+        .and_then(|pending_versions| {
+            if pending_versions.is_empty() {
+                Ok(None)
+            } else {
+                Err(UniversalInboxError::Unexpected(anyhow!(
+                    "Pending migrations: {pending_versions:?}"
+                )))
+            }
+        });
+    let job_storage_result = job_storage
+        .len()
+        .await
+        .map(|depth| Some(format!("{depth} jobs in the queue")));
+    let worker_heartbeat_result = fetch_last_worker_heartbeat(&job_storage, &cache)
+        .await
+        .and_then(|last_heartbeat| match last_heartbeat {
+            Some(sent_at) => Ok(Some(format!("Last heartbeat sent at {sent_at}"))),
+            None => Err(UniversalInboxError::Unexpected(anyhow!(
+                "No worker heartbeat for the last {} seconds",
+                WORKER_HEARTBEAT_TIMEOUT.as_secs()
+            ))),
+        });
+    let jwt_keys_result = readiness_probe
+        .jwt_key_pair_check
+        .clone()
+        .map(|_| None)
+        .map_err(|err| UniversalInboxError::Unexpected(anyhow!(err)));
+
+    let checks = BTreeMap::from([
+        ("migrations", DependencyCheck::new(migrations_result)),
+        ("job_storage", DependencyCheck::new(job_storage_result)),
+        (
+            "worker_heartbeat",
+            DependencyCheck::new(worker_heartbeat_result)
+                .required(readiness_probe.is_worker_heartbeat_required),
+        ),
+        (
+            "token_encryption_key",
+            DependencyCheck::new(token_encryption_key_result.map(|_| None)),
+        ),
+        ("jwt_keys", DependencyCheck::new(jwt_keys_result)),
+    ]);
+    let is_ready = checks.values().all(DependencyCheck::is_ready);
+    let status = if is_ready { "ready" } else { "not_ready" };
+    let mut response = if is_ready {
+        HttpResponse::Ok()
+    } else {
+        HttpResponse::ServiceUnavailable()
+    };
+    response.content_type("application/json");
+
+    // MCP access tokens carry an audience and are not accepted outside the MCP scope
+    let is_admin = maybe_authenticated
+        .into_option()
+        .is_some_and(|authenticated| {
+            authenticated.claims.aud.is_none()
+                && settings
+                    .application
+                    .security
+                    .is_admin(&authenticated.claims.sub)
+        });
+    if !is_admin {
+        return Ok(response.body(BoxBody::new(json!({ "status": status }).to_string())));
+    }
+
+    let providers = match fetch_integration_provider_health(&integration_connection_service).await {
+        Ok(providers) => Some(providers),
+        Err(err) => {
+            warn!("Failed to fetch the health of the integration providers: {err:?}");
+            None
+        }
+    };
+
+    Ok(response.body(
+        serde_json::to_string(&ReadinessReport {
+            status,
+            checks,
+            providers,
+        })
+        .context("Cannot serialize readiness report")?,
+    ))
+}
+
+async fn fetch_integration_provider_health(
+    integration_connection_service: &RwLock<IntegrationConnectionService>,
+) -> Result<Vec<IntegrationProviderHealth>, UniversalInboxError> {
+    let service = integration_connection_service.read().await;
+    let mut transaction = service
+        .begin()
+        .await
+        .context("Failed to create new transaction while fetching integration providers health")?;
+    service
+        .fetch_integration_provider_health(
+            &mut transaction,
+            Utc::now() - TimeDelta::hours(PROVIDER_HEALTH_PERIOD_IN_HOURS),
+        )
+        .await
+}
+
+/// Metrics in the Prometheus text exposition format, only served on the worker
+/// health-check port when enabled in the configuration
+pub async fn metrics() -> Result<HttpResponse, UniversalInboxError> {
+    Ok(HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(crate::metrics::render_prometheus_metrics()?))
+}
@@ -47,6 +47,10 @@ pub fn scope() -> Scope {
             web::resource("/{integration_connection_id}/config")
                 .route(web::put().to(update_integration_connection_config)),
         )
+        .service(
+            web::resource("/{integration_connection_id}/sync-runs")
+                .route(web::get().to(list_integration_connection_sync_runs)),
+        )
 }
 
 pub async fn list_integration_connections(
@@ -228,6 +232,51 @@ pub async fn disconnect_integration_connection(
     }
 }
 
+pub async fn list_integration_connection_sync_runs(
+    path: web::Path<IntegrationConnectionId>,
+    integration_connection_service: web::Data<Arc<RwLock<IntegrationConnectionService>>>,
+    authenticated: Authenticated<Claims>,
+) -> Result<HttpResponse, UniversalInboxError> {
+    let user_id = authenticated
+        .claims
+        .sub
+        .parse::<UserId>()
+        .context("Wrong user ID format")?;
+    let integration_connection_id = path.into_inner();
+    let service = integration_connection_service.read().await;
+    let mut transaction = service.begin().await.context(format!(
+        "Failed to create new transaction while listing sync runs of integration connection {integration_connection_id}"
+    ))?;
+
+    // As for the configuration, a connection owned by another user is reported
+    // as unknown
+    let sync_runs = match service
+        .fetch_sync_runs(&mut transaction, integration_connection_id, user_id)
+        .await
+    {
+        Ok(sync_runs) => sync_runs,
+        Err(UniversalInboxError::Forbidden(_) | UniversalInboxError::ItemNotFound(_)) => {
+            return Ok(HttpResponse::NotFound()
+                .content_type("application/json")
+                .body(BoxBody::new(
+                    json!({
+                        "message": format!("Cannot list sync runs of unknown integration connection {integration_connection_id}")
+                    })
+                    .to_string(),
+                )));
+        }
+        Err(err) => return Err(err),
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/json")
+        .insert_header(CacheControl(vec![CacheDirective::NoStore]))
+        .body(
+            serde_json::to_string(&sync_runs)
+                .context("Cannot serialize integration connection sync runs")?,
+        ))
+}
+
 #[derive(Debug, Deserialize)]
 pub struct SearchSlackEmojiRequest {
     matches: Option<String>,
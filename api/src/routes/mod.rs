@@ -17,8 +17,11 @@ use actix_web::{
     HttpResponse, ResponseError,
     http::header::{self, ContentType},
 };
+use chrono::Utc;
 use serde_json::json;
 
+use universal_inbox::auth::oauth2::OAuth2Scope;
+
 use crate::universal_inbox::UniversalInboxError;
 
 impl ResponseError for UniversalInboxError {
@@ -35,10 +38,13 @@ impl ResponseError for UniversalInboxError {
             UniversalInboxError::Unexpected(_) => StatusCode::INTERNAL_SERVER_ERROR,
             UniversalInboxError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
             UniversalInboxError::Forbidden(_) => StatusCode::FORBIDDEN,
+            UniversalInboxError::InsufficientScope { .. } => StatusCode::FORBIDDEN,
             UniversalInboxError::TooManyLoginAttempts { .. } => StatusCode::TOO_MANY_REQUESTS,
+            UniversalInboxError::UpstreamRateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
             UniversalInboxError::UnsupportedAction(_) => StatusCode::BAD_REQUEST,
             UniversalInboxError::DatabaseError { .. } => StatusCode::INTERNAL_SERVER_ERROR,
             UniversalInboxError::OAuth2InvalidGrant(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            UniversalInboxError::OAuth2DeviceGrant(_) => StatusCode::BAD_REQUEST,
         }
     }
 
@@ -59,6 +65,39 @@ impl ResponseError for UniversalInboxError {
             res.headers_mut().insert(header::RETRY_AFTER, value);
         }
 
+        // Same for a sync deferred until the provider's rate limit resets.
+        if let UniversalInboxError::UpstreamRateLimited { until, .. } = self {
+            let retry_after_seconds = (*until - Utc::now()).num_seconds().max(1);
+            if let Ok(value) = header::HeaderValue::from_str(&retry_after_seconds.to_string()) {
+                res.headers_mut().insert(header::RETRY_AFTER, value);
+            }
+        }
+
+        // RFC 8628 §3.5: device clients poll on the `error` code.
+        if let UniversalInboxError::OAuth2DeviceGrant(error) = self {
+            return res.set_body(BoxBody::new(
+                json!({ "message": format!("{self}"), "error": error }).to_string(),
+            ));
+        }
+
+        // RFC 6750 §3.1: tell the client which scope it needs to request.
+        if let UniversalInboxError::InsufficientScope { required_scopes } = self {
+            let scope = OAuth2Scope::to_scope_string(required_scopes);
+            if let Ok(value) = header::HeaderValue::from_str(&format!(
+                r#"Bearer error="insufficient_scope", scope="{scope}""#
+            )) {
+                res.headers_mut().insert(header::WWW_AUTHENTICATE, value);
+            }
+            return res.set_body(BoxBody::new(
+                json!({
+                    "message": format!("{self}"),
+                    "error": "insufficient_scope",
+                    "scope": scope
+                })
+                .to_string(),
+            ));
+        }
+
         res.set_body(BoxBody::new(
             json!({ "message": format!("{self}") }).to_string(),
         ))
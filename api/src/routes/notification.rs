@@ -4,7 +4,6 @@ use crate::middlewares::jwt_auth::{Authenticated, MaybeAuthenticated};
 use actix_http::body::BoxBody;
 use actix_web::{HttpResponse, Scope, web};
 use anyhow::Context;
-use apalis_redis::RedisStorage;
 use serde::Deserialize;
 use serde_json::json;
 use serde_with::{StringWithSeparator, formats::CommaSeparator, serde_as};
@@ -16,8 +15,8 @@ use universal_inbox::{
         NotificationId, NotificationListOrder, NotificationSourceKind, NotificationStatus,
         NotificationWithTask,
         service::{
-            InvitationPatch, NotificationPatch, PatchNotificationsRequest,
-            SyncNotificationsParameters,
+            GithubPullRequestAction, InvitationPatch, LinearIssueAction, NotificationPatch,
+            PatchNotificationsRequest, SyncNotificationsParameters,
         },
     },
     task::{TaskCreation, TaskId},
@@ -26,7 +25,7 @@ use universal_inbox::{
 };
 
 use crate::{
-    jobs::UniversalInboxJob,
+    jobs::storage::JobStorage,
     universal_inbox::{
         UniversalInboxError, UpdateStatus,
         integration_connection::service::IntegrationConnectionService,
@@ -57,6 +56,18 @@ pub fn scope() -> Scope {
             web::resource("/{notification_id}/invitation")
                 .route(web::patch().to(update_invitation_from_notification)),
         )
+        .service(
+            web::resource("/{notification_id}/github/pull-request")
+                .route(web::post().to(apply_github_pull_request_action)),
+        )
+        .service(
+            web::resource("/{notification_id}/linear/issue")
+                .route(web::post().to(apply_linear_issue_action)),
+        )
+        .service(
+            web::resource("/{notification_id}/linear/team")
+                .route(web::get().to(get_linear_issue_team_details)),
+        )
 }
 
 #[serde_as]
@@ -78,7 +89,7 @@ pub async fn list_notifications(
     list_notification_request: web::Query<ListNotificationRequest>,
     notification_service: web::Data<Arc<RwLock<NotificationService>>>,
     authenticated: Authenticated<Claims>,
-    job_storage: web::Data<RedisStorage<UniversalInboxJob>>,
+    job_storage: web::Data<JobStorage>,
 ) -> Result<HttpResponse, UniversalInboxError> {
     let user_id = authenticated
         .claims
@@ -120,10 +131,12 @@ pub async fn list_notifications(
                 .unwrap_or(false),
             list_notification_request.task_id,
             list_notification_request.order_by.unwrap_or_default(),
-            list_notification_request
-                .sources
-                .clone()
-                .unwrap_or_default(),
+            authenticated.claims.restrict_notification_source_kinds(
+                list_notification_request
+                    .sources
+                    .clone()
+                    .unwrap_or_default(),
+            )?,
             page_token,
             user_id,
             list_notification_request
@@ -159,6 +172,14 @@ pub async fn get_notification(
         .begin()
         .await
         .context("Failed to create new transaction while getting notification")?;
+    service
+        .check_notification_source_access(
+            &mut transaction,
+            notification_id,
+            user_id,
+            authenticated.claims.notification_source_kinds.as_deref(),
+        )
+        .await?;
 
     match service
         .get_notification_with_task(&mut transaction, notification_id, user_id)
@@ -181,10 +202,9 @@ pub async fn sync_notifications(
     notification_service: web::Data<Arc<RwLock<NotificationService>>>,
     integration_connection_service: web::Data<Arc<RwLock<IntegrationConnectionService>>>,
     maybe_authenticated: MaybeAuthenticated<Claims>,
-    storage: web::Data<RedisStorage<UniversalInboxJob>>,
+    storage: web::Data<JobStorage>,
 ) -> Result<HttpResponse, UniversalInboxError> {
     let source = params.source;
-    let mut storage = storage.as_ref().clone();
 
     if let Some(authenticated) = maybe_authenticated.into_option() {
         let user_id = authenticated
@@ -193,30 +213,46 @@ pub async fn sync_notifications(
             .parse::<UserId>()
             .context("Wrong user ID format")?;
 
+        let sources = authenticated
+            .claims
+            .restrict_notification_sync_source_kinds(source)?;
+
         if params.asynchronous.unwrap_or(true) {
             let service = integration_connection_service.read().await;
             let mut transaction = service
                 .begin()
                 .await
                 .context("Failed to create new transaction while triggering notifications sync")?;
-            service
-                .trigger_sync_notifications(&mut transaction, source, Some(user_id), &mut storage)
-                .await?;
+            match sources {
+                None => {
+                    service
+                        .trigger_sync_notifications(&mut transaction, None, Some(user_id), &storage)
+                        .await?
+                }
+                Some(sources) => {
+                    for source in sources {
+                        service
+                            .trigger_sync_notifications(
+                                &mut transaction,
+                                Some(source),
+                                Some(user_id),
+                                &storage,
+                            )
+                            .await?;
+                    }
+                }
+            }
             transaction
                 .commit()
                 .await
                 .context("Failed to commit while triggering notifications sync")?;
             Ok(HttpResponse::Created().finish())
         } else {
-            let service = notification_service.read().await;
-
-            let notifications = if let Some(source) = source {
-                service
-                    .sync_notifications_with_transaction(source, user_id, false)
-                    .await?
-            } else {
-                service.sync_all_notifications(user_id, false).await?
-            };
+            let notifications = notification_service
+                .read()
+                .await
+                .sync_notifications_from_sources(sources, user_id, false)
+                .await?;
             Ok(HttpResponse::Ok().content_type("application/json").body(
                 serde_json::to_string(&notifications).context("Cannot serialize notifications")?,
             ))
@@ -228,7 +264,7 @@ pub async fn sync_notifications(
             .await
             .context("Failed to create new transaction while triggering notifications sync")?;
         service
-            .trigger_sync_notifications(&mut transaction, source, None, &mut storage)
+            .trigger_sync_notifications(&mut transaction, source, None, &storage)
             .await?;
         transaction
             .commit()
@@ -242,7 +278,7 @@ pub async fn patch_notifications(
     patch_request: web::Json<PatchNotificationsRequest>,
     notification_service: web::Data<Arc<RwLock<NotificationService>>>,
     authenticated: Authenticated<Claims>,
-    job_storage: web::Data<RedisStorage<UniversalInboxJob>>,
+    job_storage: web::Data<JobStorage>,
 ) -> Result<HttpResponse, UniversalInboxError> {
     let user_id = authenticated
         .claims
@@ -260,10 +296,12 @@ pub async fn patch_notifications(
         .patch_notifications_bulk(
             &mut transaction,
             request.status,
-            request.sources,
+            authenticated
+                .claims
+                .restrict_notification_source_kinds(request.sources)?,
             &request.patch,
             user_id,
-            &mut job_storage.as_ref().clone(),
+            &job_storage,
         )
         .await?;
 
@@ -296,6 +334,14 @@ pub async fn patch_notification(
         .begin()
         .await
         .context(format!("Failed to patch notification {notification_id}"))?;
+    service
+        .check_notification_source_access(
+            &mut transaction,
+            notification_id,
+            user_id,
+            authenticated.claims.notification_source_kinds.as_deref(),
+        )
+        .await?;
 
     let updated_notification = service
         .patch_notification(
@@ -355,6 +401,14 @@ pub async fn create_task_from_notification(
         .begin()
         .await
         .context(format!("Failed to create task from {notification_id}"))?;
+    service
+        .check_notification_source_access(
+            &mut transaction,
+            notification_id,
+            user_id,
+            authenticated.claims.notification_source_kinds.as_deref(),
+        )
+        .await?;
 
     let notification_with_task = service
         .create_task_from_notification(
@@ -393,6 +447,14 @@ pub async fn update_invitation_from_notification(
         .begin()
         .await
         .context("Failed to create new transaction while updating invitation")?;
+    service
+        .check_notification_source_access(
+            &mut transaction,
+            notification_id,
+            user_id,
+            authenticated.claims.notification_source_kinds.as_deref(),
+        )
+        .await?;
 
     let updated_notification = service
         .update_invitation_from_notification(
@@ -431,3 +493,156 @@ pub async fn update_invitation_from_notification(
             ))),
     }
 }
+
+pub async fn apply_github_pull_request_action(
+    path: web::Path<NotificationId>,
+    action: web::Json<GithubPullRequestAction>,
+    notification_service: web::Data<Arc<RwLock<NotificationService>>>,
+    authenticated: Authenticated<Claims>,
+) -> Result<HttpResponse, UniversalInboxError> {
+    let user_id = authenticated
+        .claims
+        .sub
+        .parse::<UserId>()
+        .context("Wrong user ID format")?;
+    let notification_id = path.into_inner();
+    let action = action.into_inner();
+    let service = notification_service.read().await;
+    let mut transaction = service
+        .begin()
+        .await
+        .context("Failed to create new transaction while acting on a Github pull request")?;
+    service
+        .check_notification_source_access(
+            &mut transaction,
+            notification_id,
+            user_id,
+            authenticated.claims.notification_source_kinds.as_deref(),
+        )
+        .await?;
+
+    let updated_notification = service
+        .apply_github_pull_request_action(&mut transaction, notification_id, &action, user_id)
+        .await?;
+
+    transaction.commit().await.context(format!(
+        "Failed to commit while acting on pull request of notification {notification_id}"
+    ))?;
+
+    match updated_notification {
+        UpdateStatus {
+            updated: _,
+            result: Some(notification),
+        } => Ok(HttpResponse::Ok()
+            .content_type("application/json")
+            .body(serde_json::to_string(&notification).context("Cannot serialize notification")?)),
+        UpdateStatus {
+            updated: _,
+            result: None,
+        } => Ok(HttpResponse::NotFound()
+            .content_type("application/json")
+            .body(BoxBody::new(
+                json!({
+                    "message": format!("Cannot act on unknown notification {notification_id}")
+                })
+                .to_string(),
+            ))),
+    }
+}
+
+pub async fn apply_linear_issue_action(
+    path: web::Path<NotificationId>,
+    action: web::Json<LinearIssueAction>,
+    notification_service: web::Data<Arc<RwLock<NotificationService>>>,
+    authenticated: Authenticated<Claims>,
+) -> Result<HttpResponse, UniversalInboxError> {
+    let user_id = authenticated
+        .claims
+        .sub
+        .parse::<UserId>()
+        .context("Wrong user ID format")?;
+    let notification_id = path.into_inner();
+    let action = action.into_inner();
+    let service = notification_service.read().await;
+    let mut transaction = service
+        .begin()
+        .await
+        .context("Failed to create new transaction while acting on a Linear issue")?;
+    service
+        .check_notification_source_access(
+            &mut transaction,
+            notification_id,
+            user_id,
+            authenticated.claims.notification_source_kinds.as_deref(),
+        )
+        .await?;
+
+    let updated_notification = service
+        .apply_linear_issue_action(&mut transaction, notification_id, &action, user_id)
+        .await?;
+
+    transaction.commit().await.context(format!(
+        "Failed to commit while acting on issue of notification {notification_id}"
+    ))?;
+
+    match updated_notification {
+        UpdateStatus {
+            updated: _,
+            result: Some(notification),
+        } => Ok(HttpResponse::Ok()
+            .content_type("application/json")
+            .body(serde_json::to_string(&notification).context("Cannot serialize notification")?)),
+        UpdateStatus {
+            updated: _,
+            result: None,
+        } => Ok(HttpResponse::NotFound()
+            .content_type("application/json")
+            .body(BoxBody::new(
+                json!({
+                    "message": format!("Cannot act on unknown notification {notification_id}")
+                })
+                .to_string(),
+            ))),
+    }
+}
+
+pub async fn get_linear_issue_team_details(
+    path: web::Path<NotificationId>,
+    notification_service: web::Data<Arc<RwLock<NotificationService>>>,
+    authenticated: Authenticated<Claims>,
+) -> Result<HttpResponse, UniversalInboxError> {
+    let user_id = authenticated
+        .claims
+        .sub
+        .parse::<UserId>()
+        .context("Wrong user ID format")?;
+    let notification_id = path.into_inner();
+    let service = notification_service.read().await;
+    let mut transaction = service
+        .begin()
+        .await
+        .context("Failed to create new transaction while getting Linear issue team")?;
+    service
+        .check_notification_source_access(
+            &mut transaction,
+            notification_id,
+            user_id,
+            authenticated.claims.notification_source_kinds.as_deref(),
+        )
+        .await?;
+
+    match service
+        .get_linear_issue_team_details(&mut transaction, notification_id, user_id)
+        .await?
+    {
+        Some(team_details) => Ok(HttpResponse::Ok().content_type("application/json").body(
+            serde_json::to_string(&team_details).context("Cannot serialize Linear team details")?,
+        )),
+        None => Ok(HttpResponse::NotFound()
+            .content_type("application/json")
+            .body(BoxBody::new(
+                json!({ "message": format!("Cannot find notification {notification_id}") })
+                    .to_string(),
+            ))),
+    }
+}
@@ -11,7 +11,9 @@ use uuid::Uuid;
 
 use universal_inbox::{
     auth::oauth2::{
-        OAuth2ConsentDecision, OAuth2ConsentRequest, OAuth2ConsentResponse, OAuth2ConsentSubmission,
+        OAuth2ConsentDecision, OAuth2ConsentRequest, OAuth2ConsentResponse,
+        OAuth2ConsentSubmission, OAuth2DeviceVerification, OAuth2DeviceVerificationResponse,
+        OAuth2Scope,
     },
     user::UserId,
 };
@@ -21,7 +23,7 @@ use crate::{
     universal_inbox::{
         UniversalInboxError,
         oauth2::service::{
-            OAuth2Service, is_loopback_redirect_uri, is_origin_in_allowlist,
+            OAuth2Service, format_user_code, is_loopback_redirect_uri, is_origin_in_allowlist,
             redirect_uri_matches_registered, validate_redirect_uri,
         },
     },
@@ -31,6 +33,7 @@ use crate::{
 const OAUTH2_RATE_LIMIT_PER_MINUTE: u32 = 30;
 const OAUTH2_PENDING_CONSENT_SESSION_KEY: &str = "oauth2_pending_consent";
 const PENDING_CONSENT_TTL_SECS: i64 = 300;
+const DEVICE_CODE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
 
 type OAuth2RateLimiter = IpRateLimiter;
 
@@ -49,6 +52,13 @@ pub fn scope(rate_limiter: Arc<OAuth2RateLimiter>) -> Scope {
         .route("/authorize/consent", web::get().to(consent_get))
         .route("/authorize/consent", web::post().to(consent_post))
         .route("/token", web::post().to(token))
+        .route(
+            "/device_authorization",
+            web::post().to(device_authorization),
+        )
+        .route("/device/verify", web::post().to(device_verify))
+        .route("/revoke", web::post().to(revoke))
+        .route("/introspect", web::post().to(introspect))
 }
 
 #[derive(Debug, Deserialize)]
@@ -77,6 +87,25 @@ pub struct TokenParams {
     pub code_verifier: Option<String>,
     pub client_id: String,
     pub refresh_token: Option<String>,
+    pub device_code: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeviceAuthorizationParams {
+    pub client_id: String,
+    pub scope: Option<String>,
+    pub resource: Option<String>,
+}
+
+/// Form parameters of the revocation (RFC 7009) and introspection (RFC 7662)
+/// endpoints. Clients are public, so they identify themselves with
+/// `client_id` only. `token_type_hint` is accepted but not needed: access
+/// tokens are JWTs and are told apart from refresh tokens by decoding them.
+#[derive(Debug, Deserialize)]
+pub struct TokenManagementParams {
+    pub token: String,
+    pub token_type_hint: Option<String>,
+    pub client_id: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -85,21 +114,35 @@ pub struct ConsentRequestQuery {
 }
 
 /// Pending consent state held in the user's session between GET /authorize
-/// and POST /authorize/consent. Carries every parameter required to either
-/// issue an authorization code (on allow) or build an error redirect (on deny).
+/// (or POST /device/verify) and POST /authorize/consent. Carries every
+/// parameter required to complete the grant on allow or deny.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct PendingConsentRequest {
     request_id: String,
     csrf_token: String,
     user_id: UserId,
     client_id: String,
-    redirect_uri: String,
     scope: Option<String>,
-    state: Option<String>,
-    code_challenge: String,
-    code_challenge_method: String,
     resource: Option<String>,
     expires_at: DateTime<Utc>,
+    #[serde(flatten)]
+    grant: PendingConsentGrant,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "grant_type", rename_all = "snake_case")]
+enum PendingConsentGrant {
+    /// Issue an authorization code (on allow) or build an error redirect
+    /// (on deny)
+    AuthorizationCode {
+        redirect_uri: String,
+        state: Option<String>,
+        code_challenge: String,
+        code_challenge_method: String,
+    },
+    /// Approve or deny the device authorization request (RFC 8628) the user
+    /// entered the normalized code of
+    DeviceCode { user_code: String },
 }
 
 impl PendingConsentRequest {
@@ -238,14 +281,27 @@ pub async fn authorize(
         });
     }
 
+    // The redirect_uri is now trusted: report an unknown scope to the client
+    // as an `invalid_scope` error redirect (RFC 6749 §4.1.2.1).
+    let scope = match OAuth2Service::resolve_requested_scope(params.scope.as_deref()) {
+        Ok(scope) => scope,
+        Err(_) => {
+            let redirect_url =
+                build_redirect_with_error(&params.redirect_uri, "invalid_scope", &params.state)?;
+            return Ok(HttpResponse::Found()
+                .insert_header(("Location", redirect_url))
+                .finish());
+        }
+    };
+
     // If the user has already consented to this client with at least the
     // requested scope, skip the consent screen and issue the code immediately.
     let existing_consent = service
         .get_user_consent(&mut transaction, user_id, &params.client_id)
         .await?;
-    let consent_covers = existing_consent.as_ref().is_some_and(|consent| {
-        OAuth2Service::consent_covers_scope(&consent.scope, params.scope.as_deref())
-    });
+    let consent_covers = existing_consent
+        .as_ref()
+        .is_some_and(|consent| OAuth2Service::consent_covers_scope(&consent.scope, Some(&scope)));
 
     if consent_covers {
         let code = service
@@ -254,7 +310,7 @@ pub async fn authorize(
                 &params.client_id,
                 user_id,
                 &params.redirect_uri,
-                params.scope.as_deref(),
+                Some(&scope),
                 &params.code_challenge,
                 &params.code_challenge_method,
                 params.resource.as_deref(),
@@ -284,16 +340,15 @@ pub async fn authorize(
         csrf_token: generate_csrf_token(),
         user_id,
         client_id: params.client_id.clone(),
-        redirect_uri: params.redirect_uri.clone(),
-        scope: params.scope.clone(),
-        state: params.state.clone(),
-        code_challenge: params.code_challenge.clone(),
-        code_challenge_method: params.code_challenge_method.clone(),
+        scope: Some(scope),
         resource: params.resource.clone(),
-        expires_at: Utc::now()
-            + TimeDelta::try_seconds(PENDING_CONSENT_TTL_SECS).unwrap_or_else(|| {
-                panic!("Invalid PENDING_CONSENT_TTL_SECS value: {PENDING_CONSENT_TTL_SECS}")
-            }),
+        expires_at: pending_consent_expires_at(),
+        grant: PendingConsentGrant::AuthorizationCode {
+            redirect_uri: params.redirect_uri.clone(),
+            state: params.state.clone(),
+            code_challenge: params.code_challenge.clone(),
+            code_challenge_method: params.code_challenge_method.clone(),
+        },
     };
 
     session
@@ -343,13 +398,27 @@ pub async fn consent_get(
         .await
         .context("Failed to commit while loading OAuth2 consent request")?;
 
+    let (redirect_uri, user_code) = match &pending.grant {
+        PendingConsentGrant::AuthorizationCode { redirect_uri, .. } => {
+            (Some(redirect_uri.clone()), None)
+        }
+        PendingConsentGrant::DeviceCode { user_code } => (None, Some(format_user_code(user_code))),
+    };
     let response = OAuth2ConsentRequest {
         request_id: pending.request_id.clone(),
         csrf_token: pending.csrf_token.clone(),
         client_id: pending.client_id.clone(),
         client_name: client.client_name,
-        redirect_uri: pending.redirect_uri.clone(),
+        redirect_uri,
+        user_code,
         scope: pending.scope.clone(),
+        scopes: pending
+            .scope
+            .as_deref()
+            .map(OAuth2Scope::parse_scopes_lossy)
+            .unwrap_or_default()
+            .into_iter()
+            .collect(),
     };
     Ok(HttpResponse::Ok()
         .content_type("application/json")
@@ -361,6 +430,7 @@ pub async fn consent_post(
     authenticated: Authenticated<Claims>,
     body: web::Json<OAuth2ConsentSubmission>,
     session: Session,
+    settings: web::Data<Settings>,
 ) -> Result<HttpResponse, UniversalInboxError> {
     let user_id = authenticated
         .claims
@@ -381,17 +451,25 @@ pub async fn consent_post(
     // Consume the pending entry regardless of the decision.
     session.remove(OAUTH2_PENDING_CONSENT_SESSION_KEY);
 
-    match body.decision {
-        OAuth2ConsentDecision::Deny => {
-            let redirect_url =
-                build_redirect_with_error(&pending.redirect_uri, "access_denied", &pending.state)?;
-            Ok(HttpResponse::Ok().content_type("application/json").body(
-                serde_json::to_string(&OAuth2ConsentResponse { redirect_url })
-                    .context("Cannot serialize OAuth2 consent response")?,
-            ))
-        }
-        OAuth2ConsentDecision::Allow => {
-            let service = oauth2_service.clone();
+    let service = oauth2_service.clone();
+    let redirect_url = match (&pending.grant, body.decision) {
+        (
+            PendingConsentGrant::AuthorizationCode {
+                redirect_uri,
+                state,
+                ..
+            },
+            OAuth2ConsentDecision::Deny,
+        ) => build_redirect_with_error(redirect_uri, "access_denied", state)?,
+        (
+            PendingConsentGrant::AuthorizationCode {
+                redirect_uri,
+                state,
+                code_challenge,
+                code_challenge_method,
+            },
+            OAuth2ConsentDecision::Allow,
+        ) => {
             let mut transaction = service
                 .begin()
                 .await
@@ -411,10 +489,10 @@ pub async fn consent_post(
                     &mut transaction,
                     &pending.client_id,
                     user_id,
-                    &pending.redirect_uri,
+                    redirect_uri,
                     pending.scope.as_deref(),
-                    &pending.code_challenge,
-                    &pending.code_challenge_method,
+                    code_challenge,
+                    code_challenge_method,
                     pending.resource.as_deref(),
                 )
                 .await?;
@@ -424,14 +502,46 @@ pub async fn consent_post(
                 .await
                 .context("Failed to commit while recording OAuth2 consent")?;
 
-            let redirect_url =
-                build_redirect_with_code(&pending.redirect_uri, &code, &pending.state)?;
-            Ok(HttpResponse::Ok().content_type("application/json").body(
-                serde_json::to_string(&OAuth2ConsentResponse { redirect_url })
-                    .context("Cannot serialize OAuth2 consent response")?,
-            ))
+            build_redirect_with_code(redirect_uri, &code, state)?
         }
-    }
+        (PendingConsentGrant::DeviceCode { user_code }, decision) => {
+            let approved = decision == OAuth2ConsentDecision::Allow;
+            let mut transaction = service
+                .begin()
+                .await
+                .context("Failed to create new transaction while recording OAuth2 consent")?;
+
+            if approved {
+                service
+                    .record_user_consent(
+                        &mut transaction,
+                        user_id,
+                        &pending.client_id,
+                        pending.scope.as_deref().unwrap_or(""),
+                    )
+                    .await?;
+            }
+            service
+                .decide_device_authorization(&mut transaction, user_code, user_id, approved)
+                .await?;
+
+            transaction
+                .commit()
+                .await
+                .context("Failed to commit while recording OAuth2 consent")?;
+
+            format!(
+                "{}oauth2/device?status={}",
+                settings.application.front_base_url,
+                if approved { "approved" } else { "denied" }
+            )
+        }
+    };
+
+    Ok(HttpResponse::Ok().content_type("application/json").body(
+        serde_json::to_string(&OAuth2ConsentResponse { redirect_url })
+            .context("Cannot serialize OAuth2 consent response")?,
+    ))
 }
 
 pub async fn token(
@@ -497,6 +607,31 @@ pub async fn token(
                 .refresh_token(&mut transaction, refresh_token, &form.client_id)
                 .await?
         }
+        DEVICE_CODE_GRANT_TYPE => {
+            let device_code = form.device_code.as_deref().ok_or_else(|| {
+                UniversalInboxError::InvalidInputData {
+                    source: None,
+                    user_error: "Missing 'device_code' parameter for device_code grant".to_string(),
+                }
+            })?;
+
+            match service
+                .exchange_device_code(&mut transaction, device_code, &form.client_id)
+                .await
+            {
+                Ok(token_response) => token_response,
+                // Persist the polling state (last poll time, slowed down
+                // interval, consumed request) before reporting the error
+                Err(err @ UniversalInboxError::OAuth2DeviceGrant(_)) => {
+                    transaction
+                        .commit()
+                        .await
+                        .context("Failed to commit while polling device authorization")?;
+                    return Err(err);
+                }
+                Err(err) => return Err(err),
+            }
+        }
         _ => {
             return Err(UniversalInboxError::InvalidInputData {
                 source: None,
@@ -517,6 +652,202 @@ pub async fn token(
         .body(serde_json::to_string(&token_response).context("Cannot serialize token response")?))
 }
 
+/// RFC 7009 token revocation. Always answers `200 OK`, whether the token was
+/// revoked, already revoked, unknown or issued to another client.
+pub async fn revoke(
+    oauth2_service: web::Data<Arc<OAuth2Service>>,
+    form: web::Form<TokenManagementParams>,
+    req: actix_web::HttpRequest,
+    rate_limiter: web::Data<Arc<OAuth2RateLimiter>>,
+) -> Result<HttpResponse, UniversalInboxError> {
+    if let Err(response) = crate::utils::rate_limit::check_ip_rate_limit(&req, &rate_limiter) {
+        return Ok(response);
+    }
+    let service = oauth2_service.clone();
+    let mut transaction = service
+        .begin()
+        .await
+        .context("Failed to create new transaction while revoking token")?;
+
+    service
+        .revoke_token(&mut transaction, &form.token, &form.client_id)
+        .await?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit while revoking token")?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(("Cache-Control", "no-store"))
+        .finish())
+}
+
+/// RFC 7662 token introspection for resource servers. Resource servers
+/// authenticate with an API key granted the `tokens:introspect` scope, given
+/// as a `Bearer` token. Only the tokens of the API key's user are reported as
+/// active.
+pub async fn introspect(
+    oauth2_service: web::Data<Arc<OAuth2Service>>,
+    form: web::Form<TokenManagementParams>,
+    req: actix_web::HttpRequest,
+    rate_limiter: web::Data<Arc<OAuth2RateLimiter>>,
+    authenticated: Authenticated<Claims>,
+) -> Result<HttpResponse, UniversalInboxError> {
+    if let Err(response) = crate::utils::rate_limit::check_ip_rate_limit(&req, &rate_limiter) {
+        return Ok(response);
+    }
+    // Unscoped tokens (sessions, legacy API keys) are not resource server
+    // credentials, although they are let through by the scope guard
+    match authenticated.claims.granted_scopes() {
+        Some(granted_scopes) if granted_scopes.contains(&OAuth2Scope::TokensIntrospect) => {}
+        _ => {
+            return Err(UniversalInboxError::InsufficientScope {
+                required_scopes: vec![OAuth2Scope::TokensIntrospect],
+            });
+        }
+    }
+    let user_id = authenticated
+        .claims
+        .sub
+        .parse::<UserId>()
+        .context("Wrong user ID format")?;
+    let service = oauth2_service.clone();
+    let mut transaction = service
+        .begin()
+        .await
+        .context("Failed to create new transaction while introspecting token")?;
+
+    let introspection_response = service
+        .introspect_token(&mut transaction, &form.token, &form.client_id, user_id)
+        .await?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit while introspecting token")?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/json")
+        .insert_header(("Cache-Control", "no-store"))
+        .insert_header(("Pragma", "no-cache"))
+        .body(
+            serde_json::to_string(&introspection_response)
+                .context("Cannot serialize token introspection response")?,
+        ))
+}
+
+/// RFC 8628 device authorization request, for clients unable to drive a
+/// browser redirect (CLIs, headless agents).
+pub async fn device_authorization(
+    oauth2_service: web::Data<Arc<OAuth2Service>>,
+    form: web::Form<DeviceAuthorizationParams>,
+    req: actix_web::HttpRequest,
+    rate_limiter: web::Data<Arc<OAuth2RateLimiter>>,
+    settings: web::Data<Settings>,
+) -> Result<HttpResponse, UniversalInboxError> {
+    if let Err(response) = crate::utils::rate_limit::check_ip_rate_limit(&req, &rate_limiter) {
+        return Ok(response);
+    }
+    let service = oauth2_service.clone();
+    let mut transaction = service
+        .begin()
+        .await
+        .context("Failed to create new transaction while creating device authorization")?;
+
+    let verification_uri = format!("{}oauth2/device", settings.application.front_base_url);
+    let device_authorization_response = service
+        .create_device_authorization(
+            &mut transaction,
+            &form.client_id,
+            form.scope.as_deref(),
+            form.resource.as_deref(),
+            &verification_uri,
+        )
+        .await?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit while creating device authorization")?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/json")
+        .insert_header(("Cache-Control", "no-store"))
+        .body(
+            serde_json::to_string(&device_authorization_response)
+                .context("Cannot serialize device authorization response")?,
+        ))
+}
+
+/// Verify the user code entered on the device verification page and start a
+/// consent request for it. The consent screen is always displayed, even if
+/// the client was already granted the requested scope, so that users are
+/// never silently signed in on a device they do not control.
+pub async fn device_verify(
+    oauth2_service: web::Data<Arc<OAuth2Service>>,
+    authenticated: Authenticated<Claims>,
+    body: web::Json<OAuth2DeviceVerification>,
+    req: actix_web::HttpRequest,
+    rate_limiter: web::Data<Arc<OAuth2RateLimiter>>,
+    session: Session,
+) -> Result<HttpResponse, UniversalInboxError> {
+    if let Err(response) = crate::utils::rate_limit::check_ip_rate_limit(&req, &rate_limiter) {
+        return Ok(response);
+    }
+    let user_id = authenticated
+        .claims
+        .sub
+        .parse::<UserId>()
+        .context("Wrong user ID format")?;
+
+    let service = oauth2_service.clone();
+    let mut transaction = service
+        .begin()
+        .await
+        .context("Failed to create new transaction while verifying device user code")?;
+
+    let device_authorization = service
+        .get_pending_device_authorization(&mut transaction, &body.user_code)
+        .await?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit while verifying device user code")?;
+
+    let pending = PendingConsentRequest {
+        request_id: Uuid::new_v4().to_string(),
+        csrf_token: generate_csrf_token(),
+        user_id,
+        client_id: device_authorization.client_id,
+        scope: device_authorization.scope,
+        resource: device_authorization.resource,
+        expires_at: pending_consent_expires_at(),
+        grant: PendingConsentGrant::DeviceCode {
+            user_code: device_authorization.user_code,
+        },
+    };
+
+    session
+        .insert(OAUTH2_PENDING_CONSENT_SESSION_KEY, &pending)
+        .context("Failed to insert OAuth2 pending consent into the session")?;
+
+    Ok(HttpResponse::Ok().content_type("application/json").body(
+        serde_json::to_string(&OAuth2DeviceVerificationResponse {
+            request_id: pending.request_id,
+        })
+        .context("Cannot serialize device verification response")?,
+    ))
+}
+
+fn pending_consent_expires_at() -> DateTime<Utc> {
+    Utc::now()
+        + TimeDelta::try_seconds(PENDING_CONSENT_TTL_SECS).unwrap_or_else(|| {
+            panic!("Invalid PENDING_CONSENT_TTL_SECS value: {PENDING_CONSENT_TTL_SECS}")
+        })
+}
+
 fn read_pending_consent(
     session: &Session,
     request_id: &str,
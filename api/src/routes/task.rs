@@ -8,7 +8,6 @@ use actix_web::{
     web,
 };
 use anyhow::Context;
-use apalis_redis::RedisStorage;
 use serde::Deserialize;
 use serde_json::json;
 use tokio::sync::RwLock;
@@ -24,7 +23,7 @@ use universal_inbox::{
 };
 
 use crate::{
-    jobs::UniversalInboxJob,
+    jobs::storage::JobStorage,
     universal_inbox::{
         UniversalInboxError, UpdateStatus,
         integration_connection::service::IntegrationConnectionService, task::service::TaskService,
@@ -60,7 +59,7 @@ pub async fn list_tasks(
     list_task_request: web::Query<ListTaskRequest>,
     task_service: web::Data<Arc<RwLock<TaskService>>>,
     authenticated: Authenticated<Claims>,
-    job_storage: web::Data<RedisStorage<UniversalInboxJob>>,
+    job_storage: web::Data<JobStorage>,
 ) -> Result<HttpResponse, UniversalInboxError> {
     let user_id = authenticated
         .claims
@@ -175,10 +174,9 @@ pub async fn sync_tasks(
     task_service: web::Data<Arc<RwLock<TaskService>>>,
     integration_connection_service: web::Data<Arc<RwLock<IntegrationConnectionService>>>,
     maybe_authenticated: MaybeAuthenticated<Claims>,
-    storage: web::Data<RedisStorage<UniversalInboxJob>>,
+    storage: web::Data<JobStorage>,
 ) -> Result<HttpResponse, UniversalInboxError> {
     let source = params.source;
-    let mut storage = storage.as_ref().clone();
 
     if let Some(authenticated) = maybe_authenticated.into_option() {
         let user_id = authenticated
@@ -194,7 +192,7 @@ pub async fn sync_tasks(
                 .await
                 .context("Failed to create new transaction while triggering tasks sync")?;
             service
-                .trigger_sync_tasks(&mut transaction, source, Some(user_id), &mut storage)
+                .trigger_sync_tasks(&mut transaction, source, Some(user_id), &storage)
                 .await?;
             transaction
                 .commit()
@@ -222,7 +220,7 @@ pub async fn sync_tasks(
             .await
             .context("Failed to create new transaction while triggering tasks sync")?;
         service
-            .trigger_sync_tasks(&mut transaction, source, None, &mut storage)
+            .trigger_sync_tasks(&mut transaction, source, None, &storage)
             .await?;
         transaction
             .commit()
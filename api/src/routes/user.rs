@@ -1,4 +1,4 @@
-use std::{num::NonZeroU32, sync::Arc};
+use std::{num::NonZeroU32, sync::Arc, time::Duration};
 
 use crate::middlewares::jwt_auth::Authenticated;
 use actix_http::body::BoxBody;
@@ -10,7 +10,6 @@ use chrono::{TimeDelta, Utc};
 use email_address::EmailAddress;
 use governor::Quota;
 use rand::RngExt;
-use redis::AsyncCommands;
 use secrecy::{ExposeSecret, SecretBox};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -21,24 +20,27 @@ use webauthn_rs::prelude::*;
 
 use universal_inbox::{
     SuccessResponse,
-    auth::auth_token::{AuthenticationToken, TruncatedAuthenticationToken},
+    auth::auth_token::{
+        ApiKeyCreation, ApiKeyPatch, AuthenticationToken, AuthenticationTokenId, RevokedSessions,
+        TruncatedAuthenticationToken,
+    },
     user::{
-        Credentials, EmailValidationToken, Password, PasswordResetToken, RegisterUserParameters,
-        User, UserAuthKind, UserAuthMethod, UserId, UserPatch, UserPreferences,
-        UserPreferencesPatch, Username,
+        Credentials, EmailValidationToken, LoginResponse, Password, PasswordResetToken,
+        RegisterUserParameters, SecondFactorCode, SecondFactorKind, User, UserAuthKind,
+        UserAuthMethod, UserId, UserPatch, UserPreferences, UserPreferencesPatch, Username,
     },
 };
 
 use crate::{
     configuration::Settings,
-    routes::auth::USER_AUTH_KIND_SESSION_KEY,
+    routes::auth::{USER_AUTH_KIND_SESSION_KEY, session_client},
     universal_inbox::{
         UniversalInboxError, UpdateStatus,
         auth_token::service::AuthenticationTokenService,
         oauth2::service::OAuth2Service,
         user::{
             model::{LocalUserAuth, UserAuth},
-            service::UserService,
+            service::{PasswordLoginOutcome, UserService},
         },
     },
     utils::{
@@ -51,13 +53,28 @@ use crate::{
 
 const PASSKEY_REGISTRATION_STATE_SESSION_KEY: &str = "passkey-registration-state";
 const PASSKEY_AUTHENTICATION_STATE_SESSION_KEY: &str = "passkey-authentication-state";
+const PENDING_SECOND_FACTOR_SESSION_KEY: &str = "pending-second-factor";
+
+/// Time given to enter the second factor after a valid password
+const SECOND_FACTOR_TIMEOUT: TimeDelta = TimeDelta::minutes(5);
+
+/// Password login waiting for its second factor, stored in the session
+#[derive(Serialize, Deserialize)]
+struct PendingSecondFactor {
+    user_id: UserId,
+    expires_at: chrono::DateTime<Utc>,
+}
 
 /// Length of the per-ceremony nonce in bytes. 16 random bytes (128 bits)
 /// is well over the WebAuthn challenge entropy (16 bytes is the spec
 /// minimum) and makes a guess by a network attacker infeasible.
 const PASSKEY_NONCE_BYTES: usize = 16;
 
-/// State blob persisted to Redis for a passkey ceremony, paired with a
+/// Lifetime of a stored passkey ceremony state, well above the WebAuthn
+/// ceremony timeout
+const PASSKEY_CEREMONY_STATE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// State blob persisted in the cache for a passkey ceremony, paired with a
 /// per-ceremony nonce.
 ///
 /// Each in-flight ceremony binds two independent values:
@@ -71,7 +88,7 @@ const PASSKEY_NONCE_BYTES: usize = 16;
 /// the nonce echo a victim's cookie session by itself is no longer
 /// sufficient to drive a finish call, closing the CSRF +
 /// cross-flow-confusion gap flagged by DeepSec. Cross-flow consumption
-/// is independently prevented by the disjoint Redis key namespaces
+/// is independently prevented by the disjoint cache key namespaces
 /// `add-passkey-registration-state::{user_id}`,
 /// `passkey-registration-state::{user_id}`, and
 /// `passkey-authentication-state::{user_id}` under which this struct is
@@ -102,7 +119,7 @@ pub struct PasskeyFinishRequest<T> {
 
 /// Verify that `provided_nonce` (echoed by the client in the finish
 /// request body) matches `expected_nonce` (the nonce embedded in the
-/// Redis state blob loaded by the caller). Constant-time comparison.
+/// cache state blob loaded by the caller). Constant-time comparison.
 ///
 /// Returns `Err(Unauthorized)` on mismatch with a generic envelope.
 fn verify_passkey_nonce(
@@ -166,6 +183,7 @@ pub fn scope(auth_rate_limiter: Arc<AuthRateLimiter>) -> Scope {
                         .route(web::patch().to(patch_user))
                         .route(web::post().to(login_user)),
                 )
+                .service(web::resource("/second-factor").route(web::post().to(login_second_factor)))
                 .service(
                     web::resource("/email-verification")
                         .route(web::post().to(send_verification_email)),
@@ -176,6 +194,26 @@ pub fn scope(auth_rate_limiter: Arc<AuthRateLimiter>) -> Scope {
                         .service(
                             web::resource("/local").route(web::post().to(add_local_auth_method)),
                         )
+                        .service(
+                            web::scope("/totp")
+                                .service(
+                                    web::resource("")
+                                        .route(web::get().to(get_totp_status))
+                                        .route(web::delete().to(disable_totp)),
+                                )
+                                .service(
+                                    web::resource("/enrollment")
+                                        .route(web::post().to(start_totp_enrollment)),
+                                )
+                                .service(
+                                    web::resource("/enrollment/confirmation")
+                                        .route(web::post().to(confirm_totp_enrollment)),
+                                )
+                                .service(
+                                    web::resource("/recovery-codes")
+                                        .route(web::post().to(regenerate_recovery_codes)),
+                                ),
+                        )
                         .service(
                             web::scope("/passkey/registration")
                                 .service(
@@ -196,6 +234,18 @@ pub fn scope(auth_rate_limiter: Arc<AuthRateLimiter>) -> Scope {
                         .route(web::get().to(list_authentication_tokens))
                         .route(web::post().to(create_authentication_token)),
                 )
+                .service(
+                    web::resource("/authentication-tokens/{authentication_token_id}")
+                        .route(web::patch().to(patch_authentication_token)),
+                )
+                .service(
+                    web::resource("/sessions")
+                        .route(web::get().to(list_sessions))
+                        .route(web::delete().to(revoke_other_sessions)),
+                )
+                .service(
+                    web::resource("/sessions/{session_id}").route(web::delete().to(revoke_session)),
+                )
                 .service(
                     // `client_id` is passed as a query parameter (not a path
                     // segment) on DELETE because CIMD clients use an https URL
@@ -433,17 +483,16 @@ pub async fn start_add_passkey_registration(
         )));
     };
     cache
-        .connection_manager
-        .clone()
-        .set::<_, _, ()>(
-            format!(
+        .set_ex(
+            &format!(
                 "{}::{}",
                 ADD_PASSKEY_REGISTRATION_STATE_SESSION_KEY, user_id
             ),
-            registration_state_to_store,
+            &registration_state_to_store,
+            PASSKEY_CEREMONY_STATE_TTL,
         )
         .await
-        .context("Failed to store add Passkey registration state in Redis")?;
+        .context("Failed to store add Passkey registration state")?;
 
     transaction
         .commit()
@@ -510,15 +559,14 @@ pub async fn finish_add_passkey_registration(
     }
 
     session.remove(ADD_PASSKEY_REGISTRATION_STATE_SESSION_KEY);
-    let str: String = cache
-        .connection_manager
-        .clone()
-        .get_del(format!(
+    let str = cache
+        .get_del(&format!(
             "{}::{}",
             ADD_PASSKEY_REGISTRATION_STATE_SESSION_KEY, user_id
         ))
         .await
-        .context("Failed to fetch add Passkey registration state from Redis")?;
+        .context("Failed to fetch add Passkey registration state")?
+        .ok_or_else(|| anyhow!("Unable to find add Passkey registration state"))?;
     let Ok(bound) = serde_json::from_str::<NonceBound<PasskeyRegistration>>(&str) else {
         return Err(UniversalInboxError::Unexpected(anyhow!(
             "Failed to parse add Passkey registration state"
@@ -588,6 +636,183 @@ pub async fn remove_auth_method(
     ))
 }
 
+pub async fn get_totp_status(
+    user_service: web::Data<Arc<UserService>>,
+    authenticated: Authenticated<Claims>,
+) -> Result<HttpResponse, UniversalInboxError> {
+    let user_id = authenticated
+        .claims
+        .sub
+        .parse::<UserId>()
+        .context("Wrong user ID format")?;
+    let service = user_service.clone();
+    let mut transaction = service
+        .begin()
+        .await
+        .context("Failed to create new transaction while fetching TOTP status")?;
+
+    let totp_status = service.get_totp_status(&mut transaction, user_id).await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/json")
+        .body(serde_json::to_string(&totp_status).context("Cannot serialize TOTP status")?))
+}
+
+pub async fn start_totp_enrollment(
+    req: HttpRequest,
+    user_service: web::Data<Arc<UserService>>,
+    settings: web::Data<Settings>,
+    authenticated: Authenticated<Claims>,
+) -> Result<HttpResponse, UniversalInboxError> {
+    if let Err(response) = check_request_origin(&req, &settings.application.front_base_url) {
+        return Ok(response);
+    }
+    let user_id = authenticated
+        .claims
+        .sub
+        .parse::<UserId>()
+        .context("Wrong user ID format")?;
+    let service = user_service.clone();
+    let mut transaction = service
+        .begin()
+        .await
+        .context("Failed to create new transaction while starting TOTP enrollment")?;
+
+    let totp_enrollment = service
+        .start_totp_enrollment(&mut transaction, user_id)
+        .await?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit while starting TOTP enrollment")?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/json")
+        .body(serde_json::to_string(&totp_enrollment).context("Cannot serialize TOTP enrollment")?))
+}
+
+pub async fn confirm_totp_enrollment(
+    req: HttpRequest,
+    user_service: web::Data<Arc<UserService>>,
+    settings: web::Data<Settings>,
+    rate_limiter: web::Data<Arc<AuthRateLimiter>>,
+    authenticated: Authenticated<Claims>,
+    second_factor_code: web::Json<SecondFactorCode>,
+) -> Result<HttpResponse, UniversalInboxError> {
+    if let Err(response) = check_request_origin(&req, &settings.application.front_base_url) {
+        return Ok(response);
+    }
+    if let Err(response) = check_ip_rate_limit(&req, &rate_limiter) {
+        return Ok(response);
+    }
+    let user_id = authenticated
+        .claims
+        .sub
+        .parse::<UserId>()
+        .context("Wrong user ID format")?;
+    let service = user_service.clone();
+    let mut transaction = service
+        .begin()
+        .await
+        .context("Failed to create new transaction while confirming TOTP enrollment")?;
+
+    let recovery_codes = service
+        .confirm_totp_enrollment(&mut transaction, user_id, &second_factor_code.code)
+        .await?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit while confirming TOTP enrollment")?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/json")
+        .body(serde_json::to_string(&recovery_codes).context("Cannot serialize recovery codes")?))
+}
+
+pub async fn regenerate_recovery_codes(
+    req: HttpRequest,
+    user_service: web::Data<Arc<UserService>>,
+    settings: web::Data<Settings>,
+    rate_limiter: web::Data<Arc<AuthRateLimiter>>,
+    authenticated: Authenticated<Claims>,
+    second_factor_code: web::Json<SecondFactorCode>,
+) -> Result<HttpResponse, UniversalInboxError> {
+    if let Err(response) = check_request_origin(&req, &settings.application.front_base_url) {
+        return Ok(response);
+    }
+    if let Err(response) = check_ip_rate_limit(&req, &rate_limiter) {
+        return Ok(response);
+    }
+    let user_id = authenticated
+        .claims
+        .sub
+        .parse::<UserId>()
+        .context("Wrong user ID format")?;
+    let service = user_service.clone();
+    let mut transaction = service
+        .begin()
+        .await
+        .context("Failed to create new transaction while regenerating recovery codes")?;
+
+    let recovery_codes = service
+        .regenerate_recovery_codes(&mut transaction, user_id, &second_factor_code.code)
+        .await?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit while regenerating recovery codes")?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/json")
+        .body(serde_json::to_string(&recovery_codes).context("Cannot serialize recovery codes")?))
+}
+
+pub async fn disable_totp(
+    req: HttpRequest,
+    user_service: web::Data<Arc<UserService>>,
+    settings: web::Data<Settings>,
+    rate_limiter: web::Data<Arc<AuthRateLimiter>>,
+    authenticated: Authenticated<Claims>,
+    second_factor_code: web::Json<SecondFactorCode>,
+) -> Result<HttpResponse, UniversalInboxError> {
+    if let Err(response) = check_request_origin(&req, &settings.application.front_base_url) {
+        return Ok(response);
+    }
+    if let Err(response) = check_ip_rate_limit(&req, &rate_limiter) {
+        return Ok(response);
+    }
+    let user_id = authenticated
+        .claims
+        .sub
+        .parse::<UserId>()
+        .context("Wrong user ID format")?;
+    let service = user_service.clone();
+    let mut transaction = service
+        .begin()
+        .await
+        .context("Failed to create new transaction while disabling TOTP")?;
+
+    service
+        .disable_totp(&mut transaction, user_id, &second_factor_code.code)
+        .await?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit while disabling TOTP")?;
+
+    Ok(HttpResponse::Ok().content_type("application/json").body(
+        serde_json::to_string(&SuccessResponse {
+            success: true,
+            message: "Two-factor authentication successfully disabled".to_string(),
+        })
+        .context("Cannot serialize response")?,
+    ))
+}
+
 pub async fn register_user(
     req: HttpRequest,
     user_service: web::Data<Arc<UserService>>,
@@ -699,14 +924,40 @@ pub async fn login_user(
     // The service applies per-account throttling on top of the per-IP limit
     // above: a generic 401 on bad credentials, or `TooManyLoginAttempts`
     // (→ 429 + Retry-After) once an account is temporarily locked.
-    let user = service
+    let user = match service
         .validate_credentials(&mut transaction, credentials.into_inner())
-        .await?;
+        .await?
+    {
+        PasswordLoginOutcome::LoggedIn(user) => user,
+        PasswordLoginOutcome::TotpRequired(user) => {
+            session
+                .insert(
+                    PENDING_SECOND_FACTOR_SESSION_KEY,
+                    PendingSecondFactor {
+                        user_id: user.id,
+                        expires_at: Utc::now() + SECOND_FACTOR_TIMEOUT,
+                    },
+                )
+                .context("Failed to insert pending second factor into the session")?;
+
+            transaction
+                .commit()
+                .await
+                .context("Failed to commit while logging in user")?;
+
+            return Ok(HttpResponse::Ok().content_type("application/json").body(
+                serde_json::to_string(&LoginResponse::SecondFactorRequired {
+                    second_factor: SecondFactorKind::Totp,
+                })
+                .context("Cannot serialize login response")?,
+            ));
+        }
+    };
 
     let auth_token_service = auth_token_service.read().await;
 
     let auth_token = auth_token_service
-        .create_auth_token(&mut transaction, true, user.id, None, false)
+        .create_session_token(&mut transaction, user.id, session_client(&req))
         .await?;
     session
         .insert(
@@ -728,6 +979,72 @@ pub async fn login_user(
         .body(serde_json::to_string(&user).context("Cannot serialize user")?))
 }
 
+/// Second step of a password login for users with TOTP enabled
+pub async fn login_second_factor(
+    req: HttpRequest,
+    user_service: web::Data<Arc<UserService>>,
+    auth_token_service: web::Data<Arc<RwLock<AuthenticationTokenService>>>,
+    settings: web::Data<Settings>,
+    rate_limiter: web::Data<Arc<AuthRateLimiter>>,
+    second_factor_code: web::Json<SecondFactorCode>,
+    session: Session,
+) -> Result<HttpResponse, UniversalInboxError> {
+    if let Err(response) = check_request_origin(&req, &settings.application.front_base_url) {
+        return Ok(response);
+    }
+    if let Err(response) = check_ip_rate_limit(&req, &rate_limiter) {
+        return Ok(response);
+    }
+    let pending_second_factor = session
+        .get::<PendingSecondFactor>(PENDING_SECOND_FACTOR_SESSION_KEY)
+        .context("Failed to read pending second factor from the session")?
+        .filter(|pending| pending.expires_at > Utc::now())
+        .ok_or_else(|| {
+            UniversalInboxError::Unauthorized(anyhow!(
+                "No pending login, please log in with your password again"
+            ))
+        })?;
+
+    let service = user_service.clone();
+    let mut transaction = service
+        .begin()
+        .await
+        .context("Failed to create new transaction while validating second factor")?;
+
+    // Failures count towards the same per-account lockout as wrong passwords
+    let user = service
+        .validate_second_factor(
+            &mut transaction,
+            pending_second_factor.user_id,
+            &second_factor_code.code,
+        )
+        .await?;
+
+    let auth_token_service = auth_token_service.read().await;
+    let auth_token = auth_token_service
+        .create_session_token(&mut transaction, user.id, session_client(&req))
+        .await?;
+    session.remove(PENDING_SECOND_FACTOR_SESSION_KEY);
+    session
+        .insert(
+            JWT_SESSION_KEY,
+            auth_token.jwt_token.expose_secret().0.clone(),
+        )
+        .context("Failed to insert JWT token into the session")?;
+    session
+        .insert(USER_AUTH_KIND_SESSION_KEY, UserAuthKind::Local)
+        .context("Failed to insert authentication type into the session")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit while validating second factor")?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/json")
+        .body(serde_json::to_string(&user).context("Cannot serialize user")?))
+}
+
 pub async fn send_verification_email(
     req: HttpRequest,
     user_service: web::Data<Arc<UserService>>,
@@ -836,6 +1153,7 @@ pub async fn send_password_reset_email(
 pub async fn reset_password(
     req: HttpRequest,
     user_service: web::Data<Arc<UserService>>,
+    authentication_token_service: web::Data<Arc<RwLock<AuthenticationTokenService>>>,
     rate_limiter: web::Data<Arc<AuthRateLimiter>>,
     path_info: web::Path<(UserId, PasswordResetToken)>,
     password: web::Json<SecretBox<Password>>,
@@ -861,6 +1179,11 @@ pub async fn reset_password(
     transaction.commit().await.context(format!(
         "Failed to commit while resetting the password of {user_id}"
     ))?;
+    authentication_token_service
+        .read()
+        .await
+        .forget_revoked_sessions(user_id)
+        .await;
 
     Ok(HttpResponse::Ok().content_type("application/json").body(
         serde_json::to_string(&SuccessResponse {
@@ -895,9 +1218,13 @@ pub async fn list_authentication_tokens(
     ))
 }
 
+/// Without a body, a full access API key valid for 6 months is created (legacy
+/// behavior). Otherwise the key is created with the given name, expiration
+/// and restrictions.
 pub async fn create_authentication_token(
     authentication_token_service: web::Data<Arc<RwLock<AuthenticationTokenService>>>,
     authenticated: Authenticated<Claims>,
+    api_key_creation: Option<web::Json<ApiKeyCreation>>,
 ) -> Result<HttpResponse, UniversalInboxError> {
     let user_id = authenticated
         .claims
@@ -909,15 +1236,24 @@ pub async fn create_authentication_token(
         .begin()
         .await
         .context("Failed to create new transaction while creating authentication token")?;
-    let result: AuthenticationToken = service
-        .create_auth_token(
-            &mut transaction,
-            false,
-            user_id,
-            Some(Utc::now() + TimeDelta::try_days(30 * 6).unwrap()),
-            true,
-        )
-        .await?;
+    let result: AuthenticationToken = match api_key_creation {
+        Some(web::Json(api_key_creation)) => {
+            service
+                .create_api_key(&mut transaction, user_id, api_key_creation)
+                .await?
+        }
+        None => {
+            service
+                .create_auth_token(
+                    &mut transaction,
+                    false,
+                    user_id,
+                    Some(Utc::now() + TimeDelta::try_days(30 * 6).unwrap()),
+                    true,
+                )
+                .await?
+        }
+    };
 
     transaction
         .commit()
@@ -929,6 +1265,49 @@ pub async fn create_authentication_token(
     ))
 }
 
+pub async fn patch_authentication_token(
+    path: web::Path<AuthenticationTokenId>,
+    patch: web::Json<ApiKeyPatch>,
+    authentication_token_service: web::Data<Arc<RwLock<AuthenticationTokenService>>>,
+    authenticated: Authenticated<Claims>,
+) -> Result<HttpResponse, UniversalInboxError> {
+    let user_id = authenticated
+        .claims
+        .sub
+        .parse::<UserId>()
+        .context("Wrong user ID format")?;
+    let authentication_token_id = path.into_inner();
+    let service = authentication_token_service.read().await;
+    let mut transaction = service.begin().await.context(format!(
+        "Failed to create new transaction while patching authentication token {authentication_token_id}"
+    ))?;
+    let result = service
+        .update_api_key(
+            &mut transaction,
+            authentication_token_id.clone(),
+            patch.into_inner(),
+            user_id,
+        )
+        .await?;
+
+    transaction.commit().await.context(format!(
+        "Failed to commit while patching authentication token {authentication_token_id}"
+    ))?;
+
+    match result {
+        Some(auth_token) => Ok(HttpResponse::Ok().content_type("application/json").body(
+            serde_json::to_string(&auth_token)
+                .context("Cannot serialize patched authentication token")?,
+        )),
+        None => Ok(HttpResponse::NotFound()
+            .content_type("application/json")
+            .body(BoxBody::new(
+                json!({ "message": format!("Cannot find authentication token {authentication_token_id}") })
+                    .to_string(),
+            ))),
+    }
+}
+
 pub async fn get_user_preferences(
     user_service: web::Data<Arc<UserService>>,
     authenticated: Authenticated<Claims>,
@@ -1034,14 +1413,13 @@ pub async fn start_passkey_registration(
         )));
     };
     cache
-        .connection_manager
-        .clone()
-        .set::<_, _, ()>(
-            format!("{}::{}", PASSKEY_REGISTRATION_STATE_SESSION_KEY, user_id),
-            registration_state_to_store,
+        .set_ex(
+            &format!("{}::{}", PASSKEY_REGISTRATION_STATE_SESSION_KEY, user_id),
+            &registration_state_to_store,
+            PASSKEY_CEREMONY_STATE_TTL,
         )
         .await
-        .context("Failed to store Passkey registration state in Redis")?;
+        .context("Failed to store Passkey registration state")?;
 
     transaction
         .commit()
@@ -1092,15 +1470,14 @@ pub async fn finish_passkey_registration(
         .context("Failed to extract Passkey registration state from the session")?
         .ok_or_else(|| anyhow!("Unable to find Passkey registration state in session"))?;
     session.remove(PASSKEY_REGISTRATION_STATE_SESSION_KEY);
-    let str: String = cache
-        .connection_manager
-        .clone()
-        .get_del(format!(
+    let str = cache
+        .get_del(&format!(
             "{}::{}",
             PASSKEY_REGISTRATION_STATE_SESSION_KEY, user_id
         ))
         .await
-        .context("Failed to fetch Passkey registration state from Redis")?;
+        .context("Failed to fetch Passkey registration state")?
+        .ok_or_else(|| anyhow!("Unable to find Passkey registration state"))?;
     let Ok(bound) = serde_json::from_str::<NonceBound<PasskeyRegistration>>(&str) else {
         return Err(UniversalInboxError::Unexpected(anyhow!(
             "Failed to parse Passkey registration state"
@@ -1121,7 +1498,7 @@ pub async fn finish_passkey_registration(
 
     let auth_token_service = auth_token_service.read().await;
     let auth_token = auth_token_service
-        .create_auth_token(&mut transaction, true, user_id, None, false)
+        .create_session_token(&mut transaction, user_id, session_client(&req))
         .await?;
     session
         .insert(
@@ -1186,14 +1563,13 @@ pub async fn start_passkey_authentication(
         )));
     };
     cache
-        .connection_manager
-        .clone()
-        .set::<_, _, ()>(
-            format!("{}::{}", PASSKEY_AUTHENTICATION_STATE_SESSION_KEY, user_id),
-            authentication_state_to_store,
+        .set_ex(
+            &format!("{}::{}", PASSKEY_AUTHENTICATION_STATE_SESSION_KEY, user_id),
+            &authentication_state_to_store,
+            PASSKEY_CEREMONY_STATE_TTL,
         )
         .await
-        .context("Failed to store Passkey authentication state in Redis")?;
+        .context("Failed to store Passkey authentication state")?;
 
     transaction
         .commit()
@@ -1241,6 +1617,129 @@ pub async fn list_oauth2_authorized_clients(
     ))
 }
 
+pub async fn list_sessions(
+    authentication_token_service: web::Data<Arc<RwLock<AuthenticationTokenService>>>,
+    authenticated: Authenticated<Claims>,
+) -> Result<HttpResponse, UniversalInboxError> {
+    let user_id = authenticated
+        .claims
+        .sub
+        .parse::<UserId>()
+        .context("Wrong user ID format")?;
+    let service = authentication_token_service.read().await;
+    let mut transaction = service
+        .begin()
+        .await
+        .context("Failed to create new transaction while listing sessions")?;
+
+    let sessions = service
+        .list_sessions(&mut transaction, user_id, &authenticated.jwt.0)
+        .await?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit while listing sessions")?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/json")
+        .body(serde_json::to_string(&sessions).context("Cannot serialize sessions list")?))
+}
+
+/// Revoke one session of the user. Revoking the current session logs out.
+pub async fn revoke_session(
+    req: HttpRequest,
+    path: web::Path<AuthenticationTokenId>,
+    authentication_token_service: web::Data<Arc<RwLock<AuthenticationTokenService>>>,
+    authenticated: Authenticated<Claims>,
+    settings: web::Data<Settings>,
+    session: Session,
+) -> Result<HttpResponse, UniversalInboxError> {
+    if let Err(response) = check_request_origin(&req, &settings.application.front_base_url) {
+        return Ok(response);
+    }
+    let user_id = authenticated
+        .claims
+        .sub
+        .parse::<UserId>()
+        .context("Wrong user ID format")?;
+    let session_id = path.into_inner();
+    let service = authentication_token_service.read().await;
+    let mut transaction = service.begin().await.context(format!(
+        "Failed to create new transaction while revoking session {session_id}"
+    ))?;
+
+    let is_current_session = service
+        .list_sessions(&mut transaction, user_id, &authenticated.jwt.0)
+        .await?
+        .iter()
+        .any(|user_session| user_session.id == session_id && user_session.is_current);
+    let revoked = service
+        .revoke_session(&mut transaction, user_id, &session_id)
+        .await?;
+
+    transaction.commit().await.context(format!(
+        "Failed to commit while revoking session {session_id}"
+    ))?;
+    service.forget_revoked_sessions(user_id).await;
+
+    if !revoked {
+        return Ok(HttpResponse::NotFound()
+            .content_type("application/json")
+            .body(BoxBody::new(
+                json!({ "message": format!("Cannot find session {session_id}") }).to_string(),
+            )));
+    }
+    if is_current_session {
+        session.purge();
+    }
+
+    Ok(HttpResponse::Ok().json(SuccessResponse {
+        success: true,
+        message: "Session revoked".to_string(),
+    }))
+}
+
+/// Sign out everywhere else: revoke all the sessions of the user but the
+/// current one
+pub async fn revoke_other_sessions(
+    req: HttpRequest,
+    authentication_token_service: web::Data<Arc<RwLock<AuthenticationTokenService>>>,
+    authenticated: Authenticated<Claims>,
+    settings: web::Data<Settings>,
+) -> Result<HttpResponse, UniversalInboxError> {
+    if let Err(response) = check_request_origin(&req, &settings.application.front_base_url) {
+        return Ok(response);
+    }
+    let user_id = authenticated
+        .claims
+        .sub
+        .parse::<UserId>()
+        .context("Wrong user ID format")?;
+    let service = authentication_token_service.read().await;
+    let mut transaction = service
+        .begin()
+        .await
+        .context("Failed to create new transaction while revoking other sessions")?;
+
+    let revoked_sessions_count = service
+        .revoke_other_sessions(&mut transaction, user_id, &authenticated.jwt.0)
+        .await?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit while revoking other sessions")?;
+    service.forget_revoked_sessions(user_id).await;
+
+    Ok(HttpResponse::Ok().content_type("application/json").body(
+        serde_json::to_string(&RevokedSessions {
+            revoked_sessions_count,
+        })
+        .context("Cannot serialize revoked sessions")?,
+    ))
+}
+
 #[derive(Debug, serde::Deserialize)]
 pub struct RevokeOAuth2ClientQuery {
     pub client_id: String,
@@ -1311,15 +1810,14 @@ pub async fn finish_passkey_authentication(
         .context("Failed to extract Passkey authentication state from the session")?
         .ok_or_else(|| anyhow!("Unable to find Passkey authentication state in session"))?;
     session.remove(PASSKEY_AUTHENTICATION_STATE_SESSION_KEY);
-    let str: String = cache
-        .connection_manager
-        .clone()
-        .get_del(format!(
+    let str = cache
+        .get_del(&format!(
             "{}::{}",
             PASSKEY_AUTHENTICATION_STATE_SESSION_KEY, user_id
         ))
         .await
-        .context("Failed to fetch Passkey authentication state in Redis")?;
+        .context("Failed to fetch Passkey authentication state")?
+        .ok_or_else(|| anyhow!("Unable to find Passkey authentication state"))?;
     let Ok(bound) = serde_json::from_str::<NonceBound<PasskeyAuthentication>>(&str) else {
         return Err(UniversalInboxError::Unexpected(anyhow!(
             "Failed to load Passkey authentication state"
@@ -1334,7 +1832,7 @@ pub async fn finish_passkey_authentication(
 
     let auth_token_service = auth_token_service.read().await;
     let auth_token = auth_token_service
-        .create_auth_token(&mut transaction, true, user_id, None, false)
+        .create_session_token(&mut transaction, user_id, session_client(&req))
         .await?;
     session
         .insert(
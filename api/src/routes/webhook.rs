@@ -2,8 +2,6 @@ use std::sync::Arc;
 
 use actix_web::{HttpRequest, HttpResponse, Scope, web};
 use anyhow::Context;
-use apalis::prelude::Storage;
-use apalis_redis::RedisStorage;
 use ring::hmac;
 use secrecy::{ExposeSecret, SecretBox};
 use serde_json::json;
@@ -28,7 +26,7 @@ use universal_inbox::{
 use crate::{
     configuration::WebhookSigningSecret,
     integrations::slack::has_slack_references_in_message,
-    jobs::{UniversalInboxJob, slack::SlackPushEventCallbackJob},
+    jobs::{UniversalInboxJob, slack::SlackPushEventCallbackJob, storage::JobStorage},
     universal_inbox::{
         UniversalInboxError, integration_connection::service::IntegrationConnectionService,
         third_party::service::ThirdPartyItemService,
@@ -53,7 +51,7 @@ pub async fn push_slack_event(
     signing_secret: web::Data<Option<SlackSigningSecret>>,
     integration_connection_service: web::Data<Arc<RwLock<IntegrationConnectionService>>>,
     third_party_item_service: web::Data<Arc<RwLock<ThirdPartyItemService>>>,
-    storage: web::Data<RedisStorage<UniversalInboxJob>>,
+    storage: web::Data<JobStorage>,
 ) -> Result<HttpResponse, UniversalInboxError> {
     let current_span = tracing::Span::current();
 
@@ -266,14 +264,13 @@ pub async fn push_slack_event(
 }
 
 async fn send_slack_push_event_callback_job(
-    storage: &RedisStorage<UniversalInboxJob>,
+    storage: &JobStorage,
     event: SlackPushEventCallback,
 ) -> Result<(), UniversalInboxError> {
-    let job = Retry::spawn(
+    let task_id = Retry::spawn(
         ExponentialBackoff::from_millis(10).map(jitter).take(10),
         || async {
             storage
-                .clone()
                 .push(UniversalInboxJob::SlackPushEventCallback(
                     SlackPushEventCallbackJob(event.clone()),
                 ))
@@ -283,8 +280,8 @@ async fn send_slack_push_event_callback_job(
     .await
     .context("Failed to push Slack event to queue")?;
     debug!(
-        "Pushed a Slack event {} to the queue with job ID {}",
-        event.event_id, job.task_id
+        "Pushed a Slack event {} to the queue with job ID {task_id}",
+        event.event_id
     );
     Ok(())
 }
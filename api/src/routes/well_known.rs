@@ -1,6 +1,8 @@
 use actix_web::{HttpResponse, web};
 use serde_json::json;
 
+use universal_inbox::auth::oauth2::OAuth2Scope;
+
 use crate::configuration::Settings;
 
 /// RFC 9728: OAuth 2.0 Protected Resource Metadata
@@ -18,7 +20,7 @@ pub async fn protected_resource_metadata(settings: web::Data<Settings>) -> HttpR
         "resource": resource,
         "authorization_servers": [base_url],
         "bearer_methods_supported": ["header"],
-        "scopes_supported": ["read", "write"],
+        "scopes_supported": OAuth2Scope::requestable_by_oauth2_clients(),
         "resource_documentation": "https://doc.universal-inbox.com"
     }))
 }
@@ -38,11 +40,22 @@ pub async fn authorization_server_metadata(settings: web::Data<Settings>) -> Htt
         "authorization_endpoint": format!("{base_url}{api_path}oauth2/authorize"),
         "token_endpoint": format!("{base_url}{api_path}oauth2/token"),
         "registration_endpoint": format!("{base_url}{api_path}oauth2/register"),
+        "device_authorization_endpoint": format!("{base_url}{api_path}oauth2/device_authorization"),
+        "revocation_endpoint": format!("{base_url}{api_path}oauth2/revoke"),
+        "introspection_endpoint": format!("{base_url}{api_path}oauth2/introspect"),
         "response_types_supported": ["code"],
-        "grant_types_supported": ["authorization_code", "refresh_token"],
+        "grant_types_supported": [
+            "authorization_code",
+            "refresh_token",
+            "urn:ietf:params:oauth:grant-type:device_code"
+        ],
         "code_challenge_methods_supported": ["S256"],
         "token_endpoint_auth_methods_supported": ["none"],
-        "scopes_supported": ["read", "write"],
+        "revocation_endpoint_auth_methods_supported": ["none"],
+        // RFC 7662 §2.1: resource servers authenticate with an API key
+        // granted the `tokens:introspect` scope, as a bearer token
+        "introspection_endpoint_auth_methods_supported": ["bearer"],
+        "scopes_supported": OAuth2Scope::requestable_by_oauth2_clients(),
         "resource_indicators_supported": true,
         // draft-ietf-oauth-client-id-metadata-document / MCP 2025-11-25
         // §"Discovery". Signals that this AS accepts `client_id` values of
@@ -1,32 +1,84 @@
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use anyhow::Context;
 use chrono::{DateTime, TimeDelta, Utc};
+use futures::StreamExt;
 use jsonwebtoken::{EncodingKey, Header};
-use secrecy::SecretBox;
+use secrecy::{ExposeSecret, SecretBox};
 use sqlx::{Postgres, Transaction};
+use tracing::{error, warn};
 use uuid::Uuid;
 
 use universal_inbox::{
-    auth::auth_token::{AuthenticationToken, JWTToken, TruncatedAuthenticationToken},
+    auth::{
+        auth_token::{
+            ApiKeyCreation, ApiKeyPatch, ApiKeyProperties, AuthenticationToken,
+            AuthenticationTokenId, JWTToken, TruncatedAuthenticationToken, UserSession,
+        },
+        oauth2::OAuth2Scope,
+    },
+    notification::NotificationSourceKind,
     user::UserId,
 };
 
 use crate::{
     configuration::HttpSessionSettings,
-    repository::{Repository, auth_token::AuthenticationTokenRepository},
+    repository::{
+        Repository,
+        auth_token::{AuthenticationTokenRepository, SessionTokenFilter},
+    },
     universal_inbox::UniversalInboxError,
-    utils::jwt::{Claims, JWT_SIGNING_ALGO, JWTBase64EncodedSigningKeys, JWTSigningKeys},
+    utils::{
+        cache::Cache,
+        jwt::{Claims, JWT_SIGNING_ALGO, JWTBase64EncodedSigningKeys, JWTSigningKeys},
+    },
 };
 
+const NEVER_EXPIRING_API_KEY_VALIDITY_IN_DAYS: i64 = 365 * 100;
+const API_KEY_LAST_USED_REFRESH_INTERVAL_SECS: i64 = 60;
+const SESSION_USER_AGENT_MAX_LENGTH: usize = 512;
+const SESSION_TOKEN_CHECK_INTERVAL_SECS: i64 = 30;
+const SESSION_REVOCATIONS_CHANNEL: &str = "universal-inbox:session-revocations";
+const SESSION_REVOCATIONS_LISTENER_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Client opening a session, displayed in the list of the user's sessions
+#[derive(Debug, Clone, Default)]
+pub struct SessionClient {
+    pub ip_address: Option<IpAddr>,
+    pub user_agent: Option<String>,
+}
+
+/// Last check of a session token
+struct CheckedSessionToken {
+    user_id: UserId,
+    checked_at: DateTime<Utc>,
+    client_ip: Option<IpAddr>,
+}
+
+/// Session tokens checked by this instance during the last
+/// `SESSION_TOKEN_CHECK_INTERVAL_SECS`, indexed by JWT token
+type CheckedSessionTokens = Arc<Mutex<HashMap<String, CheckedSessionToken>>>;
+
 pub struct AuthenticationTokenService {
     repository: Arc<Repository>,
     http_session_settings: HttpSessionSettings,
     jwt_encoding_key: EncodingKey,
+    /// Broadcasts session revocations to every instance of the API
+    cache: Option<Cache>,
+    checked_session_tokens: CheckedSessionTokens,
 }
 
 impl AuthenticationTokenService {
-    pub fn new(repository: Arc<Repository>, http_session_settings: HttpSessionSettings) -> Self {
+    pub fn new(
+        repository: Arc<Repository>,
+        http_session_settings: HttpSessionSettings,
+        cache: Option<Cache>,
+    ) -> Self {
         let jwt_signing_keys =
             JWTSigningKeys::load_from_base64_encoded_keys(JWTBase64EncodedSigningKeys {
                 secret_key: http_session_settings.jwt_secret_key.clone(),
@@ -37,6 +89,8 @@ impl AuthenticationTokenService {
             repository,
             http_session_settings,
             jwt_encoding_key: jwt_signing_keys.encoding_key.clone(),
+            cache,
+            checked_session_tokens: Default::default(),
         }
     }
 
@@ -68,6 +122,54 @@ impl AuthenticationTokenService {
                         )
                     })
         });
+        let jwt_token = self.encode_jwt_token(user_id, expire_at)?;
+        let auth_token =
+            AuthenticationToken::new(user_id, jwt_token, Some(expire_at), is_session_token);
+        if store {
+            self.repository
+                .create_auth_token(executor, auth_token)
+                .await
+        } else {
+            Ok(auth_token)
+        }
+    }
+
+    /// Create and store a session token, so that the session can be listed
+    /// and revoked by the user
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(user.id = user_id.to_string()),
+        err
+    )]
+    pub async fn create_session_token(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        client: SessionClient,
+    ) -> Result<AuthenticationToken, UniversalInboxError> {
+        let mut auth_token = self
+            .create_auth_token(executor, true, user_id, None, false)
+            .await?;
+        auth_token.api_key_properties.last_used_at = Some(auth_token.created_at);
+        auth_token.api_key_properties.last_used_ip = client.ip_address.map(|ip| ip.to_string());
+        auth_token.user_agent = client.user_agent.map(|user_agent| {
+            user_agent
+                .chars()
+                .take(SESSION_USER_AGENT_MAX_LENGTH)
+                .collect()
+        });
+
+        self.repository
+            .create_auth_token(executor, auth_token)
+            .await
+    }
+
+    fn encode_jwt_token(
+        &self,
+        user_id: UserId,
+        expire_at: DateTime<Utc>,
+    ) -> Result<SecretBox<JWTToken>, UniversalInboxError> {
         let claims = Claims {
             iat: Utc::now().timestamp() as usize,
             exp: expire_at.timestamp() as usize,
@@ -76,25 +178,315 @@ impl AuthenticationTokenService {
             aud: None,
             scope: None,
             client_id: None,
+            notification_source_kinds: None,
         };
 
-        let jwt_token = SecretBox::new(Box::new(JWTToken(
+        Ok(SecretBox::new(Box::new(JWTToken(
             jsonwebtoken::encode(
                 &Header::new(JWT_SIGNING_ALGO),
                 &claims,
                 &self.jwt_encoding_key,
             )
             .context("Failed to encode JSON web token")?,
-        )));
-        let auth_token =
-            AuthenticationToken::new(user_id, jwt_token, Some(expire_at), is_session_token);
-        if store {
-            self.repository
-                .create_auth_token(executor, auth_token)
+        ))))
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(user.id = user_id.to_string()),
+        err
+    )]
+    pub async fn create_api_key(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        creation: ApiKeyCreation,
+    ) -> Result<AuthenticationToken, UniversalInboxError> {
+        if let Some(expire_at) = creation.expire_at
+            && expire_at <= Utc::now()
+        {
+            return Err(UniversalInboxError::InvalidInputData {
+                source: None,
+                user_error: "API key expiration date must be in the future".to_string(),
+            });
+        }
+        validate_api_key_restrictions(
+            creation.scopes.as_ref(),
+            creation.notification_source_kinds.as_ref(),
+            creation.rate_limit_per_minute,
+        )?;
+
+        // Scopes and restrictions are stored alongside the key (rather than in
+        // its claims) so that they can be edited after the key was handed out.
+        // The JWT itself must expire, so keys that never expire get a
+        // far-future `exp` while `expire_at` stays empty.
+        let jwt_expire_at = creation.expire_at.unwrap_or_else(|| {
+            Utc::now()
+                + TimeDelta::try_days(NEVER_EXPIRING_API_KEY_VALIDITY_IN_DAYS).unwrap_or_else(
+                    || {
+                        panic!(
+                            "Invalid NEVER_EXPIRING_API_KEY_VALIDITY_IN_DAYS value: {NEVER_EXPIRING_API_KEY_VALIDITY_IN_DAYS}"
+                        )
+                    },
+                )
+        });
+        let jwt_token = self.encode_jwt_token(user_id, jwt_expire_at)?;
+        let mut auth_token =
+            AuthenticationToken::new(user_id, jwt_token, creation.expire_at, false);
+        auth_token.api_key_properties = ApiKeyProperties {
+            name: normalize_api_key_name(creation.name),
+            scopes: creation.scopes,
+            notification_source_kinds: creation.notification_source_kinds,
+            rate_limit_per_minute: creation.rate_limit_per_minute,
+            last_used_at: None,
+            last_used_ip: None,
+        };
+
+        self.repository
+            .create_auth_token(executor, auth_token)
+            .await
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(auth_token_id = auth_token_id.to_string(), user.id = for_user_id.to_string()),
+        err
+    )]
+    pub async fn update_api_key(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        auth_token_id: AuthenticationTokenId,
+        patch: ApiKeyPatch,
+        for_user_id: UserId,
+    ) -> Result<Option<TruncatedAuthenticationToken>, UniversalInboxError> {
+        validate_api_key_restrictions(
+            patch.scopes.as_ref().and_then(Option::as_ref),
+            patch
+                .notification_source_kinds
+                .as_ref()
+                .and_then(Option::as_ref),
+            patch.rate_limit_per_minute.flatten(),
+        )?;
+        if patch.is_revoked == Some(false) {
+            return Err(UniversalInboxError::InvalidInputData {
+                source: None,
+                user_error: "A revoked API key cannot be restored".to_string(),
+            });
+        }
+
+        let patch = ApiKeyPatch {
+            name: patch.name.map(normalize_api_key_name),
+            ..patch
+        };
+        Ok(self
+            .repository
+            .update_api_key(executor, auth_token_id, &patch, for_user_id)
+            .await?
+            .map(TruncatedAuthenticationToken::new))
+    }
+
+    /// Looks up the stored token matching `jwt_token` and records its usage if
+    /// it is a valid API key. Returns `None` for tokens unknown to the database.
+    #[tracing::instrument(level = "debug", skip_all, err)]
+    pub async fn authenticate_api_key(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        jwt_token: &str,
+        client_ip: Option<IpAddr>,
+    ) -> Result<Option<AuthenticationToken>, UniversalInboxError> {
+        let Some(mut auth_token) = self
+            .repository
+            .get_auth_token_by_jwt_token(executor, jwt_token)
+            .await?
+        else {
+            return Ok(None);
+        };
+        if auth_token.is_session_token || auth_token.is_revoked || auth_token.is_expired() {
+            return Ok(Some(auth_token));
+        }
+
+        self.record_usage(executor, &mut auth_token, client_ip)
+            .await?;
+
+        Ok(Some(auth_token))
+    }
+
+    /// Looks up the stored session token matching `jwt_token` and records the
+    /// last time the session was seen. Returns `None` for tokens that are not
+    /// session tokens (API keys).
+    ///
+    /// Sessions opened before session tokens were stored are registered when
+    /// first seen, so that they can be listed and revoked like the others.
+    /// They are registered as revoked from `unregistered_sessions_rejected_from`,
+    /// as a session never seen since could otherwise not be revoked.
+    #[tracing::instrument(level = "debug", skip_all, err)]
+    pub async fn authenticate_session_token(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        jwt_token: &str,
+        claims: &Claims,
+        client_ip: Option<IpAddr>,
+    ) -> Result<Option<AuthenticationToken>, UniversalInboxError> {
+        let mut auth_token = match self
+            .repository
+            .get_auth_token_by_jwt_token(executor, jwt_token)
+            .await?
+        {
+            Some(auth_token) if !auth_token.is_session_token => return Ok(None),
+            Some(auth_token) => auth_token,
+            None => {
+                self.register_session_token(executor, jwt_token, claims, client_ip)
+                    .await?
+            }
+        };
+        if auth_token.is_revoked || auth_token.is_expired() {
+            return Ok(Some(auth_token));
+        }
+
+        self.record_usage(executor, &mut auth_token, client_ip)
+            .await?;
+        self.remember_checked_session_token(jwt_token, auth_token.user_id, client_ip);
+
+        Ok(Some(auth_token))
+    }
+
+    async fn register_session_token(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        jwt_token: &str,
+        claims: &Claims,
+        client_ip: Option<IpAddr>,
+    ) -> Result<AuthenticationToken, UniversalInboxError> {
+        let user_id = claims
+            .sub
+            .parse::<UserId>()
+            .context("Wrong user ID format")?;
+        let expire_at = DateTime::from_timestamp(claims.exp as i64, 0)
+            .context("Invalid session token expiration time")?;
+        let mut auth_token = AuthenticationToken::new(
+            user_id,
+            SecretBox::new(Box::new(JWTToken(jwt_token.to_string()))),
+            Some(expire_at),
+            true,
+        );
+        auth_token.is_revoked = self
+            .http_session_settings
+            .unregistered_sessions_rejected_from
+            .is_some_and(|rejected_from| Utc::now() >= rejected_from);
+        auth_token.api_key_properties.last_used_at = Some(auth_token.created_at);
+        auth_token.api_key_properties.last_used_ip = client_ip.map(|ip| ip.to_string());
+
+        self.repository
+            .get_or_create_session_token(executor, auth_token)
+            .await
+    }
+
+    /// Whether the session token was successfully checked from the same
+    /// client IP address during the last `SESSION_TOKEN_CHECK_INTERVAL_SECS`,
+    /// in which case it is not looked up again. Revoked sessions are forgotten
+    /// by every instance of the API (see [`Self::forget_revoked_sessions`]).
+    pub fn is_session_token_recently_checked(
+        &self,
+        jwt_token: &str,
+        client_ip: Option<IpAddr>,
+    ) -> bool {
+        let checked_session_tokens = self
+            .checked_session_tokens
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        checked_session_tokens
+            .get(jwt_token)
+            .is_some_and(|checked_session_token| {
+                checked_session_token.client_ip == client_ip
+                    && Utc::now() - checked_session_token.checked_at
+                        < TimeDelta::seconds(SESSION_TOKEN_CHECK_INTERVAL_SECS)
+            })
+    }
+
+    fn remember_checked_session_token(
+        &self,
+        jwt_token: &str,
+        user_id: UserId,
+        client_ip: Option<IpAddr>,
+    ) {
+        let now = Utc::now();
+        let mut checked_session_tokens = self
+            .checked_session_tokens
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        checked_session_tokens.retain(|_, checked_session_token| {
+            now - checked_session_token.checked_at
+                < TimeDelta::seconds(SESSION_TOKEN_CHECK_INTERVAL_SECS)
+        });
+        checked_session_tokens.insert(
+            jwt_token.to_string(),
+            CheckedSessionToken {
+                user_id,
+                checked_at: now,
+                client_ip,
+            },
+        );
+    }
+
+    /// Revoked sessions must be looked up again to be rejected right away: the
+    /// session tokens of the user checked by any instance of the API are
+    /// forgotten. Must be called once the revocation is committed, otherwise
+    /// another instance could check the session again before it is revoked.
+    /// Without a cache, only the tokens checked by this instance are forgotten.
+    pub async fn forget_revoked_sessions(&self, user_id: UserId) {
+        forget_checked_session_tokens(&self.checked_session_tokens, user_id);
+        if let Some(cache) = &self.cache
+            && let Err(err) = cache
+                .publish(SESSION_REVOCATIONS_CHANNEL, &user_id.to_string())
                 .await
-        } else {
-            Ok(auth_token)
+        {
+            error!("Failed to publish the revocation of the sessions of user {user_id}: {err:?}");
+        }
+    }
+
+    /// Listen to the session revocations published by every instance of the
+    /// API, forgetting the session tokens this instance checked for the users.
+    /// Reconnects until the process exits.
+    pub fn listen_to_session_revocations(&self) -> Option<impl Future<Output = ()> + use<>> {
+        let cache = self.cache.clone()?;
+        let checked_session_tokens = self.checked_session_tokens.clone();
+        Some(async move {
+            loop {
+                if let Err(err) =
+                    listen_to_session_revocations_once(&cache, &checked_session_tokens).await
+                {
+                    error!("Session revocations listener failed, retrying: {err:?}");
+                }
+                tokio::time::sleep(SESSION_REVOCATIONS_LISTENER_RETRY_DELAY).await;
+            }
+        })
+    }
+
+    /// Only refresh the last usage once in a while to avoid a write on every
+    /// request.
+    async fn record_usage(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        auth_token: &mut AuthenticationToken,
+        client_ip: Option<IpAddr>,
+    ) -> Result<(), UniversalInboxError> {
+        let now = Utc::now();
+        let properties = &mut auth_token.api_key_properties;
+        let client_ip = client_ip.map(|ip| ip.to_string());
+        let is_stale = properties.last_used_at.is_none_or(|last_used_at| {
+            now - last_used_at > TimeDelta::seconds(API_KEY_LAST_USED_REFRESH_INTERVAL_SECS)
+        });
+        if is_stale || properties.last_used_ip != client_ip {
+            self.repository
+                .touch_auth_token(executor, auth_token.id.clone(), now, client_ip.clone())
+                .await?;
+            properties.last_used_at = Some(now);
+            properties.last_used_ip = client_ip;
         }
+
+        Ok(())
     }
 
     #[tracing::instrument(
@@ -117,4 +509,178 @@ impl AuthenticationTokenService {
             .map(TruncatedAuthenticationToken::new)
             .collect())
     }
+
+    /// List the active sessions of the user, most recently seen first.
+    /// `current_jwt_token` identifies the session of the caller.
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(user.id = user_id.to_string()),
+        err
+    )]
+    pub async fn list_sessions(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        current_jwt_token: &str,
+    ) -> Result<Vec<UserSession>, UniversalInboxError> {
+        let mut sessions: Vec<UserSession> = self
+            .repository
+            .fetch_auth_tokens_for_user(executor, user_id, false)
+            .await?
+            .into_iter()
+            .filter(|auth_token| {
+                auth_token.is_session_token && !auth_token.is_revoked && !auth_token.is_expired()
+            })
+            .map(|auth_token| {
+                let is_current = auth_token.jwt_token.expose_secret().0 == current_jwt_token;
+                UserSession::new(auth_token, is_current)
+            })
+            .collect();
+        sessions.sort_by(|a, b| {
+            b.last_seen_at
+                .unwrap_or(b.created_at)
+                .cmp(&a.last_seen_at.unwrap_or(a.created_at))
+        });
+
+        Ok(sessions)
+    }
+
+    /// Revoke one session of the user. Returns `false` when the session does
+    /// not exist or is already revoked.
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(session_id = session_id.to_string(), user.id = user_id.to_string()),
+        err
+    )]
+    pub async fn revoke_session(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        session_id: &AuthenticationTokenId,
+    ) -> Result<bool, UniversalInboxError> {
+        let revoked_count = self
+            .repository
+            .revoke_session_tokens(executor, user_id, SessionTokenFilter::Id(session_id))
+            .await?;
+        Ok(revoked_count > 0)
+    }
+
+    /// Revoke the session of `jwt_token`, when closing it
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(user.id = user_id.to_string()),
+        err
+    )]
+    pub async fn revoke_current_session(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        jwt_token: &str,
+    ) -> Result<(), UniversalInboxError> {
+        self.repository
+            .revoke_session_tokens(executor, user_id, SessionTokenFilter::JwtToken(jwt_token))
+            .await?;
+        Ok(())
+    }
+
+    /// Sign out everywhere else: revoke all the sessions of the user but the
+    /// one of `current_jwt_token`
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(user.id = user_id.to_string()),
+        err
+    )]
+    pub async fn revoke_other_sessions(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        current_jwt_token: &str,
+    ) -> Result<u64, UniversalInboxError> {
+        let revoked_count = self
+            .repository
+            .revoke_session_tokens(
+                executor,
+                user_id,
+                SessionTokenFilter::AllExcept(current_jwt_token),
+            )
+            .await?;
+        Ok(revoked_count)
+    }
+
+    /// Sign out everywhere, after a password reset
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(user.id = user_id.to_string()),
+        err
+    )]
+    pub async fn revoke_all_sessions(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+    ) -> Result<u64, UniversalInboxError> {
+        let revoked_count = self
+            .repository
+            .revoke_session_tokens(executor, user_id, SessionTokenFilter::All)
+            .await?;
+        Ok(revoked_count)
+    }
+}
+
+fn forget_checked_session_tokens(checked_session_tokens: &CheckedSessionTokens, user_id: UserId) {
+    checked_session_tokens
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .retain(|_, checked_session_token| checked_session_token.user_id != user_id);
+}
+
+async fn listen_to_session_revocations_once(
+    cache: &Cache,
+    checked_session_tokens: &CheckedSessionTokens,
+) -> Result<(), UniversalInboxError> {
+    let mut payloads = cache.subscribe(SESSION_REVOCATIONS_CHANNEL).await?;
+    while let Some(payload) = payloads.next().await {
+        let payload = payload?;
+        match payload.parse::<UserId>() {
+            Ok(user_id) => forget_checked_session_tokens(checked_session_tokens, user_id),
+            Err(err) => warn!("Ignoring invalid session revocation `{payload}`: {err:?}"),
+        }
+    }
+    Ok(())
+}
+
+fn normalize_api_key_name(name: Option<String>) -> Option<String> {
+    name.map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+fn validate_api_key_restrictions(
+    scopes: Option<&Vec<OAuth2Scope>>,
+    notification_source_kinds: Option<&Vec<NotificationSourceKind>>,
+    rate_limit_per_minute: Option<u32>,
+) -> Result<(), UniversalInboxError> {
+    if scopes.is_some_and(|scopes| scopes.is_empty()) {
+        return Err(UniversalInboxError::InvalidInputData {
+            source: None,
+            user_error: "An API key must be granted at least one scope".to_string(),
+        });
+    }
+    if notification_source_kinds.is_some_and(|kinds| kinds.is_empty()) {
+        return Err(UniversalInboxError::InvalidInputData {
+            source: None,
+            user_error: "An API key must be granted access to at least one notification source"
+                .to_string(),
+        });
+    }
+    if rate_limit_per_minute == Some(0) {
+        return Err(UniversalInboxError::InvalidInputData {
+            source: None,
+            user_error: "API key rate limit must be greater than 0".to_string(),
+        });
+    }
+    Ok(())
 }
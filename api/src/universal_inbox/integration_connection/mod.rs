@@ -1 +1,3 @@
+pub mod provider_health;
 pub mod service;
+pub mod sync_run;
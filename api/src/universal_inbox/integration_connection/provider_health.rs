@@ -0,0 +1,95 @@
+//! Health of each integration provider, derived from the recent sync runs of
+//! all its integration connections. It is reported by the readiness probe but
+//! does not affect it: an upstream outage must not take our own service down.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use universal_inbox::integration_connection::provider::IntegrationProviderKind;
+
+/// Below this failure rate of its recent sync runs, a provider is healthy
+const DEGRADED_FAILURE_RATE: f64 = 0.2;
+/// From this failure rate of its recent sync runs, a provider is unhealthy
+const UNHEALTHY_FAILURE_RATE: f64 = 0.8;
+
+/// Sync runs of all the integration connections of a provider since a given time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntegrationProviderSyncStats {
+    pub provider_kind: IntegrationProviderKind,
+    pub sync_runs_count: u64,
+    pub failed_sync_runs_count: u64,
+    pub last_successful_sync_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrationProviderHealthStatus {
+    Healthy,
+    Degraded,
+    Unhealthy,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IntegrationProviderHealth {
+    pub provider_kind: IntegrationProviderKind,
+    pub status: IntegrationProviderHealthStatus,
+    pub sync_runs_count: u64,
+    pub failed_sync_runs_count: u64,
+    pub failure_rate: f64,
+    pub last_successful_sync_at: Option<DateTime<Utc>>,
+}
+
+impl From<IntegrationProviderSyncStats> for IntegrationProviderHealth {
+    fn from(stats: IntegrationProviderSyncStats) -> Self {
+        let failure_rate = if stats.sync_runs_count == 0 {
+            0.0
+        } else {
+            stats.failed_sync_runs_count as f64 / stats.sync_runs_count as f64
+        };
+        let status = if failure_rate >= UNHEALTHY_FAILURE_RATE {
+            IntegrationProviderHealthStatus::Unhealthy
+        } else if failure_rate >= DEGRADED_FAILURE_RATE {
+            IntegrationProviderHealthStatus::Degraded
+        } else {
+            IntegrationProviderHealthStatus::Healthy
+        };
+
+        IntegrationProviderHealth {
+            provider_kind: stats.provider_kind,
+            status,
+            sync_runs_count: stats.sync_runs_count,
+            failed_sync_runs_count: stats.failed_sync_runs_count,
+            failure_rate,
+            last_successful_sync_at: stats.last_successful_sync_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use rstest::*;
+
+    #[rstest]
+    #[case::no_sync_run(0, 0, IntegrationProviderHealthStatus::Healthy)]
+    #[case::few_failures(10, 1, IntegrationProviderHealthStatus::Healthy)]
+    #[case::some_failures(10, 5, IntegrationProviderHealthStatus::Degraded)]
+    #[case::mostly_failures(10, 9, IntegrationProviderHealthStatus::Unhealthy)]
+    fn test_integration_provider_health_status(
+        #[case] sync_runs_count: u64,
+        #[case] failed_sync_runs_count: u64,
+        #[case] expected_status: IntegrationProviderHealthStatus,
+    ) {
+        let health: IntegrationProviderHealth = IntegrationProviderSyncStats {
+            provider_kind: IntegrationProviderKind::Github,
+            sync_runs_count,
+            failed_sync_runs_count,
+            last_successful_sync_at: None,
+        }
+        .into();
+
+        assert_eq!(health.status, expected_status);
+    }
+}
@@ -2,12 +2,9 @@ use core::fmt;
 use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use anyhow::{Context, anyhow};
-use apalis::prelude::*;
-use apalis_redis::RedisStorage;
 use cached::proc_macro::io_cached;
-use chrono::{TimeDelta, Utc};
+use chrono::{DateTime, TimeDelta, Utc};
 use oauth2::{CsrfToken, PkceCodeChallenge};
-use redis::AsyncCommands;
 use secrecy::{ExposeSecret, SecretBox};
 use serde::{Deserialize, Serialize};
 use sqlx::{Postgres, Transaction};
@@ -23,6 +20,8 @@ use universal_inbox::{
         IntegrationConnection, IntegrationConnectionId, IntegrationConnectionStatus,
         config::IntegrationConnectionConfig,
         provider::{IntegrationConnectionContext, IntegrationProviderKind},
+        rate_limit::IntegrationConnectionRateLimit,
+        sync_run::IntegrationConnectionSyncRun,
     },
     notification::NotificationSyncSourceKind,
     task::TaskSyncSourceKind,
@@ -30,14 +29,19 @@ use universal_inbox::{
 };
 
 use crate::{
-    integrations::oauth2::{
-        AccessToken, AuthorizationCode, PkceVerifier, RefreshToken,
-        provider::{OAuth2FlowService, OAuth2Provider},
+    integrations::{
+        oauth2::{
+            AccessToken, AuthorizationCode, PkceVerifier, RefreshToken,
+            provider::{OAuth2FlowService, OAuth2Provider},
+        },
+        rate_limit::UpstreamRateLimit,
     },
     jobs::{
         UniversalInboxJob,
+        storage::JobStorage,
         sync::{SyncNotificationsJob, SyncTasksJob},
     },
+    metrics,
     repository::{
         Repository,
         integration_connection::{
@@ -46,12 +50,19 @@ use crate::{
             OAUTH_MISSING_REFRESH_TOKEN_ERROR_MESSAGE,
         },
         notification::NotificationRepository,
-        oauth_credential::OAuthCredentialRepository,
+        oauth_credential::{OAuthCredentialCiphertexts, OAuthCredentialRepository},
+        user::UserRepository,
+    },
+    universal_inbox::{
+        UniversalInboxError, UpdateStatus,
+        integration_connection::provider_health::IntegrationProviderHealth,
     },
-    universal_inbox::{UniversalInboxError, UpdateStatus},
     utils::{
-        cache::{Cache, build_redis_cache},
-        crypto::{TokenEncryptionKey, decrypt_token, encrypt_token},
+        cache::{Cache, IOCache, build_io_cache},
+        crypto::{
+            SecretRotationBatch, TokenEncryptionKeyRing, decrypt_token, encrypt_token,
+            reencrypt_token,
+        },
     },
 };
 
@@ -70,15 +81,16 @@ pub struct IntegrationConnectionService {
     required_oauth_scopes: HashMap<IntegrationProviderKind, Vec<String>>,
     oauth2_providers: HashMap<IntegrationProviderKind, Arc<dyn OAuth2Provider>>,
     oauth2_flow_service: OAuth2FlowService,
-    token_encryption_key: SecretBox<TokenEncryptionKey>,
+    token_encryption_keys: SecretBox<TokenEncryptionKeyRing>,
     min_sync_notifications_interval_in_minutes: i64,
     min_sync_tasks_interval_in_minutes: i64,
     sync_backoff_base_delay_in_seconds: u64,
     sync_backoff_max_delay_in_seconds: u64,
     sync_failure_window_in_hours: i64,
+    sync_run_history_size: i64,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum IntegrationConnectionSyncType {
     Notifications,
     Tasks,
@@ -100,24 +112,26 @@ impl IntegrationConnectionService {
         required_oauth_scopes: HashMap<IntegrationProviderKind, Vec<String>>,
         oauth2_providers: HashMap<IntegrationProviderKind, Arc<dyn OAuth2Provider>>,
         oauth2_flow_service: OAuth2FlowService,
-        token_encryption_key: SecretBox<TokenEncryptionKey>,
+        token_encryption_keys: SecretBox<TokenEncryptionKeyRing>,
         min_sync_notifications_interval_in_minutes: i64,
         min_sync_tasks_interval_in_minutes: i64,
         sync_backoff_base_delay_in_seconds: u64,
         sync_backoff_max_delay_in_seconds: u64,
         sync_failure_window_in_hours: i64,
+        sync_run_history_size: i64,
     ) -> IntegrationConnectionService {
         IntegrationConnectionService {
             repository,
             required_oauth_scopes,
             oauth2_providers,
             oauth2_flow_service,
-            token_encryption_key,
+            token_encryption_keys,
             min_sync_notifications_interval_in_minutes,
             min_sync_tasks_interval_in_minutes,
             sync_backoff_base_delay_in_seconds,
             sync_backoff_max_delay_in_seconds,
             sync_failure_window_in_hours,
+            sync_run_history_size,
         }
     }
 
@@ -136,6 +150,87 @@ impl IntegrationConnectionService {
         self.repository.pool.clone()
     }
 
+    /// Check the token encryption key can encrypt and decrypt OAuth tokens
+    pub fn check_token_encryption_key(&self) -> Result<(), UniversalInboxError> {
+        let key = self.token_encryption_keys.expose_secret();
+        let ciphertext = encrypt_token("readiness-probe", b"readiness-probe", key)?;
+        decrypt_token(&ciphertext, b"readiness-probe", key)?;
+        Ok(())
+    }
+
+    /// Re-encrypt the tokens of a batch of `batch_size` OAuth credentials,
+    /// starting after `after`, with the primary token encryption key.
+    /// Credentials are only checked when `dry_run` is set.
+    #[tracing::instrument(level = "debug", skip(self, executor), err)]
+    pub async fn rotate_oauth_credentials_encryption_key(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        after: Option<IntegrationConnectionId>,
+        batch_size: i64,
+        dry_run: bool,
+    ) -> Result<SecretRotationBatch<IntegrationConnectionId>, UniversalInboxError> {
+        let keys = self.token_encryption_keys.expose_secret();
+        let credentials = self
+            .repository
+            .fetch_oauth_credential_ciphertexts(executor, after, batch_size)
+            .await?;
+        let mut batch = SecretRotationBatch {
+            last_id: credentials
+                .last()
+                .map(|credential| credential.integration_connection_id),
+            scanned_count: credentials.len(),
+            rotated_count: 0,
+            failed_count: 0,
+        };
+
+        for credential in credentials {
+            if !keys.needs_rotation(&credential.encrypted_access_token)
+                && credential.encrypted_refresh_token.as_ref().is_none_or(
+                    |encrypted_refresh_token| !keys.needs_rotation(encrypted_refresh_token),
+                )
+            {
+                continue;
+            }
+
+            let integration_connection_id = credential.integration_connection_id;
+            let aad_context = integration_connection_id.0.as_bytes();
+            let rotated_credential =
+                reencrypt_token(&credential.encrypted_access_token, aad_context, keys).and_then(
+                    |encrypted_access_token| {
+                        Ok(OAuthCredentialCiphertexts {
+                            integration_connection_id,
+                            encrypted_access_token,
+                            encrypted_refresh_token: credential
+                                .encrypted_refresh_token
+                                .as_ref()
+                                .map(|encrypted_refresh_token| {
+                                    reencrypt_token(encrypted_refresh_token, aad_context, keys)
+                                })
+                                .transpose()?,
+                        })
+                    },
+                );
+            match rotated_credential {
+                Ok(rotated_credential) => {
+                    if !dry_run {
+                        self.repository
+                            .update_oauth_credential_ciphertexts(executor, &rotated_credential)
+                            .await?;
+                    }
+                    batch.rotated_count += 1;
+                }
+                Err(err) => {
+                    warn!(
+                        "Failed to re-encrypt OAuth credential of integration connection {integration_connection_id}: {err:?}"
+                    );
+                    batch.failed_count += 1;
+                }
+            }
+        }
+
+        Ok(batch)
+    }
+
     fn get_oauth2_provider(&self, kind: &IntegrationProviderKind) -> Option<&dyn OAuth2Provider> {
         self.oauth2_providers.get(kind).map(|p| p.as_ref())
     }
@@ -172,73 +267,178 @@ impl IntegrationConnectionService {
         &self,
         executor: &mut Transaction<'_, Postgres>,
         for_user_id: UserId,
-        mut job_storage: RedisStorage<UniversalInboxJob>,
+        job_storage: JobStorage,
     ) -> Result<(), UniversalInboxError> {
-        let mut integration_connections = self
-            .fetch_all_integration_connections(
+        for sync_type in [
+            IntegrationConnectionSyncType::Notifications,
+            IntegrationConnectionSyncType::Tasks,
+        ] {
+            let min_sync_interval_in_minutes = match sync_type {
+                IntegrationConnectionSyncType::Notifications => {
+                    self.min_sync_notifications_interval_in_minutes
+                }
+                IntegrationConnectionSyncType::Tasks => self.min_sync_tasks_interval_in_minutes,
+            };
+            self.trigger_due_syncs_for_user(
                 executor,
                 for_user_id,
-                Some(IntegrationConnectionStatus::Validated),
-                false,
+                sync_type,
+                min_sync_interval_in_minutes,
+                &job_storage,
             )
             .await?;
-        for integration_connection in integration_connections.iter_mut() {
-            if integration_connection.is_connected() {
-                if let Ok(notification_sync_source_kind) =
-                    integration_connection.provider.kind().try_into()
-                {
-                    let synced_before = Utc::now()
-                            - TimeDelta::try_minutes(self.min_sync_notifications_interval_in_minutes)
-                                .unwrap_or_else(|| {
-                                    panic!(
-                                        "Invalid `min_sync_notifications_interval_in_minutes` value: {}",
-                                        self.min_sync_notifications_interval_in_minutes
-                                    )
-                                });
-
-                    if integration_connection
-                        .last_notifications_sync_scheduled_at
-                        .map(|scheduled_at| scheduled_at <= synced_before)
-                        .unwrap_or(true)
-                    {
+        }
+
+        Ok(())
+    }
+
+    /// Enqueue a sync job for every connected integration connection of all
+    /// users. Users who used a session within the last
+    /// `active_user_window_in_minutes` are enqueued first and synced as often as
+    /// the `min_sync_*_interval_in_minutes` settings allow, other users at most
+    /// every `inactive_user_min_sync_interval_in_minutes`.
+    /// The jobs of each user are enqueued in their own transaction so that the
+    /// fan-out does not lock the integration connections of all users at once,
+    /// and a failure for one user does not prevent the syncs of the others.
+    /// Returns the number of enqueued jobs.
+    #[tracing::instrument(level = "debug", skip(self, job_storage), err)]
+    pub async fn trigger_scheduled_syncs_for_all_users(
+        &self,
+        sync_type: IntegrationConnectionSyncType,
+        active_user_window_in_minutes: i64,
+        inactive_user_min_sync_interval_in_minutes: i64,
+        job_storage: &JobStorage,
+    ) -> Result<usize, UniversalInboxError> {
+        let active_since = Utc::now() - TimeDelta::minutes(active_user_window_in_minutes);
+        let active_user_min_sync_interval_in_minutes = match sync_type {
+            IntegrationConnectionSyncType::Notifications => {
+                self.min_sync_notifications_interval_in_minutes
+            }
+            IntegrationConnectionSyncType::Tasks => self.min_sync_tasks_interval_in_minutes,
+        };
+
+        // Users are sorted by last activity: the jobs of online users are
+        // enqueued, and thus processed, first
+        let user_activities = {
+            let mut transaction = self.begin().await?;
+            self.repository
+                .fetch_all_user_activities(&mut transaction)
+                .await?
+        };
+        let mut enqueued_jobs_count = 0;
+        for user_activity in user_activities {
+            let is_active = user_activity
+                .last_active_at
+                .is_some_and(|last_active_at| last_active_at >= active_since);
+            match self
+                .trigger_scheduled_syncs_for_user(
+                    user_activity.user_id,
+                    sync_type,
+                    if is_active {
+                        active_user_min_sync_interval_in_minutes
+                    } else {
+                        inactive_user_min_sync_interval_in_minutes
+                    },
+                    job_storage,
+                )
+                .await
+            {
+                Ok(user_enqueued_jobs_count) => enqueued_jobs_count += user_enqueued_jobs_count,
+                Err(err) => error!(
+                    "Failed to enqueue scheduled {sync_type} syncs of user {}: {err:?}",
+                    user_activity.user_id
+                ),
+            }
+        }
+
+        Ok(enqueued_jobs_count)
+    }
+
+    async fn trigger_scheduled_syncs_for_user(
+        &self,
+        for_user_id: UserId,
+        sync_type: IntegrationConnectionSyncType,
+        min_sync_interval_in_minutes: i64,
+        job_storage: &JobStorage,
+    ) -> Result<usize, UniversalInboxError> {
+        let mut transaction = self.begin().await?;
+        let enqueued_jobs_count = self
+            .trigger_due_syncs_for_user(
+                &mut transaction,
+                for_user_id,
+                sync_type,
+                min_sync_interval_in_minutes,
+                job_storage,
+            )
+            .await?;
+        transaction.commit().await.with_context(|| {
+            format!("Failed to commit scheduled {sync_type} syncs of user {for_user_id}")
+        })?;
+
+        Ok(enqueued_jobs_count)
+    }
+
+    /// Enqueue one sync job per connected integration connection of the user
+    /// with the sync enabled, not rate limited and not synced within the last
+    /// `min_sync_interval_in_minutes`.
+    /// Returns the number of enqueued jobs.
+    async fn trigger_due_syncs_for_user(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        for_user_id: UserId,
+        sync_type: IntegrationConnectionSyncType,
+        min_sync_interval_in_minutes: i64,
+        job_storage: &JobStorage,
+    ) -> Result<usize, UniversalInboxError> {
+        let synced_before = Utc::now()
+            - TimeDelta::try_minutes(min_sync_interval_in_minutes).unwrap_or_else(|| {
+                panic!(
+                    "Invalid minimum {sync_type} sync interval value: {min_sync_interval_in_minutes}"
+                )
+            });
+        let integration_connections = self
+            .repository
+            .fetch_integration_connections_due_for_sync(
+                executor,
+                for_user_id,
+                sync_type,
+                synced_before,
+                Utc::now(),
+            )
+            .await?;
+
+        let mut enqueued_jobs_count = 0;
+        for integration_connection in integration_connections {
+            let provider_kind = integration_connection.provider.kind();
+            match sync_type {
+                IntegrationConnectionSyncType::Notifications => {
+                    if let Ok(notification_sync_source_kind) = provider_kind.try_into() {
                         self.trigger_sync_notifications(
                             executor,
                             Some(notification_sync_source_kind),
                             Some(for_user_id),
-                            &mut job_storage,
+                            job_storage,
                         )
                         .await?;
+                        enqueued_jobs_count += 1;
                     }
                 }
-                if let Ok(task_sync_source_kind) = integration_connection.provider.kind().try_into()
-                {
-                    let synced_before = Utc::now()
-                        - TimeDelta::try_minutes(self.min_sync_tasks_interval_in_minutes)
-                            .unwrap_or_else(|| {
-                                panic!(
-                                    "Invalid `min_sync_tasks_interval_in_minutes` value: {}",
-                                    self.min_sync_tasks_interval_in_minutes
-                                )
-                            });
-
-                    if integration_connection
-                        .last_tasks_sync_scheduled_at
-                        .map(|scheduled_at| scheduled_at <= synced_before)
-                        .unwrap_or(true)
-                    {
+                IntegrationConnectionSyncType::Tasks => {
+                    if let Ok(task_sync_source_kind) = provider_kind.try_into() {
                         self.trigger_sync_tasks(
                             executor,
                             Some(task_sync_source_kind),
                             Some(for_user_id),
-                            &mut job_storage,
+                            job_storage,
                         )
                         .await?;
+                        enqueued_jobs_count += 1;
                     }
                 }
             }
         }
 
-        Ok(())
+        Ok(enqueued_jobs_count)
     }
 
     #[tracing::instrument(
@@ -255,7 +455,7 @@ impl IntegrationConnectionService {
         executor: &mut Transaction<'_, Postgres>,
         notification_sync_source_kind: Option<NotificationSyncSourceKind>,
         for_user_id: Option<UserId>,
-        job_storage: &mut RedisStorage<UniversalInboxJob>,
+        job_storage: &JobStorage,
     ) -> Result<(), UniversalInboxError> {
         info!(
             "Triggering sync notifications job for {notification_sync_source_kind:?} integration connection for user {for_user_id:?}"
@@ -271,7 +471,6 @@ impl IntegrationConnectionService {
             ExponentialBackoff::from_millis(10).map(jitter).take(10),
             || async {
                 job_storage
-                    .clone()
                     .push(UniversalInboxJob::SyncNotifications(SyncNotificationsJob {
                         source: notification_sync_source_kind,
                         user_id: for_user_id,
@@ -299,7 +498,7 @@ impl IntegrationConnectionService {
         executor: &mut Transaction<'_, Postgres>,
         task_sync_source_kind: Option<TaskSyncSourceKind>,
         for_user_id: Option<UserId>,
-        job_storage: &mut RedisStorage<UniversalInboxJob>,
+        job_storage: &JobStorage,
     ) -> Result<(), UniversalInboxError> {
         info!(
             "Triggering sync tasks job for {task_sync_source_kind:?} integration connection for user {for_user_id:?}"
@@ -315,7 +514,6 @@ impl IntegrationConnectionService {
             ExponentialBackoff::from_millis(10).map(jitter).take(10),
             || async {
                 job_storage
-                    .clone()
                     .push(UniversalInboxJob::SyncTasks(SyncTasksJob {
                         source: task_sync_source_kind,
                         user_id: for_user_id,
@@ -602,6 +800,7 @@ impl IntegrationConnectionService {
                 ),
             }
         };
+        let is_sync_interval_filtered = synced_before_filter.is_some();
         let connection = self
             .repository
             .get_integration_connection_per_provider(
@@ -613,6 +812,31 @@ impl IntegrationConnectionService {
             )
             .await?;
 
+        // A connection synced recently is skipped, but its syncs may also be
+        // deferred by the provider's rate limit, which callers must be told
+        if connection.is_none()
+            && is_sync_interval_filtered
+            && let Some(conn) = self
+                .repository
+                .get_integration_connection_per_provider(
+                    executor,
+                    for_user_id,
+                    integration_provider_kind,
+                    None,
+                    Some(IntegrationConnectionStatus::Validated),
+                )
+                .await?
+            && let Some(until) = self.get_rate_limited_until(executor, conn.id).await?
+        {
+            debug!(
+                "{integration_provider_kind} API is rate limited for user {for_user_id} until {until}, deferring {sync_type} sync"
+            );
+            return Err(UniversalInboxError::UpstreamRateLimited {
+                provider_kind: integration_provider_kind,
+                until,
+            });
+        }
+
         if let Some(ref conn) = connection {
             let in_backoff = match sync_type {
                 IntegrationConnectionSyncType::Notifications => conn
@@ -631,6 +855,16 @@ impl IntegrationConnectionService {
                 );
                 return Ok(None);
             }
+
+            if let Some(until) = self.get_rate_limited_until(executor, conn.id).await? {
+                debug!(
+                    "{integration_provider_kind} API is rate limited for user {for_user_id} until {until}, deferring {sync_type} sync"
+                );
+                return Err(UniversalInboxError::UpstreamRateLimited {
+                    provider_kind: integration_provider_kind,
+                    until,
+                });
+            }
         }
 
         Ok(connection)
@@ -810,12 +1044,12 @@ impl IntegrationConnectionService {
             )));
         }
 
-        let token_encryption_key = self.token_encryption_key.expose_secret();
+        let token_encryption_keys = self.token_encryption_keys.expose_secret();
         let aad_context = integration_connection.id.0.as_bytes();
         let access_token = AccessToken(decrypt_token(
             &credential.encrypted_access_token,
             aad_context,
-            token_encryption_key,
+            token_encryption_keys,
         )?);
 
         Ok(Some((access_token, integration_connection)))
@@ -845,6 +1079,30 @@ impl IntegrationConnectionService {
             .await
     }
 
+    /// Restore the context the integration connection had before a failed sync,
+    /// so that the sync state saved by the provider's service (such as the
+    /// validators of Github's conditional requests) does not skip the items
+    /// which failed to be synced on the next sync.
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(integration_connection.id = integration_connection.id.to_string()),
+        err
+    )]
+    pub async fn restore_integration_connection_context(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        integration_connection: &IntegrationConnection,
+    ) -> Result<UpdateStatus<Box<IntegrationConnection>>, UniversalInboxError> {
+        self.repository
+            .update_integration_connection_context(
+                executor,
+                integration_connection.id,
+                integration_connection.provider.context(),
+            )
+            .await
+    }
+
     #[tracing::instrument(
         level = "debug",
         skip_all,
@@ -1103,6 +1361,149 @@ impl IntegrationConnectionService {
             .await
     }
 
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            integration_connection_id = sync_run.integration_connection_id.to_string(),
+            sync_run_kind = sync_run.kind.to_string()
+        ),
+        err
+    )]
+    pub async fn save_sync_run(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        sync_run: IntegrationConnectionSyncRun,
+    ) -> Result<Box<IntegrationConnectionSyncRun>, UniversalInboxError> {
+        self.repository
+            .create_integration_connection_sync_run(
+                executor,
+                Box::new(sync_run),
+                self.sync_run_history_size,
+            )
+            .await
+    }
+
+    /// Save the rate limit reported by the provider while syncing the
+    /// connection. When the sync failed because of it, returns the time until
+    /// which syncs of the connection are deferred.
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(integration_connection_id = integration_connection_id.to_string()),
+        err
+    )]
+    pub async fn save_sync_rate_limit(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        integration_connection_id: IntegrationConnectionId,
+        rate_limit: UpstreamRateLimit,
+        sync_error: Option<&UniversalInboxError>,
+    ) -> Result<Option<DateTime<Utc>>, UniversalInboxError> {
+        let now = Utc::now();
+        let (rate_limit, rate_limited_until) = match sync_error {
+            Some(error) => {
+                let rate_limit = UpstreamRateLimit::from_error(error, now)
+                    .map(|error_rate_limit| rate_limit.merge(error_rate_limit))
+                    .unwrap_or(rate_limit);
+                (rate_limit, rate_limit.rate_limited_until(error, now))
+            }
+            None => (rate_limit, None),
+        };
+
+        if !rate_limit.is_empty() || rate_limited_until.is_some() {
+            self.repository
+                .update_integration_connection_rate_limit(
+                    executor,
+                    Box::new(IntegrationConnectionRateLimit {
+                        integration_connection_id,
+                        remaining: rate_limit.remaining,
+                        reset_at: rate_limit.reset_at,
+                        limited_until: rate_limited_until.or_else(|| rate_limit.limited_until(now)),
+                        updated_at: now,
+                    }),
+                )
+                .await?;
+        }
+
+        Ok(rate_limited_until)
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(integration_connection_id = integration_connection_id.to_string()),
+        err
+    )]
+    pub async fn get_rate_limited_until(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        integration_connection_id: IntegrationConnectionId,
+    ) -> Result<Option<DateTime<Utc>>, UniversalInboxError> {
+        Ok(self
+            .repository
+            .get_integration_connection_rate_limit(executor, integration_connection_id)
+            .await?
+            .and_then(|rate_limit| rate_limit.limited_until(Utc::now())))
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            integration_connection_id = integration_connection_id.to_string(),
+            user.id = for_user_id.to_string()
+        ),
+        err
+    )]
+    pub async fn fetch_sync_runs(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        integration_connection_id: IntegrationConnectionId,
+        for_user_id: UserId,
+    ) -> Result<Vec<IntegrationConnectionSyncRun>, UniversalInboxError> {
+        let Some(integration_connection) = self
+            .repository
+            .get_integration_connection(executor, integration_connection_id)
+            .await?
+        else {
+            return Err(UniversalInboxError::ItemNotFound(format!(
+                "Integration connection {integration_connection_id} not found"
+            )));
+        };
+        if integration_connection.user_id != for_user_id {
+            return Err(UniversalInboxError::Forbidden(format!(
+                "Only the owner of the integration connection {integration_connection_id} can access its sync runs"
+            )));
+        }
+
+        self.repository
+            .fetch_integration_connection_sync_runs(executor, integration_connection_id)
+            .await
+    }
+
+    /// Health of each integration provider with sync runs started since the
+    /// given time
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(started_since = started_since.to_rfc3339()),
+        err
+    )]
+    pub async fn fetch_integration_provider_health(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        started_since: DateTime<Utc>,
+    ) -> Result<Vec<IntegrationProviderHealth>, UniversalInboxError> {
+        Ok(self
+            .repository
+            .fetch_integration_provider_sync_stats(executor, started_since)
+            .await?
+            .into_iter()
+            .map(IntegrationProviderHealth::from)
+            .collect())
+    }
+
     #[tracing::instrument(
         level = "debug",
         skip_all,
@@ -1169,7 +1570,7 @@ impl IntegrationConnectionService {
         minutes_before_expiry: i64,
         provider_kind: Option<IntegrationProviderKind>,
     ) -> Result<(usize, usize), UniversalInboxError> {
-        let token_encryption_key = self.token_encryption_key.expose_secret();
+        let token_encryption_keys = self.token_encryption_keys.expose_secret();
         let flow_service = &self.oauth2_flow_service;
 
         let expiring_before = Utc::now()
@@ -1200,6 +1601,7 @@ impl IntegrationConnectionService {
                     warn!(
                         "No OAuth2Provider configured for {pk:?}, skipping credential for connection {conn_id}"
                     );
+                    metrics::record_oauth_token_refresh(pk, "no_provider");
                     failed += 1;
                     continue;
                 }
@@ -1209,11 +1611,12 @@ impl IntegrationConnectionService {
             let refresh_token = match decrypt_token(
                 &credential.encrypted_refresh_token,
                 aad_context,
-                token_encryption_key,
+                token_encryption_keys,
             ) {
                 Ok(t) => RefreshToken(t),
                 Err(err) => {
                     error!("Failed to decrypt refresh token for connection {conn_id}: {err:?}");
+                    metrics::record_oauth_token_refresh(pk, "failed");
                     failed += 1;
                     continue;
                 }
@@ -1245,6 +1648,7 @@ impl IntegrationConnectionService {
                             "Failed to mark connection {conn_id} as Failing after invalid_grant: {update_err:?}"
                         );
                     }
+                    metrics::record_oauth_token_refresh(pk, "invalid_grant");
                     failed += 1;
                     continue;
                 }
@@ -1252,6 +1656,7 @@ impl IntegrationConnectionService {
                     error!(
                         "Failed to refresh access token for connection {conn_id} ({pk:?}): {err:?}"
                     );
+                    metrics::record_oauth_token_refresh(pk, "failed");
                     failed += 1;
                     continue;
                 }
@@ -1260,11 +1665,12 @@ impl IntegrationConnectionService {
             let encrypted_access_token = match encrypt_token(
                 token_response.access_token.expose_secret().as_str(),
                 aad_context,
-                token_encryption_key,
+                token_encryption_keys,
             ) {
                 Ok(t) => t,
                 Err(err) => {
                     error!("Failed to encrypt new access token for connection {conn_id}: {err:?}");
+                    metrics::record_oauth_token_refresh(pk, "failed");
                     failed += 1;
                     continue;
                 }
@@ -1277,7 +1683,7 @@ impl IntegrationConnectionService {
                     encrypt_token(
                         rt.expose_secret().as_str(),
                         aad_context,
-                        token_encryption_key,
+                        token_encryption_keys,
                     )
                 })
                 .transpose()
@@ -1285,6 +1691,7 @@ impl IntegrationConnectionService {
                 Ok(t) => t,
                 Err(err) => {
                     error!("Failed to encrypt new refresh token for connection {conn_id}: {err:?}");
+                    metrics::record_oauth_token_refresh(pk, "failed");
                     failed += 1;
                     continue;
                 }
@@ -1308,10 +1715,12 @@ impl IntegrationConnectionService {
             {
                 Ok(_) => {
                     info!("Successfully refreshed OAuth token for connection {conn_id} ({pk:?})");
+                    metrics::record_oauth_token_refresh(pk, "refreshed");
                     refreshed += 1;
                 }
                 Err(err) => {
                     error!("Failed to store refreshed token for connection {conn_id}: {err:?}");
+                    metrics::record_oauth_token_refresh(pk, "failed");
                     failed += 1;
                 }
             }
@@ -1419,11 +1828,15 @@ impl IntegrationConnectionService {
         let state_json =
             serde_json::to_string(&state_data).context("Failed to serialize OAuth state data")?;
 
-        let redis_key = format!("{OAUTH_STATE_PREFIX}{state}");
-        let mut conn = cache.connection_manager.clone();
-        conn.set_ex::<_, _, ()>(&redis_key, &state_json, OAUTH_STATE_TTL_SECONDS)
+        let key = format!("{OAUTH_STATE_PREFIX}{state}");
+        cache
+            .set_ex(
+                &key,
+                &state_json,
+                Duration::from_secs(OAUTH_STATE_TTL_SECONDS),
+            )
             .await
-            .context("Failed to store OAuth state in Redis")?;
+            .context("Failed to store OAuth state")?;
 
         Ok(authorization_url)
     }
@@ -1435,13 +1848,12 @@ impl IntegrationConnectionService {
         state: &str,
         cache: &Cache,
     ) -> Result<(), UniversalInboxError> {
-        // Look up and delete state from Redis (single-use)
-        let redis_key = format!("{OAUTH_STATE_PREFIX}{state}");
-        let mut conn = cache.connection_manager.clone();
-        let state_json: Option<String> = conn
-            .get_del(&redis_key)
+        // Look up and delete state from the cache (single-use)
+        let key = format!("{OAUTH_STATE_PREFIX}{state}");
+        let state_json = cache
+            .get_del(&key)
             .await
-            .context("Failed to retrieve OAuth state from Redis")?;
+            .context("Failed to retrieve OAuth state")?;
 
         let state_json = state_json.ok_or_else(|| {
             UniversalInboxError::Unauthorized(anyhow::anyhow!("Invalid or expired OAuth state"))
@@ -1459,7 +1871,7 @@ impl IntegrationConnectionService {
                 ))
             })?;
 
-        let token_encryption_key = self.token_encryption_key.expose_secret();
+        let token_encryption_keys = self.token_encryption_keys.expose_secret();
 
         let token_response = self
             .oauth2_flow_service
@@ -1475,7 +1887,7 @@ impl IntegrationConnectionService {
         let encrypted_access_token = encrypt_token(
             token_response.access_token.expose_secret().as_str(),
             aad_context,
-            token_encryption_key,
+            token_encryption_keys,
         )?;
         let encrypted_refresh_token = token_response
             .refresh_token
@@ -1484,7 +1896,7 @@ impl IntegrationConnectionService {
                 encrypt_token(
                     rt.expose_secret().as_str(),
                     aad_context,
-                    token_encryption_key,
+                    token_encryption_keys,
                 )
             })
             .transpose()?;
@@ -1563,9 +1975,9 @@ impl IntegrationConnectionService {
 #[io_cached(
     key = "String",
     convert = r#"{ format!("{}{}", provider_kind, provider_user_id) }"#,
-    ty = "cached::AsyncRedisCache<String, Option<IntegrationConnectionConfig>>",
+    ty = "IOCache<Option<IntegrationConnectionConfig>>",
     map_error = r##"|e| UniversalInboxError::Unexpected(anyhow!("Failed to cache Slack `is_known_provider_user_id`: {:?}", e))"##,
-    create = r##" { build_redis_cache("slack:is_known_provider_user_id", Duration::from_secs(6 * 60 * 60), false).await }"##
+    create = r##" { build_io_cache("slack:is_known_provider_user_id", Duration::from_secs(6 * 60 * 60), false).await }"##
 )]
 async fn cached_get_integration_connection_config_for_provider_user_id(
     repository: Arc<Repository>,
@@ -0,0 +1,142 @@
+//! Statistics of the synchronization running in the current task.
+//!
+//! Items are counted where they are upserted and upstream calls where their
+//! responses are received, both far away from the service running the sync.
+//! Rather than threading a collector through every integration, the running
+//! sync installs one as a task-local: recording outside of a sync is a no-op.
+
+use std::{cell::RefCell, error::Error, future::Future};
+
+use chrono::{DateTime, Utc};
+use http::{HeaderMap, StatusCode};
+use uuid::Uuid;
+
+use universal_inbox::integration_connection::{
+    IntegrationConnectionId,
+    sync_run::{IntegrationConnectionSyncRun, IntegrationConnectionSyncRunKind},
+};
+
+use crate::{integrations::rate_limit::UpstreamRateLimit, universal_inbox::UniversalInboxError};
+
+tokio::task_local! {
+    static CURRENT_SYNC_RUN_STATS: RefCell<SyncRunStats>;
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncRunStats {
+    pub items_fetched: u32,
+    pub items_created: u32,
+    pub items_updated: u32,
+    pub items_marked_stale: u32,
+    pub upstream_http_calls: u32,
+    /// Rate limit reported by the provider, the most recent values first
+    pub rate_limit: UpstreamRateLimit,
+}
+
+impl SyncRunStats {
+    pub fn into_sync_run(
+        self,
+        integration_connection_id: IntegrationConnectionId,
+        kind: IntegrationConnectionSyncRunKind,
+        started_at: DateTime<Utc>,
+        error: Option<&UniversalInboxError>,
+    ) -> IntegrationConnectionSyncRun {
+        IntegrationConnectionSyncRun {
+            id: Uuid::new_v4().into(),
+            integration_connection_id,
+            kind,
+            started_at,
+            ended_at: Utc::now(),
+            items_fetched: self.items_fetched,
+            items_created: self.items_created,
+            items_updated: self.items_updated,
+            items_marked_stale: self.items_marked_stale,
+            upstream_http_calls: self.upstream_http_calls,
+            rate_limit_remaining: self.rate_limit.remaining,
+            error_chain: error.map(|error| error_chain(error)).unwrap_or_default(),
+        }
+    }
+}
+
+/// Run `future` as a sync run, returning its output along with the statistics
+/// recorded while it ran
+pub async fn collect_sync_run_stats<F: Future>(future: F) -> (F::Output, SyncRunStats) {
+    CURRENT_SYNC_RUN_STATS
+        .scope(RefCell::new(SyncRunStats::default()), async move {
+            let output = future.await;
+            (output, CURRENT_SYNC_RUN_STATS.with(|stats| stats.take()))
+        })
+        .await
+}
+
+pub fn record_sync_run_stats(update: impl FnOnce(&mut SyncRunStats)) {
+    let _ = CURRENT_SYNC_RUN_STATS.try_with(|stats| update(&mut stats.borrow_mut()));
+}
+
+pub fn record_upstream_http_call(status: StatusCode, headers: &HeaderMap) {
+    record_sync_run_stats(|stats| {
+        stats.upstream_http_calls += 1;
+        stats.rate_limit = stats.rate_limit.merge(UpstreamRateLimit::from_response(
+            status,
+            headers,
+            Utc::now(),
+        ));
+    });
+}
+
+/// Messages of `error` and of its sources, outermost first
+pub fn error_chain(error: &(dyn Error + 'static)) -> Vec<String> {
+    let mut chain: Vec<String> = std::iter::successors(Some(error), |&error| error.source())
+        .map(|error| error.to_string())
+        .collect();
+    chain.dedup();
+    chain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use anyhow::{Context, anyhow};
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn test_collect_sync_run_stats() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", "4242".parse().unwrap());
+
+        let (output, stats) = collect_sync_run_stats(async {
+            record_upstream_http_call(StatusCode::OK, &HeaderMap::new());
+            record_upstream_http_call(StatusCode::OK, &headers);
+            record_sync_run_stats(|stats| stats.items_created += 2);
+            42
+        })
+        .await;
+
+        assert_eq!(output, 42);
+        assert_eq!(stats.items_created, 2);
+        assert_eq!(stats.upstream_http_calls, 2);
+        assert_eq!(stats.rate_limit.remaining, Some(4242));
+    }
+
+    #[test]
+    fn test_record_sync_run_stats_outside_of_a_sync_run() {
+        // Must not panic
+        record_upstream_http_call(StatusCode::OK, &HeaderMap::new());
+    }
+
+    #[test]
+    fn test_error_chain() {
+        let error = Err::<(), _>(anyhow!("Connection reset"))
+            .context("Failed to fetch notifications")
+            .unwrap_err();
+
+        assert_eq!(
+            error_chain(error.as_ref()),
+            vec![
+                "Failed to fetch notifications".to_string(),
+                "Connection reset".to_string()
+            ]
+        );
+    }
+}
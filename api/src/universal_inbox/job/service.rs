@@ -0,0 +1,122 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use sqlx::{Postgres, Transaction};
+use tracing::info;
+
+use crate::{
+    jobs::{
+        UniversalInboxJob,
+        dead_letter::{DeadLetterJob, DeadLetterJobFilter},
+        storage::JobStorage,
+    },
+    repository::{Repository, job::JobRepository},
+    universal_inbox::UniversalInboxError,
+};
+
+pub struct JobService {
+    repository: Arc<Repository>,
+}
+
+impl JobService {
+    pub fn new(repository: Arc<Repository>) -> Self {
+        Self { repository }
+    }
+
+    pub async fn begin(&self) -> Result<Transaction<'_, Postgres>, UniversalInboxError> {
+        self.repository.begin().await
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(job.id = %job_id, job.name = job.name(), attempts = attempts),
+        err
+    )]
+    pub async fn dead_letter_job(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        job: &UniversalInboxJob,
+        job_id: String,
+        error: &UniversalInboxError,
+        attempts: u32,
+    ) -> Result<Box<DeadLetterJob>, UniversalInboxError> {
+        let dead_letter_job = DeadLetterJob::new(job, job_id, error, attempts)?;
+
+        self.repository
+            .create_dead_letter_job(executor, Box::new(dead_letter_job))
+            .await
+    }
+
+    #[tracing::instrument(level = "debug", skip_all, fields(?filter), err)]
+    pub async fn fetch_dead_letter_jobs(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        filter: &DeadLetterJobFilter,
+    ) -> Result<Vec<DeadLetterJob>, UniversalInboxError> {
+        self.repository
+            .fetch_dead_letter_jobs(executor, filter)
+            .await
+    }
+
+    /// Push the matching dead-lettered jobs back into the queue and remove
+    /// them from the dead-letter store
+    #[tracing::instrument(level = "debug", skip_all, fields(?filter), err)]
+    pub async fn retry_dead_letter_jobs(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        filter: &DeadLetterJobFilter,
+        job_storage: &JobStorage,
+    ) -> Result<Vec<DeadLetterJob>, UniversalInboxError> {
+        let dead_letter_jobs = self
+            .repository
+            .fetch_dead_letter_jobs(executor, filter)
+            .await?;
+
+        for dead_letter_job in dead_letter_jobs.iter() {
+            let job = dead_letter_job.job()?;
+            let task_id = job_storage
+                .push(job)
+                .await
+                .with_context(|| format!("Failed to push job {} to queue", dead_letter_job.id))?;
+            info!(
+                "Pushed dead-lettered {} job {} back to the queue with job ID {task_id}",
+                dead_letter_job.name, dead_letter_job.id
+            );
+
+            self.repository
+                .delete_dead_letter_jobs(
+                    executor,
+                    &DeadLetterJobFilter {
+                        id: Some(dead_letter_job.id),
+                        ..Default::default()
+                    },
+                )
+                .await?;
+        }
+
+        Ok(dead_letter_jobs)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all, fields(?filter), err)]
+    pub async fn count_dead_letter_jobs(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        filter: &DeadLetterJobFilter,
+    ) -> Result<u64, UniversalInboxError> {
+        self.repository
+            .count_dead_letter_jobs(executor, filter)
+            .await
+    }
+
+    #[tracing::instrument(level = "debug", skip_all, fields(?filter), err)]
+    pub async fn purge_dead_letter_jobs(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        filter: &DeadLetterJobFilter,
+    ) -> Result<u64, UniversalInboxError> {
+        self.repository
+            .delete_dead_letter_jobs(executor, filter)
+            .await
+    }
+}
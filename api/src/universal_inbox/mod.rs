@@ -1,11 +1,18 @@
 use anyhow::{Error, anyhow};
+use chrono::{DateTime, SecondsFormat, Utc};
 use format_serde_error::SerdeError;
 use url::ParseError;
 use uuid::Uuid;
 use validator::ValidationErrors;
 
+use universal_inbox::{
+    auth::oauth2::{OAuth2DeviceGrantError, OAuth2Scope},
+    integration_connection::provider::IntegrationProviderKind,
+};
+
 pub mod auth_token;
 pub mod integration_connection;
+pub mod job;
 pub mod notification;
 pub mod oauth2;
 pub mod slack_bridge;
@@ -76,12 +83,21 @@ pub enum UniversalInboxError {
     Unauthorized(anyhow::Error),
     #[error("Forbidden access: {0}")]
     Forbidden(String),
+    #[error("Insufficient scope: {} is required", OAuth2Scope::to_scope_string(.required_scopes))]
+    InsufficientScope { required_scopes: Vec<OAuth2Scope> },
     #[error("Too many login attempts. Please try again later.")]
     TooManyLoginAttempts { retry_after_seconds: u64 },
+    #[error("{provider_kind} API is rate limited until {}", .until.to_rfc3339_opts(SecondsFormat::Secs, true))]
+    UpstreamRateLimited {
+        provider_kind: IntegrationProviderKind,
+        until: DateTime<Utc>,
+    },
     #[error("Recoverable error: {0}")]
     Recoverable(#[source] anyhow::Error),
     #[error("OAuth2 refresh token is no longer valid (invalid_grant): {0}")]
     OAuth2InvalidGrant(String),
+    #[error("Device authorization grant failed: {0}")]
+    OAuth2DeviceGrant(OAuth2DeviceGrantError),
     #[error(transparent)]
     Unexpected(#[from] anyhow::Error),
 }
@@ -4,8 +4,6 @@ use std::{
 };
 
 use anyhow::{Context, anyhow};
-use apalis::prelude::Storage;
-use apalis_redis::RedisStorage;
 use chrono::{DateTime, Utc};
 use sqlx::{Postgres, Transaction};
 use tokio::sync::RwLock;
@@ -13,23 +11,27 @@ use tokio_retry::{
     Retry,
     strategy::{ExponentialBackoff, jitter},
 };
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use universal_inbox::{
     Page, PageToken,
     integration_connection::{
         integrations::{ticktick::TickTickConfig, todoist::TodoistConfig},
         provider::{IntegrationProvider, IntegrationProviderKind},
+        sync_run::IntegrationConnectionSyncRunKind,
     },
     notification::{
         Notification, NotificationId, NotificationListOrder, NotificationSource,
         NotificationSourceKind, NotificationStatus, NotificationSyncSourceKind,
         NotificationWithTask,
-        service::{InvitationPatch, NotificationPatch},
+        service::{GithubPullRequestAction, InvitationPatch, LinearIssueAction, NotificationPatch},
     },
     task::{DueDate, Task, TaskCreation, TaskId, TaskStatus, service::TaskPatch},
     third_party::{
-        integrations::slack::{SlackReaction, SlackThread},
+        integrations::{
+            linear::LinearTeamDetails,
+            slack::{SlackReaction, SlackThread},
+        },
         item::{ThirdPartyItem, ThirdPartyItemData, ThirdPartyItemId, ThirdPartyItemKind},
     },
     user::UserId,
@@ -42,20 +44,24 @@ use crate::{
         notification::ThirdPartyNotificationSourceService, slack::SlackService,
         third_party::ThirdPartyItemSourceService,
     },
-    jobs::UniversalInboxJob,
+    jobs::{UniversalInboxJob, storage::JobStorage},
+    mcp::subscriptions::ResourceUpdatePublisher,
+    metrics,
     repository::{
         Repository, notification::NotificationRepository, task::TaskRepository,
         third_party::ThirdPartyItemRepository,
     },
     universal_inbox::{
         UniversalInboxError, UpdateStatus, UpsertStatus,
-        integration_connection::service::{
-            IntegrationConnectionService, IntegrationConnectionSyncType,
+        integration_connection::{
+            service::{IntegrationConnectionService, IntegrationConnectionSyncType},
+            sync_run::collect_sync_run_stats,
         },
         task::service::TaskService,
         third_party::service::ThirdPartyItemService,
         user::service::UserService,
     },
+    utils::jwt::check_notification_source_kind,
 };
 
 // tag: New notification integration
@@ -72,6 +78,7 @@ pub struct NotificationService {
     pub(super) third_party_item_service: Weak<RwLock<ThirdPartyItemService>>,
     user_service: Arc<UserService>,
     min_sync_notifications_interval_in_minutes: i64,
+    resource_update_publisher: Option<ResourceUpdatePublisher>,
 }
 
 impl NotificationService {
@@ -89,6 +96,7 @@ impl NotificationService {
         third_party_item_service: Weak<RwLock<ThirdPartyItemService>>,
         user_service: Arc<UserService>,
         min_sync_notifications_interval_in_minutes: i64,
+        resource_update_publisher: Option<ResourceUpdatePublisher>,
     ) -> NotificationService {
         NotificationService {
             repository,
@@ -103,6 +111,7 @@ impl NotificationService {
             third_party_item_service,
             user_service,
             min_sync_notifications_interval_in_minutes,
+            resource_update_publisher,
         }
     }
 
@@ -113,6 +122,20 @@ impl NotificationService {
     pub async fn begin(&self) -> Result<Transaction<'_, Postgres>, UniversalInboxError> {
         self.repository.begin().await
     }
+
+    /// Notify the MCP sessions subscribed to these notifications of their
+    /// update
+    async fn publish_notification_updates(
+        &self,
+        user_id: UserId,
+        notification_ids: impl IntoIterator<Item = NotificationId>,
+    ) {
+        if let Some(resource_update_publisher) = &self.resource_update_publisher {
+            resource_update_publisher
+                .publish_notification_updates(user_id, notification_ids)
+                .await;
+        }
+    }
     #[tracing::instrument(
         level = "debug",
         skip_all,
@@ -417,7 +440,7 @@ impl NotificationService {
         from_sources: Vec<NotificationSourceKind>,
         page_token: Option<PageToken>,
         user_id: UserId,
-        job_storage: Option<RedisStorage<UniversalInboxJob>>,
+        job_storage: Option<JobStorage>,
     ) -> Result<Page<NotificationWithTask>, UniversalInboxError> {
         let notifications_page = self
             .repository
@@ -476,6 +499,38 @@ impl NotificationService {
         Ok(notification)
     }
 
+    /// Fails with `Forbidden` if the notification comes from a source not in
+    /// `allowed_sources` (`None` meaning all sources). Unknown notifications
+    /// are accepted so that callers can report them as not found.
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            notification_id = notification_id.to_string(),
+            user.id = for_user_id.to_string()
+        ),
+        err
+    )]
+    pub async fn check_notification_source_access(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        notification_id: NotificationId,
+        for_user_id: UserId,
+        allowed_sources: Option<&[NotificationSourceKind]>,
+    ) -> Result<(), UniversalInboxError> {
+        if allowed_sources.is_none() {
+            return Ok(());
+        }
+
+        if let Some(notification) = self
+            .get_notification(executor, notification_id, for_user_id)
+            .await?
+        {
+            check_notification_source_kind(allowed_sources, notification.kind)?;
+        }
+        Ok(())
+    }
+
     #[tracing::instrument(
         level = "debug",
         skip_all,
@@ -548,9 +603,14 @@ impl NotificationService {
             )));
         }
 
-        self.repository
+        let notification = self
+            .repository
             .create_notification(executor, notification)
-            .await
+            .await?;
+        self.publish_notification_updates(for_user_id, [notification.id])
+            .await;
+
+        Ok(notification)
     }
 
     #[tracing::instrument(
@@ -570,14 +630,21 @@ impl NotificationService {
         notification_source_kind: NotificationSourceKind,
         update_snoozed_until: bool,
     ) -> Result<UpsertStatus<Box<Notification>>, UniversalInboxError> {
-        self.repository
+        let upsert_notification = self
+            .repository
             .create_or_update_notification(
                 executor,
                 notification,
                 notification_source_kind,
                 update_snoozed_until,
             )
-            .await
+            .await?;
+        if let Some(notification) = upsert_notification.modified_value_ref() {
+            self.publish_notification_updates(notification.user_id, [notification.id])
+                .await;
+        }
+
+        Ok(upsert_notification)
     }
 
     #[tracing::instrument(
@@ -610,6 +677,13 @@ impl NotificationService {
             "{} {notification_source_kind} notifications marked as deleted for user {user_id}.",
             deleted_notifications.len()
         );
+        self.publish_notification_updates(
+            user_id,
+            deleted_notifications
+                .iter()
+                .map(|notification| notification.id),
+        )
+        .await;
 
         Ok(deleted_notifications)
     }
@@ -721,7 +795,11 @@ impl NotificationService {
                     .context(format!("Failed to commit while syncing {source:?}"))?;
                 Ok(notifications)
             }
-            Err(error @ UniversalInboxError::Recoverable(_)) => {
+            // Keep the sync status and rate limit recorded before failing
+            Err(
+                error @ (UniversalInboxError::Recoverable(_)
+                | UniversalInboxError::UpstreamRateLimited { .. }),
+            ) => {
                 transaction
                     .commit()
                     .await
@@ -805,6 +883,27 @@ impl NotificationService {
             .collect())
     }
 
+    /// Synchronizes the given notification `sources`, or all of them if `None`
+    pub async fn sync_notifications_from_sources(
+        &self,
+        sources: Option<Vec<NotificationSyncSourceKind>>,
+        user_id: UserId,
+        force_sync: bool,
+    ) -> Result<Vec<Notification>, UniversalInboxError> {
+        let Some(sources) = sources else {
+            return self.sync_all_notifications(user_id, force_sync).await;
+        };
+
+        let mut notifications = vec![];
+        for source in sources {
+            notifications.extend(
+                self.sync_notifications_with_transaction(source, user_id, force_sync)
+                    .await?,
+            );
+        }
+        Ok(notifications)
+    }
+
     pub async fn sync_notifications_for_all_users(
         &self,
         source: Option<NotificationSyncSourceKind>,
@@ -875,6 +974,14 @@ impl NotificationService {
             .repository
             .update_notification(executor, notification_id, patch, for_user_id)
             .await?;
+        if let UpdateStatus {
+            updated: true,
+            result: Some(notification),
+        } = &updated_notification
+        {
+            self.publish_notification_updates(for_user_id, [notification.id])
+                .await;
+        }
 
         if !apply_notification_side_effects {
             return Ok(updated_notification);
@@ -934,9 +1041,26 @@ impl NotificationService {
         notification_kind: Option<NotificationSourceKind>,
         patch: &NotificationPatch,
     ) -> Result<Vec<UpdateStatus<Notification>>, UniversalInboxError> {
-        self.repository
+        let update_statuses = self
+            .repository
             .update_notifications_for_task(executor, task_id, notification_kind, patch)
-            .await
+            .await?;
+        let updated_notifications: Vec<&Notification> = update_statuses
+            .iter()
+            .filter(|update_status| update_status.updated)
+            .filter_map(|update_status| update_status.result.as_ref())
+            .collect();
+        if let Some(notification) = updated_notifications.first() {
+            self.publish_notification_updates(
+                notification.user_id,
+                updated_notifications
+                    .iter()
+                    .map(|notification| notification.id),
+            )
+            .await;
+        }
+
+        Ok(update_statuses)
     }
 
     #[tracing::instrument(
@@ -954,9 +1078,19 @@ impl NotificationService {
         linear_issue_id: &str,
         user_id: UserId,
     ) -> Result<Vec<Notification>, UniversalInboxError> {
-        self.repository
+        let deleted_notifications = self
+            .repository
             .delete_notifications_for_linear_issue_id(executor, linear_issue_id, user_id)
-            .await
+            .await?;
+        self.publish_notification_updates(
+            user_id,
+            deleted_notifications
+                .iter()
+                .map(|notification| notification.id),
+        )
+        .await;
+
+        Ok(deleted_notifications)
     }
 
     #[tracing::instrument(
@@ -977,12 +1111,19 @@ impl NotificationService {
         from_sources: Vec<NotificationSourceKind>,
         patch: &NotificationPatch,
         user_id: UserId,
-        job_storage: &mut RedisStorage<UniversalInboxJob>,
+        job_storage: &JobStorage,
     ) -> Result<Vec<Notification>, UniversalInboxError> {
         let updated_notifications = self
             .repository
             .update_notifications(executor, status, from_sources, patch, user_id)
             .await?;
+        self.publish_notification_updates(
+            user_id,
+            updated_notifications
+                .iter()
+                .map(|notification| notification.id),
+        )
+        .await;
 
         // Queue async side effects processing for each updated notification
         for notification in &updated_notifications {
@@ -990,7 +1131,6 @@ impl NotificationService {
                 ExponentialBackoff::from_millis(10).map(jitter).take(10),
                 || async {
                     job_storage
-                        .clone()
                         .push(UniversalInboxJob::ProcessNotificationSideEffects {
                             notification_id: notification.id,
                             patch: patch.clone(),
@@ -1258,6 +1398,8 @@ impl NotificationService {
                         self.repository
                             .update_notification(executor, notification.id, &delete_patch, user_id)
                             .await?;
+                        self.publish_notification_updates(user_id, [notification.id])
+                            .await;
 
                         return Ok(Some(Notification {
                             status: NotificationStatus::Deleted,
@@ -1389,16 +1531,47 @@ impl NotificationService {
             .start_notifications_sync_status(executor, integration_provider_kind, user_id)
             .await?;
 
-        let notification_creation_results = match sync_third_party_notifications(
+        let sync_started_at = Utc::now();
+        let (sync_result, sync_run_stats) = collect_sync_run_stats(sync_third_party_notifications(
             self,
             executor,
             third_party_notification_service,
             user_id,
             integration_connection.last_notifications_sync_completed_at,
-        )
-        .await
-        {
+        ))
+        .await;
+        let notification_creation_results = match sync_result {
             Err(e) => {
+                // The failed sync is committed to keep its status, but not the
+                // provider's sync state it may have advanced
+                integration_connection_service
+                    .restore_integration_connection_context(executor, &integration_connection)
+                    .await?;
+                let rate_limit = sync_run_stats.rate_limit;
+                let sync_run = sync_run_stats.into_sync_run(
+                    integration_connection.id,
+                    IntegrationConnectionSyncRunKind::Notifications,
+                    sync_started_at,
+                    Some(&e),
+                );
+                metrics::record_sync_run(integration_provider_kind, &sync_run, Some(&e));
+                integration_connection_service
+                    .save_sync_run(executor, sync_run)
+                    .await?;
+                // Being rate limited is not a failure of the integration: the
+                // sync is deferred until the provider's budget is restored
+                if let Some(until) = integration_connection_service
+                    .save_sync_rate_limit(executor, integration_connection.id, rate_limit, Some(&e))
+                    .await?
+                {
+                    warn!(
+                        "{integration_provider_kind} API is rate limited for user {user_id} until {until}, deferring notifications sync"
+                    );
+                    return Err(UniversalInboxError::UpstreamRateLimited {
+                        provider_kind: integration_provider_kind,
+                        until,
+                    });
+                }
                 integration_connection_service
                     .error_notifications_sync_status(
                         executor,
@@ -1410,6 +1583,20 @@ impl NotificationService {
                 return Err(UniversalInboxError::Recoverable(e.into()));
             }
             Ok(notification_creation_results) => {
+                let rate_limit = sync_run_stats.rate_limit;
+                let sync_run = sync_run_stats.into_sync_run(
+                    integration_connection.id,
+                    IntegrationConnectionSyncRunKind::Notifications,
+                    sync_started_at,
+                    None,
+                );
+                metrics::record_sync_run(integration_provider_kind, &sync_run, None);
+                integration_connection_service
+                    .save_sync_run(executor, sync_run)
+                    .await?;
+                integration_connection_service
+                    .save_sync_rate_limit(executor, integration_connection.id, rate_limit, None)
+                    .await?;
                 integration_connection_service
                     .complete_notifications_sync_status(
                         executor,
@@ -1465,14 +1652,13 @@ impl NotificationService {
             .third_party_item_into_notification(&data, third_party_item, user_id)
             .await?;
         notification.task_id = task_id;
-        self.repository
-            .create_or_update_notification(
-                executor,
-                notification,
-                third_party_notification_service.get_notification_source_kind(),
-                third_party_notification_service.is_supporting_snoozed_notifications(),
-            )
-            .await
+        self.create_or_update_notification(
+            executor,
+            notification,
+            third_party_notification_service.get_notification_source_kind(),
+            third_party_notification_service.is_supporting_snoozed_notifications(),
+        )
+        .await
     }
 
     #[tracing::instrument(
@@ -1668,4 +1854,169 @@ impl NotificationService {
 
         Ok(updated_notification)
     }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            notification_id = notification_id.to_string(),
+            action = action.description(),
+            user.id = for_user_id.to_string()
+        ),
+        err
+    )]
+    pub async fn apply_github_pull_request_action(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        notification_id: NotificationId,
+        action: &GithubPullRequestAction,
+        for_user_id: UserId,
+    ) -> Result<UpdateStatus<Box<Notification>>, UniversalInboxError> {
+        let Some(notification) = self
+            .get_notification(executor, notification_id, for_user_id)
+            .await?
+        else {
+            return Ok(UpdateStatus {
+                updated: false,
+                result: None,
+            });
+        };
+
+        let ThirdPartyItemData::GithubNotification(github_notification) =
+            &notification.source_item.data
+        else {
+            return Err(UniversalInboxError::UnsupportedAction(format!(
+                "Cannot {} pull request from notification {notification_id}, expected Github notification",
+                action.description()
+            )));
+        };
+
+        let updated_github_notification = self
+            .github_service
+            .apply_pull_request_action(executor, github_notification, action, for_user_id)
+            .await?;
+
+        // Store the refreshed pull request data so that the preview reflects the action
+        let mut updated_third_party_item = notification.source_item.clone();
+        updated_third_party_item.data =
+            ThirdPartyItemData::GithubNotification(Box::new(updated_github_notification));
+        let updated_third_party_item = self
+            .third_party_item_service
+            .upgrade()
+            .context("Unable to access third_party_item_service from notification_service")?
+            .read()
+            .await
+            .create_or_update_third_party_item(executor, Box::new(updated_third_party_item))
+            .await?
+            .value();
+
+        Ok(UpdateStatus {
+            updated: true,
+            result: Some(Box::new(Notification {
+                source_item: *updated_third_party_item,
+                ..notification
+            })),
+        })
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            notification_id = notification_id.to_string(),
+            action = action.description(),
+            user.id = for_user_id.to_string()
+        ),
+        err
+    )]
+    pub async fn apply_linear_issue_action(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        notification_id: NotificationId,
+        action: &LinearIssueAction,
+        for_user_id: UserId,
+    ) -> Result<UpdateStatus<Box<Notification>>, UniversalInboxError> {
+        let Some(notification) = self
+            .get_notification(executor, notification_id, for_user_id)
+            .await?
+        else {
+            return Ok(UpdateStatus {
+                updated: false,
+                result: None,
+            });
+        };
+
+        let ThirdPartyItemData::LinearNotification(linear_notification) =
+            &notification.source_item.data
+        else {
+            return Err(UniversalInboxError::UnsupportedAction(format!(
+                "Cannot {} issue from notification {notification_id}, expected Linear notification",
+                action.description()
+            )));
+        };
+
+        let updated_linear_notification = self
+            .linear_service
+            .apply_issue_action(executor, linear_notification, action, for_user_id)
+            .await?;
+
+        // Store the refreshed issue data so that the preview reflects the action
+        let mut updated_third_party_item = notification.source_item.clone();
+        updated_third_party_item.data =
+            ThirdPartyItemData::LinearNotification(Box::new(updated_linear_notification));
+        let updated_third_party_item = self
+            .third_party_item_service
+            .upgrade()
+            .context("Unable to access third_party_item_service from notification_service")?
+            .read()
+            .await
+            .create_or_update_third_party_item(executor, Box::new(updated_third_party_item))
+            .await?
+            .value();
+
+        Ok(UpdateStatus {
+            updated: true,
+            result: Some(Box::new(Notification {
+                source_item: *updated_third_party_item,
+                ..notification
+            })),
+        })
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            notification_id = notification_id.to_string(),
+            user.id = for_user_id.to_string()
+        ),
+        err
+    )]
+    pub async fn get_linear_issue_team_details(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        notification_id: NotificationId,
+        for_user_id: UserId,
+    ) -> Result<Option<LinearTeamDetails>, UniversalInboxError> {
+        let Some(notification) = self
+            .get_notification(executor, notification_id, for_user_id)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let ThirdPartyItemData::LinearNotification(linear_notification) =
+            &notification.source_item.data
+        else {
+            return Err(UniversalInboxError::UnsupportedAction(format!(
+                "Cannot fetch issue team from notification {notification_id}, expected Linear notification"
+            )));
+        };
+
+        Ok(Some(
+            self.linear_service
+                .fetch_issue_team_details(executor, linear_notification, for_user_id)
+                .await?,
+        ))
+    }
 }
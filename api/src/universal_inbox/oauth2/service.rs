@@ -2,15 +2,19 @@ use std::sync::Arc;
 
 use anyhow::Context;
 use base64::prelude::*;
-use chrono::{TimeDelta, Utc};
-use jsonwebtoken::{EncodingKey, Header};
+use chrono::{DateTime, TimeDelta, Utc};
+use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation};
 use rand::RngExt;
 use ring::digest;
 use sqlx::{Postgres, Transaction};
 use uuid::Uuid;
 
 use universal_inbox::{
-    auth::oauth2::{AuthorizedOAuth2Client, OAuth2Client, OAuth2UserConsent, TokenResponse},
+    auth::oauth2::{
+        AuthorizedOAuth2Client, DeviceAuthorizationResponse, OAuth2Client,
+        OAuth2DeviceAuthorization, OAuth2DeviceAuthorizationStatus, OAuth2DeviceGrantError,
+        OAuth2Scope, OAuth2UserConsent, TokenIntrospectionResponse, TokenResponse,
+    },
     user::UserId,
 };
 
@@ -27,10 +31,19 @@ use crate::{
 const ACCESS_TOKEN_EXPIRY_SECS: u64 = 3600;
 const REFRESH_TOKEN_EXPIRY_DAYS: i64 = 30;
 const AUTH_CODE_EXPIRY_SECS: i64 = 60;
+const DEVICE_CODE_EXPIRY_SECS: u64 = 600;
+const DEVICE_CODE_POLLING_INTERVAL_SECS: i32 = 5;
+/// RFC 8628 §3.5: on `slow_down`, the polling interval increases by 5 seconds
+const DEVICE_CODE_SLOW_DOWN_INCREMENT_SECS: i32 = 5;
+/// RFC 8628 §6.1: base-20 alphabet without vowels (to avoid forming words)
+/// nor easily confused characters
+const USER_CODE_CHARSET: &[u8] = b"BCDFGHJKLMNPQRSTVWXZ";
+const USER_CODE_LENGTH: usize = 8;
 
 pub struct OAuth2Service {
     repository: Arc<Repository>,
     jwt_encoding_key: EncodingKey,
+    jwt_decoding_key: DecodingKey,
     resource_url: String,
     cimd_settings: CimdSettings,
 }
@@ -52,6 +65,7 @@ impl OAuth2Service {
         Self {
             repository,
             jwt_encoding_key: jwt_signing_keys.encoding_key.clone(),
+            jwt_decoding_key: jwt_signing_keys.decoding_key.clone(),
             resource_url,
             cimd_settings,
         }
@@ -288,41 +302,14 @@ impl OAuth2Service {
         // Verify PKCE: SHA-256(code_verifier) == code_challenge
         verify_pkce(code_verifier, &auth_code.code_challenge)?;
 
-        let scope = auth_code.scope.clone().unwrap_or_default();
-        let resource = auth_code.resource.as_deref().unwrap_or(&self.resource_url);
-
-        // Generate access token (JWT)
-        let access_token =
-            self.create_access_token(auth_code.user_id, &scope, client_id, resource)?;
-
-        // Generate refresh token
-        let refresh_token_raw = generate_random_token();
-        let refresh_token_hash = hash_token(&refresh_token_raw);
-
-        let refresh_expires_at = Utc::now()
-            + TimeDelta::try_days(REFRESH_TOKEN_EXPIRY_DAYS).unwrap_or_else(|| {
-                panic!("Invalid REFRESH_TOKEN_EXPIRY_DAYS value: {REFRESH_TOKEN_EXPIRY_DAYS}")
-            });
-
-        self.repository
-            .create_refresh_token(
-                transaction,
-                &refresh_token_hash,
-                client_id,
-                auth_code.user_id,
-                auth_code.scope.as_deref(),
-                auth_code.resource.as_deref(),
-                Some(refresh_expires_at),
-            )
-            .await?;
-
-        Ok(TokenResponse {
-            access_token,
-            token_type: "Bearer".to_string(),
-            expires_in: ACCESS_TOKEN_EXPIRY_SECS,
-            refresh_token: refresh_token_raw,
-            scope,
-        })
+        self.issue_tokens(
+            transaction,
+            auth_code.user_id,
+            client_id,
+            auth_code.scope.as_deref(),
+            auth_code.resource.as_deref(),
+        )
+        .await
     }
 
     /// Rotate a refresh token, atomically.
@@ -419,20 +406,220 @@ impl OAuth2Service {
             }
         };
 
-        let scope = stored_token.scope.clone().unwrap_or_default();
-        let resource = stored_token
-            .resource
-            .as_deref()
-            .unwrap_or(&self.resource_url);
+        self.issue_tokens(
+            transaction,
+            stored_token.user_id,
+            client_id,
+            stored_token.scope.as_deref(),
+            stored_token.resource.as_deref(),
+        )
+        .await
+    }
+
+    /// Start an RFC 8628 device authorization request. `verification_uri` is
+    /// the web page where the user enters the returned user code.
+    #[tracing::instrument(level = "debug", skip_all, fields(client_id), err)]
+    pub async fn create_device_authorization(
+        &self,
+        transaction: &mut Transaction<'_, Postgres>,
+        client_id: &str,
+        scope: Option<&str>,
+        resource: Option<&str>,
+        verification_uri: &str,
+    ) -> Result<DeviceAuthorizationResponse, UniversalInboxError> {
+        self.resolve_client(transaction, client_id)
+            .await?
+            .ok_or_else(|| UniversalInboxError::InvalidInputData {
+                source: None,
+                user_error: format!("Unknown client_id: {client_id}"),
+            })?;
+        let scope = Self::resolve_requested_scope(scope)?;
+
+        let device_code = generate_random_token();
+        let user_code = generate_user_code();
+        let expires_at = Utc::now()
+            + TimeDelta::try_seconds(DEVICE_CODE_EXPIRY_SECS as i64).unwrap_or_else(|| {
+                panic!("Invalid DEVICE_CODE_EXPIRY_SECS value: {DEVICE_CODE_EXPIRY_SECS}")
+            });
+
+        self.repository
+            .create_device_authorization(
+                transaction,
+                &hash_token(&device_code),
+                &user_code,
+                client_id,
+                Some(&scope),
+                resource,
+                DEVICE_CODE_POLLING_INTERVAL_SECS,
+                expires_at,
+            )
+            .await?;
+
+        let user_code = format_user_code(&user_code);
+        Ok(DeviceAuthorizationResponse {
+            device_code,
+            verification_uri_complete: format!(
+                "{verification_uri}?user_code={}",
+                urlencoding::encode(&user_code)
+            ),
+            verification_uri: verification_uri.to_string(),
+            user_code,
+            expires_in: DEVICE_CODE_EXPIRY_SECS,
+            interval: DEVICE_CODE_POLLING_INTERVAL_SECS as u64,
+        })
+    }
+
+    /// Find the pending device authorization a user typed the code of, in any
+    /// case and with or without separator.
+    #[tracing::instrument(level = "debug", skip_all, err)]
+    pub async fn get_pending_device_authorization(
+        &self,
+        transaction: &mut Transaction<'_, Postgres>,
+        user_code: &str,
+    ) -> Result<OAuth2DeviceAuthorization, UniversalInboxError> {
+        self.repository
+            .get_pending_device_authorization_by_user_code(
+                transaction,
+                &normalize_user_code(user_code),
+            )
+            .await?
+            .ok_or_else(|| UniversalInboxError::InvalidInputData {
+                source: None,
+                user_error: "Invalid or expired user code".to_string(),
+            })
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(user.id = user_id.to_string(), approved),
+        err
+    )]
+    pub async fn decide_device_authorization(
+        &self,
+        transaction: &mut Transaction<'_, Postgres>,
+        user_code: &str,
+        user_id: UserId,
+        approved: bool,
+    ) -> Result<(), UniversalInboxError> {
+        let status = if approved {
+            OAuth2DeviceAuthorizationStatus::Approved
+        } else {
+            OAuth2DeviceAuthorizationStatus::Denied
+        };
+        if !self
+            .repository
+            .decide_device_authorization(
+                transaction,
+                &normalize_user_code(user_code),
+                user_id,
+                status,
+            )
+            .await?
+        {
+            return Err(UniversalInboxError::InvalidInputData {
+                source: None,
+                user_error: "Invalid or expired user code".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Exchange a device code for tokens once the user approved the request
+    /// (RFC 8628 §3.4). Until then, polls fail with `authorization_pending`,
+    /// or `slow_down` when the client polls faster than the current interval,
+    /// which is then increased.
+    ///
+    /// Polling state is written even when an `OAuth2DeviceGrant` error is
+    /// returned: the caller must commit the transaction in that case.
+    #[tracing::instrument(level = "debug", skip_all, fields(client_id), err)]
+    pub async fn exchange_device_code(
+        &self,
+        transaction: &mut Transaction<'_, Postgres>,
+        device_code: &str,
+        client_id: &str,
+    ) -> Result<TokenResponse, UniversalInboxError> {
+        let device_code_hash = hash_token(device_code);
+        let Some(device_authorization) = self
+            .repository
+            .get_device_authorization_for_update(transaction, &device_code_hash, client_id)
+            .await?
+        else {
+            return Err(UniversalInboxError::OAuth2DeviceGrant(
+                OAuth2DeviceGrantError::InvalidGrant,
+            ));
+        };
 
-        // Generate new access token
-        let access_token =
-            self.create_access_token(stored_token.user_id, &scope, client_id, resource)?;
+        let now = Utc::now();
+        if device_authorization.expires_at <= now {
+            self.repository
+                .delete_device_authorization(transaction, &device_code_hash)
+                .await?;
+            return Err(UniversalInboxError::OAuth2DeviceGrant(
+                OAuth2DeviceGrantError::ExpiredToken,
+            ));
+        }
 
-        // Generate new refresh token
-        let new_refresh_token_raw = generate_random_token();
-        let new_refresh_token_hash = hash_token(&new_refresh_token_raw);
+        match (device_authorization.status, device_authorization.user_id) {
+            (OAuth2DeviceAuthorizationStatus::Approved, Some(user_id)) => {
+                self.repository
+                    .delete_device_authorization(transaction, &device_code_hash)
+                    .await?;
+                self.issue_tokens(
+                    transaction,
+                    user_id,
+                    client_id,
+                    device_authorization.scope.as_deref(),
+                    device_authorization.resource.as_deref(),
+                )
+                .await
+            }
+            (OAuth2DeviceAuthorizationStatus::Denied, _) => {
+                self.repository
+                    .delete_device_authorization(transaction, &device_code_hash)
+                    .await?;
+                Err(UniversalInboxError::OAuth2DeviceGrant(
+                    OAuth2DeviceGrantError::AccessDenied,
+                ))
+            }
+            _ => {
+                let (error, polling_interval_secs) = next_device_poll(
+                    device_authorization.last_polled_at,
+                    device_authorization.polling_interval_secs,
+                    now,
+                );
+                self.repository
+                    .record_device_authorization_poll(
+                        transaction,
+                        &device_code_hash,
+                        polling_interval_secs,
+                    )
+                    .await?;
+                Err(UniversalInboxError::OAuth2DeviceGrant(error))
+            }
+        }
+    }
 
+    /// Mint an access token and a new refresh token for `user_id`, storing
+    /// the latter.
+    async fn issue_tokens(
+        &self,
+        transaction: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        client_id: &str,
+        scope: Option<&str>,
+        resource: Option<&str>,
+    ) -> Result<TokenResponse, UniversalInboxError> {
+        let token_scope = scope.unwrap_or_default().to_string();
+        let (access_token, access_token_claims) = self.create_access_token(
+            user_id,
+            &token_scope,
+            client_id,
+            resource.unwrap_or(&self.resource_url),
+        )?;
+
+        let refresh_token_raw = generate_random_token();
+        let refresh_token_hash = hash_token(&refresh_token_raw);
         let refresh_expires_at = Utc::now()
             + TimeDelta::try_days(REFRESH_TOKEN_EXPIRY_DAYS).unwrap_or_else(|| {
                 panic!("Invalid REFRESH_TOKEN_EXPIRY_DAYS value: {REFRESH_TOKEN_EXPIRY_DAYS}")
@@ -441,12 +628,14 @@ impl OAuth2Service {
         self.repository
             .create_refresh_token(
                 transaction,
-                &new_refresh_token_hash,
+                &refresh_token_hash,
                 client_id,
-                stored_token.user_id,
-                stored_token.scope.as_deref(),
-                stored_token.resource.as_deref(),
+                user_id,
+                scope,
+                resource,
                 Some(refresh_expires_at),
+                Some(&access_token_claims.jti),
+                claims_expires_at(&access_token_claims),
             )
             .await?;
 
@@ -454,8 +643,8 @@ impl OAuth2Service {
             access_token,
             token_type: "Bearer".to_string(),
             expires_in: ACCESS_TOKEN_EXPIRY_SECS,
-            refresh_token: new_refresh_token_raw,
-            scope,
+            refresh_token: refresh_token_raw,
+            scope: token_scope,
         })
     }
 
@@ -532,15 +721,40 @@ impl OAuth2Service {
             .await
     }
 
-    /// Returns true when `stored_scope` covers every space-separated token in
-    /// `requested_scope` (treating both as sets). An empty requested scope is
-    /// always considered covered.
+    /// Returns true when `stored_scope` covers every scope in
+    /// `requested_scope` (treating both as sets, legacy `read` / `write`
+    /// scopes being expanded). An empty requested scope is always considered
+    /// covered.
     pub fn consent_covers_scope(stored_scope: &str, requested_scope: Option<&str>) -> bool {
-        let requested = requested_scope.unwrap_or("");
-        let stored_set: std::collections::HashSet<&str> = stored_scope.split_whitespace().collect();
-        requested
-            .split_whitespace()
-            .all(|token| stored_set.contains(token))
+        let stored_scopes = OAuth2Scope::parse_scopes_lossy(stored_scope);
+        OAuth2Scope::parse_scopes_lossy(requested_scope.unwrap_or("")).is_subset(&stored_scopes)
+    }
+
+    /// Validates the `scope` parameter of an authorization request. Clients
+    /// not requesting any scope are granted the read-only default scopes.
+    /// Fails with `InvalidInputData` when an unknown or API key only scope is
+    /// requested.
+    pub fn resolve_requested_scope(
+        requested_scope: Option<&str>,
+    ) -> Result<String, UniversalInboxError> {
+        let requested_scope = requested_scope.unwrap_or("").trim();
+        if requested_scope.is_empty() {
+            return Ok(OAuth2Scope::to_scope_string(&OAuth2Scope::DEFAULT));
+        }
+
+        let scopes = OAuth2Scope::parse_scopes(requested_scope).map_err(|unknown_scope| {
+            UniversalInboxError::InvalidInputData {
+                source: None,
+                user_error: format!("Unknown scope: {unknown_scope}"),
+            }
+        })?;
+        if let Some(api_key_only_scope) = scopes.iter().find(|scope| scope.is_api_key_only()) {
+            return Err(UniversalInboxError::InvalidInputData {
+                source: None,
+                user_error: format!("Scope {api_key_only_scope} can only be granted to API keys"),
+            });
+        }
+        Ok(requested_scope.to_string())
     }
 
     fn create_access_token(
@@ -549,7 +763,7 @@ impl OAuth2Service {
         scope: &str,
         client_id: &str,
         resource: &str,
-    ) -> Result<String, UniversalInboxError> {
+    ) -> Result<(String, Claims), UniversalInboxError> {
         let now = Utc::now();
         let expires_at = now
             + TimeDelta::try_seconds(ACCESS_TOKEN_EXPIRY_SECS as i64).unwrap_or_else(|| {
@@ -564,18 +778,213 @@ impl OAuth2Service {
             aud: Some(resource.to_string()),
             scope: Some(scope.to_string()),
             client_id: Some(client_id.to_string()),
+            notification_source_kinds: None,
         };
 
-        jsonwebtoken::encode(
+        let access_token = jsonwebtoken::encode(
             &Header::new(JWT_SIGNING_ALGO),
             &claims,
             &self.jwt_encoding_key,
         )
-        .context("Failed to encode OAuth2 access token")
-        .map_err(Into::into)
+        .context("Failed to encode OAuth2 access token")?;
+
+        Ok((access_token, claims))
+    }
+
+    /// Decode an OAuth2 access token issued by this server. Returns `None`
+    /// for anything else: refresh tokens, session or API key JWTs (which have
+    /// no `aud`), tokens with an invalid signature or expired tokens.
+    fn decode_access_token(&self, token: &str) -> Option<Claims> {
+        let mut validation = Validation::new(JWT_SIGNING_ALGO);
+        validation.validate_aud = false;
+        let claims = jsonwebtoken::decode::<Claims>(token, &self.jwt_decoding_key, &validation)
+            .ok()?
+            .claims;
+        (claims.aud.is_some() && claims.client_id.is_some()).then_some(claims)
+    }
+
+    /// Revoke a token per RFC 7009. `token` may be either an access token or
+    /// a refresh token issued to `client_id`:
+    /// - revoking a refresh token also revokes the access token issued
+    ///   alongside it
+    /// - revoking an access token only revokes that access token
+    ///
+    /// Unknown, invalid or foreign tokens are silently ignored as the
+    /// endpoint must respond identically in every case.
+    #[tracing::instrument(level = "debug", skip_all, fields(client_id), err)]
+    pub async fn revoke_token(
+        &self,
+        transaction: &mut Transaction<'_, Postgres>,
+        token: &str,
+        client_id: &str,
+    ) -> Result<(), UniversalInboxError> {
+        if let Some(claims) = self.decode_access_token(token) {
+            if claims.client_id.as_deref() == Some(client_id)
+                && let Some(expires_at) = claims_expires_at(&claims)
+            {
+                self.repository
+                    .revoke_access_token(transaction, &claims.jti, expires_at)
+                    .await?;
+            }
+            return Ok(());
+        }
+
+        let Some(refresh_token) = self
+            .repository
+            .revoke_refresh_token(transaction, &hash_token(token), client_id)
+            .await?
+        else {
+            return Ok(());
+        };
+
+        if let Some(jti) = refresh_token.access_token_jti
+            && let Some(expires_at) = refresh_token.access_token_expires_at
+            && expires_at > Utc::now()
+        {
+            self.repository
+                .revoke_access_token(transaction, &jti, expires_at)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Introspect a token per RFC 7662 for a resource server authenticated as
+    /// `user_id`. Only tokens of `user_id` issued to `client_id` are reported
+    /// as active.
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(client_id, user.id = user_id.to_string()),
+        err
+    )]
+    pub async fn introspect_token(
+        &self,
+        transaction: &mut Transaction<'_, Postgres>,
+        token: &str,
+        client_id: &str,
+        user_id: UserId,
+    ) -> Result<TokenIntrospectionResponse, UniversalInboxError> {
+        if let Some(claims) = self.decode_access_token(token) {
+            if claims.client_id.as_deref() != Some(client_id)
+                || claims.sub != user_id.to_string()
+                || self
+                    .repository
+                    .is_access_token_revoked(transaction, &claims.jti)
+                    .await?
+            {
+                return Ok(TokenIntrospectionResponse::inactive());
+            }
+
+            return Ok(TokenIntrospectionResponse {
+                active: true,
+                scope: claims.scope,
+                client_id: claims.client_id,
+                token_type: Some("Bearer".to_string()),
+                exp: Some(claims.exp as i64),
+                iat: Some(claims.iat as i64),
+                sub: Some(claims.sub),
+                aud: claims.aud,
+                jti: Some(claims.jti),
+            });
+        }
+
+        let Some(refresh_token) = self
+            .repository
+            .get_refresh_token_by_hash(transaction, &hash_token(token))
+            .await?
+        else {
+            return Ok(TokenIntrospectionResponse::inactive());
+        };
+        if refresh_token.client_id != client_id
+            || refresh_token.user_id != user_id
+            || refresh_token
+                .expires_at
+                .is_some_and(|expires_at| expires_at <= Utc::now())
+        {
+            return Ok(TokenIntrospectionResponse::inactive());
+        }
+
+        Ok(TokenIntrospectionResponse {
+            active: true,
+            scope: refresh_token.scope,
+            client_id: Some(refresh_token.client_id),
+            token_type: Some("refresh_token".to_string()),
+            exp: refresh_token
+                .expires_at
+                .map(|expires_at| expires_at.timestamp()),
+            iat: Some(refresh_token.created_at.timestamp()),
+            sub: Some(refresh_token.user_id.to_string()),
+            aud: Some(
+                refresh_token
+                    .resource
+                    .unwrap_or_else(|| self.resource_url.clone()),
+            ),
+            jti: None,
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all, err)]
+    pub async fn is_access_token_revoked(
+        &self,
+        transaction: &mut Transaction<'_, Postgres>,
+        jti: &str,
+    ) -> Result<bool, UniversalInboxError> {
+        self.repository
+            .is_access_token_revoked(transaction, jti)
+            .await
     }
 }
 
+fn claims_expires_at(claims: &Claims) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(claims.exp as i64, 0)
+}
+
+/// Outcome of a poll of a still pending device authorization: the error to
+/// return and the polling interval to use from now on.
+fn next_device_poll(
+    last_polled_at: Option<DateTime<Utc>>,
+    polling_interval_secs: i32,
+    now: DateTime<Utc>,
+) -> (OAuth2DeviceGrantError, i32) {
+    let too_early = last_polled_at.is_some_and(|last_polled_at| {
+        now - last_polled_at < TimeDelta::seconds(polling_interval_secs as i64)
+    });
+    if too_early {
+        (
+            OAuth2DeviceGrantError::SlowDown,
+            polling_interval_secs + DEVICE_CODE_SLOW_DOWN_INCREMENT_SECS,
+        )
+    } else {
+        (
+            OAuth2DeviceGrantError::AuthorizationPending,
+            polling_interval_secs,
+        )
+    }
+}
+
+fn generate_user_code() -> String {
+    let mut rng = rand::rng();
+    (0..USER_CODE_LENGTH)
+        .map(|_| USER_CODE_CHARSET[rng.random_range(0..USER_CODE_CHARSET.len())] as char)
+        .collect()
+}
+
+/// Display a normalized user code as two dash-separated halves (`BCDF-GHJK`).
+pub fn format_user_code(user_code: &str) -> String {
+    let (first_half, second_half) = user_code.split_at(user_code.len() / 2);
+    format!("{first_half}-{second_half}")
+}
+
+/// Normalize a user code typed by a user: separators and whitespace are
+/// dropped and letters upper-cased.
+pub fn normalize_user_code(user_code: &str) -> String {
+    user_code
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
 fn generate_random_token() -> String {
     let mut bytes = [0u8; 32];
     rand::rng().fill(&mut bytes);
@@ -711,6 +1120,109 @@ pub fn redirect_uri_matches_registered(registered: &[String], requested: &str) -
 mod tests {
     use super::*;
 
+    mod scopes {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn test_consent_covers_scope() {
+            assert!(OAuth2Service::consent_covers_scope("tasks:read sync", None));
+            assert!(OAuth2Service::consent_covers_scope(
+                "tasks:read sync",
+                Some("sync")
+            ));
+            assert!(!OAuth2Service::consent_covers_scope(
+                "tasks:read sync",
+                Some("tasks:write")
+            ));
+            // Legacy scopes are expanded on both sides
+            assert!(OAuth2Service::consent_covers_scope(
+                "read",
+                Some("notifications:read")
+            ));
+            assert!(OAuth2Service::consent_covers_scope(
+                "notifications:read tasks:read integrations:read",
+                Some("read")
+            ));
+        }
+
+        #[test]
+        fn test_resolve_requested_scope_defaults_to_read_only() {
+            assert_eq!(
+                OAuth2Service::resolve_requested_scope(None).unwrap(),
+                "notifications:read tasks:read integrations:read"
+            );
+            assert_eq!(
+                OAuth2Service::resolve_requested_scope(Some(" ")).unwrap(),
+                "notifications:read tasks:read integrations:read"
+            );
+        }
+
+        #[test]
+        fn test_resolve_requested_scope_rejects_unknown_scope() {
+            assert_eq!(
+                OAuth2Service::resolve_requested_scope(Some("tasks:write")).unwrap(),
+                "tasks:write"
+            );
+            assert!(matches!(
+                OAuth2Service::resolve_requested_scope(Some("tasks:write admin")),
+                Err(UniversalInboxError::InvalidInputData { .. })
+            ));
+        }
+
+        #[test]
+        fn test_resolve_requested_scope_rejects_api_key_only_scope() {
+            assert!(matches!(
+                OAuth2Service::resolve_requested_scope(Some("tasks:read tokens:introspect")),
+                Err(UniversalInboxError::InvalidInputData { .. })
+            ));
+        }
+    }
+
+    mod device_code {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn test_user_code_round_trip() {
+            let user_code = generate_user_code();
+            assert_eq!(user_code.len(), USER_CODE_LENGTH);
+            assert!(user_code.bytes().all(|c| USER_CODE_CHARSET.contains(&c)));
+
+            let displayed = format_user_code(&user_code);
+            assert_eq!(displayed.len(), USER_CODE_LENGTH + 1);
+            assert_eq!(normalize_user_code(&displayed), user_code);
+        }
+
+        #[test]
+        fn test_normalize_user_code() {
+            assert_eq!(normalize_user_code(" bcdf-ghjk "), "BCDFGHJK");
+            assert_eq!(normalize_user_code("BCDF GHJK"), "BCDFGHJK");
+        }
+
+        #[test]
+        fn test_next_device_poll() {
+            let now = Utc::now();
+            assert_eq!(
+                next_device_poll(None, 5, now),
+                (OAuth2DeviceGrantError::AuthorizationPending, 5)
+            );
+            assert_eq!(
+                next_device_poll(Some(now - TimeDelta::seconds(6)), 5, now),
+                (OAuth2DeviceGrantError::AuthorizationPending, 5)
+            );
+            // Polling faster than the interval slows the client down further
+            assert_eq!(
+                next_device_poll(Some(now - TimeDelta::seconds(2)), 5, now),
+                (OAuth2DeviceGrantError::SlowDown, 10)
+            );
+            assert_eq!(
+                next_device_poll(Some(now - TimeDelta::seconds(6)), 10, now),
+                (OAuth2DeviceGrantError::SlowDown, 15)
+            );
+        }
+    }
+
     /// Regression tests for `validate_redirect_uri`, pinning the scheme
     /// allow-list introduced in 88b30871 (security fix for
     /// universal-inbox-bkj.25). Only `https://...` and `http://` against
@@ -4,15 +4,17 @@ use std::{
 };
 
 use anyhow::{Context, anyhow};
-use apalis_redis::RedisStorage;
 use chrono::{DateTime, Utc};
 use sqlx::{Postgres, Transaction};
 use tokio::sync::RwLock;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use universal_inbox::{
     HasHtmlUrl, Page,
-    integration_connection::provider::{IntegrationProviderKind, IntegrationProviderSource},
+    integration_connection::{
+        provider::{IntegrationProviderKind, IntegrationProviderSource},
+        sync_run::IntegrationConnectionSyncRunKind,
+    },
     notification::{
         Notification, NotificationSource, NotificationStatus, service::NotificationPatch,
     },
@@ -22,7 +24,7 @@ use universal_inbox::{
         TaskSyncSourceKind, service::TaskPatch,
     },
     third_party::{
-        integrations::slack::SlackReaction,
+        integrations::{github::GithubTaskItem, slack::SlackReaction},
         item::{
             ThirdPartyItem, ThirdPartyItemData, ThirdPartyItemFromSource, ThirdPartyItemSource,
             ThirdPartyItemSourceKind,
@@ -33,6 +35,7 @@ use universal_inbox::{
 
 use crate::{
     integrations::{
+        github::GithubService,
         linear::LinearService,
         slack::SlackService,
         task::{ThirdPartyTaskService, ThirdPartyTaskSourceService},
@@ -40,12 +43,15 @@ use crate::{
         ticktick::TickTickService,
         todoist::TodoistService,
     },
-    jobs::UniversalInboxJob,
+    jobs::storage::JobStorage,
+    mcp::subscriptions::ResourceUpdatePublisher,
+    metrics,
     repository::{Repository, task::TaskRepository},
     universal_inbox::{
         UniversalInboxError, UpdateStatus, UpsertStatus,
-        integration_connection::service::{
-            IntegrationConnectionService, IntegrationConnectionSyncType,
+        integration_connection::{
+            service::{IntegrationConnectionService, IntegrationConnectionSyncType},
+            sync_run::collect_sync_run_stats,
         },
         notification::service::NotificationService,
         third_party::service::ThirdPartyItemService,
@@ -58,12 +64,14 @@ pub struct TaskService {
     pub todoist_service: Arc<TodoistService>,
     pub ticktick_service: Arc<TickTickService>,
     pub linear_service: Arc<LinearService>,
+    pub github_service: Arc<GithubService>,
     notification_service: Weak<RwLock<NotificationService>>,
     pub slack_service: Arc<SlackService>,
     integration_connection_service: Arc<RwLock<IntegrationConnectionService>>,
     user_service: Arc<UserService>,
     pub(super) third_party_item_service: Weak<RwLock<ThirdPartyItemService>>,
     min_sync_tasks_interval_in_minutes: i64,
+    resource_update_publisher: Option<ResourceUpdatePublisher>,
 }
 
 impl TaskService {
@@ -73,24 +81,28 @@ impl TaskService {
         todoist_service: Arc<TodoistService>,
         ticktick_service: Arc<TickTickService>,
         linear_service: Arc<LinearService>,
+        github_service: Arc<GithubService>,
         notification_service: Weak<RwLock<NotificationService>>,
         slack_service: Arc<SlackService>,
         integration_connection_service: Arc<RwLock<IntegrationConnectionService>>,
         user_service: Arc<UserService>,
         third_party_item_service: Weak<RwLock<ThirdPartyItemService>>,
         min_sync_tasks_interval_in_minutes: i64,
+        resource_update_publisher: Option<ResourceUpdatePublisher>,
     ) -> TaskService {
         TaskService {
             repository,
             todoist_service,
             ticktick_service,
             linear_service,
+            github_service,
             notification_service,
             slack_service,
             integration_connection_service,
             user_service,
             third_party_item_service,
             min_sync_tasks_interval_in_minutes,
+            resource_update_publisher,
         }
     }
 
@@ -98,6 +110,19 @@ impl TaskService {
         self.repository.begin().await
     }
 
+    /// Notify the MCP sessions subscribed to these tasks of their update
+    async fn publish_task_updates(
+        &self,
+        user_id: UserId,
+        task_ids: impl IntoIterator<Item = TaskId>,
+    ) {
+        if let Some(resource_update_publisher) = &self.resource_update_publisher {
+            resource_update_publisher
+                .publish_task_updates(user_id, task_ids)
+                .await;
+        }
+    }
+
     #[tracing::instrument(
         level = "debug",
         skip_all,
@@ -384,7 +409,7 @@ impl TaskService {
         status: TaskStatus,
         only_synced_tasks: bool,
         user_id: UserId,
-        job_storage: Option<RedisStorage<UniversalInboxJob>>,
+        job_storage: Option<JobStorage>,
     ) -> Result<Page<Task>, UniversalInboxError> {
         let tasks_page = self
             .repository
@@ -487,6 +512,7 @@ impl TaskService {
         }
 
         let task = self.repository.create_task(executor, task).await?;
+        self.publish_task_updates(for_user_id, [task.id]).await;
         Ok(Box::new(TaskCreationResult {
             task: *task,
             notifications: vec![], // notification.into_iter().map(|n| *n).collect(),
@@ -548,7 +574,101 @@ impl TaskService {
             + Sync,
         <T as TryFrom<ThirdPartyItem>>::Error: Send + Sync,
     {
-        let user_id = notification.user_id;
+        self.create_task_with_service(
+            executor,
+            &TaskCreation {
+                body: Some(format!(
+                    "- [{}]({})",
+                    notification.title,
+                    notification.get_html_url()
+                )),
+                ..(*task_creation).clone()
+            },
+            notification.user_id,
+            third_party_task_service,
+        )
+        .await
+    }
+
+    /// Task manager to create tasks in when none is explicitly chosen: the
+    /// user's default task manager, or Todoist
+    pub async fn get_default_task_provider_kind(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+    ) -> Result<IntegrationProviderKind, UniversalInboxError> {
+        Ok(self
+            .user_service
+            .get_user_preferences(executor, user_id)
+            .await?
+            .and_then(|preferences| preferences.default_task_manager_provider_kind)
+            .unwrap_or(IntegrationProviderKind::Todoist))
+    }
+
+    /// Create a task from scratch in a task manager, ie. not from a
+    /// notification, and synchronize it as a Universal Inbox task
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(user.id = user_id.to_string()),
+        err
+    )]
+    pub async fn create_task_in_task_manager(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        task_creation: &TaskCreation,
+        user_id: UserId,
+    ) -> Result<Box<Task>, UniversalInboxError> {
+        let task_provider_kind = match task_creation.task_provider_kind {
+            Some(task_provider_kind) => task_provider_kind,
+            None => {
+                self.get_default_task_provider_kind(executor, user_id)
+                    .await?
+            }
+        };
+
+        match task_provider_kind {
+            IntegrationProviderKind::Todoist => {
+                self.create_task_with_service(
+                    executor,
+                    task_creation,
+                    user_id,
+                    self.todoist_service.clone(),
+                )
+                .await
+            }
+            IntegrationProviderKind::TickTick => {
+                self.create_task_with_service(
+                    executor,
+                    task_creation,
+                    user_id,
+                    self.ticktick_service.clone(),
+                )
+                .await
+            }
+            _ => Err(UniversalInboxError::UnsupportedAction(format!(
+                "Task creation is not supported for {task_provider_kind}"
+            ))),
+        }
+    }
+
+    async fn create_task_with_service<T, U>(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        task_creation: &TaskCreation,
+        user_id: UserId,
+        third_party_task_service: Arc<U>,
+    ) -> Result<Box<Task>, UniversalInboxError>
+    where
+        T: TryFrom<ThirdPartyItem> + ThirdPartyItemFromSource + Debug,
+        U: ThirdPartyTaskSourceService<T>
+            + ThirdPartyTaskService<T>
+            + NotificationSource
+            + TaskSource
+            + Send
+            + Sync,
+        <T as TryFrom<ThirdPartyItem>>::Error: Send + Sync,
+    {
         let integration_provider_kind = third_party_task_service.get_integration_provider_kind();
         let Some(integration_connection) = self
             .integration_connection_service
@@ -564,24 +684,13 @@ impl TaskService {
             .await?
         else {
             return Err(UniversalInboxError::Unexpected(anyhow!(
-                "No validated {integration_provider_kind} integration found for user {user_id}, cannot create a task from the notification {}",
-                notification.id
+                "No validated {integration_provider_kind} integration found for user {user_id}, cannot create the task `{}`",
+                task_creation.title
             )));
         };
 
         let third_party_task = third_party_task_service
-            .create_task(
-                executor,
-                &TaskCreation {
-                    body: Some(format!(
-                        "- [{}]({})",
-                        notification.title,
-                        notification.get_html_url()
-                    )),
-                    ..(*task_creation).clone()
-                },
-                user_id,
-            )
+            .create_task(executor, task_creation, user_id)
             .await?;
 
         let third_party_item =
@@ -843,16 +952,42 @@ impl TaskService {
             .start_tasks_sync_status(executor, integration_provider_kind, user_id)
             .await?;
 
-        let task_creation_results = match sync_third_party_tasks(
+        let sync_started_at = Utc::now();
+        let (sync_result, sync_run_stats) = collect_sync_run_stats(sync_third_party_tasks(
             self,
             executor,
             third_party_task_service,
             user_id,
             integration_connection.last_tasks_sync_completed_at,
-        )
-        .await
-        {
+        ))
+        .await;
+        let task_creation_results = match sync_result {
             Err(e) => {
+                let rate_limit = sync_run_stats.rate_limit;
+                let sync_run = sync_run_stats.into_sync_run(
+                    integration_connection.id,
+                    IntegrationConnectionSyncRunKind::Tasks,
+                    sync_started_at,
+                    Some(&e),
+                );
+                metrics::record_sync_run(integration_provider_kind, &sync_run, Some(&e));
+                integration_connection_service
+                    .save_sync_run(executor, sync_run)
+                    .await?;
+                // Being rate limited is not a failure of the integration: the
+                // sync is deferred until the provider's budget is restored
+                if let Some(until) = integration_connection_service
+                    .save_sync_rate_limit(executor, integration_connection.id, rate_limit, Some(&e))
+                    .await?
+                {
+                    warn!(
+                        "{integration_provider_kind} API is rate limited for user {user_id} until {until}, deferring tasks sync"
+                    );
+                    return Err(UniversalInboxError::UpstreamRateLimited {
+                        provider_kind: integration_provider_kind,
+                        until,
+                    });
+                }
                 integration_connection_service
                     .error_tasks_sync_status(
                         executor,
@@ -864,6 +999,20 @@ impl TaskService {
                 return Err(UniversalInboxError::Recoverable(e.into()));
             }
             Ok(task_creation_results) => {
+                let rate_limit = sync_run_stats.rate_limit;
+                let sync_run = sync_run_stats.into_sync_run(
+                    integration_connection.id,
+                    IntegrationConnectionSyncRunKind::Tasks,
+                    sync_started_at,
+                    None,
+                );
+                metrics::record_sync_run(integration_provider_kind, &sync_run, None);
+                integration_connection_service
+                    .save_sync_run(executor, sync_run)
+                    .await?;
+                integration_connection_service
+                    .save_sync_rate_limit(executor, integration_connection.id, rate_limit, None)
+                    .await?;
                 integration_connection_service
                     .complete_tasks_sync_status(executor, integration_provider_kind, user_id)
                     .await?;
@@ -1012,9 +1161,7 @@ impl TaskService {
                 user_id,
             )
             .await?;
-        self.repository
-            .create_or_update_task(executor, task_request)
-            .await
+        self.create_or_update_task(executor, task_request).await
     }
 
     #[tracing::instrument(
@@ -1032,9 +1179,15 @@ impl TaskService {
         executor: &mut Transaction<'_, Postgres>,
         task_request: Box<CreateOrUpdateTaskRequest>,
     ) -> Result<UpsertStatus<Box<Task>>, UniversalInboxError> {
-        self.repository
+        let upsert_task = self
+            .repository
             .create_or_update_task(executor, task_request)
-            .await
+            .await?;
+        if let Some(task) = upsert_task.modified_value_ref() {
+            self.publish_task_updates(task.user_id, [task.id]).await;
+        }
+
+        Ok(upsert_task)
     }
 
     #[tracing::instrument(
@@ -1082,6 +1235,15 @@ impl TaskService {
                 )
                 .await
             }
+            TaskSyncSourceKind::Github => {
+                self.sync_third_party_tasks::<GithubTaskItem, GithubService>(
+                    executor,
+                    self.github_service.clone(),
+                    user_id,
+                    force_sync,
+                )
+                .await
+            }
         }
     }
 
@@ -1106,7 +1268,11 @@ impl TaskService {
                     .context(format!("Failed to commit while syncing {source:?}"))?;
                 Ok(tasks)
             }
-            Err(error @ UniversalInboxError::Recoverable(_)) => {
+            // Keep the sync status and rate limit recorded before failing
+            Err(
+                error @ (UniversalInboxError::Recoverable(_)
+                | UniversalInboxError::UpstreamRateLimited { .. }),
+            ) => {
                 transaction
                     .commit()
                     .await
@@ -1137,10 +1303,14 @@ impl TaskService {
         let sync_result_from_ticktick = self
             .sync_tasks_with_transaction(TaskSyncSourceKind::TickTick, user_id, force_sync)
             .await?;
+        let sync_result_from_github = self
+            .sync_tasks_with_transaction(TaskSyncSourceKind::Github, user_id, force_sync)
+            .await?;
         Ok(sync_result_from_todoist
             .into_iter()
             .chain(sync_result_from_linear)
             .chain(sync_result_from_ticktick)
+            .chain(sync_result_from_github)
             .collect())
     }
 
@@ -1209,6 +1379,13 @@ impl TaskService {
             .repository
             .update_task(executor, task_id, patch, for_user_id)
             .await?;
+        if let UpdateStatus {
+            updated: true,
+            result: Some(task),
+        } = &updated_task
+        {
+            self.publish_task_updates(for_user_id, [task.id]).await;
+        }
 
         if let UpdateStatus {
             updated: _,
@@ -1453,6 +1630,16 @@ impl TaskService {
                 )
                 .await
             }
+            ThirdPartyItemSourceKind::GithubTaskItem => {
+                self.apply_updated_task_side_effect::<GithubTaskItem, GithubService>(
+                    executor,
+                    self.github_service.clone(),
+                    patch,
+                    third_party_item,
+                    for_user_id,
+                )
+                .await
+            }
             kind => Err(UniversalInboxError::Unexpected(anyhow!(
                 "Cannot apply task side effect for third party item source kind {kind}"
             ))),
@@ -1 +1,2 @@
+pub mod retention;
 pub mod service;
@@ -0,0 +1,228 @@
+//! Retention policy of the third party items.
+//!
+//! The items of the notifications deleted or unsubscribed for a long time are
+//! purged (their notification being deleted along with them) and replaced by
+//! a [`ThirdPartyItemTombstone`]: when the upstream item is fetched again
+//! without new upstream activity, it is skipped instead of being re-created as
+//! a new notification. Items used by a task or by another item are never
+//! purged.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use ring::digest::{SHA256, digest};
+use serde::{Deserialize, Serialize};
+
+use universal_inbox::{
+    integration_connection::IntegrationConnectionId,
+    notification::NotificationStatus,
+    third_party::{
+        integrations::linear::LinearNotification,
+        item::{ThirdPartyItem, ThirdPartyItemData, ThirdPartyItemKind},
+    },
+    user::UserId,
+};
+
+use crate::configuration::RetentionPolicyCronSettings;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    pub deleted_notifications_retention_in_days: i64,
+    pub unsubscribed_notifications_retention_in_days: i64,
+    pub orphan_third_party_items_retention_in_days: i64,
+    #[serde(default = "default_dead_letter_jobs_retention_in_days")]
+    pub dead_letter_jobs_retention_in_days: i64,
+    pub batch_size: i64,
+}
+
+// Policies of the `ApplyRetentionPolicy` jobs enqueued before the dead-lettered
+// jobs were purged
+fn default_dead_letter_jobs_retention_in_days() -> i64 {
+    RetentionPolicyCronSettings::default().dead_letter_jobs_retention_in_days
+}
+
+impl From<&RetentionPolicyCronSettings> for RetentionPolicy {
+    fn from(settings: &RetentionPolicyCronSettings) -> Self {
+        Self {
+            deleted_notifications_retention_in_days: settings
+                .deleted_notifications_retention_in_days,
+            unsubscribed_notifications_retention_in_days: settings
+                .unsubscribed_notifications_retention_in_days,
+            orphan_third_party_items_retention_in_days: settings
+                .orphan_third_party_items_retention_in_days,
+            dead_letter_jobs_retention_in_days: settings.dead_letter_jobs_retention_in_days,
+            batch_size: settings.batch_size,
+        }
+    }
+}
+
+impl RetentionPolicy {
+    /// Notifications with `status` whose status did not change since the
+    /// returned date have their item purged
+    pub fn notification_status_updated_before(
+        &self,
+        status: NotificationStatus,
+        now: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        let retention_in_days = match status {
+            NotificationStatus::Deleted => self.deleted_notifications_retention_in_days,
+            NotificationStatus::Unsubscribed => self.unsubscribed_notifications_retention_in_days,
+            NotificationStatus::Unread | NotificationStatus::Read => return None,
+        };
+        Some(now - TimeDelta::days(retention_in_days))
+    }
+
+    pub fn orphan_third_party_items_updated_before(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now - TimeDelta::days(self.orphan_third_party_items_retention_in_days)
+    }
+
+    pub fn dead_letter_jobs_failed_before(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now - TimeDelta::days(self.dead_letter_jobs_retention_in_days)
+    }
+}
+
+/// Number of items purged, or to be purged on a dry run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct RetentionStats {
+    pub deleted_notification_items: u64,
+    pub unsubscribed_notification_items: u64,
+    pub orphan_items: u64,
+    pub dead_letter_jobs: u64,
+}
+
+impl RetentionStats {
+    pub fn add_notification_items(&mut self, status: NotificationStatus, count: u64) {
+        match status {
+            NotificationStatus::Deleted => self.deleted_notification_items += count,
+            NotificationStatus::Unsubscribed => self.unsubscribed_notification_items += count,
+            NotificationStatus::Unread | NotificationStatus::Read => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThirdPartyItemTombstone {
+    pub source_id: String,
+    pub kind: ThirdPartyItemKind,
+    pub integration_connection_id: IntegrationConnectionId,
+    pub user_id: UserId,
+    /// Status of the notification of the item when it was purged
+    pub notification_status: NotificationStatus,
+    /// Digest of the last upstream activity on the item when it was purged
+    pub activity_digest: Vec<u8>,
+    pub purged_at: DateTime<Utc>,
+}
+
+impl ThirdPartyItemTombstone {
+    pub fn new(
+        third_party_item: &ThirdPartyItem,
+        notification_status: NotificationStatus,
+        purged_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            source_id: third_party_item.source_id.clone(),
+            kind: third_party_item.kind(),
+            integration_connection_id: third_party_item.integration_connection_id,
+            user_id: third_party_item.user_id,
+            notification_status,
+            activity_digest: third_party_item_activity_digest(&third_party_item.data),
+            purged_at,
+        }
+    }
+
+    pub fn is_for(&self, third_party_item: &ThirdPartyItem) -> bool {
+        self.source_id == third_party_item.source_id
+            && self.kind == third_party_item.kind()
+            && self.integration_connection_id == third_party_item.integration_connection_id
+    }
+
+    /// An item without new upstream activity is skipped whatever the status
+    /// of its notification. New upstream activity is skipped only if the user
+    /// unsubscribed from the item.
+    pub fn should_skip(&self, third_party_item: &ThirdPartyItem) -> bool {
+        self.notification_status == NotificationStatus::Unsubscribed
+            || third_party_item_activity_digest(&third_party_item.data) == self.activity_digest
+    }
+}
+
+fn third_party_item_activity_digest(data: &ThirdPartyItemData) -> Vec<u8> {
+    digest(&SHA256, third_party_item_activity_marker(data).as_bytes())
+        .as_ref()
+        .to_vec()
+}
+
+/// Identifies the last upstream activity on an item. Unlike the item data, it
+/// does not change along with the details fetched on each synchronization
+/// (check runs, reviews, issue state, reactions, ...).
+fn third_party_item_activity_marker(data: &ThirdPartyItemData) -> String {
+    // tag: New notification integration
+    match data {
+        ThirdPartyItemData::GithubNotification(notification) => {
+            notification.updated_at.to_rfc3339()
+        }
+        ThirdPartyItemData::LinearNotification(notification) => match notification.as_ref() {
+            LinearNotification::IssueNotification { updated_at, .. }
+            | LinearNotification::ProjectNotification { updated_at, .. } => updated_at.to_rfc3339(),
+        },
+        ThirdPartyItemData::SlackReaction(reaction) => reaction.created_at.to_rfc3339(),
+        ThirdPartyItemData::SlackThread(thread) => thread.messages.last().origin.ts.to_string(),
+        ThirdPartyItemData::GoogleMailThread(thread) => thread
+            .messages
+            .last()
+            .map(|message| message.id.clone())
+            .unwrap_or_default(),
+        ThirdPartyItemData::GoogleCalendarEvent(event) => event.updated.to_rfc3339(),
+        ThirdPartyItemData::GoogleDriveComment(comment) => comment.modified_time.to_rfc3339(),
+        ThirdPartyItemData::WebPage(web_page) => web_page.timestamp.to_rfc3339(),
+        // Task items are never purged as they are the source of a task
+        ThirdPartyItemData::TodoistItem(item) => item.added_at.to_rfc3339(),
+        ThirdPartyItemData::TickTickItem(item) => item
+            .modified_time
+            .or(item.created_time)
+            .map(|modified_time| modified_time.to_rfc3339())
+            .unwrap_or_default(),
+        ThirdPartyItemData::LinearIssue(issue) => issue.updated_at.to_rfc3339(),
+        ThirdPartyItemData::GithubTaskItem(item) => item.updated_at.to_rfc3339(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn policy() -> RetentionPolicy {
+        RetentionPolicy {
+            deleted_notifications_retention_in_days: 30,
+            unsubscribed_notifications_retention_in_days: 90,
+            orphan_third_party_items_retention_in_days: 7,
+            dead_letter_jobs_retention_in_days: 14,
+            batch_size: 100,
+        }
+    }
+
+    #[test]
+    fn test_notification_status_updated_before() {
+        let now = Utc.with_ymd_and_hms(2026, 8, 31, 3, 0, 0).unwrap();
+
+        assert_eq!(
+            policy().notification_status_updated_before(NotificationStatus::Deleted, now),
+            Some(Utc.with_ymd_and_hms(2026, 8, 1, 3, 0, 0).unwrap())
+        );
+        assert_eq!(
+            policy().notification_status_updated_before(NotificationStatus::Unsubscribed, now),
+            Some(Utc.with_ymd_and_hms(2026, 6, 2, 3, 0, 0).unwrap())
+        );
+        assert_eq!(
+            policy().notification_status_updated_before(NotificationStatus::Unread, now),
+            None
+        );
+        assert_eq!(
+            policy().orphan_third_party_items_updated_before(now),
+            Utc.with_ymd_and_hms(2026, 8, 24, 3, 0, 0).unwrap()
+        );
+        assert_eq!(
+            policy().dead_letter_jobs_failed_before(now),
+            Utc.with_ymd_and_hms(2026, 8, 17, 3, 0, 0).unwrap()
+        );
+    }
+}
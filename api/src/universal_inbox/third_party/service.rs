@@ -11,6 +11,7 @@ use tokio::sync::RwLock;
 
 use universal_inbox::{
     integration_connection::provider::{IntegrationProviderKind, IntegrationProviderSource},
+    notification::NotificationStatus,
     task::{Task, TaskCreation, service::TaskPatch},
     third_party::{
         integrations::slack::SlackReaction,
@@ -32,8 +33,12 @@ use crate::{
     repository::{Repository, third_party::ThirdPartyItemRepository, user::UserRepository},
     universal_inbox::{
         UniversalInboxError, UpsertStatus,
-        integration_connection::service::IntegrationConnectionService,
-        notification::service::NotificationService, task::service::TaskService,
+        integration_connection::{
+            service::IntegrationConnectionService, sync_run::record_sync_run_stats,
+        },
+        notification::service::NotificationService,
+        task::service::TaskService,
+        third_party::retention::ThirdPartyItemTombstone,
     },
 };
 
@@ -319,8 +324,16 @@ impl ThirdPartyItemService {
         }
 
         let kind = third_party_service.get_third_party_item_source_kind();
-        let items = third_party_service
-            .fetch_items(executor, user_id, last_sync_completed_at)
+        let Some(items) = third_party_service
+            .fetch_items_if_modified(executor, user_id, last_sync_completed_at)
+            .await?
+        else {
+            debug!("{kind} third party items of user {user_id} are unchanged, skipping sync");
+            return Ok(vec![]);
+        };
+        record_sync_run_stats(|stats| stats.items_fetched += items.len() as u32);
+        let items = self
+            .filter_out_purged_items(executor, items, user_id)
             .await?;
         let mut upserted_third_party_items = vec![];
 
@@ -329,6 +342,13 @@ impl ThirdPartyItemService {
             let upsert_result = self
                 .create_or_update_third_party_item(executor, Box::new(item))
                 .await?;
+            match upsert_result {
+                UpsertStatus::Created(_) => record_sync_run_stats(|stats| stats.items_created += 1),
+                UpsertStatus::Updated { .. } => {
+                    record_sync_run_stats(|stats| stats.items_updated += 1)
+                }
+                UpsertStatus::Untouched(_) => {}
+            }
 
             upserted_third_party_items.push(*upsert_result.value());
         }
@@ -355,6 +375,9 @@ impl ThirdPartyItemService {
                     .await?;
                 let third_party_items_to_mark_as_done_count =
                     third_party_items_to_mark_as_done.len();
+                record_sync_run_stats(|stats| {
+                    stats.items_marked_stale += third_party_items_to_mark_as_done_count as u32
+                });
                 debug!(
                     "Marking {third_party_items_to_mark_as_done_count} stale third party items as done",
                 );
@@ -375,7 +398,8 @@ impl ThirdPartyItemService {
                     "Marked {third_party_items_to_mark_as_done_count} {kind} stale third party items as done"
                 );
             } else if let Ok(notification_source_kind) = kind.try_into() {
-                self.notification_service
+                let deleted_notifications = self
+                    .notification_service
                     .upgrade()
                     .context("Unable to access notification_service from third_party_service")?
                     .read()
@@ -387,6 +411,9 @@ impl ThirdPartyItemService {
                         user_id,
                     )
                     .await?;
+                record_sync_run_stats(|stats| {
+                    stats.items_marked_stale += deleted_notifications.len() as u32
+                });
             } else {
                 return Err(anyhow!(
                     "Cannot mark stale third party items as done for {kind}, neither a TaskSource nor a NotificationSource"
@@ -555,6 +582,142 @@ impl ThirdPartyItemService {
         Ok(Box::new(*uptodate_sink_party_item))
     }
 
+    /// Drop the fetched items purged by the retention policy which must not be
+    /// re-created (see [`ThirdPartyItemTombstone::should_skip`]). The tombstone
+    /// of the other ones is removed as they are stored again.
+    async fn filter_out_purged_items(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        items: Vec<ThirdPartyItem>,
+        user_id: UserId,
+    ) -> Result<Vec<ThirdPartyItem>, UniversalInboxError> {
+        if items.is_empty() {
+            return Ok(items);
+        }
+
+        let source_ids = items
+            .iter()
+            .map(|item| item.source_id.clone())
+            .collect::<Vec<_>>();
+        let tombstones = self
+            .repository
+            .find_third_party_item_tombstones(executor, &source_ids, user_id)
+            .await?;
+        if tombstones.is_empty() {
+            return Ok(items);
+        }
+
+        let mut kept_items = Vec::with_capacity(items.len());
+        for item in items {
+            if let Some(tombstone) = tombstones.iter().find(|tombstone| tombstone.is_for(&item)) {
+                if tombstone.should_skip(&item) {
+                    debug!(
+                        "Skipping {} third party item {} for user {user_id} purged by the retention policy",
+                        item.kind(),
+                        item.source_id
+                    );
+                    continue;
+                }
+                self.repository
+                    .delete_third_party_item_tombstone(executor, tombstone)
+                    .await?;
+            }
+            kept_items.push(item);
+        }
+        Ok(kept_items)
+    }
+
+    pub async fn count_purgeable_notification_third_party_items(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        notification_status: NotificationStatus,
+        status_updated_before: DateTime<Utc>,
+    ) -> Result<u64, UniversalInboxError> {
+        let count = self
+            .repository
+            .count_purgeable_notification_third_party_items(
+                executor,
+                notification_status,
+                status_updated_before,
+            )
+            .await?;
+        Ok(count as u64)
+    }
+
+    /// Purge up to `batch_size` items of the notifications with
+    /// `notification_status` since before `status_updated_before`, their
+    /// notifications being deleted along with them, and leave a tombstone for
+    /// each of them. Returns the number of purged items.
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(notification_status = notification_status.to_string(), batch_size),
+        err
+    )]
+    pub async fn purge_notification_third_party_items(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        notification_status: NotificationStatus,
+        status_updated_before: DateTime<Utc>,
+        batch_size: i64,
+    ) -> Result<u64, UniversalInboxError> {
+        let third_party_items = self
+            .repository
+            .fetch_purgeable_notification_third_party_items(
+                executor,
+                notification_status,
+                status_updated_before,
+                batch_size,
+            )
+            .await?;
+        if third_party_items.is_empty() {
+            return Ok(0);
+        }
+
+        let purged_at = Utc::now();
+        let tombstones = third_party_items
+            .iter()
+            .map(|item| ThirdPartyItemTombstone::new(item, notification_status, purged_at))
+            .collect::<Vec<_>>();
+        self.repository
+            .create_or_update_third_party_item_tombstones(executor, &tombstones)
+            .await?;
+
+        let third_party_item_ids = third_party_items
+            .iter()
+            .map(|item| item.id)
+            .collect::<Vec<_>>();
+        self.repository
+            .delete_third_party_items(executor, &third_party_item_ids)
+            .await
+    }
+
+    pub async fn count_orphan_third_party_items(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        updated_before: DateTime<Utc>,
+    ) -> Result<u64, UniversalInboxError> {
+        let count = self
+            .repository
+            .count_orphan_third_party_items(executor, updated_before)
+            .await?;
+        Ok(count as u64)
+    }
+
+    /// Purge up to `batch_size` items without notification nor task not
+    /// updated since before `updated_before`. Returns the number of purged
+    /// items.
+    pub async fn purge_orphan_third_party_items(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        updated_before: DateTime<Utc>,
+        batch_size: i64,
+    ) -> Result<u64, UniversalInboxError> {
+        self.repository
+            .delete_orphan_third_party_items(executor, updated_before, batch_size)
+            .await
+    }
+
     pub async fn has_third_party_item_for_source_id(
         &self,
         executor: &mut Transaction<'_, Postgres>,
@@ -5,7 +5,9 @@ use secrecy::SecretBox;
 use serde::{Deserialize, Serialize};
 use universal_inbox::{
     auth::AuthIdToken,
-    user::{PasswordHash, UserAuthKind, UserAuthMethod, UserAuthMethodDisplayInfo, Username},
+    user::{
+        PasswordHash, UserAuthKind, UserAuthMethod, UserAuthMethodDisplayInfo, UserId, Username,
+    },
 };
 use webauthn_rs::prelude::*;
 
@@ -15,6 +17,7 @@ pub enum UserAuth {
     OIDCGoogleAuthorizationCode(Box<OpenIdConnectUserAuth>),
     OIDCAuthorizationCodePKCE(Box<OpenIdConnectUserAuth>),
     Passkey(Box<PasskeyUserAuth>),
+    SAML(Box<SAMLUserAuth>),
 }
 
 impl fmt::Display for UserAuth {
@@ -27,6 +30,7 @@ impl fmt::Display for UserAuth {
                 UserAuth::OIDCGoogleAuthorizationCode(_) => "OIDCGoogleAuthorizationCode",
                 UserAuth::OIDCAuthorizationCodePKCE(_) => "OIDCAuthorizationCodePKCE",
                 UserAuth::Passkey(_) => "Passkey",
+                UserAuth::SAML(_) => "SAML",
             }
         )
     }
@@ -39,6 +43,7 @@ impl UserAuth {
             UserAuth::OIDCGoogleAuthorizationCode(_) => UserAuthKind::OIDCGoogleAuthorizationCode,
             UserAuth::OIDCAuthorizationCodePKCE(_) => UserAuthKind::OIDCAuthorizationCodePKCE,
             UserAuth::Passkey(_) => UserAuthKind::Passkey,
+            UserAuth::SAML(_) => UserAuthKind::SAML,
         }
     }
 }
@@ -57,6 +62,7 @@ impl From<&UserAuth> for UserAuthMethod {
             UserAuth::Passkey(passkey_auth) => UserAuthMethodDisplayInfo::Passkey {
                 username: passkey_auth.username.to_string(),
             },
+            UserAuth::SAML(_) => UserAuthMethodDisplayInfo::SAML,
         };
         UserAuthMethod { kind, display_info }
     }
@@ -68,6 +74,38 @@ pub struct PasskeyUserAuth {
     pub passkey: Passkey,
 }
 
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Eq)]
+pub struct SAMLUserAuth {
+    /// `NameID` identifying the user in the SAML identity provider
+    pub name_id: AuthUserId,
+}
+
+/// Authentication request sent to the SAML identity provider, awaiting its response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SAMLAuthnRequest {
+    pub id: String,
+    /// Set when linking a SAML identity to this existing user
+    pub linking_user_id: Option<UserId>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Last time a user used one of their sessions, `None` if they never did
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserActivity {
+    pub user_id: UserId,
+    pub last_active_at: Option<DateTime<Utc>>,
+}
+
+/// TOTP second factor of a user's local authentication
+#[derive(Debug, Clone)]
+pub struct TotpUserAuth {
+    /// Secret encrypted with the token encryption key, bound to the user ID
+    pub encrypted_secret: Vec<u8>,
+    /// `None` until the enrollment is confirmed with a first valid code
+    pub enabled_at: Option<DateTime<Utc>>,
+    pub last_used_step: Option<i64>,
+}
+
 #[derive(Debug, Clone)]
 pub struct LocalUserAuth {
     pub password_hash: SecretBox<PasswordHash>,
@@ -2,7 +2,7 @@ use std::{str::FromStr, sync::Arc};
 
 use anyhow::{Context, anyhow};
 use argon2::{Argon2, Params, PasswordHasher, PasswordVerifier, password_hash::SaltString};
-use chrono::Utc;
+use chrono::{TimeDelta, Utc};
 use email_address::EmailAddress;
 use openidconnect::{
     AccessToken, AuthorizationCode, CsrfToken, EmptyAdditionalClaims, EndSessionUrl, IdToken,
@@ -24,16 +24,16 @@ use webauthn_rs::prelude::*;
 use universal_inbox::{
     auth::openidconnect::OpenidConnectProvider,
     user::{
-        Credentials, EmailValidationToken, Password, PasswordHash, PasswordResetToken, User,
-        UserAuthKind, UserAuthMethod, UserId, UserPatch, UserPreferences, UserPreferencesPatch,
-        Username,
+        Credentials, EmailValidationToken, Password, PasswordHash, PasswordResetToken,
+        RecoveryCodes, TotpEnrollment, TotpStatus, User, UserAuthKind, UserAuthMethod, UserId,
+        UserPatch, UserPreferences, UserPreferencesPatch, Username,
     },
 };
 
 use crate::{
     configuration::{
         ApplicationSettings, AuthenticationSettings, OIDCAuthorizationCodePKCEFlowSettings,
-        OIDCFlowSettings, OpenIDConnectSettings,
+        OIDCFlowSettings, OpenIDConnectSettings, SAMLSettings,
     },
     mailer::{EmailTemplate, Mailer},
     observability::spawn_blocking_with_tracing,
@@ -42,13 +42,37 @@ use crate::{
     repository::user_preferences::UserPreferencesRepository,
     universal_inbox::{
         UniversalInboxError, UpdateStatus,
+        auth_token::service::AuthenticationTokenService,
         user::model::{
-            AuthUserId, LocalUserAuth, OpenIdConnectUserAuth, PasskeyUserAuth, UserAuth,
+            AuthUserId, LocalUserAuth, OpenIdConnectUserAuth, PasskeyUserAuth, SAMLAuthnRequest,
+            SAMLUserAuth, TotpUserAuth, UserAuth,
+        },
+    },
+    utils::{
+        crypto::{
+            SecretRotationBatch, TokenEncryptionKeyRing, decrypt_token, encrypt_token,
+            reencrypt_token,
+        },
+        login_throttle::LoginThrottle,
+        saml::{
+            SAML_AUTHN_REQUEST_TTL_IN_MINUTES, SAMLIdentity, SAMLResponse,
+            SAMLResponseExpectations, build_authn_request_url, generate_saml_request_id,
+        },
+        totp::{
+            build_totp, generate_recovery_codes, generate_totp_secret, hash_recovery_code,
+            is_totp_code, verify_totp_code,
         },
     },
-    utils::login_throttle::LoginThrottle,
 };
 
+/// Outcome of a valid password login
+#[derive(Debug)]
+pub enum PasswordLoginOutcome {
+    LoggedIn(User),
+    /// The user enabled TOTP: the login must be completed with a second factor
+    TotpRequired(User),
+}
+
 pub struct UserService {
     repository: Arc<Repository>,
     application_settings: ApplicationSettings,
@@ -57,6 +81,9 @@ pub struct UserService {
     /// Per-account login throttle. `None` when local password auth is not
     /// configured (nothing to throttle) or when Redis is unavailable at startup.
     login_throttle: Option<LoginThrottle>,
+    /// Key encrypting the TOTP secrets at rest
+    token_encryption_keys: SecretBox<TokenEncryptionKeyRing>,
+    authentication_token_service: Arc<RwLock<AuthenticationTokenService>>,
 }
 
 impl UserService {
@@ -66,6 +93,8 @@ impl UserService {
         mailer: Arc<RwLock<dyn Mailer + Send + Sync>>,
         webauthn: Arc<Webauthn>,
         login_throttle: Option<LoginThrottle>,
+        token_encryption_keys: SecretBox<TokenEncryptionKeyRing>,
+        authentication_token_service: Arc<RwLock<AuthenticationTokenService>>,
     ) -> UserService {
         UserService {
             repository,
@@ -73,6 +102,8 @@ impl UserService {
             mailer,
             webauthn,
             login_throttle,
+            token_encryption_keys,
+            authentication_token_service,
         }
     }
 
@@ -298,10 +329,342 @@ impl UserService {
         self.repository
             .delete_user_auth(executor, user_id, kind)
             .await?;
+        // The TOTP is the second factor of password logins only
+        if kind == UserAuthKind::Local {
+            self.repository.delete_totp_auth(executor, user_id).await?;
+        }
+
+        Ok(())
+    }
+
+    // --- TOTP second factor ---
+
+    #[tracing::instrument(level = "debug", skip_all, fields(user.id = user_id.to_string()), err)]
+    pub async fn get_totp_status(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+    ) -> Result<TotpStatus, UniversalInboxError> {
+        let enabled_at = self
+            .repository
+            .get_totp_auth(executor, user_id, false)
+            .await?
+            .and_then(|totp_auth| totp_auth.enabled_at);
+        let remaining_recovery_codes = if enabled_at.is_some() {
+            self.repository
+                .count_unused_recovery_codes(executor, user_id)
+                .await?
+        } else {
+            0
+        };
+
+        Ok(TotpStatus {
+            enabled: enabled_at.is_some(),
+            enabled_at,
+            remaining_recovery_codes,
+        })
+    }
+
+    /// Generate a new TOTP secret for the user. It is only enforced once
+    /// confirmed with [`UserService::confirm_totp_enrollment`]; restarting an
+    /// enrollment replaces the pending secret.
+    #[tracing::instrument(level = "debug", skip_all, fields(user.id = user_id.to_string()), err)]
+    pub async fn start_totp_enrollment(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+    ) -> Result<TotpEnrollment, UniversalInboxError> {
+        if self
+            .repository
+            .get_user_auth(executor, user_id, UserAuthKind::Local)
+            .await?
+            .is_none()
+        {
+            return Err(UniversalInboxError::InvalidInputData {
+                source: None,
+                user_error: "Two-factor authentication requires a password authentication method"
+                    .to_string(),
+            });
+        }
+        let user = self
+            .repository
+            .get_user(executor, user_id)
+            .await?
+            .ok_or_else(|| UniversalInboxError::ItemNotFound(format!("Unknown user {user_id}")))?;
+        let account_name = user
+            .email
+            .map(|email| email.to_string())
+            .unwrap_or_else(|| user_id.to_string());
+
+        let totp = build_totp(generate_totp_secret(), &account_name)?;
+        let secret = totp.get_secret_base32();
+        let encrypted_secret = encrypt_token(
+            &secret,
+            user_id.0.as_bytes(),
+            self.token_encryption_keys.expose_secret(),
+        )?;
+        if !self
+            .repository
+            .save_pending_totp_auth(executor, user_id, &encrypted_secret)
+            .await?
+        {
+            return Err(UniversalInboxError::AlreadyExists {
+                source: None,
+                id: user_id.0,
+            });
+        }
+
+        Ok(TotpEnrollment {
+            provisioning_uri: totp.get_url(),
+            secret,
+        })
+    }
+
+    /// Enable the pending TOTP of the user with a first valid `code` and
+    /// return their recovery codes.
+    #[tracing::instrument(level = "debug", skip_all, fields(user.id = user_id.to_string()), err)]
+    pub async fn confirm_totp_enrollment(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        code: &str,
+    ) -> Result<RecoveryCodes, UniversalInboxError> {
+        let totp_auth = match self
+            .repository
+            .get_totp_auth(executor, user_id, true)
+            .await?
+        {
+            Some(totp_auth) if totp_auth.enabled_at.is_some() => {
+                return Err(UniversalInboxError::AlreadyExists {
+                    source: None,
+                    id: user_id.0,
+                });
+            }
+            Some(totp_auth) => totp_auth,
+            None => {
+                return Err(UniversalInboxError::ItemNotFound(format!(
+                    "No pending TOTP enrollment for user {user_id}"
+                )));
+            }
+        };
+
+        let totp = build_totp(self.decrypt_totp_secret(user_id, &totp_auth)?, "")?;
+        let Some(used_step) = verify_totp_code(&totp, code, Utc::now(), None) else {
+            return Err(UniversalInboxError::InvalidInputData {
+                source: None,
+                user_error: "Invalid verification code".to_string(),
+            });
+        };
+        self.repository
+            .enable_totp_auth(executor, user_id, used_step)
+            .await?;
+
+        self.replace_recovery_codes(executor, user_id).await
+    }
 
+    /// Replace the recovery codes of the user, after checking a second factor
+    #[tracing::instrument(level = "debug", skip_all, fields(user.id = user_id.to_string()), err)]
+    pub async fn regenerate_recovery_codes(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        code: &str,
+    ) -> Result<RecoveryCodes, UniversalInboxError> {
+        if !self.verify_second_factor(executor, user_id, code).await? {
+            return Err(UniversalInboxError::InvalidInputData {
+                source: None,
+                user_error: "Invalid verification code".to_string(),
+            });
+        }
+
+        self.replace_recovery_codes(executor, user_id).await
+    }
+
+    /// Disable the TOTP of the user, after checking a second factor
+    #[tracing::instrument(level = "debug", skip_all, fields(user.id = user_id.to_string()), err)]
+    pub async fn disable_totp(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        code: &str,
+    ) -> Result<(), UniversalInboxError> {
+        if !self.verify_second_factor(executor, user_id, code).await? {
+            return Err(UniversalInboxError::InvalidInputData {
+                source: None,
+                user_error: "Invalid verification code".to_string(),
+            });
+        }
+
+        self.repository.delete_totp_auth(executor, user_id).await?;
         Ok(())
     }
 
+    /// Complete a password login with a TOTP or recovery `code`, applying the
+    /// same per-account throttling as the password step: failures count
+    /// towards the lockout and trigger the lockout email.
+    #[tracing::instrument(level = "debug", skip_all, fields(user.id = user_id.to_string()), err)]
+    pub async fn validate_second_factor(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        code: &str,
+    ) -> Result<User, UniversalInboxError> {
+        let user = self
+            .repository
+            .get_user(executor, user_id)
+            .await?
+            .ok_or_else(|| UniversalInboxError::Unauthorized(anyhow!("Unknown user")))?;
+
+        if let Some(throttle) = &self.login_throttle
+            && let Some(email) = &user.email
+        {
+            match throttle.locked_for(email).await {
+                Ok(Some(retry_after_seconds)) => {
+                    return Err(UniversalInboxError::TooManyLoginAttempts {
+                        retry_after_seconds,
+                    });
+                }
+                Ok(None) => {}
+                Err(err) => warn!("Login throttle check failed, allowing attempt: {err:?}"),
+            }
+        }
+
+        if self.verify_second_factor(executor, user_id, code).await? {
+            if let Some(throttle) = &self.login_throttle
+                && let Some(email) = &user.email
+                && let Err(err) = throttle.reset(email).await
+            {
+                warn!("Failed to reset login throttle after successful login: {err:?}");
+            }
+            return Ok(user);
+        }
+
+        if let Some(email) = &user.email {
+            self.record_failed_login(executor, email, true).await;
+        }
+        // Not a 401: it would reset the session cookie and drop the pending
+        // login along with it, forcing a new password step after a typo
+        Err(UniversalInboxError::InvalidInputData {
+            source: None,
+            user_error: "Invalid verification code".to_string(),
+        })
+    }
+
+    /// Check `code` against the enabled TOTP of the user, or against their
+    /// unused recovery codes. A matching code is consumed.
+    async fn verify_second_factor(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        code: &str,
+    ) -> Result<bool, UniversalInboxError> {
+        // Lock the TOTP row so that concurrent requests cannot use the same code
+        let Some(totp_auth) = self
+            .repository
+            .get_totp_auth(executor, user_id, true)
+            .await?
+            .filter(|totp_auth| totp_auth.enabled_at.is_some())
+        else {
+            return Ok(false);
+        };
+
+        if !is_totp_code(code) {
+            return self
+                .repository
+                .use_recovery_code(executor, user_id, &hash_recovery_code(code))
+                .await;
+        }
+
+        let totp = build_totp(self.decrypt_totp_secret(user_id, &totp_auth)?, "")?;
+        let Some(used_step) = verify_totp_code(&totp, code, Utc::now(), totp_auth.last_used_step)
+        else {
+            return Ok(false);
+        };
+        self.repository
+            .update_totp_last_used_step(executor, user_id, used_step)
+            .await?;
+        Ok(true)
+    }
+
+    async fn replace_recovery_codes(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+    ) -> Result<RecoveryCodes, UniversalInboxError> {
+        let recovery_codes = generate_recovery_codes();
+        let code_hashes: Vec<String> = recovery_codes
+            .iter()
+            .map(|code| hash_recovery_code(code))
+            .collect();
+        self.repository
+            .replace_recovery_codes(executor, user_id, &code_hashes)
+            .await?;
+
+        Ok(RecoveryCodes { recovery_codes })
+    }
+
+    /// Re-encrypt the TOTP secrets of a batch of `batch_size` users, starting
+    /// after `after`, with the primary token encryption key. Secrets are only
+    /// checked when `dry_run` is set.
+    #[tracing::instrument(level = "debug", skip(self, executor), err)]
+    pub async fn rotate_totp_secrets_encryption_key(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        after: Option<UserId>,
+        batch_size: i64,
+        dry_run: bool,
+    ) -> Result<SecretRotationBatch<UserId>, UniversalInboxError> {
+        let keys = self.token_encryption_keys.expose_secret();
+        let encrypted_secrets = self
+            .repository
+            .fetch_totp_encrypted_secrets(executor, after, batch_size)
+            .await?;
+        let mut batch = SecretRotationBatch {
+            last_id: encrypted_secrets.last().map(|(user_id, _)| *user_id),
+            scanned_count: encrypted_secrets.len(),
+            rotated_count: 0,
+            failed_count: 0,
+        };
+
+        for (user_id, encrypted_secret) in encrypted_secrets {
+            if !keys.needs_rotation(&encrypted_secret) {
+                continue;
+            }
+
+            match reencrypt_token(&encrypted_secret, user_id.0.as_bytes(), keys) {
+                Ok(encrypted_secret) => {
+                    if !dry_run {
+                        self.repository
+                            .update_totp_encrypted_secret(executor, user_id, &encrypted_secret)
+                            .await?;
+                    }
+                    batch.rotated_count += 1;
+                }
+                Err(err) => {
+                    warn!("Failed to re-encrypt TOTP secret of user {user_id}: {err:?}");
+                    batch.failed_count += 1;
+                }
+            }
+        }
+
+        Ok(batch)
+    }
+
+    fn decrypt_totp_secret(
+        &self,
+        user_id: UserId,
+        totp_auth: &TotpUserAuth,
+    ) -> Result<Vec<u8>, UniversalInboxError> {
+        let secret = decrypt_token(
+            &totp_auth.encrypted_secret,
+            user_id.0.as_bytes(),
+            self.token_encryption_keys.expose_secret(),
+        )?;
+        Ok(totp_rs::Secret::Encoded(secret)
+            .to_bytes()
+            .context("Failed to decode TOTP secret")?)
+    }
+
     #[tracing::instrument(level = "debug", skip_all, fields(user.id = user_id.to_string()), err)]
     pub async fn link_oidc_auth_method(
         &self,
@@ -314,11 +677,15 @@ impl UserService {
 
         if !matches!(
             &user_auth,
-            UserAuth::OIDCAuthorizationCodePKCE(_) | UserAuth::OIDCGoogleAuthorizationCode(_)
+            UserAuth::OIDCAuthorizationCodePKCE(_)
+                | UserAuth::OIDCGoogleAuthorizationCode(_)
+                | UserAuth::SAML(_)
         ) {
             return Err(UniversalInboxError::InvalidInputData {
                 source: None,
-                user_error: format!("Cannot link non-OIDC authentication method {kind}"),
+                user_error: format!(
+                    "Cannot link authentication method {kind}, only OIDC and SAML methods can be linked"
+                ),
             });
         }
 
@@ -348,12 +715,12 @@ impl UserService {
                 return Err(UniversalInboxError::InvalidInputData {
                     source: None,
                     user_error: format!(
-                        "The email from the OIDC account ({oidc_email}) does not match your current email ({current_email})"
+                        "The email from the {kind} account ({oidc_email}) does not match your current email ({current_email})"
                     ),
                 });
             }
             None => {
-                // User has no email yet, set it from the OIDC provider
+                // User has no email yet, set it from the identity provider
                 // Check if the email domain is blacklisted
                 let domain = oidc_email.domain().to_lowercase();
                 if let Some(rejection_message) = self
@@ -453,6 +820,143 @@ impl UserService {
             .await
     }
 
+    /// Store a new SAML authentication request and return it with the URL redirecting
+    /// the user to the identity provider. When `linking_user_id` is set, the SAML
+    /// identity will be linked to this user instead of logging in.
+    #[tracing::instrument(level = "debug", skip_all, err)]
+    pub async fn build_saml_authn_request(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        saml_settings: &SAMLSettings,
+        linking_user_id: Option<UserId>,
+    ) -> Result<(SAMLAuthnRequest, Url), UniversalInboxError> {
+        let now = Utc::now();
+        let authn_request = SAMLAuthnRequest {
+            id: generate_saml_request_id(),
+            linking_user_id,
+            expires_at: now + TimeDelta::minutes(SAML_AUTHN_REQUEST_TTL_IN_MINUTES),
+        };
+        self.repository
+            .create_saml_authn_request(executor, &authn_request)
+            .await?;
+
+        let authorization_url = build_authn_request_url(
+            saml_settings,
+            &self
+                .application_settings
+                .get_saml_sp_entity_id(saml_settings)?,
+            &self.application_settings.get_saml_acs_url()?,
+            &authn_request.id,
+            now,
+        )?;
+
+        Ok((authn_request, authorization_url))
+    }
+
+    /// Validate a response received on the Assertion Consumer Service. The pending
+    /// authentication request it answers is consumed, so that it cannot be replayed.
+    /// Returns the asserted identity and the authentication request it answers.
+    #[tracing::instrument(level = "debug", skip_all, err)]
+    pub async fn validate_saml_response(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        saml_settings: &SAMLSettings,
+        encoded_response: &str,
+    ) -> Result<(SAMLIdentity, SAMLAuthnRequest), UniversalInboxError> {
+        let response = SAMLResponse::decode(encoded_response)?;
+        let request_id = response.in_response_to().ok_or_else(|| {
+            UniversalInboxError::Unauthorized(anyhow!(
+                "Unsolicited SAML responses are not supported"
+            ))
+        })?;
+        let authn_request = self
+            .repository
+            .consume_saml_authn_request(executor, request_id)
+            .await?
+            .ok_or_else(|| {
+                UniversalInboxError::Unauthorized(anyhow!(
+                    "Unknown or expired SAML authentication request {request_id}"
+                ))
+            })?;
+
+        let identity = response.validate(
+            &SAMLResponseExpectations {
+                settings: saml_settings,
+                sp_entity_id: &self
+                    .application_settings
+                    .get_saml_sp_entity_id(saml_settings)?,
+                acs_url: &self.application_settings.get_saml_acs_url()?,
+                request_id: &authn_request.id,
+            },
+            Utc::now(),
+        )?;
+
+        Ok((identity, authn_request))
+    }
+
+    /// Find the user linked to the SAML identity, creating it on the fly (just-in-time
+    /// provisioning) on its first login.
+    #[tracing::instrument(level = "debug", skip_all, err)]
+    pub async fn authenticate_for_saml(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        identity: SAMLIdentity,
+    ) -> Result<User, UniversalInboxError> {
+        let name_id: AuthUserId = identity.name_id.into();
+        if let Some(user) = self
+            .repository
+            .get_user_by_saml_name_id(executor, &name_id)
+            .await?
+        {
+            return Ok(user);
+        }
+
+        info!("User with SAML NameID {name_id} does not exists, creating a new one");
+        // Check if the email domain is blacklisted
+        let domain = identity.email.domain().to_lowercase();
+        if let Some(rejection_message) = self
+            .application_settings
+            .security
+            .email_domain_blacklist
+            .get(&domain)
+        {
+            return Err(UniversalInboxError::Forbidden(rejection_message.clone()));
+        }
+
+        self.repository
+            .create_user(
+                executor,
+                User::new(identity.first_name, identity.last_name, identity.email),
+                UserAuth::SAML(Box::new(SAMLUserAuth { name_id })),
+            )
+            .await
+    }
+
+    /// Link a SAML identity to an existing user.
+    #[tracing::instrument(level = "debug", skip_all, fields(user.id = user_id.to_string()), err)]
+    pub async fn link_for_saml(
+        &self,
+        executor: &mut Transaction<'_, Postgres>,
+        user_id: UserId,
+        identity: SAMLIdentity,
+    ) -> Result<UserAuthMethod, UniversalInboxError> {
+        let name_id: AuthUserId = identity.name_id.into();
+        if let Some(user) = self
+            .repository
+            .get_user_by_saml_name_id(executor, &name_id)
+            .await?
+            && user.id != user_id
+        {
+            return Err(UniversalInboxError::Conflict(
+                "This SAML identity is already linked to another user".to_string(),
+            ));
+        }
+
+        let user_auth = UserAuth::SAML(Box::new(SAMLUserAuth { name_id }));
+        self.link_oidc_auth_method(executor, user_id, user_auth, identity.email)
+            .await
+    }
+
     #[tracing::instrument(level = "debug", skip_all, fields(user.id = user_id.to_string()), err)]
     pub async fn delete_user(
         &self,
@@ -469,9 +973,10 @@ impl UserService {
         user_id: UserId,
         patch: &UserPatch,
     ) -> Result<UpdateStatus<User>, UniversalInboxError> {
-        // Block email changes when an OIDC authentication method is linked
+        // Block email changes when an OIDC or SAML authentication method is linked,
+        // the email being managed by the identity provider
         if patch.email.is_some() {
-            let has_oidc = self
+            let has_identity_provider = self
                 .repository
                 .get_all_user_auths(executor, user_id, false)
                 .await?
@@ -481,14 +986,15 @@ impl UserService {
                         auth.kind(),
                         UserAuthKind::OIDCGoogleAuthorizationCode
                             | UserAuthKind::OIDCAuthorizationCodePKCE
+                            | UserAuthKind::SAML
                     )
                 });
 
-            if has_oidc {
+            if has_identity_provider {
                 return Err(UniversalInboxError::InvalidInputData {
                     source: None,
                     user_error:
-                        "Email cannot be changed while an OIDC authentication method is linked"
+                        "Email cannot be changed while an OIDC or SAML authentication method is linked"
                             .to_string(),
                 });
             }
@@ -832,7 +1338,9 @@ impl UserService {
                     }
                 }
             }
-            AuthenticationSettings::Local(_) | AuthenticationSettings::Passkey => {
+            AuthenticationSettings::Local(_)
+            | AuthenticationSettings::Passkey
+            | AuthenticationSettings::SAML(_) => {
                 Ok(self.application_settings.front_base_url.clone())
             }
         }
@@ -922,7 +1430,10 @@ impl UserService {
     /// is even checked. On a wrong password it records the failure (locking the
     /// account with exponential backoff past the threshold, emailing the owner
     /// once per lock episode) and returns a generic `Unauthorized` that does not
-    /// reveal whether the account exists. A correct password resets the counter.
+    /// reveal whether the account exists. A correct password resets the counter,
+    /// unless the user enabled TOTP: the counter is then only reset once the
+    /// second factor is validated, so that TOTP codes cannot be brute forced by
+    /// interleaving valid password logins.
     ///
     /// Throttle (Redis) errors fail open: the per-IP limiter still applies and
     /// we prefer availability over locking everyone out during a Redis outage.
@@ -931,7 +1442,7 @@ impl UserService {
         &self,
         executor: &mut Transaction<'_, Postgres>,
         credentials: Credentials,
-    ) -> Result<User, UniversalInboxError> {
+    ) -> Result<PasswordLoginOutcome, UniversalInboxError> {
         let email = credentials.email.clone();
 
         if let Some(throttle) = &self.login_throttle {
@@ -948,15 +1459,24 @@ impl UserService {
 
         match self.check_password(executor, credentials).await {
             Ok(user) => {
+                let is_totp_enabled = self
+                    .repository
+                    .get_totp_auth(executor, user.id, false)
+                    .await?
+                    .is_some_and(|totp_auth| totp_auth.enabled_at.is_some());
+                if is_totp_enabled {
+                    return Ok(PasswordLoginOutcome::TotpRequired(user));
+                }
+
                 if let Some(throttle) = &self.login_throttle
                     && let Err(err) = throttle.reset(&email).await
                 {
                     warn!("Failed to reset login throttle after successful login: {err:?}");
                 }
-                Ok(user)
+                Ok(PasswordLoginOutcome::LoggedIn(user))
             }
             Err(UniversalInboxError::Unauthorized(_)) => {
-                self.record_failed_login(executor, &email).await;
+                self.record_failed_login(executor, &email, false).await;
                 // Generic message: must not reveal whether the account exists.
                 Err(UniversalInboxError::Unauthorized(anyhow!(
                     "Invalid email address or password"
@@ -969,10 +1489,13 @@ impl UserService {
     /// Record a failed attempt with the throttle and, if it newly locked the
     /// account, email the (real) owner once. Best-effort: throttle/email errors
     /// are logged, never surfaced, so a failed login still returns its 401.
+    /// `second_factor_failed` tells a wrong TOTP code (after a valid password)
+    /// apart from a wrong password.
     async fn record_failed_login(
         &self,
         executor: &mut Transaction<'_, Postgres>,
         email: &EmailAddress,
+        second_factor_failed: bool,
     ) {
         let Some(throttle) = &self.login_throttle else {
             return;
@@ -981,7 +1504,7 @@ impl UserService {
             Ok(outcome) if outcome.newly_locked => {
                 let dry_run = self.application_settings.dry_run;
                 if let Err(err) = self
-                    .send_account_lockout_email(executor, email, dry_run)
+                    .send_account_lockout_email(executor, email, second_factor_failed, dry_run)
                     .await
                 {
                     warn!("Failed to send account lockout email: {err:?}");
@@ -1259,6 +1782,7 @@ impl UserService {
         &self,
         executor: &mut Transaction<'_, Postgres>,
         email: &EmailAddress,
+        second_factor_failed: bool,
         dry_run: bool,
     ) -> Result<(), UniversalInboxError> {
         let user = self.repository.get_user_by_email(executor, email).await?;
@@ -1278,6 +1802,7 @@ impl UserService {
         let template = EmailTemplate::AccountLockout {
             first_name: user.first_name.clone(),
             login_url,
+            second_factor_failed,
         };
         self.mailer
             .read()
@@ -1328,6 +1853,10 @@ impl UserService {
         Ok(())
     }
 
+    /// Reset the password with a token received by email. All the sessions of
+    /// the user are revoked as the password may have been compromised, to be
+    /// forgotten once committed (see
+    /// `AuthenticationTokenService::forget_revoked_sessions`).
     #[tracing::instrument(
         level = "debug",
         skip_all,
@@ -1355,7 +1884,16 @@ impl UserService {
         match updated_user {
             UpdateStatus {
                 result: Some(_), ..
-            } => Ok(()),
+            } => {
+                let revoked_count = self
+                    .authentication_token_service
+                    .read()
+                    .await
+                    .revoke_all_sessions(executor, user_id)
+                    .await?;
+                debug!("Revoked {revoked_count} sessions of user {user_id} after password reset");
+                Ok(())
+            }
             UpdateStatus { result: None, .. } => Err(UniversalInboxError::InvalidInputData {
                 source: None,
                 user_error: format!("Invalid password reset token for user {user_id}"),
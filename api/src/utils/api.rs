@@ -9,7 +9,10 @@ use reqwest_middleware::{
 // Use the reqwest version re-exported by reqwest-middleware (reqwest 0.13)
 // to ensure type compatibility with the middleware client.
 // The workspace `reqwest` dep is 0.12, used by openidconnect, opentelemetry, etc.
-use reqwest_middleware::reqwest::{IntoUrl, Response, StatusCode};
+use reqwest_middleware::reqwest::{
+    IntoUrl, Response, StatusCode,
+    header::{ETAG, HeaderName, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+};
 use reqwest_retry::{
     Jitter, RetryTransientMiddleware, Retryable, RetryableStrategy, default_on_request_failure,
     default_on_request_success, policies::ExponentialBackoff,
@@ -20,7 +23,9 @@ use reqwest_tracing::{
 use serde::{Serialize, de::DeserializeOwned};
 use thiserror::Error;
 
-use crate::universal_inbox::UniversalInboxError;
+use crate::universal_inbox::{
+    UniversalInboxError, integration_connection::sync_run::record_upstream_http_call,
+};
 
 pub static APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"),);
 
@@ -96,6 +101,18 @@ impl RetryableStrategy for RateLimitRetryableStrategy {
     }
 }
 
+/// Outcome of a conditional request
+#[derive(Debug)]
+pub enum ConditionalResponse<R> {
+    /// The resource did not change since the given validators were returned
+    NotModified,
+    Modified {
+        value: R,
+        last_modified: Option<String>,
+        etag: Option<String>,
+    },
+}
+
 pub struct ApiClient {
     rate_limit_detector: Arc<dyn RateLimitDetector>,
     client: ClientWithMiddleware,
@@ -202,6 +219,7 @@ impl ApiClient {
         response: Result<Response, reqwest_middleware::Error>,
     ) -> Result<JsonBody, ApiClientError> {
         let response = response.map_err(ApiClientError::MiddlewareError)?;
+        record_upstream_http_call(response.status(), response.headers());
 
         if self.rate_limit_detector.is_rate_limit_response(&response) {
             return Err(ApiClientError::rate_limit_error(
@@ -238,6 +256,48 @@ impl ApiClient {
         response.parse_json()
     }
 
+    /// Send a GET request with `If-Modified-Since` and `If-None-Match` headers built from
+    /// the validators of a previous response
+    pub async fn get_if_modified<R: DeserializeOwned, U: IntoUrl>(
+        &self,
+        url: U,
+        last_modified: Option<&str>,
+        etag: Option<&str>,
+    ) -> Result<ConditionalResponse<R>, ApiClientError> {
+        let mut request_builder = self.client.get(url);
+        if let Some(last_modified) = last_modified {
+            request_builder = request_builder.header(IF_MODIFIED_SINCE, last_modified);
+        }
+        if let Some(etag) = etag {
+            request_builder = request_builder.header(IF_NONE_MATCH, etag);
+        }
+        let response = request_builder.send().await;
+
+        let mut validators = (None, None);
+        if let Ok(response) = &response {
+            if response.status() == StatusCode::NOT_MODIFIED {
+                record_upstream_http_call(response.status(), response.headers());
+                return Ok(ConditionalResponse::NotModified);
+            }
+            let header_value = |name: HeaderName| {
+                response
+                    .headers()
+                    .get(name)
+                    .and_then(|value| value.to_str().ok())
+                    .map(|value| value.to_string())
+            };
+            validators = (header_value(LAST_MODIFIED), header_value(ETAG));
+        }
+        let value = self.handle_response(response).await?.parse_json()?;
+        let (last_modified, etag) = validators;
+
+        Ok(ConditionalResponse::Modified {
+            value,
+            last_modified,
+            etag,
+        })
+    }
+
     pub async fn post<R: DeserializeOwned, U: IntoUrl, T: Serialize + ?Sized>(
         &self,
         url: U,
@@ -350,7 +410,7 @@ mod tests_api_error {
     use rstest::*;
     use wiremock::{
         Mock, MockServer, ResponseTemplate,
-        matchers::{method, path},
+        matchers::{header, method, path},
     };
 
     // Mock rate limit detector for testing
@@ -446,4 +506,54 @@ mod tests_api_error {
         let result: Result<serde_json::Value, ApiClientError> = api_client.get(url).await;
         assert!(matches!(result, Err(ApiClientError::NetworkError(_))));
     }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_api_client_get_if_modified(#[future] mock_server: MockServer) {
+        let mock_server = mock_server.await;
+        Mock::given(method("GET"))
+            .and(path("/"))
+            .and(header("if-none-match", "\"v1\""))
+            .respond_with(ResponseTemplate::new(304))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("etag", "\"v1\"")
+                    .insert_header("last-modified", "Thu, 01 Oct 2026 10:00:00 GMT")
+                    .set_body_json(serde_json::json!({ "message": "Success" })),
+            )
+            .mount(&mock_server)
+            .await;
+        let url = format!("{}/", mock_server.uri());
+        let api_client =
+            ApiClient::build(HeaderMap::new(), vec![url.clone()], Duration::from_secs(1)).unwrap();
+
+        let result: ConditionalResponse<serde_json::Value> = api_client
+            .get_if_modified(url.clone(), None, None)
+            .await
+            .unwrap();
+        let ConditionalResponse::Modified {
+            value,
+            last_modified,
+            etag,
+        } = result
+        else {
+            panic!("Expected a modified response, got {result:?}");
+        };
+        assert_eq!(value, serde_json::json!({ "message": "Success" }));
+        assert_eq!(
+            last_modified.as_deref(),
+            Some("Thu, 01 Oct 2026 10:00:00 GMT")
+        );
+        assert_eq!(etag.as_deref(), Some("\"v1\""));
+
+        let result: ConditionalResponse<serde_json::Value> = api_client
+            .get_if_modified(url, last_modified.as_deref(), etag.as_deref())
+            .await
+            .unwrap();
+        assert!(matches!(result, ConditionalResponse::NotModified));
+    }
 }
@@ -1,18 +1,32 @@
-use std::{sync::Arc, time::Duration};
+use std::{marker::PhantomData, sync::Arc, time::Duration};
 
 use anyhow::Context;
-use cached::AsyncRedisCache;
+use async_trait::async_trait;
+use cached::{AsyncRedisCache, IOCachedAsync};
+use futures::{StreamExt, stream::BoxStream};
 use once_cell::sync::Lazy;
-use redis::{Client, Script, aio::ConnectionManager};
+use redis::{
+    AsyncCommands, Client, ExistenceCheck, Script, SetExpiry, SetOptions, aio::ConnectionManager,
+};
 use serde::{Serialize, de::DeserializeOwned};
+use sqlx::{
+    PgPool,
+    postgres::{PgListener, PgPoolOptions},
+};
 use tokio::sync::RwLock;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
-use crate::{configuration::Settings, universal_inbox::UniversalInboxError};
+use crate::{
+    configuration::{Settings, StorageBackend},
+    universal_inbox::UniversalInboxError,
+};
+
+const EXPIRED_ENTRIES_DELETION_INTERVAL: Duration = Duration::from_secs(5 * 60);
 
 pub struct Config {
     settings: Settings,
     namespace: Arc<RwLock<String>>,
+    postgres_pool: RwLock<Option<Arc<PgPool>>>,
 }
 
 impl Config {
@@ -20,6 +34,7 @@ impl Config {
         Self {
             settings: Settings::new().unwrap(),
             namespace: Arc::new(RwLock::new("universal-inbox:cache:".to_string())),
+            postgres_pool: RwLock::new(None),
         }
     }
 
@@ -30,72 +45,732 @@ impl Config {
     async fn set_namespace(&self, namespace: String) {
         *(self.namespace.write().await) = namespace;
     }
+
+    async fn set_postgres_pool(&self, pool: Arc<PgPool>) {
+        *(self.postgres_pool.write().await) = Some(pool);
+    }
+
+    /// Pool used by the function caches when the PostgreSQL backend is
+    /// configured: the pool of the last created `Cache`, or a new one if the
+    /// function caches are used before any `Cache` is created
+    async fn postgres_pool(&self) -> Option<Arc<PgPool>> {
+        if self.settings.storage.backend != StorageBackend::Postgres {
+            return None;
+        }
+
+        let mut postgres_pool = self.postgres_pool.write().await;
+        let pool = postgres_pool.get_or_insert_with(|| {
+            Arc::new(
+                PgPoolOptions::new()
+                    .max_connections(self.settings.database.max_connections)
+                    .connect_lazy_with(self.settings.database.connect_options()),
+            )
+        });
+        Some(pool.clone())
+    }
 }
 
 static CACHE_CONFIG: Lazy<Config> = Lazy::new(Config::load);
 
+/// Short-lived state shared by the API and the workers (cron locks, OAuth and
+/// passkey challenges, MCP sessions and subscriptions...), along with a
+/// publish/subscribe channel. Depending on the `storage.backend` setting, it
+/// is stored in Redis or in the `cache_*` tables of PostgreSQL.
 #[derive(Clone)]
-pub struct Cache {
-    pub connection_manager: ConnectionManager,
+pub enum Cache {
+    Redis {
+        client: Client,
+        connection_manager: ConnectionManager,
+    },
+    Postgres {
+        pool: Arc<PgPool>,
+    },
 }
 
 impl Cache {
-    pub async fn new(redis_address: String) -> Result<Self, UniversalInboxError> {
-        let client = Client::open(redis_address)
-            .context("Failed to open setup Redis client for {redis_address}")?;
-        let connection_manager = client
-            .get_connection_manager()
+    pub async fn new(settings: &Settings, pool: Arc<PgPool>) -> Result<Self, UniversalInboxError> {
+        match settings.storage.backend {
+            StorageBackend::Redis => {
+                let redis_address = settings.redis.connection_string();
+                let client = Client::open(redis_address)
+                    .context("Failed to open setup Redis client for {redis_address}")?;
+                let connection_manager = client
+                    .get_connection_manager()
+                    .await
+                    .context("Failed to get connection manager for Redis client")?;
+                Ok(Cache::Redis {
+                    client,
+                    connection_manager,
+                })
+            }
+            StorageBackend::Postgres => {
+                // The configured database may not be the one of the application
+                // (e.g. in tests), so the function caches use its pool
+                CACHE_CONFIG.set_postgres_pool(pool.clone()).await;
+                Ok(Cache::Postgres { pool })
+            }
+        }
+    }
+
+    pub async fn ping(&self) -> Result<(), UniversalInboxError> {
+        match self {
+            Cache::Redis {
+                connection_manager, ..
+            } => {
+                let _: String = connection_manager
+                    .clone()
+                    .ping()
+                    .await
+                    .context("Failed to ping Redis")?;
+            }
+            Cache::Postgres { pool } => {
+                sqlx::query("SELECT 1")
+                    .execute(&**pool)
+                    .await
+                    .context("Failed to ping PostgreSQL cache")?;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn get(&self, key: &str) -> Result<Option<String>, UniversalInboxError> {
+        let value = match self {
+            Cache::Redis {
+                connection_manager, ..
+            } => connection_manager
+                .clone()
+                .get(key)
+                .await
+                .with_context(|| format!("Failed to get `{key}` from Redis"))?,
+            Cache::Postgres { pool } => sqlx::query_scalar(
+                r#"
+                  SELECT value
+                  FROM cache_entry
+                  WHERE key = $1 AND expires_at > NOW()
+                "#,
+            )
+            .bind(key)
+            .fetch_optional(&**pool)
             .await
-            .context("Failed to get connection manager for Redis client")?;
-        Ok(Cache { connection_manager })
+            .with_context(|| format!("Failed to get `{key}` from PostgreSQL cache"))?,
+        };
+        Ok(value)
+    }
+
+    pub async fn set_ex(
+        &self,
+        key: &str,
+        value: &str,
+        ttl: Duration,
+    ) -> Result<(), UniversalInboxError> {
+        match self {
+            Cache::Redis {
+                connection_manager, ..
+            } => connection_manager
+                .clone()
+                .set_ex::<_, _, ()>(key, value, ttl.as_secs())
+                .await
+                .with_context(|| format!("Failed to set `{key}` in Redis"))?,
+            Cache::Postgres { pool } => {
+                sqlx::query(
+                    r#"
+                      INSERT INTO cache_entry (key, value, expires_at)
+                      VALUES ($1, $2, NOW() + make_interval(secs => $3))
+                      ON CONFLICT (key) DO UPDATE
+                      SET value = EXCLUDED.value, expires_at = EXCLUDED.expires_at
+                    "#,
+                )
+                .bind(key)
+                .bind(value)
+                .bind(ttl.as_secs_f64())
+                .execute(&**pool)
+                .await
+                .with_context(|| format!("Failed to set `{key}` in PostgreSQL cache"))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Set `key` only if it does not exist yet, returning whether it was set
+    pub async fn set_nx_ex(
+        &self,
+        key: &str,
+        value: &str,
+        ttl: Duration,
+    ) -> Result<bool, UniversalInboxError> {
+        let is_set = match self {
+            Cache::Redis {
+                connection_manager, ..
+            } => {
+                let options = SetOptions::default()
+                    .conditional_set(ExistenceCheck::NX)
+                    .with_expiration(SetExpiry::EX(ttl.as_secs()));
+                let result: Option<String> = connection_manager
+                    .clone()
+                    .set_options(key, value, options)
+                    .await
+                    .with_context(|| format!("Failed to set `{key}` if absent in Redis"))?;
+                result.is_some()
+            }
+            Cache::Postgres { pool } => {
+                sqlx::query(
+                    r#"
+                      INSERT INTO cache_entry (key, value, expires_at)
+                      VALUES ($1, $2, NOW() + make_interval(secs => $3))
+                      ON CONFLICT (key) DO UPDATE
+                      SET value = EXCLUDED.value, expires_at = EXCLUDED.expires_at
+                      WHERE cache_entry.expires_at <= NOW()
+                    "#,
+                )
+                .bind(key)
+                .bind(value)
+                .bind(ttl.as_secs_f64())
+                .execute(&**pool)
+                .await
+                .with_context(|| format!("Failed to set `{key}` if absent in PostgreSQL cache"))?
+                .rows_affected()
+                    == 1
+            }
+        };
+        Ok(is_set)
+    }
+
+    /// Atomically get and delete `key`, so that its value is used only once
+    pub async fn get_del(&self, key: &str) -> Result<Option<String>, UniversalInboxError> {
+        let value = match self {
+            Cache::Redis {
+                connection_manager, ..
+            } => connection_manager
+                .clone()
+                .get_del(key)
+                .await
+                .with_context(|| format!("Failed to get and delete `{key}` from Redis"))?,
+            Cache::Postgres { pool } => sqlx::query_scalar::<_, Option<String>>(
+                r#"
+                  DELETE FROM cache_entry
+                  WHERE key = $1
+                  RETURNING CASE WHEN expires_at > NOW() THEN value END
+                "#,
+            )
+            .bind(key)
+            .fetch_optional(&**pool)
+            .await
+            .with_context(|| format!("Failed to get and delete `{key}` from PostgreSQL cache"))?
+            .flatten(),
+        };
+        Ok(value)
+    }
+
+    pub async fn del(&self, key: &str) -> Result<(), UniversalInboxError> {
+        match self {
+            Cache::Redis {
+                connection_manager, ..
+            } => connection_manager
+                .clone()
+                .del::<_, ()>(key)
+                .await
+                .with_context(|| format!("Failed to delete `{key}` from Redis"))?,
+            Cache::Postgres { pool } => {
+                sqlx::query("DELETE FROM cache_entry WHERE key = $1")
+                    .bind(key)
+                    .execute(&**pool)
+                    .await
+                    .with_context(|| format!("Failed to delete `{key}` from PostgreSQL cache"))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Atomically delete `key` only if it still holds `value`, returning
+    /// whether it was deleted
+    pub async fn del_if_eq(&self, key: &str, value: &str) -> Result<bool, UniversalInboxError> {
+        let is_deleted = match self {
+            Cache::Redis {
+                connection_manager, ..
+            } => {
+                let deleted_keys_count: usize =
+                    Script::new(include_str!("../../scripts/lua/del_if_eq.lua"))
+                        .key(key)
+                        .arg(value)
+                        .invoke_async(&mut connection_manager.clone())
+                        .await
+                        .with_context(|| format!("Failed to delete `{key}` if equal from Redis"))?;
+                deleted_keys_count == 1
+            }
+            Cache::Postgres { pool } => {
+                sqlx::query("DELETE FROM cache_entry WHERE key = $1 AND value = $2")
+                    .bind(key)
+                    .bind(value)
+                    .execute(&**pool)
+                    .await
+                    .with_context(|| {
+                        format!("Failed to delete `{key}` if equal from PostgreSQL cache")
+                    })?
+                    .rows_affected()
+                    == 1
+            }
+        };
+        Ok(is_deleted)
+    }
+
+    /// Remaining time to live of `key`, `None` if it does not exist
+    pub async fn time_to_live(&self, key: &str) -> Result<Option<Duration>, UniversalInboxError> {
+        let ttl_in_seconds: Option<i64> = match self {
+            Cache::Redis {
+                connection_manager, ..
+            } => {
+                let ttl_in_seconds: i64 = connection_manager
+                    .clone()
+                    .ttl(key)
+                    .await
+                    .with_context(|| format!("Failed to get the TTL of `{key}` from Redis"))?;
+                // Negative values are returned for missing keys
+                (ttl_in_seconds >= 0).then_some(ttl_in_seconds)
+            }
+            Cache::Postgres { pool } => sqlx::query_scalar(
+                r#"
+                  SELECT CEIL(EXTRACT(EPOCH FROM expires_at - NOW()))::BIGINT
+                  FROM cache_entry
+                  WHERE key = $1 AND expires_at > NOW()
+                "#,
+            )
+            .bind(key)
+            .fetch_optional(&**pool)
+            .await
+            .with_context(|| format!("Failed to get the TTL of `{key}` from PostgreSQL cache"))?,
+        };
+        Ok(ttl_in_seconds.map(|ttl_in_seconds| Duration::from_secs(ttl_in_seconds as u64)))
+    }
+
+    /// Add `member` to the set stored at `key`, and (re)set the TTL of the set
+    pub async fn add_set_member(
+        &self,
+        key: &str,
+        member: &str,
+        ttl: Duration,
+    ) -> Result<(), UniversalInboxError> {
+        match self {
+            Cache::Redis {
+                connection_manager, ..
+            } => redis::pipe()
+                .sadd(key, member)
+                .ignore()
+                .expire(key, ttl.as_secs() as i64)
+                .ignore()
+                .query_async::<()>(&mut connection_manager.clone())
+                .await
+                .with_context(|| format!("Failed to add a member to `{key}` in Redis"))?,
+            Cache::Postgres { pool } => {
+                sqlx::query(
+                    r#"
+                      WITH added_member AS (
+                        INSERT INTO cache_set_member (key, member, expires_at)
+                        VALUES ($1, $2, NOW() + make_interval(secs => $3))
+                        ON CONFLICT (key, member) DO UPDATE
+                        SET expires_at = EXCLUDED.expires_at
+                      )
+                      UPDATE cache_set_member
+                      SET expires_at = NOW() + make_interval(secs => $3)
+                      WHERE key = $1 AND expires_at > NOW()
+                    "#,
+                )
+                .bind(key)
+                .bind(member)
+                .bind(ttl.as_secs_f64())
+                .execute(&**pool)
+                .await
+                .with_context(|| {
+                    format!("Failed to add a member to `{key}` in PostgreSQL cache")
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn remove_set_member(
+        &self,
+        key: &str,
+        member: &str,
+    ) -> Result<(), UniversalInboxError> {
+        match self {
+            Cache::Redis {
+                connection_manager, ..
+            } => connection_manager
+                .clone()
+                .srem::<_, _, ()>(key, member)
+                .await
+                .with_context(|| format!("Failed to remove a member from `{key}` in Redis"))?,
+            Cache::Postgres { pool } => {
+                sqlx::query("DELETE FROM cache_set_member WHERE key = $1 AND member = $2")
+                    .bind(key)
+                    .bind(member)
+                    .execute(&**pool)
+                    .await
+                    .with_context(|| {
+                        format!("Failed to remove a member from `{key}` in PostgreSQL cache")
+                    })?;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn fetch_set_members(&self, key: &str) -> Result<Vec<String>, UniversalInboxError> {
+        let members = match self {
+            Cache::Redis {
+                connection_manager, ..
+            } => connection_manager
+                .clone()
+                .smembers(key)
+                .await
+                .with_context(|| format!("Failed to fetch the members of `{key}` from Redis"))?,
+            Cache::Postgres { pool } => sqlx::query_scalar(
+                r#"
+                  SELECT member
+                  FROM cache_set_member
+                  WHERE key = $1 AND expires_at > NOW()
+                "#,
+            )
+            .bind(key)
+            .fetch_all(&**pool)
+            .await
+            .with_context(|| {
+                format!("Failed to fetch the members of `{key}` from PostgreSQL cache")
+            })?,
+        };
+        Ok(members)
+    }
+
+    /// With PostgreSQL, `payload` must be shorter than 8000 bytes
+    pub async fn publish(&self, channel: &str, payload: &str) -> Result<(), UniversalInboxError> {
+        match self {
+            Cache::Redis {
+                connection_manager, ..
+            } => connection_manager
+                .clone()
+                .publish::<_, _, ()>(channel, payload)
+                .await
+                .with_context(|| format!("Failed to publish on Redis channel `{channel}`"))?,
+            Cache::Postgres { pool } => {
+                sqlx::query("SELECT pg_notify($1, $2)")
+                    .bind(channel)
+                    .bind(payload)
+                    .execute(&**pool)
+                    .await
+                    .with_context(|| {
+                        format!("Failed to publish on PostgreSQL channel `{channel}`")
+                    })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Stream of the payloads published on `channel`, on a dedicated
+    /// connection. The stream ends when the connection is lost.
+    pub async fn subscribe(
+        &self,
+        channel: &str,
+    ) -> Result<BoxStream<'static, Result<String, UniversalInboxError>>, UniversalInboxError> {
+        match self {
+            Cache::Redis { client, .. } => {
+                let mut pubsub = client
+                    .get_async_pubsub()
+                    .await
+                    .context("Failed to open Redis publish/subscribe connection")?;
+                pubsub
+                    .subscribe(channel)
+                    .await
+                    .with_context(|| format!("Failed to subscribe to Redis channel `{channel}`"))?;
+                Ok(pubsub
+                    .into_on_message()
+                    .map(|message| {
+                        Ok(message
+                            .get_payload()
+                            .context("Invalid Redis publish/subscribe message payload")?)
+                    })
+                    .boxed())
+            }
+            Cache::Postgres { pool } => {
+                let mut listener = PgListener::connect_with(pool)
+                    .await
+                    .context("Failed to open PostgreSQL listener connection")?;
+                listener.listen(channel).await.with_context(|| {
+                    format!("Failed to subscribe to PostgreSQL channel `{channel}`")
+                })?;
+                Ok(listener
+                    .into_stream()
+                    .map(|notification| {
+                        Ok(notification
+                            .context("Failed to receive PostgreSQL notification")?
+                            .payload()
+                            .to_string())
+                    })
+                    .boxed())
+            }
+        }
     }
 
     pub async fn clear(&self, prefix: &Option<String>) -> Result<(), UniversalInboxError> {
-        let mut connection = self.connection_manager.clone();
         let namespace = CACHE_CONFIG.namespace().await;
         let full_prefix = prefix
             .as_ref()
             .map(|p| format!("{namespace}{p}"))
             .unwrap_or(namespace.to_string());
-        let pattern = format!("{full_prefix}*");
 
-        let deleted_keys_count: usize =
-            Script::new(include_str!("../../scripts/lua/clear_cache.lua"))
-                .arg(pattern.clone())
-                .invoke_async(&mut connection)
-                .await
-                .context("Failed to clear cache")?;
+        match self {
+            Cache::Redis {
+                connection_manager, ..
+            } => {
+                let mut connection = connection_manager.clone();
+                let pattern = format!("{full_prefix}*");
+
+                let deleted_keys_count: usize =
+                    Script::new(include_str!("../../scripts/lua/clear_cache.lua"))
+                        .arg(pattern.clone())
+                        .invoke_async(&mut connection)
+                        .await
+                        .context("Failed to clear cache")?;
 
-        debug!("Cleared Redis {deleted_keys_count} cache entries with pattern: `{pattern}`");
+                debug!(
+                    "Cleared Redis {deleted_keys_count} cache entries with pattern: `{pattern}`"
+                );
+            }
+            Cache::Postgres { pool } => {
+                let deleted_entries_count =
+                    sqlx::query("DELETE FROM cache_entry WHERE starts_with(key, $1)")
+                        .bind(&full_prefix)
+                        .execute(&**pool)
+                        .await
+                        .context("Failed to clear cache")?
+                        .rows_affected();
+
+                debug!(
+                    "Cleared PostgreSQL {deleted_entries_count} cache entries with prefix: `{full_prefix}`"
+                );
+            }
+        }
         Ok(())
     }
 
+    /// Redis expires keys on its own, whereas expired PostgreSQL entries are
+    /// only ignored until they are deleted here
+    pub async fn delete_expired_entries(&self) -> Result<u64, UniversalInboxError> {
+        let Cache::Postgres { pool } = self else {
+            return Ok(0);
+        };
+
+        let mut deleted_entries_count = 0;
+        for table in ["cache_entry", "cache_set_member", "login_throttle"] {
+            deleted_entries_count +=
+                sqlx::query(&format!("DELETE FROM {table} WHERE expires_at <= NOW()"))
+                    .execute(&**pool)
+                    .await
+                    .with_context(|| format!("Failed to delete expired entries from `{table}`"))?
+                    .rows_affected();
+        }
+        Ok(deleted_entries_count)
+    }
+
     pub async fn set_namespace(namespace: String) {
         CACHE_CONFIG.set_namespace(namespace).await;
     }
 }
 
-pub async fn build_redis_cache<T>(
-    prefix: &str,
-    ttl_in_seconds: Duration,
+pub async fn delete_expired_cache_entries(cache: Cache) {
+    let mut interval = tokio::time::interval(EXPIRED_ENTRIES_DELETION_INTERVAL);
+    loop {
+        interval.tick().await;
+        match cache.delete_expired_entries().await {
+            Ok(0) => {}
+            Ok(count) => debug!("Deleted {count} expired cache entries"),
+            Err(err) => warn!("{err:?}"),
+        }
+    }
+}
+
+/// Storage of the `io_cached` functions, in the same backend as [`Cache`]
+pub enum IOCache<V> {
+    Redis(AsyncRedisCache<String, V>),
+    Postgres(PostgresIOCache<V>),
+}
+
+#[async_trait]
+impl<V> IOCachedAsync<String, V> for IOCache<V>
+where
+    V: Serialize + DeserializeOwned + Send + Sync,
+{
+    type Error = UniversalInboxError;
+
+    async fn cache_get(&self, key: &String) -> Result<Option<V>, Self::Error> {
+        match self {
+            IOCache::Redis(cache) => Ok(cache
+                .cache_get(key)
+                .await
+                .context("Failed to get cached value from Redis")?),
+            IOCache::Postgres(cache) => cache.get(key).await,
+        }
+    }
+
+    async fn cache_set(&self, key: String, value: V) -> Result<Option<V>, Self::Error> {
+        match self {
+            IOCache::Redis(cache) => Ok(cache
+                .cache_set(key, value)
+                .await
+                .context("Failed to set cached value in Redis")?),
+            IOCache::Postgres(cache) => cache.set(key, value).await,
+        }
+    }
+
+    async fn cache_remove(&self, key: &String) -> Result<Option<V>, Self::Error> {
+        match self {
+            IOCache::Redis(cache) => Ok(cache
+                .cache_remove(key)
+                .await
+                .context("Failed to remove cached value from Redis")?),
+            IOCache::Postgres(cache) => cache.remove(key).await,
+        }
+    }
+
+    fn cache_set_refresh(&mut self, refresh: bool) -> bool {
+        match self {
+            IOCache::Redis(cache) => cache.cache_set_refresh(refresh),
+            IOCache::Postgres(cache) => std::mem::replace(&mut cache.refresh, refresh),
+        }
+    }
+}
+
+/// Values are stored as JSON in the `cache_entry` table, with the same key
+/// format as `AsyncRedisCache`
+pub struct PostgresIOCache<V> {
+    key_prefix: String,
+    ttl: Duration,
     refresh: bool,
-) -> AsyncRedisCache<String, T>
+    value_type: PhantomData<fn() -> V>,
+}
+
+impl<V> PostgresIOCache<V>
+where
+    V: Serialize + DeserializeOwned + Send + Sync,
+{
+    fn key(&self, key: &str) -> String {
+        format!("{}{key}", self.key_prefix)
+    }
+
+    async fn pool() -> Result<Arc<PgPool>, UniversalInboxError> {
+        Ok(CACHE_CONFIG
+            .postgres_pool()
+            .await
+            .context("PostgreSQL storage backend is not configured")?)
+    }
+
+    fn deserialize(value: Option<String>) -> Result<Option<V>, UniversalInboxError> {
+        Ok(value
+            .map(|value| serde_json::from_str(&value))
+            .transpose()
+            .context("Failed to deserialize cached value")?)
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<V>, UniversalInboxError> {
+        let pool = Self::pool().await?;
+        let value: Option<String> = if self.refresh {
+            sqlx::query_scalar(
+                r#"
+                  UPDATE cache_entry
+                  SET expires_at = NOW() + make_interval(secs => $2)
+                  WHERE key = $1 AND expires_at > NOW()
+                  RETURNING value
+                "#,
+            )
+            .bind(self.key(key))
+            .bind(self.ttl.as_secs_f64())
+            .fetch_optional(&*pool)
+            .await
+        } else {
+            sqlx::query_scalar(
+                r#"
+                  SELECT value
+                  FROM cache_entry
+                  WHERE key = $1 AND expires_at > NOW()
+                "#,
+            )
+            .bind(self.key(key))
+            .fetch_optional(&*pool)
+            .await
+        }
+        .context("Failed to get cached value from PostgreSQL")?;
+        Self::deserialize(value)
+    }
+
+    async fn set(&self, key: String, value: V) -> Result<Option<V>, UniversalInboxError> {
+        let value = serde_json::to_string(&value).context("Failed to serialize cached value")?;
+        let pool = Self::pool().await?;
+        let previous_value: Option<String> = sqlx::query_scalar(
+            r#"
+              WITH previous_entry AS (
+                SELECT value FROM cache_entry WHERE key = $1 AND expires_at > NOW()
+              )
+              INSERT INTO cache_entry (key, value, expires_at)
+              VALUES ($1, $2, NOW() + make_interval(secs => $3))
+              ON CONFLICT (key) DO UPDATE
+              SET value = EXCLUDED.value, expires_at = EXCLUDED.expires_at
+              RETURNING (SELECT value FROM previous_entry)
+            "#,
+        )
+        .bind(self.key(&key))
+        .bind(value)
+        .bind(self.ttl.as_secs_f64())
+        .fetch_one(&*pool)
+        .await
+        .context("Failed to set cached value in PostgreSQL")?;
+        Self::deserialize(previous_value)
+    }
+
+    async fn remove(&self, key: &str) -> Result<Option<V>, UniversalInboxError> {
+        let pool = Self::pool().await?;
+        let value: Option<String> = sqlx::query_scalar::<_, Option<String>>(
+            r#"
+              DELETE FROM cache_entry
+              WHERE key = $1
+              RETURNING CASE WHEN expires_at > NOW() THEN value END
+            "#,
+        )
+        .bind(self.key(key))
+        .fetch_optional(&*pool)
+        .await
+        .context("Failed to remove cached value from PostgreSQL")?
+        .flatten();
+        Self::deserialize(value)
+    }
+}
+
+pub async fn build_io_cache<T>(prefix: &str, ttl_in_seconds: Duration, refresh: bool) -> IOCache<T>
 where
     T: Serialize + DeserializeOwned + Send + Sync,
 {
     let settings = &CACHE_CONFIG.settings;
     let namespace = CACHE_CONFIG.namespace().await;
+    if CACHE_CONFIG.postgres_pool().await.is_some() {
+        info!("Using PostgreSQL for caching with namespace: {namespace}:{prefix}");
+        return IOCache::Postgres(PostgresIOCache {
+            key_prefix: format!("{namespace}{prefix}"),
+            ttl: ttl_in_seconds,
+            refresh,
+            value_type: PhantomData,
+        });
+    }
+
     info!(
         "Connecting to Redis server for caching on {} with namespace: {}:{}",
         &settings.redis.safe_connection_string(),
         &namespace,
         &prefix
     );
-    AsyncRedisCache::new(prefix, ttl_in_seconds)
-        .set_refresh(refresh)
-        .set_namespace(&namespace)
-        .set_connection_string(&settings.redis.connection_string())
-        .build()
-        .await
-        .expect("error building Redis cache")
+    IOCache::Redis(
+        AsyncRedisCache::new(prefix, ttl_in_seconds)
+            .set_refresh(refresh)
+            .set_namespace(&namespace)
+            .set_connection_string(&settings.redis.connection_string())
+            .build()
+            .await
+            .expect("error building Redis cache"),
+    )
 }
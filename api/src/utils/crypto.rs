@@ -1,13 +1,20 @@
-use anyhow::Context;
+use std::collections::HashMap;
+
+use anyhow::{Context, anyhow};
 use ring::aead::{self, Aad, BoundKey, Nonce, NonceSequence, OpeningKey, SealingKey, UnboundKey};
 use ring::error::Unspecified;
 use ring::rand::{SecureRandom, SystemRandom};
 use secrecy::CloneableSecret;
 use secrecy::zeroize::Zeroize;
 
-use crate::universal_inbox::UniversalInboxError;
+use crate::{configuration::Oauth2Settings, universal_inbox::UniversalInboxError};
 
 const NONCE_LEN: usize = 12; // 96-bit nonce for AES-256-GCM
+/// Ciphertexts start with this marker followed by the length and the ID of
+/// the key used to encrypt them. Ciphertexts written before key rotation was
+/// supported have no header.
+const KEY_ID_HEADER_MARKER: &[u8] = b"UIK1";
+const MAX_KEY_ID_LEN: usize = u8::MAX as usize;
 
 struct SingleNonce(Option<[u8; NONCE_LEN]>);
 
@@ -52,10 +59,193 @@ impl TokenEncryptionKey {
     }
 }
 
-/// Encrypt a plaintext token. Returns nonce (12 bytes) prepended to ciphertext+tag.
+/// Token encryption keys by ID. New tokens are encrypted with the primary key
+/// while tokens encrypted with any of the keys can be decrypted, so that the
+/// primary key can be rotated (see the `secrets rotate` command).
+#[derive(Clone)]
+pub struct TokenEncryptionKeyRing {
+    primary_key_id: String,
+    keys: HashMap<String, TokenEncryptionKey>,
+}
+
+impl Zeroize for TokenEncryptionKeyRing {
+    fn zeroize(&mut self) {
+        self.keys.values_mut().for_each(Zeroize::zeroize);
+    }
+}
+
+impl CloneableSecret for TokenEncryptionKeyRing {}
+
+impl TokenEncryptionKeyRing {
+    pub fn new(
+        primary_key_id: &str,
+        primary_key: TokenEncryptionKey,
+        previous_keys: HashMap<String, TokenEncryptionKey>,
+    ) -> Result<Self, UniversalInboxError> {
+        if previous_keys.contains_key(primary_key_id) {
+            return Err(UniversalInboxError::Unexpected(anyhow!(
+                "Token encryption key `{primary_key_id}` is both the primary and a previous key"
+            )));
+        }
+        let mut keys = previous_keys;
+        keys.insert(primary_key_id.to_string(), primary_key);
+        if let Some(key_id) = keys
+            .keys()
+            .find(|key_id| key_id.is_empty() || key_id.len() > MAX_KEY_ID_LEN)
+        {
+            return Err(UniversalInboxError::Unexpected(anyhow!(
+                "Token encryption key IDs must be 1 to {MAX_KEY_ID_LEN} bytes long, got `{key_id}`"
+            )));
+        }
+
+        Ok(Self {
+            primary_key_id: primary_key_id.to_string(),
+            keys,
+        })
+    }
+
+    pub fn from_settings(settings: &Oauth2Settings) -> Result<Self, UniversalInboxError> {
+        let previous_keys = settings
+            .previous_token_encryption_keys
+            .iter()
+            .map(|(key_id, hex_key)| {
+                TokenEncryptionKey::from_hex(hex_key)
+                    .with_context(|| format!("Invalid previous token encryption key `{key_id}`"))
+                    .map(|key| (key_id.clone(), key))
+            })
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        Self::new(
+            &settings.token_encryption_key_id,
+            TokenEncryptionKey::from_hex(&settings.token_encryption_key)?,
+            previous_keys,
+        )
+    }
+
+    pub fn primary_key_id(&self) -> &str {
+        &self.primary_key_id
+    }
+
+    /// ID of the key `ciphertext` was encrypted with, `None` for ciphertexts
+    /// written before key IDs were stored
+    pub fn key_id<'a>(&self, ciphertext: &'a [u8]) -> Option<&'a str> {
+        split_key_id_header(ciphertext).map(|(key_id, _)| key_id)
+    }
+
+    /// Whether `ciphertext` must be re-encrypted with the primary key
+    pub fn needs_rotation(&self, ciphertext: &[u8]) -> bool {
+        self.key_id(ciphertext) != Some(self.primary_key_id.as_str())
+    }
+
+    fn primary_key(&self) -> &TokenEncryptionKey {
+        &self.keys[&self.primary_key_id]
+    }
+}
+
+fn split_key_id_header(ciphertext: &[u8]) -> Option<(&str, &[u8])> {
+    let rest = ciphertext.strip_prefix(KEY_ID_HEADER_MARKER)?;
+    let (key_id_len, rest) = rest.split_first()?;
+    let key_id_len = *key_id_len as usize;
+    if key_id_len == 0 || rest.len() < key_id_len {
+        return None;
+    }
+    let (key_id, payload) = rest.split_at(key_id_len);
+    Some((std::str::from_utf8(key_id).ok()?, payload))
+}
+
+/// Encrypt a plaintext token with the primary key of `keys`. Returns the key ID
+/// header, followed by the nonce (12 bytes) and the ciphertext+tag.
 /// `aad_context` binds the ciphertext to a specific context (e.g. connection ID bytes)
 /// so that it cannot be decrypted in a different context.
 pub fn encrypt_token(
+    plaintext: &str,
+    aad_context: &[u8],
+    keys: &TokenEncryptionKeyRing,
+) -> Result<Vec<u8>, UniversalInboxError> {
+    let key_id = keys.primary_key_id.as_bytes();
+    let sealed = seal(plaintext, aad_context, keys.primary_key())?;
+
+    let mut result =
+        Vec::with_capacity(KEY_ID_HEADER_MARKER.len() + 1 + key_id.len() + sealed.len());
+    result.extend_from_slice(KEY_ID_HEADER_MARKER);
+    result.push(key_id.len() as u8);
+    result.extend_from_slice(key_id);
+    result.extend_from_slice(&sealed);
+    Ok(result)
+}
+
+/// Decrypt a token encrypted by [`encrypt_token`] with any key of `keys`.
+/// Tokens without a key ID header are tried with every key.
+/// `aad_context` must match the value used during encryption.
+pub fn decrypt_token(
+    ciphertext: &[u8],
+    aad_context: &[u8],
+    keys: &TokenEncryptionKeyRing,
+) -> Result<String, UniversalInboxError> {
+    let header = split_key_id_header(ciphertext);
+    if let Some((key_id, payload)) = header
+        && let Some(key) = keys.keys.get(key_id)
+        && let Ok(plaintext) = open(payload, aad_context, key)
+    {
+        return Ok(plaintext);
+    }
+
+    // Ciphertexts written before key IDs were stored may start with the
+    // header marker by chance
+    open_with_any_key(ciphertext, aad_context, keys).map_err(|err| match header {
+        Some((key_id, _)) if !keys.keys.contains_key(key_id) => UniversalInboxError::Unexpected(
+            anyhow!("Failed to decrypt token: unknown token encryption key `{key_id}`"),
+        ),
+        _ => err,
+    })
+}
+
+/// Re-encrypt a token with the primary key of `keys`
+pub fn reencrypt_token(
+    ciphertext: &[u8],
+    aad_context: &[u8],
+    keys: &TokenEncryptionKeyRing,
+) -> Result<Vec<u8>, UniversalInboxError> {
+    encrypt_token(
+        &decrypt_token(ciphertext, aad_context, keys)?,
+        aad_context,
+        keys,
+    )
+}
+
+/// Outcome of re-encrypting a batch of secrets with the primary key
+#[derive(Debug)]
+pub struct SecretRotationBatch<Id> {
+    /// Last secret of the batch, from which the next batch starts. `None`
+    /// when there was no more secrets to process.
+    pub last_id: Option<Id>,
+    pub scanned_count: usize,
+    pub rotated_count: usize,
+    pub failed_count: usize,
+}
+
+fn open_with_any_key(
+    ciphertext: &[u8],
+    aad_context: &[u8],
+    keys: &TokenEncryptionKeyRing,
+) -> Result<String, UniversalInboxError> {
+    // Try the primary key first as it encrypts most of the tokens
+    std::iter::once(keys.primary_key())
+        .chain(
+            keys.keys
+                .iter()
+                .filter(|(key_id, _)| **key_id != keys.primary_key_id)
+                .map(|(_, key)| key),
+        )
+        .find_map(|key| open(ciphertext, aad_context, key).ok())
+        .ok_or_else(|| {
+            UniversalInboxError::Unexpected(anyhow!(
+                "Failed to decrypt token: invalid key or corrupted ciphertext"
+            ))
+        })
+}
+
+/// Returns nonce (12 bytes) prepended to ciphertext+tag.
+fn seal(
     plaintext: &str,
     aad_context: &[u8],
     key: &TokenEncryptionKey,
@@ -81,8 +271,7 @@ pub fn encrypt_token(
 }
 
 /// Decrypt a token from nonce (12 bytes) + ciphertext+tag format.
-/// `aad_context` must match the value used during encryption.
-pub fn decrypt_token(
+fn open(
     ciphertext: &[u8],
     aad_context: &[u8],
     key: &TokenEncryptionKey,
@@ -121,14 +310,25 @@ mod tests {
     use super::*;
     use std::collections::HashSet;
 
-    fn test_key() -> TokenEncryptionKey {
+    fn test_key() -> TokenEncryptionKeyRing {
         // 32-byte key as 64 hex chars
+        TokenEncryptionKeyRing::new("test", first_key(), HashMap::new()).unwrap()
+    }
+
+    fn first_key() -> TokenEncryptionKey {
         TokenEncryptionKey::from_hex(
             "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
         )
         .unwrap()
     }
 
+    fn second_key() -> TokenEncryptionKey {
+        TokenEncryptionKey::from_hex(
+            "abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789",
+        )
+        .unwrap()
+    }
+
     const TEST_AAD: &[u8] = b"test-connection-id";
 
     #[test]
@@ -171,7 +371,8 @@ mod tests {
 
         for _ in 0..100 {
             let encrypted = encrypt_token("token", TEST_AAD, &key).unwrap();
-            let nonce = &encrypted[..NONCE_LEN];
+            let (_, payload) = split_key_id_header(&encrypted).unwrap();
+            let nonce = &payload[..NONCE_LEN];
             nonces.insert(nonce.to_vec());
         }
 
@@ -181,10 +382,7 @@ mod tests {
     #[test]
     fn test_decrypt_with_wrong_key_fails() {
         let key1 = test_key();
-        let key2 = TokenEncryptionKey::from_hex(
-            "abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789",
-        )
-        .unwrap();
+        let key2 = TokenEncryptionKeyRing::new("test", second_key(), HashMap::new()).unwrap();
 
         let encrypted = encrypt_token("secret-token", TEST_AAD, &key1).unwrap();
         assert!(decrypt_token(&encrypted, TEST_AAD, &key2).is_err());
@@ -246,4 +444,80 @@ mod tests {
         let decrypted = decrypt_token(&encrypted, TEST_AAD, &key).unwrap();
         assert_eq!(decrypted, plaintext);
     }
+
+    #[test]
+    fn test_encrypted_token_stores_primary_key_id() {
+        let key = test_key();
+        let encrypted = encrypt_token("token", TEST_AAD, &key).unwrap();
+
+        assert_eq!(key.key_id(&encrypted), Some("test"));
+        assert!(!key.needs_rotation(&encrypted));
+    }
+
+    #[test]
+    fn test_decrypt_with_previous_key() {
+        let old_keys = TokenEncryptionKeyRing::new("old", first_key(), HashMap::new()).unwrap();
+        let new_keys = TokenEncryptionKeyRing::new(
+            "new",
+            second_key(),
+            HashMap::from([("old".to_string(), first_key())]),
+        )
+        .unwrap();
+
+        let encrypted = encrypt_token("secret-token", TEST_AAD, &old_keys).unwrap();
+
+        assert!(new_keys.needs_rotation(&encrypted));
+        assert_eq!(
+            decrypt_token(&encrypted, TEST_AAD, &new_keys).unwrap(),
+            "secret-token"
+        );
+        let reencrypted = reencrypt_token(&encrypted, TEST_AAD, &new_keys).unwrap();
+        assert_eq!(new_keys.key_id(&reencrypted), Some("new"));
+        assert!(!new_keys.needs_rotation(&reencrypted));
+        assert!(decrypt_token(&reencrypted, TEST_AAD, &old_keys).is_err());
+    }
+
+    #[test]
+    fn test_decrypt_token_without_key_id_with_any_key() {
+        let keys = TokenEncryptionKeyRing::new(
+            "new",
+            second_key(),
+            HashMap::from([("old".to_string(), first_key())]),
+        )
+        .unwrap();
+        let encrypted = seal("legacy-token", TEST_AAD, &first_key()).unwrap();
+
+        assert_eq!(keys.key_id(&encrypted), None);
+        assert!(keys.needs_rotation(&encrypted));
+        assert_eq!(
+            decrypt_token(&encrypted, TEST_AAD, &keys).unwrap(),
+            "legacy-token"
+        );
+    }
+
+    #[test]
+    fn test_decrypt_with_removed_key_fails() {
+        let old_keys = TokenEncryptionKeyRing::new("old", first_key(), HashMap::new()).unwrap();
+        let new_keys = TokenEncryptionKeyRing::new("new", second_key(), HashMap::new()).unwrap();
+
+        let encrypted = encrypt_token("secret-token", TEST_AAD, &old_keys).unwrap();
+        let err = decrypt_token(&encrypted, TEST_AAD, &new_keys).unwrap_err();
+
+        assert!(
+            err.to_string()
+                .contains("unknown token encryption key `old`")
+        );
+    }
+
+    #[test]
+    fn test_key_ring_with_duplicate_key_id_fails() {
+        assert!(
+            TokenEncryptionKeyRing::new(
+                "test",
+                first_key(),
+                HashMap::from([("test".to_string(), second_key())]),
+            )
+            .is_err()
+        );
+    }
 }
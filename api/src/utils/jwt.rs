@@ -1,9 +1,17 @@
+use std::collections::BTreeSet;
+
 use anyhow::Context;
 use base64::prelude::*;
-use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
 use ring::signature::KeyPair;
 use ring::{rand::SystemRandom, signature::Ed25519KeyPair};
 use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use universal_inbox::{
+    auth::oauth2::OAuth2Scope,
+    notification::{NotificationSourceKind, NotificationSyncSourceKind},
+};
 
 use crate::universal_inbox::UniversalInboxError;
 
@@ -56,6 +64,23 @@ impl JWTSigningKeys {
             decoding_key,
         })
     }
+
+    /// Check tokens signed with the secret key are verified with the public key
+    pub fn check_key_pair(&self) -> Result<(), UniversalInboxError> {
+        let token = encode(
+            &Header::new(JWT_SIGNING_ALGO),
+            &json!({ "sub": "readiness-probe" }),
+            &self.encoding_key,
+        )
+        .context("Failed to sign JWT with the secret key")?;
+
+        let mut validation = Validation::new(JWT_SIGNING_ALGO);
+        validation.required_spec_claims.clear();
+        validation.validate_exp = false;
+        decode::<Value>(&token, &self.decoding_key, &validation)
+            .context("Failed to verify JWT with the public key")?;
+        Ok(())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -71,4 +96,139 @@ pub struct Claims {
     pub scope: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub client_id: Option<String>,
+    // Notification sources an API key is restricted to. Never encoded in
+    // issued JWTs: set from the stored key by `middlewares::api_key_guard`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notification_source_kinds: Option<Vec<NotificationSourceKind>>,
+}
+
+impl Claims {
+    /// Scopes granted to the token, or `None` for unscoped tokens (session and
+    /// legacy API key tokens) which have full access to the user's account.
+    /// A scoped token without any known scope falls back to the read-only
+    /// default.
+    pub fn granted_scopes(&self) -> Option<BTreeSet<OAuth2Scope>> {
+        self.scope.as_deref().map(|scope| {
+            let scopes = OAuth2Scope::parse_scopes_lossy(scope);
+            if scopes.is_empty() {
+                BTreeSet::from(OAuth2Scope::DEFAULT)
+            } else {
+                scopes
+            }
+        })
+    }
+
+    pub fn require_scopes(
+        &self,
+        required_scopes: &[OAuth2Scope],
+    ) -> Result<(), UniversalInboxError> {
+        check_scopes(self.granted_scopes().as_ref(), required_scopes)
+    }
+
+    /// Fails with `Forbidden` if the token is restricted to notification
+    /// sources not including `kind`.
+    pub fn check_notification_source_kind(
+        &self,
+        kind: NotificationSourceKind,
+    ) -> Result<(), UniversalInboxError> {
+        check_notification_source_kind(self.notification_source_kinds.as_deref(), kind)
+    }
+
+    /// Narrows the notification sources `requested` by a caller (empty meaning
+    /// all sources) to the ones the token is restricted to.
+    pub fn restrict_notification_source_kinds(
+        &self,
+        requested: Vec<NotificationSourceKind>,
+    ) -> Result<Vec<NotificationSourceKind>, UniversalInboxError> {
+        restrict_notification_source_kinds(self.notification_source_kinds.as_deref(), requested)
+    }
+
+    /// Narrows the notification source `requested` to be synchronized (`None`
+    /// meaning all sources) to the ones the token is restricted to.
+    pub fn restrict_notification_sync_source_kinds(
+        &self,
+        requested: Option<NotificationSyncSourceKind>,
+    ) -> Result<Option<Vec<NotificationSyncSourceKind>>, UniversalInboxError> {
+        restrict_notification_sync_source_kinds(
+            self.notification_source_kinds.as_deref(),
+            requested,
+        )
+    }
+}
+
+pub fn check_notification_source_kind(
+    allowed: Option<&[NotificationSourceKind]>,
+    kind: NotificationSourceKind,
+) -> Result<(), UniversalInboxError> {
+    match allowed {
+        Some(allowed) if !allowed.contains(&kind) => Err(UniversalInboxError::Forbidden(format!(
+            "This token cannot access {kind} notifications"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+pub fn restrict_notification_source_kinds(
+    allowed: Option<&[NotificationSourceKind]>,
+    requested: Vec<NotificationSourceKind>,
+) -> Result<Vec<NotificationSourceKind>, UniversalInboxError> {
+    let Some(allowed) = allowed else {
+        return Ok(requested);
+    };
+    if requested.is_empty() {
+        return Ok(allowed.to_vec());
+    }
+
+    let restricted: Vec<NotificationSourceKind> = requested
+        .into_iter()
+        .filter(|kind| allowed.contains(kind))
+        .collect();
+    if restricted.is_empty() {
+        return Err(UniversalInboxError::Forbidden(
+            "This token cannot access notifications from the requested sources".to_string(),
+        ));
+    }
+    Ok(restricted)
+}
+
+/// Returns the notification sources to synchronize, or `None` to synchronize
+/// all of them when neither the caller nor the token restrict them.
+pub fn restrict_notification_sync_source_kinds(
+    allowed: Option<&[NotificationSourceKind]>,
+    requested: Option<NotificationSyncSourceKind>,
+) -> Result<Option<Vec<NotificationSyncSourceKind>>, UniversalInboxError> {
+    if let Some(requested) = requested {
+        check_notification_source_kind(allowed, requested.into())?;
+        return Ok(Some(vec![requested]));
+    }
+    let Some(allowed) = allowed else {
+        return Ok(None);
+    };
+
+    let restricted: Vec<NotificationSyncSourceKind> = allowed
+        .iter()
+        .filter_map(|kind| NotificationSyncSourceKind::try_from(*kind).ok())
+        .collect();
+    if restricted.is_empty() {
+        return Err(UniversalInboxError::Forbidden(
+            "This token cannot synchronize notifications from any source".to_string(),
+        ));
+    }
+    Ok(Some(restricted))
+}
+
+/// Fails with `InsufficientScope` unless `granted_scopes` (`None` meaning
+/// unrestricted) contains every scope in `required_scopes`.
+pub fn check_scopes(
+    granted_scopes: Option<&BTreeSet<OAuth2Scope>>,
+    required_scopes: &[OAuth2Scope],
+) -> Result<(), UniversalInboxError> {
+    match granted_scopes {
+        Some(granted) if !required_scopes.iter().all(|scope| granted.contains(scope)) => {
+            Err(UniversalInboxError::InsufficientScope {
+                required_scopes: required_scopes.to_vec(),
+            })
+        }
+        _ => Ok(()),
+    }
 }
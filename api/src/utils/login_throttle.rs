@@ -1,22 +1,24 @@
-//! Per-account login-attempt throttling, backed by Redis.
+//! Per-account login-attempt throttling, backed by Redis or PostgreSQL
+//! depending on the storage backend of the [`Cache`].
 //!
 //! This is the second layer of brute-force protection for the local-password
 //! login path. The first layer (`utils::rate_limit`) caps total request volume
 //! per IP; this layer caps *failed* attempts per account (keyed by email) and
 //! temporarily locks the account with exponential backoff once a threshold is
-//! crossed. Because the state lives in shared Redis (reusing the same
-//! [`ConnectionManager`] as the cache and MCP session store), it survives API
-//! restarts and is consistent across replicas — unlike the in-memory governor.
+//! crossed. Because the state lives in shared storage (reusing the same
+//! [`Cache`] as the MCP session store), it survives API restarts and is
+//! consistent across replicas — unlike the in-memory governor.
 //!
 //! **No account enumeration:** the counter is keyed by the submitted email
 //! regardless of whether an account exists, and the caller returns an identical
 //! throttled response for existing, non-existent, and locked accounts. The
 //! email is SHA-256 hashed before use as a key so raw addresses never persist
-//! in Redis.
+//! in storage.
 //!
 //! The mutate path (`record_failure`) runs as a single atomic Lua script
-//! (`scripts/lua/login_throttle.lua`) so the increment / lock / TTL decision
-//! cannot race across worker threads or pods.
+//! (`scripts/lua/login_throttle.lua`) with Redis, and in a transaction holding
+//! the `login_throttle` row lock with PostgreSQL, so the increment / lock / TTL
+//! decision cannot race across worker threads or pods.
 //!
 //! Tuning lives directly on [`LocalAuthenticationSettings`] (`max_login_attempts`,
 //! `login_attempt_window_seconds`, `login_lockout_base_seconds`,
@@ -26,10 +28,14 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 use anyhow::Context;
 use email_address::EmailAddress;
-use redis::{AsyncCommands, Script, aio::ConnectionManager};
+use redis::{AsyncCommands, Script};
 use ring::digest;
+use sqlx::PgPool;
 
-use crate::{configuration::LocalAuthenticationSettings, universal_inbox::UniversalInboxError};
+use crate::{
+    configuration::LocalAuthenticationSettings, universal_inbox::UniversalInboxError,
+    utils::cache::Cache,
+};
 
 const NAMESPACE: &str = "universal-inbox:login-throttle:";
 
@@ -49,8 +55,9 @@ pub struct FailureOutcome {
 }
 
 /// Reference implementation of the backoff curve, kept in sync with
-/// `scripts/lua/login_throttle.lua` (the Lua is authoritative at runtime; this
-/// mirror exists so the curve can be unit-tested without a live Redis).
+/// `scripts/lua/login_throttle.lua` (the Lua is authoritative at runtime with
+/// Redis; this mirror is used with PostgreSQL and lets the curve be
+/// unit-tested without a live Redis).
 pub fn backoff_seconds(fail_count: u32, settings: &LocalAuthenticationSettings) -> u64 {
     if fail_count < settings.max_login_attempts {
         return 0;
@@ -65,13 +72,13 @@ pub fn backoff_seconds(fail_count: u32, settings: &LocalAuthenticationSettings)
 
 #[derive(Clone)]
 pub struct LoginThrottle {
-    conn: ConnectionManager,
+    cache: Cache,
     settings: LocalAuthenticationSettings,
 }
 
 impl LoginThrottle {
-    pub fn new(conn: ConnectionManager, settings: LocalAuthenticationSettings) -> Self {
-        Self { conn, settings }
+    pub fn new(cache: Cache, settings: LocalAuthenticationSettings) -> Self {
+        Self { cache, settings }
     }
 
     /// SHA-256 of the lowercased address, namespaced. Emails are treated
@@ -96,12 +103,27 @@ impl LoginThrottle {
         &self,
         email: &EmailAddress,
     ) -> Result<Option<u64>, UniversalInboxError> {
-        let mut conn = self.conn.clone();
         let key = Self::key(email);
-        let locked_until: Option<i64> = conn
-            .hget(&key, "locked_until")
+        let locked_until: Option<i64> = match &self.cache {
+            Cache::Redis {
+                connection_manager, ..
+            } => connection_manager
+                .clone()
+                .hget(&key, "locked_until")
+                .await
+                .context("Failed to read login throttle state from Redis")?,
+            Cache::Postgres { pool } => sqlx::query_scalar(
+                r#"
+                  SELECT locked_until
+                  FROM login_throttle
+                  WHERE key = $1 AND expires_at > NOW()
+                "#,
+            )
+            .bind(&key)
+            .fetch_optional(&**pool)
             .await
-            .context("Failed to read login throttle state from Redis")?;
+            .context("Failed to read login throttle state from PostgreSQL")?,
+        };
         let now = Self::now_secs() as i64;
         match locked_until {
             Some(locked_until) if locked_until > now => Ok(Some((locked_until - now) as u64)),
@@ -115,20 +137,23 @@ impl LoginThrottle {
         &self,
         email: &EmailAddress,
     ) -> Result<FailureOutcome, UniversalInboxError> {
-        let mut conn = self.conn.clone();
         let key = Self::key(email);
         let now = Self::now_secs();
-        let (fail_count, locked_until, newly_locked): (i64, i64, i64) =
-            Script::new(include_str!("../../scripts/lua/login_throttle.lua"))
+        let (fail_count, locked_until, newly_locked): (i64, i64, i64) = match &self.cache {
+            Cache::Redis {
+                connection_manager, ..
+            } => Script::new(include_str!("../../scripts/lua/login_throttle.lua"))
                 .key(&key)
                 .arg(now)
                 .arg(self.settings.max_login_attempts)
                 .arg(self.settings.login_attempt_window_seconds)
                 .arg(self.settings.login_lockout_base_seconds)
                 .arg(self.settings.login_lockout_max_seconds)
-                .invoke_async(&mut conn)
+                .invoke_async(&mut connection_manager.clone())
                 .await
-                .context("Failed to record failed login attempt in Redis")?;
+                .context("Failed to record failed login attempt in Redis")?,
+            Cache::Postgres { pool } => self.record_failure_in_postgres(pool, &key, now).await?,
+        };
         Ok(FailureOutcome {
             fail_count: fail_count.max(0) as u32,
             locked: locked_until > now as i64,
@@ -140,14 +165,95 @@ impl LoginThrottle {
     /// Clear the counter after a successful login.
     #[tracing::instrument(level = "debug", skip_all, err)]
     pub async fn reset(&self, email: &EmailAddress) -> Result<(), UniversalInboxError> {
-        let mut conn = self.conn.clone();
         let key = Self::key(email);
-        let _: () = conn
-            .del(&key)
-            .await
-            .context("Failed to reset login throttle state in Redis")?;
+        match &self.cache {
+            Cache::Redis {
+                connection_manager, ..
+            } => connection_manager
+                .clone()
+                .del::<_, ()>(&key)
+                .await
+                .context("Failed to reset login throttle state in Redis")?,
+            Cache::Postgres { pool } => {
+                sqlx::query("DELETE FROM login_throttle WHERE key = $1")
+                    .bind(&key)
+                    .execute(&**pool)
+                    .await
+                    .context("Failed to reset login throttle state in PostgreSQL")?;
+            }
+        }
         Ok(())
     }
+
+    /// Same logic as `scripts/lua/login_throttle.lua`, the row lock taken by
+    /// the upsert serializing concurrent failures for the same account
+    async fn record_failure_in_postgres(
+        &self,
+        pool: &PgPool,
+        key: &str,
+        now: u64,
+    ) -> Result<(i64, i64, i64), UniversalInboxError> {
+        let mut transaction = pool
+            .begin()
+            .await
+            .context("Failed to begin login throttle transaction")?;
+        // State which expired is started over
+        let (fail_count, previous_locked_until): (i32, i64) = sqlx::query_as(
+            r#"
+              INSERT INTO login_throttle (key, fail_count, locked_until, expires_at)
+              VALUES ($1, 1, 0, NOW())
+              ON CONFLICT (key) DO UPDATE
+              SET
+                fail_count = CASE
+                  WHEN login_throttle.expires_at > NOW() THEN login_throttle.fail_count + 1
+                  ELSE 1
+                END,
+                locked_until = CASE
+                  WHEN login_throttle.expires_at > NOW() THEN login_throttle.locked_until
+                  ELSE 0
+                END
+              RETURNING fail_count, locked_until
+            "#,
+        )
+        .bind(key)
+        .fetch_one(&mut *transaction)
+        .await
+        .context("Failed to record failed login attempt in PostgreSQL")?;
+
+        let lock_seconds = backoff_seconds(fail_count.max(0) as u32, &self.settings);
+        let (locked_until, newly_locked) = if fail_count as u32 >= self.settings.max_login_attempts
+        {
+            (now + lock_seconds, previous_locked_until <= now as i64)
+        } else {
+            (0, false)
+        };
+        let ttl_seconds = self
+            .settings
+            .login_attempt_window_seconds
+            .max(locked_until.saturating_sub(now));
+
+        sqlx::query(
+            r#"
+              UPDATE login_throttle
+              SET
+                locked_until = CASE WHEN $2 > 0 THEN $2 ELSE locked_until END,
+                expires_at = NOW() + make_interval(secs => $3)
+              WHERE key = $1
+            "#,
+        )
+        .bind(key)
+        .bind(locked_until as i64)
+        .bind(ttl_seconds as f64)
+        .execute(&mut *transaction)
+        .await
+        .context("Failed to update login throttle state in PostgreSQL")?;
+        transaction
+            .commit()
+            .await
+            .context("Failed to commit login throttle transaction")?;
+
+        Ok((fail_count as i64, locked_until as i64, newly_locked as i64))
+    }
 }
 
 #[cfg(test)]
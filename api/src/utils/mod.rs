@@ -7,3 +7,5 @@ pub mod login_throttle;
 pub mod origin;
 pub mod passkey;
 pub mod rate_limit;
+pub mod saml;
+pub mod totp;
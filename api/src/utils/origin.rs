@@ -17,8 +17,8 @@
 //! request is malformed from the API's perspective, not unauthenticated.
 //!
 //! This helper is deliberately **not** applied to OAuth2 / MCP / well-known
-//! endpoints: those must remain reachable from third-party origins per the
-//! protocol.
+//! endpoints and to the SAML Assertion Consumer Service: those must remain
+//! reachable from third-party origins per the protocol.
 
 use actix_web::{HttpRequest, HttpResponse, http::header};
 use url::Url;
@@ -16,14 +16,67 @@
 //! legitimate clients keep their own buckets). Refusing with `400 Bad
 //! Request` matches the OAuth2 limiter.
 
-use std::net::IpAddr;
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    num::NonZeroU32,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use actix_web::{HttpRequest, HttpResponse};
-use governor::{RateLimiter, clock::DefaultClock, state::keyed::DefaultKeyedStateStore};
+use governor::{
+    DefaultDirectRateLimiter, Quota, RateLimiter,
+    clock::{Clock, DefaultClock},
+    state::keyed::DefaultKeyedStateStore,
+};
+
+use universal_inbox::auth::auth_token::AuthenticationTokenId;
 
 /// A keyed governor rate limiter scoped on the caller's real IP.
 pub type IpRateLimiter = RateLimiter<IpAddr, DefaultKeyedStateStore<IpAddr>, DefaultClock>;
 
+/// Per-API-key rate limiter. Unlike [`IpRateLimiter`], every key has its own
+/// quota (chosen by the user), so each key gets a dedicated direct limiter,
+/// rebuilt whenever its quota changes.
+#[derive(Default)]
+pub struct ApiKeyRateLimiter {
+    limiters: Mutex<HashMap<AuthenticationTokenId, (NonZeroU32, Arc<DefaultDirectRateLimiter>)>>,
+}
+
+impl ApiKeyRateLimiter {
+    /// Check whether one more request from `auth_token_id` fits within
+    /// `per_minute` requests per minute. Returns the time to wait before
+    /// retrying when it does not.
+    pub fn check(
+        &self,
+        auth_token_id: &AuthenticationTokenId,
+        per_minute: NonZeroU32,
+    ) -> Result<(), Duration> {
+        let limiter = {
+            let mut limiters = self
+                .limiters
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            let (quota, limiter) = limiters.entry(auth_token_id.clone()).or_insert_with(|| {
+                (
+                    per_minute,
+                    Arc::new(RateLimiter::direct(Quota::per_minute(per_minute))),
+                )
+            });
+            if *quota != per_minute {
+                *quota = per_minute;
+                *limiter = Arc::new(RateLimiter::direct(Quota::per_minute(per_minute)));
+            }
+            limiter.clone()
+        };
+
+        limiter
+            .check()
+            .map_err(|not_until| not_until.wait_time_from(DefaultClock::default().now()))
+    }
+}
+
 /// Check whether the given request fits within the per-IP budget.
 ///
 /// Returns `Ok(())` to proceed, or an `Err(HttpResponse)` ready to be
@@ -110,6 +163,26 @@ fn parse_remote_addr(raw: &str) -> Option<IpAddr> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn api_key_rate_limiter_applies_per_key_quota() {
+        let rate_limiter = ApiKeyRateLimiter::default();
+        let key = AuthenticationTokenId(uuid::Uuid::new_v4());
+        let other_key = AuthenticationTokenId(uuid::Uuid::new_v4());
+        let quota = NonZeroU32::new(2).unwrap();
+
+        assert!(rate_limiter.check(&key, quota).is_ok());
+        assert!(rate_limiter.check(&key, quota).is_ok());
+        assert!(rate_limiter.check(&key, quota).is_err());
+        // Keys do not share their budget
+        assert!(rate_limiter.check(&other_key, quota).is_ok());
+        // Changing the quota resets the key's budget
+        assert!(
+            rate_limiter
+                .check(&key, NonZeroU32::new(3).unwrap())
+                .is_ok()
+        );
+    }
+
     #[test]
     fn parse_remote_addr_handles_bare_ipv4() {
         assert_eq!(
@@ -0,0 +1,1142 @@
+//! SAML 2.0 Web Browser SSO, with Universal Inbox acting as the service provider.
+//!
+//! Authentication requests are sent to the identity provider with the
+//! HTTP-Redirect binding and its responses are received with the HTTP-POST
+//! binding on the Assertion Consumer Service (ACS).
+//!
+//! A response is only trusted when the `Response` or its `Assertion` carries
+//! an enveloped XML signature (exclusive canonicalization, RSA-SHA256) made
+//! with the configured identity provider certificate. Values are then only
+//! read from the signed elements, so that unsigned content injected elsewhere
+//! in the document (XML signature wrapping) is never used. DTDs are rejected,
+//! so that no entity can be expanded.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::io::Write;
+
+use anyhow::{Context, anyhow, bail, ensure};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use email_address::EmailAddress;
+use flate2::{Compression, write::DeflateEncoder};
+use quick_xml::{
+    Reader, XmlVersion,
+    escape::{escape, resolve_predefined_entity},
+    events::{BytesStart, Event},
+};
+use ring::{digest, signature};
+use url::Url;
+use uuid::Uuid;
+use x509_parser::{oid_registry::OID_PKCS1_RSAENCRYPTION, pem::parse_x509_pem};
+
+use crate::{configuration::SAMLSettings, universal_inbox::UniversalInboxError};
+
+pub const SAML_AUTHN_REQUEST_TTL_IN_MINUTES: i64 = 10;
+pub const SAML_METADATA_CONTENT_TYPE: &str = "application/samlmetadata+xml";
+
+const SAML_METADATA_NS: &str = "urn:oasis:names:tc:SAML:2.0:metadata";
+const SAML_PROTOCOL_NS: &str = "urn:oasis:names:tc:SAML:2.0:protocol";
+const SAML_ASSERTION_NS: &str = "urn:oasis:names:tc:SAML:2.0:assertion";
+const XML_DSIG_NS: &str = "http://www.w3.org/2000/09/xmldsig#";
+const XML_NS: &str = "http://www.w3.org/XML/1998/namespace";
+const EXCLUSIVE_C14N_ALGORITHM: &str = "http://www.w3.org/2001/10/xml-exc-c14n#";
+const ENVELOPED_SIGNATURE_TRANSFORM: &str = "http://www.w3.org/2000/09/xmldsig#enveloped-signature";
+const RSA_SHA256_SIGNATURE_ALGORITHM: &str = "http://www.w3.org/2001/04/xmldsig-more#rsa-sha256";
+const SHA256_DIGEST_ALGORITHM: &str = "http://www.w3.org/2001/04/xmlenc#sha256";
+const HTTP_POST_BINDING: &str = "urn:oasis:names:tc:SAML:2.0:bindings:HTTP-POST";
+const SUCCESS_STATUS: &str = "urn:oasis:names:tc:SAML:2.0:status:Success";
+const BEARER_CONFIRMATION_METHOD: &str = "urn:oasis:names:tc:SAML:2.0:cm:bearer";
+const PERSISTENT_NAME_ID_FORMAT: &str = "urn:oasis:names:tc:SAML:2.0:nameid-format:persistent";
+/// Base64 encoded responses larger than this are rejected before being decoded
+pub const MAX_SAML_RESPONSE_LENGTH: usize = 256 * 1024;
+const MAX_XML_DEPTH: usize = 64;
+
+/// User identity asserted by the identity provider
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SAMLIdentity {
+    pub name_id: String,
+    pub email: EmailAddress,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+}
+
+/// What a response must match to be accepted
+#[derive(Debug)]
+pub struct SAMLResponseExpectations<'a> {
+    pub settings: &'a SAMLSettings,
+    pub sp_entity_id: &'a str,
+    pub acs_url: &'a Url,
+    /// ID of the pending authentication request the response must answer
+    pub request_id: &'a str,
+}
+
+/// Generate the ID of an authentication request. IDs must not start with a
+/// digit (`xs:ID` type).
+pub fn generate_saml_request_id() -> String {
+    format!("_{}", Uuid::new_v4().simple())
+}
+
+pub fn build_sp_metadata(sp_entity_id: &str, acs_url: &Url) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<md:EntityDescriptor xmlns:md="{SAML_METADATA_NS}" entityID="{}">
+  <md:SPSSODescriptor AuthnRequestsSigned="false" WantAssertionsSigned="true" protocolSupportEnumeration="{SAML_PROTOCOL_NS}">
+    <md:NameIDFormat>{PERSISTENT_NAME_ID_FORMAT}</md:NameIDFormat>
+    <md:AssertionConsumerService Binding="{HTTP_POST_BINDING}" Location="{}" index="0" isDefault="true"/>
+  </md:SPSSODescriptor>
+</md:EntityDescriptor>
+"#,
+        escape(sp_entity_id),
+        escape(acs_url.as_str()),
+    )
+}
+
+/// Build the URL redirecting the user to the identity provider with an
+/// `AuthnRequest` (HTTP-Redirect binding: deflated, base64 encoded and sent as
+/// the `SAMLRequest` query parameter).
+pub fn build_authn_request_url(
+    settings: &SAMLSettings,
+    sp_entity_id: &str,
+    acs_url: &Url,
+    request_id: &str,
+    issue_instant: DateTime<Utc>,
+) -> Result<Url, UniversalInboxError> {
+    let authn_request = format!(
+        r#"<samlp:AuthnRequest xmlns:samlp="{SAML_PROTOCOL_NS}" xmlns:saml="{SAML_ASSERTION_NS}" ID="{}" Version="2.0" IssueInstant="{}" Destination="{}" AssertionConsumerServiceURL="{}" ProtocolBinding="{HTTP_POST_BINDING}"><saml:Issuer>{}</saml:Issuer><samlp:NameIDPolicy AllowCreate="true"/></samlp:AuthnRequest>"#,
+        escape(request_id),
+        issue_instant.to_rfc3339_opts(SecondsFormat::Secs, true),
+        escape(settings.idp_sso_url.as_str()),
+        escape(acs_url.as_str()),
+        escape(sp_entity_id),
+    );
+
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(authn_request.as_bytes())
+        .context("Failed to deflate SAML authentication request")?;
+    let deflated_request = encoder
+        .finish()
+        .context("Failed to deflate SAML authentication request")?;
+
+    let mut url = settings.idp_sso_url.clone();
+    url.query_pairs_mut()
+        .append_pair("SAMLRequest", &STANDARD.encode(deflated_request));
+    Ok(url)
+}
+
+/// Response of the identity provider, parsed but not validated yet
+#[derive(Debug)]
+pub struct SAMLResponse {
+    root: XmlElement,
+}
+
+impl SAMLResponse {
+    /// Decode the `SAMLResponse` form parameter of the HTTP-POST binding
+    pub fn decode(encoded_response: &str) -> Result<Self, UniversalInboxError> {
+        Self::decode_and_parse(encoded_response)
+            .map_err(|err| UniversalInboxError::Unauthorized(err.context("Invalid SAML response")))
+    }
+
+    fn decode_and_parse(encoded_response: &str) -> anyhow::Result<Self> {
+        ensure!(
+            encoded_response.len() <= MAX_SAML_RESPONSE_LENGTH,
+            "SAML response is too large"
+        );
+        let xml = String::from_utf8(decode_base64(encoded_response)?)
+            .context("SAML response is not valid UTF-8")?;
+        Ok(Self {
+            root: parse_xml(&xml)?,
+        })
+    }
+
+    /// ID of the authentication request this response claims to answer. It is
+    /// not authenticated yet and must only be used to find the pending request
+    /// then given to [`SAMLResponse::validate`].
+    pub fn in_response_to(&self) -> Option<&str> {
+        self.root.attribute("InResponseTo")
+    }
+
+    pub fn validate(
+        &self,
+        expectations: &SAMLResponseExpectations<'_>,
+        now: DateTime<Utc>,
+    ) -> Result<SAMLIdentity, UniversalInboxError> {
+        self.validate_identity(expectations, now)
+            .map_err(|err| UniversalInboxError::Unauthorized(err.context("Invalid SAML response")))
+    }
+
+    fn validate_identity(
+        &self,
+        expectations: &SAMLResponseExpectations<'_>,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<SAMLIdentity> {
+        let settings = expectations.settings;
+        let response = &self.root;
+        ensure!(
+            response.is(SAML_PROTOCOL_NS, "Response"),
+            "Expected a SAML Response, got {}",
+            response.qualified_name
+        );
+        ensure!(
+            response.attribute("Version") == Some("2.0"),
+            "Unsupported SAML version"
+        );
+        ensure!(
+            response.attribute("InResponseTo") == Some(expectations.request_id),
+            "The response does not answer the pending authentication request"
+        );
+        if let Some(destination) = response.attribute("Destination") {
+            ensure!(
+                destination == expectations.acs_url.as_str(),
+                "Unexpected response destination {destination}"
+            );
+        }
+        if let Some(issuer) = response.child(SAML_ASSERTION_NS, "Issuer") {
+            ensure!(
+                issuer.text() == settings.idp_entity_id,
+                "Unexpected response issuer {}",
+                issuer.text()
+            );
+        }
+        let status_code = response
+            .child(SAML_PROTOCOL_NS, "Status")
+            .and_then(|status| status.child(SAML_PROTOCOL_NS, "StatusCode"))
+            .and_then(|status_code| status_code.attribute("Value"))
+            .context("Missing response status")?;
+        ensure!(
+            status_code == SUCCESS_STATUS,
+            "Authentication failed with status {status_code}"
+        );
+        ensure_unique_ids(response)?;
+
+        let idp_public_key = idp_public_key(&settings.idp_certificate)?;
+        let is_response_signed = verify_enveloped_signature(response, &idp_public_key)?;
+
+        ensure!(
+            response
+                .child(SAML_ASSERTION_NS, "EncryptedAssertion")
+                .is_none(),
+            "Encrypted assertions are not supported"
+        );
+        let mut assertions = response.children(SAML_ASSERTION_NS, "Assertion");
+        let assertion = assertions.next().context("Missing assertion")?;
+        ensure!(assertions.next().is_none(), "Expected a single assertion");
+        let is_assertion_signed = verify_enveloped_signature(assertion, &idp_public_key)?;
+        ensure!(
+            is_response_signed || is_assertion_signed,
+            "Neither the response nor the assertion is signed"
+        );
+
+        let issuer = assertion
+            .child(SAML_ASSERTION_NS, "Issuer")
+            .context("Missing assertion issuer")?;
+        ensure!(
+            issuer.text() == settings.idp_entity_id,
+            "Unexpected assertion issuer {}",
+            issuer.text()
+        );
+
+        let clock_skew = Duration::seconds(settings.allowed_clock_skew_seconds);
+        let subject = assertion
+            .child(SAML_ASSERTION_NS, "Subject")
+            .context("Missing assertion subject")?;
+        let name_id = subject
+            .child(SAML_ASSERTION_NS, "NameID")
+            .map(|name_id| name_id.text())
+            .filter(|name_id| !name_id.is_empty())
+            .context("Missing subject NameID")?;
+        ensure!(
+            subject
+                .children(SAML_ASSERTION_NS, "SubjectConfirmation")
+                .any(|confirmation| is_valid_bearer_confirmation(
+                    confirmation,
+                    expectations,
+                    now,
+                    clock_skew
+                )),
+            "No valid bearer subject confirmation"
+        );
+
+        let conditions = assertion
+            .child(SAML_ASSERTION_NS, "Conditions")
+            .context("Missing assertion conditions")?;
+        if let Some(not_before) = conditions.attribute("NotBefore") {
+            ensure!(
+                parse_instant(not_before)? <= now + clock_skew,
+                "The assertion is not valid yet"
+            );
+        }
+        if let Some(not_on_or_after) = conditions.attribute("NotOnOrAfter") {
+            ensure!(
+                parse_instant(not_on_or_after)? > now - clock_skew,
+                "The assertion has expired"
+            );
+        }
+        let audience_restrictions: Vec<&XmlElement> = conditions
+            .children(SAML_ASSERTION_NS, "AudienceRestriction")
+            .collect();
+        ensure!(
+            !audience_restrictions.is_empty(),
+            "Missing assertion audience restriction"
+        );
+        for audience_restriction in audience_restrictions {
+            ensure!(
+                audience_restriction
+                    .children(SAML_ASSERTION_NS, "Audience")
+                    .any(|audience| audience.text() == expectations.sp_entity_id),
+                "The assertion is not intended for this service provider"
+            );
+        }
+
+        let attributes = assertion_attributes(assertion);
+        let attribute_mapping = &settings.attribute_mapping;
+        let email_value = attributes
+            .get(attribute_mapping.email.as_str())
+            .cloned()
+            .unwrap_or_else(|| name_id.clone());
+        let email: EmailAddress = email_value
+            .parse()
+            .with_context(|| format!("Invalid email address {email_value}"))?;
+
+        Ok(SAMLIdentity {
+            name_id,
+            email,
+            first_name: attributes
+                .get(attribute_mapping.first_name.as_str())
+                .cloned(),
+            last_name: attributes
+                .get(attribute_mapping.last_name.as_str())
+                .cloned(),
+        })
+    }
+}
+
+fn is_valid_bearer_confirmation(
+    confirmation: &XmlElement,
+    expectations: &SAMLResponseExpectations<'_>,
+    now: DateTime<Utc>,
+    clock_skew: Duration,
+) -> bool {
+    if confirmation.attribute("Method") != Some(BEARER_CONFIRMATION_METHOD) {
+        return false;
+    }
+    let Some(data) = confirmation.child(SAML_ASSERTION_NS, "SubjectConfirmationData") else {
+        return false;
+    };
+    let is_before_expiry = data
+        .attribute("NotOnOrAfter")
+        .and_then(|instant| parse_instant(instant).ok())
+        .is_some_and(|not_on_or_after| not_on_or_after > now - clock_skew);
+    let is_after_start = data
+        .attribute("NotBefore")
+        .map(parse_instant)
+        .transpose()
+        .is_ok_and(|not_before| not_before.is_none_or(|not_before| not_before <= now + clock_skew));
+
+    is_before_expiry
+        && is_after_start
+        && data.attribute("Recipient") == Some(expectations.acs_url.as_str())
+        && data
+            .attribute("InResponseTo")
+            .is_none_or(|in_response_to| in_response_to == expectations.request_id)
+}
+
+/// First value of each attribute of the assertion, by name
+fn assertion_attributes(assertion: &XmlElement) -> BTreeMap<&str, String> {
+    assertion
+        .children(SAML_ASSERTION_NS, "AttributeStatement")
+        .flat_map(|statement| statement.children(SAML_ASSERTION_NS, "Attribute"))
+        .filter_map(|attribute| {
+            let name = attribute.attribute("Name")?;
+            let value = attribute.child(SAML_ASSERTION_NS, "AttributeValue")?.text();
+            (!value.is_empty()).then_some((name, value))
+        })
+        .collect()
+}
+
+fn parse_instant(instant: &str) -> anyhow::Result<DateTime<Utc>> {
+    Ok(DateTime::parse_from_rfc3339(instant)
+        .with_context(|| format!("Invalid instant {instant}"))?
+        .with_timezone(&Utc))
+}
+
+fn decode_base64(encoded: &str) -> anyhow::Result<Vec<u8>> {
+    let encoded: String = encoded.split_ascii_whitespace().collect();
+    STANDARD
+        .decode(encoded)
+        .context("Invalid base64 encoded value")
+}
+
+/// RSA public key (PKCS#1 DER) of the identity provider certificate
+fn idp_public_key(idp_certificate: &str) -> anyhow::Result<Vec<u8>> {
+    let (_, pem) = parse_x509_pem(idp_certificate.trim().as_bytes())
+        .map_err(|err| anyhow!("Invalid identity provider certificate: {err}"))?;
+    let certificate = pem
+        .parse_x509()
+        .map_err(|err| anyhow!("Invalid identity provider certificate: {err}"))?;
+    let public_key = certificate.public_key();
+    ensure!(
+        public_key.algorithm.algorithm == OID_PKCS1_RSAENCRYPTION,
+        "Only RSA identity provider certificates are supported"
+    );
+    Ok(public_key.subject_public_key.data.to_vec())
+}
+
+/// Reject documents with duplicated IDs, which could make a signature
+/// reference another element than the one it is checked against
+fn ensure_unique_ids(root: &XmlElement) -> anyhow::Result<()> {
+    fn collect_ids<'a>(element: &'a XmlElement, ids: &mut HashSet<&'a str>) -> anyhow::Result<()> {
+        if let Some(id) = element.attribute("ID") {
+            ensure!(ids.insert(id), "Duplicated ID {id}");
+        }
+        for child in element.child_elements() {
+            collect_ids(child, ids)?;
+        }
+        Ok(())
+    }
+
+    collect_ids(root, &mut HashSet::new())
+}
+
+/// Verify the enveloped signature of `element`. Returns `false` when the
+/// element is not signed and fails when its signature is invalid.
+fn verify_enveloped_signature(element: &XmlElement, idp_public_key: &[u8]) -> anyhow::Result<bool> {
+    let mut signatures = element.children(XML_DSIG_NS, "Signature");
+    let Some(signature) = signatures.next() else {
+        return Ok(false);
+    };
+    ensure!(signatures.next().is_none(), "Expected a single signature");
+    let id = element
+        .attribute("ID")
+        .context("Signed element without an ID")?;
+
+    let signed_info = signature
+        .child(XML_DSIG_NS, "SignedInfo")
+        .context("Missing SignedInfo")?;
+    let canonicalization_method = signed_info
+        .child(XML_DSIG_NS, "CanonicalizationMethod")
+        .context("Missing CanonicalizationMethod")?;
+    ensure!(
+        canonicalization_method.attribute("Algorithm") == Some(EXCLUSIVE_C14N_ALGORITHM),
+        "Unsupported canonicalization method"
+    );
+    ensure!(
+        signed_info
+            .child(XML_DSIG_NS, "SignatureMethod")
+            .and_then(|method| method.attribute("Algorithm"))
+            == Some(RSA_SHA256_SIGNATURE_ALGORITHM),
+        "Unsupported signature method"
+    );
+
+    let mut references = signed_info.children(XML_DSIG_NS, "Reference");
+    let reference = references.next().context("Missing signature reference")?;
+    ensure!(
+        references.next().is_none(),
+        "Expected a single signature reference"
+    );
+    ensure!(
+        reference.attribute("URI") == Some(format!("#{id}").as_str()),
+        "The signature does not reference the signed element"
+    );
+
+    let mut inclusive_prefixes = None;
+    for transform in reference
+        .child(XML_DSIG_NS, "Transforms")
+        .context("Missing signature transforms")?
+        .children(XML_DSIG_NS, "Transform")
+    {
+        match transform.attribute("Algorithm") {
+            Some(ENVELOPED_SIGNATURE_TRANSFORM) => {}
+            Some(EXCLUSIVE_C14N_ALGORITHM) => {
+                inclusive_prefixes = Some(inclusive_namespace_prefixes(transform));
+            }
+            algorithm => bail!("Unsupported signature transform {algorithm:?}"),
+        }
+    }
+    let inclusive_prefixes =
+        inclusive_prefixes.context("Missing exclusive canonicalization transform")?;
+    ensure!(
+        reference
+            .child(XML_DSIG_NS, "DigestMethod")
+            .and_then(|method| method.attribute("Algorithm"))
+            == Some(SHA256_DIGEST_ALGORITHM),
+        "Unsupported digest method"
+    );
+    let expected_digest = decode_base64(
+        &reference
+            .child(XML_DSIG_NS, "DigestValue")
+            .context("Missing DigestValue")?
+            .text(),
+    )?;
+    let digest = digest::digest(
+        &digest::SHA256,
+        canonicalize(element, &inclusive_prefixes, Some(signature)).as_bytes(),
+    );
+    ensure!(
+        digest.as_ref() == expected_digest.as_slice(),
+        "The digest of the signed element does not match"
+    );
+
+    let signature_value = decode_base64(
+        &signature
+            .child(XML_DSIG_NS, "SignatureValue")
+            .context("Missing SignatureValue")?
+            .text(),
+    )?;
+    let canonical_signed_info = canonicalize(
+        signed_info,
+        &inclusive_namespace_prefixes(canonicalization_method),
+        None,
+    );
+    signature::UnparsedPublicKey::new(&signature::RSA_PKCS1_2048_8192_SHA256, idp_public_key)
+        .verify(canonical_signed_info.as_bytes(), &signature_value)
+        .map_err(|_| anyhow!("Invalid signature"))?;
+
+    Ok(true)
+}
+
+/// `PrefixList` of the `InclusiveNamespaces` parameter of an exclusive
+/// canonicalization, treated as with inclusive canonicalization
+fn inclusive_namespace_prefixes(method: &XmlElement) -> Vec<String> {
+    method
+        .child(EXCLUSIVE_C14N_ALGORITHM, "InclusiveNamespaces")
+        .and_then(|inclusive_namespaces| inclusive_namespaces.attribute("PrefixList"))
+        .map(|prefix_list| {
+            prefix_list
+                .split_ascii_whitespace()
+                .map(|prefix| {
+                    if prefix == "#default" {
+                        String::new()
+                    } else {
+                        prefix.to_string()
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[derive(Debug)]
+struct XmlElement {
+    qualified_name: String,
+    local_name: String,
+    namespace: Option<String>,
+    /// Namespaces in scope by prefix, the default namespace having an empty prefix
+    in_scope_namespaces: BTreeMap<String, String>,
+    attributes: Vec<XmlAttribute>,
+    children: Vec<XmlNode>,
+}
+
+#[derive(Debug)]
+struct XmlAttribute {
+    qualified_name: String,
+    local_name: String,
+    namespace: Option<String>,
+    value: String,
+}
+
+/// Comments are not kept, as with canonicalization without comments
+#[derive(Debug)]
+enum XmlNode {
+    Element(XmlElement),
+    Text(String),
+    ProcessingInstruction { target: String, content: String },
+}
+
+impl XmlElement {
+    fn is(&self, namespace: &str, local_name: &str) -> bool {
+        self.namespace.as_deref() == Some(namespace) && self.local_name == local_name
+    }
+
+    /// Value of the attribute `name` without namespace
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.namespace.is_none() && attribute.local_name == name)
+            .map(|attribute| attribute.value.as_str())
+    }
+
+    fn child_elements(&self) -> impl Iterator<Item = &XmlElement> {
+        self.children.iter().filter_map(|child| match child {
+            XmlNode::Element(element) => Some(element),
+            _ => None,
+        })
+    }
+
+    fn children<'a>(
+        &'a self,
+        namespace: &'a str,
+        local_name: &'a str,
+    ) -> impl Iterator<Item = &'a XmlElement> {
+        self.child_elements()
+            .filter(move |element| element.is(namespace, local_name))
+    }
+
+    fn child(&self, namespace: &str, local_name: &str) -> Option<&XmlElement> {
+        self.child_elements()
+            .find(|element| element.is(namespace, local_name))
+    }
+
+    /// Trimmed text content of the element, without its descendants' text
+    fn text(&self) -> String {
+        self.children
+            .iter()
+            .filter_map(|child| match child {
+                XmlNode::Text(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<String>()
+            .trim()
+            .to_string()
+    }
+}
+
+fn split_qualified_name(qualified_name: &str) -> (Option<&str>, &str) {
+    match qualified_name.split_once(':') {
+        Some((prefix, local_name)) => (Some(prefix), local_name),
+        None => (None, qualified_name),
+    }
+}
+
+fn parse_xml(xml: &str) -> anyhow::Result<XmlElement> {
+    let mut reader = Reader::from_str(xml);
+    let mut open_elements: Vec<XmlElement> = vec![];
+    let mut root = None;
+
+    loop {
+        match reader.read_event().context("Invalid XML")? {
+            Event::Start(start) => {
+                ensure!(root.is_none(), "Multiple XML root elements");
+                ensure!(open_elements.len() < MAX_XML_DEPTH, "XML is too deep");
+                let element = new_xml_element(&start, open_elements.last())?;
+                open_elements.push(element);
+            }
+            Event::Empty(start) => {
+                ensure!(root.is_none(), "Multiple XML root elements");
+                let element = new_xml_element(&start, open_elements.last())?;
+                close_xml_element(element, &mut open_elements, &mut root);
+            }
+            Event::End(_) => {
+                let element = open_elements.pop().context("Unexpected XML end tag")?;
+                close_xml_element(element, &mut open_elements, &mut root);
+            }
+            Event::Text(text) => push_xml_text(&mut open_elements, &text.xml10_content())?,
+            Event::CData(cdata) => push_xml_text(&mut open_elements, &cdata.xml10_content())?,
+            Event::GeneralRef(reference) => {
+                let text = match reference.resolve_char_ref().context("Invalid XML")? {
+                    Some(character) => character.to_string(),
+                    None => resolve_predefined_entity(&reference)
+                        .with_context(|| format!("Undeclared XML entity {}", &*reference))?
+                        .to_string(),
+                };
+                push_xml_text(&mut open_elements, &text)?;
+            }
+            Event::PI(instruction) => {
+                if let Some(parent) = open_elements.last_mut() {
+                    parent.children.push(XmlNode::ProcessingInstruction {
+                        target: instruction.target().to_string(),
+                        content: instruction.content().trim_start().to_string(),
+                    });
+                }
+            }
+            Event::DocType(_) => bail!("DTDs are not allowed"),
+            Event::Comment(_) | Event::Decl(_) => {}
+            Event::Eof => break,
+        }
+    }
+
+    ensure!(open_elements.is_empty(), "Unclosed XML element");
+    root.context("Empty XML document")
+}
+
+fn new_xml_element(start: &BytesStart, parent: Option<&XmlElement>) -> anyhow::Result<XmlElement> {
+    let mut in_scope_namespaces = parent
+        .map(|parent| parent.in_scope_namespaces.clone())
+        .unwrap_or_default();
+    let mut raw_attributes = vec![];
+    for attribute in start.attributes() {
+        let attribute = attribute.context("Invalid XML attribute")?;
+        let name = attribute.key.0.to_string();
+        let value = attribute
+            .normalized_value(XmlVersion::Implicit1_0)
+            .context("Invalid XML attribute value")?
+            .into_owned();
+        if name == "xmlns" {
+            in_scope_namespaces.insert(String::new(), value);
+        } else if let Some(prefix) = name.strip_prefix("xmlns:") {
+            in_scope_namespaces.insert(prefix.to_string(), value);
+        } else {
+            raw_attributes.push((name, value));
+        }
+    }
+
+    let resolve_prefix = |prefix: &str| -> anyhow::Result<String> {
+        if prefix == "xml" {
+            return Ok(XML_NS.to_string());
+        }
+        in_scope_namespaces
+            .get(prefix)
+            .filter(|namespace| !namespace.is_empty())
+            .cloned()
+            .with_context(|| format!("Undeclared XML namespace prefix {prefix}"))
+    };
+
+    let qualified_name = start.name().0.to_string();
+    let (prefix, local_name) = split_qualified_name(&qualified_name);
+    let namespace = match prefix {
+        Some(prefix) => Some(resolve_prefix(prefix)?),
+        None => in_scope_namespaces
+            .get("")
+            .filter(|namespace| !namespace.is_empty())
+            .cloned(),
+    };
+    let local_name = local_name.to_string();
+
+    let attributes = raw_attributes
+        .into_iter()
+        .map(|(qualified_name, value)| {
+            let (prefix, local_name) = split_qualified_name(&qualified_name);
+            Ok(XmlAttribute {
+                namespace: prefix.map(resolve_prefix).transpose()?,
+                local_name: local_name.to_string(),
+                qualified_name,
+                value,
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    Ok(XmlElement {
+        qualified_name,
+        local_name,
+        namespace,
+        in_scope_namespaces,
+        attributes,
+        children: vec![],
+    })
+}
+
+fn close_xml_element(
+    element: XmlElement,
+    open_elements: &mut [XmlElement],
+    root: &mut Option<XmlElement>,
+) {
+    match open_elements.last_mut() {
+        Some(parent) => parent.children.push(XmlNode::Element(element)),
+        None => *root = Some(element),
+    }
+}
+
+fn push_xml_text(open_elements: &mut [XmlElement], text: &str) -> anyhow::Result<()> {
+    let Some(parent) = open_elements.last_mut() else {
+        ensure!(
+            text.trim().is_empty(),
+            "Text outside of the XML root element"
+        );
+        return Ok(());
+    };
+    match parent.children.last_mut() {
+        Some(XmlNode::Text(previous_text)) => previous_text.push_str(text),
+        _ => parent.children.push(XmlNode::Text(text.to_string())),
+    }
+    Ok(())
+}
+
+/// Exclusive XML canonicalization, without comments, of `element` and its
+/// descendants, leaving out `excluded` (ie. the enveloped signature).
+fn canonicalize(
+    element: &XmlElement,
+    inclusive_prefixes: &[String],
+    excluded: Option<&XmlElement>,
+) -> String {
+    let mut output = String::new();
+    write_canonical_element(
+        element,
+        &BTreeMap::new(),
+        inclusive_prefixes,
+        excluded,
+        &mut output,
+    );
+    output
+}
+
+/// `rendered_namespaces` are the namespaces declared by the output ancestors
+fn write_canonical_element(
+    element: &XmlElement,
+    rendered_namespaces: &BTreeMap<String, String>,
+    inclusive_prefixes: &[String],
+    excluded: Option<&XmlElement>,
+    output: &mut String,
+) {
+    // Only the namespaces visibly utilized by the element are rendered
+    let mut prefixes: BTreeSet<&str> = BTreeSet::new();
+    prefixes.insert(
+        split_qualified_name(&element.qualified_name)
+            .0
+            .unwrap_or(""),
+    );
+    for attribute in &element.attributes {
+        if let (Some(prefix), _) = split_qualified_name(&attribute.qualified_name)
+            && prefix != "xml"
+        {
+            prefixes.insert(prefix);
+        }
+    }
+    for prefix in inclusive_prefixes {
+        if element.in_scope_namespaces.contains_key(prefix) {
+            prefixes.insert(prefix);
+        }
+    }
+
+    let mut namespaces = rendered_namespaces.clone();
+    output.push('<');
+    output.push_str(&element.qualified_name);
+    for prefix in prefixes {
+        let namespace = element
+            .in_scope_namespaces
+            .get(prefix)
+            .map(String::as_str)
+            .unwrap_or("");
+        if namespaces.get(prefix).map(String::as_str).unwrap_or("") == namespace {
+            continue;
+        }
+        if prefix.is_empty() {
+            output.push_str(" xmlns=\"");
+        } else {
+            output.push_str(" xmlns:");
+            output.push_str(prefix);
+            output.push_str("=\"");
+        }
+        output.push_str(&escape_canonical_attribute_value(namespace));
+        output.push('"');
+        namespaces.insert(prefix.to_string(), namespace.to_string());
+    }
+
+    let mut attributes: Vec<&XmlAttribute> = element.attributes.iter().collect();
+    attributes.sort_by(|attribute, other| {
+        (
+            attribute.namespace.as_deref().unwrap_or(""),
+            &attribute.local_name,
+        )
+            .cmp(&(other.namespace.as_deref().unwrap_or(""), &other.local_name))
+    });
+    for attribute in attributes {
+        output.push(' ');
+        output.push_str(&attribute.qualified_name);
+        output.push_str("=\"");
+        output.push_str(&escape_canonical_attribute_value(&attribute.value));
+        output.push('"');
+    }
+    output.push('>');
+
+    for child in &element.children {
+        match child {
+            XmlNode::Element(child_element) => {
+                if excluded.is_some_and(|excluded| std::ptr::eq(excluded, child_element)) {
+                    continue;
+                }
+                write_canonical_element(
+                    child_element,
+                    &namespaces,
+                    inclusive_prefixes,
+                    excluded,
+                    output,
+                );
+            }
+            XmlNode::Text(text) => output.push_str(&escape_canonical_text(text)),
+            XmlNode::ProcessingInstruction { target, content } => {
+                output.push_str("<?");
+                output.push_str(target);
+                if !content.is_empty() {
+                    output.push(' ');
+                    output.push_str(content);
+                }
+                output.push_str("?>");
+            }
+        }
+    }
+
+    output.push_str("</");
+    output.push_str(&element.qualified_name);
+    output.push('>');
+}
+
+fn escape_canonical_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '\r' => escaped.push_str("&#xD;"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+fn escape_canonical_attribute_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for character in value.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\t' => escaped.push_str("&#x9;"),
+            '\n' => escaped.push_str("&#xA;"),
+            '\r' => escaped.push_str("&#xD;"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, io::Read};
+
+    use chrono::TimeZone;
+    use flate2::read::DeflateDecoder;
+    use rstest::*;
+
+    use crate::configuration::SAMLAttributeMapping;
+
+    use super::*;
+
+    const REQUEST_ID: &str = "_0d7f5c8e2b2f4b8c9a6f0e1d2c3b4a59";
+    const SP_ENTITY_ID: &str = "https://inbox.example.com/api/auth/saml/metadata";
+
+    fn fixture(name: &str) -> String {
+        let path = format!(
+            "{}/tests/api/fixtures/saml/{name}",
+            env::var("CARGO_MANIFEST_DIR").unwrap()
+        );
+        fs::read_to_string(path).unwrap()
+    }
+
+    #[fixture]
+    fn settings() -> SAMLSettings {
+        SAMLSettings {
+            idp_entity_id: "https://idp.example.com/saml".to_string(),
+            idp_sso_url: "https://idp.example.com/saml/sso".parse().unwrap(),
+            idp_certificate: fixture("test_idp_certificate.pem"),
+            sp_entity_id: None,
+            attribute_mapping: SAMLAttributeMapping::default(),
+            allowed_clock_skew_seconds: 180,
+        }
+    }
+
+    #[fixture]
+    fn acs_url() -> Url {
+        "https://inbox.example.com/api/auth/saml/acs"
+            .parse()
+            .unwrap()
+    }
+
+    // The response fixture is signed at this instant
+    fn issue_instant() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 7, 25, 9, 0, 0).unwrap()
+    }
+
+    fn validate(
+        xml: &str,
+        settings: &SAMLSettings,
+        acs_url: &Url,
+        sp_entity_id: &str,
+        request_id: &str,
+        now: DateTime<Utc>,
+    ) -> Result<SAMLIdentity, UniversalInboxError> {
+        SAMLResponse::decode(&STANDARD.encode(xml))?.validate(
+            &SAMLResponseExpectations {
+                settings,
+                sp_entity_id,
+                acs_url,
+                request_id,
+            },
+            now,
+        )
+    }
+
+    #[test]
+    fn test_canonicalize_matches_exclusive_c14n_reference() {
+        // Reference output computed with `xmllint --exc-c14n`
+        let xml = r#"<?xml version="1.0"?>
+<root xmlns="urn:default" xmlns:a="urn:a" xmlns:unused="urn:unused"><!-- comment -->
+  <a:child b="2" a:attr="1" xml:lang="en" note="x&#9;y&#10;z &quot;q&quot; &lt;">text &amp; &#62; &#xD; <![CDATA[<cdata>]]></a:child>
+  <inner xmlns="urn:other"><leaf/><?pi  some data?></inner>
+  <plain xmlns=""/>
+</root>"#;
+
+        let root = parse_xml(xml).unwrap();
+
+        assert_eq!(
+            canonicalize(&root, &[], None),
+            r#"<root xmlns="urn:default">
+  <a:child xmlns:a="urn:a" b="2" note="x&#x9;y&#xA;z &quot;q&quot; &lt;" xml:lang="en" a:attr="1">text &amp; &gt; &#xD; &lt;cdata&gt;</a:child>
+  <inner xmlns="urn:other"><leaf></leaf><?pi some data?></inner>
+  <plain xmlns=""></plain>
+</root>"#
+        );
+    }
+
+    #[test]
+    fn test_parse_xml_rejects_dtd() {
+        let xml = r#"<!DOCTYPE root [<!ENTITY boom "boom">]><root>&boom;</root>"#;
+
+        assert!(parse_xml(xml).is_err());
+    }
+
+    #[rstest]
+    fn test_validate_signed_response(settings: SAMLSettings, acs_url: Url) {
+        let identity = validate(
+            &fixture("signed_response.xml"),
+            &settings,
+            &acs_url,
+            SP_ENTITY_ID,
+            REQUEST_ID,
+            issue_instant(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            identity,
+            SAMLIdentity {
+                name_id: "f6a1b2c3-d4e5-4f60-8a9b-0c1d2e3f4a5b".to_string(),
+                email: "jane.doe@example.com".parse().unwrap(),
+                first_name: Some("Jane & Co".to_string()),
+                last_name: Some("Doe".to_string()),
+            }
+        );
+    }
+
+    #[rstest]
+    #[case::tampered_attribute(
+        fixture("signed_response.xml").replace(">Doe<", ">Roe<"),
+        SP_ENTITY_ID,
+        REQUEST_ID,
+        issue_instant()
+    )]
+    #[case::other_request(
+        fixture("signed_response.xml"),
+        SP_ENTITY_ID,
+        "_another_request",
+        issue_instant()
+    )]
+    #[case::expired(
+        fixture("signed_response.xml"),
+        SP_ENTITY_ID,
+        REQUEST_ID,
+        issue_instant() + Duration::minutes(10)
+    )]
+    #[case::other_audience(
+        fixture("signed_response.xml"),
+        "https://other.example.com",
+        REQUEST_ID,
+        issue_instant()
+    )]
+    fn test_validate_rejects_invalid_response(
+        settings: SAMLSettings,
+        acs_url: Url,
+        #[case] xml: String,
+        #[case] sp_entity_id: &str,
+        #[case] request_id: &str,
+        #[case] now: DateTime<Utc>,
+    ) {
+        let result = validate(&xml, &settings, &acs_url, sp_entity_id, request_id, now);
+
+        assert!(matches!(result, Err(UniversalInboxError::Unauthorized(_))));
+    }
+
+    #[rstest]
+    fn test_validate_rejects_unsigned_response(settings: SAMLSettings, acs_url: Url) {
+        let signed_response = fixture("signed_response.xml");
+        let signature_start = signed_response.find("<ds:Signature").unwrap();
+        let signature_end =
+            signed_response.find("</ds:Signature>").unwrap() + "</ds:Signature>".len();
+        let unsigned_response = format!(
+            "{}{}",
+            &signed_response[..signature_start],
+            &signed_response[signature_end..]
+        );
+
+        let result = validate(
+            &unsigned_response,
+            &settings,
+            &acs_url,
+            SP_ENTITY_ID,
+            REQUEST_ID,
+            issue_instant(),
+        );
+
+        assert!(matches!(result, Err(UniversalInboxError::Unauthorized(_))));
+    }
+
+    #[rstest]
+    fn test_validate_rejects_wrapped_assertion(settings: SAMLSettings, acs_url: Url) {
+        // The signed assertion is moved into an extension while a forged one
+        // takes its place
+        let signed_response = fixture("signed_response.xml");
+        let assertion_start = signed_response.find("<saml:Assertion").unwrap();
+        let assertion_end =
+            signed_response.find("</saml:Assertion>").unwrap() + "</saml:Assertion>".len();
+        let signed_assertion = &signed_response[assertion_start..assertion_end];
+        let forged_assertion = signed_assertion
+            .replace("ID=\"_assertion-1\"", "ID=\"_forged\"")
+            .replace("jane.doe@example.com", "attacker@example.com");
+        let wrapped_response = format!(
+            "{}<samlp:Extensions>{signed_assertion}</samlp:Extensions>{forged_assertion}{}",
+            &signed_response[..assertion_start],
+            &signed_response[assertion_end..]
+        );
+
+        let result = validate(
+            &wrapped_response,
+            &settings,
+            &acs_url,
+            SP_ENTITY_ID,
+            REQUEST_ID,
+            issue_instant(),
+        );
+
+        assert!(matches!(result, Err(UniversalInboxError::Unauthorized(_))));
+    }
+
+    #[rstest]
+    fn test_build_authn_request_url(settings: SAMLSettings, acs_url: Url) {
+        let url = build_authn_request_url(
+            &settings,
+            SP_ENTITY_ID,
+            &acs_url,
+            REQUEST_ID,
+            issue_instant(),
+        )
+        .unwrap();
+
+        assert_eq!(url.host_str(), Some("idp.example.com"));
+        let (_, encoded_request) = url
+            .query_pairs()
+            .find(|(name, _)| name == "SAMLRequest")
+            .unwrap();
+        let mut authn_request = String::new();
+        DeflateDecoder::new(
+            STANDARD
+                .decode(encoded_request.as_bytes())
+                .unwrap()
+                .as_slice(),
+        )
+        .read_to_string(&mut authn_request)
+        .unwrap();
+        let authn_request = parse_xml(&authn_request).unwrap();
+        assert!(authn_request.is(SAML_PROTOCOL_NS, "AuthnRequest"));
+        assert_eq!(authn_request.attribute("ID"), Some(REQUEST_ID));
+        assert_eq!(
+            authn_request.attribute("AssertionConsumerServiceURL"),
+            Some(acs_url.as_str())
+        );
+        assert_eq!(
+            authn_request
+                .child(SAML_ASSERTION_NS, "Issuer")
+                .map(|issuer| issuer.text()),
+            Some(SP_ENTITY_ID.to_string())
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use anyhow::Context;
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Utc};
+use rand::RngExt;
+use ring::digest;
+use subtle::ConstantTimeEq;
+use totp_rs::{Algorithm, TOTP};
+
+use crate::universal_inbox::UniversalInboxError;
+
+pub const TOTP_ISSUER: &str = "Universal Inbox";
+pub const TOTP_DIGITS: usize = 6;
+pub const TOTP_STEP_IN_SECONDS: u64 = 30;
+/// 160 bits, the HMAC-SHA1 key length recommended by RFC 4226
+pub const TOTP_SECRET_BYTES: usize = 20;
+/// Number of time steps accepted before and after the current one, to
+/// tolerate clock drift between the server and the authenticator app.
+pub const TOTP_ALLOWED_STEP_SKEW: i64 = 1;
+pub const RECOVERY_CODES_COUNT: usize = 10;
+const RECOVERY_CODE_GROUP_LENGTH: usize = 5;
+const RECOVERY_CODE_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+pub fn generate_totp_secret() -> Vec<u8> {
+    let mut secret = vec![0u8; TOTP_SECRET_BYTES];
+    rand::rng().fill(&mut secret[..]);
+    secret
+}
+
+/// Build the TOTP of `secret` for the account `account_name` (the user email).
+/// Authenticator apps use the issuer and the account name as the label of the
+/// entry created from the provisioning URI.
+pub fn build_totp(secret: Vec<u8>, account_name: &str) -> Result<TOTP, UniversalInboxError> {
+    // `:` separates the issuer from the account name in the provisioning URI label
+    let account_name = account_name.replace(':', "");
+    Ok(TOTP::new(
+        Algorithm::SHA1,
+        TOTP_DIGITS,
+        0,
+        TOTP_STEP_IN_SECONDS,
+        secret,
+        Some(TOTP_ISSUER.to_string()),
+        account_name,
+    )
+    .context("Failed to build TOTP")?)
+}
+
+/// Check `code` against the time steps around `now` and return the matching
+/// step. Steps up to `last_used_step` are rejected so that a code cannot be
+/// used twice.
+pub fn verify_totp_code(
+    totp: &TOTP,
+    code: &str,
+    now: DateTime<Utc>,
+    last_used_step: Option<i64>,
+) -> Option<i64> {
+    let code = code.trim();
+    if code.len() != TOTP_DIGITS || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let current_step = now.timestamp() / TOTP_STEP_IN_SECONDS as i64;
+    (current_step - TOTP_ALLOWED_STEP_SKEW..=current_step + TOTP_ALLOWED_STEP_SKEW)
+        .filter(|step| *step >= 0 && last_used_step.is_none_or(|last_step| *step > last_step))
+        .find(|step| {
+            let expected_code = totp.generate(*step as u64 * TOTP_STEP_IN_SECONDS);
+            bool::from(expected_code.as_bytes().ct_eq(code.as_bytes()))
+        })
+}
+
+/// Tell apart a TOTP code from a recovery code typed in the same field
+pub fn is_totp_code(code: &str) -> bool {
+    let code = code.trim();
+    code.len() == TOTP_DIGITS && code.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Generate one-time recovery codes formatted as `xxxxx-xxxxx` (50 bits each)
+pub fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODES_COUNT)
+        .map(|_| {
+            let mut bytes = [0u8; 2 * RECOVERY_CODE_GROUP_LENGTH];
+            rand::rng().fill(&mut bytes);
+            // 256 is a multiple of the alphabet length: no modulo bias
+            let chars: String = bytes
+                .iter()
+                .map(|byte| RECOVERY_CODE_ALPHABET[(*byte as usize) % 32] as char)
+                .collect();
+            format!(
+                "{}-{}",
+                &chars[..RECOVERY_CODE_GROUP_LENGTH],
+                &chars[RECOVERY_CODE_GROUP_LENGTH..]
+            )
+        })
+        .collect()
+}
+
+/// Hash a recovery code for storage. Recovery codes are random, so a plain
+/// SHA-256 is enough. The code is normalized first so that it can be typed
+/// without the dash and in any case.
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    let digest = digest::digest(&digest::SHA256, normalized.as_bytes());
+    URL_SAFE_NO_PAD.encode(digest.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use rstest::*;
+
+    use super::*;
+
+    // RFC 6238 appendix B test secret for HMAC-SHA1
+    const RFC_6238_SECRET: &[u8] = b"12345678901234567890";
+
+    fn rfc_totp() -> TOTP {
+        build_totp(RFC_6238_SECRET.to_vec(), "john@example.com").unwrap()
+    }
+
+    #[rstest]
+    // RFC 6238 8-digit values truncated to 6 digits
+    #[case::t59(59, "287082")]
+    #[case::t1111111109(1111111109, "081804")]
+    #[case::t1234567890(1234567890, "005924")]
+    #[case::t2000000000(2000000000, "279037")]
+    fn test_totp_matches_rfc_6238_vectors(#[case] timestamp: i64, #[case] code: &str) {
+        let now = Utc.timestamp_opt(timestamp, 0).unwrap();
+
+        assert_eq!(
+            verify_totp_code(&rfc_totp(), code, now, None),
+            Some(timestamp / 30)
+        );
+    }
+
+    #[test]
+    fn test_totp_tolerates_one_step_of_clock_drift() {
+        let totp = rfc_totp();
+        let now = Utc.timestamp_opt(1111111109, 0).unwrap();
+        let step = 1111111109 / 30;
+
+        assert_eq!(
+            verify_totp_code(&totp, "081804", now + chrono::Duration::seconds(30), None),
+            Some(step)
+        );
+        assert_eq!(
+            verify_totp_code(&totp, "081804", now + chrono::Duration::seconds(90), None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_totp_code_cannot_be_replayed() {
+        let now = Utc.timestamp_opt(59, 0).unwrap();
+
+        assert_eq!(verify_totp_code(&rfc_totp(), "287082", now, Some(1)), None);
+        assert_eq!(
+            verify_totp_code(&rfc_totp(), "287082", now, Some(0)),
+            Some(1)
+        );
+    }
+
+    #[rstest]
+    #[case::wrong_code("123456")]
+    #[case::too_short("28708")]
+    #[case::not_digits("28708a")]
+    fn test_totp_rejects_invalid_codes(#[case] code: &str) {
+        let now = Utc.timestamp_opt(59, 0).unwrap();
+
+        assert_eq!(verify_totp_code(&rfc_totp(), code, now, None), None);
+    }
+
+    #[test]
+    fn test_provisioning_uri() {
+        assert_eq!(
+            rfc_totp().get_url(),
+            "otpauth://totp/Universal%20Inbox:john%40example.com?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=Universal%20Inbox"
+        );
+    }
+
+    #[test]
+    fn test_recovery_codes() {
+        let codes = generate_recovery_codes();
+
+        assert_eq!(codes.len(), RECOVERY_CODES_COUNT);
+        for code in &codes {
+            assert_eq!(code.len(), 11);
+            assert!(!is_totp_code(code));
+            assert_eq!(
+                hash_recovery_code(code),
+                hash_recovery_code(&code.to_uppercase().replace('-', " "))
+            );
+        }
+        assert_ne!(hash_recovery_code(&codes[0]), hash_recovery_code(&codes[1]));
+    }
+}
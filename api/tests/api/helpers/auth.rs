@@ -211,9 +211,24 @@ pub async fn mock_oidc_user_info(
         .await;
 }
 
+/// Fetch the API keys of the user, session tokens excluded
 pub async fn fetch_auth_tokens_for_user(
     app: &TestedApp,
     user_id: UserId,
+) -> Vec<AuthenticationToken> {
+    let mut transaction = app.repository.begin().await.unwrap();
+    let auth_tokens = app
+        .repository
+        .fetch_auth_tokens_for_user(&mut transaction, user_id, true)
+        .await
+        .unwrap();
+    transaction.commit().await.unwrap();
+    auth_tokens
+}
+
+pub async fn fetch_session_tokens_for_user(
+    app: &TestedApp,
+    user_id: UserId,
 ) -> Vec<AuthenticationToken> {
     let mut transaction = app.repository.begin().await.unwrap();
     let auth_tokens = app
@@ -223,6 +238,9 @@ pub async fn fetch_auth_tokens_for_user(
         .unwrap();
     transaction.commit().await.unwrap();
     auth_tokens
+        .into_iter()
+        .filter(|auth_token| auth_token.is_session_token)
+        .collect()
 }
 
 pub async fn get_user_auth(app: &TestedApp, user_id: UserId, kind: UserAuthKind) -> UserAuth {
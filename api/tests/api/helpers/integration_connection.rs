@@ -9,6 +9,7 @@ use universal_inbox::{
         config::IntegrationConnectionConfig,
         integrations::slack::SlackContext,
         provider::{IntegrationConnectionContext, IntegrationProviderKind},
+        sync_run::IntegrationConnectionSyncRun,
     },
     user::UserId,
 };
@@ -23,7 +24,7 @@ use universal_inbox_api::{
         oauth_credential::OAuthCredentialRepository,
     },
     universal_inbox::UpdateStatus,
-    utils::crypto::{TokenEncryptionKey, encrypt_token},
+    utils::crypto::{TokenEncryptionKeyRing, encrypt_token},
 };
 
 use crate::helpers::{TestedApp, auth::AuthenticatedApp};
@@ -61,6 +62,32 @@ pub async fn list_integration_connections(
         .expect("Cannot parse JSON result")
 }
 
+pub async fn list_integration_connection_sync_runs_response(
+    client: &Client,
+    api_address: &str,
+    integration_connection_id: IntegrationConnectionId,
+) -> Response {
+    client
+        .get(format!(
+            "{api_address}integration-connections/{integration_connection_id}/sync-runs"
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request")
+}
+
+pub async fn list_integration_connection_sync_runs(
+    client: &Client,
+    api_address: &str,
+    integration_connection_id: IntegrationConnectionId,
+) -> Vec<IntegrationConnectionSyncRun> {
+    list_integration_connection_sync_runs_response(client, api_address, integration_connection_id)
+        .await
+        .json()
+        .await
+        .expect("Cannot parse JSON result")
+}
+
 #[allow(clippy::too_many_arguments)]
 pub async fn create_integration_connection(
     app: &TestedApp,
@@ -233,19 +260,18 @@ pub async fn create_and_mock_integration_connection_with_backoff(
     )
     .await;
 
-    let token_encryption_key =
-        TokenEncryptionKey::from_hex(&settings.oauth2.token_encryption_key).unwrap();
+    let token_encryption_keys = TokenEncryptionKeyRing::from_settings(&settings.oauth2).unwrap();
     let aad_context = integration_connection.id.0.as_bytes();
     let encrypted_access_token = encrypt_token(
         credential.access_token.as_str(),
         aad_context,
-        &token_encryption_key,
+        &token_encryption_keys,
     )
     .unwrap();
     let encrypted_refresh_token = credential
         .refresh_token
         .as_ref()
-        .map(|rt| encrypt_token(rt.as_str(), aad_context, &token_encryption_key).unwrap());
+        .map(|rt| encrypt_token(rt.as_str(), aad_context, &token_encryption_keys).unwrap());
 
     let mut transaction = app.repository.begin().await.unwrap();
     app.repository
@@ -1,6 +1,5 @@
 use std::{collections::HashMap, env, fs, sync::Arc};
 
-use apalis_redis::RedisStorage;
 use openidconnect::{ClientId, IntrospectionUrl, IssuerUrl};
 use rstest::*;
 use sqlx::PgPool;
@@ -12,10 +11,11 @@ use url::Url;
 
 use universal_inbox_api::{
     configuration::{
-        AuthenticationSettings, LocalAuthenticationSettings, OIDCFlowSettings, Settings,
+        AuthenticationSettings, LocalAuthenticationSettings, OIDCFlowSettings,
+        SAMLAttributeMapping, SAMLSettings, Settings,
     },
     integrations::slack::SlackService,
-    jobs::UniversalInboxJob,
+    jobs::storage::JobStorage,
     repository::Repository,
     universal_inbox::{
         integration_connection::service::IntegrationConnectionService,
@@ -28,15 +28,17 @@ use universal_inbox_api::{
 
 use crate::common::mailer::MailerStub;
 use crate::common::{build_and_spawn, setup_test_env};
+use crate::helpers::saml::{SAML_IDP_ENTITY_ID, SAML_IDP_SSO_URL, saml_idp_certificate};
 
 // Re-export shared fixtures so rstest can resolve them by name in this module's fixtures
-pub use crate::common::{db_connection, redis_storage, settings, tracing_setup};
+pub use crate::common::{db_connection, settings, tracing_setup};
 
 pub mod auth;
 pub mod integration_connection;
 pub mod mailer;
 pub mod notification;
 pub mod rest;
+pub mod saml;
 pub mod slack;
 pub mod task;
 pub mod third_party;
@@ -65,7 +67,7 @@ pub struct TestedApp {
     pub ticktick_mock_server: MockServer,
     pub oidc_issuer_mock_server: Option<MockServer>,
     pub mailer_stub: Arc<RwLock<MailerStub>>,
-    pub redis_storage: RedisStorage<UniversalInboxJob>,
+    pub job_storage: JobStorage,
     pub cache: Cache,
 }
 
@@ -96,11 +98,11 @@ pub async fn tested_app(
     mut settings: Settings,
     #[allow(unused, clippy::let_unit_value)] tracing_setup: (),
     #[future] db_connection: Arc<PgPool>,
-    #[future] redis_storage: RedisStorage<UniversalInboxJob>,
 ) -> TestedApp {
     info!("Setting up server");
 
-    let (listener, port, cache, mock_servers) = setup_test_env(&settings).await;
+    let pool: Arc<PgPool> = db_connection.await;
+    let (listener, port, cache, mock_servers) = setup_test_env(&settings, pool.clone()).await;
 
     let oidc_issuer_mock_server = MockServer::start().await;
     let oidc_issuer_mock_server_url = &oidc_issuer_mock_server.uri();
@@ -119,15 +121,12 @@ pub async fn tested_app(
         }
     }
 
-    let pool: Arc<PgPool> = db_connection.await;
-    let redis_storage = redis_storage.await;
-
-    let (services, mailer_stub, redis_storage) = build_and_spawn(
+    let (services, mailer_stub, job_storage) = build_and_spawn(
         listener,
         pool.clone(),
         settings.clone(),
         &mock_servers,
-        redis_storage,
+        cache.clone(),
     )
     .await;
 
@@ -158,7 +157,7 @@ pub async fn tested_app(
         ticktick_mock_server: mock_servers.ticktick,
         oidc_issuer_mock_server: Some(oidc_issuer_mock_server),
         mailer_stub,
-        redis_storage,
+        job_storage,
         cache,
     }
 }
@@ -168,11 +167,11 @@ pub async fn tested_app_with_local_auth(
     mut settings: Settings,
     #[allow(unused, clippy::let_unit_value)] tracing_setup: (),
     #[future] db_connection: Arc<PgPool>,
-    #[future] redis_storage: RedisStorage<UniversalInboxJob>,
 ) -> TestedApp {
     info!("Setting up server");
 
-    let (listener, port, cache, mock_servers) = setup_test_env(&settings).await;
+    let pool: Arc<PgPool> = db_connection.await;
+    let (listener, port, cache, mock_servers) = setup_test_env(&settings, pool.clone()).await;
 
     settings.application.security.authentication =
         vec![AuthenticationSettings::Local(LocalAuthenticationSettings {
@@ -188,15 +187,90 @@ pub async fn tested_app_with_local_auth(
         })];
     settings.application.security.email_domain_blacklist = HashMap::new();
 
+    let (services, mailer_stub, job_storage) = build_and_spawn(
+        listener,
+        pool.clone(),
+        settings.clone(),
+        &mock_servers,
+        cache.clone(),
+    )
+    .await;
+
+    let app_address = format!("http://127.0.0.1:{port}");
+    let api_address = format!("{app_address}{}", settings.application.api_path);
+    let front_base_url = settings.application.front_base_url.clone();
+    let repository = Arc::new(Repository::new(pool.clone()));
+
+    TestedApp {
+        app_address,
+        api_address,
+        front_base_url,
+        repository,
+        user_service: services.user_service,
+        task_service: services.task_service,
+        notification_service: services.notification_service,
+        integration_connection_service: services.integration_connection_service,
+        third_party_item_service: services.third_party_item_service,
+        slack_service: services.slack_service,
+        oauth2_service: services.oauth2_service.clone(),
+        github_mock_server: mock_servers.github,
+        linear_mock_server: mock_servers.linear,
+        google_calendar_mock_server: mock_servers.google_calendar,
+        google_mail_mock_server: mock_servers.google_mail,
+        google_drive_mock_server: mock_servers.google_drive,
+        slack_mock_server: mock_servers.slack,
+        todoist_mock_server: mock_servers.todoist,
+        ticktick_mock_server: mock_servers.ticktick,
+        oidc_issuer_mock_server: None,
+        mailer_stub,
+        job_storage,
+        cache,
+    }
+}
+
+/// Application authenticating users with the SAML identity provider stand-in
+/// of `helpers::saml`. Local authentication is enabled as well to test the
+/// linking of a SAML identity to an existing user.
+#[fixture]
+pub async fn tested_app_with_saml_auth(
+    mut settings: Settings,
+    #[allow(unused, clippy::let_unit_value)] tracing_setup: (),
+    #[future] db_connection: Arc<PgPool>,
+) -> TestedApp {
+    info!("Setting up server with SAML authentication");
+
     let pool: Arc<PgPool> = db_connection.await;
-    let redis_storage = redis_storage.await;
+    let (listener, port, cache, mock_servers) = setup_test_env(&settings, pool.clone()).await;
 
-    let (services, mailer_stub, redis_storage) = build_and_spawn(
+    settings.application.security.authentication = vec![
+        AuthenticationSettings::Local(LocalAuthenticationSettings {
+            argon2_algorithm: argon2::Algorithm::Argon2id,
+            argon2_version: argon2::Version::V0x13,
+            argon2_memory_size: 20000,
+            argon2_iterations: 2,
+            argon2_parallelism: 1,
+            max_login_attempts: 5,
+            login_attempt_window_seconds: 900,
+            login_lockout_base_seconds: 60,
+            login_lockout_max_seconds: 900,
+        }),
+        AuthenticationSettings::SAML(Box::new(SAMLSettings {
+            idp_entity_id: SAML_IDP_ENTITY_ID.to_string(),
+            idp_sso_url: SAML_IDP_SSO_URL.parse().unwrap(),
+            idp_certificate: saml_idp_certificate(),
+            sp_entity_id: None,
+            attribute_mapping: SAMLAttributeMapping::default(),
+            allowed_clock_skew_seconds: 180,
+        })),
+    ];
+    settings.application.security.email_domain_blacklist = HashMap::new();
+
+    let (services, mailer_stub, job_storage) = build_and_spawn(
         listener,
         pool.clone(),
         settings.clone(),
         &mock_servers,
-        redis_storage,
+        cache.clone(),
     )
     .await;
 
@@ -227,7 +301,7 @@ pub async fn tested_app_with_local_auth(
         ticktick_mock_server: mock_servers.ticktick,
         oidc_issuer_mock_server: None,
         mailer_stub,
-        redis_storage,
+        job_storage,
         cache,
     }
 }
@@ -237,11 +311,11 @@ pub async fn tested_app_with_domain_blacklist(
     mut settings: Settings,
     #[allow(unused, clippy::let_unit_value)] tracing_setup: (),
     #[future] db_connection: Arc<PgPool>,
-    #[future] redis_storage: RedisStorage<UniversalInboxJob>,
 ) -> TestedApp {
     info!("Setting up server with domain blacklist");
 
-    let (listener, port, cache, mock_servers) = setup_test_env(&settings).await;
+    let pool: Arc<PgPool> = db_connection.await;
+    let (listener, port, cache, mock_servers) = setup_test_env(&settings, pool.clone()).await;
 
     let oidc_issuer_mock_server = MockServer::start().await;
     let oidc_issuer_mock_server_url = &oidc_issuer_mock_server.uri();
@@ -281,15 +355,12 @@ pub async fn tested_app_with_domain_blacklist(
         "Registration is not allowed from this domain".to_string(),
     );
 
-    let pool: Arc<PgPool> = db_connection.await;
-    let redis_storage = redis_storage.await;
-
-    let (services, mailer_stub, redis_storage) = build_and_spawn(
+    let (services, mailer_stub, job_storage) = build_and_spawn(
         listener,
         pool.clone(),
         settings.clone(),
         &mock_servers,
-        redis_storage,
+        cache.clone(),
     )
     .await;
 
@@ -320,7 +391,7 @@ pub async fn tested_app_with_domain_blacklist(
         ticktick_mock_server: mock_servers.ticktick,
         oidc_issuer_mock_server: Some(oidc_issuer_mock_server),
         mailer_stub,
-        redis_storage,
+        job_storage,
         cache,
     }
 }
@@ -0,0 +1,276 @@
+//! Stand-in for a SAML identity provider, signing its assertions with the test
+//! key of `fixtures/saml`.
+//!
+//! Assertions are written directly in their exclusive canonical form (sorted
+//! attributes, no self-closing tags, namespaces declared where they are used)
+//! so that they can be digested and signed without a canonicalization step.
+
+use std::{fs, io::Read};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use chrono::{DateTime, SecondsFormat, TimeDelta, Utc};
+use flate2::read::DeflateDecoder;
+use reqwest::{Client, header, redirect::Policy};
+use ring::{digest, rand::SystemRandom, signature};
+use url::Url;
+use uuid::Uuid;
+
+use universal_inbox::user::User;
+
+use crate::helpers::{TestedApp, fixture_path, user::get_current_user};
+
+pub const SAML_IDP_ENTITY_ID: &str = "https://idp.example.com/saml";
+pub const SAML_IDP_SSO_URL: &str = "https://idp.example.com/saml/sso";
+const SAML_LINKING_REQUEST_COOKIE_NAME: &str = "saml_linking_request";
+
+/// Authentication request received by the identity provider
+#[derive(Debug, Clone)]
+pub struct ReceivedAuthnRequest {
+    pub id: String,
+    pub acs_url: String,
+    pub sp_entity_id: String,
+}
+
+/// Identity the identity provider asserts
+#[derive(Debug, Clone)]
+pub struct SAMLTestIdentity {
+    pub name_id: String,
+    pub email: String,
+    pub first_name: String,
+    pub last_name: String,
+}
+
+pub fn saml_idp_certificate() -> String {
+    fs::read_to_string(fixture_path("saml/test_idp_certificate.pem")).unwrap()
+}
+
+/// Client of a browser posting the identity provider's response: the `303`
+/// redirection to the front is checked rather than followed.
+pub fn saml_client() -> Client {
+    Client::builder()
+        .cookie_store(true)
+        .redirect(Policy::none())
+        .build()
+        .unwrap()
+}
+
+pub async fn authorize_saml_response(client: &Client, app: &TestedApp) -> reqwest::Response {
+    client
+        .get(format!("{}auth/saml/authorize", app.api_address))
+        .send()
+        .await
+        .unwrap()
+}
+
+pub async fn authorize_link_saml_response(client: &Client, app: &TestedApp) -> reqwest::Response {
+    client
+        .get(format!("{}auth/link-saml/authorize", app.api_address))
+        .send()
+        .await
+        .unwrap()
+}
+
+/// Decode the `AuthnRequest` carried by an authorization URL (HTTP-Redirect binding)
+pub async fn receive_authn_request(response: reqwest::Response) -> ReceivedAuthnRequest {
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let authorization_url: Url = body["authorization_url"].as_str().unwrap().parse().unwrap();
+    assert!(authorization_url.as_str().starts_with(SAML_IDP_SSO_URL));
+
+    let (_, encoded_request) = authorization_url
+        .query_pairs()
+        .find(|(name, _)| name == "SAMLRequest")
+        .unwrap();
+    let mut authn_request = String::new();
+    DeflateDecoder::new(
+        STANDARD
+            .decode(encoded_request.as_bytes())
+            .unwrap()
+            .as_slice(),
+    )
+    .read_to_string(&mut authn_request)
+    .unwrap();
+
+    ReceivedAuthnRequest {
+        id: xml_attribute(&authn_request, "ID"),
+        acs_url: xml_attribute(&authn_request, "AssertionConsumerServiceURL"),
+        sp_entity_id: xml_text(&authn_request, "saml:Issuer"),
+    }
+}
+
+/// Base64 encoded `Response` to `authn_request`, with an assertion signed by
+/// the test identity provider
+pub fn build_signed_saml_response(
+    authn_request: &ReceivedAuthnRequest,
+    identity: &SAMLTestIdentity,
+) -> String {
+    let now = Utc::now();
+    let assertion_id = format!("_assertion-{}", Uuid::new_v4().simple());
+    let assertion = sign_assertion(
+        &build_canonical_assertion(&assertion_id, authn_request, identity, now),
+        &assertion_id,
+    );
+    let response = format!(
+        r#"<samlp:Response xmlns:samlp="urn:oasis:names:tc:SAML:2.0:protocol" xmlns:saml="urn:oasis:names:tc:SAML:2.0:assertion" Destination="{}" ID="_response-{}" InResponseTo="{}" IssueInstant="{}" Version="2.0"><saml:Issuer>{SAML_IDP_ENTITY_ID}</saml:Issuer><samlp:Status><samlp:StatusCode Value="urn:oasis:names:tc:SAML:2.0:status:Success"/></samlp:Status>{assertion}</samlp:Response>"#,
+        authn_request.acs_url,
+        Uuid::new_v4().simple(),
+        authn_request.id,
+        instant(now),
+    );
+
+    STANDARD.encode(response)
+}
+
+/// Post the identity provider's response to the Assertion Consumer Service
+pub async fn post_saml_response(
+    client: &Client,
+    app: &TestedApp,
+    encoded_response: &str,
+) -> reqwest::Response {
+    client
+        .post(format!("{}auth/saml/acs", app.api_address))
+        .form(&[("SAMLResponse", encoded_response)])
+        .send()
+        .await
+        .unwrap()
+}
+
+/// Start linking a SAML identity to the user logged in with `client`. Returns
+/// the authentication request and the `Cookie` header value binding it to
+/// the browser.
+pub async fn start_saml_linking(
+    client: &Client,
+    app: &TestedApp,
+) -> (ReceivedAuthnRequest, String) {
+    let response = authorize_link_saml_response(client, app).await;
+    let linking_cookie = response
+        .cookies()
+        .find(|cookie| cookie.name() == SAML_LINKING_REQUEST_COOKIE_NAME)
+        .map(|cookie| format!("{}={}", cookie.name(), cookie.value()))
+        .unwrap();
+
+    (receive_authn_request(response).await, linking_cookie)
+}
+
+/// Post the identity provider's response from the browser which started the
+/// linking flow: only its linking cookie is sent along the cross-site POST.
+pub async fn post_saml_linking_response(
+    app: &TestedApp,
+    encoded_response: &str,
+    linking_cookie: &str,
+) -> reqwest::Response {
+    saml_client()
+        .post(format!("{}auth/saml/acs", app.api_address))
+        .header(header::COOKIE, linking_cookie)
+        .form(&[("SAMLResponse", encoded_response)])
+        .send()
+        .await
+        .unwrap()
+}
+
+/// Run the whole SAML login flow and return the logged in client and user
+pub async fn saml_login(app: &TestedApp, identity: &SAMLTestIdentity) -> (Client, User) {
+    let client = saml_client();
+    let authn_request = receive_authn_request(authorize_saml_response(&client, app).await).await;
+    let response = post_saml_response(
+        &client,
+        app,
+        &build_signed_saml_response(&authn_request, identity),
+    )
+    .await;
+    assert_eq!(response.status(), 303);
+
+    let user = get_current_user(&client, app).await;
+    (client, user)
+}
+
+fn build_canonical_assertion(
+    assertion_id: &str,
+    authn_request: &ReceivedAuthnRequest,
+    identity: &SAMLTestIdentity,
+    now: DateTime<Utc>,
+) -> String {
+    let not_before = instant(now - TimeDelta::minutes(1));
+    let not_on_or_after = instant(now + TimeDelta::minutes(5));
+    let attribute = |name: &str, value: &str| {
+        format!(
+            r#"<saml:Attribute Name="{name}"><saml:AttributeValue>{}</saml:AttributeValue></saml:Attribute>"#,
+            escape_text(value)
+        )
+    };
+
+    format!(
+        r#"<saml:Assertion xmlns:saml="urn:oasis:names:tc:SAML:2.0:assertion" ID="{assertion_id}" IssueInstant="{}" Version="2.0"><saml:Issuer>{SAML_IDP_ENTITY_ID}</saml:Issuer><saml:Subject><saml:NameID Format="urn:oasis:names:tc:SAML:2.0:nameid-format:persistent">{}</saml:NameID><saml:SubjectConfirmation Method="urn:oasis:names:tc:SAML:2.0:cm:bearer"><saml:SubjectConfirmationData InResponseTo="{}" NotOnOrAfter="{not_on_or_after}" Recipient="{}"></saml:SubjectConfirmationData></saml:SubjectConfirmation></saml:Subject><saml:Conditions NotBefore="{not_before}" NotOnOrAfter="{not_on_or_after}"><saml:AudienceRestriction><saml:Audience>{}</saml:Audience></saml:AudienceRestriction></saml:Conditions><saml:AttributeStatement>{}{}{}</saml:AttributeStatement></saml:Assertion>"#,
+        instant(now),
+        escape_text(&identity.name_id),
+        authn_request.id,
+        authn_request.acs_url,
+        authn_request.sp_entity_id,
+        attribute("email", &identity.email),
+        attribute("firstName", &identity.first_name),
+        attribute("lastName", &identity.last_name),
+    )
+}
+
+/// Insert an enveloped signature right after the assertion's `Issuer`
+fn sign_assertion(canonical_assertion: &str, assertion_id: &str) -> String {
+    let digest = digest::digest(&digest::SHA256, canonical_assertion.as_bytes());
+    let signed_info = format!(
+        r##"<ds:SignedInfo xmlns:ds="http://www.w3.org/2000/09/xmldsig#"><ds:CanonicalizationMethod Algorithm="http://www.w3.org/2001/10/xml-exc-c14n#"></ds:CanonicalizationMethod><ds:SignatureMethod Algorithm="http://www.w3.org/2001/04/xmldsig-more#rsa-sha256"></ds:SignatureMethod><ds:Reference URI="#{assertion_id}"><ds:Transforms><ds:Transform Algorithm="http://www.w3.org/2000/09/xmldsig#enveloped-signature"></ds:Transform><ds:Transform Algorithm="http://www.w3.org/2001/10/xml-exc-c14n#"></ds:Transform></ds:Transforms><ds:DigestMethod Algorithm="http://www.w3.org/2001/04/xmlenc#sha256"></ds:DigestMethod><ds:DigestValue>{}</ds:DigestValue></ds:Reference></ds:SignedInfo>"##,
+        STANDARD.encode(digest.as_ref())
+    );
+
+    let key_pair = signature::RsaKeyPair::from_pkcs8(&idp_private_key()).unwrap();
+    let mut signature_value = vec![0; key_pair.public().modulus_len()];
+    key_pair
+        .sign(
+            &signature::RSA_PKCS1_SHA256,
+            &SystemRandom::new(),
+            signed_info.as_bytes(),
+            &mut signature_value,
+        )
+        .unwrap();
+    let signature = format!(
+        r#"<ds:Signature xmlns:ds="http://www.w3.org/2000/09/xmldsig#">{signed_info}<ds:SignatureValue>{}</ds:SignatureValue></ds:Signature>"#,
+        STANDARD.encode(signature_value)
+    );
+
+    let issuer_end = canonical_assertion.find("</saml:Issuer>").unwrap() + "</saml:Issuer>".len();
+    format!(
+        "{}{signature}{}",
+        &canonical_assertion[..issuer_end],
+        &canonical_assertion[issuer_end..]
+    )
+}
+
+/// PKCS#8 DER private key of the test identity provider
+fn idp_private_key() -> Vec<u8> {
+    let pem = fs::read_to_string(fixture_path("saml/test_idp_key.pem")).unwrap();
+    let encoded_key: String = pem
+        .lines()
+        .filter(|line| !line.starts_with("-----"))
+        .collect();
+    STANDARD.decode(encoded_key).unwrap()
+}
+
+fn instant(instant: DateTime<Utc>) -> String {
+    instant.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn xml_attribute(xml: &str, name: &str) -> String {
+    let start = xml.find(&format!(" {name}=\"")).unwrap() + name.len() + 3;
+    let end = start + xml[start..].find('"').unwrap();
+    xml[start..end].to_string()
+}
+
+fn xml_text(xml: &str, element: &str) -> String {
+    let start = xml.find(&format!("<{element}>")).unwrap() + element.len() + 2;
+    let end = start + xml[start..].find('<').unwrap();
+    xml[start..end].to_string()
+}
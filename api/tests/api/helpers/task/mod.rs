@@ -11,6 +11,7 @@ use universal_inbox::{
     },
 };
 
+pub mod github;
 pub mod linear;
 pub mod ticktick;
 pub mod todoist;
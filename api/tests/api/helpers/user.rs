@@ -5,16 +5,21 @@ use serde_json::Value;
 use webauthn_rs::prelude::{CreationChallengeResponse, RegisterPublicKeyCredential};
 
 use universal_inbox::{
-    auth::SessionAuthValidationParameters,
+    auth::{
+        SessionAuthValidationParameters,
+        auth_token::{AuthenticationTokenId, RevokedSessions, UserSession},
+    },
     user::{
-        Credentials, EmailValidationToken, Password, PasswordResetToken, RegisterUserParameters,
-        User, UserAuthKind, UserAuthMethod, UserId, UserPatch, Username,
+        Credentials, EmailValidationToken, Password, PasswordResetToken, RecoveryCodes,
+        RegisterUserParameters, SecondFactorCode, TotpEnrollment, TotpStatus, User, UserAuthKind,
+        UserAuthMethod, UserId, UserPatch, Username,
     },
 };
 
 use universal_inbox_api::{
     repository::user::UserRepository,
     universal_inbox::user::model::{LocalUserAuth, UserAuth},
+    utils::totp::{TOTP_STEP_IN_SECONDS, build_totp},
 };
 
 use crate::helpers::TestedApp;
@@ -348,3 +353,135 @@ pub async fn finish_passkey_registration_response_unauthenticated(
         .await
         .unwrap()
 }
+
+pub async fn get_totp_status(client: &Client, app: &TestedApp) -> TotpStatus {
+    client
+        .get(format!("{}users/me/auth-methods/totp", app.api_address))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+pub async fn start_totp_enrollment(client: &Client, app: &TestedApp) -> TotpEnrollment {
+    let response = client
+        .post(format!(
+            "{}users/me/auth-methods/totp/enrollment",
+            app.api_address
+        ))
+        .header(reqwest::header::ORIGIN, front_origin_header(app))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), http::StatusCode::OK);
+    response.json().await.unwrap()
+}
+
+pub async fn confirm_totp_enrollment_response(
+    client: &Client,
+    app: &TestedApp,
+    code: &str,
+) -> reqwest::Response {
+    client
+        .post(format!(
+            "{}users/me/auth-methods/totp/enrollment/confirmation",
+            app.api_address
+        ))
+        .header(reqwest::header::ORIGIN, front_origin_header(app))
+        .json(&SecondFactorCode {
+            code: code.to_string(),
+        })
+        .send()
+        .await
+        .unwrap()
+}
+
+pub async fn disable_totp_response(
+    client: &Client,
+    app: &TestedApp,
+    code: &str,
+) -> reqwest::Response {
+    client
+        .delete(format!("{}users/me/auth-methods/totp", app.api_address))
+        .header(reqwest::header::ORIGIN, front_origin_header(app))
+        .json(&SecondFactorCode {
+            code: code.to_string(),
+        })
+        .send()
+        .await
+        .unwrap()
+}
+
+pub async fn login_second_factor_response(
+    client: &Client,
+    app: &TestedApp,
+    code: &str,
+) -> reqwest::Response {
+    client
+        .post(format!("{}users/me/second-factor", app.api_address))
+        .header(reqwest::header::ORIGIN, front_origin_header(app))
+        .json(&SecondFactorCode {
+            code: code.to_string(),
+        })
+        .send()
+        .await
+        .unwrap()
+}
+
+/// Compute the code an authenticator app would display `steps_ahead` time
+/// steps from now. A code can only be used once, so successive calls in the
+/// same test must target distinct steps.
+pub fn totp_code(enrollment: &TotpEnrollment, steps_ahead: u64) -> String {
+    let secret = totp_rs::Secret::Encoded(enrollment.secret.clone())
+        .to_bytes()
+        .unwrap();
+    build_totp(secret, "test")
+        .unwrap()
+        .generate(chrono::Utc::now().timestamp() as u64 + steps_ahead * TOTP_STEP_IN_SECONDS)
+}
+
+/// Enroll the logged in user to TOTP and return the enrollment along with
+/// the recovery codes. The current time step is consumed by the enrollment.
+pub async fn enable_totp(client: &Client, app: &TestedApp) -> (TotpEnrollment, Vec<String>) {
+    let enrollment = start_totp_enrollment(client, app).await;
+    let response = confirm_totp_enrollment_response(client, app, &totp_code(&enrollment, 0)).await;
+    assert_eq!(response.status(), http::StatusCode::OK);
+    let RecoveryCodes { recovery_codes } = response.json().await.unwrap();
+    (enrollment, recovery_codes)
+}
+
+pub async fn list_sessions(client: &Client, app: &TestedApp) -> Vec<UserSession> {
+    let response = client
+        .get(format!("{}users/me/sessions", app.api_address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), http::StatusCode::OK);
+    response.json().await.unwrap()
+}
+
+pub async fn revoke_session_response(
+    client: &Client,
+    app: &TestedApp,
+    session_id: &AuthenticationTokenId,
+) -> reqwest::Response {
+    client
+        .delete(format!("{}users/me/sessions/{session_id}", app.api_address))
+        .header(reqwest::header::ORIGIN, front_origin_header(app))
+        .send()
+        .await
+        .unwrap()
+}
+
+pub async fn revoke_other_sessions(client: &Client, app: &TestedApp) -> RevokedSessions {
+    let response = client
+        .delete(format!("{}users/me/sessions", app.api_address))
+        .header(reqwest::header::ORIGIN, front_origin_header(app))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), http::StatusCode::OK);
+    response.json().await.unwrap()
+}
@@ -6,11 +6,13 @@ mod test_api_notification;
 mod test_auth;
 mod test_auth_methods;
 mod test_cron;
+mod test_dead_letter_jobs;
 mod test_github_notifications;
 mod test_google_calendar_notifications;
 mod test_google_drive_notifications;
 mod test_google_mail_notifications;
 mod test_health_check;
+mod test_integration_connection_sync_runs;
 mod test_integration_connections;
 mod test_linear_notifications;
 mod test_linear_tasks;
@@ -22,13 +24,18 @@ mod test_multi_task_manager;
 mod test_notifications;
 mod test_oauth2_cimd;
 mod test_oauth_callback;
+mod test_retention_policy;
+mod test_saml;
+mod test_sessions;
 mod test_slack_bridge;
 mod test_slack_notifications;
 mod test_slack_tasks;
 mod test_slack_webhook;
 mod test_slack_webhook_message;
 mod test_slack_webhook_star_reaction;
+mod test_sync_concurrency_limiter;
 mod test_sync_github_notifications;
+mod test_sync_github_tasks;
 mod test_sync_google_drive_comments;
 mod test_sync_google_mail_threads;
 mod test_sync_linear_notifications;
@@ -41,4 +48,5 @@ mod test_third_party;
 mod test_ticktick_tasks;
 mod test_todoist_notifications;
 mod test_todoist_tasks;
+mod test_totp;
 mod test_users;
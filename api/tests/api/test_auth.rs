@@ -13,9 +13,9 @@ use universal_inbox_api::{configuration::Settings, universal_inbox::user::model:
 use crate::helpers::{
     TestedApp,
     auth::{
-        AuthenticatedApp, authenticated_app, fetch_auth_tokens_for_user, get_user_auth,
-        mock_oidc_introspection, mock_oidc_keys, mock_oidc_openid_configuration,
-        mock_oidc_user_info,
+        AuthenticatedApp, authenticated_app, fetch_auth_tokens_for_user,
+        fetch_session_tokens_for_user, get_user_auth, mock_oidc_introspection, mock_oidc_keys,
+        mock_oidc_openid_configuration, mock_oidc_user_info,
     },
     settings, tested_app,
     user::logout_user_response,
@@ -72,6 +72,8 @@ mod authenticate_session {
 
         let auth_tokens = fetch_auth_tokens_for_user(&app, user.id).await;
         assert_eq!(auth_tokens.len(), 0);
+        let session_tokens = fetch_session_tokens_for_user(&app, user.id).await;
+        assert_eq!(session_tokens.len(), 1);
 
         // Test a new ID token is updated
         let response = client
@@ -148,6 +150,14 @@ mod close_session {
 
         let close_session_response: CloseSessionResponse = response.json().await.unwrap();
 
+        let session_tokens = fetch_session_tokens_for_user(&app.app, app.user.id).await;
+        assert!(!session_tokens.is_empty());
+        assert!(
+            session_tokens
+                .iter()
+                .all(|session_token| session_token.is_revoked)
+        );
+
         let user_auth = get_user_auth(
             &app.app,
             app.user.id,
@@ -1,25 +1,54 @@
-use apalis::prelude::{Data, Storage};
+use std::sync::Arc;
+
+use apalis::prelude::Data;
 use apalis_cron::CronContext;
-use apalis_redis::RedisStorage;
-use chrono::{TimeZone, Timelike, Utc};
+use chrono::{TimeDelta, TimeZone, Timelike, Utc};
 use rstest::*;
+use sqlx::PgPool;
 use uuid::Uuid;
 
+use universal_inbox::integration_connection::{
+    config::IntegrationConnectionConfig,
+    integrations::{
+        github::GithubConfig,
+        linear::{LinearConfig, LinearSyncTaskConfig},
+        todoist::TodoistConfig,
+    },
+    rate_limit::IntegrationConnectionRateLimit,
+};
+
 use universal_inbox_api::{
-    configuration::{RefreshOAuthTokensCronSettings, Settings},
-    jobs::{
-        UniversalInboxJob,
-        cron::{handle_refresh_oauth_tokens_cron_tick, try_acquire_cron_tick_lock},
+    configuration::{RefreshOAuthTokensCronSettings, Settings, StorageBackend, SyncCronSettings},
+    jobs::cron::{
+        handle_refresh_oauth_tokens_cron_tick, handle_sync_tasks_cron_tick,
+        try_acquire_cron_tick_lock,
     },
+    repository::integration_connection::IntegrationConnectionRepository,
     utils::cache::Cache,
 };
 
-use crate::common::{redis_storage, settings};
+use crate::{
+    common::{db_connection, job_storage, settings},
+    helpers::{
+        auth::{AuthenticatedApp, authenticated_app},
+        integration_connection::{
+            OAuthCredentialFixture, create_and_mock_integration_connection,
+            github_oauth_credential, linear_oauth_credential, todoist_oauth_credential,
+        },
+    },
+};
 
 #[rstest]
+#[case::redis(StorageBackend::Redis)]
+#[case::postgres(StorageBackend::Postgres)]
 #[tokio::test]
-async fn test_try_acquire_cron_tick_lock_dedupes_same_tick(settings: Settings) {
-    let cache = Cache::new(settings.redis.connection_string())
+async fn test_try_acquire_cron_tick_lock_dedupes_same_tick(
+    mut settings: Settings,
+    #[future] db_connection: Arc<PgPool>,
+    #[case] storage_backend: StorageBackend,
+) {
+    settings.storage.backend = storage_backend;
+    let cache = Cache::new(&settings, db_connection.await)
         .await
         .expect("Failed to create cache");
     let job_name = format!("test-cron-job-{}", Uuid::new_v4());
@@ -42,13 +71,18 @@ async fn test_try_acquire_cron_tick_lock_dedupes_same_tick(settings: Settings) {
 }
 
 #[rstest]
+#[case::redis(StorageBackend::Redis)]
+#[case::postgres(StorageBackend::Postgres)]
 #[tokio::test]
 async fn test_refresh_oauth_tokens_cron_tick_enqueues_job_once(
-    settings: Settings,
-    #[future] redis_storage: RedisStorage<UniversalInboxJob>,
+    mut settings: Settings,
+    #[future] db_connection: Arc<PgPool>,
+    #[case] storage_backend: StorageBackend,
 ) {
-    let mut redis_storage = redis_storage.await;
-    let cache = Cache::new(settings.redis.connection_string())
+    settings.storage.backend = storage_backend;
+    let pool = db_connection.await;
+    let job_storage = job_storage(&settings, pool.clone()).await;
+    let cache = Cache::new(&settings, pool)
         .await
         .expect("Failed to create cache");
     let cron_settings = RefreshOAuthTokensCronSettings {
@@ -62,7 +96,7 @@ async fn test_refresh_oauth_tokens_cron_tick_enqueues_job_once(
         handle_refresh_oauth_tokens_cron_tick(
             Default::default(),
             CronContext::new(tick),
-            Data::new(redis_storage.clone()),
+            Data::new(job_storage.clone()),
             Data::new(cache.clone()),
             Data::new(cron_settings.clone()),
         )
@@ -70,10 +104,10 @@ async fn test_refresh_oauth_tokens_cron_tick_enqueues_job_once(
         .expect("Failed to handle cron tick");
     }
 
-    let queued_jobs = redis_storage
+    let queued_jobs = job_storage
         .len()
         .await
-        .expect("Failed to get Redis storage length");
+        .expect("Failed to get job storage length");
     assert_eq!(
         queued_jobs, 1,
         "the same tick handled by 2 processes should enqueue exactly 1 job"
@@ -90,3 +124,188 @@ fn test_refresh_oauth_tokens_cron_settings(settings: Settings) {
     assert_eq!(cron_settings.minutes_before_expiry, 10);
     assert_eq!(cron_settings.lock_ttl_seconds, 60);
 }
+
+#[rstest]
+#[tokio::test]
+async fn test_sync_tasks_cron_tick_enqueues_one_job_per_connection_once(
+    settings: Settings,
+    #[future] authenticated_app: AuthenticatedApp,
+    #[future] db_connection: Arc<PgPool>,
+    todoist_oauth_credential: OAuthCredentialFixture,
+    linear_oauth_credential: OAuthCredentialFixture,
+) {
+    let app = authenticated_app.await;
+    // Not consumed by the worker of the tested app
+    let job_storage = job_storage(&settings, db_connection.await).await;
+    create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Todoist(TodoistConfig::enabled()),
+        &settings,
+        todoist_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+    create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Linear(LinearConfig {
+            sync_notifications_enabled: true,
+            sync_task_config: LinearSyncTaskConfig {
+                enabled: true,
+                ..Default::default()
+            },
+        }),
+        &settings,
+        linear_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+    let cron_settings = SyncCronSettings::default();
+    let tick = Utc.with_ymd_and_hms(2026, 7, 5, 12, 0, 0).unwrap();
+
+    // Simulate 2 worker processes handling the same cron tick
+    for _ in 0..2 {
+        handle_sync_tasks_cron_tick(
+            Default::default(),
+            CronContext::new(tick),
+            Data::new(job_storage.clone()),
+            Data::new(app.app.cache.clone()),
+            Data::new(cron_settings.clone()),
+            Data::new(app.app.integration_connection_service.clone()),
+        )
+        .await
+        .expect("Failed to handle cron tick");
+    }
+
+    let queued_jobs = job_storage
+        .len()
+        .await
+        .expect("Failed to get job storage length");
+    assert_eq!(
+        queued_jobs, 2,
+        "the same tick handled by 2 processes should enqueue 1 job per connection"
+    );
+
+    // Connections which sync has just been scheduled are not due on the next tick
+    handle_sync_tasks_cron_tick(
+        Default::default(),
+        CronContext::new(tick.with_minute(1).unwrap()),
+        Data::new(job_storage.clone()),
+        Data::new(app.app.cache.clone()),
+        Data::new(cron_settings),
+        Data::new(app.app.integration_connection_service.clone()),
+    )
+    .await
+    .expect("Failed to handle cron tick");
+
+    let queued_jobs = job_storage
+        .len()
+        .await
+        .expect("Failed to get job storage length");
+    assert_eq!(queued_jobs, 2);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_sync_tasks_cron_tick_skips_disabled_and_rate_limited_connections(
+    settings: Settings,
+    #[future] authenticated_app: AuthenticatedApp,
+    #[future] db_connection: Arc<PgPool>,
+    todoist_oauth_credential: OAuthCredentialFixture,
+    linear_oauth_credential: OAuthCredentialFixture,
+    github_oauth_credential: OAuthCredentialFixture,
+) {
+    let app = authenticated_app.await;
+    // Not consumed by the worker of the tested app
+    let job_storage = job_storage(&settings, db_connection.await).await;
+    let todoist_integration_connection = create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Todoist(TodoistConfig::enabled()),
+        &settings,
+        todoist_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+    create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Linear(LinearConfig {
+            sync_notifications_enabled: true,
+            sync_task_config: LinearSyncTaskConfig {
+                enabled: false,
+                ..Default::default()
+            },
+        }),
+        &settings,
+        linear_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+    create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Github(GithubConfig::enabled()),
+        &settings,
+        github_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+    let mut transaction = app.app.repository.begin().await.unwrap();
+    app.app
+        .repository
+        .update_integration_connection_rate_limit(
+            &mut transaction,
+            Box::new(IntegrationConnectionRateLimit {
+                integration_connection_id: todoist_integration_connection.id,
+                remaining: Some(0),
+                reset_at: None,
+                limited_until: Some(Utc::now() + TimeDelta::minutes(10)),
+                updated_at: Utc::now(),
+            }),
+        )
+        .await
+        .unwrap();
+    transaction.commit().await.unwrap();
+
+    handle_sync_tasks_cron_tick(
+        Default::default(),
+        CronContext::new(Utc.with_ymd_and_hms(2026, 7, 5, 12, 0, 0).unwrap()),
+        Data::new(job_storage.clone()),
+        Data::new(app.app.cache.clone()),
+        Data::new(SyncCronSettings::default()),
+        Data::new(app.app.integration_connection_service.clone()),
+    )
+    .await
+    .expect("Failed to handle cron tick");
+
+    let queued_jobs = job_storage
+        .len()
+        .await
+        .expect("Failed to get job storage length");
+    assert_eq!(
+        queued_jobs, 1,
+        "only the Github connection has its task sync enabled and is not rate limited"
+    );
+}
+
+#[rstest]
+fn test_sync_cron_settings(settings: Settings) {
+    for cron_settings in [
+        settings.application.cron.sync_notifications,
+        settings.application.cron.sync_tasks,
+    ] {
+        // Disabled in config/default.toml
+        assert!(!cron_settings.is_enabled);
+        assert_eq!(cron_settings.schedule, "0 * * * * *");
+        assert_eq!(cron_settings.lock_ttl_seconds, 60);
+        assert_eq!(cron_settings.active_user_window_in_minutes, 15);
+        assert_eq!(cron_settings.inactive_user_min_sync_interval_in_minutes, 60);
+    }
+}
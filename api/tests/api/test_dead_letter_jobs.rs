@@ -0,0 +1,322 @@
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::anyhow;
+use apalis::prelude::{Data, TaskId};
+use chrono::{TimeDelta, Utc};
+use pretty_assertions::assert_eq;
+use rstest::*;
+use sqlx::PgPool;
+use wiremock::{
+    Mock, ResponseTemplate,
+    matchers::{method, path},
+};
+
+use universal_inbox::{
+    integration_connection::{
+        config::IntegrationConnectionConfig, integrations::github::GithubConfig,
+    },
+    notification::{NotificationStatus, NotificationSyncSourceKind, service::NotificationPatch},
+    third_party::integrations::github::GithubNotification,
+};
+
+use universal_inbox_api::{
+    configuration::Settings,
+    jobs::{
+        UniversalInboxJob,
+        dead_letter::DeadLetterJobFilter,
+        handle_universal_inbox_job,
+        sync::{SyncConcurrencyLimiter, SyncNotificationsJob, SyncTasksJob},
+    },
+    universal_inbox::{UniversalInboxError, job::service::JobService},
+};
+
+use crate::{
+    common::{db_connection, job_storage, settings},
+    helpers::{
+        auth::{AuthenticatedApp, authenticated_app},
+        integration_connection::{
+            OAuthCredentialFixture, create_and_mock_integration_connection,
+            get_integration_connection, github_oauth_credential,
+        },
+        notification::github::{create_notification_from_github_notification, github_notification},
+    },
+};
+
+#[rstest]
+#[tokio::test]
+async fn test_retry_and_purge_dead_letter_jobs(
+    settings: Settings,
+    #[future] authenticated_app: AuthenticatedApp,
+    #[future] db_connection: Arc<PgPool>,
+) {
+    let app = authenticated_app.await;
+    // Not consumed by the worker of the tested app
+    let job_storage = job_storage(&settings, db_connection.await).await;
+    let job_service = JobService::new(app.app.repository.clone());
+    let user_filter = DeadLetterJobFilter {
+        user_id: Some(app.user.id),
+        ..Default::default()
+    };
+
+    let mut transaction = job_service.begin().await.unwrap();
+    let sync_notifications_job = job_service
+        .dead_letter_job(
+            &mut transaction,
+            &UniversalInboxJob::SyncNotifications(SyncNotificationsJob {
+                source: Some(NotificationSyncSourceKind::Github),
+                user_id: Some(app.user.id),
+            }),
+            "job-1".to_string(),
+            &UniversalInboxError::Unexpected(anyhow!("Github API is down")),
+            1,
+        )
+        .await
+        .unwrap();
+    job_service
+        .dead_letter_job(
+            &mut transaction,
+            &UniversalInboxJob::SyncTasks(SyncTasksJob {
+                source: None,
+                user_id: Some(app.user.id),
+            }),
+            "job-2".to_string(),
+            &UniversalInboxError::Unexpected(anyhow!("Todoist API is down")),
+            1,
+        )
+        .await
+        .unwrap();
+
+    let dead_letter_jobs = job_service
+        .fetch_dead_letter_jobs(&mut transaction, &user_filter)
+        .await
+        .unwrap();
+    assert_eq!(dead_letter_jobs.len(), 2);
+
+    let latest_dead_letter_jobs = job_service
+        .fetch_dead_letter_jobs(
+            &mut transaction,
+            &DeadLetterJobFilter {
+                limit: Some(1),
+                ..user_filter.clone()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(latest_dead_letter_jobs, dead_letter_jobs[..1]);
+
+    let sync_notifications_filter = DeadLetterJobFilter {
+        name: Some("SyncNotifications".to_string()),
+        ..user_filter.clone()
+    };
+    let dead_letter_jobs = job_service
+        .fetch_dead_letter_jobs(&mut transaction, &sync_notifications_filter)
+        .await
+        .unwrap();
+    assert_eq!(dead_letter_jobs, vec![*sync_notifications_job.clone()]);
+    assert_eq!(
+        dead_letter_jobs[0].error_chain,
+        vec!["Github API is down".to_string()]
+    );
+
+    let old_jobs = job_service
+        .fetch_dead_letter_jobs(
+            &mut transaction,
+            &DeadLetterJobFilter {
+                failed_before: Some(Utc::now() - TimeDelta::hours(1)),
+                ..user_filter.clone()
+            },
+        )
+        .await
+        .unwrap();
+    assert!(old_jobs.is_empty());
+
+    let retried_jobs = job_service
+        .retry_dead_letter_jobs(&mut transaction, &sync_notifications_filter, &job_storage)
+        .await
+        .unwrap();
+    assert_eq!(retried_jobs, vec![*sync_notifications_job]);
+    assert_eq!(job_storage.len().await.unwrap(), 1);
+
+    let purged_jobs_count = job_service
+        .purge_dead_letter_jobs(&mut transaction, &user_filter)
+        .await
+        .unwrap();
+    // The retried job is no longer dead-lettered
+    assert_eq!(purged_jobs_count, 1);
+    assert!(
+        job_service
+            .fetch_dead_letter_jobs(&mut transaction, &user_filter)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    transaction.commit().await.unwrap();
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_failed_job_is_scheduled_again_until_its_last_attempt(
+    settings: Settings,
+    #[future] authenticated_app: AuthenticatedApp,
+    #[future] db_connection: Arc<PgPool>,
+    github_notification: Box<GithubNotification>,
+    github_oauth_credential: OAuthCredentialFixture,
+) {
+    let app = authenticated_app.await;
+    // Not consumed by the worker of the tested app
+    let job_storage = job_storage(&settings, db_connection.await).await;
+    let job_service = Arc::new(JobService::new(app.app.repository.clone()));
+    let github_integration_connection = create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Github(GithubConfig::enabled()),
+        &settings,
+        github_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+    let notification = create_notification_from_github_notification(
+        &app.app,
+        &github_notification,
+        app.user.id,
+        github_integration_connection.id,
+    )
+    .await;
+    Mock::given(method("DELETE"))
+        .and(path("/notifications/threads/1"))
+        .respond_with(ResponseTemplate::new(403))
+        .mount(&app.app.github_mock_server)
+        .await;
+
+    let job = UniversalInboxJob::ProcessNotificationSideEffects {
+        notification_id: notification.id,
+        patch: NotificationPatch {
+            status: Some(NotificationStatus::Deleted),
+            ..Default::default()
+        },
+        user_id: app.user.id,
+    };
+    let handle_job = |job: UniversalInboxJob| {
+        handle_universal_inbox_job(
+            job,
+            TaskId::new(),
+            Data::new(app.app.notification_service.clone()),
+            Data::new(app.app.task_service.clone()),
+            Data::new(app.app.integration_connection_service.clone()),
+            Data::new(app.app.third_party_item_service.clone()),
+            Data::new(app.app.slack_service.clone()),
+            Data::new(job_service.clone()),
+            Data::new(SyncConcurrencyLimiter::new(
+                app.app.cache.clone(),
+                &HashMap::new(),
+            )),
+            Data::new(job_storage.clone()),
+        )
+    };
+    let user_filter = DeadLetterJobFilter {
+        user_id: Some(app.user.id),
+        ..Default::default()
+    };
+
+    // The job is not retried while holding the worker, but scheduled again as
+    // a new job
+    handle_job(job.clone()).await.unwrap();
+    let mut transaction = job_service.begin().await.unwrap();
+    assert!(
+        job_service
+            .fetch_dead_letter_jobs(&mut transaction, &user_filter)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    let max_attempts = job.retry_policy().max_attempts;
+    let result = handle_job(UniversalInboxJob::Retry {
+        job: Box::new(job.clone()),
+        attempt: max_attempts,
+    })
+    .await;
+    assert!(result.is_err());
+    let dead_letter_jobs = job_service
+        .fetch_dead_letter_jobs(&mut transaction, &user_filter)
+        .await
+        .unwrap();
+    assert_eq!(dead_letter_jobs.len(), 1);
+    assert_eq!(dead_letter_jobs[0].name, "ProcessNotificationSideEffects");
+    assert_eq!(dead_letter_jobs[0].attempts, max_attempts);
+    // The original job is dead-lettered to be retried from its first attempt
+    assert_eq!(
+        dead_letter_jobs[0].payload,
+        serde_json::to_value(&job).unwrap()
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_sync_failure_recorded_on_the_connection_is_not_dead_lettered(
+    settings: Settings,
+    #[future] authenticated_app: AuthenticatedApp,
+    #[future] db_connection: Arc<PgPool>,
+    github_oauth_credential: OAuthCredentialFixture,
+) {
+    let app = authenticated_app.await;
+    // Not consumed by the worker of the tested app
+    let job_storage = job_storage(&settings, db_connection.await).await;
+    let job_service = Arc::new(JobService::new(app.app.repository.clone()));
+    let github_integration_connection = create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Github(GithubConfig::enabled()),
+        &settings,
+        github_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+    Mock::given(method("GET"))
+        .and(path("/notifications"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&app.app.github_mock_server)
+        .await;
+
+    let result = handle_universal_inbox_job(
+        UniversalInboxJob::SyncNotifications(SyncNotificationsJob {
+            source: Some(NotificationSyncSourceKind::Github),
+            user_id: Some(app.user.id),
+        }),
+        TaskId::new(),
+        Data::new(app.app.notification_service.clone()),
+        Data::new(app.app.task_service.clone()),
+        Data::new(app.app.integration_connection_service.clone()),
+        Data::new(app.app.third_party_item_service.clone()),
+        Data::new(app.app.slack_service.clone()),
+        Data::new(job_service.clone()),
+        Data::new(SyncConcurrencyLimiter::new(
+            app.app.cache.clone(),
+            &HashMap::new(),
+        )),
+        Data::new(job_storage.clone()),
+    )
+    .await;
+
+    assert!(matches!(result, Err(UniversalInboxError::Recoverable(_))));
+    let integration_connection = get_integration_connection(&app, github_integration_connection.id)
+        .await
+        .unwrap();
+    assert_eq!(integration_connection.notifications_sync_failures, 1);
+    let mut transaction = job_service.begin().await.unwrap();
+    assert!(
+        job_service
+            .fetch_dead_letter_jobs(
+                &mut transaction,
+                &DeadLetterJobFilter {
+                    user_id: Some(app.user.id),
+                    ..Default::default()
+                }
+            )
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
@@ -459,3 +459,296 @@ mod patch_resource {
         );
     }
 }
+
+mod pull_request_action {
+    use graphql_client::Response;
+    use pretty_assertions::assert_eq;
+
+    use universal_inbox::{
+        notification::service::GithubPullRequestAction,
+        third_party::integrations::github::{
+            GithubMergeMethod, GithubNotificationItem, GithubNotificationSubject,
+            GithubPullRequest, GithubPullRequestReviewDecision, GithubPullRequestState,
+        },
+    };
+    use universal_inbox_api::integrations::github::graphql::{
+        add_pull_request_review_mutation, merge_pull_request_mutation, pull_request_query,
+    };
+
+    use crate::helpers::notification::github::{
+        github_pull_request_123_response, mock_github_add_pull_request_review_mutation,
+        mock_github_merge_pull_request_mutation, mock_github_pull_request_query,
+    };
+
+    use super::*;
+
+    fn pull_request_notification(
+        github_notification: &GithubNotification,
+        github_pull_request: &GithubPullRequest,
+    ) -> GithubNotification {
+        GithubNotification {
+            subject: GithubNotificationSubject {
+                title: "Add a new feature".to_string(),
+                url: Some(
+                    "https://api.github.com/repos/octokit/octokit.rb/pulls/123"
+                        .parse()
+                        .unwrap(),
+                ),
+                latest_comment_url: None,
+                r#type: "PullRequest".to_string(),
+            },
+            item: Some(GithubNotificationItem::GithubPullRequest(
+                github_pull_request.clone(),
+            )),
+            ..github_notification.clone()
+        }
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_approve_github_pull_request(
+        settings: Settings,
+        #[future] authenticated_app: AuthenticatedApp,
+        github_oauth_credential: OAuthCredentialFixture,
+        github_notification: Box<GithubNotification>,
+        github_pull_request_123_response: Response<pull_request_query::ResponseData>,
+    ) {
+        let app = authenticated_app.await;
+        let github_integration_connection = create_and_mock_integration_connection(
+            &app.app,
+            app.user.id,
+            IntegrationConnectionConfig::Github(GithubConfig::enabled()),
+            &settings,
+            github_oauth_credential,
+            None,
+            None,
+        )
+        .await;
+        let github_pull_request: GithubPullRequest = github_pull_request_123_response
+            .data
+            .clone()
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let pull_request_notification =
+            pull_request_notification(&github_notification, &github_pull_request);
+        let expected_notification = create_notification_from_github_notification(
+            &app.app,
+            &pull_request_notification,
+            app.user.id,
+            github_integration_connection.id,
+        )
+        .await;
+
+        mock_github_add_pull_request_review_mutation(
+            &app.app.github_mock_server,
+            add_pull_request_review_mutation::Variables {
+                pull_request_id: github_pull_request.id.clone(),
+                event: add_pull_request_review_mutation::PullRequestReviewEvent::APPROVE,
+                body: Some("LGTM".to_string()),
+            },
+        )
+        .await;
+        // Refreshed pull request data once approved
+        let mut refreshed_pull_request_response = github_pull_request_123_response;
+        if let Some(pull_request) = refreshed_pull_request_response
+            .data
+            .as_mut()
+            .and_then(|data| data.repository.as_mut())
+            .and_then(|repository| repository.pull_request.as_mut())
+        {
+            pull_request.review_decision =
+                Some(pull_request_query::PullRequestReviewDecision::APPROVED);
+        }
+        mock_github_pull_request_query(
+            &app.app.github_mock_server,
+            "octokit".to_string(),
+            "octokit.rb".to_string(),
+            123,
+            &refreshed_pull_request_response,
+        )
+        .await;
+
+        let updated_notification: Box<Notification> = app
+            .client
+            .post(format!(
+                "{}notifications/{}/github/pull-request",
+                app.app.api_address, expected_notification.id
+            ))
+            .json(&GithubPullRequestAction::Approve {
+                body: Some("LGTM".to_string()),
+            })
+            .send()
+            .await
+            .expect("Failed to execute request")
+            .json()
+            .await
+            .expect("Cannot parse JSON result");
+
+        assert_eq!(updated_notification.id, expected_notification.id);
+        assert_eq!(updated_notification.status, expected_notification.status);
+        let ThirdPartyItemData::GithubNotification(github_notification) =
+            &updated_notification.source_item.data
+        else {
+            unreachable!("notification's third party item must be a GithubNotification");
+        };
+        let Some(GithubNotificationItem::GithubPullRequest(pull_request)) =
+            &github_notification.item
+        else {
+            unreachable!("Github notification's item must be a GithubPullRequest");
+        };
+        assert_eq!(
+            pull_request.review_decision,
+            Some(GithubPullRequestReviewDecision::Approved)
+        );
+
+        // The refreshed pull request data is stored
+        let stored_notification: Box<NotificationWithTask> = get_resource(
+            &app.client,
+            &app.app.api_address,
+            "notifications",
+            expected_notification.id.into(),
+        )
+        .await;
+        assert_eq!(
+            stored_notification.source_item.data,
+            updated_notification.source_item.data
+        );
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_merge_github_pull_request(
+        settings: Settings,
+        #[future] authenticated_app: AuthenticatedApp,
+        github_oauth_credential: OAuthCredentialFixture,
+        github_notification: Box<GithubNotification>,
+        github_pull_request_123_response: Response<pull_request_query::ResponseData>,
+    ) {
+        let app = authenticated_app.await;
+        let github_integration_connection = create_and_mock_integration_connection(
+            &app.app,
+            app.user.id,
+            IntegrationConnectionConfig::Github(GithubConfig::enabled()),
+            &settings,
+            github_oauth_credential,
+            None,
+            None,
+        )
+        .await;
+        let github_pull_request: GithubPullRequest = github_pull_request_123_response
+            .data
+            .clone()
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let pull_request_notification =
+            pull_request_notification(&github_notification, &github_pull_request);
+        let expected_notification = create_notification_from_github_notification(
+            &app.app,
+            &pull_request_notification,
+            app.user.id,
+            github_integration_connection.id,
+        )
+        .await;
+
+        // The merge only applies to the commit displayed in the preview
+        mock_github_merge_pull_request_mutation(
+            &app.app.github_mock_server,
+            merge_pull_request_mutation::Variables {
+                pull_request_id: github_pull_request.id.clone(),
+                merge_method: merge_pull_request_mutation::PullRequestMergeMethod::SQUASH,
+                expected_head_oid: github_pull_request.latest_commit.git_commit_id.clone(),
+            },
+        )
+        .await;
+        let mut refreshed_pull_request_response = github_pull_request_123_response;
+        if let Some(pull_request) = refreshed_pull_request_response
+            .data
+            .as_mut()
+            .and_then(|data| data.repository.as_mut())
+            .and_then(|repository| repository.pull_request.as_mut())
+        {
+            pull_request.state = pull_request_query::PullRequestState::MERGED;
+        }
+        mock_github_pull_request_query(
+            &app.app.github_mock_server,
+            "octokit".to_string(),
+            "octokit.rb".to_string(),
+            123,
+            &refreshed_pull_request_response,
+        )
+        .await;
+
+        let updated_notification: Box<Notification> = app
+            .client
+            .post(format!(
+                "{}notifications/{}/github/pull-request",
+                app.app.api_address, expected_notification.id
+            ))
+            .json(&GithubPullRequestAction::Merge {
+                merge_method: GithubMergeMethod::Squash,
+            })
+            .send()
+            .await
+            .expect("Failed to execute request")
+            .json()
+            .await
+            .expect("Cannot parse JSON result");
+
+        let ThirdPartyItemData::GithubNotification(github_notification) =
+            &updated_notification.source_item.data
+        else {
+            unreachable!("notification's third party item must be a GithubNotification");
+        };
+        let Some(GithubNotificationItem::GithubPullRequest(pull_request)) =
+            &github_notification.item
+        else {
+            unreachable!("Github notification's item must be a GithubPullRequest");
+        };
+        assert_eq!(pull_request.state, GithubPullRequestState::Merged);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_act_on_pull_request_of_a_non_pull_request_notification(
+        settings: Settings,
+        #[future] authenticated_app: AuthenticatedApp,
+        github_oauth_credential: OAuthCredentialFixture,
+        github_notification: Box<GithubNotification>,
+    ) {
+        let app = authenticated_app.await;
+        let github_integration_connection = create_and_mock_integration_connection(
+            &app.app,
+            app.user.id,
+            IntegrationConnectionConfig::Github(GithubConfig::enabled()),
+            &settings,
+            github_oauth_credential,
+            None,
+            None,
+        )
+        .await;
+        let expected_notification = create_notification_from_github_notification(
+            &app.app,
+            &github_notification,
+            app.user.id,
+            github_integration_connection.id,
+        )
+        .await;
+
+        let response = app
+            .client
+            .post(format!(
+                "{}notifications/{}/github/pull-request",
+                app.app.api_address, expected_notification.id
+            ))
+            .json(&GithubPullRequestAction::Comment {
+                body: "Looks good".to_string(),
+            })
+            .send()
+            .await
+            .expect("Failed to execute request");
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -1,7 +1,14 @@
+use pretty_assertions::assert_eq;
 use rstest::*;
 use serde_json::json;
 
-use crate::helpers::{TestedApp, tested_app};
+use universal_inbox_api::jobs::heartbeat::{send_worker_heartbeat, worker_heartbeat_key};
+
+use crate::helpers::{
+    TestedApp,
+    auth::{AuthenticatedApp, authenticated_app},
+    tested_app,
+};
 
 #[rstest]
 #[tokio::test]
@@ -64,3 +71,59 @@ async fn test_ping_is_ip_rate_limited(#[future] tested_app: TestedApp) {
         last_status
     );
 }
+
+#[rstest]
+#[tokio::test]
+async fn test_readiness_only_reports_status_to_non_admins(
+    #[future] authenticated_app: AuthenticatedApp,
+) {
+    let app = authenticated_app.await;
+    // Do not depend on the first heartbeat of the spawned worker
+    send_worker_heartbeat(&app.app.job_storage, &app.app.cache)
+        .await
+        .expect("Failed to send worker heartbeat");
+
+    let response = reqwest::Client::new()
+        .get(format!("{}/ready", app.app.app_address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body = response.text().await.expect("Failed to parse JSON result");
+    assert_eq!(json!({ "status": "ready" }).to_string(), body);
+
+    let response = app
+        .client
+        .get(format!("{}/ready", app.app.app_address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body = response.text().await.expect("Failed to parse JSON result");
+    assert_eq!(json!({ "status": "ready" }).to_string(), body);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_api_readiness_does_not_depend_on_worker_heartbeat(
+    #[future] authenticated_app: AuthenticatedApp,
+) {
+    let app = authenticated_app.await;
+    app.app
+        .cache
+        .del(&worker_heartbeat_key(&app.app.job_storage))
+        .await
+        .expect("Failed to delete worker heartbeat");
+
+    let response = reqwest::Client::new()
+        .get(format!("{}/ready", app.app.app_address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body = response.text().await.expect("Failed to parse JSON result");
+    assert_eq!(json!({ "status": "ready" }).to_string(), body);
+}
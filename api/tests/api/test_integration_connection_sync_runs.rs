@@ -0,0 +1,320 @@
+use chrono::{TimeDelta, Utc};
+use http::StatusCode;
+use pretty_assertions::assert_eq;
+use rstest::*;
+use wiremock::{
+    Mock, ResponseTemplate,
+    matchers::{method, path},
+};
+
+use universal_inbox::{
+    integration_connection::{
+        IntegrationConnectionStatus, config::IntegrationConnectionConfig,
+        integrations::todoist::TodoistConfig, provider::IntegrationProviderKind,
+        sync_run::IntegrationConnectionSyncRunKind,
+    },
+    task::{TaskCreationResult, TaskSourceKind},
+};
+use universal_inbox_api::{
+    configuration::Settings, integrations::todoist::TodoistSyncResponse,
+    universal_inbox::integration_connection::provider_health::IntegrationProviderHealthStatus,
+};
+
+use crate::helpers::{
+    auth::{AuthenticatedApp, authenticate_user, authenticated_app},
+    integration_connection::{
+        OAuthCredentialFixture, create_and_mock_integration_connection, get_integration_connection,
+        list_integration_connection_sync_runs, list_integration_connection_sync_runs_response,
+        todoist_oauth_credential,
+    },
+    settings,
+    task::{
+        sync_tasks, sync_tasks_response,
+        todoist::{
+            mock_todoist_sync_resources_service, sync_todoist_items_response,
+            sync_todoist_projects_response,
+        },
+    },
+};
+
+#[rstest]
+#[tokio::test]
+async fn test_list_sync_runs_after_successful_sync(
+    settings: Settings,
+    #[future] authenticated_app: AuthenticatedApp,
+    sync_todoist_items_response: TodoistSyncResponse,
+    sync_todoist_projects_response: TodoistSyncResponse,
+    todoist_oauth_credential: OAuthCredentialFixture,
+) {
+    let app = authenticated_app.await;
+    let integration_connection = create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Todoist(TodoistConfig::enabled()),
+        &settings,
+        todoist_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+    mock_todoist_sync_resources_service(
+        &app.app.todoist_mock_server,
+        "projects",
+        &sync_todoist_projects_response,
+        None,
+    )
+    .await;
+    mock_todoist_sync_resources_service(
+        &app.app.todoist_mock_server,
+        "items",
+        &sync_todoist_items_response,
+        None,
+    )
+    .await;
+    let todoist_items = sync_todoist_items_response.items.clone().unwrap();
+
+    let sync_runs = list_integration_connection_sync_runs(
+        &app.client,
+        &app.app.api_address,
+        integration_connection.id,
+    )
+    .await;
+    assert!(sync_runs.is_empty());
+
+    let task_creations: Vec<TaskCreationResult> = sync_tasks(
+        &app.client,
+        &app.app.api_address,
+        Some(TaskSourceKind::Todoist),
+        false,
+    )
+    .await;
+    assert_eq!(task_creations.len(), todoist_items.len());
+
+    let sync_runs = list_integration_connection_sync_runs(
+        &app.client,
+        &app.app.api_address,
+        integration_connection.id,
+    )
+    .await;
+    assert_eq!(sync_runs.len(), 1);
+    let sync_run = &sync_runs[0];
+    assert!(sync_run.is_successful());
+    assert_eq!(
+        sync_run.integration_connection_id,
+        integration_connection.id
+    );
+    assert_eq!(sync_run.kind, IntegrationConnectionSyncRunKind::Tasks);
+    assert!(sync_run.started_at <= sync_run.ended_at);
+    assert_eq!(sync_run.items_fetched, todoist_items.len() as u32);
+    assert_eq!(sync_run.items_created, todoist_items.len() as u32);
+    assert_eq!(sync_run.items_updated, 0);
+    assert!(sync_run.upstream_http_calls >= 1);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_list_sync_runs_after_failed_sync(
+    settings: Settings,
+    #[future] authenticated_app: AuthenticatedApp,
+    todoist_oauth_credential: OAuthCredentialFixture,
+) {
+    let app = authenticated_app.await;
+    let integration_connection = create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Todoist(TodoistConfig::enabled()),
+        &settings,
+        todoist_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+    Mock::given(method("POST"))
+        .and(path("/sync"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&app.app.todoist_mock_server)
+        .await;
+
+    let response = sync_tasks_response(
+        &app.client,
+        &app.app.api_address,
+        Some(TaskSourceKind::Todoist),
+        false,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+    let sync_runs = list_integration_connection_sync_runs(
+        &app.client,
+        &app.app.api_address,
+        integration_connection.id,
+    )
+    .await;
+    assert_eq!(sync_runs.len(), 1);
+    let sync_run = &sync_runs[0];
+    assert!(!sync_run.is_successful());
+    assert_eq!(sync_run.kind, IntegrationConnectionSyncRunKind::Tasks);
+    assert_eq!(sync_run.items_fetched, 0);
+    assert!(sync_run.upstream_http_calls >= 1);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_integration_provider_health_after_failed_sync(
+    settings: Settings,
+    #[future] authenticated_app: AuthenticatedApp,
+    todoist_oauth_credential: OAuthCredentialFixture,
+) {
+    let app = authenticated_app.await;
+    create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Todoist(TodoistConfig::enabled()),
+        &settings,
+        todoist_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+    Mock::given(method("POST"))
+        .and(path("/sync"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&app.app.todoist_mock_server)
+        .await;
+    let started_since = Utc::now();
+
+    let response = sync_tasks_response(
+        &app.client,
+        &app.app.api_address,
+        Some(TaskSourceKind::Todoist),
+        false,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+    let service = app.app.integration_connection_service.read().await;
+    let mut transaction = service.begin().await.unwrap();
+    let providers_health = service
+        .fetch_integration_provider_health(&mut transaction, started_since)
+        .await
+        .unwrap();
+
+    assert_eq!(providers_health.len(), 1);
+    let provider_health = &providers_health[0];
+    assert_eq!(
+        provider_health.provider_kind,
+        IntegrationProviderKind::Todoist
+    );
+    assert_eq!(
+        provider_health.status,
+        IntegrationProviderHealthStatus::Unhealthy
+    );
+    assert_eq!(provider_health.sync_runs_count, 1);
+    assert_eq!(provider_health.failed_sync_runs_count, 1);
+    assert_eq!(provider_health.last_successful_sync_at, None);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_list_sync_runs_of_another_user(
+    settings: Settings,
+    #[future] authenticated_app: AuthenticatedApp,
+    todoist_oauth_credential: OAuthCredentialFixture,
+) {
+    let app = authenticated_app.await;
+    let integration_connection = create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Todoist(TodoistConfig::enabled()),
+        &settings,
+        todoist_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+    let (client, _user) =
+        authenticate_user(&app.app, "5678", "Jane", "Doe", "jane@example.com").await;
+
+    let response = list_integration_connection_sync_runs_response(
+        &client,
+        &app.app.api_address,
+        integration_connection.id,
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_sync_deferred_when_upstream_budget_is_exhausted(
+    settings: Settings,
+    #[future] authenticated_app: AuthenticatedApp,
+    todoist_oauth_credential: OAuthCredentialFixture,
+) {
+    let app = authenticated_app.await;
+    let integration_connection = create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Todoist(TodoistConfig::enabled()),
+        &settings,
+        todoist_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+    let reset_at = Utc::now() + TimeDelta::minutes(10);
+    Mock::given(method("POST"))
+        .and(path("/sync"))
+        .respond_with(
+            ResponseTemplate::new(403)
+                .insert_header("x-ratelimit-remaining", "0")
+                .insert_header(
+                    "x-ratelimit-reset",
+                    reset_at.timestamp().to_string().as_str(),
+                ),
+        )
+        .mount(&app.app.todoist_mock_server)
+        .await;
+
+    let response = sync_tasks_response(
+        &app.client,
+        &app.app.api_address,
+        Some(TaskSourceKind::Todoist),
+        false,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(response.headers().contains_key("retry-after"));
+    let body = response.text().await.unwrap();
+    assert!(body.contains("rate limited until"));
+
+    // A rate limited sync is not counted as a failure
+    let integration_connection = get_integration_connection(&app, integration_connection.id)
+        .await
+        .unwrap();
+    assert_eq!(integration_connection.tasks_sync_failures, 0);
+    assert_eq!(
+        integration_connection.status,
+        IntegrationConnectionStatus::Validated
+    );
+
+    // Until the budget resets, the provider is not called again
+    let response = sync_tasks_response(
+        &app.client,
+        &app.app.api_address,
+        Some(TaskSourceKind::Todoist),
+        false,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    let sync_runs = list_integration_connection_sync_runs(
+        &app.client,
+        &app.app.api_address,
+        integration_connection.id,
+    )
+    .await;
+    assert_eq!(sync_runs.len(), 1);
+    assert_eq!(sync_runs[0].rate_limit_remaining, Some(0));
+}
@@ -161,7 +161,7 @@ mod find_access_token {
             oauth_credential::OAuthCredentialRepository,
         },
         universal_inbox::UniversalInboxError,
-        utils::crypto::{TokenEncryptionKey, encrypt_token},
+        utils::crypto::{TokenEncryptionKeyRing, encrypt_token},
     };
 
     use crate::helpers::{TestedApp, settings};
@@ -188,13 +188,13 @@ mod find_access_token {
         )
         .await;
 
-        let token_encryption_key =
-            TokenEncryptionKey::from_hex(&settings.oauth2.token_encryption_key).unwrap();
+        let token_encryption_keys =
+            TokenEncryptionKeyRing::from_settings(&settings.oauth2).unwrap();
         let aad_context = connection.id.0.as_bytes();
         let encrypted_access_token =
-            encrypt_token("expired_access_token", aad_context, &token_encryption_key).unwrap();
+            encrypt_token("expired_access_token", aad_context, &token_encryption_keys).unwrap();
         let encrypted_refresh_token =
-            refresh_token.map(|rt| encrypt_token(rt, aad_context, &token_encryption_key).unwrap());
+            refresh_token.map(|rt| encrypt_token(rt, aad_context, &token_encryption_keys).unwrap());
 
         let mut transaction = app.repository.begin().await.unwrap();
         app.repository
@@ -359,6 +359,7 @@ mod update_integration_connection_config {
             app.user.id,
             IntegrationConnectionConfig::Github(GithubConfig {
                 sync_notifications_enabled: true,
+                ..GithubConfig::default()
             }),
             IntegrationConnectionStatus::Validated,
             None,
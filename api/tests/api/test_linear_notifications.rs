@@ -7,7 +7,7 @@ use universal_inbox::{
         config::IntegrationConnectionConfig, integrations::linear::LinearConfig,
     },
     notification::{Notification, NotificationStatus, service::NotificationPatch},
-    third_party::integrations::linear::LinearNotification,
+    third_party::integrations::linear::{LinearIssue, LinearNotification},
 };
 
 use universal_inbox_api::{
@@ -439,3 +439,390 @@ mod patch_resource {
         );
     }
 }
+
+mod linear_issue_action {
+    use pretty_assertions::assert_eq;
+    use uuid::Uuid;
+
+    use universal_inbox::{
+        notification::{NotificationWithTask, service::LinearIssueAction},
+        third_party::{
+            integrations::linear::{LinearIssuePriority, LinearTeamDetails, LinearUser},
+            item::ThirdPartyItemData,
+        },
+    };
+    use universal_inbox_api::integrations::linear::graphql::{issue_query, issue_team_query};
+
+    use crate::helpers::{
+        notification::linear::{
+            create_notification_from_linear_notification, linear_issue_response,
+            linear_issue_team_response, mock_linear_issue_query, mock_linear_issue_team_query,
+            mock_linear_update_issue_assignee_query, mock_linear_update_issue_priority_query,
+        },
+        rest::get_resource,
+    };
+
+    use super::*;
+
+    fn get_linear_issue(notification: &Notification) -> LinearIssue {
+        let ThirdPartyItemData::LinearNotification(linear_notification) =
+            &notification.source_item.data
+        else {
+            unreachable!("notification's third party item must be a LinearNotification");
+        };
+        let LinearNotification::IssueNotification { issue, .. } = linear_notification.as_ref()
+        else {
+            unreachable!("Linear notification must be an IssueNotification");
+        };
+        issue.clone()
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_set_linear_issue_priority(
+        settings: Settings,
+        #[future] authenticated_app: AuthenticatedApp,
+        linear_oauth_credential: OAuthCredentialFixture,
+        sync_linear_notifications_response: Response<notifications_query::ResponseData>,
+        mut linear_issue_response: Response<issue_query::ResponseData>,
+    ) {
+        let app = authenticated_app.await;
+        let linear_notifications: Vec<LinearNotification> = sync_linear_notifications_response
+            .data
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let linear_notification = linear_notifications[2].clone(); // Get an IssueNotification
+        let linear_integration_connection = create_and_mock_integration_connection(
+            &app.app,
+            app.user.id,
+            IntegrationConnectionConfig::Linear(LinearConfig::enabled()),
+            &settings,
+            linear_oauth_credential,
+            None,
+            None,
+        )
+        .await;
+        let expected_notification = create_notification_from_linear_notification(
+            &app.app,
+            &linear_notification,
+            app.user.id,
+            linear_integration_connection.id,
+        )
+        .await;
+        let issue_id = linear_notification.get_issue_id().unwrap();
+
+        mock_linear_update_issue_priority_query(&app.app.linear_mock_server, issue_id, 2).await;
+        // Refreshed issue data once updated
+        if let Some(data) = linear_issue_response.data.as_mut() {
+            data.issue.priority = 2.0;
+        }
+        mock_linear_issue_query(
+            &app.app.linear_mock_server,
+            issue_id,
+            &linear_issue_response,
+        )
+        .await;
+
+        let updated_notification: Box<Notification> = app
+            .client
+            .post(format!(
+                "{}notifications/{}/linear/issue",
+                app.app.api_address, expected_notification.id
+            ))
+            .json(&LinearIssueAction::SetPriority {
+                priority: LinearIssuePriority::High,
+            })
+            .send()
+            .await
+            .expect("Failed to execute request")
+            .json()
+            .await
+            .expect("Cannot parse JSON result");
+
+        assert_eq!(updated_notification.id, expected_notification.id);
+        assert_eq!(
+            get_linear_issue(&updated_notification).priority,
+            LinearIssuePriority::High
+        );
+
+        // The refreshed issue data is stored
+        let stored_notification: Box<NotificationWithTask> = get_resource(
+            &app.client,
+            &app.app.api_address,
+            "notifications",
+            expected_notification.id.into(),
+        )
+        .await;
+        assert_eq!(
+            stored_notification.source_item.data,
+            updated_notification.source_item.data
+        );
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_assign_linear_issue(
+        settings: Settings,
+        #[future] authenticated_app: AuthenticatedApp,
+        linear_oauth_credential: OAuthCredentialFixture,
+        sync_linear_notifications_response: Response<notifications_query::ResponseData>,
+        mut linear_issue_response: Response<issue_query::ResponseData>,
+        linear_issue_team_response: Response<issue_team_query::ResponseData>,
+    ) {
+        let app = authenticated_app.await;
+        let linear_notifications: Vec<LinearNotification> = sync_linear_notifications_response
+            .data
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let linear_notification = linear_notifications[2].clone(); // Get an IssueNotification
+        let linear_integration_connection = create_and_mock_integration_connection(
+            &app.app,
+            app.user.id,
+            IntegrationConnectionConfig::Linear(LinearConfig::enabled()),
+            &settings,
+            linear_oauth_credential,
+            None,
+            None,
+        )
+        .await;
+        let expected_notification = create_notification_from_linear_notification(
+            &app.app,
+            &linear_notification,
+            app.user.id,
+            linear_integration_connection.id,
+        )
+        .await;
+        let issue_id = linear_notification.get_issue_id().unwrap();
+        let assignee_id: Uuid = "7d5c1b2a-2f4e-4c8d-8b6a-1e3f5a7c9d10".parse().unwrap(); // jane
+
+        mock_linear_issue_team_query(
+            &app.app.linear_mock_server,
+            issue_id,
+            &linear_issue_team_response,
+        )
+        .await;
+        mock_linear_update_issue_assignee_query(
+            &app.app.linear_mock_server,
+            issue_id,
+            Some(assignee_id),
+        )
+        .await;
+        if let Some(data) = linear_issue_response.data.as_mut() {
+            data.issue.assignee = Some(issue_query::IssueQueryIssueAssignee {
+                display_name: "jane".to_string(),
+                avatar_url: None,
+                url: "https://linear.app/universal-inbox/profiles/jane".to_string(),
+            });
+        }
+        mock_linear_issue_query(
+            &app.app.linear_mock_server,
+            issue_id,
+            &linear_issue_response,
+        )
+        .await;
+
+        let updated_notification: Box<Notification> = app
+            .client
+            .post(format!(
+                "{}notifications/{}/linear/issue",
+                app.app.api_address, expected_notification.id
+            ))
+            .json(&LinearIssueAction::Assign { assignee_id })
+            .send()
+            .await
+            .expect("Failed to execute request")
+            .json()
+            .await
+            .expect("Cannot parse JSON result");
+
+        assert_eq!(
+            get_linear_issue(&updated_notification).assignee,
+            Some(LinearUser {
+                name: "jane".to_string(),
+                avatar_url: None,
+                url: "https://linear.app/universal-inbox/profiles/jane"
+                    .parse()
+                    .unwrap(),
+            })
+        );
+    }
+
+    #[rstest]
+    #[case::unknown_workflow_state(LinearIssueAction::UpdateState {
+        state_id: Uuid::new_v4()
+    })]
+    #[case::inactive_member(LinearIssueAction::Assign {
+        assignee_id: "9b8a7c6d-5e4f-4a3b-2c1d-0e9f8a7b6c5d".parse().unwrap()
+    })]
+    #[tokio::test]
+    async fn test_act_on_linear_issue_outside_of_its_team(
+        settings: Settings,
+        #[future] authenticated_app: AuthenticatedApp,
+        linear_oauth_credential: OAuthCredentialFixture,
+        sync_linear_notifications_response: Response<notifications_query::ResponseData>,
+        linear_issue_team_response: Response<issue_team_query::ResponseData>,
+        #[case] action: LinearIssueAction,
+    ) {
+        let app = authenticated_app.await;
+        let linear_notifications: Vec<LinearNotification> = sync_linear_notifications_response
+            .data
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let linear_notification = linear_notifications[2].clone(); // Get an IssueNotification
+        let linear_integration_connection = create_and_mock_integration_connection(
+            &app.app,
+            app.user.id,
+            IntegrationConnectionConfig::Linear(LinearConfig::enabled()),
+            &settings,
+            linear_oauth_credential,
+            None,
+            None,
+        )
+        .await;
+        let expected_notification = create_notification_from_linear_notification(
+            &app.app,
+            &linear_notification,
+            app.user.id,
+            linear_integration_connection.id,
+        )
+        .await;
+        mock_linear_issue_team_query(
+            &app.app.linear_mock_server,
+            linear_notification.get_issue_id().unwrap(),
+            &linear_issue_team_response,
+        )
+        .await;
+
+        let response = app
+            .client
+            .post(format!(
+                "{}notifications/{}/linear/issue",
+                app.app.api_address, expected_notification.id
+            ))
+            .json(&action)
+            .send()
+            .await
+            .expect("Failed to execute request");
+
+        // The issue is not updated with an ID of another team
+        assert_eq!(response.status(), 400);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_get_linear_issue_team_details(
+        settings: Settings,
+        #[future] authenticated_app: AuthenticatedApp,
+        linear_oauth_credential: OAuthCredentialFixture,
+        sync_linear_notifications_response: Response<notifications_query::ResponseData>,
+        linear_issue_team_response: Response<issue_team_query::ResponseData>,
+    ) {
+        let app = authenticated_app.await;
+        let linear_notifications: Vec<LinearNotification> = sync_linear_notifications_response
+            .data
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let linear_notification = linear_notifications[2].clone(); // Get an IssueNotification
+        let linear_integration_connection = create_and_mock_integration_connection(
+            &app.app,
+            app.user.id,
+            IntegrationConnectionConfig::Linear(LinearConfig::enabled()),
+            &settings,
+            linear_oauth_credential,
+            None,
+            None,
+        )
+        .await;
+        let expected_notification = create_notification_from_linear_notification(
+            &app.app,
+            &linear_notification,
+            app.user.id,
+            linear_integration_connection.id,
+        )
+        .await;
+        mock_linear_issue_team_query(
+            &app.app.linear_mock_server,
+            linear_notification.get_issue_id().unwrap(),
+            &linear_issue_team_response,
+        )
+        .await;
+
+        let team_details: LinearTeamDetails = app
+            .client
+            .get(format!(
+                "{}notifications/{}/linear/team",
+                app.app.api_address, expected_notification.id
+            ))
+            .send()
+            .await
+            .expect("Failed to execute request")
+            .json()
+            .await
+            .expect("Cannot parse JSON result");
+
+        // Workflow states are sorted by position
+        assert_eq!(
+            team_details
+                .workflow_states
+                .iter()
+                .map(|state| state.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Todo", "In progress", "Done"]
+        );
+        // Inactive members are ignored
+        assert_eq!(team_details.members.len(), 2);
+        assert!(team_details.members[0].is_viewer);
+        assert!(!team_details.members[1].is_viewer);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_act_on_issue_of_a_linear_project_notification(
+        settings: Settings,
+        #[future] authenticated_app: AuthenticatedApp,
+        linear_oauth_credential: OAuthCredentialFixture,
+        sync_linear_notifications_response: Response<notifications_query::ResponseData>,
+    ) {
+        let app = authenticated_app.await;
+        let linear_notifications: Vec<LinearNotification> = sync_linear_notifications_response
+            .data
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let linear_notification = linear_notifications[0].clone(); // Get a ProjectNotification
+        let linear_integration_connection = create_and_mock_integration_connection(
+            &app.app,
+            app.user.id,
+            IntegrationConnectionConfig::Linear(LinearConfig::enabled()),
+            &settings,
+            linear_oauth_credential,
+            None,
+            None,
+        )
+        .await;
+        let expected_notification = create_notification_from_linear_notification(
+            &app.app,
+            &linear_notification,
+            app.user.id,
+            linear_integration_connection.id,
+        )
+        .await;
+
+        let response = app
+            .client
+            .post(format!(
+                "{}notifications/{}/linear/issue",
+                app.app.api_address, expected_notification.id
+            ))
+            .json(&LinearIssueAction::Unassign)
+            .send()
+            .await
+            .expect("Failed to execute request");
+
+        assert_eq!(response.status(), 400);
+    }
+}
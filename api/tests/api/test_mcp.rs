@@ -1,4 +1,5 @@
 use base64::prelude::*;
+use chrono::{DateTime, Datelike, Utc, Weekday};
 use http::StatusCode;
 use ring::digest;
 use rstest::*;
@@ -256,6 +257,9 @@ mod protocol {
         assert!(tool_names.contains(&"create_task_from_notification".to_string()));
         assert!(tool_names.contains(&"list_tasks".to_string()));
         assert!(tool_names.contains(&"update_task".to_string()));
+        assert!(tool_names.contains(&"create_task".to_string()));
+        assert!(tool_names.contains(&"list_integration_connections".to_string()));
+        assert!(tool_names.contains(&"link_notification_to_task".to_string()));
     }
 
     #[rstest]
@@ -416,6 +420,34 @@ mod protocol {
 mod scenario {
     use super::*;
 
+    // Each MCP tool call needs a fresh session (sessions close after SSE response)
+    async fn mcp_tool_call(
+        app: &TestedApp,
+        token: &str,
+        tool_name: &str,
+        arguments: Value,
+    ) -> Value {
+        let client = mcp_client();
+        let (session_id, _) = mcp_initialize(&client, app, token).await;
+        let response = mcp_call(
+            &client,
+            app,
+            token,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "tools/call",
+                "params": {
+                    "name": tool_name,
+                    "arguments": arguments
+                }
+            }),
+            session_id.as_deref(),
+        )
+        .await;
+        mcp_json(response).await
+    }
+
     #[rstest]
     #[tokio::test]
     async fn manage_notifications_and_tasks_via_mcp(
@@ -489,34 +521,6 @@ mod scenario {
         )
         .await;
 
-        // Helper: each MCP tool call needs a fresh session (sessions close after SSE response)
-        async fn mcp_tool_call(
-            app: &TestedApp,
-            token: &str,
-            tool_name: &str,
-            arguments: Value,
-        ) -> Value {
-            let client = mcp_client();
-            let (session_id, _) = mcp_initialize(&client, app, token).await;
-            let response = mcp_call(
-                &client,
-                app,
-                token,
-                json!({
-                    "jsonrpc": "2.0",
-                    "id": 2,
-                    "method": "tools/call",
-                    "params": {
-                        "name": tool_name,
-                        "arguments": arguments
-                    }
-                }),
-                session_id.as_deref(),
-            )
-            .await;
-            mcp_json(response).await
-        }
-
         let body = mcp_tool_call(
             &app.app,
             &token,
@@ -619,13 +623,364 @@ mod scenario {
         assert_eq!(body["result"]["isError"], false);
         assert_eq!(body["result"]["structuredContent"]["status"], "Done");
     }
+
+    #[rstest]
+    #[tokio::test]
+    async fn manage_integrations_and_snooze_notifications_via_mcp(
+        settings: Settings,
+        #[future] authenticated_app: AuthenticatedApp,
+        sync_github_notifications: Vec<
+            universal_inbox::third_party::integrations::github::GithubNotification,
+        >,
+        github_oauth_credential: OAuthCredentialFixture,
+    ) {
+        let app = authenticated_app.await;
+        let api_key = create_api_key(&app).await;
+        let token = api_key.jwt_token.expose_secret().0.clone();
+        let github_connection = create_and_mock_integration_connection(
+            &app.app,
+            app.user.id,
+            IntegrationConnectionConfig::Github(GithubConfig::enabled()),
+            &settings,
+            github_oauth_credential,
+            None,
+            None,
+        )
+        .await;
+        let notification = create_notification_from_github_notification(
+            &app.app,
+            &sync_github_notifications[0],
+            app.user.id,
+            github_connection.id,
+        )
+        .await;
+
+        let body = mcp_tool_call(&app.app, &token, "list_integration_connections", json!({})).await;
+        assert_eq!(body["result"]["isError"], false);
+        let integration_connections =
+            body["result"]["structuredContent"]["integration_connections"]
+                .as_array()
+                .expect("Expected integration connections");
+        assert_eq!(integration_connections.len(), 1);
+        assert_eq!(
+            integration_connections[0]["id"],
+            github_connection.id.to_string()
+        );
+        assert_eq!(integration_connections[0]["provider_kind"], "Github");
+        assert_eq!(integration_connections[0]["status"], "Validated");
+        assert!(integration_connections[0].get("provider").is_none());
+
+        let body = mcp_tool_call(
+            &app.app,
+            &token,
+            "snooze_notification",
+            json!({
+                "notification_id": notification.id,
+                "preset": "next_week",
+                "timezone": "Europe/Paris"
+            }),
+        )
+        .await;
+        assert_eq!(body["result"]["isError"], false);
+        let snoozed_until: DateTime<Utc> = body["result"]["structuredContent"]["snoozed_until"]
+            .as_str()
+            .expect("Expected snoozed_until")
+            .parse()
+            .unwrap();
+        assert!(snoozed_until > Utc::now());
+        assert_eq!(
+            snoozed_until
+                .with_timezone(&chrono_tz::Europe::Paris)
+                .weekday(),
+            Weekday::Mon
+        );
+
+        let body = mcp_tool_call(
+            &app.app,
+            &token,
+            "snooze_notification",
+            json!({
+                "notification_id": notification.id,
+                "preset": "tomorrow",
+                "timezone": "Mars/Olympus_Mons"
+            }),
+        )
+        .await;
+        assert_eq!(body["error"]["code"], -32602);
+    }
+}
+
+mod resources_and_prompts {
+    use super::*;
+
+    /// Send a single MCP request on a fresh session and return the JSON-RPC
+    /// response.
+    async fn mcp_request(app: &TestedApp, token: &str, method: &str, params: Value) -> Value {
+        let client = mcp_client();
+        let (session_id, _) = mcp_initialize(&client, app, token).await;
+        let response = mcp_call(
+            &client,
+            app,
+            token,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": method,
+                "params": params
+            }),
+            session_id.as_deref(),
+        )
+        .await;
+        mcp_json(response).await
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn read_notification_and_summary_resources(
+        settings: Settings,
+        #[future] authenticated_app: AuthenticatedApp,
+        sync_github_notifications: Vec<
+            universal_inbox::third_party::integrations::github::GithubNotification,
+        >,
+        github_oauth_credential: OAuthCredentialFixture,
+    ) {
+        let app = authenticated_app.await;
+        let api_key = create_api_key(&app).await;
+        let token = api_key.jwt_token.expose_secret().0.clone();
+        let github_connection = create_and_mock_integration_connection(
+            &app.app,
+            app.user.id,
+            IntegrationConnectionConfig::Github(GithubConfig::enabled()),
+            &settings,
+            github_oauth_credential,
+            None,
+            None,
+        )
+        .await;
+        let notification = create_notification_from_github_notification(
+            &app.app,
+            &sync_github_notifications[0],
+            app.user.id,
+            github_connection.id,
+        )
+        .await;
+
+        let (_, initialize) = mcp_initialize(&mcp_client(), &app.app, &token).await;
+        assert!(initialize["result"]["capabilities"]["resources"].is_object());
+        assert!(initialize["result"]["capabilities"]["prompts"].is_object());
+
+        let body = mcp_request(&app.app, &token, "resources/list", json!({})).await;
+        assert_eq!(body["result"]["resources"][0]["uri"], "inbox://summary");
+
+        let body = mcp_request(&app.app, &token, "resources/templates/list", json!({})).await;
+        let uri_templates: Vec<&str> = body["result"]["resourceTemplates"]
+            .as_array()
+            .expect("Expected resource templates")
+            .iter()
+            .map(|template| template["uriTemplate"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            uri_templates,
+            vec!["inbox://notifications/{id}", "inbox://tasks/{id}"]
+        );
+
+        let notification_uri = format!("inbox://notifications/{}", notification.id);
+        let body = mcp_request(
+            &app.app,
+            &token,
+            "resources/read",
+            json!({ "uri": notification_uri }),
+        )
+        .await;
+        let contents = &body["result"]["contents"][0];
+        assert_eq!(contents["uri"], notification_uri.as_str());
+        assert_eq!(contents["mimeType"], "text/markdown");
+        let markdown = contents["text"].as_str().unwrap();
+        assert!(markdown.starts_with(&format!("# {}\n", notification.title)));
+        assert!(markdown.contains("- **Source**: Github"));
+        assert!(markdown.contains("## GithubNotification details"));
+
+        let body = mcp_request(
+            &app.app,
+            &token,
+            "resources/read",
+            json!({ "uri": "inbox://summary" }),
+        )
+        .await;
+        let markdown = body["result"]["contents"][0]["text"].as_str().unwrap();
+        assert!(markdown.contains("1 unread notification(s)."));
+        assert!(markdown.contains(&notification_uri));
+        assert!(markdown.contains("0 active task(s), 0 overdue, 0 due today."));
+
+        let body = mcp_request(
+            &app.app,
+            &token,
+            "resources/read",
+            json!({ "uri": format!("inbox://notifications/{}", Uuid::new_v4()) }),
+        )
+        .await;
+        assert_eq!(body["error"]["code"], -32002);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn subscribe_to_resources(
+        settings: Settings,
+        #[future] authenticated_app: AuthenticatedApp,
+        sync_github_notifications: Vec<
+            universal_inbox::third_party::integrations::github::GithubNotification,
+        >,
+        github_oauth_credential: OAuthCredentialFixture,
+    ) {
+        let app = authenticated_app.await;
+        let api_key = create_api_key(&app).await;
+        let token = api_key.jwt_token.expose_secret().0.clone();
+        let github_connection = create_and_mock_integration_connection(
+            &app.app,
+            app.user.id,
+            IntegrationConnectionConfig::Github(GithubConfig::enabled()),
+            &settings,
+            github_oauth_credential,
+            None,
+            None,
+        )
+        .await;
+        let notification = create_notification_from_github_notification(
+            &app.app,
+            &sync_github_notifications[0],
+            app.user.id,
+            github_connection.id,
+        )
+        .await;
+
+        let (_, initialize) = mcp_initialize(&mcp_client(), &app.app, &token).await;
+        assert_eq!(
+            initialize["result"]["capabilities"]["resources"]["subscribe"],
+            true
+        );
+
+        let notification_uri = format!("inbox://notifications/{}", notification.id);
+        let body = mcp_request(
+            &app.app,
+            &token,
+            "resources/subscribe",
+            json!({ "uri": notification_uri }),
+        )
+        .await;
+        assert!(body["error"].is_null(), "Unexpected error: {body}");
+
+        let body = mcp_request(
+            &app.app,
+            &token,
+            "resources/subscribe",
+            json!({ "uri": "inbox://summary" }),
+        )
+        .await;
+        assert!(body["error"].is_null(), "Unexpected error: {body}");
+
+        let body = mcp_request(
+            &app.app,
+            &token,
+            "resources/unsubscribe",
+            json!({ "uri": notification_uri }),
+        )
+        .await;
+        assert!(body["error"].is_null(), "Unexpected error: {body}");
+
+        let body = mcp_request(
+            &app.app,
+            &token,
+            "resources/subscribe",
+            json!({ "uri": format!("inbox://tasks/{}", Uuid::new_v4()) }),
+        )
+        .await;
+        assert_eq!(body["error"]["code"], -32002);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn get_prompts(#[future] authenticated_app: AuthenticatedApp) {
+        let app = authenticated_app.await;
+        let api_key = create_api_key(&app).await;
+        let token = api_key.jwt_token.expose_secret().0.clone();
+
+        let body = mcp_request(&app.app, &token, "prompts/list", json!({})).await;
+        let prompt_names: Vec<&str> = body["result"]["prompts"]
+            .as_array()
+            .expect("Expected prompts")
+            .iter()
+            .map(|prompt| prompt["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(prompt_names, vec!["triage_inbox", "plan_today"]);
+
+        let body = mcp_request(
+            &app.app,
+            &token,
+            "prompts/get",
+            json!({ "name": "triage_inbox", "arguments": { "source": "Github" } }),
+        )
+        .await;
+        let messages = body["result"]["messages"]
+            .as_array()
+            .expect("Expected prompt messages");
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0]["content"]["type"], "resource");
+        assert_eq!(messages[0]["content"]["resource"]["uri"], "inbox://summary");
+        assert!(
+            messages[0]["content"]["resource"]["text"]
+                .as_str()
+                .unwrap()
+                .starts_with("# Inbox summary")
+        );
+        assert!(
+            messages[1]["content"]["text"]
+                .as_str()
+                .unwrap()
+                .contains(r#"`sources: ["Github"]`"#)
+        );
+
+        let body = mcp_request(
+            &app.app,
+            &token,
+            "prompts/get",
+            json!({ "name": "plan_today", "arguments": { "available_time": "4 hours" } }),
+        )
+        .await;
+        assert!(
+            body["result"]["messages"][1]["content"]["text"]
+                .as_str()
+                .unwrap()
+                .contains("I have 4 hours available today.")
+        );
+
+        let body = mcp_request(
+            &app.app,
+            &token,
+            "prompts/get",
+            json!({ "name": "triage_inbox", "arguments": { "source": "Unknown" } }),
+        )
+        .await;
+        assert_eq!(body["error"]["code"], -32602);
+
+        let body = mcp_request(
+            &app.app,
+            &token,
+            "prompts/get",
+            json!({ "name": "unknown_prompt" }),
+        )
+        .await;
+        assert_eq!(body["error"]["code"], -32602);
+    }
 }
 
 mod oauth2 {
-    use universal_inbox::user::UserId;
+    use universal_inbox::{
+        auth::{auth_token::ApiKeyCreation, oauth2::OAuth2Scope},
+        user::UserId,
+    };
 
     use super::*;
-    use crate::helpers::TestedApp;
+    use crate::helpers::{TestedApp, auth::authenticate_user};
 
     fn pkce_challenge(verifier: &str) -> String {
         let digest = digest::digest(&digest::SHA256, verifier.as_bytes());
@@ -700,11 +1055,30 @@ mod oauth2 {
         user_id: UserId,
         client_id: &str,
         code_challenge: &str,
+    ) -> String {
+        oauth2_authorize_with_scope(
+            auth_client,
+            app,
+            user_id,
+            client_id,
+            code_challenge,
+            "read write",
+        )
+        .await
+    }
+
+    async fn oauth2_authorize_with_scope(
+        auth_client: &reqwest::Client,
+        app: &TestedApp,
+        user_id: UserId,
+        client_id: &str,
+        code_challenge: &str,
+        scope: &str,
     ) -> String {
         // Pre-seed the consent so the /authorize call takes the
         // "already consented" path and issues a code directly. The consent
         // flow itself is exercised by dedicated tests below.
-        seed_oauth2_consent(app, user_id, client_id, "read write").await;
+        seed_oauth2_consent(app, user_id, client_id, scope).await;
 
         // Get an API key to authenticate the authorize request
         let api_key: AuthenticationToken = auth_client
@@ -727,11 +1101,12 @@ mod oauth2 {
 
         let response = no_redirect
             .get(format!(
-                "{}oauth2/authorize?response_type=code&client_id={}&redirect_uri={}&code_challenge={}&code_challenge_method=S256&scope=read+write&state=test_state&resource={}",
+                "{}oauth2/authorize?response_type=code&client_id={}&redirect_uri={}&code_challenge={}&code_challenge_method=S256&scope={}&state=test_state&resource={}",
                 app.api_address,
                 client_id,
                 urlencoding::encode("http://localhost:12345/callback"),
                 code_challenge,
+                urlencoding::encode(scope),
                 urlencoding::encode(&resource_url),
             ))
             .bearer_auth(&token)
@@ -815,9 +1190,19 @@ mod oauth2 {
         assert!(body["resource"].as_str().unwrap().ends_with("/mcp"));
         assert!(!body["authorization_servers"].as_array().unwrap().is_empty());
         assert_eq!(body["bearer_methods_supported"], json!(["header"]));
-        assert_eq!(body["scopes_supported"], json!(["read", "write"]));
-
-        // Resource-specific variant
+        assert_eq!(
+            body["scopes_supported"],
+            json!([
+                "notifications:read",
+                "notifications:write",
+                "tasks:read",
+                "tasks:write",
+                "integrations:read",
+                "sync"
+            ])
+        );
+
+        // Resource-specific variant
         let response = client
             .get(format!(
                 "{}/.well-known/oauth-protected-resource/api/mcp",
@@ -844,16 +1229,46 @@ mod oauth2 {
         assert!(body["authorization_endpoint"].as_str().is_some());
         assert!(body["token_endpoint"].as_str().is_some());
         assert!(body["registration_endpoint"].as_str().is_some());
+        assert!(
+            body["revocation_endpoint"]
+                .as_str()
+                .unwrap()
+                .ends_with("/oauth2/revoke")
+        );
+        assert!(
+            body["introspection_endpoint"]
+                .as_str()
+                .unwrap()
+                .ends_with("/oauth2/introspect")
+        );
         assert_eq!(body["response_types_supported"], json!(["code"]));
         assert_eq!(
             body["grant_types_supported"],
-            json!(["authorization_code", "refresh_token"])
+            json!([
+                "authorization_code",
+                "refresh_token",
+                "urn:ietf:params:oauth:grant-type:device_code"
+            ])
+        );
+        assert!(
+            body["device_authorization_endpoint"]
+                .as_str()
+                .unwrap()
+                .ends_with("/oauth2/device_authorization")
         );
         assert_eq!(body["code_challenge_methods_supported"], json!(["S256"]));
         assert_eq!(
             body["token_endpoint_auth_methods_supported"],
             json!(["none"])
         );
+        assert_eq!(
+            body["revocation_endpoint_auth_methods_supported"],
+            json!(["none"])
+        );
+        assert_eq!(
+            body["introspection_endpoint_auth_methods_supported"],
+            json!(["bearer"])
+        );
         assert_eq!(body["resource_indicators_supported"], true);
         // CIMD discovery flag — clients use this to know they can pass
         // `client_id=https://example.com/client.json` instead of doing DCR.
@@ -1013,6 +1428,119 @@ mod oauth2 {
         assert!(session_id.is_some());
     }
 
+    #[rstest]
+    #[tokio::test]
+    async fn oauth2_read_only_token_cannot_call_write_tools(
+        #[future] authenticated_app: AuthenticatedApp,
+    ) {
+        let app = authenticated_app.await;
+        let registered = register_oauth2_client(&app.app).await;
+        let client_id = registered["client_id"].as_str().unwrap();
+        let code_verifier = "read-only-verifier-0123456789-0123456789-abcdef";
+        let code = oauth2_authorize_with_scope(
+            &app.client,
+            &app.app,
+            app.user.id,
+            client_id,
+            &pkce_challenge(code_verifier),
+            "notifications:read tasks:read",
+        )
+        .await;
+        let token_response = oauth2_token_exchange(
+            &reqwest::Client::new(),
+            &app.app,
+            client_id,
+            &code,
+            code_verifier,
+        )
+        .await;
+        assert_eq!(token_response["scope"], "notifications:read tasks:read");
+        let access_token = token_response["access_token"].as_str().unwrap();
+
+        let mcp = mcp_client();
+        let (session_id, _) = mcp_initialize(&mcp, &app.app, access_token).await;
+        let list_tasks = mcp_call(
+            &mcp,
+            &app.app,
+            access_token,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "tools/call",
+                "params": { "name": "list_tasks", "arguments": {} }
+            }),
+            session_id.as_deref(),
+        )
+        .await;
+        let body: Value = mcp_json(list_tasks).await;
+        assert!(body["error"].is_null(), "Unexpected error: {body}");
+
+        let mcp = mcp_client();
+        let (session_id, _) = mcp_initialize(&mcp, &app.app, access_token).await;
+        let update_task = mcp_call(
+            &mcp,
+            &app.app,
+            access_token,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 3,
+                "method": "tools/call",
+                "params": {
+                    "name": "update_task",
+                    "arguments": {
+                        "task_id": Uuid::new_v4(),
+                        "patch": { "status": "Done" }
+                    }
+                }
+            }),
+            session_id.as_deref(),
+        )
+        .await;
+        let body: Value = mcp_json(update_task).await;
+        assert_eq!(body["error"]["data"]["error"], "insufficient_scope");
+        assert_eq!(body["error"]["data"]["scope"], "tasks:write");
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn oauth2_authorize_rejects_unknown_scope(#[future] authenticated_app: AuthenticatedApp) {
+        let app = authenticated_app.await;
+        let registered = register_oauth2_client(&app.app).await;
+        let client_id = registered["client_id"].as_str().unwrap();
+        let api_key = create_api_key(&app).await;
+        let token = api_key.jwt_token.expose_secret().0.clone();
+
+        let response = no_redirect_client()
+            .get(format!(
+                "{}oauth2/authorize?response_type=code&client_id={}&redirect_uri={}&code_challenge={}&code_challenge_method=S256&scope=admin&state=test_state",
+                app.app.api_address,
+                client_id,
+                urlencoding::encode("http://localhost:12345/callback"),
+                pkce_challenge("verifier-unknown-scope"),
+            ))
+            .bearer_auth(&token)
+            .send()
+            .await
+            .expect("Failed to call /authorize");
+
+        assert_eq!(response.status(), StatusCode::FOUND);
+        let location = response
+            .headers()
+            .get("location")
+            .unwrap()
+            .to_str()
+            .unwrap();
+        let redirect_url = url::Url::parse(location).unwrap();
+        assert_eq!(redirect_url.path(), "/callback");
+        let error = redirect_url
+            .query_pairs()
+            .find(|(k, _)| k == "error")
+            .expect("Missing error in redirect")
+            .1
+            .to_string();
+        assert_eq!(error, "invalid_scope");
+    }
+
     #[rstest]
     #[tokio::test]
     async fn oauth2_rejects_invalid_pkce_verifier(#[future] authenticated_app: AuthenticatedApp) {
@@ -1726,6 +2254,44 @@ mod oauth2 {
         response["csrf_token"].as_str().unwrap().to_string()
     }
 
+    #[rstest]
+    #[tokio::test]
+    async fn consent_request_lists_requested_scopes(#[future] authenticated_app: AuthenticatedApp) {
+        let app = authenticated_app.await;
+        let registered = register_oauth2_client(&app.app).await;
+        let client_id = registered["client_id"].as_str().unwrap();
+        let (client, bearer, request_id) =
+            start_pending_consent(&app, client_id, &pkce_challenge("verifier-scopes")).await;
+
+        let response: Value = client
+            .get(format!(
+                "{}oauth2/authorize/consent?request_id={}",
+                app.app.api_address,
+                urlencoding::encode(&request_id),
+            ))
+            .bearer_auth(&bearer)
+            .send()
+            .await
+            .expect("Failed to call GET /authorize/consent")
+            .json()
+            .await
+            .expect("Failed to parse consent GET response");
+
+        assert_eq!(response["scope"], "read write");
+        // Legacy scopes are expanded into the fine-grained ones they cover
+        assert_eq!(
+            response["scopes"],
+            json!([
+                "notifications:read",
+                "notifications:write",
+                "tasks:read",
+                "tasks:write",
+                "integrations:read",
+                "sync"
+            ])
+        );
+    }
+
     #[rstest]
     #[tokio::test]
     async fn consent_post_deny_returns_access_denied(
@@ -1959,6 +2525,13 @@ mod oauth2 {
     /// `(refresh_token, client_id, user_id)`. Used as setup for race / reuse
     /// regression tests below.
     async fn mint_initial_refresh_token(app: &AuthenticatedApp) -> (String, String, UserId) {
+        let (_, refresh_token, client_id, user_id) = mint_initial_tokens(app).await;
+        (refresh_token, client_id, user_id)
+    }
+
+    /// Same as [`mint_initial_refresh_token`], also returning the access token
+    /// as the first element.
+    async fn mint_initial_tokens(app: &AuthenticatedApp) -> (String, String, String, UserId) {
         let registered = register_oauth2_client(&app.app).await;
         let client_id = registered["client_id"].as_str().unwrap().to_string();
 
@@ -1982,12 +2555,16 @@ mod oauth2 {
             code_verifier,
         )
         .await;
+        let access_token = token_response["access_token"]
+            .as_str()
+            .expect("Missing access_token in token response")
+            .to_string();
         let refresh_token = token_response["refresh_token"]
             .as_str()
             .expect("Missing refresh_token in token response")
             .to_string();
 
-        (refresh_token, client_id, app.user.id)
+        (access_token, refresh_token, client_id, app.user.id)
     }
 
     /// Regression test for universal-inbox-bkj.27: the previous SELECT → check
@@ -2141,4 +2718,616 @@ mod oauth2 {
             "After family revocation, even the previously-valid new refresh token must be rejected"
         );
     }
+
+    async fn oauth2_revoke(app: &TestedApp, token: &str, client_id: &str) {
+        let response = reqwest::Client::new()
+            .post(format!("{}oauth2/revoke", app.api_address))
+            .form(&[("token", token), ("client_id", client_id)])
+            .send()
+            .await
+            .expect("Failed to call the revocation endpoint");
+        // RFC 7009 §2.2: the endpoint answers 200 whatever the token was
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    /// API key of a resource server allowed to introspect the user's tokens
+    async fn create_introspection_api_key(client: &reqwest::Client, app: &TestedApp) -> String {
+        let auth_token: AuthenticationToken = client
+            .post(format!("{}users/me/authentication-tokens", app.api_address))
+            .json(&ApiKeyCreation {
+                scopes: Some(vec![OAuth2Scope::TokensIntrospect]),
+                ..Default::default()
+            })
+            .send()
+            .await
+            .expect("Failed to create API key")
+            .json()
+            .await
+            .expect("Failed to deserialize API key response");
+        auth_token.jwt_token.expose_secret().0.clone()
+    }
+
+    async fn oauth2_introspect_response(
+        client: &reqwest::Client,
+        app: &TestedApp,
+        api_key: Option<&str>,
+        token: &str,
+        client_id: &str,
+    ) -> reqwest::Response {
+        let request = client
+            .post(format!("{}oauth2/introspect", app.api_address))
+            .form(&[("token", token), ("client_id", client_id)]);
+        match api_key {
+            Some(api_key) => request.bearer_auth(api_key),
+            None => request,
+        }
+        .send()
+        .await
+        .expect("Failed to call the introspection endpoint")
+    }
+
+    async fn oauth2_introspect(app: &AuthenticatedApp, token: &str, client_id: &str) -> Value {
+        let api_key = create_introspection_api_key(&app.client, &app.app).await;
+        let response = oauth2_introspect_response(
+            &reqwest::Client::new(),
+            &app.app,
+            Some(&api_key),
+            token,
+            client_id,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        response
+            .json()
+            .await
+            .expect("Failed to parse introspection response")
+    }
+
+    async fn mcp_initialize_status(app: &TestedApp, access_token: &str) -> reqwest::Response {
+        mcp_call(
+            &mcp_client(),
+            app,
+            access_token,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {
+                    "protocolVersion": "2025-06-18",
+                    "capabilities": {},
+                    "clientInfo": { "name": "test-client", "version": "1.0.0" }
+                }
+            }),
+            None,
+        )
+        .await
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn introspect_reports_active_tokens(#[future] authenticated_app: AuthenticatedApp) {
+        let app = authenticated_app.await;
+        let (access_token, refresh_token, client_id, user_id) = mint_initial_tokens(&app).await;
+
+        let introspection = oauth2_introspect(&app, &access_token, &client_id).await;
+        assert_eq!(introspection["active"], true);
+        assert_eq!(introspection["token_type"], "Bearer");
+        assert_eq!(introspection["client_id"], client_id.as_str());
+        assert_eq!(introspection["sub"], user_id.to_string());
+        assert_eq!(introspection["scope"], "read write");
+        assert!(introspection["aud"].as_str().unwrap().ends_with("/mcp"));
+        assert!(introspection["exp"].as_i64().is_some());
+        assert!(introspection["jti"].as_str().is_some());
+
+        let introspection = oauth2_introspect(&app, &refresh_token, &client_id).await;
+        assert_eq!(introspection["active"], true);
+        assert_eq!(introspection["token_type"], "refresh_token");
+        assert_eq!(introspection["sub"], user_id.to_string());
+
+        // Tokens are only disclosed to the client they were issued to
+        let other_client = register_oauth2_client(&app.app).await;
+        let other_client_id = other_client["client_id"].as_str().unwrap();
+        assert_eq!(
+            oauth2_introspect(&app, &access_token, other_client_id).await,
+            json!({ "active": false })
+        );
+        assert_eq!(
+            oauth2_introspect(&app, &refresh_token, other_client_id).await,
+            json!({ "active": false })
+        );
+        assert_eq!(
+            oauth2_introspect(&app, "unknown-token", &client_id).await,
+            json!({ "active": false })
+        );
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn introspect_requires_a_resource_server_api_key(
+        #[future] authenticated_app: AuthenticatedApp,
+    ) {
+        let app = authenticated_app.await;
+        let (access_token, _, client_id, _) = mint_initial_tokens(&app).await;
+        let anonymous_client = reqwest::Client::new();
+
+        let response = oauth2_introspect_response(
+            &anonymous_client,
+            &app.app,
+            None,
+            &access_token,
+            &client_id,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // Neither the session nor API keys without the `tokens:introspect`
+        // scope are resource server credentials
+        let response =
+            oauth2_introspect_response(&app.client, &app.app, None, &access_token, &client_id)
+                .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let unscoped_api_key = create_api_key(&app)
+            .await
+            .jwt_token
+            .expose_secret()
+            .0
+            .clone();
+        let response = oauth2_introspect_response(
+            &anonymous_client,
+            &app.app,
+            Some(&unscoped_api_key),
+            &access_token,
+            &client_id,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let read_only_api_key: AuthenticationToken = app
+            .client
+            .post(format!(
+                "{}users/me/authentication-tokens",
+                app.app.api_address
+            ))
+            .json(&ApiKeyCreation {
+                scopes: Some(OAuth2Scope::DEFAULT.to_vec()),
+                ..Default::default()
+            })
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let response = oauth2_introspect_response(
+            &anonymous_client,
+            &app.app,
+            Some(&read_only_api_key.jwt_token.expose_secret().0),
+            &access_token,
+            &client_id,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // Tokens of other users are not disclosed
+        let (other_user_client, _) =
+            authenticate_user(&app.app, "5678", "Jane", "Doe", "jane@example.com").await;
+        let other_user_api_key = create_introspection_api_key(&other_user_client, &app.app).await;
+        let response = oauth2_introspect_response(
+            &anonymous_client,
+            &app.app,
+            Some(&other_user_api_key),
+            &access_token,
+            &client_id,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.json::<Value>().await.unwrap(),
+            json!({ "active": false })
+        );
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn revoking_refresh_token_revokes_its_access_token(
+        #[future] authenticated_app: AuthenticatedApp,
+    ) {
+        let app = authenticated_app.await;
+        let (access_token, refresh_token, client_id, user_id) = mint_initial_tokens(&app).await;
+        assert_eq!(
+            mcp_initialize_status(&app.app, &access_token)
+                .await
+                .status(),
+            StatusCode::OK
+        );
+
+        oauth2_revoke(&app.app, &refresh_token, &client_id).await;
+
+        assert_eq!(
+            count_active_refresh_tokens(&app.app, &client_id, user_id).await,
+            0
+        );
+        assert_eq!(
+            oauth2_introspect(&app, &refresh_token, &client_id).await["active"],
+            false
+        );
+        assert_eq!(
+            oauth2_introspect(&app, &access_token, &client_id).await["active"],
+            false
+        );
+        let response = mcp_initialize_status(&app.app, &access_token).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(
+            response
+                .headers()
+                .get("WWW-Authenticate")
+                .and_then(|value| value.to_str().ok())
+                .unwrap()
+                .contains(r#"error="invalid_token""#)
+        );
+
+        let refresh_response = reqwest::Client::new()
+            .post(format!("{}oauth2/token", app.app.api_address))
+            .form(&[
+                ("grant_type", "refresh_token"),
+                ("refresh_token", refresh_token.as_str()),
+                ("client_id", client_id.as_str()),
+            ])
+            .send()
+            .await
+            .expect("Failed to refresh token");
+        assert_eq!(refresh_response.status(), StatusCode::BAD_REQUEST);
+
+        // Revoking an already revoked token is a no-op
+        oauth2_revoke(&app.app, &refresh_token, &client_id).await;
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn revoking_access_token_keeps_refresh_token(
+        #[future] authenticated_app: AuthenticatedApp,
+    ) {
+        let app = authenticated_app.await;
+        let (access_token, refresh_token, client_id, user_id) = mint_initial_tokens(&app).await;
+
+        oauth2_revoke(&app.app, &access_token, &client_id).await;
+
+        assert_eq!(
+            mcp_initialize_status(&app.app, &access_token)
+                .await
+                .status(),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            oauth2_introspect(&app, &access_token, &client_id).await["active"],
+            false
+        );
+        assert_eq!(
+            count_active_refresh_tokens(&app.app, &client_id, user_id).await,
+            1
+        );
+        assert_eq!(
+            oauth2_introspect(&app, &refresh_token, &client_id).await["active"],
+            true
+        );
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn revoke_ignores_foreign_and_unknown_tokens(
+        #[future] authenticated_app: AuthenticatedApp,
+    ) {
+        let app = authenticated_app.await;
+        let (access_token, refresh_token, client_id, user_id) = mint_initial_tokens(&app).await;
+        let other_client = register_oauth2_client(&app.app).await;
+        let other_client_id = other_client["client_id"].as_str().unwrap();
+
+        oauth2_revoke(&app.app, &access_token, other_client_id).await;
+        oauth2_revoke(&app.app, &refresh_token, other_client_id).await;
+        oauth2_revoke(&app.app, "unknown-token", &client_id).await;
+
+        assert_eq!(
+            mcp_initialize_status(&app.app, &access_token)
+                .await
+                .status(),
+            StatusCode::OK
+        );
+        assert_eq!(
+            count_active_refresh_tokens(&app.app, &client_id, user_id).await,
+            1
+        );
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn refresh_token_reuse_revokes_issued_access_tokens(
+        #[future] authenticated_app: AuthenticatedApp,
+    ) {
+        let app = authenticated_app.await;
+        let (access_token, refresh_token, client_id, _) = mint_initial_tokens(&app).await;
+        let url = format!("{}oauth2/token", app.app.api_address);
+        let form = [
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token.as_str()),
+            ("client_id", client_id.as_str()),
+        ];
+        let http = reqwest::Client::new();
+
+        let refreshed: Value = http
+            .post(&url)
+            .form(&form)
+            .send()
+            .await
+            .expect("First refresh failed to send")
+            .json()
+            .await
+            .expect("Failed to parse first refresh response");
+        let new_access_token = refreshed["access_token"].as_str().unwrap();
+        let replay = http
+            .post(&url)
+            .form(&form)
+            .send()
+            .await
+            .expect("Replay refresh failed to send");
+        assert_eq!(replay.status(), StatusCode::BAD_REQUEST);
+
+        // Access tokens minted from both the rotated and the latest refresh
+        // tokens are revoked along with the family
+        for token in [access_token.as_str(), new_access_token] {
+            assert_eq!(
+                mcp_initialize_status(&app.app, token).await.status(),
+                StatusCode::UNAUTHORIZED
+            );
+        }
+    }
+
+    async fn start_device_authorization(app: &TestedApp, client_id: &str, scope: &str) -> Value {
+        let response = reqwest::Client::new()
+            .post(format!("{}oauth2/device_authorization", app.api_address))
+            .form(&[("client_id", client_id), ("scope", scope)])
+            .send()
+            .await
+            .expect("Failed to call the device authorization endpoint");
+        assert_eq!(response.status(), StatusCode::OK);
+        response
+            .json()
+            .await
+            .expect("Failed to parse device authorization response")
+    }
+
+    async fn poll_device_token(
+        app: &TestedApp,
+        client_id: &str,
+        device_code: &str,
+    ) -> (StatusCode, Value) {
+        let response = reqwest::Client::new()
+            .post(format!("{}oauth2/token", app.api_address))
+            .form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+                ("device_code", device_code),
+                ("client_id", client_id),
+            ])
+            .send()
+            .await
+            .expect("Failed to poll the token endpoint");
+        let status = response.status();
+        (
+            status,
+            response
+                .json()
+                .await
+                .expect("Failed to parse token response"),
+        )
+    }
+
+    /// Let the next poll happen right away instead of waiting for the polling
+    /// interval.
+    async fn reset_device_last_poll(app: &TestedApp) {
+        sqlx::query("UPDATE oauth2_device_authorization SET last_polled_at = NULL")
+            .execute(&*app.repository.pool)
+            .await
+            .expect("Failed to reset device authorization last poll");
+    }
+
+    /// Enter `user_code` on the verification page, then answer the consent
+    /// screen with `decision`. Returns the consent `redirect_url`.
+    async fn decide_device_authorization(
+        app: &AuthenticatedApp,
+        user_code: &str,
+        decision: &str,
+    ) -> String {
+        let client = reqwest::Client::builder()
+            .cookie_store(true)
+            .build()
+            .unwrap();
+        let api_key = create_api_key(app).await;
+        let bearer = api_key.jwt_token.expose_secret().0.clone();
+
+        let response = client
+            .post(format!("{}oauth2/device/verify", app.app.api_address))
+            .bearer_auth(&bearer)
+            .json(&json!({ "user_code": user_code }))
+            .send()
+            .await
+            .expect("Failed to call POST /oauth2/device/verify");
+        assert_eq!(response.status(), StatusCode::OK);
+        let request_id = response.json::<Value>().await.unwrap()["request_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let consent: Value = client
+            .get(format!(
+                "{}oauth2/authorize/consent?request_id={}",
+                app.app.api_address,
+                urlencoding::encode(&request_id),
+            ))
+            .bearer_auth(&bearer)
+            .send()
+            .await
+            .expect("Failed to call GET /authorize/consent")
+            .json()
+            .await
+            .expect("Failed to parse consent GET response");
+        assert_eq!(consent["redirect_uri"], Value::Null);
+        assert!(consent["user_code"].as_str().is_some());
+
+        let response: Value = client
+            .post(format!("{}oauth2/authorize/consent", app.app.api_address))
+            .bearer_auth(&bearer)
+            .json(&json!({
+                "request_id": request_id,
+                "csrf_token": consent["csrf_token"],
+                "decision": decision,
+            }))
+            .send()
+            .await
+            .expect("Failed to call POST /authorize/consent")
+            .json()
+            .await
+            .expect("Failed to parse consent POST response");
+        response["redirect_url"].as_str().unwrap().to_string()
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn device_authorization_grant_flow(#[future] authenticated_app: AuthenticatedApp) {
+        let app = authenticated_app.await;
+        let registered = register_oauth2_client(&app.app).await;
+        let client_id = registered["client_id"].as_str().unwrap();
+
+        let device_authorization =
+            start_device_authorization(&app.app, client_id, "tasks:read").await;
+        let device_code = device_authorization["device_code"].as_str().unwrap();
+        let user_code = device_authorization["user_code"].as_str().unwrap();
+        assert_eq!(user_code.len(), 9);
+        assert_eq!(&user_code[4..5], "-");
+        assert_eq!(device_authorization["interval"], 5);
+        assert_eq!(device_authorization["expires_in"], 600);
+        assert!(
+            device_authorization["verification_uri"]
+                .as_str()
+                .unwrap()
+                .ends_with("/oauth2/device")
+        );
+        assert!(
+            device_authorization["verification_uri_complete"]
+                .as_str()
+                .unwrap()
+                .ends_with(&format!("/oauth2/device?user_code={user_code}"))
+        );
+
+        let (status, body) = poll_device_token(&app.app, client_id, device_code).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "authorization_pending");
+        // Polling again before the interval elapsed
+        let (status, body) = poll_device_token(&app.app, client_id, device_code).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "slow_down");
+
+        // The user code is accepted in any case and without separator
+        let redirect_url =
+            decide_device_authorization(&app, &user_code.replace('-', "").to_lowercase(), "allow")
+                .await;
+        assert!(redirect_url.ends_with("/oauth2/device?status=approved"));
+
+        reset_device_last_poll(&app.app).await;
+        let (status, token_response) = poll_device_token(&app.app, client_id, device_code).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(token_response["token_type"], "Bearer");
+        assert_eq!(token_response["scope"], "tasks:read");
+        assert!(token_response["refresh_token"].as_str().is_some());
+        let access_token = token_response["access_token"].as_str().unwrap();
+        let mcp = mcp_client();
+        let (session_id, _) = mcp_initialize(&mcp, &app.app, access_token).await;
+        assert!(session_id.is_some());
+
+        // The device code can only be exchanged once
+        let (status, body) = poll_device_token(&app.app, client_id, device_code).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "invalid_grant");
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn device_authorization_grant_denied(#[future] authenticated_app: AuthenticatedApp) {
+        let app = authenticated_app.await;
+        let registered = register_oauth2_client(&app.app).await;
+        let client_id = registered["client_id"].as_str().unwrap();
+        let device_authorization =
+            start_device_authorization(&app.app, client_id, "tasks:read").await;
+        let device_code = device_authorization["device_code"].as_str().unwrap();
+        let user_code = device_authorization["user_code"].as_str().unwrap();
+
+        let redirect_url = decide_device_authorization(&app, user_code, "deny").await;
+        assert!(redirect_url.ends_with("/oauth2/device?status=denied"));
+
+        let (status, body) = poll_device_token(&app.app, client_id, device_code).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "access_denied");
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn device_authorization_grant_expires(#[future] authenticated_app: AuthenticatedApp) {
+        let app = authenticated_app.await;
+        let registered = register_oauth2_client(&app.app).await;
+        let client_id = registered["client_id"].as_str().unwrap();
+        let device_authorization =
+            start_device_authorization(&app.app, client_id, "tasks:read").await;
+        let device_code = device_authorization["device_code"].as_str().unwrap();
+        let user_code = device_authorization["user_code"].as_str().unwrap();
+
+        sqlx::query(
+            "UPDATE oauth2_device_authorization SET expires_at = now() - interval '1 second'",
+        )
+        .execute(&*app.app.repository.pool)
+        .await
+        .expect("Failed to expire device authorization");
+
+        let api_key = create_api_key(&app).await;
+        let response = reqwest::Client::new()
+            .post(format!("{}oauth2/device/verify", app.app.api_address))
+            .bearer_auth(api_key.jwt_token.expose_secret().0.clone())
+            .json(&json!({ "user_code": user_code }))
+            .send()
+            .await
+            .expect("Failed to call POST /oauth2/device/verify");
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let (status, body) = poll_device_token(&app.app, client_id, device_code).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "expired_token");
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn device_authorization_rejects_invalid_requests(
+        #[future] authenticated_app: AuthenticatedApp,
+    ) {
+        let app = authenticated_app.await;
+        let registered = register_oauth2_client(&app.app).await;
+        let client_id = registered["client_id"].as_str().unwrap();
+        let http = reqwest::Client::new();
+        let url = format!("{}oauth2/device_authorization", app.app.api_address);
+
+        let response = http
+            .post(&url)
+            .form(&[("client_id", "unknown-client")])
+            .send()
+            .await
+            .expect("Failed to call the device authorization endpoint");
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = http
+            .post(&url)
+            .form(&[("client_id", client_id), ("scope", "admin")])
+            .send()
+            .await
+            .expect("Failed to call the device authorization endpoint");
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let (status, body) = poll_device_token(&app.app, client_id, "unknown-device-code").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "invalid_grant");
+    }
 }
@@ -1,4 +1,3 @@
-use redis::AsyncCommands;
 use rmcp::{
     model::{ClientCapabilities, Implementation, InitializeRequestParams},
     transport::streamable_http_server::session::{SessionState, SessionStore},
@@ -6,7 +5,7 @@ use rmcp::{
 use rstest::*;
 use uuid::Uuid;
 
-use universal_inbox_api::mcp::RedisSessionStore;
+use universal_inbox_api::mcp::CacheSessionStore;
 
 use crate::helpers::{TestedApp, tested_app};
 
@@ -25,7 +24,7 @@ fn sample_state() -> SessionState {
 #[tokio::test]
 async fn store_then_load_round_trips(#[future] tested_app: TestedApp) {
     let app = tested_app.await;
-    let store = RedisSessionStore::new(app.cache.connection_manager.clone(), 60);
+    let store = CacheSessionStore::new(app.cache.clone(), 60);
     let id = fresh_session_id();
     let state = sample_state();
 
@@ -47,7 +46,7 @@ async fn store_then_load_round_trips(#[future] tested_app: TestedApp) {
 #[tokio::test]
 async fn load_returns_none_for_unknown_session(#[future] tested_app: TestedApp) {
     let app = tested_app.await;
-    let store = RedisSessionStore::new(app.cache.connection_manager.clone(), 60);
+    let store = CacheSessionStore::new(app.cache.clone(), 60);
 
     let loaded = store.load(&fresh_session_id()).await.expect("load failed");
     assert!(loaded.is_none());
@@ -57,7 +56,7 @@ async fn load_returns_none_for_unknown_session(#[future] tested_app: TestedApp)
 #[tokio::test]
 async fn delete_removes_persisted_session(#[future] tested_app: TestedApp) {
     let app = tested_app.await;
-    let store = RedisSessionStore::new(app.cache.connection_manager.clone(), 60);
+    let store = CacheSessionStore::new(app.cache.clone(), 60);
     let id = fresh_session_id();
 
     store
@@ -75,7 +74,7 @@ async fn delete_removes_persisted_session(#[future] tested_app: TestedApp) {
 async fn store_applies_configured_ttl(#[future] tested_app: TestedApp) {
     let app = tested_app.await;
     let configured_ttl: u64 = 600;
-    let store = RedisSessionStore::new(app.cache.connection_manager.clone(), configured_ttl);
+    let store = CacheSessionStore::new(app.cache.clone(), configured_ttl);
     let id = fresh_session_id();
     let key = format!("universal-inbox:mcp:session:{id}");
 
@@ -84,12 +83,17 @@ async fn store_applies_configured_ttl(#[future] tested_app: TestedApp) {
         .await
         .expect("store failed");
 
-    let mut conn = app.cache.connection_manager.clone();
-    let ttl: i64 = conn.ttl(&key).await.expect("TTL query failed");
+    let ttl = app
+        .cache
+        .time_to_live(&key)
+        .await
+        .expect("TTL query failed")
+        .expect("expected a TTL on the session key")
+        .as_secs();
     assert!(
-        ttl > 0 && ttl <= configured_ttl as i64,
+        ttl > 0 && ttl <= configured_ttl,
         "expected TTL within (0, {configured_ttl}], got {ttl}"
     );
 
-    let _: () = conn.del(&key).await.unwrap_or(());
+    app.cache.del(&key).await.unwrap_or(());
 }
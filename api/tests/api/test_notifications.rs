@@ -586,7 +586,6 @@ mod get_notification {
 }
 
 mod patch_notifications_bulk {
-    use apalis::prelude::Storage;
     use universal_inbox::notification::service::PatchNotificationsRequest;
 
     use super::*;
@@ -600,7 +599,7 @@ mod patch_notifications_bulk {
         github_notification: Box<GithubNotification>,
         github_oauth_credential: OAuthCredentialFixture,
     ) {
-        let mut app = authenticated_app.await;
+        let app = authenticated_app.await;
         let github_integration_connection = create_and_mock_integration_connection(
             &app.app,
             app.user.id,
@@ -687,7 +686,7 @@ mod patch_notifications_bulk {
 
         let job_count = app
             .app
-            .redis_storage
+            .job_storage
             .len()
             .await
             .expect("Failed to get job count");
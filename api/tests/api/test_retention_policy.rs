@@ -0,0 +1,412 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use chrono::TimeDelta;
+use graphql_client::Response;
+use pretty_assertions::assert_eq;
+use rstest::*;
+use serde_json::json;
+
+use universal_inbox::{
+    integration_connection::{
+        config::IntegrationConnectionConfig, integrations::github::GithubConfig,
+    },
+    notification::{
+        Notification, NotificationSourceKind, NotificationStatus, NotificationSyncSourceKind,
+        service::NotificationPatch,
+    },
+    third_party::integrations::github::{GithubNotification, GithubUrl},
+};
+
+use universal_inbox_api::{
+    configuration::Settings,
+    integrations::github::graphql::{issue_query, pull_request_query},
+    jobs::{
+        UniversalInboxJob,
+        dead_letter::{DeadLetterJob, DeadLetterJobFilter},
+        retention::apply_retention_policy,
+        sync::SyncNotificationsJob,
+    },
+    repository::{notification::NotificationRepository, third_party::ThirdPartyItemRepository},
+    universal_inbox::{
+        UniversalInboxError,
+        job::service::JobService,
+        third_party::retention::{RetentionPolicy, RetentionStats},
+    },
+};
+
+use crate::helpers::{
+    auth::{AuthenticatedApp, authenticated_app},
+    integration_connection::{
+        OAuthCredentialFixture, create_and_mock_integration_connection, github_oauth_credential,
+    },
+    notification::{
+        github::{
+            create_notification_from_github_notification, github_issue_456_response,
+            github_pull_request_123_response, mock_github_notification_items_query,
+            mock_github_notifications_service, sync_github_notifications,
+        },
+        sync_notifications, update_notification,
+    },
+    settings,
+};
+
+fn retention_policy() -> RetentionPolicy {
+    RetentionPolicy {
+        deleted_notifications_retention_in_days: 30,
+        unsubscribed_notifications_retention_in_days: 90,
+        orphan_third_party_items_retention_in_days: 30,
+        dead_letter_jobs_retention_in_days: 30,
+        batch_size: 1,
+    }
+}
+
+async fn update_notification_status(
+    app: &AuthenticatedApp,
+    notification: &Notification,
+    status: NotificationStatus,
+    status_age_in_days: i32,
+) {
+    update_notification(
+        app,
+        notification.id,
+        &NotificationPatch {
+            status: Some(status),
+            ..Default::default()
+        },
+        app.user.id,
+    )
+    .await;
+
+    let mut transaction = app.app.repository.begin().await.unwrap();
+    sqlx::query(
+        "UPDATE notification SET status_updated_at = status_updated_at - make_interval(days => $1) WHERE id = $2",
+    )
+    .bind(status_age_in_days)
+    .bind(notification.id.0)
+    .execute(&mut *transaction)
+    .await
+    .unwrap();
+    transaction.commit().await.unwrap();
+}
+
+async fn get_notification(
+    app: &AuthenticatedApp,
+    notification: &Notification,
+) -> Option<Notification> {
+    let mut transaction = app.app.repository.begin().await.unwrap();
+    app.app
+        .repository
+        .get_one_notification(&mut transaction, notification.id)
+        .await
+        .unwrap()
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_apply_retention_policy_should_purge_items_of_old_deleted_notifications(
+    settings: Settings,
+    #[future] authenticated_app: AuthenticatedApp,
+    sync_github_notifications: Vec<GithubNotification>,
+    github_oauth_credential: OAuthCredentialFixture,
+) {
+    let app = authenticated_app.await;
+    let github_integration_connection = create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Github(GithubConfig::enabled()),
+        &settings,
+        github_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+    let mut github_notifications = sync_github_notifications.clone();
+    let mut recently_deleted_github_notification = github_notifications[0].clone();
+    recently_deleted_github_notification.id = "recently-deleted".to_string();
+    github_notifications.push(recently_deleted_github_notification);
+    let mut notifications = vec![];
+    for github_notification in &github_notifications {
+        notifications.push(
+            create_notification_from_github_notification(
+                &app.app,
+                github_notification,
+                app.user.id,
+                github_integration_connection.id,
+            )
+            .await,
+        );
+    }
+    let [
+        unread_notification,
+        deleted_notification,
+        recently_deleted_notification,
+    ] = &notifications[..]
+    else {
+        panic!("Expected 3 notifications, got {}", notifications.len());
+    };
+    update_notification_status(&app, deleted_notification, NotificationStatus::Deleted, 31).await;
+    update_notification_status(
+        &app,
+        recently_deleted_notification,
+        NotificationStatus::Deleted,
+        1,
+    )
+    .await;
+
+    let stats = apply_retention_policy(
+        app.app.third_party_item_service.clone(),
+        Arc::new(JobService::new(app.app.repository.clone())),
+        retention_policy(),
+        true,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        stats,
+        RetentionStats {
+            deleted_notification_items: 1,
+            unsubscribed_notification_items: 0,
+            orphan_items: 0,
+            dead_letter_jobs: 0,
+        }
+    );
+    // Nothing is purged on a dry run
+    assert!(get_notification(&app, deleted_notification).await.is_some());
+
+    let stats = apply_retention_policy(
+        app.app.third_party_item_service.clone(),
+        Arc::new(JobService::new(app.app.repository.clone())),
+        retention_policy(),
+        false,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(stats.deleted_notification_items, 1);
+    assert!(get_notification(&app, deleted_notification).await.is_none());
+    assert!(get_notification(&app, unread_notification).await.is_some());
+    assert!(
+        get_notification(&app, recently_deleted_notification)
+            .await
+            .is_some()
+    );
+
+    let mut transaction = app.app.repository.begin().await.unwrap();
+    let tombstones = app
+        .app
+        .repository
+        .find_third_party_item_tombstones(
+            &mut transaction,
+            std::slice::from_ref(&deleted_notification.source_item.source_id),
+            app.user.id,
+        )
+        .await
+        .unwrap();
+    assert_eq!(tombstones.len(), 1);
+    assert_eq!(
+        tombstones[0].notification_status,
+        NotificationStatus::Deleted
+    );
+    assert_eq!(
+        tombstones[0].integration_connection_id,
+        github_integration_connection.id
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_sync_should_skip_purged_items_without_new_upstream_activity(
+    settings: Settings,
+    #[future] authenticated_app: AuthenticatedApp,
+    // Vec[GithubNotification { source_id: "123", ... }, GithubNotification { source_id: "456", ... } ]
+    sync_github_notifications: Vec<GithubNotification>,
+    github_pull_request_123_response: Response<pull_request_query::ResponseData>,
+    github_issue_456_response: Response<issue_query::ResponseData>,
+    github_oauth_credential: OAuthCredentialFixture,
+) {
+    let app = authenticated_app.await;
+    let github_integration_connection = create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Github(GithubConfig::enabled()),
+        &settings,
+        github_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+    // Stored without the details of their pull request and issue
+    for github_notification in &sync_github_notifications {
+        let notification = create_notification_from_github_notification(
+            &app.app,
+            github_notification,
+            app.user.id,
+            github_integration_connection.id,
+        )
+        .await;
+        update_notification_status(&app, &notification, NotificationStatus::Deleted, 31).await;
+    }
+    let stats = apply_retention_policy(
+        app.app.third_party_item_service.clone(),
+        Arc::new(JobService::new(app.app.repository.clone())),
+        retention_policy(),
+        false,
+    )
+    .await
+    .unwrap();
+    assert_eq!(stats.deleted_notification_items, 2);
+
+    // Only the second notification has new upstream activity, the first one
+    // differs by the details fetched during the synchronization
+    let mut upstream_github_notifications = sync_github_notifications.clone();
+    upstream_github_notifications[1].updated_at += TimeDelta::hours(1);
+    mock_github_notifications_service(
+        &app.app.github_mock_server,
+        "1",
+        &upstream_github_notifications,
+    )
+    .await;
+    mock_github_notifications_service(&app.app.github_mock_server, "2", &vec![]).await;
+    mock_github_notification_items_query(
+        &app.app.github_mock_server,
+        &[
+            (
+                GithubUrl::PullRequest {
+                    owner: "octokit".to_string(),
+                    repository: "octokit.rb".to_string(),
+                    number: 123,
+                },
+                json!(github_pull_request_123_response),
+            ),
+            (
+                GithubUrl::Issue {
+                    owner: "octokit".to_string(),
+                    repository: "octokit.rb".to_string(),
+                    number: 456,
+                },
+                json!(github_issue_456_response),
+            ),
+        ],
+    )
+    .await;
+
+    let notifications: Vec<Notification> = sync_notifications(
+        &app.client,
+        &app.app.api_address,
+        Some(NotificationSourceKind::Github),
+        false,
+    )
+    .await;
+
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(
+        notifications[0].source_item.source_id,
+        sync_github_notifications[1].id
+    );
+    assert_eq!(notifications[0].status, NotificationStatus::Read);
+
+    let mut transaction = app.app.repository.begin().await.unwrap();
+    let tombstones = app
+        .app
+        .repository
+        .find_third_party_item_tombstones(
+            &mut transaction,
+            &[
+                sync_github_notifications[0].id.clone(),
+                sync_github_notifications[1].id.clone(),
+            ],
+            app.user.id,
+        )
+        .await
+        .unwrap();
+    assert_eq!(tombstones.len(), 1);
+    assert_eq!(tombstones[0].source_id, sync_github_notifications[0].id);
+}
+
+async fn create_dead_letter_job(
+    app: &AuthenticatedApp,
+    job_service: &JobService,
+    failure_age_in_days: i32,
+) -> DeadLetterJob {
+    let mut transaction = job_service.begin().await.unwrap();
+    let dead_letter_job = job_service
+        .dead_letter_job(
+            &mut transaction,
+            &UniversalInboxJob::SyncNotifications(SyncNotificationsJob {
+                source: Some(NotificationSyncSourceKind::Github),
+                user_id: Some(app.user.id),
+            }),
+            format!("job-{failure_age_in_days}"),
+            &UniversalInboxError::Unexpected(anyhow!("Github API is down")),
+            1,
+        )
+        .await
+        .unwrap();
+    sqlx::query(
+        "UPDATE dead_letter_job SET failed_at = failed_at - make_interval(days => $1) WHERE id = $2",
+    )
+    .bind(failure_age_in_days)
+    .bind(dead_letter_job.id.0)
+    .execute(&mut *transaction)
+    .await
+    .unwrap();
+    transaction.commit().await.unwrap();
+
+    *dead_letter_job
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_apply_retention_policy_should_purge_old_dead_letter_jobs(
+    #[future] authenticated_app: AuthenticatedApp,
+) {
+    let app = authenticated_app.await;
+    let job_service = Arc::new(JobService::new(app.app.repository.clone()));
+    create_dead_letter_job(&app, &job_service, 31).await;
+    create_dead_letter_job(&app, &job_service, 32).await;
+    let recent_dead_letter_job = create_dead_letter_job(&app, &job_service, 1).await;
+
+    let stats = apply_retention_policy(
+        app.app.third_party_item_service.clone(),
+        job_service.clone(),
+        retention_policy(),
+        true,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(stats.dead_letter_jobs, 2);
+    let mut transaction = job_service.begin().await.unwrap();
+    assert_eq!(
+        job_service
+            .count_dead_letter_jobs(&mut transaction, &DeadLetterJobFilter::default())
+            .await
+            .unwrap(),
+        3
+    );
+
+    // Purged in batches of 1 job
+    let stats = apply_retention_policy(
+        app.app.third_party_item_service.clone(),
+        job_service.clone(),
+        retention_policy(),
+        false,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(stats.dead_letter_jobs, 2);
+    let mut transaction = job_service.begin().await.unwrap();
+    assert_eq!(
+        job_service
+            .fetch_dead_letter_jobs(&mut transaction, &DeadLetterJobFilter::default())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|dead_letter_job| dead_letter_job.id)
+            .collect::<Vec<_>>(),
+        vec![recent_dead_letter_job.id]
+    );
+}
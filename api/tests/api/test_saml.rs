@@ -0,0 +1,219 @@
+//! Integration tests for SAML 2.0 authentication.
+//!
+//! Responses are built and signed by the identity provider stand-in of
+//! `helpers::saml` from the authentication request the API redirects to.
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use email_address::EmailAddress;
+use reqwest::StatusCode;
+use rstest::*;
+use uuid::Uuid;
+
+use universal_inbox::user::{UserAuthKind, UserAuthMethodDisplayInfo};
+
+use crate::helpers::{
+    TestedApp,
+    saml::{
+        SAMLTestIdentity, authorize_link_saml_response, authorize_saml_response,
+        build_signed_saml_response, post_saml_linking_response, post_saml_response,
+        receive_authn_request, saml_client, saml_login, start_saml_linking,
+    },
+    tested_app_with_saml_auth,
+    user::{create_user_and_login, get_current_user_response, list_auth_methods},
+};
+
+const PASSWORD: &str = "Very-harD-pasSword-5";
+
+fn identity(email: &str) -> SAMLTestIdentity {
+    SAMLTestIdentity {
+        name_id: Uuid::new_v4().to_string(),
+        email: email.to_string(),
+        first_name: "Jane".to_string(),
+        last_name: "Doe".to_string(),
+    }
+}
+
+fn unique_email(prefix: &str) -> String {
+    format!("{prefix}-{}@example.com", Uuid::new_v4())
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_saml_metadata(#[future] tested_app_with_saml_auth: TestedApp) {
+    let app = tested_app_with_saml_auth.await;
+
+    let response = reqwest::get(format!("{}auth/saml/metadata", app.api_address))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()["content-type"],
+        "application/samlmetadata+xml"
+    );
+    let metadata = response.text().await.unwrap();
+    assert!(metadata.contains(&format!(
+        r#"entityID="{}auth/saml/metadata""#,
+        app.front_base_url.join("/api/").unwrap()
+    )));
+    assert!(metadata.contains(&format!(
+        r#"Location="{}auth/saml/acs""#,
+        app.front_base_url.join("/api/").unwrap()
+    )));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_saml_login_provisions_user(#[future] tested_app_with_saml_auth: TestedApp) {
+    let app = tested_app_with_saml_auth.await;
+    let identity = identity(&unique_email("saml"));
+
+    let (_, user) = saml_login(&app, &identity).await;
+
+    assert_eq!(user.first_name, Some("Jane".to_string()));
+    assert_eq!(user.last_name, Some("Doe".to_string()));
+    assert_eq!(
+        user.email,
+        Some(identity.email.parse::<EmailAddress>().unwrap())
+    );
+
+    // The next login uses the provisioned user
+    let (client, logged_user) = saml_login(&app, &identity).await;
+    assert_eq!(logged_user.id, user.id);
+    let auth_methods = list_auth_methods(&client, &app).await;
+    assert_eq!(auth_methods.len(), 1);
+    assert_eq!(auth_methods[0].kind, UserAuthKind::SAML);
+    assert_eq!(
+        auth_methods[0].display_info,
+        UserAuthMethodDisplayInfo::SAML
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_saml_response_cannot_be_replayed(#[future] tested_app_with_saml_auth: TestedApp) {
+    let app = tested_app_with_saml_auth.await;
+    let client = saml_client();
+    let authn_request = receive_authn_request(authorize_saml_response(&client, &app).await).await;
+    let saml_response =
+        build_signed_saml_response(&authn_request, &identity(&unique_email("saml-replay")));
+
+    let response = post_saml_response(&client, &app, &saml_response).await;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    let response = post_saml_response(&saml_client(), &app, &saml_response).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_saml_tampered_response_is_rejected(#[future] tested_app_with_saml_auth: TestedApp) {
+    let app = tested_app_with_saml_auth.await;
+    let client = saml_client();
+    let authn_request = receive_authn_request(authorize_saml_response(&client, &app).await).await;
+    let email = unique_email("saml-tampered");
+    let saml_response = build_signed_saml_response(&authn_request, &identity(&email));
+    let tampered_response = STANDARD.encode(
+        String::from_utf8(STANDARD.decode(saml_response).unwrap())
+            .unwrap()
+            .replace(&email, "attacker@example.com"),
+    );
+
+    let response = post_saml_response(&client, &app, &tampered_response).await;
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let response = get_current_user_response(&client, &app).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_link_saml_auth_method(#[future] tested_app_with_saml_auth: TestedApp) {
+    let app = tested_app_with_saml_auth.await;
+    let email = unique_email("saml-link");
+    let (client, user) = create_user_and_login(&app, email.parse().unwrap(), PASSWORD).await;
+    let identity = identity(&email);
+
+    let (authn_request, linking_cookie) = start_saml_linking(&client, &app).await;
+    // The identity provider posts its response cross-site, without the session cookie
+    let response = post_saml_linking_response(
+        &app,
+        &build_signed_saml_response(&authn_request, &identity),
+        &linking_cookie,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    let kinds: Vec<UserAuthKind> = list_auth_methods(&client, &app)
+        .await
+        .into_iter()
+        .map(|method| method.kind)
+        .collect();
+    assert!(kinds.contains(&UserAuthKind::Local));
+    assert!(kinds.contains(&UserAuthKind::SAML));
+
+    let (_, logged_user) = saml_login(&app, &identity).await;
+    assert_eq!(logged_user.id, user.id);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_link_saml_auth_method_with_another_email(
+    #[future] tested_app_with_saml_auth: TestedApp,
+) {
+    let app = tested_app_with_saml_auth.await;
+    let (client, _) =
+        create_user_and_login(&app, unique_email("saml-link").parse().unwrap(), PASSWORD).await;
+
+    let (authn_request, linking_cookie) = start_saml_linking(&client, &app).await;
+    let response = post_saml_linking_response(
+        &app,
+        &build_signed_saml_response(&authn_request, &identity(&unique_email("saml-other"))),
+        &linking_cookie,
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let kinds: Vec<UserAuthKind> = list_auth_methods(&client, &app)
+        .await
+        .into_iter()
+        .map(|method| method.kind)
+        .collect();
+    assert_eq!(kinds, vec![UserAuthKind::Local]);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_link_saml_auth_method_from_another_browser_is_rejected(
+    #[future] tested_app_with_saml_auth: TestedApp,
+) {
+    let app = tested_app_with_saml_auth.await;
+    let (attacker_client, attacker) = create_user_and_login(
+        &app,
+        unique_email("saml-attacker").parse().unwrap(),
+        PASSWORD,
+    )
+    .await;
+    let victim_identity = identity(&unique_email("saml-victim"));
+
+    // The attacker sends the identity provider URL of their linking request to the
+    // victim, whose browser does not hold the linking cookie
+    let authn_request =
+        receive_authn_request(authorize_link_saml_response(&attacker_client, &app).await).await;
+    let response = post_saml_response(
+        &saml_client(),
+        &app,
+        &build_signed_saml_response(&authn_request, &victim_identity),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let kinds: Vec<UserAuthKind> = list_auth_methods(&attacker_client, &app)
+        .await
+        .into_iter()
+        .map(|method| method.kind)
+        .collect();
+    assert_eq!(kinds, vec![UserAuthKind::Local]);
+    let (_, victim) = saml_login(&app, &victim_identity).await;
+    assert_ne!(victim.id, attacker.id);
+}
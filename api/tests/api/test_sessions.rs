@@ -0,0 +1,291 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use email_address::EmailAddress;
+use reqwest::{Client, StatusCode};
+use rstest::*;
+use uuid::Uuid;
+
+use universal_inbox::user::Password;
+use universal_inbox_api::{
+    configuration::Settings, universal_inbox::auth_token::service::AuthenticationTokenService,
+    utils::jwt::Claims,
+};
+
+use crate::helpers::{
+    TestedApp, settings, tested_app_with_local_auth,
+    user::{
+        create_user_and_login, get_current_user_response, get_password_reset_token, list_sessions,
+        login_user_response, revoke_other_sessions, revoke_session_response,
+    },
+};
+
+const PASSWORD: &str = "Very-harD-pasSword-5";
+const USER_AGENT: &str = "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0";
+
+async fn login_from_another_device(app: &TestedApp, email: EmailAddress) -> Client {
+    let client = Client::builder()
+        .cookie_store(true)
+        .user_agent(USER_AGENT)
+        .build()
+        .unwrap();
+    let response = login_user_response(&client, app, email, PASSWORD).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    client
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_list_sessions(#[future] tested_app_with_local_auth: TestedApp) {
+    let app = tested_app_with_local_auth.await;
+    let email: EmailAddress = "john@doe.name".parse().unwrap();
+    let (client, _) = create_user_and_login(&app, email.clone(), PASSWORD).await;
+    let other_client = login_from_another_device(&app, email).await;
+
+    let sessions = list_sessions(&client, &app).await;
+
+    assert_eq!(sessions.len(), 2);
+    let current_sessions: Vec<_> = sessions.iter().filter(|s| s.is_current).collect();
+    assert_eq!(current_sessions.len(), 1);
+    let other_session = sessions.iter().find(|s| !s.is_current).unwrap();
+    assert_eq!(other_session.user_agent.as_deref(), Some(USER_AGENT));
+    assert_eq!(other_session.ip_address.as_deref(), Some("127.0.0.1"));
+    assert!(other_session.last_seen_at.is_some());
+
+    let other_sessions = list_sessions(&other_client, &app).await;
+    assert_eq!(
+        other_sessions.iter().find(|s| s.is_current).unwrap().id,
+        other_session.id
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_revoke_session(#[future] tested_app_with_local_auth: TestedApp) {
+    let app = tested_app_with_local_auth.await;
+    let email: EmailAddress = "john@doe.name".parse().unwrap();
+    let (client, _) = create_user_and_login(&app, email.clone(), PASSWORD).await;
+    let other_client = login_from_another_device(&app, email).await;
+    let other_session_id = list_sessions(&other_client, &app)
+        .await
+        .into_iter()
+        .find(|s| s.is_current)
+        .unwrap()
+        .id;
+
+    let response = revoke_session_response(&client, &app, &other_session_id).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = get_current_user_response(&other_client, &app).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let response = get_current_user_response(&client, &app).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(list_sessions(&client, &app).await.len(), 1);
+
+    // Already revoked
+    let response = revoke_session_response(&client, &app, &other_session_id).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_revoked_session_is_forgotten_by_other_instances(
+    settings: Settings,
+    #[future] tested_app_with_local_auth: TestedApp,
+) {
+    let app = tested_app_with_local_auth.await;
+    let email: EmailAddress = "john@doe.name".parse().unwrap();
+    let (client, user) = create_user_and_login(&app, email.clone(), PASSWORD).await;
+    login_from_another_device(&app, email).await;
+    let (other_session_id, other_session_jwt_token): (Uuid, String) = sqlx::query_as(
+        "SELECT id, jwt_token FROM authentication_token WHERE user_id = $1 AND user_agent = $2",
+    )
+    .bind(user.id.0)
+    .bind(USER_AGENT)
+    .fetch_one(&*app.repository.pool)
+    .await
+    .unwrap();
+    let other_instance = AuthenticationTokenService::new(
+        app.repository.clone(),
+        settings.application.http_session.clone(),
+        Some(app.cache.clone()),
+    );
+    tokio::spawn(other_instance.listen_to_session_revocations().unwrap());
+    let claims = Claims {
+        exp: (Utc::now().timestamp() + 3600) as usize,
+        iat: Utc::now().timestamp() as usize,
+        sub: user.id.to_string(),
+        jti: Uuid::new_v4().to_string(),
+        aud: None,
+        scope: None,
+        client_id: None,
+        notification_source_kinds: None,
+    };
+    let mut transaction = app.repository.begin().await.unwrap();
+    other_instance
+        .authenticate_session_token(&mut transaction, &other_session_jwt_token, &claims, None)
+        .await
+        .unwrap();
+    transaction.commit().await.unwrap();
+    assert!(other_instance.is_session_token_recently_checked(&other_session_jwt_token, None));
+    // Let the listener subscribe to the revocations
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let response = revoke_session_response(&client, &app, &other_session_id.into()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mut is_forgotten = false;
+    for _ in 0..50 {
+        if !other_instance.is_session_token_recently_checked(&other_session_jwt_token, None) {
+            is_forgotten = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert!(is_forgotten);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_cannot_revoke_session_of_another_user(
+    #[future] tested_app_with_local_auth: TestedApp,
+) {
+    let app = tested_app_with_local_auth.await;
+    let (client, _) = create_user_and_login(&app, "john@doe.name".parse().unwrap(), PASSWORD).await;
+    let (other_client, _) =
+        create_user_and_login(&app, "jane@doe.name".parse().unwrap(), PASSWORD).await;
+    let other_session_id = list_sessions(&other_client, &app).await[0].id.clone();
+
+    let response = revoke_session_response(&client, &app, &other_session_id).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = revoke_session_response(&client, &app, &Uuid::new_v4().into()).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = get_current_user_response(&other_client, &app).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_sign_out_everywhere_else(#[future] tested_app_with_local_auth: TestedApp) {
+    let app = tested_app_with_local_auth.await;
+    let email: EmailAddress = "john@doe.name".parse().unwrap();
+    let (client, _) = create_user_and_login(&app, email.clone(), PASSWORD).await;
+    let other_clients = [
+        login_from_another_device(&app, email.clone()).await,
+        login_from_another_device(&app, email).await,
+    ];
+
+    let revoked_sessions = revoke_other_sessions(&client, &app).await;
+
+    assert_eq!(revoked_sessions.revoked_sessions_count, 2);
+    for other_client in &other_clients {
+        let response = get_current_user_response(other_client, &app).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+    let sessions = list_sessions(&client, &app).await;
+    assert_eq!(sessions.len(), 1);
+    assert!(sessions[0].is_current);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_sign_out_everywhere_else_revokes_unregistered_sessions(
+    #[future] tested_app_with_local_auth: TestedApp,
+) {
+    let app = tested_app_with_local_auth.await;
+    let email: EmailAddress = "john@doe.name".parse().unwrap();
+    let (client, user) = create_user_and_login(&app, email.clone(), PASSWORD).await;
+    let other_client = login_from_another_device(&app, email).await;
+    // Forget the other session as if it was opened before sessions were stored
+    let mut transaction = app.repository.begin().await.unwrap();
+    sqlx::query("DELETE FROM authentication_token WHERE user_id = $1 AND user_agent = $2")
+        .bind(user.id.0)
+        .bind(USER_AGENT)
+        .execute(&mut *transaction)
+        .await
+        .unwrap();
+    transaction.commit().await.unwrap();
+
+    let response = get_current_user_response(&other_client, &app).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(list_sessions(&client, &app).await.len(), 2);
+
+    let revoked_sessions = revoke_other_sessions(&client, &app).await;
+
+    assert_eq!(revoked_sessions.revoked_sessions_count, 1);
+    let response = get_current_user_response(&other_client, &app).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_reset_password_revokes_all_sessions(#[future] tested_app_with_local_auth: TestedApp) {
+    let app = tested_app_with_local_auth.await;
+    let email: EmailAddress = "john@doe.name".parse().unwrap();
+    let (client, user) = create_user_and_login(&app, email.clone(), PASSWORD).await;
+
+    let anonymous_client = Client::builder().cookie_store(true).build().unwrap();
+    let response = anonymous_client
+        .post(format!("{}users/password-reset", app.api_address))
+        .json(&email)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let password_reset_token = get_password_reset_token(&app, user.id).await.unwrap();
+    let response = anonymous_client
+        .post(format!(
+            "{}users/{}/password-reset/{password_reset_token}",
+            app.api_address, user.id
+        ))
+        .json(&"New-very-harD-pasSword-5".parse::<Password>().unwrap())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = get_current_user_response(&client, &app).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_unregistered_session_replayed_as_bearer_token_can_be_revoked(
+    #[future] tested_app_with_local_auth: TestedApp,
+) {
+    let app = tested_app_with_local_auth.await;
+    let email: EmailAddress = "john@doe.name".parse().unwrap();
+    let (client, user) = create_user_and_login(&app, email.clone(), PASSWORD).await;
+    login_from_another_device(&app, email).await;
+    // Forget the other session as if it was opened before sessions were stored
+    let mut transaction = app.repository.begin().await.unwrap();
+    let (other_session_jwt_token,): (String,) = sqlx::query_as(
+        "DELETE FROM authentication_token WHERE user_id = $1 AND user_agent = $2 RETURNING jwt_token",
+    )
+    .bind(user.id.0)
+    .bind(USER_AGENT)
+    .fetch_one(&mut *transaction)
+    .await
+    .unwrap();
+    transaction.commit().await.unwrap();
+    let bearer_client = Client::new();
+    let get_current_user_with_bearer_token = || {
+        bearer_client
+            .get(format!("{}users/me", app.api_address))
+            .bearer_auth(&other_session_jwt_token)
+            .send()
+    };
+
+    // The session is registered when first seen, as with its cookie
+    let response = get_current_user_with_bearer_token().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(list_sessions(&client, &app).await.len(), 2);
+
+    let revoked_sessions = revoke_other_sessions(&client, &app).await;
+
+    assert_eq!(revoked_sessions.revoked_sessions_count, 1);
+    let response = get_current_user_with_bearer_token().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
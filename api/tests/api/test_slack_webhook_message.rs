@@ -1,6 +1,5 @@
 #![allow(clippy::too_many_arguments)]
 use anyhow::Context;
-use apalis::prelude::*;
 use rstest::*;
 use slack_morphism::prelude::*;
 
@@ -222,7 +221,7 @@ mod webhook {
 
     async fn assert_message_ignored(app: &mut TestedApp) {
         assert!(
-            app.redis_storage
+            app.job_storage
                 .is_empty()
                 .await
                 .expect("Failed to get jobs count")
@@ -230,11 +229,11 @@ mod webhook {
     }
 
     async fn assert_message_processed(app: &mut TestedApp) {
-        // The job is pushed to Redis during the webhook handler, but there can
+        // The job is pushed to the job storage during the webhook handler, but there can
         // be a small delay before it becomes visible to `is_empty()` in CI.
         for _ in 0..10 {
             if !app
-                .redis_storage
+                .job_storage
                 .is_empty()
                 .await
                 .expect("Failed to get jobs count")
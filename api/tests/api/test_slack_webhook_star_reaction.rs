@@ -1,7 +1,6 @@
 #![allow(clippy::too_many_arguments)]
 use std::collections::HashMap;
 
-use apalis::prelude::Storage;
 use pretty_assertions::assert_eq;
 use rstest::*;
 use slack_blocks_render::SlackReferences;
@@ -62,7 +61,7 @@ async fn test_receive_reaction_event_for_unknown_user(
     #[future] authenticated_app: AuthenticatedApp,
     slack_push_reaction_added_event: Box<SlackPushEvent>,
 ) {
-    let mut app = authenticated_app.await;
+    let app = authenticated_app.await;
 
     let response = post_signed_slack_event(
         &app.client,
@@ -75,7 +74,7 @@ async fn test_receive_reaction_event_for_unknown_user(
 
     assert!(
         app.app
-            .redis_storage
+            .job_storage
             .is_empty()
             .await
             .expect("Failed to get jobs count")
@@ -102,7 +101,7 @@ async fn test_receive_reaction_event_for_disabled_config(
     slack_push_reaction_added_event: Box<SlackPushEvent>,
     slack_oauth_credential: OAuthCredentialFixture,
 ) {
-    let mut app = authenticated_app.await;
+    let app = authenticated_app.await;
     create_and_mock_integration_connection(
         &app.app,
         app.user.id,
@@ -125,7 +124,7 @@ async fn test_receive_reaction_event_for_disabled_config(
 
     assert!(
         app.app
-            .redis_storage
+            .job_storage
             .is_empty()
             .await
             .expect("Failed to get jobs count")
@@ -152,7 +151,7 @@ async fn test_receive_reaction_event_for_different_reaction(
     slack_push_reaction_added_event: Box<SlackPushEvent>,
     slack_oauth_credential: OAuthCredentialFixture,
 ) {
-    let mut app = authenticated_app.await;
+    let app = authenticated_app.await;
     create_and_mock_integration_connection(
         &app.app,
         app.user.id,
@@ -183,7 +182,7 @@ async fn test_receive_reaction_event_for_different_reaction(
 
     assert!(
         app.app
-            .redis_storage
+            .job_storage
             .is_empty()
             .await
             .expect("Failed to get jobs count")
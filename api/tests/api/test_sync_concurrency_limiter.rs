@@ -0,0 +1,107 @@
+use std::{sync::Arc, time::Duration};
+
+use rstest::*;
+use sqlx::PgPool;
+
+use universal_inbox::integration_connection::provider::IntegrationProviderKind;
+
+use universal_inbox_api::{
+    configuration::{Settings, StorageBackend},
+    jobs::sync::{SyncConcurrencyLimiter, SyncSlot},
+    utils::cache::Cache,
+};
+
+use crate::common::{db_connection, settings};
+
+#[rstest]
+#[case::redis(StorageBackend::Redis)]
+#[case::postgres(StorageBackend::Postgres)]
+#[tokio::test]
+async fn test_sync_slots_are_shared_by_workers(
+    mut settings: Settings,
+    #[future] db_connection: Arc<PgPool>,
+    #[case] storage_backend: StorageBackend,
+) {
+    settings.storage.backend = storage_backend;
+    for integration_settings in settings.integrations.values_mut() {
+        integration_settings.max_concurrent_syncs =
+            (integration_settings.kind == IntegrationProviderKind::Github).then_some(1);
+    }
+    let cache = Cache::new(&settings, db_connection.await)
+        .await
+        .expect("Failed to create cache");
+    // Limiters of 2 worker processes sharing the same cache
+    let worker_limiter = SyncConcurrencyLimiter::new(cache.clone(), &settings.integrations);
+    let other_worker_limiter = SyncConcurrencyLimiter::new(cache, &settings.integrations);
+
+    let slot = worker_limiter
+        .try_acquire(IntegrationProviderKind::Github)
+        .await
+        .unwrap();
+    assert!(matches!(slot, SyncSlot::Acquired { .. }));
+    assert!(matches!(
+        other_worker_limiter
+            .try_acquire(IntegrationProviderKind::Github)
+            .await
+            .unwrap(),
+        SyncSlot::Unavailable
+    ));
+    assert!(matches!(
+        other_worker_limiter
+            .try_acquire(IntegrationProviderKind::Linear)
+            .await
+            .unwrap(),
+        SyncSlot::Unlimited
+    ));
+
+    worker_limiter.release(slot).await;
+    let slot = other_worker_limiter
+        .try_acquire(IntegrationProviderKind::Github)
+        .await
+        .unwrap();
+    assert!(matches!(slot, SyncSlot::Acquired { .. }));
+    other_worker_limiter.release(slot).await;
+}
+
+#[rstest]
+#[case::redis(StorageBackend::Redis)]
+#[case::postgres(StorageBackend::Postgres)]
+#[tokio::test]
+async fn test_expired_sync_slot_taken_by_another_worker_is_not_released(
+    mut settings: Settings,
+    #[future] db_connection: Arc<PgPool>,
+    #[case] storage_backend: StorageBackend,
+) {
+    settings.storage.backend = storage_backend;
+    for integration_settings in settings.integrations.values_mut() {
+        integration_settings.max_concurrent_syncs =
+            (integration_settings.kind == IntegrationProviderKind::Github).then_some(1);
+    }
+    let cache = Cache::new(&settings, db_connection.await)
+        .await
+        .expect("Failed to create cache");
+    let worker_limiter = SyncConcurrencyLimiter::new(cache.clone(), &settings.integrations);
+    let other_worker_limiter = SyncConcurrencyLimiter::new(cache.clone(), &settings.integrations);
+
+    let slot = worker_limiter
+        .try_acquire(IntegrationProviderKind::Github)
+        .await
+        .unwrap();
+    let SyncSlot::Acquired { key, .. } = &slot else {
+        panic!("Expected an acquired sync slot, got {slot:?}");
+    };
+    // The slot expired and has been taken by another worker meanwhile
+    cache
+        .set_ex(key, "other-worker-token", Duration::from_secs(60))
+        .await
+        .unwrap();
+
+    worker_limiter.release(slot).await;
+    assert!(matches!(
+        other_worker_limiter
+            .try_acquire(IntegrationProviderKind::Github)
+            .await
+            .unwrap(),
+        SyncSlot::Unavailable
+    ));
+}
@@ -3,14 +3,20 @@ use chrono::{TimeDelta, TimeZone, Utc};
 use graphql_client::{Error, Response};
 use http::StatusCode;
 use rstest::*;
+use serde_json::json;
 use tokio::time::{Duration, sleep};
+use uuid::Uuid;
 
 use universal_inbox::{
+    HasHtmlUrl,
     integration_connection::{
         IntegrationConnectionStatus,
         config::IntegrationConnectionConfig,
-        integrations::{github::GithubConfig, todoist::TodoistConfig},
-        provider::IntegrationProviderKind,
+        integrations::{
+            github::{GithubConfig, GithubContext},
+            todoist::TodoistConfig,
+        },
+        provider::{IntegrationConnectionContext, IntegrationProvider, IntegrationProviderKind},
     },
     notification::{
         Notification, NotificationSourceKind, NotificationStatus, NotificationWithTask,
@@ -18,7 +24,10 @@ use universal_inbox::{
     },
     third_party::{
         integrations::{
-            github::{GithubNotification, GithubNotificationItem, GithubNotificationSubject},
+            github::{
+                GithubCheckConclusionState, GithubIssueState, GithubNotification,
+                GithubNotificationItem, GithubNotificationSubject, GithubUrl,
+            },
             todoist::TodoistItem,
         },
         item::ThirdPartyItemData,
@@ -28,7 +37,11 @@ use universal_inbox::{
 use universal_inbox_api::{
     configuration::Settings,
     integrations::{
-        github::graphql::{discussion_query, pull_request_query},
+        github::{
+            graphql::{discussion_query, issue_query, pull_request_query},
+            release::RawGithubRelease,
+            workflow_run::{RawGithubWorkflowJobs, RawGithubWorkflowRuns},
+        },
         todoist::TodoistSyncResponse,
     },
     repository::integration_connection::TOO_MANY_SYNC_FAILURES_ERROR_MESSAGE,
@@ -42,14 +55,18 @@ use crate::helpers::{
     integration_connection::{
         create_and_mock_integration_connection,
         create_and_mock_integration_connection_with_backoff, create_integration_connection,
-        get_integration_connection_per_provider, github_oauth_credential, todoist_oauth_credential,
+        get_integration_connection, get_integration_connection_per_provider,
+        github_oauth_credential, todoist_oauth_credential, update_integration_connection_context,
     },
     notification::{
         github::{
             assert_sync_notifications, create_notification_from_github_notification,
-            github_discussion_123_response, github_notification, github_pull_request_123_response,
-            mock_github_discussion_query, mock_github_notifications_service,
-            mock_github_pull_request_query, sync_github_notifications,
+            github_discussion_123_response, github_issue_456_response, github_notification,
+            github_pull_request_123_response, github_release_response,
+            github_workflow_run_jobs_response, github_workflow_runs_response,
+            mock_github_notification_items_query, mock_github_notifications_service,
+            mock_github_release_service, mock_github_workflow_run_jobs_service,
+            mock_github_workflow_runs_service, sync_github_notifications,
         },
         list_notifications, sync_notifications, sync_notifications_response, update_notification,
     },
@@ -61,7 +78,10 @@ use crate::helpers::{
     tested_app_with_local_auth,
     user::create_user_and_login,
 };
-use wiremock::{Mock, ResponseTemplate};
+use wiremock::{
+    Mock, ResponseTemplate,
+    matchers::{header, header_regex, method, path, query_param},
+};
 
 #[rstest]
 #[tokio::test]
@@ -71,6 +91,7 @@ async fn test_sync_notifications_should_add_new_notification_and_update_existing
     // Vec[GithubNotification { source_id: "123", ... }, GithubNotification { source_id: "456", ... } ]
     sync_github_notifications: Vec<GithubNotification>,
     github_pull_request_123_response: Response<pull_request_query::ResponseData>,
+    github_issue_456_response: Response<issue_query::ResponseData>,
     todoist_item: Box<TodoistItem>,
     sync_todoist_projects_response: TodoistSyncResponse,
     github_oauth_credential: OAuthCredentialFixture,
@@ -147,12 +168,26 @@ async fn test_sync_notifications_should_add_new_notification_and_update_existing
     let _github_notifications_mock2 =
         mock_github_notifications_service(&app.app.github_mock_server, "2", &empty_result).await;
 
-    let _github_pull_request_123_query_mock = mock_github_pull_request_query(
+    mock_github_notification_items_query(
         &app.app.github_mock_server,
-        "octokit".to_string(),
-        "octokit.rb".to_string(),
-        123,
-        &github_pull_request_123_response,
+        &[
+            (
+                GithubUrl::PullRequest {
+                    owner: "octokit".to_string(),
+                    repository: "octokit.rb".to_string(),
+                    number: 123,
+                },
+                json!(github_pull_request_123_response),
+            ),
+            (
+                GithubUrl::Issue {
+                    owner: "octokit".to_string(),
+                    repository: "octokit.rb".to_string(),
+                    number: 456,
+                },
+                json!(github_issue_456_response),
+            ),
+        ],
     )
     .await;
 
@@ -176,6 +211,9 @@ async fn test_sync_notifications_should_add_new_notification_and_update_existing
                 .try_into()
                 .unwrap(),
         )),
+        Some(GithubNotificationItem::GithubIssue(
+            github_issue_456_response.data.unwrap().try_into().unwrap(),
+        )),
     );
 
     let updated_notification: Box<NotificationWithTask> = get_resource(
@@ -251,6 +289,7 @@ async fn test_sync_notifications_should_mark_deleted_notification_without_subscr
     // Vec[GithubNotification { source_id: "123", ... }, GithubNotification { source_id: "456", ... } ]
     sync_github_notifications: Vec<GithubNotification>,
     github_pull_request_123_response: Response<pull_request_query::ResponseData>,
+    github_issue_456_response: Response<issue_query::ResponseData>,
     github_oauth_credential: OAuthCredentialFixture,
 ) {
     let app = tested_app_with_local_auth.await;
@@ -322,14 +361,28 @@ async fn test_sync_notifications_should_mark_deleted_notification_without_subscr
     let _github_notifications_mock2 =
         mock_github_notifications_service(&app.github_mock_server, "2", &empty_result).await;
 
-    // Sync of Github notification 123 will trigger a query of the associated pull request
-    // sync_github_notifications[1] won't trigger any query
-    let _github_pull_request_123_query_mock = mock_github_pull_request_query(
+    // Sync of Github notifications 123 and 456 will trigger a single query of the
+    // associated pull request and issue
+    mock_github_notification_items_query(
         &app.github_mock_server,
-        "octokit".to_string(),
-        "octokit.rb".to_string(),
-        123,
-        &github_pull_request_123_response,
+        &[
+            (
+                GithubUrl::PullRequest {
+                    owner: "octokit".to_string(),
+                    repository: "octokit.rb".to_string(),
+                    number: 123,
+                },
+                json!(github_pull_request_123_response),
+            ),
+            (
+                GithubUrl::Issue {
+                    owner: "octokit".to_string(),
+                    repository: "octokit.rb".to_string(),
+                    number: 456,
+                },
+                json!(github_issue_456_response),
+            ),
+        ],
     )
     .await;
 
@@ -353,6 +406,9 @@ async fn test_sync_notifications_should_mark_deleted_notification_without_subscr
                 .try_into()
                 .unwrap(),
         )),
+        Some(GithubNotificationItem::GithubIssue(
+            github_issue_456_response.data.unwrap().try_into().unwrap(),
+        )),
     );
 
     let deleted_notification: Box<NotificationWithTask> = get_resource(
@@ -389,6 +445,7 @@ async fn test_sync_all_notifications_asynchronously(
     // Vec[GithubNotification { source_id: "123", ... }, GithubNotification { source_id: "456", ... } ]
     sync_github_notifications: Vec<GithubNotification>,
     github_pull_request_123_response: Response<pull_request_query::ResponseData>,
+    github_issue_456_response: Response<issue_query::ResponseData>,
     github_oauth_credential: OAuthCredentialFixture,
     #[case] trigger_sync_when_listing_notifications: bool,
 ) {
@@ -430,12 +487,26 @@ async fn test_sync_all_notifications_asynchronously(
     let empty_result = Vec::<GithubNotification>::new();
     let _github_notifications_mock2 =
         mock_github_notifications_service(&app.app.github_mock_server, "2", &empty_result).await;
-    let _github_pull_request_123_query_mock = mock_github_pull_request_query(
+    mock_github_notification_items_query(
         &app.app.github_mock_server,
-        "octokit".to_string(),
-        "octokit.rb".to_string(),
-        123,
-        &github_pull_request_123_response,
+        &[
+            (
+                GithubUrl::PullRequest {
+                    owner: "octokit".to_string(),
+                    repository: "octokit.rb".to_string(),
+                    number: 123,
+                },
+                json!(github_pull_request_123_response),
+            ),
+            (
+                GithubUrl::Issue {
+                    owner: "octokit".to_string(),
+                    repository: "octokit.rb".to_string(),
+                    number: 456,
+                },
+                json!(github_issue_456_response),
+            ),
+        ],
     )
     .await;
 
@@ -742,12 +813,16 @@ async fn test_sync_discussion_notification_with_details(
     )
     .await;
 
-    let _github_discussion_query_mock = mock_github_discussion_query(
+    mock_github_notification_items_query(
         &app.app.github_mock_server,
-        "octokit".to_string(),
-        "octokit.rb".to_string(),
-        123,
-        &github_discussion_123_response,
+        &[(
+            GithubUrl::Discussion {
+                owner: "octokit".to_string(),
+                repository: "octokit.rb".to_string(),
+                number: 123,
+            },
+            json!(github_discussion_123_response),
+        )],
     )
     .await;
 
@@ -801,6 +876,348 @@ async fn test_sync_discussion_notification_with_details(
     }
 }
 
+#[rstest]
+#[tokio::test]
+async fn test_sync_issue_notification_with_details(
+    settings: Settings,
+    #[future] authenticated_app: AuthenticatedApp,
+    mut github_notification: Box<GithubNotification>,
+    github_issue_456_response: Response<issue_query::ResponseData>,
+    github_oauth_credential: OAuthCredentialFixture,
+) {
+    github_notification.subject = GithubNotificationSubject {
+        title: "Load custom emoji from Slack".to_string(),
+        url: Some(
+            "https://api.github.com/repos/octokit/octokit.rb/issues/456"
+                .parse()
+                .unwrap(),
+        ),
+        latest_comment_url: None,
+        r#type: "Issue".to_string(),
+    };
+
+    let app = authenticated_app.await;
+    create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Github(GithubConfig::enabled()),
+        &settings,
+        github_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+
+    let github_notifications_response = vec![*github_notification];
+    let _github_notifications_mock = mock_github_notifications_service(
+        &app.app.github_mock_server,
+        "1",
+        &github_notifications_response,
+    )
+    .await;
+
+    mock_github_notification_items_query(
+        &app.app.github_mock_server,
+        &[(
+            GithubUrl::Issue {
+                owner: "octokit".to_string(),
+                repository: "octokit.rb".to_string(),
+                number: 456,
+            },
+            json!(github_issue_456_response),
+        )],
+    )
+    .await;
+
+    let notifications: Vec<Notification> = sync_notifications(
+        &app.client,
+        &app.app.api_address,
+        Some(NotificationSourceKind::Github),
+        false,
+    )
+    .await;
+
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(
+        notifications[0].get_html_url(),
+        "https://github.com/octokit/octokit.rb/issues/456"
+            .parse()
+            .unwrap()
+    );
+    match &notifications[0].source_item.data {
+        ThirdPartyItemData::GithubNotification(github_notification) => {
+            match &github_notification.item {
+                Some(GithubNotificationItem::GithubIssue(issue)) => {
+                    assert_eq!(issue.number, 456);
+                    assert_eq!(issue.state, GithubIssueState::Open);
+                    assert_eq!(issue.labels.len(), 1);
+                    assert_eq!(issue.labels[0].name, "enhancement");
+                    assert_eq!(issue.assignees.len(), 1);
+                    assert_eq!(
+                        issue
+                            .milestone
+                            .as_ref()
+                            .map(|milestone| milestone.title.as_str()),
+                        Some("v1.0")
+                    );
+                    assert_eq!(issue.comments_count, 7);
+                    assert_eq!(issue.comments.len(), 1);
+                    // Only pull requests are kept from the cross references
+                    assert_eq!(issue.linked_pull_requests.len(), 1);
+                    assert_eq!(issue.linked_pull_requests[0].number, 789);
+                    assert!(issue.linked_pull_requests[0].will_close_issue);
+                }
+                _ => unreachable!("Expected a GithubIssue notification"),
+            }
+        }
+        _ => unreachable!("Expected a GithubIssue notification"),
+    }
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_sync_check_suite_notification_with_failed_workflow_run(
+    settings: Settings,
+    #[future] authenticated_app: AuthenticatedApp,
+    mut github_notification: Box<GithubNotification>,
+    github_workflow_runs_response: RawGithubWorkflowRuns,
+    github_workflow_run_jobs_response: RawGithubWorkflowJobs,
+    github_oauth_credential: OAuthCredentialFixture,
+) {
+    github_notification.subject = GithubNotificationSubject {
+        title: "CI workflow run failed for main branch".to_string(),
+        url: None,
+        latest_comment_url: None,
+        r#type: "CheckSuite".to_string(),
+    };
+
+    let app = authenticated_app.await;
+    create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Github(GithubConfig::enabled()),
+        &settings,
+        github_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+
+    let github_notifications_response = vec![*github_notification];
+    mock_github_notifications_service(
+        &app.app.github_mock_server,
+        "1",
+        &github_notifications_response,
+    )
+    .await;
+    mock_github_workflow_runs_service(
+        &app.app.github_mock_server,
+        "universal-inbox",
+        "universal-inbox",
+        "main",
+        &github_workflow_runs_response,
+    )
+    .await;
+    mock_github_workflow_run_jobs_service(
+        &app.app.github_mock_server,
+        "universal-inbox",
+        "universal-inbox",
+        30433642,
+        &github_workflow_run_jobs_response,
+    )
+    .await;
+
+    let notifications: Vec<Notification> = sync_notifications(
+        &app.client,
+        &app.app.api_address,
+        Some(NotificationSourceKind::Github),
+        false,
+    )
+    .await;
+
+    assert_eq!(notifications.len(), 1);
+    // The notification links to the failing run instead of the repository's actions page
+    assert_eq!(
+        notifications[0].get_html_url(),
+        "https://github.com/universal-inbox/universal-inbox/actions/runs/30433642"
+            .parse()
+            .unwrap()
+    );
+    match &notifications[0].source_item.data {
+        ThirdPartyItemData::GithubNotification(github_notification) => {
+            match &github_notification.item {
+                Some(GithubNotificationItem::GithubWorkflowRun(workflow_run)) => {
+                    assert_eq!(workflow_run.id, 30433642);
+                    assert_eq!(workflow_run.name, "CI");
+                    assert_eq!(
+                        workflow_run.conclusion,
+                        Some(GithubCheckConclusionState::Failure)
+                    );
+                    assert_eq!(
+                        workflow_run
+                            .head_commit
+                            .as_ref()
+                            .map(|commit| commit.url.to_string()),
+                        Some("https://github.com/universal-inbox/universal-inbox/commit/acb5820ced9479c074f688cc328bf03f341a511d".to_string())
+                    );
+                    assert_eq!(workflow_run.failed_jobs.len(), 1);
+                    assert_eq!(workflow_run.failed_jobs[0].name, "Test");
+                    assert_eq!(
+                        workflow_run.failed_jobs[0].failed_steps,
+                        vec!["Run tests".to_string()]
+                    );
+                }
+                _ => unreachable!("Expected a GithubWorkflowRun notification"),
+            }
+        }
+        _ => unreachable!("Expected a GithubNotification"),
+    }
+}
+
+#[rstest]
+#[case::actions_not_readable(403)]
+#[case::repository_not_found(404)]
+#[tokio::test]
+async fn test_sync_check_suite_notification_with_unavailable_workflow_runs(
+    settings: Settings,
+    #[future] authenticated_app: AuthenticatedApp,
+    mut github_notification: Box<GithubNotification>,
+    github_oauth_credential: OAuthCredentialFixture,
+    #[case] workflow_runs_status: u16,
+) {
+    github_notification.subject = GithubNotificationSubject {
+        title: "CI workflow run failed for main branch".to_string(),
+        url: None,
+        latest_comment_url: None,
+        r#type: "CheckSuite".to_string(),
+    };
+
+    let app = authenticated_app.await;
+    create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Github(GithubConfig::enabled()),
+        &settings,
+        github_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+
+    let github_notifications_response = vec![*github_notification];
+    mock_github_notifications_service(
+        &app.app.github_mock_server,
+        "1",
+        &github_notifications_response,
+    )
+    .await;
+    Mock::given(method("GET"))
+        .and(path("/repos/universal-inbox/universal-inbox/actions/runs"))
+        .respond_with(ResponseTemplate::new(workflow_runs_status))
+        .mount(&app.app.github_mock_server)
+        .await;
+
+    let notifications: Vec<Notification> = sync_notifications(
+        &app.client,
+        &app.app.api_address,
+        Some(NotificationSourceKind::Github),
+        false,
+    )
+    .await;
+
+    // The notification is still synced, without the details of the workflow run
+    assert_eq!(notifications.len(), 1);
+    match &notifications[0].source_item.data {
+        ThirdPartyItemData::GithubNotification(github_notification) => {
+            assert_eq!(github_notification.item, None);
+        }
+        _ => unreachable!("Expected a GithubNotification"),
+    }
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_sync_release_notification_with_details(
+    settings: Settings,
+    #[future] authenticated_app: AuthenticatedApp,
+    mut github_notification: Box<GithubNotification>,
+    github_release_response: RawGithubRelease,
+    github_oauth_credential: OAuthCredentialFixture,
+) {
+    github_notification.subject = GithubNotificationSubject {
+        title: "v1.0.0".to_string(),
+        url: Some(
+            "https://api.github.com/repos/universal-inbox/universal-inbox/releases/1"
+                .parse()
+                .unwrap(),
+        ),
+        latest_comment_url: None,
+        r#type: "Release".to_string(),
+    };
+
+    let app = authenticated_app.await;
+    create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Github(GithubConfig::enabled()),
+        &settings,
+        github_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+
+    let github_notifications_response = vec![*github_notification];
+    mock_github_notifications_service(
+        &app.app.github_mock_server,
+        "1",
+        &github_notifications_response,
+    )
+    .await;
+    mock_github_release_service(
+        &app.app.github_mock_server,
+        "universal-inbox",
+        "universal-inbox",
+        1,
+        &github_release_response,
+    )
+    .await;
+
+    let notifications: Vec<Notification> = sync_notifications(
+        &app.client,
+        &app.app.api_address,
+        Some(NotificationSourceKind::Github),
+        false,
+    )
+    .await;
+
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(
+        notifications[0].get_html_url(),
+        "https://github.com/universal-inbox/universal-inbox/releases/v1.0.0"
+            .parse()
+            .unwrap()
+    );
+    match &notifications[0].source_item.data {
+        ThirdPartyItemData::GithubNotification(github_notification) => {
+            match &github_notification.item {
+                Some(GithubNotificationItem::GithubRelease(release)) => {
+                    assert_eq!(release.tag_name, "v1.0.0");
+                    assert!(!release.is_prerelease);
+                    assert_eq!(
+                        release.repository.name_with_owner,
+                        "universal-inbox/universal-inbox"
+                    );
+                    assert_eq!(release.assets.len(), 1);
+                    assert_eq!(release.assets[0].download_count, 42);
+                }
+                _ => unreachable!("Expected a GithubRelease notification"),
+            }
+        }
+        _ => unreachable!("Expected a GithubNotification"),
+    }
+}
+
 #[rstest]
 #[tokio::test]
 async fn test_sync_discussion_notification_with_error(
@@ -840,7 +1257,7 @@ async fn test_sync_discussion_notification_with_error(
     )
     .await;
 
-    let error_response = Response {
+    let error_response: Response<discussion_query::ResponseData> = Response {
         data: None,
         errors: Some(vec![Error {
             message: "Something went wrong".to_string(),
@@ -850,12 +1267,16 @@ async fn test_sync_discussion_notification_with_error(
         }]),
         extensions: None,
     };
-    let _github_discussion_query_mock = mock_github_discussion_query(
+    mock_github_notification_items_query(
         &app.app.github_mock_server,
-        "octokit".to_string(),
-        "octokit.rb".to_string(),
-        123,
-        &error_response,
+        &[(
+            GithubUrl::Discussion {
+                owner: "octokit".to_string(),
+                repository: "octokit.rb".to_string(),
+                number: 123,
+            },
+            json!(error_response),
+        )],
     )
     .await;
 
@@ -919,3 +1340,287 @@ async fn test_sync_discussion_notification_with_error(
         IntegrationConnectionStatus::Validated
     );
 }
+
+#[rstest]
+#[tokio::test]
+async fn test_sync_notifications_should_store_notifications_validators(
+    settings: Settings,
+    #[future] authenticated_app: AuthenticatedApp,
+    sync_github_notifications: Vec<GithubNotification>,
+    github_pull_request_123_response: Response<pull_request_query::ResponseData>,
+    github_issue_456_response: Response<issue_query::ResponseData>,
+    github_oauth_credential: OAuthCredentialFixture,
+) {
+    let app = authenticated_app.await;
+    let github_integration_connection = create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Github(GithubConfig::enabled()),
+        &settings,
+        github_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+
+    Mock::given(method("GET"))
+        .and(path("/notifications"))
+        .and(query_param("page", "1"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "application/json")
+                .insert_header("last-modified", "Thu, 01 Oct 2026 10:00:00 GMT")
+                .insert_header("etag", "\"abcdef\"")
+                .set_body_json(&sync_github_notifications),
+        )
+        .mount(&app.app.github_mock_server)
+        .await;
+    let empty_result = Vec::<GithubNotification>::new();
+    mock_github_notifications_service(&app.app.github_mock_server, "2", &empty_result).await;
+    mock_github_notification_items_query(
+        &app.app.github_mock_server,
+        &[
+            (
+                GithubUrl::PullRequest {
+                    owner: "octokit".to_string(),
+                    repository: "octokit.rb".to_string(),
+                    number: 123,
+                },
+                json!(github_pull_request_123_response),
+            ),
+            (
+                GithubUrl::Issue {
+                    owner: "octokit".to_string(),
+                    repository: "octokit.rb".to_string(),
+                    number: 456,
+                },
+                json!(github_issue_456_response),
+            ),
+        ],
+    )
+    .await;
+
+    let notifications: Vec<Notification> = sync_notifications(
+        &app.client,
+        &app.app.api_address,
+        Some(NotificationSourceKind::Github),
+        false,
+    )
+    .await;
+    assert_eq!(notifications.len(), sync_github_notifications.len());
+
+    let integration_connection = get_integration_connection(&app, github_integration_connection.id)
+        .await
+        .unwrap();
+    assert_eq!(
+        integration_connection.provider,
+        IntegrationProvider::Github {
+            context: Some(GithubContext {
+                notifications_last_modified: Some("Thu, 01 Oct 2026 10:00:00 GMT".to_string()),
+                notifications_etag: Some("\"abcdef\"".to_string()),
+            }),
+            config: GithubConfig::enabled(),
+        }
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_sync_notifications_should_not_store_notifications_validators_when_failing(
+    settings: Settings,
+    #[future] authenticated_app: AuthenticatedApp,
+    sync_github_notifications: Vec<GithubNotification>,
+    github_pull_request_123_response: Response<pull_request_query::ResponseData>,
+    github_issue_456_response: Response<issue_query::ResponseData>,
+    github_oauth_credential: OAuthCredentialFixture,
+) {
+    let app = authenticated_app.await;
+    let github_integration_connection = create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Github(GithubConfig::enabled()),
+        &settings,
+        github_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+    // A stored item which cannot be read back makes the sync fail once the
+    // notifications have been fetched
+    let mut transaction = app.app.repository.begin().await.unwrap();
+    sqlx::query(
+        r#"
+          INSERT INTO third_party_item
+            (id, source_id, data, created_at, updated_at, user_id, integration_connection_id)
+          VALUES ($1, $2, '{"type": "GithubNotification", "content": {}}', now(), now(), $3, $4)
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(&sync_github_notifications[0].id)
+    .bind(app.user.id.0)
+    .bind(github_integration_connection.id.0)
+    .execute(&mut *transaction)
+    .await
+    .unwrap();
+    transaction.commit().await.unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/notifications"))
+        .and(query_param("page", "1"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "application/json")
+                .insert_header("last-modified", "Thu, 01 Oct 2026 10:00:00 GMT")
+                .insert_header("etag", "\"abcdef\"")
+                .set_body_json(&sync_github_notifications),
+        )
+        .mount(&app.app.github_mock_server)
+        .await;
+    let empty_result = Vec::<GithubNotification>::new();
+    mock_github_notifications_service(&app.app.github_mock_server, "2", &empty_result).await;
+    mock_github_notification_items_query(
+        &app.app.github_mock_server,
+        &[
+            (
+                GithubUrl::PullRequest {
+                    owner: "octokit".to_string(),
+                    repository: "octokit.rb".to_string(),
+                    number: 123,
+                },
+                json!(github_pull_request_123_response),
+            ),
+            (
+                GithubUrl::Issue {
+                    owner: "octokit".to_string(),
+                    repository: "octokit.rb".to_string(),
+                    number: 456,
+                },
+                json!(github_issue_456_response),
+            ),
+        ],
+    )
+    .await;
+
+    let response = sync_notifications_response(
+        &app.client,
+        &app.app.api_address,
+        Some(NotificationSourceKind::Github),
+        false,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+    // The failure is recorded, but not the validators of the failed sync,
+    // otherwise the next sync would skip the notifications
+    let integration_connection = get_integration_connection(&app, github_integration_connection.id)
+        .await
+        .unwrap();
+    assert!(
+        integration_connection
+            .last_notifications_sync_failed_at
+            .is_some()
+    );
+    assert_eq!(
+        integration_connection.provider,
+        IntegrationProvider::Github {
+            context: None,
+            config: GithubConfig::enabled(),
+        }
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_sync_notifications_should_keep_notifications_when_not_modified(
+    settings: Settings,
+    #[future] authenticated_app: AuthenticatedApp,
+    sync_github_notifications: Vec<GithubNotification>,
+    github_oauth_credential: OAuthCredentialFixture,
+) {
+    let app = authenticated_app.await;
+    let github_integration_connection = create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Github(GithubConfig::enabled()),
+        &settings,
+        github_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+    update_integration_connection_context(
+        &app,
+        github_integration_connection.id,
+        IntegrationConnectionContext::Github(GithubContext {
+            notifications_last_modified: Some("Thu, 01 Oct 2026 10:00:00 GMT".to_string()),
+            notifications_etag: Some("\"abcdef\"".to_string()),
+        }),
+    )
+    .await;
+    let existing_notification = create_notification_from_github_notification(
+        &app.app,
+        &sync_github_notifications[0],
+        app.user.id,
+        github_integration_connection.id,
+    )
+    .await;
+
+    Mock::given(method("GET"))
+        .and(path("/notifications"))
+        .and(query_param("page", "1"))
+        // `header` would split the date on its comma
+        .and(header_regex(
+            "if-modified-since",
+            "^Thu, 01 Oct 2026 10:00:00 GMT$",
+        ))
+        .and(header("if-none-match", "\"abcdef\""))
+        .respond_with(ResponseTemplate::new(304))
+        .expect(1)
+        .mount(&app.app.github_mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/graphql"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(&app.app.github_mock_server)
+        .await;
+
+    let notifications: Vec<Notification> = sync_notifications(
+        &app.client,
+        &app.app.api_address,
+        Some(NotificationSourceKind::Github),
+        false,
+    )
+    .await;
+    assert!(notifications.is_empty());
+
+    // Unchanged notifications must not be considered as stale
+    let notifications = list_notifications(
+        &app.client,
+        &app.app.api_address,
+        vec![NotificationStatus::Unread],
+        false,
+        None,
+        None,
+        false,
+    )
+    .await;
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0].id, existing_notification.id);
+
+    let integration_connection = get_integration_connection_per_provider(
+        &app,
+        app.user.id,
+        IntegrationProviderKind::Github,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    assert!(
+        integration_connection
+            .last_notifications_sync_completed_at
+            .is_some()
+    );
+    assert_eq!(integration_connection.notifications_sync_failures, 0);
+}
@@ -0,0 +1,427 @@
+use graphql_client::Response;
+use rstest::*;
+
+use universal_inbox::{
+    HasHtmlUrl,
+    integration_connection::{
+        config::IntegrationConnectionConfig,
+        integrations::{
+            github::{GithubConfig, GithubSyncTaskConfig},
+            todoist::TodoistConfig,
+        },
+        provider::IntegrationProviderKind,
+    },
+    task::{
+        DueDate, PresetDueDate, ProjectSummary, TaskCreationResult, TaskSourceKind, TaskStatus,
+    },
+    third_party::{
+        integrations::{
+            github::{GithubTaskItem, GithubTaskItemKind},
+            todoist::{TodoistItem, TodoistItemPriority},
+        },
+        item::ThirdPartyItemKind,
+    },
+};
+
+use universal_inbox_api::{
+    configuration::Settings, integrations::github::graphql::task_items_query,
+    integrations::todoist::TodoistSyncResponse,
+};
+
+use crate::helpers::{
+    auth::{AuthenticatedApp, authenticated_app},
+    integration_connection::{
+        OAuthCredentialFixture, create_and_mock_integration_connection,
+        get_integration_connection_per_provider, github_oauth_credential, todoist_oauth_credential,
+    },
+    notification::github::{mock_github_task_items_query, sync_github_task_items_response},
+    settings,
+    task::{
+        github::create_github_task,
+        sync_tasks,
+        todoist::{
+            mock_todoist_complete_item_service, mock_todoist_get_item_service,
+            mock_todoist_item_add_service, mock_todoist_sync_resources_service,
+            sync_todoist_projects_response, todoist_item,
+        },
+    },
+};
+
+#[rstest]
+#[tokio::test]
+async fn test_sync_tasks_should_create_new_task(
+    settings: Settings,
+    #[future] authenticated_app: AuthenticatedApp,
+    mut sync_github_task_items_response: Response<task_items_query::ResponseData>,
+    todoist_item: Box<TodoistItem>,
+    sync_todoist_projects_response: TodoistSyncResponse,
+    github_oauth_credential: OAuthCredentialFixture,
+    todoist_oauth_credential: OAuthCredentialFixture,
+) {
+    let app = authenticated_app.await;
+    create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Todoist(TodoistConfig::enabled()),
+        &settings,
+        todoist_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+    create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Github(GithubConfig {
+            sync_notifications_enabled: true,
+            sync_task_config: GithubSyncTaskConfig {
+                enabled: true,
+                target_project: Some(ProjectSummary {
+                    name: "Project2".to_string(),
+                    source_id: "2222".into(),
+                }),
+                default_due_at: Some(PresetDueDate::Today),
+                ..Default::default()
+            },
+        }),
+        &settings,
+        github_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+
+    // Only keep the assigned issue to create a single task
+    if let Some(data) = sync_github_task_items_response.data.as_mut() {
+        data.review_requests.nodes = Some(vec![]);
+    }
+    let github_task_items: Vec<GithubTaskItem> = sync_github_task_items_response
+        .data
+        .clone()
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert_eq!(github_task_items.len(), 1);
+    let github_task_item = &github_task_items[0];
+    assert_eq!(github_task_item.kind, GithubTaskItemKind::AssignedIssue);
+    mock_github_task_items_query(
+        &app.app.github_mock_server,
+        None,
+        None,
+        &sync_github_task_items_response,
+    )
+    .await;
+
+    mock_todoist_sync_resources_service(
+        &app.app.todoist_mock_server,
+        "projects",
+        &sync_todoist_projects_response,
+        None,
+    )
+    .await;
+    let expected_task_title = format!(
+        "[octo-org/octo-repo#42 {}]({})",
+        github_task_item.title,
+        github_task_item.get_html_url()
+    );
+    let due_at: DueDate = PresetDueDate::Today.into();
+    mock_todoist_item_add_service(
+        &app.app.todoist_mock_server,
+        &todoist_item.id,
+        expected_task_title.clone(),
+        Some(github_task_item.body.clone()),
+        Some("2222".to_string()), // ie. "Project2"
+        Some((&due_at).into()),
+        TodoistItemPriority::P1,
+    )
+    .await;
+    mock_todoist_get_item_service(&app.app.todoist_mock_server, todoist_item.clone()).await;
+
+    let task_creation_results: Vec<TaskCreationResult> = sync_tasks(
+        &app.client,
+        &app.app.api_address,
+        Some(TaskSourceKind::Github),
+        false,
+    )
+    .await;
+
+    assert_eq!(task_creation_results.len(), 1);
+    assert!(task_creation_results[0].notifications.is_empty());
+    let task = &task_creation_results[0].task;
+    assert_eq!(task.title, expected_task_title);
+    assert_eq!(task.body, github_task_item.body);
+    assert_eq!(task.status, TaskStatus::Active);
+    assert_eq!(task.kind, TaskSourceKind::Github);
+    assert_eq!(task.source_item.kind(), ThirdPartyItemKind::GithubTaskItem);
+    assert_eq!(task.source_item.source_id, github_task_item.id);
+    assert_eq!(task.tags, vec!["bug".to_string()]);
+    assert_eq!(task.project, "Project2".to_string());
+    assert_eq!(task.due_at, Some(due_at));
+
+    let sink_item = task.sink_item.clone().unwrap();
+    assert_eq!(sink_item.kind(), ThirdPartyItemKind::TodoistItem);
+    assert_eq!(sink_item.source_id, todoist_item.id);
+
+    let integration_connection = get_integration_connection_per_provider(
+        &app,
+        app.user.id,
+        IntegrationProviderKind::Github,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    assert!(
+        integration_connection
+            .last_tasks_sync_completed_at
+            .is_some()
+    );
+    assert!(integration_connection.last_tasks_sync_failed_at.is_none());
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_sync_tasks_should_complete_task_when_review_is_no_longer_requested(
+    settings: Settings,
+    #[future] authenticated_app: AuthenticatedApp,
+    sync_github_task_items_response: Response<task_items_query::ResponseData>,
+    sync_todoist_projects_response: TodoistSyncResponse,
+    github_oauth_credential: OAuthCredentialFixture,
+    todoist_oauth_credential: OAuthCredentialFixture,
+) {
+    let app = authenticated_app.await;
+    let todoist_integration_connection = create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Todoist(TodoistConfig::enabled()),
+        &settings,
+        todoist_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+    let project = ProjectSummary {
+        name: "Project2".to_string(),
+        source_id: "2222".into(),
+    };
+    let github_integration_connection = create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Github(GithubConfig {
+            sync_notifications_enabled: true,
+            sync_task_config: GithubSyncTaskConfig {
+                enabled: true,
+                target_project: Some(project.clone()),
+                ..Default::default()
+            },
+        }),
+        &settings,
+        github_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+
+    let github_task_items: Vec<GithubTaskItem> = sync_github_task_items_response
+        .data
+        .clone()
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let review_request = github_task_items
+        .iter()
+        .find(|item| item.kind == GithubTaskItemKind::ReviewRequest)
+        .unwrap();
+    let existing_task = create_github_task(
+        &app.app,
+        review_request,
+        project,
+        app.user.id,
+        github_integration_connection.id,
+        todoist_integration_connection.id,
+        "todoist_source_id".to_string(),
+    )
+    .await;
+
+    // Once the review is submitted, the pull request is no longer returned by the search query
+    let empty_sync_github_task_items_response = Response {
+        data: Some(task_items_query::ResponseData {
+            assigned_issues: task_items_query::TaskItemsQueryAssignedIssues {
+                page_info: task_items_query::TaskItemsQueryAssignedIssuesPageInfo {
+                    has_next_page: false,
+                    end_cursor: None,
+                },
+                nodes: None,
+            },
+            review_requests: task_items_query::TaskItemsQueryReviewRequests {
+                page_info: task_items_query::TaskItemsQueryReviewRequestsPageInfo {
+                    has_next_page: false,
+                    end_cursor: None,
+                },
+                nodes: None,
+            },
+        }),
+        errors: None,
+        extensions: None,
+    };
+    mock_github_task_items_query(
+        &app.app.github_mock_server,
+        None,
+        None,
+        &empty_sync_github_task_items_response,
+    )
+    .await;
+    mock_todoist_sync_resources_service(
+        &app.app.todoist_mock_server,
+        "projects",
+        &sync_todoist_projects_response,
+        None,
+    )
+    .await;
+    mock_todoist_complete_item_service(
+        &app.app.todoist_mock_server,
+        &existing_task.sink_item.as_ref().unwrap().source_id,
+    )
+    .await;
+
+    let task_creation_results: Vec<TaskCreationResult> = sync_tasks(
+        &app.client,
+        &app.app.api_address,
+        Some(TaskSourceKind::Github),
+        false,
+    )
+    .await;
+
+    assert_eq!(task_creation_results.len(), 1);
+    let task = &task_creation_results[0].task;
+    assert_eq!(task.id, existing_task.id);
+    assert_eq!(task.status, TaskStatus::Done);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_sync_tasks_should_fetch_all_search_pages(
+    settings: Settings,
+    #[future] authenticated_app: AuthenticatedApp,
+    sync_github_task_items_response: Response<task_items_query::ResponseData>,
+    todoist_item: Box<TodoistItem>,
+    sync_todoist_projects_response: TodoistSyncResponse,
+    github_oauth_credential: OAuthCredentialFixture,
+    todoist_oauth_credential: OAuthCredentialFixture,
+) {
+    let app = authenticated_app.await;
+    create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Todoist(TodoistConfig::enabled()),
+        &settings,
+        todoist_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+    create_and_mock_integration_connection(
+        &app.app,
+        app.user.id,
+        IntegrationConnectionConfig::Github(GithubConfig {
+            sync_notifications_enabled: true,
+            sync_task_config: GithubSyncTaskConfig {
+                enabled: true,
+                target_project: Some(ProjectSummary {
+                    name: "Project2".to_string(),
+                    source_id: "2222".into(),
+                }),
+                ..Default::default()
+            },
+        }),
+        &settings,
+        github_oauth_credential,
+        None,
+        None,
+    )
+    .await;
+
+    // The first page has no review requests and a full page of assigned issues
+    let first_page_response = Response {
+        data: Some(task_items_query::ResponseData {
+            assigned_issues: task_items_query::TaskItemsQueryAssignedIssues {
+                page_info: task_items_query::TaskItemsQueryAssignedIssuesPageInfo {
+                    has_next_page: true,
+                    end_cursor: Some("Y3Vyc29yOjEwMA==".to_string()),
+                },
+                nodes: Some(vec![]),
+            },
+            review_requests: task_items_query::TaskItemsQueryReviewRequests {
+                page_info: task_items_query::TaskItemsQueryReviewRequestsPageInfo {
+                    has_next_page: false,
+                    end_cursor: None,
+                },
+                nodes: Some(vec![]),
+            },
+        }),
+        errors: None,
+        extensions: None,
+    };
+    mock_github_task_items_query(
+        &app.app.github_mock_server,
+        None,
+        None,
+        &first_page_response,
+    )
+    .await;
+    // The review request of the second page must be ignored as its search has no more pages
+    mock_github_task_items_query(
+        &app.app.github_mock_server,
+        Some("Y3Vyc29yOjEwMA=="),
+        None,
+        &sync_github_task_items_response,
+    )
+    .await;
+    let github_task_items: Vec<GithubTaskItem> = sync_github_task_items_response
+        .data
+        .clone()
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let assigned_issue = github_task_items
+        .iter()
+        .find(|item| item.kind == GithubTaskItemKind::AssignedIssue)
+        .unwrap();
+
+    mock_todoist_sync_resources_service(
+        &app.app.todoist_mock_server,
+        "projects",
+        &sync_todoist_projects_response,
+        None,
+    )
+    .await;
+    let expected_task_title = format!(
+        "[octo-org/octo-repo#42 {}]({})",
+        assigned_issue.title,
+        assigned_issue.get_html_url()
+    );
+    mock_todoist_item_add_service(
+        &app.app.todoist_mock_server,
+        &todoist_item.id,
+        expected_task_title.clone(),
+        Some(assigned_issue.body.clone()),
+        Some("2222".to_string()), // ie. "Project2"
+        None,
+        TodoistItemPriority::P1,
+    )
+    .await;
+    mock_todoist_get_item_service(&app.app.todoist_mock_server, todoist_item.clone()).await;
+
+    let task_creation_results: Vec<TaskCreationResult> = sync_tasks(
+        &app.client,
+        &app.app.api_address,
+        Some(TaskSourceKind::Github),
+        false,
+    )
+    .await;
+
+    assert_eq!(task_creation_results.len(), 1);
+    let task = &task_creation_results[0].task;
+    assert_eq!(task.title, expected_task_title);
+    assert_eq!(task.source_item.source_id, assigned_issue.id);
+}
@@ -0,0 +1,216 @@
+//! Integration tests for TOTP two-factor authentication of local accounts.
+//!
+//! Codes are computed from the enrollment secret the same way an
+//! authenticator app would. A TOTP code cannot be replayed, so each test uses
+//! the current time step for the enrollment and the next one for the login.
+
+use email_address::EmailAddress;
+use reqwest::{Client, StatusCode};
+use rstest::*;
+use uuid::Uuid;
+
+use universal_inbox::user::{LoginResponse, SecondFactorCode, SecondFactorKind, User};
+use universal_inbox_api::mailer::EmailTemplate;
+
+use crate::helpers::{
+    TestedApp, tested_app_with_local_auth,
+    user::{
+        create_user_and_login, disable_totp_response, enable_totp, get_current_user_response,
+        get_totp_status, login_second_factor_response, login_user_response, totp_code,
+    },
+};
+
+const PASSWORD: &str = "Very-harD-pasSword-5";
+const MAX_ATTEMPTS: usize = 5;
+
+fn client() -> Client {
+    Client::builder().cookie_store(true).build().unwrap()
+}
+
+/// The login throttle state is shared in Redis across test app instances
+fn unique_email(prefix: &str) -> EmailAddress {
+    format!("{prefix}-{}@example.com", Uuid::new_v4())
+        .parse()
+        .unwrap()
+}
+
+async fn assert_second_factor_required(response: reqwest::Response) {
+    assert_eq!(response.status(), StatusCode::OK);
+    let login_response: LoginResponse = response.json().await.unwrap();
+    assert!(matches!(
+        login_response,
+        LoginResponse::SecondFactorRequired {
+            second_factor: SecondFactorKind::Totp
+        }
+    ));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_login_with_totp(#[future] tested_app_with_local_auth: TestedApp) {
+    let app = tested_app_with_local_auth.await;
+    let email = unique_email("totp");
+    let (client, user) = create_user_and_login(&app, email.clone(), PASSWORD).await;
+    let (enrollment, recovery_codes) = enable_totp(&client, &app).await;
+
+    assert!(
+        enrollment
+            .provisioning_uri
+            .starts_with("otpauth://totp/Universal%20Inbox:")
+    );
+    let status = get_totp_status(&client, &app).await;
+    assert!(status.enabled);
+    assert_eq!(status.remaining_recovery_codes, recovery_codes.len());
+
+    // The password alone does not open a session
+    let client = self::client();
+    let response = login_user_response(&client, &app, email.clone(), PASSWORD).await;
+    assert_second_factor_required(response).await;
+    let response = get_current_user_response(&client, &app).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // The 401 above reset the session cookie along with the pending login
+    let response = login_user_response(&client, &app, email.clone(), PASSWORD).await;
+    assert_second_factor_required(response).await;
+    let response = login_second_factor_response(&client, &app, &totp_code(&enrollment, 1)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let logged_user: User = response.json().await.unwrap();
+    assert_eq!(logged_user.id, user.id);
+    let response = get_current_user_response(&client, &app).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_second_factor_requires_a_pending_password_login(
+    #[future] tested_app_with_local_auth: TestedApp,
+) {
+    let app = tested_app_with_local_auth.await;
+    let email = unique_email("totp-pending");
+    let (client, _) = create_user_and_login(&app, email.clone(), PASSWORD).await;
+    let (enrollment, _) = enable_totp(&client, &app).await;
+
+    let response =
+        login_second_factor_response(&self::client(), &app, &totp_code(&enrollment, 1)).await;
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_second_factor_rejects_a_foreign_origin(
+    #[future] tested_app_with_local_auth: TestedApp,
+) {
+    let app = tested_app_with_local_auth.await;
+    let email = unique_email("totp-origin");
+    let (client, _) = create_user_and_login(&app, email.clone(), PASSWORD).await;
+    let (enrollment, _) = enable_totp(&client, &app).await;
+    let client = self::client();
+    let response = login_user_response(&client, &app, email, PASSWORD).await;
+    assert_second_factor_required(response).await;
+
+    let response = client
+        .post(format!("{}users/me/second-factor", app.api_address))
+        .header(reqwest::header::ORIGIN, "https://evil.example")
+        .json(&SecondFactorCode {
+            code: totp_code(&enrollment, 1),
+        })
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    // The rejected request did not consume the pending login
+    let response = login_second_factor_response(&client, &app, &totp_code(&enrollment, 1)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_login_with_recovery_code_only_once(#[future] tested_app_with_local_auth: TestedApp) {
+    let app = tested_app_with_local_auth.await;
+    let email = unique_email("totp-recovery");
+    let (client, _) = create_user_and_login(&app, email.clone(), PASSWORD).await;
+    let (_, recovery_codes) = enable_totp(&client, &app).await;
+
+    let client = self::client();
+    let response = login_user_response(&client, &app, email.clone(), PASSWORD).await;
+    assert_second_factor_required(response).await;
+    let response = login_second_factor_response(&client, &app, &recovery_codes[0]).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        get_totp_status(&client, &app)
+            .await
+            .remaining_recovery_codes,
+        recovery_codes.len() - 1
+    );
+
+    let client = self::client();
+    let response = login_user_response(&client, &app, email.clone(), PASSWORD).await;
+    assert_second_factor_required(response).await;
+    let response = login_second_factor_response(&client, &app, &recovery_codes[0]).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_wrong_second_factor_codes_lock_the_account(
+    #[future] tested_app_with_local_auth: TestedApp,
+) {
+    let app = tested_app_with_local_auth.await;
+    let email = unique_email("totp-lockme");
+    let (client, _) = create_user_and_login(&app, email.clone(), PASSWORD).await;
+    let (enrollment, _) = enable_totp(&client, &app).await;
+
+    let client = self::client();
+    let response = login_user_response(&client, &app, email.clone(), PASSWORD).await;
+    assert_second_factor_required(response).await;
+    for attempt in 1..=MAX_ATTEMPTS {
+        let response = login_second_factor_response(&client, &app, "000000").await;
+        assert_eq!(
+            response.status(),
+            StatusCode::BAD_REQUEST,
+            "attempt {attempt} should be a generic 400"
+        );
+    }
+
+    // Even the right code is refused while locked
+    let response = login_second_factor_response(&client, &app, &totp_code(&enrollment, 1)).await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    let emails_sent = (*app.mailer_stub.read().await.emails_sent.read().await).clone();
+    let lockout_emails: Vec<_> = emails_sent
+        .iter()
+        .filter_map(|(_, template)| match template {
+            EmailTemplate::AccountLockout {
+                second_factor_failed,
+                ..
+            } => Some(*second_factor_failed),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(lockout_emails, vec![true]);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_disable_totp(#[future] tested_app_with_local_auth: TestedApp) {
+    let app = tested_app_with_local_auth.await;
+    let email = unique_email("totp-disable");
+    let (client, _) = create_user_and_login(&app, email.clone(), PASSWORD).await;
+    let (enrollment, _) = enable_totp(&client, &app).await;
+
+    let response = disable_totp_response(&client, &app, "000000").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = disable_totp_response(&client, &app, &totp_code(&enrollment, 1)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let status = get_totp_status(&client, &app).await;
+    assert!(!status.enabled);
+    assert_eq!(status.remaining_recovery_codes, 0);
+
+    let response = login_user_response(&self::client(), &app, email, PASSWORD).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let login_response: LoginResponse = response.json().await.unwrap();
+    assert!(matches!(login_response, LoginResponse::LoggedIn(_)));
+}
@@ -1,13 +1,30 @@
 use std::{collections::HashMap, str::FromStr};
 
+use chrono::{TimeDelta, Utc};
 use email_address::EmailAddress;
 use itertools::Itertools;
 use rstest::*;
+use secrecy::ExposeSecret;
 use uuid::Uuid;
+use wiremock::{
+    Mock, ResponseTemplate,
+    matchers::{method, path},
+};
 
 use universal_inbox::{
     SuccessResponse,
-    auth::auth_token::AuthenticationToken,
+    auth::{
+        auth_token::{
+            ApiKeyCreation, ApiKeyPatch, ApiKeyProperties, AuthenticationToken,
+            AuthenticationTokenId, TruncatedAuthenticationToken,
+        },
+        oauth2::OAuth2Scope,
+    },
+    integration_connection::{
+        config::IntegrationConnectionConfig,
+        integrations::{github::GithubConfig, linear::LinearConfig},
+    },
+    notification::{Notification, NotificationSourceKind},
     user::{
         EmailValidationToken, Password, PasswordResetToken, User, UserAuthKind, UserId, UserPatch,
     },
@@ -20,11 +37,16 @@ use universal_inbox_api::{
 use crate::helpers::{
     TestedApp,
     auth::{AuthenticatedApp, authenticated_app, fetch_auth_tokens_for_user, get_user_auth},
+    integration_connection::{
+        OAuthCredentialFixture, create_and_mock_integration_connection, github_oauth_credential,
+        linear_oauth_credential,
+    },
+    notification::github::mock_github_notifications_service,
     settings, tested_app_with_local_auth,
     user::{
         get_current_user, get_current_user_response, get_password_reset_token,
-        get_user_email_validation_token, login_user_response, logout_user_response,
-        patch_user_response, register_user, register_user_response,
+        get_user_email_validation_token, list_sessions, login_user_response, logout_user_response,
+        patch_user_response, register_user, register_user_response, revoke_other_sessions,
     },
 };
 
@@ -877,6 +899,412 @@ mod create_authentication_token {
     }
 }
 
+mod api_keys {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    async fn create_api_key(
+        app: &AuthenticatedApp,
+        creation: &ApiKeyCreation,
+    ) -> AuthenticationToken {
+        app.client
+            .post(format!(
+                "{}users/me/authentication-tokens",
+                app.app.api_address
+            ))
+            .json(creation)
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap()
+    }
+
+    async fn patch_api_key_response(
+        app: &AuthenticatedApp,
+        auth_token_id: AuthenticationTokenId,
+        patch: &ApiKeyPatch,
+    ) -> reqwest::Response {
+        app.client
+            .patch(format!(
+                "{}users/me/authentication-tokens/{auth_token_id}",
+                app.app.api_address
+            ))
+            .json(patch)
+            .send()
+            .await
+            .unwrap()
+    }
+
+    async fn list_api_keys(app: &AuthenticatedApp) -> Vec<TruncatedAuthenticationToken> {
+        app.client
+            .get(format!(
+                "{}users/me/authentication-tokens",
+                app.app.api_address
+            ))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap()
+    }
+
+    async fn call_with_api_key(
+        app: &AuthenticatedApp,
+        auth_token: &AuthenticationToken,
+        method: reqwest::Method,
+        path: &str,
+    ) -> reqwest::Response {
+        reqwest::Client::new()
+            .request(method, format!("{}{path}", app.app.api_address))
+            .bearer_auth(auth_token.jwt_token.expose_secret().0.clone())
+            .json(&serde_json::json!({ "status": "Done" }))
+            .send()
+            .await
+            .unwrap()
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_create_api_key_with_properties(#[future] authenticated_app: AuthenticatedApp) {
+        let app = authenticated_app.await;
+        let expire_at = Utc::now() + TimeDelta::try_days(7).unwrap();
+
+        let auth_token = create_api_key(
+            &app,
+            &ApiKeyCreation {
+                name: Some("  CI script ".to_string()),
+                expire_at: Some(expire_at),
+                scopes: Some(vec![OAuth2Scope::NotificationsRead]),
+                notification_source_kinds: Some(vec![NotificationSourceKind::Github]),
+                rate_limit_per_minute: Some(30),
+            },
+        )
+        .await;
+
+        assert_eq!(auth_token.expire_at, Some(expire_at));
+        assert_eq!(
+            auth_token.api_key_properties,
+            ApiKeyProperties {
+                name: Some("CI script".to_string()),
+                scopes: Some(vec![OAuth2Scope::NotificationsRead]),
+                notification_source_kinds: Some(vec![NotificationSourceKind::Github]),
+                rate_limit_per_minute: Some(30),
+                last_used_at: None,
+                last_used_ip: None,
+            }
+        );
+        assert!(auth_token.api_key_properties.is_read_only());
+
+        let api_keys = list_api_keys(&app).await;
+        assert_eq!(api_keys.len(), 1);
+        assert_eq!(api_keys[0].id, auth_token.id);
+        assert_eq!(
+            api_keys[0].api_key_properties,
+            auth_token.api_key_properties
+        );
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_create_never_expiring_api_key(#[future] authenticated_app: AuthenticatedApp) {
+        let app = authenticated_app.await;
+
+        let auth_token = create_api_key(&app, &ApiKeyCreation::default()).await;
+
+        assert_eq!(auth_token.expire_at, None);
+        assert_eq!(auth_token.api_key_properties, ApiKeyProperties::default());
+
+        let response =
+            call_with_api_key(&app, &auth_token, reqwest::Method::GET, "notifications").await;
+        assert_eq!(response.status(), 200);
+
+        let api_keys = list_api_keys(&app).await;
+        assert!(api_keys[0].api_key_properties.last_used_at.is_some());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_read_only_api_key_cannot_write(#[future] authenticated_app: AuthenticatedApp) {
+        let app = authenticated_app.await;
+        let auth_token = create_api_key(
+            &app,
+            &ApiKeyCreation {
+                scopes: Some(vec![OAuth2Scope::NotificationsRead, OAuth2Scope::TasksRead]),
+                ..Default::default()
+            },
+        )
+        .await;
+        let patch_task_path = format!("tasks/{}", Uuid::new_v4());
+
+        let response = call_with_api_key(
+            &app,
+            &auth_token,
+            reqwest::Method::GET,
+            "tasks?status=Active",
+        )
+        .await;
+        assert_eq!(response.status(), 200);
+
+        let response =
+            call_with_api_key(&app, &auth_token, reqwest::Method::PATCH, &patch_task_path).await;
+        assert_eq!(response.status(), 403);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["error"], "insufficient_scope");
+
+        let response = call_with_api_key(
+            &app,
+            &auth_token,
+            reqwest::Method::GET,
+            "users/me/authentication-tokens",
+        )
+        .await;
+        assert_eq!(response.status(), 403);
+
+        // Restrictions are read from the stored key: widening them applies to
+        // the already issued key
+        let response = patch_api_key_response(
+            &app,
+            auth_token.id.clone(),
+            &ApiKeyPatch {
+                scopes: Some(None),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(response.status(), 200);
+        let updated: TruncatedAuthenticationToken = response.json().await.unwrap();
+        assert_eq!(updated.api_key_properties.scopes, None);
+
+        let response =
+            call_with_api_key(&app, &auth_token, reqwest::Method::PATCH, &patch_task_path).await;
+        assert_eq!(response.status(), 404);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_source_restricted_api_key(#[future] authenticated_app: AuthenticatedApp) {
+        let app = authenticated_app.await;
+        let auth_token = create_api_key(
+            &app,
+            &ApiKeyCreation {
+                notification_source_kinds: Some(vec![NotificationSourceKind::Github]),
+                ..Default::default()
+            },
+        )
+        .await;
+
+        let response = call_with_api_key(
+            &app,
+            &auth_token,
+            reqwest::Method::GET,
+            "notifications?sources=Github",
+        )
+        .await;
+        assert_eq!(response.status(), 200);
+
+        let response = call_with_api_key(
+            &app,
+            &auth_token,
+            reqwest::Method::GET,
+            "notifications?sources=Linear",
+        )
+        .await;
+        assert_eq!(response.status(), 403);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_source_restricted_api_key_sync_notifications(
+        settings: Settings,
+        #[future] authenticated_app: AuthenticatedApp,
+        github_oauth_credential: OAuthCredentialFixture,
+        linear_oauth_credential: OAuthCredentialFixture,
+    ) {
+        let app = authenticated_app.await;
+        create_and_mock_integration_connection(
+            &app.app,
+            app.user.id,
+            IntegrationConnectionConfig::Github(GithubConfig::enabled()),
+            &settings,
+            github_oauth_credential,
+            None,
+            None,
+        )
+        .await;
+        create_and_mock_integration_connection(
+            &app.app,
+            app.user.id,
+            IntegrationConnectionConfig::Linear(LinearConfig::enabled()),
+            &settings,
+            linear_oauth_credential,
+            None,
+            None,
+        )
+        .await;
+        mock_github_notifications_service(&app.app.github_mock_server, "1", &vec![]).await;
+        // Linear must not be synchronized with a Github only API key
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(0)
+            .mount(&app.app.linear_mock_server)
+            .await;
+        let auth_token = create_api_key(
+            &app,
+            &ApiKeyCreation {
+                notification_source_kinds: Some(vec![NotificationSourceKind::Github]),
+                ..Default::default()
+            },
+        )
+        .await;
+        let sync_notifications = |body: serde_json::Value| {
+            reqwest::Client::new()
+                .post(format!("{}notifications/sync", app.app.api_address))
+                .bearer_auth(auth_token.jwt_token.expose_secret().0.clone())
+                .json(&body)
+                .send()
+        };
+
+        let response = sync_notifications(serde_json::json!({ "asynchronous": false }))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        let notifications: Vec<Notification> = response.json().await.unwrap();
+        assert!(
+            notifications
+                .iter()
+                .all(|notification| { notification.kind == NotificationSourceKind::Github })
+        );
+
+        let response =
+            sync_notifications(serde_json::json!({ "source": "Linear", "asynchronous": false }))
+                .await
+                .unwrap();
+        assert_eq!(response.status(), 403);
+
+        let response =
+            sync_notifications(serde_json::json!({ "source": "Slack", "asynchronous": true }))
+                .await
+                .unwrap();
+        assert_eq!(response.status(), 403);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_revoked_api_key_is_rejected(#[future] authenticated_app: AuthenticatedApp) {
+        let app = authenticated_app.await;
+        let auth_token = create_api_key(&app, &ApiKeyCreation::default()).await;
+
+        let response = patch_api_key_response(
+            &app,
+            auth_token.id.clone(),
+            &ApiKeyPatch {
+                is_revoked: Some(true),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(response.status(), 200);
+
+        let response =
+            call_with_api_key(&app, &auth_token, reqwest::Method::GET, "notifications").await;
+        assert_eq!(response.status(), 401);
+
+        let response = patch_api_key_response(
+            &app,
+            auth_token.id.clone(),
+            &ApiKeyPatch {
+                is_revoked: Some(false),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(response.status(), 400);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_api_key_rate_limit(#[future] authenticated_app: AuthenticatedApp) {
+        let app = authenticated_app.await;
+        let auth_token = create_api_key(
+            &app,
+            &ApiKeyCreation {
+                rate_limit_per_minute: Some(1),
+                ..Default::default()
+            },
+        )
+        .await;
+
+        let response =
+            call_with_api_key(&app, &auth_token, reqwest::Method::GET, "notifications").await;
+        assert_eq!(response.status(), 200);
+
+        let response =
+            call_with_api_key(&app, &auth_token, reqwest::Method::GET, "notifications").await;
+        assert_eq!(response.status(), 429);
+        assert!(response.headers().contains_key("retry-after"));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_patch_unknown_api_key(#[future] authenticated_app: AuthenticatedApp) {
+        let app = authenticated_app.await;
+
+        let response = patch_api_key_response(
+            &app,
+            AuthenticationTokenId(Uuid::new_v4()),
+            &ApiKeyPatch {
+                name: Some(Some("unknown".to_string())),
+                ..Default::default()
+            },
+        )
+        .await;
+
+        assert_eq!(response.status(), 404);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_unknown_bearer_token_is_handled_as_a_session(
+        #[future] authenticated_app: AuthenticatedApp,
+    ) {
+        let app = authenticated_app.await;
+        let auth_token = create_api_key(
+            &app,
+            &ApiKeyCreation {
+                scopes: Some(vec![OAuth2Scope::NotificationsRead]),
+                rate_limit_per_minute: Some(1),
+                ..Default::default()
+            },
+        )
+        .await;
+        // Forget the key, so that its token is not known as an API key anymore
+        let mut transaction = app.app.repository.begin().await.unwrap();
+        sqlx::query("DELETE FROM authentication_token WHERE id = $1")
+            .bind(auth_token.id.0)
+            .execute(&mut *transaction)
+            .await
+            .unwrap();
+        transaction.commit().await.unwrap();
+
+        let response =
+            call_with_api_key(&app, &auth_token, reqwest::Method::GET, "notifications").await;
+        assert_eq!(response.status(), 200);
+
+        // It is registered as a session which can be listed and revoked
+        assert!(list_api_keys(&app).await.is_empty());
+        assert_eq!(list_sessions(&app.client, &app.app).await.len(), 2);
+        let revoked_sessions = revoke_other_sessions(&app.client, &app.app).await;
+        assert_eq!(revoked_sessions.revoked_sessions_count, 1);
+        let response =
+            call_with_api_key(&app, &auth_token, reqwest::Method::GET, "notifications").await;
+        assert_eq!(response.status(), 401);
+    }
+}
+
 mod patch_user {
     use super::*;
     use crate::helpers::tested_app_with_domain_blacklist;
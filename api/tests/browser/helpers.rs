@@ -1,6 +1,5 @@
 use std::{collections::HashMap, env, sync::Arc, time::Duration};
 
-use apalis_redis::RedisStorage;
 use email_address::EmailAddress;
 use rstest::*;
 use sqlx::PgPool;
@@ -18,7 +17,6 @@ pub const EXPECT_TIMEOUT: Duration = Duration::from_secs(60);
 use universal_inbox_api::{
     commands::generate::generate_testing_user,
     configuration::{AuthenticationSettings, LocalAuthenticationSettings, Settings},
-    jobs::UniversalInboxJob,
     repository::{Repository, user::UserRepository},
     universal_inbox::{
         integration_connection::service::IntegrationConnectionService,
@@ -31,7 +29,7 @@ use universal_inbox_api::{
 use crate::common::{build_and_spawn, setup_test_env};
 
 // Re-export shared fixtures so rstest can resolve them by name in this module's fixtures
-pub use crate::common::{db_connection, redis_storage, settings, tracing_setup};
+pub use crate::common::{db_connection, settings, tracing_setup};
 
 pub const DEFAULT_PASSWORD: &str = "test123456";
 
@@ -69,11 +67,11 @@ pub async fn browser_tested_app(
     mut settings: Settings,
     #[allow(unused, clippy::let_unit_value)] tracing_setup: (),
     #[future] db_connection: Arc<PgPool>,
-    #[future] redis_storage: RedisStorage<UniversalInboxJob>,
 ) -> BrowserTestedApp {
     info!("Setting up browser test server");
 
-    let (listener, port, cache, mock_servers) = setup_test_env(&settings).await;
+    let pool: Arc<PgPool> = db_connection.await;
+    let (listener, port, cache, mock_servers) = setup_test_env(&settings, pool.clone()).await;
 
     // Configure local auth (password-based)
     settings.application.security.authentication =
@@ -100,16 +98,14 @@ pub async fn browser_tested_app(
         env::var("CARGO_MANIFEST_DIR").unwrap()
     ));
 
-    let pool: Arc<PgPool> = db_connection.await;
     let repository = Arc::new(Repository::new(pool.clone()));
-    let redis_storage = redis_storage.await;
 
-    let (services, _mailer_stub, _redis_storage) = build_and_spawn(
+    let (services, _mailer_stub, _job_storage) = build_and_spawn(
         listener,
         pool,
         settings.clone(),
         &mock_servers,
-        redis_storage,
+        cache.clone(),
     )
     .await;
 
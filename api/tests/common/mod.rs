@@ -1,6 +1,5 @@
-use std::{net::TcpListener, str::FromStr, sync::Arc};
+use std::{collections::HashMap, net::TcpListener, str::FromStr, sync::Arc};
 
-use apalis_redis::RedisStorage;
 use rstest::*;
 use sqlx::{
     ConnectOptions, Connection, Executor, PgConnection, PgPool, postgres::PgConnectOptions,
@@ -13,7 +12,10 @@ use wiremock::MockServer;
 use universal_inbox_api::{
     configuration::{CronSettings, Settings},
     integrations::slack::SlackService,
-    jobs::UniversalInboxJob,
+    jobs::{
+        storage::{JOB_STORAGE_NAMESPACE, JobStorage},
+        sync::SyncConcurrencyLimiter,
+    },
     observability::{get_subscriber, init_subscriber},
     universal_inbox::{
         auth_token::service::AuthenticationTokenService,
@@ -85,15 +87,16 @@ pub async fn db_connection(mut settings: Settings) -> Arc<PgPool> {
     Arc::new(db_connection)
 }
 
-#[fixture]
-pub async fn redis_storage(settings: Settings) -> RedisStorage<UniversalInboxJob> {
-    let namespace = format!("universal-inbox:jobs:UniversalInboxJob:{}", Uuid::new_v4());
-    RedisStorage::new_with_config(
-        apalis_redis::connect(settings.redis.connection_string())
-            .await
-            .expect("Redis storage connection failed"),
-        apalis_redis::Config::default().set_namespace(&namespace),
+/// Job storage isolated in its own namespace, in the test database when using
+/// the PostgreSQL backend
+pub async fn job_storage(settings: &Settings, pool: Arc<PgPool>) -> JobStorage {
+    JobStorage::connect(
+        settings,
+        pool,
+        &format!("{JOB_STORAGE_NAMESPACE}:{}", Uuid::new_v4()),
     )
+    .await
+    .expect("Job storage connection failed")
 }
 
 #[fixture]
@@ -156,6 +159,7 @@ pub struct TestServices {
     pub slack_bridge_service:
         Arc<universal_inbox_api::universal_inbox::slack_bridge::service::SlackBridgeService>,
     pub oauth2_service: Arc<OAuth2Service>,
+    pub job_service: Arc<universal_inbox_api::universal_inbox::job::service::JobService>,
 }
 
 pub async fn build_test_services(
@@ -179,6 +183,7 @@ pub async fn build_test_services(
         slack_service,
         slack_bridge_service,
         oauth2_service,
+        job_service,
     ) = universal_inbox_api::build_services(
         pool,
         settings,
@@ -205,6 +210,7 @@ pub async fn build_test_services(
         slack_service,
         slack_bridge_service,
         oauth2_service,
+        job_service,
     };
 
     (services, auth_token_service)
@@ -216,14 +222,16 @@ pub async fn build_test_services(
 
 pub async fn spawn_test_server(
     listener: TcpListener,
-    redis_storage: RedisStorage<UniversalInboxJob>,
+    job_storage: JobStorage,
+    cache: Cache,
     settings: Settings,
     services: &TestServices,
     auth_token_service: Arc<RwLock<AuthenticationTokenService>>,
 ) {
     let server = universal_inbox_api::run_server(
         listener,
-        redis_storage,
+        job_storage,
+        cache,
         settings,
         services.notification_service.clone(),
         services.task_service.clone(),
@@ -241,21 +249,23 @@ pub async fn spawn_test_server(
 }
 
 pub async fn spawn_test_worker(
-    redis_storage: RedisStorage<UniversalInboxJob>,
+    job_storage: JobStorage,
     cron_settings: CronSettings,
     cache: Cache,
     services: &TestServices,
 ) {
     let worker = universal_inbox_api::run_worker(
         Some(1),
-        redis_storage,
+        job_storage,
         cron_settings,
-        cache,
+        cache.clone(),
         services.notification_service.clone(),
         services.task_service.clone(),
         services.integration_connection_service.clone(),
         services.third_party_item_service.clone(),
         services.slack_service.clone(),
+        services.job_service.clone(),
+        SyncConcurrencyLimiter::new(cache, &HashMap::new()),
     )
     .await;
 
@@ -268,7 +278,10 @@ pub async fn spawn_test_worker(
 
 /// Sets up the common test environment: rustls, listener, cache, mock servers.
 /// Returns (listener, port, cache, mock_servers).
-pub async fn setup_test_env(settings: &Settings) -> (TcpListener, u16, Cache, MockServers) {
+pub async fn setup_test_env(
+    settings: &Settings,
+    pool: Arc<PgPool>,
+) -> (TcpListener, u16, Cache, MockServers) {
     // Use `let _ =` because `install_default` can only succeed once per process.
     // Subsequent calls (from other tests in the same binary) return Err, which is harmless.
     let _ = rustls::crypto::ring::default_provider().install_default();
@@ -276,7 +289,7 @@ pub async fn setup_test_env(settings: &Settings) -> (TcpListener, u16, Cache, Mo
     let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
     let port = listener.local_addr().unwrap().port();
 
-    let cache = Cache::new(settings.redis.connection_string())
+    let cache = Cache::new(settings, pool)
         .await
         .expect("Failed to create cache");
     Cache::set_namespace(Uuid::new_v4().to_string()).await;
@@ -286,36 +299,31 @@ pub async fn setup_test_env(settings: &Settings) -> (TcpListener, u16, Cache, Mo
     (listener, port, cache, mock_servers)
 }
 
-/// Builds services, spawns server and worker. Returns (TestServices, mailer_stub, redis_storage).
+/// Builds services, spawns server and worker. Returns (TestServices, mailer_stub, job_storage).
 pub async fn build_and_spawn(
     listener: TcpListener,
     pool: Arc<PgPool>,
     settings: Settings,
     mock_servers: &MockServers,
-    redis_storage: RedisStorage<UniversalInboxJob>,
-) -> (
-    TestServices,
-    Arc<RwLock<MailerStub>>,
-    RedisStorage<UniversalInboxJob>,
-) {
+    cache: Cache,
+) -> (TestServices, Arc<RwLock<MailerStub>>, JobStorage) {
+    let job_storage = job_storage(&settings, pool.clone()).await;
     let mailer_stub = Arc::new(RwLock::new(MailerStub::new()));
     let (services, auth_token_service) =
         build_test_services(pool, &settings, mock_servers, mailer_stub.clone()).await;
 
     let cron_settings = settings.application.cron.clone();
-    let cache = Cache::new(settings.redis.connection_string())
-        .await
-        .expect("Failed to create cache");
     spawn_test_server(
         listener,
-        redis_storage.clone(),
+        job_storage.clone(),
+        cache.clone(),
         settings,
         &services,
         auth_token_service,
     )
     .await;
 
-    spawn_test_worker(redis_storage.clone(), cron_settings, cache, &services).await;
+    spawn_test_worker(job_storage.clone(), cron_settings, cache, &services).await;
 
-    (services, mailer_stub, redis_storage)
+    (services, mailer_stub, job_storage)
 }
@@ -5,7 +5,7 @@ use secrecy::{CloneableSecret, ExposeSecret, SecretBox, SerializableSecret, zero
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::user::UserId;
+use crate::{auth::oauth2::OAuth2Scope, notification::NotificationSourceKind, user::UserId};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AuthenticationToken {
@@ -17,6 +17,82 @@ pub struct AuthenticationToken {
     pub expire_at: Option<DateTime<Utc>>,
     pub is_revoked: bool,
     pub is_session_token: bool,
+    #[serde(flatten)]
+    pub api_key_properties: ApiKeyProperties,
+    /// User agent of the client which opened the session (session tokens only)
+    #[serde(default)]
+    pub user_agent: Option<String>,
+}
+
+/// User-editable properties of an API key (a non-session authentication token).
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct ApiKeyProperties {
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Scopes the key is restricted to, `None` granting full access
+    #[serde(default)]
+    pub scopes: Option<Vec<OAuth2Scope>>,
+    /// Notification sources the key can access, `None` granting access to all of them
+    #[serde(default)]
+    pub notification_source_kinds: Option<Vec<NotificationSourceKind>>,
+    #[serde(default)]
+    pub rate_limit_per_minute: Option<u32>,
+    #[serde(default)]
+    pub last_used_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_used_ip: Option<String>,
+}
+
+impl ApiKeyProperties {
+    pub fn is_read_only(&self) -> bool {
+        self.scopes
+            .as_ref()
+            .is_some_and(|scopes| scopes.iter().all(|scope| scope.is_read_only()))
+    }
+}
+
+/// Body of `POST /users/me/authentication-tokens`
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct ApiKeyCreation {
+    pub name: Option<String>,
+    /// `None` creates a key that never expires
+    pub expire_at: Option<DateTime<Utc>>,
+    pub scopes: Option<Vec<OAuth2Scope>>,
+    pub notification_source_kinds: Option<Vec<NotificationSourceKind>>,
+    pub rate_limit_per_minute: Option<u32>,
+}
+
+/// Body of `PATCH /users/me/authentication-tokens/{id}`. For optional
+/// properties, `null` removes the restriction while an absent field leaves it
+/// untouched.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct ApiKeyPatch {
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    pub name: Option<Option<String>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    pub scopes: Option<Option<Vec<OAuth2Scope>>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    pub notification_source_kinds: Option<Option<Vec<NotificationSourceKind>>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    pub rate_limit_per_minute: Option<Option<u32>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_revoked: Option<bool>,
 }
 
 impl AuthenticationToken {
@@ -35,6 +111,8 @@ impl AuthenticationToken {
             expire_at,
             is_revoked: false,
             is_session_token,
+            api_key_properties: Default::default(),
+            user_agent: None,
         }
     }
 
@@ -102,14 +180,17 @@ impl Zeroize for JWTToken {
 impl CloneableSecret for JWTToken {}
 impl SerializableSecret for JWTToken {}
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct TruncatedAuthenticationToken {
     pub id: AuthenticationTokenId,
     pub user_id: UserId,
     pub truncated_jwt_token: String,
+    pub created_at: DateTime<Utc>,
     pub expire_at: Option<DateTime<Utc>>,
     pub is_revoked: bool,
     pub is_session_token: bool,
+    #[serde(flatten)]
+    pub api_key_properties: ApiKeyProperties,
 }
 
 impl TruncatedAuthenticationToken {
@@ -122,13 +203,48 @@ impl TruncatedAuthenticationToken {
             id: authentication_token.id,
             user_id: authentication_token.user_id,
             truncated_jwt_token,
+            created_at: authentication_token.created_at,
             expire_at: authentication_token.expire_at,
             is_revoked: authentication_token.is_revoked,
             is_session_token: authentication_token.is_session_token,
+            api_key_properties: authentication_token.api_key_properties,
+        }
+    }
+}
+
+/// An active login session of a user, backed by a stored session token
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct UserSession {
+    pub id: AuthenticationTokenId,
+    pub created_at: DateTime<Utc>,
+    pub expire_at: Option<DateTime<Utc>>,
+    pub last_seen_at: Option<DateTime<Utc>>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    /// Whether this is the session of the client listing the sessions
+    pub is_current: bool,
+}
+
+impl UserSession {
+    pub fn new(session_token: AuthenticationToken, is_current: bool) -> Self {
+        Self {
+            id: session_token.id,
+            created_at: session_token.created_at,
+            expire_at: session_token.expire_at,
+            last_seen_at: session_token.api_key_properties.last_used_at,
+            ip_address: session_token.api_key_properties.last_used_ip,
+            user_agent: session_token.user_agent,
+            is_current,
         }
     }
 }
 
+/// Response of `DELETE /users/me/sessions`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct RevokedSessions {
+    pub revoked_sessions_count: u64,
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -151,4 +267,25 @@ mod tests {
             );
         }
     }
+
+    mod api_key_patch {
+        use super::super::*;
+        use pretty_assertions::assert_eq;
+        use rstest::*;
+
+        #[rstest]
+        fn test_null_removes_restriction_and_absent_field_is_untouched() {
+            let patch: ApiKeyPatch =
+                serde_json::from_str(r#"{"name": "CI", "notification_source_kinds": null}"#)
+                    .unwrap();
+            assert_eq!(
+                patch,
+                ApiKeyPatch {
+                    name: Some(Some("CI".to_string())),
+                    notification_source_kinds: Some(None),
+                    ..Default::default()
+                }
+            );
+        }
+    }
 }
@@ -1,9 +1,152 @@
+use std::collections::BTreeSet;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
 use uuid::Uuid;
 
 use crate::user::UserId;
 
+/// Permission granted to an OAuth2 client (or scoped token) over the user's
+/// data. Scopes travel as the space-separated `scope` claim of the access
+/// token and are enforced on API routes and MCP tools.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    EnumIter,
+)]
+pub enum OAuth2Scope {
+    #[serde(rename = "notifications:read")]
+    #[strum(serialize = "notifications:read")]
+    NotificationsRead,
+    #[serde(rename = "notifications:write")]
+    #[strum(serialize = "notifications:write")]
+    NotificationsWrite,
+    #[serde(rename = "tasks:read")]
+    #[strum(serialize = "tasks:read")]
+    TasksRead,
+    #[serde(rename = "tasks:write")]
+    #[strum(serialize = "tasks:write")]
+    TasksWrite,
+    #[serde(rename = "integrations:read")]
+    #[strum(serialize = "integrations:read")]
+    IntegrationsRead,
+    #[serde(rename = "sync")]
+    #[strum(serialize = "sync")]
+    Sync,
+    /// Lets a resource server authenticate to the token introspection
+    /// endpoint with an API key
+    #[serde(rename = "tokens:introspect")]
+    #[strum(serialize = "tokens:introspect")]
+    TokensIntrospect,
+}
+
+/// Legacy coarse-grained scopes advertised before fine-grained scopes existed.
+/// Still accepted so that previously authorized clients keep working.
+const LEGACY_READ_SCOPE: &str = "read";
+const LEGACY_WRITE_SCOPE: &str = "write";
+
+impl OAuth2Scope {
+    /// Scopes granted when a client does not request any: agents are
+    /// read-only unless the user explicitly grants more.
+    pub const DEFAULT: [OAuth2Scope; 3] = [
+        OAuth2Scope::NotificationsRead,
+        OAuth2Scope::TasksRead,
+        OAuth2Scope::IntegrationsRead,
+    ];
+
+    pub fn all() -> Vec<OAuth2Scope> {
+        OAuth2Scope::iter().collect()
+    }
+
+    /// Scopes OAuth2 clients can request, all but the API keys only ones
+    pub fn requestable_by_oauth2_clients() -> Vec<OAuth2Scope> {
+        OAuth2Scope::iter()
+            .filter(|scope| !scope.is_api_key_only())
+            .collect()
+    }
+
+    /// Whether the scope can only be granted to API keys, as it does not give
+    /// access to the user's data
+    pub fn is_api_key_only(&self) -> bool {
+        matches!(self, OAuth2Scope::TokensIntrospect)
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        matches!(
+            self,
+            OAuth2Scope::NotificationsRead | OAuth2Scope::TasksRead | OAuth2Scope::IntegrationsRead
+        )
+    }
+
+    /// Human-readable description displayed on the consent screen.
+    pub fn description(&self) -> &'static str {
+        match self {
+            OAuth2Scope::NotificationsRead => "Read your notifications",
+            OAuth2Scope::NotificationsWrite => {
+                "Update your notifications (mark as read, delete, snooze, act on them)"
+            }
+            OAuth2Scope::TasksRead => "Read your tasks and projects",
+            OAuth2Scope::TasksWrite => "Create and update your tasks",
+            OAuth2Scope::IntegrationsRead => "Read your integration connections",
+            OAuth2Scope::Sync => "Trigger synchronization with your integrations",
+            OAuth2Scope::TokensIntrospect => "Introspect the OAuth2 tokens issued for your account",
+        }
+    }
+
+    /// Parses a space-separated `scope` value into a set of scopes, expanding
+    /// the legacy `read` / `write` scopes. Returns the first unknown token as
+    /// an error.
+    pub fn parse_scopes(scope: &str) -> Result<BTreeSet<OAuth2Scope>, String> {
+        let mut scopes = BTreeSet::new();
+        for token in scope.split_whitespace() {
+            match token {
+                LEGACY_READ_SCOPE => {
+                    scopes.extend(OAuth2Scope::iter().filter(|s| s.is_read_only()))
+                }
+                LEGACY_WRITE_SCOPE => scopes.extend(
+                    OAuth2Scope::iter().filter(|s| !s.is_read_only() && !s.is_api_key_only()),
+                ),
+                _ => {
+                    scopes.insert(
+                        token
+                            .parse::<OAuth2Scope>()
+                            .map_err(|_| token.to_string())?,
+                    );
+                }
+            }
+        }
+        Ok(scopes)
+    }
+
+    /// Same as [`OAuth2Scope::parse_scopes`] but silently ignores unknown
+    /// tokens. Used on already issued tokens and stored consents.
+    pub fn parse_scopes_lossy(scope: &str) -> BTreeSet<OAuth2Scope> {
+        scope
+            .split_whitespace()
+            .flat_map(|token| OAuth2Scope::parse_scopes(token).unwrap_or_default())
+            .collect()
+    }
+
+    pub fn to_scope_string<'a>(scopes: impl IntoIterator<Item = &'a OAuth2Scope>) -> String {
+        scopes
+            .into_iter()
+            .map(|scope| scope.to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct OAuth2Client {
     pub id: Uuid,
@@ -31,6 +174,71 @@ pub struct OAuth2AuthorizationCode {
     pub created_at: DateTime<Utc>,
 }
 
+/// RFC 8628 device authorization request, pending until the user approves
+/// or denies it on the verification page.
+#[derive(Debug, Clone)]
+pub struct OAuth2DeviceAuthorization {
+    pub device_code_hash: String,
+    /// Normalized user code, without separator
+    pub user_code: String,
+    pub client_id: String,
+    pub scope: Option<String>,
+    pub resource: Option<String>,
+    pub status: OAuth2DeviceAuthorizationStatus,
+    /// User who approved or denied the request
+    pub user_id: Option<UserId>,
+    pub polling_interval_secs: i32,
+    pub last_polled_at: Option<DateTime<Utc>>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display, EnumString)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum OAuth2DeviceAuthorizationStatus {
+    Pending,
+    Approved,
+    Denied,
+}
+
+/// Response from `POST /oauth2/device_authorization` (RFC 8628 §3.2).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceAuthorizationResponse {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    pub verification_uri_complete: String,
+    pub expires_in: u64,
+    pub interval: u64,
+}
+
+/// Errors of the device code grant returned while polling the token endpoint
+/// (RFC 8628 §3.5).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum OAuth2DeviceGrantError {
+    AuthorizationPending,
+    SlowDown,
+    AccessDenied,
+    ExpiredToken,
+    InvalidGrant,
+}
+
+/// Body of `POST /oauth2/device/verify`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuth2DeviceVerification {
+    pub user_code: String,
+}
+
+/// Response from `POST /oauth2/device/verify`: the consent request to
+/// display for the device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuth2DeviceVerificationResponse {
+    pub request_id: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct OAuth2RefreshToken {
     pub id: Uuid,
@@ -42,6 +250,9 @@ pub struct OAuth2RefreshToken {
     pub expires_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub revoked_at: Option<DateTime<Utc>>,
+    /// `jti` of the access token issued alongside this refresh token
+    pub access_token_jti: Option<String>,
+    pub access_token_expires_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -53,6 +264,35 @@ pub struct TokenResponse {
     pub scope: String,
 }
 
+/// RFC 7662 token introspection response. Inactive tokens only carry
+/// `active: false`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenIntrospectionResponse {
+    pub active: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exp: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iat: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aud: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+}
+
+impl TokenIntrospectionResponse {
+    pub fn inactive() -> Self {
+        Self::default()
+    }
+}
+
 /// Summary of an authorized OAuth2 client for display in the user profile
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthorizedOAuth2Client {
@@ -95,8 +335,17 @@ pub struct OAuth2ConsentRequest {
     pub csrf_token: String,
     pub client_id: String,
     pub client_name: Option<String>,
-    pub redirect_uri: String,
+    /// Redirect URI of an authorization code request, `None` for a device
+    /// authorization request
+    pub redirect_uri: Option<String>,
+    /// User code of a device authorization request
+    #[serde(default)]
+    pub user_code: Option<String>,
     pub scope: Option<String>,
+    /// Parsed `scope`, in display order, so the consent screen can describe
+    /// each requested permission.
+    #[serde(default)]
+    pub scopes: Vec<OAuth2Scope>,
 }
 
 /// Body of `POST /oauth2/authorize/consent`.
@@ -120,3 +369,47 @@ pub enum OAuth2ConsentDecision {
 pub struct OAuth2ConsentResponse {
     pub redirect_url: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_scopes_expands_legacy_scopes() {
+        assert_eq!(
+            OAuth2Scope::parse_scopes("read").unwrap(),
+            BTreeSet::from(OAuth2Scope::DEFAULT)
+        );
+        assert_eq!(
+            OAuth2Scope::parse_scopes("read write").unwrap(),
+            OAuth2Scope::requestable_by_oauth2_clients()
+                .into_iter()
+                .collect()
+        );
+    }
+
+    #[test]
+    fn test_parse_scopes_fine_grained() {
+        assert_eq!(
+            OAuth2Scope::parse_scopes("tasks:read  sync").unwrap(),
+            BTreeSet::from([OAuth2Scope::TasksRead, OAuth2Scope::Sync])
+        );
+        assert_eq!(
+            OAuth2Scope::parse_scopes("tasks:read admin"),
+            Err("admin".to_string())
+        );
+        assert_eq!(
+            OAuth2Scope::parse_scopes_lossy("tasks:read admin"),
+            BTreeSet::from([OAuth2Scope::TasksRead])
+        );
+    }
+
+    #[test]
+    fn test_scope_string_round_trip() {
+        let scopes = BTreeSet::from([OAuth2Scope::NotificationsWrite, OAuth2Scope::TasksRead]);
+        let scope_string = OAuth2Scope::to_scope_string(&scopes);
+        assert_eq!(scope_string, "notifications:write tasks:read");
+        assert_eq!(OAuth2Scope::parse_scopes(&scope_string).unwrap(), scopes);
+    }
+}
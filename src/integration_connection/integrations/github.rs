@@ -1,14 +1,21 @@
 use serde::{Deserialize, Serialize};
 
+use crate::integration_connection::integrations::task_time_config::TaskTimeConfig;
+use crate::integration_connection::provider::IntegrationProviderKind;
+use crate::task::{PresetDueDate, ProjectSummary, TaskPriority};
+
 #[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone)]
 pub struct GithubConfig {
     pub sync_notifications_enabled: bool,
+    #[serde(default)]
+    pub sync_task_config: GithubSyncTaskConfig,
 }
 
 impl Default for GithubConfig {
     fn default() -> Self {
         Self {
             sync_notifications_enabled: true,
+            sync_task_config: GithubSyncTaskConfig::default(),
         }
     }
 }
@@ -17,12 +24,61 @@ impl GithubConfig {
     pub fn enabled() -> Self {
         Self {
             sync_notifications_enabled: true,
+            sync_task_config: GithubSyncTaskConfig::enabled(),
         }
     }
 
     pub fn disabled() -> Self {
         Self {
             sync_notifications_enabled: false,
+            sync_task_config: GithubSyncTaskConfig::disabled(),
         }
     }
 }
+
+/// Cache validators of the last fetched page of Github notifications, sent back
+/// as conditional request headers so that unchanged notifications are not refetched
+#[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone, Default)]
+pub struct GithubContext {
+    pub notifications_last_modified: Option<String>,
+    pub notifications_etag: Option<String>,
+}
+
+/// Configuration of the synchronization of Github issues assigned to the user
+/// and pull requests awaiting the user's review as tasks
+#[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone)]
+pub struct GithubSyncTaskConfig {
+    pub enabled: bool,
+    pub target_project: Option<ProjectSummary>,
+    pub default_due_at: Option<PresetDueDate>,
+    pub default_priority: TaskPriority,
+    pub task_manager_provider_kind: Option<IntegrationProviderKind>,
+    #[serde(default)]
+    pub default_time_config: Option<TaskTimeConfig>,
+}
+
+impl Default for GithubSyncTaskConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_project: None,
+            default_due_at: None,
+            default_priority: TaskPriority::P4,
+            task_manager_provider_kind: None,
+            default_time_config: None,
+        }
+    }
+}
+
+impl GithubSyncTaskConfig {
+    pub fn enabled() -> Self {
+        Self {
+            enabled: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+}
@@ -1,6 +1,7 @@
 use std::{fmt, str::FromStr};
 
 use chrono::{DateTime, TimeDelta, Timelike, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use uuid::Uuid;
@@ -16,6 +17,8 @@ use crate::{
 pub mod config;
 pub mod integrations;
 pub mod provider;
+pub mod rate_limit;
+pub mod sync_run;
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Eq)]
@@ -215,6 +218,50 @@ impl IntegrationConnection {
     }
 }
 
+/// Status of an integration connection and of its synchronizations, without
+/// its provider configuration and context
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Eq, JsonSchema)]
+pub struct IntegrationConnectionSummary {
+    pub id: IntegrationConnectionId,
+    pub provider_kind: IntegrationProviderKind,
+    pub status: IntegrationConnectionStatus,
+    pub failure_message: Option<String>,
+    pub is_syncing_notifications: bool,
+    pub last_notifications_sync_completed_at: Option<DateTime<Utc>>,
+    pub last_notifications_sync_failed_at: Option<DateTime<Utc>>,
+    pub last_notifications_sync_failure_message: Option<String>,
+    pub notifications_sync_failures: u32,
+    pub is_syncing_tasks: bool,
+    pub last_tasks_sync_completed_at: Option<DateTime<Utc>>,
+    pub last_tasks_sync_failed_at: Option<DateTime<Utc>>,
+    pub last_tasks_sync_failure_message: Option<String>,
+    pub tasks_sync_failures: u32,
+}
+
+impl From<IntegrationConnection> for IntegrationConnectionSummary {
+    fn from(integration_connection: IntegrationConnection) -> Self {
+        Self {
+            id: integration_connection.id,
+            provider_kind: integration_connection.provider.kind(),
+            status: integration_connection.status,
+            is_syncing_notifications: integration_connection.is_syncing_notifications(),
+            is_syncing_tasks: integration_connection.is_syncing_tasks(),
+            failure_message: integration_connection.failure_message,
+            last_notifications_sync_completed_at: integration_connection
+                .last_notifications_sync_completed_at,
+            last_notifications_sync_failed_at: integration_connection
+                .last_notifications_sync_failed_at,
+            last_notifications_sync_failure_message: integration_connection
+                .last_notifications_sync_failure_message,
+            notifications_sync_failures: integration_connection.notifications_sync_failures,
+            last_tasks_sync_completed_at: integration_connection.last_tasks_sync_completed_at,
+            last_tasks_sync_failed_at: integration_connection.last_tasks_sync_failed_at,
+            last_tasks_sync_failure_message: integration_connection.last_tasks_sync_failure_message,
+            tasks_sync_failures: integration_connection.tasks_sync_failures,
+        }
+    }
+}
+
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Eq)]
 pub struct IntegrationConnectionCreation {
@@ -222,7 +269,7 @@ pub struct IntegrationConnectionCreation {
 }
 
 macro_attr! {
-    #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy, Eq, EnumFromStr!, EnumDisplay!, Hash)]
+    #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy, Eq, EnumFromStr!, EnumDisplay!, Hash, JsonSchema)]
     pub enum IntegrationConnectionStatus {
         Created,
         Validated,
@@ -7,7 +7,7 @@ use crate::{
     integration_connection::{
         config::IntegrationConnectionConfig,
         integrations::{
-            github::GithubConfig,
+            github::{GithubConfig, GithubContext},
             google_calendar::GoogleCalendarConfig,
             google_drive::{GoogleDriveConfig, GoogleDriveContext},
             google_mail::{GoogleMailConfig, GoogleMailContext},
@@ -27,6 +27,7 @@ use crate::{
 #[serde(tag = "type", content = "content")]
 pub enum IntegrationProvider {
     Github {
+        context: Option<GithubContext>,
         config: GithubConfig,
     },
     Linear {
@@ -65,7 +66,18 @@ impl IntegrationProvider {
         context: Option<IntegrationConnectionContext>,
     ) -> Result<Self> {
         match config {
-            IntegrationConnectionConfig::Github(config) => Ok(Self::Github { config }),
+            IntegrationConnectionConfig::Github(config) => Ok(Self::Github {
+                context: context
+                    .map(|c| {
+                        if let IntegrationConnectionContext::Github(c) = c {
+                            Ok(c)
+                        } else {
+                            Err(anyhow!("Unexpect context for Github provider: {c:?}"))
+                        }
+                    })
+                    .transpose()?,
+                config,
+            }),
             IntegrationConnectionConfig::Linear(config) => Ok(Self::Linear { config }),
             IntegrationConnectionConfig::GoogleCalendar(config) => {
                 Ok(Self::GoogleCalendar { config })
@@ -135,6 +147,33 @@ impl IntegrationProvider {
         }
     }
 
+    pub fn context(&self) -> Option<IntegrationConnectionContext> {
+        match self {
+            IntegrationProvider::Github { context, .. } => {
+                context.clone().map(IntegrationConnectionContext::Github)
+            }
+            IntegrationProvider::GoogleDrive { context, .. } => context
+                .clone()
+                .map(IntegrationConnectionContext::GoogleDrive),
+            IntegrationProvider::GoogleMail { context, .. } => context
+                .clone()
+                .map(IntegrationConnectionContext::GoogleMail),
+            IntegrationProvider::Slack { context, .. } => {
+                context.clone().map(IntegrationConnectionContext::Slack)
+            }
+            IntegrationProvider::Todoist { context, .. } => {
+                context.clone().map(IntegrationConnectionContext::Todoist)
+            }
+            IntegrationProvider::TickTick { context, .. } => {
+                context.clone().map(IntegrationConnectionContext::TickTick)
+            }
+            IntegrationProvider::Linear { .. }
+            | IntegrationProvider::GoogleCalendar { .. }
+            | IntegrationProvider::Notion
+            | IntegrationProvider::API => None,
+        }
+    }
+
     pub fn context_is_empty(&self) -> bool {
         match self {
             IntegrationProvider::Github { .. } => false,
@@ -175,7 +214,7 @@ impl IntegrationProvider {
 
     pub fn config(&self) -> IntegrationConnectionConfig {
         match self {
-            IntegrationProvider::Github { config } => {
+            IntegrationProvider::Github { config, .. } => {
                 IntegrationConnectionConfig::Github(config.clone())
             }
             IntegrationProvider::Linear { config } => {
@@ -206,7 +245,7 @@ impl IntegrationProvider {
 
     pub fn is_sync_notifications_enabled(&self) -> bool {
         match self {
-            IntegrationProvider::Github { config } => config.sync_notifications_enabled,
+            IntegrationProvider::Github { config, .. } => config.sync_notifications_enabled,
             IntegrationProvider::Linear { config } => config.sync_notifications_enabled,
             IntegrationProvider::GoogleDrive { config, .. } => config.sync_notifications_enabled,
             IntegrationProvider::GoogleMail { config, .. } => config.sync_notifications_enabled,
@@ -220,6 +259,7 @@ impl IntegrationProvider {
             IntegrationProvider::Todoist { config, .. } => config.sync_tasks_enabled,
             IntegrationProvider::TickTick { config, .. } => config.sync_tasks_enabled,
             IntegrationProvider::Linear { config } => config.sync_task_config.enabled,
+            IntegrationProvider::Github { config, .. } => config.sync_task_config.enabled,
             IntegrationProvider::Slack { .. } => false, // Slack tasks are not synced but received via the webhook
             _ => false,
         }
@@ -297,6 +337,18 @@ impl IntegrationProvider {
                 config.sync_task_config.task_manager_provider_kind.as_ref(),
                 config.sync_task_config.default_time_config.as_ref(),
             ),
+            IntegrationProvider::Github { config, .. } => {
+                match third_party_item.get_third_party_item_source_kind() {
+                    ThirdPartyItemSourceKind::GithubTaskItem => (
+                        config.sync_task_config.target_project.as_ref(),
+                        config.sync_task_config.default_due_at.as_ref(),
+                        &config.sync_task_config.default_priority,
+                        config.sync_task_config.task_manager_provider_kind.as_ref(),
+                        config.sync_task_config.default_time_config.as_ref(),
+                    ),
+                    _ => return None,
+                }
+            }
             _ => return None,
         };
 
@@ -325,6 +377,7 @@ pub enum IntegrationConnectionContext {
     GoogleDrive(GoogleDriveContext),
     GoogleMail(GoogleMailContext),
     Slack(SlackContext),
+    Github(GithubContext),
 }
 
 pub trait IntegrationProviderSource {
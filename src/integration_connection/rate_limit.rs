@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::integration_connection::IntegrationConnectionId;
+
+/// Upstream API budget of an integration connection, as last reported by its
+/// provider
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Eq, JsonSchema)]
+pub struct IntegrationConnectionRateLimit {
+    pub integration_connection_id: IntegrationConnectionId,
+    /// Remaining upstream API calls (or points) in the current window
+    pub remaining: Option<i64>,
+    /// When the current window resets
+    pub reset_at: Option<DateTime<Utc>>,
+    /// The provider must not be called again before this time
+    pub limited_until: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl IntegrationConnectionRateLimit {
+    /// Time until which syncing the connection must be deferred, if any
+    pub fn limited_until(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.limited_until
+            .filter(|limited_until| *limited_until > now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::TimeDelta;
+    use pretty_assertions::assert_eq;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_limited_until() {
+        let now = Utc::now();
+        let rate_limit = IntegrationConnectionRateLimit {
+            integration_connection_id: Uuid::new_v4().into(),
+            remaining: Some(0),
+            reset_at: Some(now + TimeDelta::minutes(5)),
+            limited_until: Some(now + TimeDelta::minutes(5)),
+            updated_at: now,
+        };
+
+        assert_eq!(
+            rate_limit.limited_until(now),
+            Some(now + TimeDelta::minutes(5))
+        );
+        assert_eq!(rate_limit.limited_until(now + TimeDelta::minutes(6)), None);
+    }
+}
@@ -0,0 +1,72 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::integration_connection::IntegrationConnectionId;
+
+/// A past synchronization of an integration connection, kept to diagnose
+/// misbehaving syncs
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Eq, JsonSchema)]
+pub struct IntegrationConnectionSyncRun {
+    pub id: IntegrationConnectionSyncRunId,
+    pub integration_connection_id: IntegrationConnectionId,
+    pub kind: IntegrationConnectionSyncRunKind,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub items_fetched: u32,
+    pub items_created: u32,
+    pub items_updated: u32,
+    pub items_marked_stale: u32,
+    pub upstream_http_calls: u32,
+    /// Remaining upstream API calls as reported by the last rate-limit header
+    pub rate_limit_remaining: Option<i64>,
+    /// Error and its causes, outermost first. Empty for a successful run.
+    pub error_chain: Vec<String>,
+}
+
+impl IntegrationConnectionSyncRun {
+    pub fn is_successful(&self) -> bool {
+        self.error_chain.is_empty()
+    }
+}
+
+macro_attr! {
+    #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy, Eq, EnumFromStr!, EnumDisplay!, Hash, JsonSchema)]
+    pub enum IntegrationConnectionSyncRunKind {
+        Notifications,
+        Tasks,
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Copy, Clone, Eq, Hash, JsonSchema)]
+#[serde(transparent)]
+pub struct IntegrationConnectionSyncRunId(pub Uuid);
+
+impl fmt::Display for IntegrationConnectionSyncRunId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<Uuid> for IntegrationConnectionSyncRunId {
+    fn from(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+}
+
+impl From<IntegrationConnectionSyncRunId> for Uuid {
+    fn from(sync_run_id: IntegrationConnectionSyncRunId) -> Self {
+        sync_run_id.0
+    }
+}
+
+impl FromStr for IntegrationConnectionSyncRunId {
+    type Err = uuid::Error;
+
+    fn from_str(uuid: &str) -> Result<Self, Self::Err> {
+        Ok(Self(Uuid::parse_str(uuid)?))
+    }
+}
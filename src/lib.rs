@@ -51,6 +51,7 @@ pub enum FrontAuthenticationConfig {
     OIDCGoogleAuthorizationCodeFlow(FrontOIDCGoogleAuthorizationCodeFlowConfig),
     Local,
     Passkey,
+    SAML,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Eq)]
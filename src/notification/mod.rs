@@ -320,6 +320,41 @@ impl From<NotificationSyncSourceKind> for IntegrationProviderKind {
     }
 }
 
+impl From<NotificationSyncSourceKind> for NotificationSourceKind {
+    // tag: New notification integration
+    fn from(sync_source_kind: NotificationSyncSourceKind) -> Self {
+        match sync_source_kind {
+            NotificationSyncSourceKind::Github => NotificationSourceKind::Github,
+            NotificationSyncSourceKind::Linear => NotificationSourceKind::Linear,
+            NotificationSyncSourceKind::GoogleMail => NotificationSourceKind::GoogleMail,
+            NotificationSyncSourceKind::GoogleDrive => NotificationSourceKind::GoogleDrive,
+            NotificationSyncSourceKind::Slack => NotificationSourceKind::Slack,
+            NotificationSyncSourceKind::Todoist => NotificationSourceKind::Todoist,
+            NotificationSyncSourceKind::TickTick => NotificationSourceKind::TickTick,
+        }
+    }
+}
+
+impl TryFrom<NotificationSourceKind> for NotificationSyncSourceKind {
+    type Error = anyhow::Error;
+
+    // tag: New notification integration
+    fn try_from(source_kind: NotificationSourceKind) -> Result<Self, Self::Error> {
+        match source_kind {
+            NotificationSourceKind::Github => Ok(Self::Github),
+            NotificationSourceKind::Linear => Ok(Self::Linear),
+            NotificationSourceKind::GoogleMail => Ok(Self::GoogleMail),
+            NotificationSourceKind::GoogleDrive => Ok(Self::GoogleDrive),
+            NotificationSourceKind::Slack => Ok(Self::Slack),
+            NotificationSourceKind::Todoist => Ok(Self::Todoist),
+            NotificationSourceKind::TickTick => Ok(Self::TickTick),
+            _ => Err(anyhow!(
+                "NotificationSourceKind {source_kind} is not a valid NotificationSyncSourceKind"
+            )),
+        }
+    }
+}
+
 impl TryFrom<NotificationSyncSourceKind> for crate::task::TaskSyncSourceKind {
     type Error = anyhow::Error;
 
@@ -1,10 +1,15 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Days, NaiveDate, TimeDelta, TimeZone, Timelike, Utc, Weekday};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 use crate::{
     notification::{NotificationSourceKind, NotificationStatus, NotificationSyncSourceKind},
     task::TaskId,
-    third_party::integrations::google_calendar::GoogleCalendarEventAttendeeResponseStatus,
+    third_party::integrations::{
+        github::GithubMergeMethod, google_calendar::GoogleCalendarEventAttendeeResponseStatus,
+        linear::LinearIssuePriority,
+    },
 };
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -20,14 +25,186 @@ pub struct NotificationPatch {
     pub task_id: Option<TaskId>,
 }
 
+/// Hour of the day, in the user's time zone, until which notifications are
+/// snoozed by day-based presets
+pub const SNOOZE_RESET_HOUR: u32 = 6;
+
+/// Preset periods to snooze a notification for
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SnoozePreset {
+    /// In 3 hours
+    LaterToday,
+    /// Tomorrow morning, or this morning before the reset hour
+    Tomorrow,
+    /// Next Saturday morning
+    ThisWeekend,
+    /// Next Monday morning
+    NextWeek,
+}
+
+impl SnoozePreset {
+    pub fn snoozed_until<Tz: TimeZone>(&self, now: DateTime<Tz>) -> DateTime<Utc> {
+        let days_offset = match self {
+            SnoozePreset::LaterToday => return (now + TimeDelta::hours(3)).with_timezone(&Utc),
+            SnoozePreset::Tomorrow if now.hour() < SNOOZE_RESET_HOUR => 0,
+            SnoozePreset::Tomorrow => 1,
+            SnoozePreset::ThisWeekend => days_until_next(now.weekday(), Weekday::Sat),
+            SnoozePreset::NextWeek => days_until_next(now.weekday(), Weekday::Mon),
+        };
+        let reset_time = (now.date_naive() + Days::new(days_offset))
+            .and_hms_opt(SNOOZE_RESET_HOUR, 0, 0)
+            .unwrap();
+        now.timezone()
+            .from_local_datetime(&reset_time)
+            .earliest()
+            .map(|snoozed_until| snoozed_until.with_timezone(&Utc))
+            // The reset time does not exist on this day in this time zone (DST gap)
+            .unwrap_or_else(|| reset_time.and_utc())
+    }
+}
+
+fn days_until_next(from: Weekday, to: Weekday) -> u64 {
+    match (7 + to.num_days_from_monday() - from.num_days_from_monday()) % 7 {
+        0 => 7,
+        days => days as u64,
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct InvitationPatch {
     pub response_status: GoogleCalendarEventAttendeeResponseStatus,
 }
 
+/// An action applied to the pull request of a Github notification
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, JsonSchema)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum GithubPullRequestAction {
+    Approve { body: Option<String> },
+    RequestChanges { body: String },
+    Comment { body: String },
+    Merge { merge_method: GithubMergeMethod },
+}
+
+impl GithubPullRequestAction {
+    pub fn description(&self) -> &'static str {
+        match self {
+            GithubPullRequestAction::Approve { .. } => "approve",
+            GithubPullRequestAction::RequestChanges { .. } => "request changes on",
+            GithubPullRequestAction::Comment { .. } => "comment on",
+            GithubPullRequestAction::Merge { .. } => "merge",
+        }
+    }
+}
+
+/// An action applied to the issue of a Linear notification
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, JsonSchema)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum LinearIssueAction {
+    Comment {
+        body: String,
+    },
+    /// Move the issue to one of the workflow states of its team
+    UpdateState {
+        state_id: Uuid,
+    },
+    /// Assign the issue to one of the members of its team
+    Assign {
+        assignee_id: Uuid,
+    },
+    Unassign,
+    SetPriority {
+        #[schemars(
+            with = "u8",
+            description = "0: no priority, 1: urgent, 2: high, 3: normal, 4: low"
+        )]
+        priority: LinearIssuePriority,
+    },
+    /// Set the due date of the issue, or clear it when `due_date` is null
+    SetDueDate {
+        due_date: Option<NaiveDate>,
+    },
+}
+
+impl LinearIssueAction {
+    pub fn description(&self) -> &'static str {
+        match self {
+            LinearIssueAction::Comment { .. } => "comment on",
+            LinearIssueAction::UpdateState { .. } => "update the state of",
+            LinearIssueAction::Assign { .. } => "assign",
+            LinearIssueAction::Unassign => "unassign",
+            LinearIssueAction::SetPriority { .. } => "set the priority of",
+            LinearIssueAction::SetDueDate { .. } => "set the due date of",
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PatchNotificationsRequest {
     pub status: Vec<NotificationStatus>,
     pub sources: Vec<NotificationSourceKind>,
     pub patch: NotificationPatch,
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::FixedOffset;
+    use pretty_assertions::assert_eq;
+    use rstest::*;
+
+    use super::*;
+
+    fn local_time(datetime: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(datetime).unwrap()
+    }
+
+    #[rstest]
+    // Wednesday evening
+    #[case(
+        SnoozePreset::LaterToday,
+        "2026-10-14T20:30:00+02:00",
+        "2026-10-14T21:30:00Z"
+    )]
+    #[case(
+        SnoozePreset::Tomorrow,
+        "2026-10-14T20:30:00+02:00",
+        "2026-10-15T04:00:00Z"
+    )]
+    #[case(
+        SnoozePreset::ThisWeekend,
+        "2026-10-14T20:30:00+02:00",
+        "2026-10-17T04:00:00Z"
+    )]
+    #[case(
+        SnoozePreset::NextWeek,
+        "2026-10-14T20:30:00+02:00",
+        "2026-10-19T04:00:00Z"
+    )]
+    // Before the reset hour
+    #[case(
+        SnoozePreset::Tomorrow,
+        "2026-10-14T05:00:00+02:00",
+        "2026-10-14T04:00:00Z"
+    )]
+    // On the target day of the week
+    #[case(
+        SnoozePreset::ThisWeekend,
+        "2026-10-17T10:00:00+02:00",
+        "2026-10-24T04:00:00Z"
+    )]
+    #[case(
+        SnoozePreset::NextWeek,
+        "2026-10-19T10:00:00+02:00",
+        "2026-10-26T04:00:00Z"
+    )]
+    fn test_snooze_preset_snoozed_until(
+        #[case] preset: SnoozePreset,
+        #[case] now: &str,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(
+            preset.snoozed_until(local_time(now)),
+            expected.parse::<DateTime<Utc>>().unwrap()
+        );
+    }
+}
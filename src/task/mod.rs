@@ -533,7 +533,8 @@ macro_attr! {
     pub enum TaskSyncSourceKind {
         Todoist,
         TickTick,
-        Linear
+        Linear,
+        Github
     }
 }
 
@@ -543,7 +544,8 @@ macro_attr! {
         Todoist,
         TickTick,
         Slack,
-        Linear
+        Linear,
+        Github
     }
 }
 
@@ -556,6 +558,7 @@ impl TryFrom<ThirdPartyItemSourceKind> for TaskSourceKind {
             ThirdPartyItemSourceKind::TickTick => Ok(Self::TickTick),
             ThirdPartyItemSourceKind::LinearIssue => Ok(Self::Linear),
             ThirdPartyItemSourceKind::SlackReaction => Ok(Self::Slack),
+            ThirdPartyItemSourceKind::GithubTaskItem => Ok(Self::Github),
             _ => Err(anyhow!(
                 "ThirdPartyItemSourceKind {source_kind} is not a valid TaskSourceKind"
             )),
@@ -571,6 +574,7 @@ impl TryFrom<IntegrationProviderKind> for TaskSyncSourceKind {
             IntegrationProviderKind::Todoist => Ok(Self::Todoist),
             IntegrationProviderKind::TickTick => Ok(Self::TickTick),
             IntegrationProviderKind::Linear => Ok(Self::Linear),
+            IntegrationProviderKind::Github => Ok(Self::Github),
             _ => Err(anyhow!(
                 "IntegrationProviderKind {provider_kind} is not a valid TaskSyncSourceKind"
             )),
@@ -584,6 +588,7 @@ impl From<TaskSyncSourceKind> for IntegrationProviderKind {
             TaskSyncSourceKind::Todoist => IntegrationProviderKind::Todoist,
             TaskSyncSourceKind::TickTick => IntegrationProviderKind::TickTick,
             TaskSyncSourceKind::Linear => IntegrationProviderKind::Linear,
+            TaskSyncSourceKind::Github => IntegrationProviderKind::Github,
         }
     }
 }
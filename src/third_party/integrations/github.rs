@@ -3,6 +3,7 @@ use std::fmt;
 use anyhow::{Context, Result, anyhow};
 use chrono::{DateTime, Timelike, Utc};
 use git_url_parse::GitUrl;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::{DisplayFromStr, serde_as};
 use url::{Host, Url};
@@ -101,6 +102,9 @@ pub struct GithubNotification {
 pub enum GithubNotificationItem {
     GithubPullRequest(GithubPullRequest),
     GithubDiscussion(GithubDiscussion),
+    GithubIssue(GithubIssue),
+    GithubWorkflowRun(GithubWorkflowRun),
+    GithubRelease(GithubRelease),
 }
 
 impl GithubNotification {
@@ -176,6 +180,15 @@ impl HasHtmlUrl for GithubNotification {
         if let Some(GithubNotificationItem::GithubDiscussion(github_discussion)) = &self.item {
             return github_discussion.url.clone();
         }
+        if let Some(GithubNotificationItem::GithubIssue(github_issue)) = &self.item {
+            return github_issue.url.clone();
+        }
+        if let Some(GithubNotificationItem::GithubWorkflowRun(github_workflow_run)) = &self.item {
+            return github_workflow_run.url.clone();
+        }
+        if let Some(GithubNotificationItem::GithubRelease(github_release)) = &self.item {
+            return github_release.url.clone();
+        }
         if let Some(html_url) = GithubNotification::get_html_url_from_api_url(&self.subject.url) {
             return html_url;
         }
@@ -645,6 +658,14 @@ pub enum GithubPullRequestReviewDecision {
     ReviewRequired,
 }
 
+#[derive(Debug, Serialize, Deserialize, PartialEq, Copy, Clone, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GithubMergeMethod {
+    Merge,
+    Squash,
+    Rebase,
+}
+
 #[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone)]
 #[serde(tag = "type", content = "content")]
 pub enum GithubActor {
@@ -871,6 +892,219 @@ pub struct GithubIssueComment {
     pub author: Option<GithubActor>,
 }
 
+#[serde_as]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone)]
+pub struct GithubIssue {
+    pub id: String,
+    pub number: i64,
+    #[serde_as(as = "DisplayFromStr")]
+    pub url: Url,
+    pub title: String,
+    pub body: String,
+    pub state: GithubIssueState,
+    pub state_reason: Option<GithubIssueStateReason>,
+    pub closed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub repository: GithubRepositorySummary,
+    pub labels: Vec<GithubLabel>,
+    pub assignees: Vec<GithubActor>,
+    pub milestone: Option<GithubMilestone>,
+    pub comments_count: i64,
+    /// The latest comments of the issue, oldest first
+    pub comments: Vec<GithubIssueComment>,
+    /// Pull requests referencing the issue
+    pub linked_pull_requests: Vec<GithubLinkedPullRequest>,
+    pub author: Option<GithubActor>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Copy, Clone, Eq)]
+pub enum GithubIssueState {
+    Open,
+    Closed,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Copy, Clone, Eq)]
+pub enum GithubIssueStateReason {
+    Completed,
+    NotPlanned,
+    Reopened,
+}
+
+#[serde_as]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone)]
+pub struct GithubMilestone {
+    pub title: String,
+    #[serde_as(as = "DisplayFromStr")]
+    pub url: Url,
+    pub due_on: Option<DateTime<Utc>>,
+}
+
+#[serde_as]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone)]
+pub struct GithubLinkedPullRequest {
+    pub number: i64,
+    #[serde_as(as = "DisplayFromStr")]
+    pub url: Url,
+    pub title: String,
+    pub state: GithubPullRequestState,
+    pub repository: GithubRepositorySummary,
+    /// Whether merging the pull request will close the issue
+    pub will_close_issue: bool,
+}
+
+/// A Github Actions workflow run matched with a `CheckSuite` or `WorkflowRun` notification
+#[serde_as]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone)]
+pub struct GithubWorkflowRun {
+    pub id: i64,
+    pub name: String,
+    pub display_title: String,
+    pub run_number: i64,
+    pub event: String,
+    pub head_branch: Option<String>,
+    pub status: GithubCheckStatusState,
+    pub conclusion: Option<GithubCheckConclusionState>,
+    #[serde_as(as = "DisplayFromStr")]
+    pub url: Url,
+    pub repository: GithubRepositorySummary,
+    pub head_commit: Option<GithubCommitSummary>,
+    pub failed_jobs: Vec<GithubWorkflowJob>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[serde_as]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone)]
+pub struct GithubCommitSummary {
+    pub sha: String,
+    pub message: String,
+    pub author_name: Option<String>,
+    #[serde_as(as = "DisplayFromStr")]
+    pub url: Url,
+}
+
+#[serde_as]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone)]
+pub struct GithubWorkflowJob {
+    pub id: i64,
+    pub name: String,
+    pub conclusion: Option<GithubCheckConclusionState>,
+    #[serde_as(as = "DisplayFromStr")]
+    pub url: Url,
+    /// Names of the steps which failed
+    pub failed_steps: Vec<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[serde_as]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone)]
+pub struct GithubRelease {
+    pub id: i64,
+    pub tag_name: String,
+    pub name: Option<String>,
+    /// Release notes in Markdown
+    pub body: Option<String>,
+    #[serde_as(as = "DisplayFromStr")]
+    pub url: Url,
+    pub is_draft: bool,
+    pub is_prerelease: bool,
+    pub created_at: DateTime<Utc>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub author: Option<GithubActor>,
+    pub repository: GithubRepositorySummary,
+    pub assets: Vec<GithubReleaseAsset>,
+}
+
+#[serde_as]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone)]
+pub struct GithubReleaseAsset {
+    pub name: String,
+    #[serde_as(as = "DisplayFromStr")]
+    pub url: Url,
+    pub content_type: String,
+    pub size: i64,
+    pub download_count: i64,
+}
+
+/// A Github issue assigned to the user or a pull request awaiting the user's review,
+/// synchronized as a task
+#[serde_as]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone)]
+pub struct GithubTaskItem {
+    pub id: String,
+    pub kind: GithubTaskItemKind,
+    pub number: i64,
+    #[serde_as(as = "DisplayFromStr")]
+    pub url: Url,
+    pub title: String,
+    pub body: String,
+    pub state: GithubTaskItemState,
+    pub repository: GithubRepositorySummary,
+    pub labels: Vec<GithubLabel>,
+    pub author: Option<GithubActor>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Copy, Clone, Eq)]
+pub enum GithubTaskItemKind {
+    AssignedIssue,
+    ReviewRequest,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Copy, Clone, Eq)]
+pub enum GithubTaskItemState {
+    Open,
+    /// The issue has been closed or the review has been submitted
+    Completed,
+}
+
+impl HasHtmlUrl for GithubTaskItem {
+    fn get_html_url(&self) -> Url {
+        self.url.clone()
+    }
+}
+
+impl ThirdPartyItemFromSource for GithubTaskItem {
+    fn into_third_party_item(
+        self,
+        user_id: UserId,
+        integration_connection_id: IntegrationConnectionId,
+    ) -> ThirdPartyItem {
+        ThirdPartyItem {
+            id: Uuid::new_v4().into(),
+            source_id: self.source_id(),
+            data: ThirdPartyItemData::GithubTaskItem(Box::new(self.clone())),
+            created_at: Utc::now().with_nanosecond(0).unwrap(),
+            updated_at: Utc::now().with_nanosecond(0).unwrap(),
+            user_id,
+            integration_connection_id,
+            source_item: None,
+        }
+    }
+
+    fn source_id(&self) -> String {
+        self.id.clone()
+    }
+}
+
+impl TryFrom<ThirdPartyItem> for GithubTaskItem {
+    type Error = anyhow::Error;
+
+    fn try_from(item: ThirdPartyItem) -> Result<Self, Self::Error> {
+        match item.data {
+            ThirdPartyItemData::GithubTaskItem(task_item) => Ok(*task_item),
+            _ => Err(anyhow!(
+                "Unable to convert ThirdPartyItem {} to GithubTaskItem",
+                item.id
+            )),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Eq)]
 pub enum GithubUrl {
     PullRequest {
@@ -883,6 +1117,16 @@ pub enum GithubUrl {
         repository: String,
         number: i64,
     },
+    Issue {
+        owner: String,
+        repository: String,
+        number: i64,
+    },
+    Release {
+        owner: String,
+        repository: String,
+        id: i64,
+    },
 }
 
 impl GithubUrl {
@@ -919,6 +1163,30 @@ impl GithubUrl {
             });
         }
 
+        if let &["", "repos", owner, repository, "issues", number] = splitted_url.as_slice() {
+            return Ok(GithubUrl::Issue {
+                owner: owner.to_string(),
+                repository: repository.to_string(),
+                number: number.parse()
+                    .with_context(|| {
+                        format!(
+                            "Failed to parse Github API resource URL: Issue number must be an integer: {}", number)
+                    })?
+            });
+        }
+
+        if let &["", "repos", owner, repository, "releases", id] = splitted_url.as_slice() {
+            return Ok(GithubUrl::Release {
+                owner: owner.to_string(),
+                repository: repository.to_string(),
+                id: id.parse()
+                    .with_context(|| {
+                        format!(
+                            "Failed to parse Github API resource URL: Release ID must be an integer: {}", id)
+                    })?
+            });
+        }
+
         Err(anyhow!(
             "Failed to parse Github API resource URL: unknown resource type: {}",
             resource_url
@@ -1055,6 +1323,22 @@ mod tests {
                 number: 123
             }
         )]
+        #[case::issue(
+            "https://api.github.com/repos/octokit/octokit.rb/issues/123",
+            GithubUrl::Issue {
+                owner: "octokit".to_string(),
+                repository: "octokit.rb".to_string(),
+                number: 123
+            }
+        )]
+        #[case::release(
+            "https://api.github.com/repos/octokit/octokit.rb/releases/123",
+            GithubUrl::Release {
+                owner: "octokit".to_string(),
+                repository: "octokit.rb".to_string(),
+                id: 123
+            }
+        )]
         fn test_try_from_api_url(
             #[case] resource_url: &str,
             #[case] expected_github_url: GithubUrl,
@@ -2,6 +2,7 @@ use std::fmt;
 
 use anyhow::anyhow;
 use chrono::{DateTime, NaiveDate, Timelike, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use serde_with::{DisplayFromStr, serde_as};
@@ -309,7 +310,7 @@ pub struct LinearLabel {
     pub color: String,
 }
 
-#[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone)]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone, JsonSchema)]
 pub struct LinearWorkflowState {
     pub name: String,
     pub description: Option<String>,
@@ -318,7 +319,7 @@ pub struct LinearWorkflowState {
     pub id: Option<Uuid>, // Optional to be retro-compatible
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy, Eq)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy, Eq, JsonSchema)]
 pub enum LinearWorkflowStateType {
     Triage,
     Backlog,
@@ -363,6 +364,23 @@ impl From<LinearWorkflowStateType> for TaskStatus {
     }
 }
 
+/// The workflow states and members of the team of a Linear issue, ie. the values
+/// the issue can be moved to or assigned to
+#[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone, JsonSchema)]
+pub struct LinearTeamDetails {
+    pub workflow_states: Vec<LinearWorkflowState>,
+    pub members: Vec<LinearTeamMember>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone, JsonSchema)]
+pub struct LinearTeamMember {
+    pub id: Uuid,
+    pub name: String,
+    pub avatar_url: Option<Url>,
+    /// Whether this member is the Linear user of the integration connection
+    pub is_viewer: bool,
+}
+
 #[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone)]
 pub struct LinearWorkflowStateIds {
     // Only gathering these ones for now as we won't use the others
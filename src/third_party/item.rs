@@ -17,7 +17,7 @@ use crate::{
     task::Task,
     third_party::integrations::{
         api::WebPage,
-        github::GithubNotification,
+        github::{GithubNotification, GithubTaskItem, GithubTaskItemState},
         google_calendar::GoogleCalendarEvent,
         google_drive::GoogleDriveComment,
         google_mail::GoogleMailThread,
@@ -34,7 +34,7 @@ use crate::{
 pub struct ThirdPartyItem {
     pub id: ThirdPartyItemId,
     pub source_id: String,
-    // The 11-variant provider-specific union (TodoistItem, LinearIssue,
+    // The 12-variant provider-specific union (TodoistItem, LinearIssue,
     // GithubNotification, GoogleMailThread, …) is intentionally opaque in the
     // MCP output schema — clients dispatch on `id` / `source_id` /
     // `source_item` and read provider-specific fields on demand. Deriving full
@@ -72,6 +72,7 @@ impl HasHtmlUrl for ThirdPartyItem {
             ThirdPartyItemData::LinearIssue(ref issue) => issue.get_html_url(),
             ThirdPartyItemData::LinearNotification(ref notification) => notification.get_html_url(),
             ThirdPartyItemData::GithubNotification(ref notification) => notification.get_html_url(),
+            ThirdPartyItemData::GithubTaskItem(ref task_item) => task_item.get_html_url(),
             ThirdPartyItemData::GoogleMailThread(ref thread) => thread.get_html_url(),
             ThirdPartyItemData::GoogleCalendarEvent(ref event) => event.get_html_url(),
             ThirdPartyItemData::GoogleDriveComment(ref comment) => comment.get_html_url(),
@@ -92,6 +93,7 @@ pub enum ThirdPartyItemData {
     LinearIssue(Box<LinearIssue>),
     LinearNotification(Box<LinearNotification>),
     GithubNotification(Box<GithubNotification>),
+    GithubTaskItem(Box<GithubTaskItem>),
     GoogleMailThread(Box<GoogleMailThread>),
     GoogleCalendarEvent(Box<GoogleCalendarEvent>),
     GoogleDriveComment(Box<GoogleDriveComment>),
@@ -108,6 +110,7 @@ impl ThirdPartyItemData {
             ThirdPartyItemData::LinearIssue(_) => ThirdPartyItemKind::LinearIssue,
             ThirdPartyItemData::LinearNotification(_) => ThirdPartyItemKind::LinearNotification,
             ThirdPartyItemData::GithubNotification(_) => ThirdPartyItemKind::GithubNotification,
+            ThirdPartyItemData::GithubTaskItem(_) => ThirdPartyItemKind::GithubTaskItem,
             ThirdPartyItemData::GoogleMailThread(_) => ThirdPartyItemKind::GoogleMailThread,
             ThirdPartyItemData::GoogleCalendarEvent(_) => ThirdPartyItemKind::GoogleCalendarEvent,
             ThirdPartyItemData::GoogleDriveComment(_) => ThirdPartyItemKind::GoogleDriveComment,
@@ -126,6 +129,7 @@ macro_attr! {
         LinearIssue,
         LinearNotification,
         GithubNotification,
+        GithubTaskItem,
         GoogleMailThread,
         GoogleCalendarEvent,
         GoogleDriveComment,
@@ -148,7 +152,9 @@ impl IntegrationProviderSource for ThirdPartyItem {
             ThirdPartyItemData::LinearIssue(_) | ThirdPartyItemData::LinearNotification(_) => {
                 IntegrationProviderKind::Linear
             }
-            ThirdPartyItemData::GithubNotification(_) => IntegrationProviderKind::Github,
+            ThirdPartyItemData::GithubNotification(_) | ThirdPartyItemData::GithubTaskItem(_) => {
+                IntegrationProviderKind::Github
+            }
             ThirdPartyItemData::GoogleMailThread(_) => IntegrationProviderKind::GoogleMail,
             ThirdPartyItemData::GoogleCalendarEvent(_) => IntegrationProviderKind::GoogleCalendar,
             ThirdPartyItemData::GoogleDriveComment(_) => IntegrationProviderKind::GoogleDrive,
@@ -171,6 +177,7 @@ impl ThirdPartyItemSource for ThirdPartyItem {
             ThirdPartyItemData::GithubNotification(_) => {
                 ThirdPartyItemSourceKind::GithubNotification
             }
+            ThirdPartyItemData::GithubTaskItem(_) => ThirdPartyItemSourceKind::GithubTaskItem,
             ThirdPartyItemData::GoogleMailThread(_) => ThirdPartyItemSourceKind::GoogleMailThread,
             ThirdPartyItemData::GoogleCalendarEvent(_) => {
                 ThirdPartyItemSourceKind::GoogleCalendarEvent
@@ -248,6 +255,13 @@ impl ThirdPartyItem {
                     ..*issue.clone()
                 }))
             }
+            ThirdPartyItemData::GithubTaskItem(ref task_item) => {
+                ThirdPartyItemData::GithubTaskItem(Box::new(GithubTaskItem {
+                    state: GithubTaskItemState::Completed,
+                    completed_at: Some(Utc::now()),
+                    ..*task_item.clone()
+                }))
+            }
             ThirdPartyItemData::LinearNotification(_)
             | ThirdPartyItemData::GithubNotification(_)
             | ThirdPartyItemData::GoogleMailThread(_)
@@ -297,6 +311,7 @@ macro_attr! {
         LinearIssue,
         LinearNotification,
         GithubNotification,
+        GithubTaskItem,
         GoogleMailThread,
         GoogleCalendarEvent,
         GoogleDriveComment,
@@ -336,7 +351,10 @@ mod tests {
         SlackApiTeamInfoResponse,
     };
 
-    use crate::test_helpers::load_json_fixture_file;
+    use crate::{
+        test_helpers::load_json_fixture_file,
+        third_party::integrations::github::{GithubRepositorySummary, GithubTaskItemKind},
+    };
 
     use super::*;
 
@@ -393,4 +411,45 @@ mod tests {
             Some(last_message.origin.ts.clone())
         );
     }
+
+    #[rstest]
+    fn test_mark_as_done_a_github_task_item() {
+        let github_task_item = GithubTaskItem {
+            id: "I_123".to_string(),
+            kind: GithubTaskItemKind::AssignedIssue,
+            number: 42,
+            url: "https://github.com/octo-org/octo-repo/issues/42"
+                .parse()
+                .unwrap(),
+            title: "Fix the bug".to_string(),
+            body: "".to_string(),
+            state: GithubTaskItemState::Open,
+            repository: GithubRepositorySummary {
+                name_with_owner: "octo-org/octo-repo".to_string(),
+                url: "https://github.com/octo-org/octo-repo".parse().unwrap(),
+            },
+            labels: vec![],
+            author: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            completed_at: None,
+        };
+        let third_party_item = ThirdPartyItem::new(
+            github_task_item.id.clone(),
+            ThirdPartyItemData::GithubTaskItem(Box::new(github_task_item)),
+            Uuid::new_v4().into(),
+            Uuid::new_v4().into(),
+        );
+
+        let ThirdPartyItemData::GithubTaskItem(ref github_task_item_marked_as_done) =
+            third_party_item.marked_as_done().data
+        else {
+            unreachable!("Expected GithubTaskItem data");
+        };
+        assert_eq!(
+            github_task_item_marked_as_done.state,
+            GithubTaskItemState::Completed
+        );
+        assert!(github_task_item_marked_as_done.completed_at.is_some());
+    }
 }
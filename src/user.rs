@@ -279,6 +279,7 @@ macro_attr! {
         OIDCGoogleAuthorizationCode,
         OIDCAuthorizationCodePKCE,
         Passkey,
+        SAML,
     }
 }
 
@@ -296,6 +297,48 @@ pub enum UserAuthMethodDisplayInfo {
     Passkey { username: String },
     OIDCGoogleAuthorizationCode,
     OIDCAuthorizationCodePKCE,
+    SAML,
+}
+
+/// Response of a local (password) login. When the user enabled a second
+/// factor, the login must be completed with `POST /users/me/second-factor`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum LoginResponse {
+    SecondFactorRequired { second_factor: SecondFactorKind },
+    LoggedIn(Box<User>),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum SecondFactorKind {
+    Totp,
+}
+
+/// A TOTP code from an authenticator app, or a one-time recovery code
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SecondFactorCode {
+    pub code: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct TotpStatus {
+    pub enabled: bool,
+    pub enabled_at: Option<DateTime<Utc>>,
+    pub remaining_recovery_codes: usize,
+}
+
+/// Pending TOTP enrollment, to be confirmed with a first code
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct TotpEnrollment {
+    /// Base32 encoded secret, for authenticator apps without QR code scanning
+    pub secret: String,
+    /// `otpauth://` URI to be displayed as a QR code
+    pub provisioning_uri: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct RecoveryCodes {
+    pub recovery_codes: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -127,6 +127,11 @@ pub fn Authenticated(
                                 *error.write() = Some(auth_error);
                             }
                         }
+                        FrontAuthenticationConfig::SAML => {
+                            if let Err(auth_error) = authenticate_saml_flow(&api_base_url).await {
+                                *error.write() = Some(auth_error);
+                            }
+                        }
                         _ => {}
                     }
                 }
@@ -229,6 +234,24 @@ pub async fn authenticate_authorization_code_flow(api_base_url: &Url) -> Result<
     redirect_to(&auth_url)
 }
 
+/// Redirect the user to the SAML identity provider with an authentication request
+/// built by the API. The identity provider posts its response back to the API.
+pub async fn authenticate_saml_flow(api_base_url: &Url) -> Result<()> {
+    debug!("auth: Authenticating with SAML");
+    let response: AuthorizeSessionResponse = call_api(
+        Method::GET,
+        api_base_url,
+        "auth/saml/authorize",
+        None::<i32>,
+        None,
+    )
+    .await?;
+    let auth_url = response.authorization_url.to_string();
+    UI_MODEL.write().authentication_state = AuthenticationState::RedirectingToAuthProvider;
+    debug!("auth: Redirecting to SAML identity provider: {auth_url}");
+    redirect_to(&auth_url)
+}
+
 // - verify if there is an existing access token in the local storage, and if so, check if it
 // - if there is no access token or if invalid, redirect to the auth provider
 // - if called on the auth callback URL with a `code` query parameter, fetch the access token and create
@@ -67,6 +67,17 @@ pub fn AuthMethodsCard() -> Element {
             })
         })
         .unwrap_or(false);
+    let has_saml = auth_methods.iter().any(|m| m.kind == UserAuthKind::SAML);
+    let is_saml_auth_enabled = APP_CONFIG
+        .read()
+        .as_ref()
+        .map(|config| {
+            config
+                .authentication_configs
+                .iter()
+                .any(|auth_config| matches!(auth_config, FrontAuthenticationConfig::SAML))
+        })
+        .unwrap_or(false);
 
     rsx! {
         section {
@@ -196,6 +207,31 @@ pub fn AuthMethodsCard() -> Element {
                         }
                     }
 
+                    if is_saml_auth_enabled && !has_saml {
+                        button {
+                            class: "group flex flex-1 min-w-[120px] flex-col items-center gap-2 \
+                                    px-3 py-3 bg-ui-surface border border-ui-border rounded-ui-md \
+                                    hover:border-ui-primary hover:bg-ui-surface-hover \
+                                    focus-visible:outline-2 focus-visible:outline-ui-primary focus-visible:outline-offset-2 \
+                                    transition-colors cursor-pointer",
+                            r#type: "button",
+                            onclick: move |_| {
+                                user_service.send(UserCommand::LinkSAMLAuth);
+                            },
+                            div {
+                                class: "flex items-center justify-center size-7 rounded-ui-sm bg-ui-surface-alt",
+                                span {
+                                    class: "icon-[lucide--building-2] size-4 \
+                                            text-ui-base-muted group-hover:text-ui-primary transition-colors",
+                                }
+                            }
+                            span {
+                                class: "text-[12.5px] font-medium text-ui-base-content",
+                                "Link single sign-on account"
+                            }
+                        }
+                    }
+
                     if !has_passkey {
                         if show_add_passkey() {
                             form {
@@ -310,6 +346,11 @@ fn AuthMethodRow(method: UserAuthMethod, can_remove: bool) -> Element {
             BadgeTone::Info,
             rsx! { span { class: "icon-[lucide--shield-check] size-3" } },
         ),
+        UserAuthMethodDisplayInfo::SAML => (
+            "Single sign-on".to_string(),
+            BadgeTone::Success,
+            rsx! { span { class: "icon-[lucide--building-2] size-3" } },
+        ),
     };
 
     rsx! {
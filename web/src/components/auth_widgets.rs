@@ -43,6 +43,20 @@ pub fn PasskeyBtn(children: Element, to: Option<Route>) -> Element {
     }
 }
 
+#[component]
+pub fn SsoBtn(children: Element, onclick: EventHandler<MouseEvent>) -> Element {
+    rsx! {
+        Button {
+            variant: ButtonVariant::Ghost,
+            size: ButtonSize::Md,
+            class: "btn-block".to_string(),
+            onclick: move |evt| onclick.call(evt),
+            icon_class: "icon-[lucide--building-2]".to_string(),
+            { children }
+        }
+    }
+}
+
 #[component]
 pub fn GoogleBtn(children: Element, onclick: EventHandler<MouseEvent>) -> Element {
     rsx! {
@@ -2,19 +2,27 @@
 
 use std::ops::Deref;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeDelta, Utc};
 use dioxus::prelude::*;
 
 use secrecy::ExposeSecret;
-use universal_inbox::auth::auth_token::AuthenticationTokenId;
+use strum::IntoEnumIterator;
+use universal_inbox::{
+    auth::{
+        auth_token::{ApiKeyCreation, ApiKeyPatch, ApiKeyProperties, AuthenticationTokenId},
+        oauth2::OAuth2Scope,
+    },
+    notification::NotificationSourceKind,
+};
 
 use crate::{
     components::{
         loading::Loading,
+        settings_controls::SettingRow,
         spinner::Spinner,
         ui::{
-            Button, ButtonVariant, Card, CardEmptyState, CardHeader, CardMeta, CardRight,
-            CardVariant,
+            Badge, BadgeTone, BadgeVariant, Button, ButtonVariant, Card, CardEmptyState,
+            CardHeader, CardMeta, CardRight, CardVariant, UIMultiSelect, UISelect, UISelectOption,
         },
     },
     model::LoadState,
@@ -24,9 +32,124 @@ use crate::{
     utils::copy_to_clipboard,
 };
 
+const INPUT_CLASSES: &str = "w-full px-2.5 py-1.5 text-[var(--ui-text-base)] font-ui bg-ui-base-200 text-ui-base-content border border-ui-border rounded-ui-sm outline-none transition-[border-color,box-shadow] duration-150 ease-[var(--ui-ease)] focus:border-ui-primary focus:shadow-[var(--ui-focus-ring)] focus:outline-none";
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum ApiKeyExpiration {
+    ThirtyDays,
+    NinetyDays,
+    SixMonths,
+    OneYear,
+    Never,
+}
+
+impl ApiKeyExpiration {
+    fn expire_at(self) -> Option<DateTime<Utc>> {
+        let days = match self {
+            ApiKeyExpiration::ThirtyDays => 30,
+            ApiKeyExpiration::NinetyDays => 90,
+            ApiKeyExpiration::SixMonths => 30 * 6,
+            ApiKeyExpiration::OneYear => 365,
+            ApiKeyExpiration::Never => return None,
+        };
+        TimeDelta::try_days(days).map(|delta| Utc::now() + delta)
+    }
+
+    fn options() -> Vec<UISelectOption<ApiKeyExpiration>> {
+        vec![
+            UISelectOption::new(ApiKeyExpiration::ThirtyDays, "30 days"),
+            UISelectOption::new(ApiKeyExpiration::NinetyDays, "90 days"),
+            UISelectOption::new(ApiKeyExpiration::SixMonths, "6 months"),
+            UISelectOption::new(ApiKeyExpiration::OneYear, "1 year"),
+            UISelectOption::new(ApiKeyExpiration::Never, "Never"),
+        ]
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum ApiKeyAccess {
+    Full,
+    ReadOnly,
+    Custom,
+}
+
+impl ApiKeyAccess {
+    fn of(properties: &ApiKeyProperties) -> Self {
+        match &properties.scopes {
+            None => ApiKeyAccess::Full,
+            Some(scopes) if *scopes == read_only_scopes() => ApiKeyAccess::ReadOnly,
+            Some(_) => ApiKeyAccess::Custom,
+        }
+    }
+
+    fn options() -> Vec<UISelectOption<ApiKeyAccess>> {
+        vec![
+            UISelectOption::new(ApiKeyAccess::Full, "Full access"),
+            UISelectOption::new(ApiKeyAccess::ReadOnly, "Read-only"),
+            UISelectOption::new(ApiKeyAccess::Custom, "Custom scopes"),
+        ]
+    }
+}
+
+fn read_only_scopes() -> Vec<OAuth2Scope> {
+    OAuth2Scope::all()
+        .into_iter()
+        .filter(OAuth2Scope::is_read_only)
+        .collect()
+}
+
+fn scope_options() -> Vec<UISelectOption<OAuth2Scope>> {
+    OAuth2Scope::all()
+        .into_iter()
+        .map(|scope| UISelectOption::new(scope, scope.to_string()).with_meta(scope.description()))
+        .collect()
+}
+
+fn notification_source_kind_options() -> Vec<UISelectOption<NotificationSourceKind>> {
+    NotificationSourceKind::iter()
+        .map(|kind| UISelectOption::new(kind, kind.to_string()))
+        .collect()
+}
+
+/// Values of the API key form, shared by creation and edition. `expiration`
+/// is only used on creation as the expiration date cannot be changed.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ApiKeyFormValues {
+    name: Option<String>,
+    expiration: ApiKeyExpiration,
+    scopes: Option<Vec<OAuth2Scope>>,
+    notification_source_kinds: Option<Vec<NotificationSourceKind>>,
+    rate_limit_per_minute: Option<u32>,
+}
+
+impl From<ApiKeyFormValues> for ApiKeyCreation {
+    fn from(values: ApiKeyFormValues) -> Self {
+        ApiKeyCreation {
+            name: values.name,
+            expire_at: values.expiration.expire_at(),
+            scopes: values.scopes,
+            notification_source_kinds: values.notification_source_kinds,
+            rate_limit_per_minute: values.rate_limit_per_minute,
+        }
+    }
+}
+
+impl From<ApiKeyFormValues> for ApiKeyPatch {
+    fn from(values: ApiKeyFormValues) -> Self {
+        ApiKeyPatch {
+            name: Some(values.name),
+            scopes: Some(values.scopes),
+            notification_source_kinds: Some(values.notification_source_kinds),
+            rate_limit_per_minute: Some(values.rate_limit_per_minute),
+            is_revoked: None,
+        }
+    }
+}
+
 #[component]
 pub fn AuthenticationTokensCard() -> Element {
     let authentication_token_service = use_coroutine_handle::<AuthenticationTokenCommand>();
+    let mut is_creating = use_signal(|| false);
 
     let _resource = use_resource(move || {
         to_owned![authentication_token_service];
@@ -68,9 +191,8 @@ pub fn AuthenticationTokensCard() -> Element {
                                 Button {
                                     variant: ButtonVariant::Primary,
                                     icon_class: "icon-[lucide--key]".to_string(),
-                                    onclick: move |_| {
-                                        authentication_token_service.send(AuthenticationTokenCommand::CreateAuthenticationToken);
-                                    },
+                                    disabled: is_creating(),
+                                    onclick: move |_| is_creating.set(true),
                                     "Create new API key"
                                 }
                             }
@@ -78,6 +200,19 @@ pub fn AuthenticationTokensCard() -> Element {
                     }
                 }
 
+                if is_creating() {
+                    ApiKeyForm {
+                        properties: None,
+                        on_submit: move |values: ApiKeyFormValues| {
+                            authentication_token_service.send(
+                                AuthenticationTokenCommand::CreateAuthenticationToken(values.into()),
+                            );
+                            is_creating.set(false);
+                        },
+                        on_cancel: move |_| is_creating.set(false),
+                    }
+                }
+
                 if authentication_tokens.is_empty() && !matches!(CREATED_AUTHENTICATION_TOKEN.read().deref(), LoadState::Loaded(_) | LoadState::Error(_)) {
                     CardEmptyState {
                         icon_class: "icon-[lucide--key-round]".to_string(),
@@ -89,7 +224,10 @@ pub fn AuthenticationTokensCard() -> Element {
                         class: "api-keys-table max-md:block max-md:overflow-x-auto",
                         thead {
                             tr {
-                                th { style: "width: 25%;", "Expiration date" }
+                                th { style: "width: 20%;", "Name" }
+                                th { style: "width: 20%;", "Access" }
+                                th { style: "width: 15%;", "Last used" }
+                                th { style: "width: 12%;", "Expiration date" }
                                 th { "Key" }
                                 th { style: "width: 20%;", aria_label: "Actions", "" }
                             }
@@ -100,21 +238,26 @@ pub fn AuthenticationTokensCard() -> Element {
                                     AuthenticationToken {
                                         id: created_authentication_token.id.clone(),
                                         expire_at: created_authentication_token.expire_at,
+                                        is_revoked: false,
+                                        properties: created_authentication_token.api_key_properties.clone(),
                                         jwt_token: created_authentication_token.jwt_token.expose_secret().to_string(),
                                         is_copiable: true
                                     }
                                 },
                                 LoadState::Error(error) => rsx! {
                                     tr {
-                                        td { colspan: "3", "Failed to create a new API key: {error}" }
+                                        td { colspan: "6", "Failed to create a new API key: {error}" }
                                     }
                                 },
                                 _ => rsx! {}
                             }
                             for auth_token in authentication_tokens.into_iter() {
                                 AuthenticationToken {
+                                    key: "{auth_token.id}",
                                     id: auth_token.id,
                                     expire_at: auth_token.expire_at,
+                                    is_revoked: auth_token.is_revoked,
+                                    properties: auth_token.api_key_properties,
                                     jwt_token: format!("**********{}", auth_token.truncated_jwt_token.clone()),
                                     is_copiable: false
                                 }
@@ -131,17 +274,86 @@ pub fn AuthenticationTokensCard() -> Element {
 pub fn AuthenticationToken(
     id: AuthenticationTokenId,
     #[props(!optional)] expire_at: Option<DateTime<Utc>>,
+    is_revoked: bool,
+    properties: ApiKeyProperties,
     jwt_token: String,
     is_copiable: bool,
 ) -> Element {
+    let authentication_token_service = use_coroutine_handle::<AuthenticationTokenCommand>();
     let mut is_copied = use_signal(|| false);
+    let mut is_editing = use_signal(|| false);
     let row_class = if is_copiable { "token-new" } else { "" };
+    let name = properties
+        .name
+        .clone()
+        .unwrap_or_else(|| "Unnamed key".to_string());
+    let access = ApiKeyAccess::of(&properties);
+    let scopes_title = properties
+        .scopes
+        .as_ref()
+        .map(OAuth2Scope::to_scope_string)
+        .unwrap_or_default();
+    let sources = properties.notification_source_kinds.as_ref().map(|kinds| {
+        kinds
+            .iter()
+            .map(|kind| kind.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    });
+    let last_used_at = properties
+        .last_used_at
+        .map(|last_used_at| last_used_at.format("%Y-%m-%d %H:%M").to_string());
+    let edit_id = id.clone();
+    let revoke_id = id.clone();
 
     rsx! {
         tr {
             class: "{row_class}",
 
-            if let Some(expire_at) = expire_at {
+            td {
+                span { class: "block truncate", "{name}" }
+            }
+
+            td {
+                div {
+                    class: "flex flex-col gap-0.5",
+                    span {
+                        title: "{scopes_title}",
+                        match access {
+                            ApiKeyAccess::Full => rsx! {
+                                Badge { variant: BadgeVariant::Method, tone: BadgeTone::Primary, "Full access" }
+                            },
+                            ApiKeyAccess::ReadOnly => rsx! {
+                                Badge { variant: BadgeVariant::Method, tone: BadgeTone::Info, "Read-only" }
+                            },
+                            ApiKeyAccess::Custom => rsx! {
+                                Badge { variant: BadgeVariant::Method, tone: BadgeTone::Warning, "Custom scopes" }
+                            },
+                        }
+                    }
+                    if let Some(sources) = sources {
+                        span { class: "text-[11px] text-ui-base-muted", "Sources: {sources}" }
+                    }
+                    if let Some(rate_limit_per_minute) = properties.rate_limit_per_minute {
+                        span { class: "text-[11px] text-ui-base-muted", "{rate_limit_per_minute} requests/min" }
+                    }
+                }
+            }
+
+            if let Some(last_used_at) = last_used_at {
+                td {
+                    span { class: "block", "{last_used_at}" }
+                    if let Some(last_used_ip) = &properties.last_used_ip {
+                        span { class: "block font-mono text-[11px] text-ui-base-muted", "{last_used_ip}" }
+                    }
+                }
+            } else {
+                td { "Never used" }
+            }
+
+            if is_revoked {
+                td { "Revoked" }
+            } else if let Some(expire_at) = expire_at {
                 td { r#"{expire_at.date_naive().format("%Y-%m-%d")}"# }
             } else {
                 td { "Never expire" }
@@ -154,34 +366,236 @@ pub fn AuthenticationToken(
             td {
                 div {
                     class: "flex items-center justify-end gap-1",
-                    if !is_copiable {
-                        Button {
-                            variant: ButtonVariant::Danger,
-                            disabled: true,
-                            icon_class: "icon-[lucide--trash-2]".to_string(),
-                            "Revoke"
+                    if is_copiable {
+                        if is_copied() {
+                            span {
+                                class: "inline-flex items-center gap-1 px-2 py-0.5 rounded-ui-pill text-xs font-medium border border-ui-border text-ui-base-muted",
+                                "Copied!"
+                            }
+                        } else {
+                            Button {
+                                variant: ButtonVariant::Primary,
+                                icon_class: "icon-[lucide--copy]".to_string(),
+                                onclick: move |_| {
+                                    let jwt_token = jwt_token.clone();
+                                    async move {
+                                        copy_to_clipboard(&jwt_token).await.unwrap();
+                                        *is_copied.write() = true;
+                                    }
+                                },
+                                "Copy"
+                            }
                         }
-                    } else if is_copied() {
-                        span {
-                            class: "inline-flex items-center gap-1 px-2 py-0.5 rounded-ui-pill text-xs font-medium border border-ui-border text-ui-base-muted",
-                            "Copied!"
+                    } else if !is_revoked {
+                        Button {
+                            variant: ButtonVariant::Ghost,
+                            icon_class: "icon-[lucide--pencil]".to_string(),
+                            disabled: is_editing(),
+                            onclick: move |_| is_editing.set(true),
+                            "Edit"
                         }
-                    } else {
                         Button {
-                            variant: ButtonVariant::Primary,
-                            icon_class: "icon-[lucide--copy]".to_string(),
+                            variant: ButtonVariant::Danger,
+                            icon_class: "icon-[lucide--trash-2]".to_string(),
                             onclick: move |_| {
-                                let jwt_token = jwt_token.clone();
-                                async move {
-                                    copy_to_clipboard(&jwt_token).await.unwrap();
-                                    *is_copied.write() = true;
-                                }
+                                authentication_token_service.send(
+                                    AuthenticationTokenCommand::RevokeAuthenticationToken(revoke_id.clone()),
+                                );
                             },
-                            "Copy"
+                            "Revoke"
                         }
                     }
                 }
             }
         }
+
+        if is_editing() {
+            tr {
+                td {
+                    colspan: "6",
+                    ApiKeyForm {
+                        properties: Some(properties.clone()),
+                        on_submit: move |values: ApiKeyFormValues| {
+                            authentication_token_service.send(
+                                AuthenticationTokenCommand::UpdateAuthenticationToken(
+                                    edit_id.clone(),
+                                    values.into(),
+                                ),
+                            );
+                            is_editing.set(false);
+                        },
+                        on_cancel: move |_| is_editing.set(false),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Form to create an API key (`properties` is `None`) or to edit the
+/// properties of an existing one.
+#[component]
+fn ApiKeyForm(
+    #[props(!optional)] properties: Option<ApiKeyProperties>,
+    on_submit: EventHandler<ApiKeyFormValues>,
+    on_cancel: EventHandler<()>,
+) -> Element {
+    let is_creation = properties.is_none();
+    let properties = properties.unwrap_or_default();
+    let mut name = use_signal(|| properties.name.clone().unwrap_or_default());
+    let mut expiration = use_signal(|| Some(ApiKeyExpiration::SixMonths));
+    let mut access = use_signal(|| Some(ApiKeyAccess::of(&properties)));
+    let mut custom_scopes = use_signal(|| properties.scopes.clone().unwrap_or_default());
+    let mut notification_source_kinds = use_signal(|| {
+        properties
+            .notification_source_kinds
+            .clone()
+            .unwrap_or_default()
+    });
+    let mut rate_limit_per_minute = use_signal(|| {
+        properties
+            .rate_limit_per_minute
+            .map(|rate_limit| rate_limit.to_string())
+            .unwrap_or_default()
+    });
+    let mut error = use_signal(|| None::<String>);
+
+    let submit = move |_| {
+        let scopes = match access().unwrap_or(ApiKeyAccess::Full) {
+            ApiKeyAccess::Full => None,
+            ApiKeyAccess::ReadOnly => Some(read_only_scopes()),
+            ApiKeyAccess::Custom if custom_scopes().is_empty() => {
+                error.set(Some("Select at least one scope".to_string()));
+                return;
+            }
+            ApiKeyAccess::Custom => Some(custom_scopes()),
+        };
+        let rate_limit_per_minute = match rate_limit_per_minute().trim() {
+            "" => None,
+            value => match value.parse::<u32>() {
+                Ok(rate_limit) if rate_limit > 0 => Some(rate_limit),
+                _ => {
+                    error.set(Some(
+                        "The rate limit must be a positive number of requests".to_string(),
+                    ));
+                    return;
+                }
+            },
+        };
+        let name = name().trim().to_string();
+        let notification_source_kinds = notification_source_kinds();
+
+        error.set(None);
+        on_submit.call(ApiKeyFormValues {
+            name: (!name.is_empty()).then_some(name),
+            expiration: expiration().unwrap_or(ApiKeyExpiration::SixMonths),
+            scopes,
+            notification_source_kinds: (!notification_source_kinds.is_empty())
+                .then_some(notification_source_kinds),
+            rate_limit_per_minute,
+        });
+    };
+
+    rsx! {
+        div {
+            class: "flex flex-col gap-2 p-3 my-2 bg-ui-base-200 border border-ui-border rounded-ui-sm",
+
+            SettingRow {
+                label: rsx! { "Name" },
+                input {
+                    class: "{INPUT_CLASSES}",
+                    style: "width: 260px;",
+                    name: "api-key-name",
+                    r#type: "text",
+                    placeholder: "e.g. CI script",
+                    value: "{name}",
+                    oninput: move |evt| name.set(evt.value()),
+                }
+            }
+
+            if is_creation {
+                SettingRow {
+                    label: rsx! { "Expiration" },
+                    UISelect::<ApiKeyExpiration> {
+                        value: expiration,
+                        options: ApiKeyExpiration::options(),
+                        on_change: move |value| expiration.set(value),
+                        width: "260px".to_string(),
+                        name: "api-key-expiration-input".to_string(),
+                    }
+                }
+            }
+
+            SettingRow {
+                label: rsx! { "Access" },
+                UISelect::<ApiKeyAccess> {
+                    value: access,
+                    options: ApiKeyAccess::options(),
+                    on_change: move |value| access.set(value),
+                    width: "260px".to_string(),
+                    name: "api-key-access-input".to_string(),
+                }
+            }
+
+            if access() == Some(ApiKeyAccess::Custom) {
+                SettingRow {
+                    label: rsx! { "Scopes" },
+                    UIMultiSelect::<OAuth2Scope> {
+                        value: custom_scopes,
+                        options: scope_options(),
+                        on_change: move |value| custom_scopes.set(value),
+                        placeholder: "Pick scopes…".to_string(),
+                        width: "260px".to_string(),
+                        name: "api-key-scopes-input".to_string(),
+                    }
+                }
+            }
+
+            SettingRow {
+                label: rsx! { "Notification sources" },
+                description: "Leave empty to allow all sources".to_string(),
+                UIMultiSelect::<NotificationSourceKind> {
+                    value: notification_source_kinds,
+                    options: notification_source_kind_options(),
+                    on_change: move |value| notification_source_kinds.set(value),
+                    placeholder: "All sources".to_string(),
+                    width: "260px".to_string(),
+                    name: "api-key-sources-input".to_string(),
+                }
+            }
+
+            SettingRow {
+                label: rsx! { "Rate limit (requests per minute)" },
+                description: "Leave empty for no limit".to_string(),
+                input {
+                    class: "{INPUT_CLASSES}",
+                    style: "width: 260px;",
+                    name: "api-key-rate-limit",
+                    r#type: "number",
+                    min: "1",
+                    value: "{rate_limit_per_minute}",
+                    oninput: move |evt| rate_limit_per_minute.set(evt.value()),
+                }
+            }
+
+            if let Some(error) = error() {
+                p { class: "text-xs text-ui-error", "{error}" }
+            }
+
+            div {
+                class: "flex gap-2 justify-end",
+                Button {
+                    variant: ButtonVariant::Ghost,
+                    onclick: move |_| on_cancel.call(()),
+                    "Cancel"
+                }
+                Button {
+                    variant: ButtonVariant::Primary,
+                    icon_class: "icon-[lucide--check]".to_string(),
+                    onclick: submit,
+                    if is_creation { "Create API key" } else { "Save" }
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,131 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use log::error;
+use reqwest::Method;
+
+use universal_inbox::integration_connection::{
+    IntegrationConnectionId, sync_run::IntegrationConnectionSyncRun,
+};
+
+use crate::{
+    components::{
+        loading::Loading,
+        ui::{Badge, BadgeTone, BadgeVariant, Button, ButtonVariant},
+    },
+    config::get_api_base_url,
+    services::api::call_api,
+    utils::format_absolute_time,
+};
+
+/// Recent synchronizations of an integration connection, fetched only once
+/// the user asks for them
+#[component]
+pub fn IntegrationSyncRuns(connection_id: IntegrationConnectionId) -> Element {
+    let mut is_shown = use_signal(|| false);
+
+    let sync_runs = use_resource(move || async move {
+        if !is_shown() {
+            return None;
+        }
+        let api_base_url = get_api_base_url().ok()?;
+        let result: Result<Vec<IntegrationConnectionSyncRun>, _> = call_api(
+            Method::GET,
+            &api_base_url,
+            &format!("integration-connections/{connection_id}/sync-runs"),
+            None::<()>,
+            None,
+        )
+        .await;
+        match result {
+            Ok(sync_runs) => Some(sync_runs),
+            Err(err) => {
+                error!(
+                    "Failed to fetch sync runs of integration connection {connection_id}: {err}"
+                );
+                None
+            }
+        }
+    });
+
+    rsx! {
+        div {
+            class: "flex flex-col gap-2 mt-1",
+
+            div {
+                Button {
+                    variant: ButtonVariant::Ghost,
+                    icon_class: "icon-[lucide--history]".to_string(),
+                    onclick: move |_| is_shown.toggle(),
+                    if is_shown() { "Hide sync history" } else { "Show sync history" }
+                }
+            }
+
+            if is_shown() {
+                match sync_runs() {
+                    None => rsx! { Loading { label: "Loading sync history..." } },
+                    Some(None) => rsx! {
+                        p { class: "text-ui-error text-[length:var(--ui-text-sm)]", "Failed to load the sync history" }
+                    },
+                    Some(Some(sync_runs)) if sync_runs.is_empty() => rsx! {
+                        p { class: "text-ui-base-muted text-[length:var(--ui-text-sm)]", "No sync recorded yet" }
+                    },
+                    Some(Some(sync_runs)) => rsx! {
+                        table {
+                            class: "api-keys-table max-md:block max-md:overflow-x-auto",
+                            thead {
+                                tr {
+                                    th { style: "width: 150px;", "Started" }
+                                    th { style: "width: 90px;", "Kind" }
+                                    th { style: "width: 90px;", "Duration" }
+                                    th { class: "max-md:hidden", "Fetched / created / updated / stale" }
+                                    th { class: "max-md:hidden", style: "width: 110px;", "API calls" }
+                                    th { style: "width: 90px;", "Result" }
+                                }
+                            }
+                            tbody {
+                                for sync_run in sync_runs.into_iter() {
+                                    SyncRunRow { key: "{sync_run.id}", sync_run }
+                                }
+                            }
+                        }
+                    },
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn SyncRunRow(sync_run: IntegrationConnectionSyncRun) -> Element {
+    let duration_in_seconds =
+        (sync_run.ended_at - sync_run.started_at).num_milliseconds() as f64 / 1000.0;
+    let api_calls = match sync_run.rate_limit_remaining {
+        Some(remaining) => format!("{} ({remaining} left)", sync_run.upstream_http_calls),
+        None => sync_run.upstream_http_calls.to_string(),
+    };
+    let error_chain = sync_run.error_chain.join("\ncaused by: ");
+
+    rsx! {
+        tr {
+            td { "{format_absolute_time(sync_run.started_at)}" }
+            td { "{sync_run.kind}" }
+            td { "{duration_in_seconds:.1}s" }
+            td {
+                class: "max-md:hidden",
+                "{sync_run.items_fetched} / {sync_run.items_created} / {sync_run.items_updated} / {sync_run.items_marked_stale}"
+            }
+            td { class: "max-md:hidden", "{api_calls}" }
+            td {
+                if sync_run.is_successful() {
+                    Badge { variant: BadgeVariant::Method, tone: BadgeTone::Success, "Success" }
+                } else {
+                    span {
+                        title: "{error_chain}",
+                        Badge { variant: BadgeVariant::Method, tone: BadgeTone::Error, "Failed" }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -3,7 +3,8 @@
 use dioxus::prelude::*;
 
 use universal_inbox::third_party::integrations::github::{
-    GithubDiscussion, GithubDiscussionStateReason, GithubPullRequest, GithubPullRequestState,
+    GithubDiscussion, GithubDiscussionStateReason, GithubIssue, GithubIssueState,
+    GithubIssueStateReason, GithubPullRequest, GithubPullRequestState,
 };
 
 use crate::theme::{
@@ -174,3 +175,37 @@ pub fn GithubDiscussionIcon(
 
     rsx! { GithubDiscussionOpened { class: "{class} {opened_icon_style}" } }
 }
+
+#[component]
+pub fn GithubIssueIcon(
+    github_issue: Option<GithubIssue>,
+    class: Option<String>,
+    should_style_icon: Option<bool>,
+) -> Element {
+    let (completed_icon_style, not_planned_icon_style, opened_icon_style) =
+        if should_style_icon.unwrap_or(true) {
+            (
+                COMPLETED_TEXT_COLOR_CLASS,
+                CANCELED_TEXT_COLOR_CLASS,
+                STARTED_TEXT_COLOR_CLASS,
+            )
+        } else {
+            ("", "", "")
+        };
+    let class = class.unwrap_or_default();
+    let Some(github_issue) = github_issue else {
+        return rsx! { span { class: "icon-[lucide--circle-dot] {class}" } };
+    };
+
+    match (github_issue.state, github_issue.state_reason) {
+        (GithubIssueState::Closed, Some(GithubIssueStateReason::NotPlanned)) => {
+            rsx! { span { class: "icon-[lucide--circle-slash] {class} {not_planned_icon_style}" } }
+        }
+        (GithubIssueState::Closed, _) => {
+            rsx! { span { class: "icon-[lucide--circle-check] {class} {completed_icon_style}" } }
+        }
+        (GithubIssueState::Open, _) => {
+            rsx! { span { class: "icon-[lucide--circle-dot] {class} {opened_icon_style}" } }
+        }
+    }
+}
@@ -13,6 +13,7 @@ pub mod config;
 pub mod icons;
 pub mod notification_list_item;
 pub mod preview;
+pub mod task_list_item;
 
 #[component]
 pub fn GithubActorDisplay(actor: ReadSignal<GithubActor>, display_name: Option<bool>) -> Element {
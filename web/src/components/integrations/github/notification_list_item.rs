@@ -5,13 +5,14 @@ use dioxus::prelude::*;
 use universal_inbox::{
     notification::{NotificationStatus, NotificationWithTask},
     third_party::integrations::github::{
-        GithubDiscussion, GithubNotification, GithubNotificationItem, GithubPullRequest,
+        GithubCheckConclusionState, GithubDiscussion, GithubIssue, GithubNotification,
+        GithubNotificationItem, GithubPullRequest, GithubRelease, GithubWorkflowRun,
     },
 };
 
 use crate::{
     components::{
-        integrations::github::icons::{Github, GithubPullRequestIcon},
+        integrations::github::icons::{Github, GithubIssueIcon, GithubPullRequestIcon},
         list::ListItem,
     },
     utils::format_elapsed_time,
@@ -49,6 +50,42 @@ pub fn GithubNotificationListItem(
                 on_select,
             }
         },
+        GithubNotification {
+            item: Some(GithubNotificationItem::GithubIssue(github_issue)),
+            ..
+        } => rsx! {
+            GithubIssueNotificationListItem {
+                notification,
+                github_notification,
+                github_issue,
+                is_selected,
+                on_select,
+            }
+        },
+        GithubNotification {
+            item: Some(GithubNotificationItem::GithubWorkflowRun(github_workflow_run)),
+            ..
+        } => rsx! {
+            GithubWorkflowRunNotificationListItem {
+                notification,
+                github_notification,
+                github_workflow_run,
+                is_selected,
+                on_select,
+            }
+        },
+        GithubNotification {
+            item: Some(GithubNotificationItem::GithubRelease(github_release)),
+            ..
+        } => rsx! {
+            GithubReleaseNotificationListItem {
+                notification,
+                github_notification,
+                github_release,
+                is_selected,
+                on_select,
+            }
+        },
         _ => rsx! {
             DefaultGithubNotificationListItem {
                 notification,
@@ -166,6 +203,134 @@ pub fn GithubDiscussionNotificationListItem(
     }
 }
 
+#[component]
+pub fn GithubIssueNotificationListItem(
+    notification: ReadSignal<NotificationWithTask>,
+    github_notification: ReadSignal<GithubNotification>,
+    github_issue: ReadSignal<GithubIssue>,
+    is_selected: ReadSignal<bool>,
+    on_select: EventHandler<()>,
+) -> Element {
+    let notification_updated_at = use_memo(move || format_elapsed_time(notification().updated_at));
+    let is_unread = notification().status == NotificationStatus::Unread;
+
+    rsx! {
+        ListItem {
+            key: "{notification().id}",
+            linked_task: notification().task,
+            title: "{notification().title}",
+            subtitle: rsx! { GithubNotificationSubtitle { github_notification } },
+            time: "{notification_updated_at}",
+            icon: rsx! {
+                div {
+                    class: "w-full h-full flex items-center justify-center rounded-[inherit] bg-[var(--ui-surface)] border border-[var(--ui-border)]",
+                    Github { class: "h-4 w-4" }
+                }
+            },
+            meta_icon: rsx! {
+                GithubIssueIcon {
+                    class: "w-full h-full",
+                    github_issue: github_issue(),
+                }
+            },
+            is_selected,
+            is_unread,
+            provider: Some("github"),
+            data_kind: Some("issue"),
+            on_select,
+        }
+    }
+}
+
+#[component]
+pub fn GithubWorkflowRunNotificationListItem(
+    notification: ReadSignal<NotificationWithTask>,
+    github_notification: ReadSignal<GithubNotification>,
+    github_workflow_run: ReadSignal<GithubWorkflowRun>,
+    is_selected: ReadSignal<bool>,
+    on_select: EventHandler<()>,
+) -> Element {
+    let notification_updated_at = use_memo(move || format_elapsed_time(notification().updated_at));
+    let is_unread = notification().status == NotificationStatus::Unread;
+    let meta_icon_class = match github_workflow_run().conclusion {
+        Some(GithubCheckConclusionState::Success) => "icon-[lucide--circle-check] text-success",
+        Some(GithubCheckConclusionState::Cancelled) | Some(GithubCheckConclusionState::Skipped) => {
+            "icon-[lucide--circle-slash]"
+        }
+        Some(_) => "icon-[lucide--circle-x] text-error",
+        None => "icon-[lucide--circle-play]",
+    };
+
+    rsx! {
+        ListItem {
+            key: "{notification().id}",
+            linked_task: notification().task,
+            title: "{notification().title}",
+            subtitle: rsx! {
+                span {
+                    class: "ui-nrow-meta-text",
+                    "{github_notification().repository.full_name}"
+                    if let Some(head_branch) = github_workflow_run().head_branch {
+                        " · {head_branch}"
+                    }
+                }
+            },
+            time: "{notification_updated_at}",
+            icon: rsx! {
+                div {
+                    class: "w-full h-full flex items-center justify-center rounded-[inherit] bg-[var(--ui-surface)] border border-[var(--ui-border)]",
+                    Github { class: "h-4 w-4" }
+                }
+            },
+            meta_icon: rsx! { span { class: "{meta_icon_class} w-full h-full" } },
+            is_selected,
+            is_unread,
+            provider: Some("github"),
+            data_kind: Some("workflow_run"),
+            on_select,
+        }
+    }
+}
+
+#[component]
+pub fn GithubReleaseNotificationListItem(
+    notification: ReadSignal<NotificationWithTask>,
+    github_notification: ReadSignal<GithubNotification>,
+    github_release: ReadSignal<GithubRelease>,
+    is_selected: ReadSignal<bool>,
+    on_select: EventHandler<()>,
+) -> Element {
+    let notification_updated_at = use_memo(move || format_elapsed_time(notification().updated_at));
+    let is_unread = notification().status == NotificationStatus::Unread;
+
+    rsx! {
+        ListItem {
+            key: "{notification().id}",
+            linked_task: notification().task,
+            title: "{notification().title}",
+            subtitle: rsx! {
+                span {
+                    class: "ui-nrow-meta-text",
+                    "{github_notification().repository.full_name} · {github_release().tag_name}"
+                }
+            },
+            time: "{notification_updated_at}",
+            icon: rsx! {
+                div {
+                    class: "w-full h-full flex items-center justify-center rounded-[inherit] bg-[var(--ui-surface)] border border-[var(--ui-border)]",
+                    Github { class: "h-4 w-4" }
+                }
+            },
+            meta_icon: rsx! { span { class: "icon-[lucide--tag] w-full h-full" } },
+            is_selected,
+            is_unread,
+            provider: Some("github"),
+            data_kind: Some("release"),
+            on_select,
+        }
+    }
+}
+
 #[component]
 fn GithubNotificationSubtitle(github_notification: ReadSignal<GithubNotification>) -> Element {
     rsx! {
@@ -0,0 +1,212 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+
+use universal_inbox::third_party::integrations::github::{
+    GithubIssue, GithubIssueState, GithubIssueStateReason, GithubLinkedPullRequest,
+    GithubPullRequestState,
+};
+
+use crate::{
+    components::{
+        TagList, UserWithAvatar,
+        integrations::github::{
+            GithubActorDisplay, get_github_actor_name_and_url, icons::GithubIssueIcon,
+            preview::pull_request::GithubCommentList,
+        },
+        preview_card_header::PreviewCardHeader,
+        ui::{Card, CardVariant, MetadataGrid, MetadataItem, Tag as UiTag, TagVariant},
+    },
+    utils::format_elapsed_time,
+};
+
+#[component]
+pub fn GithubIssuePreview(
+    github_issue: ReadSignal<GithubIssue>,
+    title: ReadSignal<String>,
+    expand_details: ReadSignal<bool>,
+) -> Element {
+    let issue = github_issue();
+    let (state_variant, state_label) = match (issue.state, issue.state_reason) {
+        (GithubIssueState::Closed, Some(GithubIssueStateReason::NotPlanned)) => {
+            (TagVariant::Muted, "Closed as not planned")
+        }
+        (GithubIssueState::Closed, _) => (TagVariant::Success, "Closed"),
+        (GithubIssueState::Open, _) => (TagVariant::Open, "Open"),
+    };
+
+    let created_ago = format_elapsed_time(issue.created_at);
+    let identifier = format!("#{}", issue.number);
+    let repo_name = issue.repository.name_with_owner.clone();
+    let repo_url = issue.repository.url.clone();
+    let issue_url = issue.url.clone();
+    let author = issue.author.clone();
+
+    rsx! {
+        div {
+            class: "flex flex-col w-full h-full",
+
+            PreviewCardHeader {
+                brand_icon: rsx! {
+                    GithubIssueIcon { class: "size-4", github_issue: issue.clone() }
+                },
+                title: title(),
+                identifier: Some(identifier),
+                subline: rsx! {
+                    if let Some(actor) = author {
+                        span { "Opened by" }
+                        {
+                            let (name, url) = get_github_actor_name_and_url(actor);
+                            rsx! {
+                                UserWithAvatar {
+                                    user_name: name,
+                                    avatar_url: Some(Some(url)),
+                                    display_name: true,
+                                    class: "text-[11px]",
+                                }
+                            }
+                        }
+                        span { class: "sep", "·" }
+                        span { "{created_ago} ago" }
+                    }
+                }
+            }
+
+            div {
+                id: "notification-preview-details",
+                class: "flex flex-col gap-2 w-full h-full overflow-y-auto scroll-y-auto p-3",
+
+                Card {
+                    variant: CardVariant::Default,
+
+                    MetadataGrid {
+                        MetadataItem {
+                            label: "Repository".to_string(),
+                            value: rsx! {
+                                a {
+                                    href: "{repo_url}",
+                                    target: "_blank",
+                                    rel: "noopener noreferrer",
+                                    "{repo_name}"
+                                }
+                                a {
+                                    href: "{issue_url}",
+                                    target: "_blank",
+                                    rel: "noopener noreferrer",
+                                    "#{issue.number}"
+                                }
+                            },
+                        }
+
+                        MetadataItem {
+                            label: "State".to_string(),
+                            value: rsx! {
+                                UiTag { variant: state_variant, "{state_label}" }
+                            },
+                        }
+
+                        if !issue.assignees.is_empty() {
+                            MetadataItem {
+                                label: "Assigned to".to_string(),
+                                value: rsx! {
+                                    for assignee in issue.assignees.clone() {
+                                        GithubActorDisplay { actor: assignee, display_name: true }
+                                    }
+                                },
+                            }
+                        }
+
+                        if let Some(milestone) = &issue.milestone {
+                            MetadataItem {
+                                label: "Milestone".to_string(),
+                                value: rsx! {
+                                    a {
+                                        href: "{milestone.url}",
+                                        target: "_blank",
+                                        rel: "noopener noreferrer",
+                                        "{milestone.title}"
+                                    }
+                                    if let Some(due_on) = milestone.due_on {
+                                        span { class: "text-xs text-base-content/50", r#"due {due_on.date_naive().format("%Y-%m-%d")}"# }
+                                    }
+                                },
+                            }
+                        }
+
+                        MetadataItem {
+                            label: "Updated".to_string(),
+                            value: rsx! {
+                                span { "{format_elapsed_time(issue.updated_at)} ago" }
+                            },
+                        }
+                    }
+
+                    if !issue.labels.is_empty() {
+                        TagList {
+                            tags: issue
+                                .labels
+                                .iter()
+                                .map(|label| label.clone().into())
+                                .collect()
+                        }
+                    }
+                }
+
+                if !issue.linked_pull_requests.is_empty() {
+                    Card {
+                        variant: CardVariant::Default,
+                        div {
+                            class: "flex flex-col gap-1.5",
+                            span { class: "text-xs font-semibold", "Linked pull requests" }
+                            for linked_pull_request in issue.linked_pull_requests.clone() {
+                                GithubLinkedPullRequestRow { linked_pull_request }
+                            }
+                        }
+                    }
+                }
+
+                if !issue.body.is_empty() {
+                    Card {
+                        variant: CardVariant::Default,
+                        div {
+                            class: "w-full max-w-full prose prose-sm dark:prose-invert",
+                            dangerous_inner_html: "{issue.body}"
+                        }
+                    }
+                }
+
+                GithubCommentList { comments: issue.comments.clone() }
+
+                if expand_details() { div { class: "hidden" } }
+            }
+        }
+    }
+}
+
+#[component]
+fn GithubLinkedPullRequestRow(linked_pull_request: ReadSignal<GithubLinkedPullRequest>) -> Element {
+    let pr = linked_pull_request();
+    let (state_variant, state_label) = match pr.state {
+        GithubPullRequestState::Open => (TagVariant::Open, "Open"),
+        GithubPullRequestState::Merged => (TagVariant::Success, "Merged"),
+        GithubPullRequestState::Closed => (TagVariant::Muted, "Closed"),
+    };
+
+    rsx! {
+        div {
+            class: "flex items-center gap-2 text-xs",
+            span { class: "icon-[lucide--git-pull-request] size-4 shrink-0" }
+            a {
+                class: "truncate",
+                href: "{pr.url}",
+                target: "_blank",
+                rel: "noopener noreferrer",
+                "{pr.repository.name_with_owner}#{pr.number} {pr.title}"
+            }
+            UiTag { variant: state_variant, "{state_label}" }
+            if pr.will_close_issue {
+                span { class: "text-base-content/50 shrink-0", "closes this issue" }
+            }
+        }
+    }
+}
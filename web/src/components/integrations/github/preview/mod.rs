@@ -13,7 +13,11 @@ use crate::components::{
 };
 
 pub mod discussion;
+pub mod issue;
 pub mod pull_request;
+pub mod release;
+pub mod task_item;
+pub mod workflow_run;
 
 #[component]
 pub fn GithubNotificationDefaultPreview(
@@ -5,13 +5,16 @@ use std::{collections::HashMap, default::Default};
 use dioxus::prelude::*;
 use itertools::Itertools;
 
-use universal_inbox::third_party::integrations::github::{
-    GithubActor, GithubBotSummary, GithubCheckConclusionState, GithubCheckRun,
-    GithubCheckStatusState, GithubCheckSuite, GithubCheckSuiteApp, GithubCommitChecks,
-    GithubIssueComment, GithubLabel, GithubMannequinSummary, GithubMergeableState,
-    GithubPullRequest, GithubPullRequestReview, GithubPullRequestReviewState,
-    GithubPullRequestState, GithubRepositorySummary, GithubReviewer, GithubTeamSummary,
-    GithubUserSummary, GithubWorkflow,
+use universal_inbox::{
+    notification::{NotificationId, service::GithubPullRequestAction},
+    third_party::integrations::github::{
+        GithubActor, GithubBotSummary, GithubCheckConclusionState, GithubCheckRun,
+        GithubCheckStatusState, GithubCheckSuite, GithubCheckSuiteApp, GithubCommitChecks,
+        GithubIssueComment, GithubLabel, GithubMannequinSummary, GithubMergeMethod,
+        GithubMergeStateStatus, GithubMergeableState, GithubPullRequest, GithubPullRequestReview,
+        GithubPullRequestReviewState, GithubPullRequestState, GithubRepositorySummary,
+        GithubReviewer, GithubTeamSummary, GithubUserSummary, GithubWorkflow,
+    },
 };
 
 use crate::{
@@ -24,10 +27,12 @@ use crate::{
         thread::{Thread, ThreadItem},
         threaded_message::ThreadedMessage,
         ui::{
-            Card, CardVariant, MetadataGrid, MetadataItem, STATUS_ROW_ACTION_CLASS,
-            STATUS_ROW_NAME_CLASS, StatusDot, StatusRow, StatusSection, StatusVariant,
+            Button, ButtonSize, ButtonVariant, Card, CardVariant, MetadataGrid, MetadataItem,
+            STATUS_ROW_ACTION_CLASS, STATUS_ROW_NAME_CLASS, StatusDot, StatusRow, StatusSection,
+            StatusVariant,
         },
     },
+    services::notification_service::NotificationCommand,
     utils::format_elapsed_time,
 };
 
@@ -36,6 +41,7 @@ pub fn GithubPullRequestPreview(
     github_pull_request: ReadSignal<GithubPullRequest>,
     title: ReadSignal<String>,
     expand_details: ReadSignal<bool>,
+    notification_id: ReadSignal<NotificationId>,
 ) -> Element {
     let pr = github_pull_request();
     let identifier = format!("#{}", pr.number);
@@ -72,7 +78,7 @@ pub fn GithubPullRequestPreview(
                 }
             }
 
-            GithubPullRequestDetails { github_pull_request, expand_details }
+            GithubPullRequestDetails { github_pull_request, expand_details, notification_id }
         }
     }
 }
@@ -90,6 +96,7 @@ impl From<GithubLabel> for Tag {
 fn GithubPullRequestDetails(
     github_pull_request: ReadSignal<GithubPullRequest>,
     expand_details: ReadSignal<bool>,
+    notification_id: ReadSignal<NotificationId>,
 ) -> Element {
     let show_base_and_head_repositories = match (
         &github_pull_request().head_repository,
@@ -277,11 +284,135 @@ fn GithubPullRequestDetails(
                 }
             }
 
+            if github_pull_request().state == GithubPullRequestState::Open {
+                GithubPullRequestActions { github_pull_request, notification_id }
+            }
+
             GithubCommentList { comments: github_pull_request().comments }
         }
     }
 }
 
+#[component]
+fn GithubPullRequestActions(
+    github_pull_request: ReadSignal<GithubPullRequest>,
+    notification_id: ReadSignal<NotificationId>,
+) -> Element {
+    let notification_service = use_coroutine_handle::<NotificationCommand>();
+    let mut body = use_signal(String::new);
+    let mut merge_method = use_signal(|| GithubMergeMethod::Merge);
+    let has_body = !body().trim().is_empty();
+    let can_merge = !github_pull_request().is_draft
+        && matches!(
+            github_pull_request().merge_state_status,
+            GithubMergeStateStatus::Clean
+                | GithubMergeStateStatus::HasHook
+                | GithubMergeStateStatus::Unstable
+        );
+    let merge_title = if can_merge {
+        "Merge the pull request"
+    } else {
+        "The pull request cannot be merged yet"
+    };
+    let mut send_action = move |action: GithubPullRequestAction| {
+        notification_service.send(NotificationCommand::ActOnGithubPullRequest(
+            notification_id(),
+            action,
+        ));
+        body.set(String::new());
+    };
+    let optional_body = move || Some(body().trim().to_string()).filter(|body| !body.is_empty());
+
+    rsx! {
+        Card {
+            variant: CardVariant::Default,
+
+            div {
+                class: "flex flex-col gap-2",
+                span { class: "text-xs font-semibold", "Review" }
+                textarea {
+                    class: "w-full px-2.5 py-1.5 text-[var(--ui-text-base)] font-ui bg-ui-base-200 text-ui-base-content border border-ui-border rounded-ui-sm outline-none transition-[border-color,box-shadow] duration-150 ease-[var(--ui-ease)] focus:border-ui-primary focus:shadow-[var(--ui-focus-ring)] focus:outline-none",
+                    rows: 3,
+                    placeholder: "Leave a comment",
+                    value: "{body}",
+                    oninput: move |evt| body.set(evt.value()),
+                }
+                div {
+                    class: "flex flex-wrap items-center gap-2",
+                    Button {
+                        variant: ButtonVariant::Primary,
+                        size: ButtonSize::Xs,
+                        icon_class: "icon-[lucide--check] size-3.5".to_string(),
+                        onclick: move |_| send_action(GithubPullRequestAction::Approve { body: optional_body() }),
+                        "Approve"
+                    }
+                    Button {
+                        variant: ButtonVariant::Warning,
+                        size: ButtonSize::Xs,
+                        icon_class: "icon-[lucide--file-diff] size-3.5".to_string(),
+                        disabled: !has_body,
+                        title: "A comment is required to request changes".to_string(),
+                        onclick: move |_| {
+                            if let Some(body) = optional_body() {
+                                send_action(GithubPullRequestAction::RequestChanges { body });
+                            }
+                        },
+                        "Request changes"
+                    }
+                    Button {
+                        variant: ButtonVariant::Ghost,
+                        size: ButtonSize::Xs,
+                        icon_class: "icon-[lucide--message-square] size-3.5".to_string(),
+                        disabled: !has_body,
+                        onclick: move |_| {
+                            if let Some(body) = optional_body() {
+                                send_action(GithubPullRequestAction::Comment { body });
+                            }
+                        },
+                        "Comment"
+                    }
+                }
+            }
+
+            div {
+                class: "flex flex-col gap-2 border-t border-ui-border-light mt-2.5 pt-2.5",
+                span { class: "text-xs font-semibold", "Merge" }
+                div {
+                    class: "flex flex-wrap items-center gap-2",
+                    div {
+                        class: "join",
+                        for (method, label) in [
+                            (GithubMergeMethod::Merge, "Merge commit"),
+                            (GithubMergeMethod::Squash, "Squash"),
+                            (GithubMergeMethod::Rebase, "Rebase"),
+                        ] {
+                            button {
+                                class: if merge_method() == method { "btn btn-xs join-item btn-active" } else { "btn btn-xs join-item" },
+                                r#type: "button",
+                                onclick: move |_| merge_method.set(method),
+                                "{label}"
+                            }
+                        }
+                    }
+                    Button {
+                        variant: ButtonVariant::Primary,
+                        size: ButtonSize::Xs,
+                        icon_class: "icon-[lucide--git-merge] size-3.5".to_string(),
+                        disabled: !can_merge,
+                        title: merge_title.to_string(),
+                        onclick: move |_| {
+                            if can_merge {
+                                send_action(GithubPullRequestAction::Merge { merge_method: merge_method() });
+                            }
+                        },
+                        "Merge"
+                    }
+                }
+            }
+        }
+    }
+}
+
 #[component]
 fn ChecksSection(
     latest_commit: ReadSignal<GithubCommitChecks>,
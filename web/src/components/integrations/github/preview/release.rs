@@ -0,0 +1,174 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+
+use universal_inbox::third_party::integrations::github::{GithubRelease, GithubReleaseAsset};
+
+use crate::{
+    components::{
+        UserWithAvatar,
+        integrations::github::{get_github_actor_name_and_url, icons::Github},
+        markdown::Markdown,
+        preview_card_header::PreviewCardHeader,
+        ui::{Card, CardVariant, MetadataGrid, MetadataItem, Tag as UiTag, TagVariant},
+    },
+    utils::format_elapsed_time,
+};
+
+#[component]
+pub fn GithubReleasePreview(
+    github_release: ReadSignal<GithubRelease>,
+    title: ReadSignal<String>,
+    expand_details: ReadSignal<bool>,
+) -> Element {
+    let release = github_release();
+    let (state_variant, state_label) = if release.is_draft {
+        (TagVariant::Muted, "Draft")
+    } else if release.is_prerelease {
+        (TagVariant::Warning, "Pre-release")
+    } else {
+        (TagVariant::Success, "Latest")
+    };
+    let published_ago = format_elapsed_time(release.published_at.unwrap_or(release.created_at));
+    let repo_name = release.repository.name_with_owner.clone();
+    let repo_url = release.repository.url.clone();
+    let release_url = release.url.clone();
+    let author = release.author.clone();
+
+    rsx! {
+        div {
+            class: "flex flex-col w-full h-full",
+
+            PreviewCardHeader {
+                brand_icon: rsx! { Github { class: "size-4" } },
+                title: title(),
+                identifier: Some(release.tag_name.clone()),
+                subline: rsx! {
+                    if let Some(actor) = author {
+                        span { "Published by" }
+                        {
+                            let (name, url) = get_github_actor_name_and_url(actor);
+                            rsx! {
+                                UserWithAvatar {
+                                    user_name: name,
+                                    avatar_url: Some(Some(url)),
+                                    display_name: true,
+                                    class: "text-[11px]",
+                                }
+                            }
+                        }
+                        span { class: "sep", "·" }
+                    }
+                    span { "{published_ago} ago" }
+                }
+            }
+
+            div {
+                id: "notification-preview-details",
+                class: "flex flex-col gap-2 w-full h-full overflow-y-auto scroll-y-auto p-3",
+
+                Card {
+                    variant: CardVariant::Default,
+
+                    MetadataGrid {
+                        MetadataItem {
+                            label: "Repository".to_string(),
+                            value: rsx! {
+                                a {
+                                    href: "{repo_url}",
+                                    target: "_blank",
+                                    rel: "noopener noreferrer",
+                                    "{repo_name}"
+                                }
+                            },
+                        }
+
+                        MetadataItem {
+                            label: "Tag".to_string(),
+                            value: rsx! {
+                                a {
+                                    class: "font-mono text-xs",
+                                    href: "{release_url}",
+                                    target: "_blank",
+                                    rel: "noopener noreferrer",
+                                    "{release.tag_name}"
+                                }
+                                UiTag { variant: state_variant, "{state_label}" }
+                            },
+                        }
+
+                        if let Some(published_at) = release.published_at {
+                            MetadataItem {
+                                label: "Published".to_string(),
+                                value: rsx! {
+                                    span { r#"{published_at.date_naive().format("%Y-%m-%d")}"# }
+                                },
+                            }
+                        }
+                    }
+                }
+
+                if let Some(body) = &release.body {
+                    Card {
+                        variant: CardVariant::Default,
+                        Markdown {
+                            class: "prose prose-sm dark:prose-invert w-full max-w-full",
+                            text: body.clone()
+                        }
+                    }
+                }
+
+                if !release.assets.is_empty() {
+                    Card {
+                        variant: CardVariant::Default,
+                        div {
+                            class: "flex flex-col gap-1.5",
+                            span { class: "text-xs font-semibold", "Assets" }
+                            for asset in release.assets.clone() {
+                                GithubReleaseAssetRow { asset }
+                            }
+                        }
+                    }
+                }
+
+                if expand_details() { div { class: "hidden" } }
+            }
+        }
+    }
+}
+
+#[component]
+fn GithubReleaseAssetRow(asset: ReadSignal<GithubReleaseAsset>) -> Element {
+    let asset = asset();
+
+    rsx! {
+        div {
+            class: "flex items-center gap-2 text-xs",
+            span { class: "icon-[lucide--package] size-4 shrink-0" }
+            a {
+                class: "truncate",
+                href: "{asset.url}",
+                target: "_blank",
+                rel: "noopener noreferrer",
+                "{asset.name}"
+            }
+            span { class: "text-base-content/50 shrink-0", "{format_size(asset.size)}" }
+            span { class: "text-base-content/50 shrink-0", "{asset.download_count} downloads" }
+        }
+    }
+}
+
+fn format_size(size: i64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = size as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{size} {}", UNITS[unit])
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
@@ -0,0 +1,139 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+
+use universal_inbox::{
+    task::Task,
+    third_party::integrations::github::{GithubTaskItem, GithubTaskItemKind, GithubTaskItemState},
+};
+
+use crate::{
+    components::{
+        TagList, UserWithAvatar,
+        integrations::github::get_github_actor_name_and_url,
+        markdown::Markdown,
+        preview_card_header::PreviewCardHeader,
+        ui::{Card, CardVariant, MetadataGrid, MetadataItem, Tag as UiTag, TagVariant},
+    },
+    utils::format_elapsed_time,
+};
+
+#[component]
+pub fn GithubTaskItemPreview(
+    github_task_item: ReadSignal<GithubTaskItem>,
+    task: ReadSignal<Task>,
+) -> Element {
+    let item = github_task_item();
+    let (kind_label, meta_icon_class) = match item.kind {
+        GithubTaskItemKind::AssignedIssue => ("Assigned issue", "icon-[lucide--circle-dot] size-4"),
+        GithubTaskItemKind::ReviewRequest => {
+            ("Review request", "icon-[lucide--git-pull-request] size-4")
+        }
+    };
+    let (state_variant, state_label) = match (item.state, item.kind) {
+        (GithubTaskItemState::Open, _) => (TagVariant::Info, "Open"),
+        (GithubTaskItemState::Completed, GithubTaskItemKind::AssignedIssue) => {
+            (TagVariant::Success, "Closed")
+        }
+        (GithubTaskItemState::Completed, GithubTaskItemKind::ReviewRequest) => {
+            (TagVariant::Success, "Reviewed")
+        }
+    };
+    let created_ago = format_elapsed_time(item.created_at);
+    let identifier = format!("#{}", item.number);
+
+    rsx! {
+        div {
+            class: "flex flex-col w-full h-full",
+
+            PreviewCardHeader {
+                brand_icon: rsx! { span { class: "{meta_icon_class}" } },
+                title: item.title.clone(),
+                identifier: Some(identifier.clone()),
+                subline: rsx! {
+                    span { "{kind_label}" }
+                    if let Some(actor) = item.author.clone() {
+                        span { class: "sep", "·" }
+                        span { "Opened by" }
+                        {
+                            let (name, url) = get_github_actor_name_and_url(actor);
+                            rsx! {
+                                UserWithAvatar {
+                                    user_name: name,
+                                    avatar_url: Some(Some(url)),
+                                    display_name: true,
+                                    class: "text-[11px]",
+                                }
+                            }
+                        }
+                        span { class: "sep", "·" }
+                        span { "{created_ago} ago" }
+                    }
+                }
+            }
+
+            div {
+                id: "task-preview-details",
+                class: "flex flex-col gap-2 w-full h-full overflow-y-auto scroll-y-auto p-3",
+
+                Card {
+                    variant: CardVariant::Default,
+
+                    MetadataGrid {
+                        MetadataItem {
+                            label: "Repository".to_string(),
+                            value: rsx! {
+                                a {
+                                    href: "{item.repository.url}",
+                                    target: "_blank",
+                                    rel: "noopener noreferrer",
+                                    "{item.repository.name_with_owner}"
+                                }
+                                a {
+                                    href: "{item.url}",
+                                    target: "_blank",
+                                    rel: "noopener noreferrer",
+                                    "{identifier}"
+                                }
+                            },
+                        }
+
+                        MetadataItem {
+                            label: "State".to_string(),
+                            value: rsx! {
+                                UiTag { variant: state_variant, "{state_label}" }
+                            },
+                        }
+
+                        MetadataItem {
+                            label: "Updated".to_string(),
+                            value: rsx! {
+                                span { "{format_elapsed_time(task().updated_at)} ago" }
+                            },
+                        }
+                    }
+
+                    if !item.labels.is_empty() {
+                        TagList {
+                            tags: item
+                                .labels
+                                .iter()
+                                .map(|label| label.clone().into())
+                                .collect()
+                        }
+                    }
+                }
+
+                if !item.body.is_empty() {
+                    Card {
+                        variant: CardVariant::Default,
+                        Markdown {
+                            class: "prose prose-sm dark:prose-invert w-full max-w-full",
+                            text: item.body.clone()
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,197 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+
+use universal_inbox::third_party::integrations::github::{
+    GithubCheckConclusionState, GithubCheckStatusState, GithubWorkflowJob, GithubWorkflowRun,
+};
+
+use crate::{
+    components::{
+        integrations::github::icons::Github,
+        preview_card_header::PreviewCardHeader,
+        ui::{Card, CardVariant, MetadataGrid, MetadataItem, Tag as UiTag, TagVariant},
+    },
+    utils::format_elapsed_time,
+};
+
+#[component]
+pub fn GithubWorkflowRunPreview(
+    github_workflow_run: ReadSignal<GithubWorkflowRun>,
+    title: ReadSignal<String>,
+    expand_details: ReadSignal<bool>,
+) -> Element {
+    let run = github_workflow_run();
+    let (state_variant, state_label) = workflow_run_state(&run.status, run.conclusion.as_ref());
+    let identifier = format!("#{}", run.run_number);
+    let repo_name = run.repository.name_with_owner.clone();
+    let repo_url = run.repository.url.clone();
+    let run_url = run.url.clone();
+    let started_ago = format_elapsed_time(run.created_at);
+
+    rsx! {
+        div {
+            class: "flex flex-col w-full h-full",
+
+            PreviewCardHeader {
+                brand_icon: rsx! { Github { class: "size-4" } },
+                title: title(),
+                identifier: Some(identifier),
+                subline: rsx! {
+                    span { "{run.name}" }
+                    span { class: "sep", "·" }
+                    span { "Triggered by {run.event}" }
+                    span { class: "sep", "·" }
+                    span { "{started_ago} ago" }
+                }
+            }
+
+            div {
+                id: "notification-preview-details",
+                class: "flex flex-col gap-2 w-full h-full overflow-y-auto scroll-y-auto p-3",
+
+                Card {
+                    variant: CardVariant::Default,
+
+                    MetadataGrid {
+                        MetadataItem {
+                            label: "Repository".to_string(),
+                            value: rsx! {
+                                a {
+                                    href: "{repo_url}",
+                                    target: "_blank",
+                                    rel: "noopener noreferrer",
+                                    "{repo_name}"
+                                }
+                            },
+                        }
+
+                        MetadataItem {
+                            label: "Run".to_string(),
+                            value: rsx! {
+                                a {
+                                    href: "{run_url}",
+                                    target: "_blank",
+                                    rel: "noopener noreferrer",
+                                    "{run.display_title}"
+                                }
+                                UiTag { variant: state_variant, "{state_label}" }
+                            },
+                        }
+
+                        if let Some(head_branch) = &run.head_branch {
+                            MetadataItem {
+                                label: "Branch".to_string(),
+                                value: rsx! {
+                                    span { class: "font-mono text-xs", "{head_branch}" }
+                                },
+                            }
+                        }
+
+                        if let Some(head_commit) = &run.head_commit {
+                            MetadataItem {
+                                label: "Commit".to_string(),
+                                value: rsx! {
+                                    a {
+                                        class: "font-mono text-xs",
+                                        href: "{head_commit.url}",
+                                        target: "_blank",
+                                        rel: "noopener noreferrer",
+                                        "{head_commit.sha.chars().take(7).collect::<String>()}"
+                                    }
+                                    span {
+                                        class: "truncate",
+                                        "{head_commit.message.lines().next().unwrap_or_default()}"
+                                    }
+                                    if let Some(author_name) = &head_commit.author_name {
+                                        span { class: "text-xs text-base-content/50", "by {author_name}" }
+                                    }
+                                },
+                            }
+                        }
+
+                        MetadataItem {
+                            label: "Updated".to_string(),
+                            value: rsx! {
+                                span { "{format_elapsed_time(run.updated_at)} ago" }
+                            },
+                        }
+                    }
+                }
+
+                if !run.failed_jobs.is_empty() {
+                    Card {
+                        variant: CardVariant::Default,
+                        div {
+                            class: "flex flex-col gap-1.5",
+                            span { class: "text-xs font-semibold", "Failed jobs" }
+                            for failed_job in run.failed_jobs.clone() {
+                                GithubWorkflowJobRow { workflow_job: failed_job }
+                            }
+                        }
+                    }
+                }
+
+                if expand_details() { div { class: "hidden" } }
+            }
+        }
+    }
+}
+
+#[component]
+fn GithubWorkflowJobRow(workflow_job: ReadSignal<GithubWorkflowJob>) -> Element {
+    let job = workflow_job();
+
+    rsx! {
+        div {
+            class: "flex flex-col gap-0.5 text-xs",
+            div {
+                class: "flex items-center gap-2",
+                span { class: "icon-[lucide--x-circle] size-4 text-error flex-none" }
+                a {
+                    class: "truncate",
+                    href: "{job.url}",
+                    target: "_blank",
+                    rel: "noopener noreferrer",
+                    "{job.name}"
+                }
+                if let (Some(started_at), Some(completed_at)) = (job.started_at, job.completed_at) {
+                    span {
+                        class: "text-base-content/50 shrink-0",
+                        "{(completed_at - started_at).num_seconds()}s"
+                    }
+                }
+            }
+            for failed_step in job.failed_steps.iter() {
+                span { class: "pl-6 text-base-content/70", "{failed_step}" }
+            }
+        }
+    }
+}
+
+fn workflow_run_state(
+    status: &GithubCheckStatusState,
+    conclusion: Option<&GithubCheckConclusionState>,
+) -> (TagVariant, &'static str) {
+    match (status, conclusion) {
+        (GithubCheckStatusState::Completed, Some(GithubCheckConclusionState::Success)) => {
+            (TagVariant::Success, "Succeeded")
+        }
+        (GithubCheckStatusState::Completed, Some(GithubCheckConclusionState::Cancelled)) => {
+            (TagVariant::Muted, "Cancelled")
+        }
+        (GithubCheckStatusState::Completed, Some(GithubCheckConclusionState::Skipped)) => {
+            (TagVariant::Muted, "Skipped")
+        }
+        (GithubCheckStatusState::Completed, Some(GithubCheckConclusionState::TimedOut)) => {
+            (TagVariant::Error, "Timed out")
+        }
+        (GithubCheckStatusState::Completed, Some(GithubCheckConclusionState::Neutral)) => {
+            (TagVariant::Info, "Neutral")
+        }
+        (GithubCheckStatusState::Completed, Some(_)) => (TagVariant::Error, "Failed"),
+        (GithubCheckStatusState::Completed, None) => (TagVariant::Muted, "Completed"),
+        (GithubCheckStatusState::InProgress, _) => (TagVariant::Warning, "In progress"),
+        (_, _) => (TagVariant::Muted, "Queued"),
+    }
+}
@@ -0,0 +1,47 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+
+use universal_inbox::{
+    task::Task,
+    third_party::integrations::github::{GithubTaskItem, GithubTaskItemKind},
+};
+
+use crate::{
+    components::{integrations::github::icons::Github, list::ListItem},
+    utils::format_elapsed_time,
+};
+
+#[component]
+pub fn GithubTaskListItem(
+    task: ReadSignal<Task>,
+    github_task_item: ReadSignal<GithubTaskItem>,
+    is_selected: ReadSignal<bool>,
+    on_select: EventHandler<()>,
+) -> Element {
+    let task_updated_at = use_memo(move || format_elapsed_time(task().updated_at));
+    let meta_icon_class = match github_task_item().kind {
+        GithubTaskItemKind::AssignedIssue => "icon-[lucide--circle-dot] w-full h-full",
+        GithubTaskItemKind::ReviewRequest => "icon-[lucide--git-pull-request] w-full h-full",
+    };
+
+    rsx! {
+        ListItem {
+            key: "{task().id}",
+            title: "{github_task_item().title}",
+            subtitle: rsx! {
+                span {
+                    class: "ui-nrow-meta-text flex items-center gap-1 min-w-0",
+                    span { "{github_task_item().repository.name_with_owner} #{github_task_item().number}" }
+                }
+            },
+            time: "{task_updated_at}",
+            icon: rsx! {
+                Github { class: "h-5 w-5" }
+            },
+            meta_icon: rsx! { span { class: "{meta_icon_class}" } },
+            is_selected,
+            on_select,
+        }
+    }
+}
@@ -94,6 +94,7 @@ pub fn TaskIcon(class: String, kind: TaskSourceKind) -> Element {
         TaskSourceKind::Todoist => rsx! { Todoist { class } },
         TaskSourceKind::TickTick => rsx! { TickTick { class } },
         TaskSourceKind::Linear => rsx! { Linear { class } },
+        TaskSourceKind::Github => rsx! { Github { class } },
         TaskSourceKind::Slack => rsx! { span { class: "icon-[logos--slack-icon]" } },
     }
 }
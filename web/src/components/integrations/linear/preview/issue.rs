@@ -1,9 +1,16 @@
 #![allow(non_snake_case)]
 
+use chrono::NaiveDate;
 use dioxus::prelude::*;
+use log::error;
+use reqwest::Method;
+use uuid::Uuid;
 
-use universal_inbox::third_party::integrations::linear::{
-    LinearComment, LinearIssue, LinearIssuePriority, LinearNotification,
+use universal_inbox::{
+    notification::{NotificationId, service::LinearIssueAction},
+    third_party::integrations::linear::{
+        LinearComment, LinearIssue, LinearIssuePriority, LinearNotification, LinearTeamDetails,
+    },
 };
 
 use crate::{
@@ -15,8 +22,13 @@ use crate::{
         priority_field::{PriorityField, PriorityLevel},
         thread::{Thread, ThreadChildren, ThreadItem},
         threaded_message::ThreadedMessage,
-        ui::{Card, CardVariant, MetadataGrid, MetadataItem},
+        ui::{
+            Button, ButtonSize, ButtonVariant, Card, CardVariant, MetadataGrid, MetadataItem,
+            UISelect, UISelectOption,
+        },
     },
+    config::get_api_base_url,
+    services::{api::call_api, notification_service::NotificationCommand},
     utils::format_elapsed_time,
 };
 
@@ -36,6 +48,8 @@ pub fn LinearIssuePreview(
     linear_issue: ReadSignal<LinearIssue>,
     linear_notification: ReadSignal<Option<LinearNotification>>,
     expand_details: ReadSignal<bool>,
+    /// The notification the issue comes from, required to act on it
+    notification_id: ReadSignal<Option<NotificationId>>,
 ) -> Element {
     let _ = expand_details;
     let issue = linear_issue();
@@ -68,7 +82,7 @@ pub fn LinearIssuePreview(
                 }
             }
 
-            LinearIssueDetails { linear_issue, linear_notification }
+            LinearIssueDetails { linear_issue, linear_notification, notification_id }
         }
     }
 }
@@ -77,6 +91,7 @@ pub fn LinearIssuePreview(
 fn LinearIssueDetails(
     linear_issue: ReadSignal<LinearIssue>,
     linear_notification: ReadSignal<Option<LinearNotification>>,
+    notification_id: ReadSignal<Option<NotificationId>>,
 ) -> Element {
     // status_color is `LinearWorkflowState.color` — already a hex string; strip any leading
     // '#' so we can inject it deterministically.
@@ -174,6 +189,10 @@ fn LinearIssueDetails(
                 }
             }
 
+            if let Some(notification_id) = notification_id() {
+                LinearIssueActions { linear_issue, notification_id }
+            }
+
             if let Some(description) = linear_issue().description {
                 Card {
                     variant: CardVariant::Default,
@@ -197,6 +216,223 @@ fn LinearIssueDetails(
     }
 }
 
+const LINEAR_ISSUE_PRIORITIES: [LinearIssuePriority; 5] = [
+    LinearIssuePriority::NoPriority,
+    LinearIssuePriority::Urgent,
+    LinearIssuePriority::High,
+    LinearIssuePriority::Normal,
+    LinearIssuePriority::Low,
+];
+
+#[component]
+fn LinearIssueActions(
+    linear_issue: ReadSignal<LinearIssue>,
+    notification_id: ReadSignal<NotificationId>,
+) -> Element {
+    let notification_service = use_coroutine_handle::<NotificationCommand>();
+    let mut comment = use_signal(String::new);
+    let mut selected_state_id: Signal<Option<Uuid>> = use_signal(|| None);
+    let mut selected_assignee_id: Signal<Option<Uuid>> = use_signal(|| None);
+    let mut selected_priority: Signal<Option<LinearIssuePriority>> = use_signal(|| None);
+
+    let team_details = use_resource(move || async move {
+        let api_base_url = get_api_base_url().ok()?;
+        let result: Result<LinearTeamDetails, _> = call_api(
+            Method::GET,
+            &api_base_url,
+            &format!("notifications/{}/linear/team", notification_id()),
+            None::<()>,
+            None,
+        )
+        .await;
+        match result {
+            Ok(team_details) => Some(team_details),
+            Err(err) => {
+                error!("Failed to fetch Linear issue team: {err}");
+                None
+            }
+        }
+    });
+
+    // Keep the selectors in sync with the issue, refreshed after each action
+    use_effect(move || {
+        let issue = linear_issue();
+        selected_state_id.set(issue.state.id);
+        selected_priority.set(Some(issue.priority));
+        // `LinearUser` does not store the user id, the team member is found by its name
+        let assignee_id = issue.assignee.and_then(|assignee| {
+            team_details()
+                .flatten()?
+                .members
+                .into_iter()
+                .find(|member| member.name == assignee.name)
+                .map(|member| member.id)
+        });
+        selected_assignee_id.set(assignee_id);
+    });
+
+    let send_action = move |action: LinearIssueAction| {
+        notification_service.send(NotificationCommand::ActOnLinearIssue(
+            notification_id(),
+            action,
+        ));
+    };
+    let team_details = team_details().flatten();
+    let state_options: Vec<UISelectOption<Uuid>> = team_details
+        .as_ref()
+        .map(|team_details| {
+            team_details
+                .workflow_states
+                .iter()
+                .filter_map(|state| Some(UISelectOption::new(state.id?, state.name.clone())))
+                .collect()
+        })
+        .unwrap_or_default();
+    let member_options: Vec<UISelectOption<Uuid>> = team_details
+        .as_ref()
+        .map(|team_details| {
+            team_details
+                .members
+                .iter()
+                .map(|member| {
+                    let mut option = UISelectOption::new(member.id, member.name.clone());
+                    if member.is_viewer {
+                        option.meta = Some("You".to_string());
+                    }
+                    option
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let priority_options: Vec<UISelectOption<LinearIssuePriority>> = LINEAR_ISSUE_PRIORITIES
+        .into_iter()
+        .map(|priority| {
+            let label = match priority {
+                LinearIssuePriority::NoPriority => "No priority".to_string(),
+                priority => priority.to_string(),
+            };
+            UISelectOption::new(priority, label)
+        })
+        .collect();
+    let is_team_loaded = team_details.is_some();
+    let due_date = linear_issue()
+        .due_date
+        .map(|due_date| due_date.format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+
+    rsx! {
+        Card {
+            variant: CardVariant::Default,
+
+            MetadataGrid {
+                MetadataItem {
+                    label: "Move to".to_string(),
+                    value: rsx! {
+                        UISelect::<Uuid> {
+                            value: selected_state_id,
+                            options: state_options,
+                            on_change: move |state_id: Option<Uuid>| {
+                                if let Some(state_id) = state_id
+                                    && linear_issue().state.id != Some(state_id)
+                                {
+                                    send_action(LinearIssueAction::UpdateState { state_id });
+                                }
+                            },
+                            placeholder: "Pick a state…".to_string(),
+                            disabled: !is_team_loaded,
+                            name: "linear-issue-state".to_string(),
+                        }
+                    },
+                }
+
+                MetadataItem {
+                    label: "Assignee".to_string(),
+                    value: rsx! {
+                        UISelect::<Uuid> {
+                            value: selected_assignee_id,
+                            options: member_options,
+                            on_change: move |assignee_id: Option<Uuid>| {
+                                match assignee_id {
+                                    Some(assignee_id) => send_action(LinearIssueAction::Assign { assignee_id }),
+                                    None => send_action(LinearIssueAction::Unassign),
+                                }
+                            },
+                            placeholder: "Unassigned".to_string(),
+                            allow_clear: true,
+                            disabled: !is_team_loaded,
+                            name: "linear-issue-assignee".to_string(),
+                        }
+                    },
+                }
+
+                MetadataItem {
+                    label: "Priority".to_string(),
+                    value: rsx! {
+                        UISelect::<LinearIssuePriority> {
+                            value: selected_priority,
+                            options: priority_options,
+                            on_change: move |priority: Option<LinearIssuePriority>| {
+                                if let Some(priority) = priority
+                                    && linear_issue().priority != priority
+                                {
+                                    send_action(LinearIssueAction::SetPriority { priority });
+                                }
+                            },
+                            name: "linear-issue-priority".to_string(),
+                        }
+                    },
+                }
+
+                MetadataItem {
+                    label: "Due date".to_string(),
+                    value: rsx! {
+                        input {
+                            class: "px-2.5 py-1 text-xs font-ui bg-ui-base-200 text-ui-base-content border border-ui-border rounded-ui-sm outline-none focus:border-ui-primary focus:shadow-[var(--ui-focus-ring)] focus:outline-none",
+                            r#type: "date",
+                            name: "linear-issue-due-date",
+                            value: "{due_date}",
+                            onchange: move |evt| {
+                                let due_date = NaiveDate::parse_from_str(&evt.value(), "%Y-%m-%d").ok();
+                                if due_date != linear_issue().due_date {
+                                    send_action(LinearIssueAction::SetDueDate { due_date });
+                                }
+                            },
+                        }
+                    },
+                }
+            }
+
+            div {
+                class: "flex flex-col gap-2 border-t border-ui-border-light mt-2.5 pt-2.5",
+                textarea {
+                    class: "w-full px-2.5 py-1.5 text-[var(--ui-text-base)] font-ui bg-ui-base-200 text-ui-base-content border border-ui-border rounded-ui-sm outline-none transition-[border-color,box-shadow] duration-150 ease-[var(--ui-ease)] focus:border-ui-primary focus:shadow-[var(--ui-focus-ring)] focus:outline-none",
+                    rows: 3,
+                    placeholder: "Leave a comment",
+                    value: "{comment}",
+                    oninput: move |evt| comment.set(evt.value()),
+                }
+                div {
+                    class: "flex justify-end",
+                    Button {
+                        variant: ButtonVariant::Primary,
+                        size: ButtonSize::Xs,
+                        icon_class: "icon-[lucide--message-square] size-3.5".to_string(),
+                        disabled: comment().trim().is_empty(),
+                        onclick: move |_| {
+                            let body = comment().trim().to_string();
+                            if !body.is_empty() {
+                                send_action(LinearIssueAction::Comment { body });
+                                comment.set(String::new());
+                            }
+                        },
+                        "Comment"
+                    }
+                }
+            }
+        }
+    }
+}
+
 /// One row of the Linear comment thread. Renders the author header, the body,
 /// and (when present) a [`ThreadChildren`] block recursing into each child.
 #[component]
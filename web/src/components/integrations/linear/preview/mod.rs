@@ -2,7 +2,10 @@
 
 use dioxus::prelude::*;
 
-use universal_inbox::third_party::integrations::linear::{LinearLabel, LinearNotification};
+use universal_inbox::{
+    notification::NotificationId,
+    third_party::integrations::linear::{LinearLabel, LinearNotification},
+};
 
 use crate::components::{
     Tag,
@@ -25,13 +28,15 @@ impl From<LinearLabel> for Tag {
 pub fn LinearNotificationPreview(
     linear_notification: ReadSignal<LinearNotification>,
     expand_details: ReadSignal<bool>,
+    notification_id: ReadSignal<NotificationId>,
 ) -> Element {
     match linear_notification() {
         LinearNotification::IssueNotification { issue, .. } => rsx! {
             LinearIssuePreview {
                 linear_issue: issue,
                 linear_notification: Some(linear_notification()),
-                expand_details
+                expand_details,
+                notification_id: Some(notification_id()),
             }
         },
         LinearNotification::ProjectNotification { project, .. } => rsx! {
@@ -21,6 +21,7 @@ use universal_inbox::{
 use crate::{
     components::{
         ai_agents_card::AiAgentsCard,
+        integration_sync_runs::IntegrationSyncRuns,
         integrations::{
             github::config::GithubProviderConfiguration,
             google_calendar::config::GoogleCalendarProviderConfiguration,
@@ -724,6 +725,12 @@ pub fn IntegrationSettings(
                             }
                         }
 
+                        if let Some(Some(ref conn)) = connection() {
+                            if conn.provider.is_notification_service() || conn.provider.is_task_service() {
+                                IntegrationSyncRuns { connection_id: conn.id }
+                            }
+                        }
+
                         if let Some(ref warning_message) = config().warning_message {
                             if !warning_message.is_empty() {
                                 div {
@@ -819,8 +826,9 @@ pub fn IntegrationConnectionProviderConfiguration(
                 config: config.clone(),
             }
         },
-        IntegrationProvider::Github { config } => rsx! {
+        IntegrationProvider::Github { config, .. } => rsx! {
             GithubProviderConfiguration {
+                ui_model: ui_model,
                 on_config_change: move |c| on_config_change.call(c),
                 config: config.clone()
             }
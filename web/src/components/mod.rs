@@ -19,6 +19,7 @@ pub mod field_grid;
 pub mod floating_label_inputs;
 pub mod flyonui;
 pub mod footer;
+pub mod integration_sync_runs;
 pub mod integrations;
 pub mod integrations_panel;
 pub mod list;
@@ -31,6 +32,7 @@ pub mod preview_card_header;
 pub mod priority_field;
 pub mod project_search_field;
 pub mod resizable_panel;
+pub mod sessions_card;
 pub mod settings_controls;
 pub mod sidebar;
 pub mod spinner;
@@ -43,6 +45,7 @@ pub mod tasks_list;
 pub mod thread;
 pub mod threaded_message;
 pub mod toast_zone;
+pub mod two_factor_card;
 pub mod ui;
 pub mod universal_inbox_title;
 pub mod user_profile_card;
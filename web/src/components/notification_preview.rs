@@ -18,7 +18,8 @@ use crate::{
             api::web_page::preview::WebPagePreview,
             github::preview::{
                 GithubNotificationDefaultPreview, discussion::GithubDiscussionPreview,
-                pull_request::GithubPullRequestPreview,
+                issue::GithubIssuePreview, pull_request::GithubPullRequestPreview,
+                release::GithubReleasePreview, workflow_run::GithubWorkflowRunPreview,
             },
             google_calendar::preview::GoogleCalendarEventPreview,
             google_drive::preview::GoogleDriveCommentPreview,
@@ -348,7 +349,8 @@ fn NotificationDetailsPreview(
                     GithubPullRequestPreview {
                         github_pull_request,
                         title: notification().title,
-                        expand_details
+                        expand_details,
+                        notification_id: notification().id,
                     }
                 },
                 Some(GithubNotificationItem::GithubDiscussion(github_discussion)) => rsx! {
@@ -358,6 +360,27 @@ fn NotificationDetailsPreview(
                         expand_details
                     }
                 },
+                Some(GithubNotificationItem::GithubIssue(github_issue)) => rsx! {
+                    GithubIssuePreview {
+                        github_issue,
+                        title: notification().title,
+                        expand_details
+                    }
+                },
+                Some(GithubNotificationItem::GithubWorkflowRun(github_workflow_run)) => rsx! {
+                    GithubWorkflowRunPreview {
+                        github_workflow_run,
+                        title: notification().title,
+                        expand_details
+                    }
+                },
+                Some(GithubNotificationItem::GithubRelease(github_release)) => rsx! {
+                    GithubReleasePreview {
+                        github_release,
+                        title: notification().title,
+                        expand_details
+                    }
+                },
                 _ => rsx! {
                     GithubNotificationDefaultPreview {
                         notification,
@@ -384,7 +407,8 @@ fn NotificationDetailsPreview(
         ThirdPartyItemData::LinearNotification(linear_notification) => rsx! {
             LinearNotificationPreview {
                 linear_notification: *linear_notification,
-                expand_details
+                expand_details,
+                notification_id: notification().id,
             }
         },
         ThirdPartyItemData::GoogleMailThread(google_mail_thread) => rsx! {
@@ -412,6 +436,7 @@ fn NotificationDetailsPreview(
             WebPagePreview { notification, web_page: *web_page }
         },
         ThirdPartyItemData::LinearIssue(_)
+        | ThirdPartyItemData::GithubTaskItem(_)
         | ThirdPartyItemData::TodoistItem(_)
         | ThirdPartyItemData::TickTickItem(_) => rsx! {},
     }
@@ -437,6 +462,9 @@ fn notification_sub_type(data: &ThirdPartyItemData) -> Option<&'static str> {
         ThirdPartyItemData::GithubNotification(n) => match &n.item {
             Some(GithubNotificationItem::GithubPullRequest(_)) => Some("Pull request"),
             Some(GithubNotificationItem::GithubDiscussion(_)) => Some("Discussion"),
+            Some(GithubNotificationItem::GithubIssue(_)) => Some("Issue"),
+            Some(GithubNotificationItem::GithubWorkflowRun(_)) => Some("Workflow run"),
+            Some(GithubNotificationItem::GithubRelease(_)) => Some("Release"),
             _ => Some("Notification"),
         },
         ThirdPartyItemData::LinearNotification(_) => Some("Notification"),
@@ -447,7 +475,7 @@ fn notification_sub_type(data: &ThirdPartyItemData) -> Option<&'static str> {
         ThirdPartyItemData::SlackThread(_) => Some("Thread"),
         ThirdPartyItemData::TodoistItem(_) | ThirdPartyItemData::TickTickItem(_) => Some("Task"),
         ThirdPartyItemData::WebPage(_) => Some("Web page"),
-        ThirdPartyItemData::LinearIssue(_) => None,
+        ThirdPartyItemData::LinearIssue(_) | ThirdPartyItemData::GithubTaskItem(_) => None,
     }
 }
 
@@ -341,7 +341,7 @@ fn NotificationListItem(
                 on_select,
             }
         },
-        ThirdPartyItemData::LinearIssue(_) => rsx! {},
+        ThirdPartyItemData::LinearIssue(_) | ThirdPartyItemData::GithubTaskItem(_) => rsx! {},
         ThirdPartyItemData::WebPage(web_page) => rsx! {
             WebPageNotificationListItem {
                 notification,
@@ -0,0 +1,167 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+
+use universal_inbox::auth::auth_token::UserSession;
+
+use crate::{
+    components::{
+        loading::Loading,
+        ui::{
+            Badge, BadgeTone, BadgeVariant, Button, ButtonVariant, Card, CardHeader, CardMeta,
+            CardRight, CardVariant,
+        },
+    },
+    services::session_service::{SessionCommand, USER_SESSIONS},
+    utils::{describe_user_agent, format_absolute_time},
+};
+
+#[component]
+pub fn SessionsCard() -> Element {
+    let session_service = use_coroutine_handle::<SessionCommand>();
+    let mut confirming_sign_out_others = use_signal(|| false);
+
+    let _resource = use_resource(move || {
+        to_owned![session_service];
+
+        async move {
+            session_service.send(SessionCommand::Refresh);
+        }
+    });
+
+    let Some(sessions) = USER_SESSIONS.read().clone() else {
+        return rsx! {
+            Card { variant: CardVariant::ApiKeys,
+                Loading { label: "Loading sessions..." }
+            }
+        };
+    };
+    let has_other_sessions = sessions.iter().any(|session| !session.is_current);
+
+    rsx! {
+        section {
+            role: "region",
+            aria_label: "Active sessions",
+
+            Card {
+                variant: CardVariant::ApiKeys,
+                CardHeader {
+                    interactive: false,
+                    span { class: "icon-[lucide--monitor-smartphone] size-5" }
+                    CardMeta {
+                        name: "Active sessions",
+                        description: rsx! { "Devices currently logged in to your account." },
+                    }
+
+                    if has_other_sessions {
+                        CardRight {
+                            if confirming_sign_out_others() {
+                                Button {
+                                    variant: ButtonVariant::Danger,
+                                    onclick: move |_| {
+                                        session_service.send(SessionCommand::RevokeOtherSessions);
+                                        confirming_sign_out_others.set(false);
+                                    },
+                                    "Confirm"
+                                }
+                                Button {
+                                    variant: ButtonVariant::Ghost,
+                                    onclick: move |_| confirming_sign_out_others.set(false),
+                                    "Cancel"
+                                }
+                            } else {
+                                Button {
+                                    variant: ButtonVariant::Ghost,
+                                    icon_class: "icon-[lucide--log-out]".to_string(),
+                                    onclick: move |_| confirming_sign_out_others.set(true),
+                                    "Sign out everywhere else"
+                                }
+                            }
+                        }
+                    }
+                }
+
+                table {
+                    class: "api-keys-table max-md:block max-md:overflow-x-auto",
+                    thead {
+                        tr {
+                            th { style: "width: 160px;", "Device" }
+                            th { class: "max-md:hidden", style: "width: 100px;", "IP address" }
+                            th { class: "max-md:hidden", style: "width: 100px;", "Signed in" }
+                            th { style: "width: 100px;", "Last seen" }
+                            th { style: "width: 120px;", aria_label: "Actions", "" }
+                        }
+                    }
+                    tbody {
+                        for session in sessions.into_iter() {
+                            SessionRow { key: "{session.id}", session }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn SessionRow(session: UserSession) -> Element {
+    let session_service = use_coroutine_handle::<SessionCommand>();
+    let mut confirming = use_signal(|| false);
+    let device = session
+        .user_agent
+        .as_deref()
+        .map(describe_user_agent)
+        .unwrap_or_else(|| "Unknown device".to_string());
+    let user_agent = session.user_agent.clone().unwrap_or_default();
+    let ip_address = session.ip_address.clone().unwrap_or_default();
+    let last_seen_at = session.last_seen_at.unwrap_or(session.created_at);
+    let session_id = session.id.clone();
+
+    rsx! {
+        tr {
+            td {
+                div {
+                    class: "flex items-center gap-2",
+                    p { class: "truncate", title: "{user_agent}", "{device}" }
+                    if session.is_current {
+                        Badge { variant: BadgeVariant::Method, tone: BadgeTone::Success, "This device" }
+                    }
+                }
+            }
+            td { class: "max-md:hidden", p { class: "truncate", "{ip_address}" } }
+            td {
+                class: "max-md:hidden",
+                title: "{format_absolute_time(session.created_at)}",
+                r#"{session.created_at.date_naive().format("%Y-%m-%d")}"#
+            }
+            td { title: "{format_absolute_time(last_seen_at)}", r#"{last_seen_at.date_naive().format("%Y-%m-%d")}"# }
+            td {
+                div {
+                    class: "flex items-center justify-end gap-1",
+                    if confirming() {
+                        Button {
+                            variant: ButtonVariant::Danger,
+                            onclick: move |_| {
+                                session_service.send(SessionCommand::RevokeSession(session_id.clone()));
+                                confirming.set(false);
+                            },
+                            "Confirm"
+                        }
+                        Button {
+                            variant: ButtonVariant::Ghost,
+                            onclick: move |_| confirming.set(false),
+                            "Cancel"
+                        }
+                    } else if !session.is_current {
+                        Button {
+                            variant: ButtonVariant::Danger,
+                            icon_class: "icon-[lucide--log-out]".to_string(),
+                            onclick: move |_| confirming.set(true),
+                            span { class: "hidden sm:inline", "Sign out" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -5,13 +5,17 @@ use dioxus::prelude::*;
 use universal_inbox::{
     HasHtmlUrl,
     task::{Task, TaskId, TaskSourceKind},
-    third_party::item::{ThirdPartyItemData, ThirdPartyItemKind},
+    third_party::{
+        integrations::github::GithubTaskItemKind,
+        item::{ThirdPartyItemData, ThirdPartyItemKind},
+    },
 };
 
 use crate::{
     components::{
         integrations::{
-            icons::TaskIcon, linear::preview::issue::LinearIssuePreview,
+            github::preview::task_item::GithubTaskItemPreview, icons::TaskIcon,
+            linear::preview::issue::LinearIssuePreview,
             slack::preview::slack_reaction::SlackReactionTaskPreview,
             ticktick::preview::TickTickTaskPreview, todoist::preview::TodoistTaskPreview,
         },
@@ -182,9 +186,13 @@ pub fn TaskDetailsPreview(task: ReadSignal<Task>, expand_details: ReadSignal<boo
             LinearIssuePreview {
                 linear_issue: *linear_issue,
                 linear_notification: None,
-                expand_details
+                expand_details,
+                notification_id: None,
             }
         },
+        ThirdPartyItemData::GithubTaskItem(github_task_item) => rsx! {
+            GithubTaskItemPreview { github_task_item: *github_task_item, task }
+        },
         ThirdPartyItemData::SlackThread(_)
         | ThirdPartyItemData::LinearNotification(_)
         | ThirdPartyItemData::GithubNotification(_)
@@ -201,6 +209,7 @@ pub fn task_source_display_name(kind: TaskSourceKind) -> &'static str {
         TaskSourceKind::TickTick => "TickTick",
         TaskSourceKind::Slack => "Slack",
         TaskSourceKind::Linear => "Linear",
+        TaskSourceKind::Github => "GitHub",
     }
 }
 
@@ -208,6 +217,14 @@ pub fn task_sub_type(task: &Task) -> &'static str {
     match task.source_item.kind() {
         ThirdPartyItemKind::SlackReaction => "Reaction",
         ThirdPartyItemKind::LinearIssue => "Issue",
+        ThirdPartyItemKind::GithubTaskItem => match &task.source_item.data {
+            ThirdPartyItemData::GithubTaskItem(item)
+                if item.kind == GithubTaskItemKind::ReviewRequest =>
+            {
+                "Review request"
+            }
+            _ => "Issue",
+        },
         ThirdPartyItemKind::TickTickItem
         | ThirdPartyItemKind::TodoistItem
         | ThirdPartyItemKind::SlackThread
@@ -9,7 +9,7 @@ use universal_inbox::{task::Task, third_party::item::ThirdPartyItemData};
 use crate::{
     components::{
         integrations::{
-            linear::task_list_item::LinearTaskListItem,
+            github::task_list_item::GithubTaskListItem, linear::task_list_item::LinearTaskListItem,
             slack::task_list_item::SlackReactionTaskListItem,
             ticktick::task_list_item::TickTickTaskListItem,
             todoist::task_list_item::TodoistTaskListItem,
@@ -130,6 +130,14 @@ fn TaskListItem(
                 on_select,
             }
         },
+        ThirdPartyItemData::GithubTaskItem(github_task_item) => rsx! {
+            GithubTaskListItem {
+                task,
+                github_task_item: *github_task_item,
+                is_selected,
+                on_select,
+            }
+        },
         ThirdPartyItemData::SlackThread(_)
         | ThirdPartyItemData::LinearNotification(_)
         | ThirdPartyItemData::GithubNotification(_)
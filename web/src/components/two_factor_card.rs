@@ -0,0 +1,284 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use log::error;
+use qrcode::{QrCode, render::svg};
+
+use universal_inbox::user::{SecondFactorCode, TotpStatus, UserAuthKind};
+
+use crate::{
+    components::{
+        floating_label_inputs::FloatingLabelInputText,
+        loading::Loading,
+        ui::{
+            Badge, BadgeTone, BadgeVariant, Button, ButtonVariant, Card, CardHeader, CardMeta,
+            CardRight, CardVariant,
+        },
+    },
+    services::{
+        totp_service::{RECOVERY_CODES, TOTP_ENROLLMENT, TOTP_STATUS, TotpCommand},
+        user_service::{AUTH_METHODS, UserCommand},
+    },
+    utils::copy_to_clipboard,
+};
+
+/// Actions on an enabled TOTP, which must be confirmed with a code
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum TotpAction {
+    RegenerateRecoveryCodes,
+    Disable,
+}
+
+#[component]
+pub fn TwoFactorCard() -> Element {
+    let totp_service = use_coroutine_handle::<TotpCommand>();
+    let user_service = use_coroutine_handle::<UserCommand>();
+    let mut pending_action = use_signal(|| None::<TotpAction>);
+
+    let _resource = use_resource(move || {
+        to_owned![totp_service, user_service];
+
+        async move {
+            totp_service.send(TotpCommand::Refresh);
+            user_service.send(UserCommand::ListAuthMethods);
+        }
+    });
+
+    let Some(TotpStatus {
+        enabled,
+        remaining_recovery_codes,
+        ..
+    }) = TOTP_STATUS.read().clone()
+    else {
+        return rsx! {
+            Card { variant: CardVariant::ApiKeys,
+                Loading { label: "Loading two-factor authentication..." }
+            }
+        };
+    };
+    let has_local = AUTH_METHODS
+        .read()
+        .as_ref()
+        .is_some_and(|methods| methods.iter().any(|m| m.kind == UserAuthKind::Local));
+    let enrollment = TOTP_ENROLLMENT.read().clone();
+    let recovery_codes = RECOVERY_CODES.read().clone();
+
+    rsx! {
+        section {
+            role: "region",
+            aria_label: "Two-factor authentication",
+
+            Card { variant: CardVariant::ApiKeys,
+                CardHeader {
+                    interactive: false,
+                    span { class: "icon-[lucide--smartphone] size-5" }
+                    CardMeta {
+                        name: "Two-factor authentication",
+                        description: rsx! {
+                            "Require a code from an authenticator app when logging in with your password."
+                        },
+                    }
+
+                    CardRight {
+                        if enabled {
+                            Badge { variant: BadgeVariant::Method, tone: BadgeTone::Success,
+                                span { class: "icon-[lucide--shield-check] size-3" }
+                                "Enabled"
+                            }
+                        } else if enrollment.is_none() {
+                            Button {
+                                variant: ButtonVariant::Primary,
+                                icon_class: "icon-[lucide--shield-plus]".to_string(),
+                                disabled: !has_local,
+                                title: (!has_local).then(|| "Add a password authentication method first".to_string()),
+                                onclick: move |_| totp_service.send(TotpCommand::StartEnrollment),
+                                "Enable"
+                            }
+                        }
+                    }
+                }
+
+                if let Some(recovery_codes) = recovery_codes {
+                    RecoveryCodesPanel { recovery_codes }
+                } else if let Some(enrollment) = enrollment {
+                    div {
+                        class: "flex flex-col gap-3 px-4 pb-4",
+
+                        p { class: "text-sm text-ui-base-muted",
+                            "Scan this QR code with your authenticator app, or enter the secret manually, then type the 6-digit code it displays."
+                        }
+
+                        div { class: "flex flex-wrap items-center gap-4",
+                            div {
+                                class: "size-44 p-2 bg-white rounded-ui-md [&>svg]:size-full",
+                                dangerous_inner_html: "{provisioning_qr_code_svg(&enrollment.provisioning_uri)}",
+                            }
+                            div { class: "flex flex-col gap-1",
+                                span { class: "text-[11px] text-ui-base-muted", "Secret" }
+                                code { class: "font-mono text-sm break-all", "{enrollment.secret}" }
+                            }
+                        }
+
+                        SecondFactorCodeForm {
+                            submit_label: "Verify and enable",
+                            on_submit: move |code| totp_service.send(TotpCommand::ConfirmEnrollment(code)),
+                            on_cancel: move |_| totp_service.send(TotpCommand::CancelEnrollment),
+                        }
+                    }
+                } else if enabled {
+                    div {
+                        class: "flex flex-col gap-3 px-4 pb-4",
+
+                        p { class: "text-sm text-ui-base-muted",
+                            "{remaining_recovery_codes} unused recovery codes left."
+                        }
+
+                        match pending_action() {
+                            Some(action) => rsx! {
+                                SecondFactorCodeForm {
+                                    submit_label: match action {
+                                        TotpAction::RegenerateRecoveryCodes => "Generate new recovery codes",
+                                        TotpAction::Disable => "Disable",
+                                    },
+                                    on_submit: move |code| {
+                                        totp_service.send(match action {
+                                            TotpAction::RegenerateRecoveryCodes => TotpCommand::RegenerateRecoveryCodes(code),
+                                            TotpAction::Disable => TotpCommand::Disable(code),
+                                        });
+                                        pending_action.set(None);
+                                    },
+                                    on_cancel: move |_| pending_action.set(None),
+                                }
+                            },
+                            None => rsx! {
+                                div { class: "flex flex-wrap gap-2",
+                                    Button {
+                                        variant: ButtonVariant::Ghost,
+                                        icon_class: "icon-[lucide--refresh-cw]".to_string(),
+                                        onclick: move |_| pending_action.set(Some(TotpAction::RegenerateRecoveryCodes)),
+                                        "Regenerate recovery codes"
+                                    }
+                                    Button {
+                                        variant: ButtonVariant::Danger,
+                                        icon_class: "icon-[lucide--shield-off]".to_string(),
+                                        onclick: move |_| pending_action.set(Some(TotpAction::Disable)),
+                                        "Disable"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn SecondFactorCodeForm(
+    submit_label: String,
+    on_submit: EventHandler<SecondFactorCode>,
+    on_cancel: EventHandler<()>,
+) -> Element {
+    let mut code = use_signal(|| "".to_string());
+    let mut force_validation = use_signal(|| false);
+
+    rsx! {
+        form {
+            class: "flex flex-col gap-4",
+            onsubmit: move |evt| {
+                evt.prevent_default();
+                let value = code().trim().to_string();
+                if value.is_empty() {
+                    *force_validation.write() = true;
+                    error!("Missing verification code");
+                    return;
+                }
+                on_submit.call(SecondFactorCode { code: value });
+                code.set("".to_string());
+                force_validation.set(false);
+            },
+
+            FloatingLabelInputText::<String> {
+                name: "code".to_string(),
+                label: Some("Code from your authenticator app, or a recovery code".to_string()),
+                required: true,
+                value: code,
+                autofocus: true,
+                force_validation: force_validation(),
+                r#type: "text".to_string(),
+            }
+
+            div {
+                class: "flex gap-2",
+                Button {
+                    variant: ButtonVariant::Ghost,
+                    onclick: move |_| on_cancel.call(()),
+                    "Cancel"
+                }
+                Button {
+                    variant: ButtonVariant::Primary,
+                    button_type: "submit".to_string(),
+                    "{submit_label}"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn RecoveryCodesPanel(recovery_codes: Vec<String>) -> Element {
+    let totp_service = use_coroutine_handle::<TotpCommand>();
+    let all_codes = recovery_codes.join("\n");
+
+    rsx! {
+        div {
+            class: "flex flex-col gap-3 px-4 pb-4",
+
+            p { class: "text-sm text-ui-base-muted",
+                "Save these recovery codes in a safe place. Each of them can be used once to log in if you lose access to your authenticator app. They will not be displayed again."
+            }
+
+            ul { class: "grid grid-cols-2 gap-1.5 font-mono text-sm",
+                for recovery_code in recovery_codes.iter() {
+                    li { key: "{recovery_code}", "{recovery_code}" }
+                }
+            }
+
+            div { class: "flex flex-wrap gap-2",
+                Button {
+                    variant: ButtonVariant::Ghost,
+                    icon_class: "icon-[lucide--copy]".to_string(),
+                    onclick: move |_| {
+                        let all_codes = all_codes.clone();
+                        spawn(async move {
+                            if let Err(error) = copy_to_clipboard(&all_codes).await {
+                                error!("Failed to copy recovery codes to the clipboard: {error:?}");
+                            }
+                        });
+                    },
+                    "Copy"
+                }
+                Button {
+                    variant: ButtonVariant::Primary,
+                    onclick: move |_| totp_service.send(TotpCommand::DismissRecoveryCodes),
+                    "I have saved my recovery codes"
+                }
+            }
+        }
+    }
+}
+
+fn provisioning_qr_code_svg(provisioning_uri: &str) -> String {
+    match QrCode::new(provisioning_uri.as_bytes()) {
+        Ok(qr_code) => qr_code
+            .render::<svg::Color>()
+            .min_dimensions(160, 160)
+            .quiet_zone(false)
+            .build(),
+        Err(error) => {
+            error!("Failed to build the TOTP provisioning QR code: {error:?}");
+            String::new()
+        }
+    }
+}
@@ -44,6 +44,7 @@ pub fn UserProfileCard() -> Element {
                     m.kind,
                     UserAuthKind::OIDCGoogleAuthorizationCode
                         | UserAuthKind::OIDCAuthorizationCodePKCE
+                        | UserAuthKind::SAML
                 )
             })
         })
@@ -23,8 +23,10 @@ use services::{
     integration_connection_service::{INTEGRATION_CONNECTIONS, integration_connnection_service},
     notification_service::{NOTIFICATION_FILTERS, NOTIFICATIONS_PAGE, notification_service},
     oauth2_client_service::{OAUTH2_AUTHORIZED_CLIENTS, oauth2_client_service},
+    session_service::{USER_SESSIONS, session_service},
     task_service::task_service,
     toast_service::{TOASTS, VIEWPORT_WIDTH, toast_service},
+    totp_service::{RECOVERY_CODES, TOTP_ENROLLMENT, TOTP_STATUS, totp_service},
     user_preferences_service::{
         USER_PREFERENCES, UserPreferencesCommand, user_preferences_service,
     },
@@ -141,6 +143,28 @@ pub fn App() -> Element {
         )
     });
 
+    let _session_service_handle = use_coroutine(move |rx| {
+        session_service(
+            rx,
+            api_base_url(),
+            USER_SESSIONS.signal(),
+            UI_MODEL.signal(),
+            toast_service_handle,
+        )
+    });
+
+    let _totp_service_handle = use_coroutine(move |rx| {
+        totp_service(
+            rx,
+            api_base_url(),
+            TOTP_STATUS.signal(),
+            TOTP_ENROLLMENT.signal(),
+            RECOVERY_CODES.signal(),
+            UI_MODEL.signal(),
+            toast_service_handle,
+        )
+    });
+
     // Initialize viewport width and set up resize listener
     use_effect(move || {
         // Set initial viewport width
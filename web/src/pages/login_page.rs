@@ -5,20 +5,23 @@ use dioxus::prelude::*;
 use email_address::EmailAddress;
 use log::error;
 
-use universal_inbox::{FrontAuthenticationConfig, user::Password};
+use universal_inbox::{
+    FrontAuthenticationConfig,
+    user::{Password, SecondFactorCode},
+};
 
 use crate::{
-    auth::authenticate_authorization_code_flow,
+    auth::{authenticate_authorization_code_flow, authenticate_saml_flow},
     components::{
-        auth_widgets::{AuthDivider, GoogleBtn, LegalFooter, PasskeyBtn, PrimaryBtn},
+        auth_widgets::{AuthDivider, GoogleBtn, LegalFooter, PasskeyBtn, PrimaryBtn, SsoBtn},
         floating_label_inputs::FloatingLabelInputText,
         loading::Loading,
-        ui::PageHeader,
+        ui::{Button, ButtonVariant, PageHeader},
     },
     config::{APP_CONFIG, get_api_base_url},
     form::FormValues,
     route::Route,
-    services::user_service::{CONNECTED_USER, UserCommand},
+    services::user_service::{CONNECTED_USER, SECOND_FACTOR_REQUIRED, UserCommand},
 };
 
 pub fn LoginPage() -> Element {
@@ -35,6 +38,10 @@ pub fn LoginPage() -> Element {
         return rsx! {};
     };
 
+    if SECOND_FACTOR_REQUIRED.read().is_some() {
+        return rsx! { SecondFactorForm {} };
+    }
+
     let app_config = APP_CONFIG.read();
     let Some(app_config) = app_config.as_ref() else {
         return rsx! { Loading { label: "Loading Universal Inbox settings..." } };
@@ -53,6 +60,10 @@ pub fn LoginPage() -> Element {
         .authentication_configs
         .iter()
         .any(|auth_config| matches!(auth_config, FrontAuthenticationConfig::Passkey));
+    let is_saml_auth_enabled = app_config
+        .authentication_configs
+        .iter()
+        .any(|auth_config| matches!(auth_config, FrontAuthenticationConfig::SAML));
 
     rsx! {
         PageHeader {
@@ -127,7 +138,7 @@ pub fn LoginPage() -> Element {
                 PrimaryBtn { button_type: "submit".to_string(), "Log in" }
             }
 
-            if is_google_auth_enabled || is_passkey_auth_enabled {
+            if is_google_auth_enabled || is_passkey_auth_enabled || is_saml_auth_enabled {
                 AuthDivider { label: "or continue with".to_string() }
             }
 
@@ -151,6 +162,20 @@ pub fn LoginPage() -> Element {
                 if is_passkey_auth_enabled {
                     PasskeyBtn { to: Route::PasskeyLoginPage {}, "Sign in with a passkey" }
                 }
+                if is_saml_auth_enabled {
+                    SsoBtn {
+                        onclick: move |_| {
+                            spawn({
+                                async move {
+                                    if let Err(auth_error) = authenticate_saml_flow(&api_base_url()).await {
+                                        error!("An error occured while authenticating: {:?}", auth_error);
+                                    }
+                                }
+                            });
+                        },
+                        "Continue with single sign-on"
+                    }
+                }
             }
 
             LegalFooter {}
@@ -166,3 +191,56 @@ pub fn LoginPage() -> Element {
         }
     }
 }
+
+#[component]
+fn SecondFactorForm() -> Element {
+    let user_service = use_coroutine_handle::<UserCommand>();
+    let code = use_signal(|| "".to_string());
+    let mut force_validation = use_signal(|| false);
+
+    rsx! {
+        PageHeader {
+            title: "Two-factor authentication".to_string(),
+            subtitle: Some(
+                "Enter the code displayed by your authenticator app, or one of your recovery codes."
+                    .to_string(),
+            ),
+        }
+
+        form {
+            "novalidate": "true",
+            onsubmit: move |evt| {
+                evt.prevent_default();
+                let code = code().trim().to_string();
+                if code.is_empty() {
+                    *force_validation.write() = true;
+                    error!("Missing second factor code");
+                    return;
+                }
+                user_service.send(UserCommand::LoginSecondFactor(SecondFactorCode { code }));
+            },
+
+            FloatingLabelInputText::<String> {
+                name: "code".to_string(),
+                label: Some("Verification code".to_string()),
+                required: true,
+                value: code,
+                autofocus: true,
+                force_validation: force_validation(),
+                r#type: "text".to_string(),
+                field_icon_class: "icon-[lucide--smartphone]".to_string(),
+                placeholder: "123456".to_string(),
+            }
+
+            div { class: "flex flex-col gap-2.5 mt-1",
+                PrimaryBtn { button_type: "submit".to_string(), "Verify" }
+                Button {
+                    variant: ButtonVariant::Ghost,
+                    class: "btn-block".to_string(),
+                    onclick: move |_| user_service.send(UserCommand::CancelSecondFactor),
+                    "Back to login"
+                }
+            }
+        }
+    }
+}
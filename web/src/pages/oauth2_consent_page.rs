@@ -49,11 +49,7 @@ pub fn OAuth2ConsentPage(request_id: String) -> Element {
         }
     };
 
-    let scope_label = consent
-        .scope
-        .clone()
-        .filter(|s| !s.trim().is_empty())
-        .unwrap_or_else(|| "default".to_string());
+    let requested_scopes = consent.scopes.clone();
     let client_label = consent
         .client_name
         .clone()
@@ -76,10 +72,32 @@ pub fn OAuth2ConsentPage(request_id: String) -> Element {
             p { class: "text-xs uppercase tracking-wide text-ui-base-muted font-semibold mb-1",
                 "Requested permissions"
             }
-            p { class: "text-sm text-ui-base-content font-mono break-all", "{scope_label}" }
-            p { class: "text-xs text-ui-base-muted mt-3",
-                "Redirect URI: "
-                span { class: "font-mono break-all", "{consent.redirect_uri}" }
+            ul { class: "flex flex-col gap-1.5",
+                for scope in requested_scopes {
+                    li { class: "flex items-start gap-2 text-sm text-ui-base-content",
+                        if scope.is_read_only() {
+                            span { class: "icon-[tabler--eye] text-ui-base-muted mt-0.5 shrink-0" }
+                        } else {
+                            span { class: "icon-[tabler--pencil] text-ui-warning mt-0.5 shrink-0" }
+                        }
+                        div {
+                            p { "{scope.description()}" }
+                            p { class: "text-xs text-ui-base-muted font-mono", "{scope}" }
+                        }
+                    }
+                }
+            }
+            if let Some(redirect_uri) = consent.redirect_uri.as_ref() {
+                p { class: "text-xs text-ui-base-muted mt-3",
+                    "Redirect URI: "
+                    span { class: "font-mono break-all", "{redirect_uri}" }
+                }
+            }
+            if let Some(user_code) = consent.user_code.as_ref() {
+                p { class: "text-xs text-ui-base-muted mt-3",
+                    "Device code: "
+                    span { class: "font-mono", "{user_code}" }
+                }
             }
         }
         if let Some(msg) = error_message.read().as_ref() {
@@ -0,0 +1,104 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use log::error;
+
+use crate::{
+    components::{
+        auth_widgets::PrimaryBtn, floating_label_inputs::FloatingLabelInputText, ui::PageHeader,
+    },
+    config::get_api_base_url,
+    route::Route,
+    services::oauth2_consent_service::verify_device_user_code,
+};
+
+/// RFC 8628 verification page: the user enters the code displayed by a CLI or
+/// headless agent, then approves or denies it on the regular consent screen.
+/// The consent screen redirects back here with the resulting `status`.
+#[component]
+pub fn OAuth2DevicePage(user_code: Option<String>, status: Option<String>) -> Element {
+    let api_base_url = use_memo(move || get_api_base_url().unwrap());
+    let nav = use_navigator();
+    let user_code_value = use_signal(|| user_code.clone().unwrap_or_default());
+    let mut force_validation = use_signal(|| false);
+    let mut submitting = use_signal(|| false);
+    let mut error_message = use_signal(|| None::<String>);
+
+    match status.as_deref() {
+        Some("approved") => {
+            return rsx! {
+                PageHeader {
+                    title: "Device connected".to_string(),
+                    subtitle: Some("You can close this window and return to your device.".to_string()),
+                }
+            };
+        }
+        Some("denied") => {
+            return rsx! {
+                PageHeader {
+                    title: "Access denied".to_string(),
+                    subtitle: Some("The device was not granted access to your Universal Inbox.".to_string()),
+                }
+            };
+        }
+        _ => {}
+    }
+
+    rsx! {
+        PageHeader {
+            title: "Connect a device".to_string(),
+            subtitle: Some("Enter the code displayed on your device to authorize it.".to_string()),
+        }
+
+        if let Some(msg) = error_message.read().as_ref() {
+            div { class: "rounded-ui-md border border-ui-error bg-ui-surface text-ui-error text-sm p-3 mb-4",
+                "{msg}"
+            }
+        }
+
+        form {
+            "novalidate": "true",
+            onsubmit: move |evt| {
+                evt.prevent_default();
+                let user_code = user_code_value();
+                if user_code.trim().is_empty() {
+                    *force_validation.write() = true;
+                    return;
+                }
+                if submitting() {
+                    return;
+                }
+                let api_base_url = api_base_url();
+                spawn(async move {
+                    *submitting.write() = true;
+                    *error_message.write() = None;
+                    match verify_device_user_code(&api_base_url, &user_code).await {
+                        Ok(response) => {
+                            nav.push(Route::OAuth2ConsentPage {
+                                request_id: response.request_id,
+                            });
+                        }
+                        Err(err) => {
+                            error!("Failed to verify device user code: {err:?}");
+                            *error_message.write() = Some(err.to_string());
+                            *submitting.write() = false;
+                        }
+                    }
+                });
+            },
+
+            FloatingLabelInputText::<String> {
+                name: "user_code".to_string(),
+                label: Some("Code".to_string()),
+                required: true,
+                value: user_code_value,
+                autofocus: true,
+                force_validation: force_validation(),
+                field_icon_class: "icon-[tabler--device-desktop]".to_string(),
+                placeholder: "BCDF-GHJK".to_string(),
+            }
+
+            PrimaryBtn { button_type: "submit".to_string(), "Continue" }
+        }
+    }
+}
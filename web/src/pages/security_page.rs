@@ -5,7 +5,7 @@ use log::debug;
 
 use crate::components::{
     authentication_tokens_card::AuthenticationTokensCard, oauth_clients_card::OAuthClientsCard,
-    ui::PageHeader,
+    sessions_card::SessionsCard, two_factor_card::TwoFactorCard, ui::PageHeader,
 };
 
 pub fn SecurityPage() -> Element {
@@ -20,9 +20,13 @@ pub fn SecurityPage() -> Element {
 
                 PageHeader {
                     title: "Security".to_string(),
-                    subtitle: Some("Review tokens and registered apps with access to your account.".to_string()),
+                    subtitle: Some("Manage two-factor authentication and active sessions, and review tokens and registered apps with access to your account.".to_string()),
                 }
 
+                TwoFactorCard {}
+
+                SessionsCard {}
+
                 AuthenticationTokensCard {}
 
                 OAuthClientsCard {}
@@ -19,6 +19,7 @@ use crate::{
             DeletedNotificationsPage, NotificationPage, NotificationsPage, SnoozedNotificationsPage,
         },
         oauth2_consent_page::OAuth2ConsentPage,
+        oauth2_device_page::OAuth2DevicePage,
         page_not_found::PageNotFound,
         passkey_login_page::PasskeyLoginPage,
         passkey_signup_page::PasskeySignupPage,
@@ -57,6 +58,8 @@ pub enum Route {
         PasskeySignupPage {},
         #[route("/oauth2/consent?:request_id")]
         OAuth2ConsentPage { request_id: String },
+        #[route("/oauth2/device?:user_code&:status")]
+        OAuth2DevicePage { user_code: Option<String>, status: Option<String> },
       #[end_layout]
       #[route("/auth-oidc-callback?:query")]
       AuthPage { query: String },
@@ -6,7 +6,10 @@ use log::error;
 use reqwest::Method;
 use url::Url;
 
-use universal_inbox::auth::auth_token::{AuthenticationToken, TruncatedAuthenticationToken};
+use universal_inbox::auth::auth_token::{
+    ApiKeyCreation, ApiKeyPatch, AuthenticationToken, AuthenticationTokenId,
+    TruncatedAuthenticationToken,
+};
 
 use crate::{
     model::{LoadState, UniversalInboxUIModel},
@@ -19,7 +22,9 @@ use crate::{
 #[derive(Debug)]
 pub enum AuthenticationTokenCommand {
     Refresh,
-    CreateAuthenticationToken,
+    CreateAuthenticationToken(ApiKeyCreation),
+    UpdateAuthenticationToken(AuthenticationTokenId, ApiKeyPatch),
+    RevokeAuthenticationToken(AuthenticationTokenId),
 }
 
 pub static AUTHENTICATION_TOKENS: GlobalSignal<Option<Vec<TruncatedAuthenticationToken>>> =
@@ -46,14 +51,14 @@ pub async fn authentication_token_service(
                     error!("An error occurred while refreshing authentication tokens: {error:?}");
                 }
             }
-            Some(AuthenticationTokenCommand::CreateAuthenticationToken) => {
+            Some(AuthenticationTokenCommand::CreateAuthenticationToken(api_key_creation)) => {
                 *created_authentication_token.write() = LoadState::Loading;
 
                 let result: Result<AuthenticationToken> = call_api_and_notify(
                     Method::POST,
                     &api_base_url,
                     "users/me/authentication-tokens",
-                    None::<i32>,
+                    Some(api_key_creation),
                     Some(ui_model),
                     &toast_service,
                     "Creating API key...",
@@ -71,11 +76,79 @@ pub async fn authentication_token_service(
                     }
                 }
             }
+            Some(AuthenticationTokenCommand::UpdateAuthenticationToken(
+                authentication_token_id,
+                patch,
+            )) => {
+                let _result = patch_authentication_token(
+                    authentication_tokens,
+                    &api_base_url,
+                    authentication_token_id,
+                    patch,
+                    ui_model,
+                    &toast_service,
+                    "Updating API key...",
+                    "API key successfully updated",
+                )
+                .await;
+            }
+            Some(AuthenticationTokenCommand::RevokeAuthenticationToken(
+                authentication_token_id,
+            )) => {
+                let _result = patch_authentication_token(
+                    authentication_tokens,
+                    &api_base_url,
+                    authentication_token_id,
+                    ApiKeyPatch {
+                        is_revoked: Some(true),
+                        ..Default::default()
+                    },
+                    ui_model,
+                    &toast_service,
+                    "Revoking API key...",
+                    "API key successfully revoked",
+                )
+                .await;
+            }
             None => {}
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
+async fn patch_authentication_token(
+    mut authentication_tokens: Signal<Option<Vec<TruncatedAuthenticationToken>>>,
+    api_base_url: &Url,
+    authentication_token_id: AuthenticationTokenId,
+    patch: ApiKeyPatch,
+    ui_model: Signal<UniversalInboxUIModel>,
+    toast_service: &Coroutine<ToastCommand>,
+    loading_message: &str,
+    success_message: &str,
+) -> Result<TruncatedAuthenticationToken> {
+    let updated_authentication_token: TruncatedAuthenticationToken = call_api_and_notify(
+        Method::PATCH,
+        api_base_url,
+        &format!("users/me/authentication-tokens/{authentication_token_id}"),
+        Some(patch),
+        Some(ui_model),
+        toast_service,
+        loading_message,
+        success_message,
+    )
+    .await?;
+
+    if let Some(authentication_tokens) = authentication_tokens.write().as_mut() {
+        for authentication_token in authentication_tokens.iter_mut() {
+            if authentication_token.id == updated_authentication_token.id {
+                *authentication_token = updated_authentication_token.clone();
+            }
+        }
+    }
+
+    Ok(updated_authentication_token)
+}
+
 async fn refresh_authentication_tokens(
     mut authentication_tokens: Signal<Option<Vec<TruncatedAuthenticationToken>>>,
     api_base_url: &Url,
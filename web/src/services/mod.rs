@@ -8,8 +8,10 @@ pub mod local_storage;
 pub mod notification_service;
 pub mod oauth2_client_service;
 pub mod oauth2_consent_service;
+pub mod session_service;
 pub mod task_service;
 pub mod toast_service;
+pub mod totp_service;
 pub mod user_preferences_service;
 pub mod user_service;
 pub mod version;
@@ -12,8 +12,8 @@ use universal_inbox::{
         Notification, NotificationId, NotificationListOrder, NotificationSourceKind,
         NotificationStatus, NotificationSyncSourceKind, NotificationWithTask,
         service::{
-            InvitationPatch, NotificationPatch, PatchNotificationsRequest,
-            SyncNotificationsParameters,
+            GithubPullRequestAction, InvitationPatch, LinearIssueAction, NotificationPatch,
+            PatchNotificationsRequest, SyncNotificationsParameters,
         },
     },
     task::{TaskCreation, TaskId, TaskPlanning},
@@ -51,6 +51,8 @@ pub enum NotificationCommand {
     AcceptInvitation(NotificationId),
     DeclineInvitation(NotificationId),
     TentativelyAcceptInvitation(NotificationId),
+    ActOnGithubPullRequest(NotificationId, GithubPullRequestAction),
+    ActOnLinearIssue(NotificationId, LinearIssueAction),
 }
 
 /// The list of notifications a section displays: the inbox, snoozed notifications
@@ -416,6 +418,35 @@ pub async fn notification_service(
                 )
                 .await;
             }
+            Some(NotificationCommand::ActOnGithubPullRequest(notification_id, action)) => {
+                act_on_notification_source_item(
+                    notifications_page,
+                    notification_id,
+                    &api_base_url,
+                    "github/pull-request",
+                    &action,
+                    &format!(
+                        "Sending request to {} the pull request...",
+                        action.description()
+                    ),
+                    ui_model,
+                    toast_service,
+                )
+                .await;
+            }
+            Some(NotificationCommand::ActOnLinearIssue(notification_id, action)) => {
+                act_on_notification_source_item(
+                    notifications_page,
+                    notification_id,
+                    &api_base_url,
+                    "linear/issue",
+                    &action,
+                    &format!("Sending request to {} the issue...", action.description()),
+                    ui_model,
+                    toast_service,
+                )
+                .await;
+            }
             None => {}
         }
 
@@ -769,6 +800,43 @@ async fn patch_invitation(
     .await;
 }
 
+/// Apply an action on the third party item of a notification (ie. a pull request or an
+/// issue) and replace it in the list with the refreshed data returned by the API.
+#[allow(clippy::too_many_arguments)]
+async fn act_on_notification_source_item<B: serde::Serialize>(
+    mut notifications_page: Signal<Page<NotificationWithTask>>,
+    notification_id: NotificationId,
+    api_base_url: &Url,
+    path: &str,
+    action: B,
+    loading_message: &str,
+    ui_model: Signal<UniversalInboxUIModel>,
+    toast_service: Coroutine<ToastCommand>,
+) {
+    let result: Result<Option<Notification>> = call_api_and_notify(
+        Method::POST,
+        api_base_url,
+        &format!("notifications/{notification_id}/{path}"),
+        Some(action),
+        Some(ui_model),
+        &toast_service,
+        loading_message,
+        "Notification successfully updated",
+    )
+    .await;
+
+    if let Ok(Some(updated_notification)) = result
+        && let Some(notification) = notifications_page
+            .write()
+            .content
+            .iter_mut()
+            .find(|notif| notif.id == notification_id)
+    {
+        notification.source_item = updated_notification.source_item;
+        notification.updated_at = updated_notification.updated_at;
+    }
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct NotificationSourceKindFilter {
     pub selected: bool,
@@ -4,6 +4,7 @@ use url::Url;
 
 use universal_inbox::auth::oauth2::{
     OAuth2ConsentDecision, OAuth2ConsentRequest, OAuth2ConsentResponse, OAuth2ConsentSubmission,
+    OAuth2DeviceVerification, OAuth2DeviceVerificationResponse,
 };
 
 use crate::services::api::call_api;
@@ -45,3 +46,21 @@ pub async fn submit_consent_decision(
     )
     .await
 }
+
+/// Start the consent request of the device authorization matching the user
+/// code entered on the device verification page.
+pub async fn verify_device_user_code(
+    api_base_url: &Url,
+    user_code: &str,
+) -> Result<OAuth2DeviceVerificationResponse> {
+    call_api(
+        Method::POST,
+        api_base_url,
+        "oauth2/device/verify",
+        Some(OAuth2DeviceVerification {
+            user_code: user_code.to_string(),
+        }),
+        None,
+    )
+    .await
+}
@@ -0,0 +1,108 @@
+use anyhow::Result;
+use dioxus::prelude::*;
+
+use futures_util::StreamExt;
+use log::error;
+use reqwest::Method;
+use url::Url;
+
+use universal_inbox::{
+    SuccessResponse,
+    auth::auth_token::{AuthenticationTokenId, RevokedSessions, UserSession},
+};
+
+use crate::{
+    model::UniversalInboxUIModel,
+    services::{
+        api::{call_api, call_api_and_notify},
+        toast_service::ToastCommand,
+    },
+};
+
+#[derive(Debug)]
+pub enum SessionCommand {
+    Refresh,
+    RevokeSession(AuthenticationTokenId),
+    RevokeOtherSessions,
+}
+
+pub static USER_SESSIONS: GlobalSignal<Option<Vec<UserSession>>> = Signal::global(|| None);
+
+pub async fn session_service(
+    mut rx: UnboundedReceiver<SessionCommand>,
+    api_base_url: Url,
+    sessions: Signal<Option<Vec<UserSession>>>,
+    ui_model: Signal<UniversalInboxUIModel>,
+    toast_service: Coroutine<ToastCommand>,
+) {
+    loop {
+        let msg = rx.next().await;
+        match msg {
+            Some(SessionCommand::Refresh) => {
+                if let Err(error) = refresh_sessions(sessions, &api_base_url, ui_model).await {
+                    error!("An error occurred while refreshing sessions: {error:?}");
+                }
+            }
+            Some(SessionCommand::RevokeSession(session_id)) => {
+                let result: Result<SuccessResponse> = call_api_and_notify(
+                    Method::DELETE,
+                    &api_base_url,
+                    &format!("users/me/sessions/{session_id}"),
+                    None::<i32>,
+                    Some(ui_model),
+                    &toast_service,
+                    "Signing out session...",
+                    "Session signed out",
+                )
+                .await;
+
+                if let Err(error) = result {
+                    error!("An error occurred while revoking session {session_id}: {error:?}");
+                } else if let Err(error) = refresh_sessions(sessions, &api_base_url, ui_model).await
+                {
+                    error!("An error occurred while refreshing sessions after revoke: {error:?}");
+                }
+            }
+            Some(SessionCommand::RevokeOtherSessions) => {
+                let result: Result<RevokedSessions> = call_api_and_notify(
+                    Method::DELETE,
+                    &api_base_url,
+                    "users/me/sessions",
+                    None::<i32>,
+                    Some(ui_model),
+                    &toast_service,
+                    "Signing out other sessions...",
+                    "Signed out everywhere else",
+                )
+                .await;
+
+                if let Err(error) = result {
+                    error!("An error occurred while revoking other sessions: {error:?}");
+                } else if let Err(error) = refresh_sessions(sessions, &api_base_url, ui_model).await
+                {
+                    error!("An error occurred while refreshing sessions after revoke: {error:?}");
+                }
+            }
+            None => {}
+        }
+    }
+}
+
+async fn refresh_sessions(
+    mut sessions: Signal<Option<Vec<UserSession>>>,
+    api_base_url: &Url,
+    ui_model: Signal<UniversalInboxUIModel>,
+) -> Result<()> {
+    let new_sessions: Vec<UserSession> = call_api(
+        Method::GET,
+        api_base_url,
+        "users/me/sessions",
+        None::<i32>,
+        Some(ui_model),
+    )
+    .await?;
+
+    *sessions.write() = Some(new_sessions);
+
+    Ok(())
+}
@@ -0,0 +1,177 @@
+use anyhow::Result;
+use dioxus::prelude::*;
+
+use futures_util::StreamExt;
+use log::error;
+use reqwest::Method;
+use url::Url;
+
+use universal_inbox::{
+    SuccessResponse,
+    user::{RecoveryCodes, SecondFactorCode, TotpEnrollment, TotpStatus},
+};
+
+use crate::{
+    model::UniversalInboxUIModel,
+    services::{
+        api::{call_api, call_api_and_notify},
+        toast_service::ToastCommand,
+    },
+};
+
+#[derive(Debug)]
+pub enum TotpCommand {
+    Refresh,
+    StartEnrollment,
+    CancelEnrollment,
+    ConfirmEnrollment(SecondFactorCode),
+    RegenerateRecoveryCodes(SecondFactorCode),
+    DismissRecoveryCodes,
+    Disable(SecondFactorCode),
+}
+
+pub static TOTP_STATUS: GlobalSignal<Option<TotpStatus>> = Signal::global(|| None);
+/// Enrollment in progress, waiting for the first code of the authenticator app
+pub static TOTP_ENROLLMENT: GlobalSignal<Option<TotpEnrollment>> = Signal::global(|| None);
+/// Recovery codes are only displayed once, right after being generated
+pub static RECOVERY_CODES: GlobalSignal<Option<Vec<String>>> = Signal::global(|| None);
+
+pub async fn totp_service(
+    mut rx: UnboundedReceiver<TotpCommand>,
+    api_base_url: Url,
+    totp_status: Signal<Option<TotpStatus>>,
+    mut totp_enrollment: Signal<Option<TotpEnrollment>>,
+    mut recovery_codes: Signal<Option<Vec<String>>>,
+    ui_model: Signal<UniversalInboxUIModel>,
+    toast_service: Coroutine<ToastCommand>,
+) {
+    loop {
+        let msg = rx.next().await;
+        match msg {
+            Some(TotpCommand::Refresh) => {
+                if let Err(error) = refresh_totp_status(totp_status, &api_base_url, ui_model).await
+                {
+                    error!("An error occurred while refreshing TOTP status: {error:?}");
+                }
+            }
+            Some(TotpCommand::StartEnrollment) => {
+                let result: Result<TotpEnrollment> = call_api(
+                    Method::POST,
+                    &api_base_url,
+                    "users/me/auth-methods/totp/enrollment",
+                    None::<i32>,
+                    Some(ui_model),
+                )
+                .await;
+
+                match result {
+                    Ok(enrollment) => {
+                        *totp_enrollment.write() = Some(enrollment);
+                    }
+                    Err(error) => {
+                        error!("An error occurred while starting TOTP enrollment: {error:?}");
+                    }
+                }
+            }
+            Some(TotpCommand::CancelEnrollment) => {
+                *totp_enrollment.write() = None;
+            }
+            Some(TotpCommand::ConfirmEnrollment(code)) => {
+                let result: Result<RecoveryCodes> = call_api_and_notify(
+                    Method::POST,
+                    &api_base_url,
+                    "users/me/auth-methods/totp/enrollment/confirmation",
+                    Some(code),
+                    Some(ui_model),
+                    &toast_service,
+                    "Enabling two-factor authentication...",
+                    "Two-factor authentication enabled",
+                )
+                .await;
+
+                if let Ok(RecoveryCodes {
+                    recovery_codes: new_recovery_codes,
+                }) = result
+                {
+                    *totp_enrollment.write() = None;
+                    *recovery_codes.write() = Some(new_recovery_codes);
+                    if let Err(error) =
+                        refresh_totp_status(totp_status, &api_base_url, ui_model).await
+                    {
+                        error!("An error occurred while refreshing TOTP status: {error:?}");
+                    }
+                }
+            }
+            Some(TotpCommand::RegenerateRecoveryCodes(code)) => {
+                let result: Result<RecoveryCodes> = call_api_and_notify(
+                    Method::POST,
+                    &api_base_url,
+                    "users/me/auth-methods/totp/recovery-codes",
+                    Some(code),
+                    Some(ui_model),
+                    &toast_service,
+                    "Generating new recovery codes...",
+                    "New recovery codes generated",
+                )
+                .await;
+
+                if let Ok(RecoveryCodes {
+                    recovery_codes: new_recovery_codes,
+                }) = result
+                {
+                    *recovery_codes.write() = Some(new_recovery_codes);
+                    if let Err(error) =
+                        refresh_totp_status(totp_status, &api_base_url, ui_model).await
+                    {
+                        error!("An error occurred while refreshing TOTP status: {error:?}");
+                    }
+                }
+            }
+            Some(TotpCommand::DismissRecoveryCodes) => {
+                *recovery_codes.write() = None;
+            }
+            Some(TotpCommand::Disable(code)) => {
+                let result: Result<SuccessResponse> = call_api_and_notify(
+                    Method::DELETE,
+                    &api_base_url,
+                    "users/me/auth-methods/totp",
+                    Some(code),
+                    Some(ui_model),
+                    &toast_service,
+                    "Disabling two-factor authentication...",
+                    "Two-factor authentication disabled",
+                )
+                .await;
+
+                if result.is_ok() {
+                    *recovery_codes.write() = None;
+                    if let Err(error) =
+                        refresh_totp_status(totp_status, &api_base_url, ui_model).await
+                    {
+                        error!("An error occurred while refreshing TOTP status: {error:?}");
+                    }
+                }
+            }
+            None => {}
+        }
+    }
+}
+
+async fn refresh_totp_status(
+    mut totp_status: Signal<Option<TotpStatus>>,
+    api_base_url: &Url,
+    ui_model: Signal<UniversalInboxUIModel>,
+) -> Result<()> {
+    let new_totp_status: TotpStatus = call_api(
+        Method::GET,
+        api_base_url,
+        "users/me/auth-methods/totp",
+        None::<i32>,
+        Some(ui_model),
+    )
+    .await?;
+
+    *totp_status.write() = Some(new_totp_status);
+
+    Ok(())
+}
@@ -14,8 +14,9 @@ use universal_inbox::{
     SuccessResponse,
     auth::{AuthorizeSessionResponse, CloseSessionResponse},
     user::{
-        Credentials, EmailValidationToken, Password, PasswordResetToken, RegisterUserParameters,
-        User, UserAuthKind, UserAuthMethod, UserId, UserPatch, Username,
+        Credentials, EmailValidationToken, LoginResponse, Password, PasswordResetToken,
+        RegisterUserParameters, SecondFactorCode, SecondFactorKind, User, UserAuthKind,
+        UserAuthMethod, UserId, UserPatch, Username,
     },
 };
 
@@ -29,6 +30,8 @@ pub enum UserCommand {
     GetUser,
     RegisterUser(RegisterUserParameters),
     Login(Credentials),
+    LoginSecondFactor(SecondFactorCode),
+    CancelSecondFactor,
     Logout,
     ResendVerificationEmail,
     VerifyEmail(UserId, EmailValidationToken),
@@ -41,11 +44,14 @@ pub enum UserCommand {
     AddLocalAuth(SecretBox<Password>),
     AddPasskeyAuthMethod(Username),
     LinkOIDCAuth,
+    LinkSAMLAuth,
     RemoveAuthMethod(UserAuthKind),
 }
 
 pub static CONNECTED_USER: GlobalSignal<Option<User>> = Signal::global(|| None);
 pub static AUTH_METHODS: GlobalSignal<Option<Vec<UserAuthMethod>>> = Signal::global(|| None);
+/// Set when the password was accepted but a second factor is needed to log in
+pub static SECOND_FACTOR_REQUIRED: GlobalSignal<Option<SecondFactorKind>> = Signal::global(|| None);
 
 /// Per-ceremony nonce hardening for passkey.
 /// The API returns a fresh server-generated
@@ -99,7 +105,7 @@ pub async fn user_service(
 
             Some(UserCommand::Login(credentials)) => {
                 ui_model.write().error_message = None;
-                let result: Result<User> = call_api(
+                let result: Result<LoginResponse> = call_api(
                     Method::POST,
                     &api_base_url,
                     "users/me",
@@ -108,8 +114,32 @@ pub async fn user_service(
                 )
                 .await;
 
+                match result {
+                    Ok(LoginResponse::LoggedIn(user)) => {
+                        connected_user.write().replace(*user);
+                    }
+                    Ok(LoginResponse::SecondFactorRequired { second_factor }) => {
+                        *SECOND_FACTOR_REQUIRED.write() = Some(second_factor);
+                    }
+                    Err(err) => {
+                        ui_model.write().error_message = Some(err.to_string());
+                    }
+                };
+            }
+            Some(UserCommand::LoginSecondFactor(code)) => {
+                ui_model.write().error_message = None;
+                let result: Result<User> = call_api(
+                    Method::POST,
+                    &api_base_url,
+                    "users/me/second-factor",
+                    Some(code),
+                    Some(ui_model),
+                )
+                .await;
+
                 match result {
                     Ok(user) => {
+                        *SECOND_FACTOR_REQUIRED.write() = None;
                         connected_user.write().replace(user);
                     }
                     Err(err) => {
@@ -117,6 +147,10 @@ pub async fn user_service(
                     }
                 };
             }
+            Some(UserCommand::CancelSecondFactor) => {
+                ui_model.write().error_message = None;
+                *SECOND_FACTOR_REQUIRED.write() = None;
+            }
             Some(UserCommand::Logout) => {
                 unload_crisp();
                 let result: Result<CloseSessionResponse> = call_api(
@@ -309,6 +343,27 @@ pub async fn user_service(
                     }
                 };
             }
+            Some(UserCommand::LinkSAMLAuth) => {
+                let result: Result<AuthorizeSessionResponse> = call_api(
+                    Method::GET,
+                    &api_base_url,
+                    "auth/link-saml/authorize",
+                    None::<i32>,
+                    Some(ui_model),
+                )
+                .await;
+
+                match result {
+                    Ok(response) => {
+                        if let Err(err) = redirect_to(response.authorization_url.as_str()) {
+                            ui_model.write().error_message = Some(err.to_string());
+                        }
+                    }
+                    Err(err) => {
+                        ui_model.write().error_message = Some(err.to_string());
+                    }
+                };
+            }
             Some(UserCommand::RemoveAuthMethod(kind)) => {
                 let result: Result<SuccessResponse> = call_api(
                     Method::DELETE,